@@ -0,0 +1,241 @@
+//! Episode release calendar: grouping by day for `/serialy-online/kalendar/`
+//! and iCalendar rendering for `/serialy-online/{slug}/kalendar.ics`.
+//!
+//! Entries come from
+//! [`EpisodeCalendarRepository`](cr_domain::repository::EpisodeCalendarRepository),
+//! already ordered by air date. Air dates are days without a time zone
+//! (TMDB only knows the day), so every event is an all-day one.
+
+use cr_domain::catalog::CatalogKind;
+use cr_domain::date::Date;
+use cr_domain::dto::CalendarEpisode;
+
+/// Weeks after the current one listed under "Připravujeme".
+pub const UPCOMING_WEEKS: i64 = 4;
+
+/// Episodes in the "Připravované epizody" block of a series page.
+pub const SERIES_UPCOMING_LEN: i64 = 6;
+
+/// The `.ics` export starts this many days back, so an episode that aired
+/// last week doesn't vanish from subscribers' calendars right away.
+pub const ICS_PAST_DAYS: i64 = 30;
+
+/// Most events in one `.ics` export.
+pub const ICS_LEN: i64 = 200;
+
+/// Domain part of event UIDs. Fixed for good — changing it would make
+/// calendar apps duplicate every event.
+const UID_DOMAIN: &str = "ceskarepublika.wiki";
+
+const WEEKDAYS_CS: [&str; 7] = [
+    "pondělí",
+    "úterý",
+    "středa",
+    "čtvrtek",
+    "pátek",
+    "sobota",
+    "neděle",
+];
+
+/// One day of the calendar page.
+#[derive(Debug, Clone)]
+pub struct CalendarDay {
+    pub date: Date,
+    /// `pondělí`
+    pub weekday: &'static str,
+    /// `20. 10.`
+    pub label: String,
+    pub is_today: bool,
+    pub episodes: Vec<CalendarEpisode>,
+}
+
+/// Group `entries` into days `from..=to`. With `keep_empty`, days without
+/// any episode are kept too (the current week shows all seven).
+pub fn group_by_day(
+    entries: &[CalendarEpisode],
+    from: Date,
+    to: Date,
+    today: Date,
+    keep_empty: bool,
+) -> Vec<CalendarDay> {
+    let mut days = Vec::new();
+    let mut date = from;
+    while date <= to {
+        let episodes: Vec<CalendarEpisode> = entries
+            .iter()
+            .filter(|e| e.air_date == date)
+            .cloned()
+            .collect();
+        if keep_empty || !episodes.is_empty() {
+            days.push(CalendarDay {
+                date,
+                weekday: WEEKDAYS_CS[usize::from(date.weekday())],
+                label: format!("{}. {}.", date.day(), date.month()),
+                is_today: date == today,
+                episodes,
+            });
+        }
+        date = date.add_days(1);
+    }
+    days
+}
+
+/// A `VCALENDAR` of `entries`, with event URLs on `origin`
+/// (`https://ceskarepublika.wiki`). `stamp` is the `DTSTAMP`, UTC in the
+/// iCalendar basic format (`20261019T080000Z`).
+pub fn render_ics(name: &str, entries: &[CalendarEpisode], origin: &str, stamp: &str) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(
+        &mut out,
+        "PRODID:-//ceskarepublika.wiki//Kalendar epizod//CS",
+    );
+    line(&mut out, "CALSCALE:GREGORIAN");
+    line(&mut out, "METHOD:PUBLISH");
+    line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(name)));
+    // Air dates move around; ask subscribers to refresh twice a day.
+    line(&mut out, "REFRESH-INTERVAL;VALUE=DURATION:PT12H");
+    line(&mut out, "X-PUBLISHED-TTL:PT12H");
+    for e in entries {
+        let kind = match e.kind {
+            CatalogKind::TvShow => "tv",
+            _ => "series",
+        };
+        let summary = match &e.episode_name {
+            Some(name) => format!("{} — {} {name}", e.show_title, e.code()),
+            None => format!("{} — {}", e.show_title, e.code()),
+        };
+        let url = e.path.clone().unwrap_or_else(|| e.show_path());
+        line(&mut out, "BEGIN:VEVENT");
+        line(
+            &mut out,
+            &format!(
+                "UID:{kind}-{}-s{}e{}@{UID_DOMAIN}",
+                e.show_id, e.season, e.episode
+            ),
+        );
+        line(&mut out, &format!("DTSTAMP:{stamp}"));
+        line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{}", ics_date(e.air_date)),
+        );
+        line(
+            &mut out,
+            &format!("DTEND;VALUE=DATE:{}", ics_date(e.air_date.add_days(1))),
+        );
+        line(&mut out, &format!("SUMMARY:{}", escape_text(&summary)));
+        line(&mut out, &format!("URL:{origin}{url}"));
+        line(&mut out, "TRANSP:TRANSPARENT");
+        line(&mut out, "END:VEVENT");
+    }
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// `20261026`
+fn ics_date(d: Date) -> String {
+    format!("{:04}{:02}{:02}", d.year(), d.month(), d.day())
+}
+
+/// RFC 5545 TEXT escaping.
+fn escape_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Append one content line, folded at 75 octets (never inside a UTF-8
+/// sequence) and terminated with CRLF.
+fn line(out: &mut String, content: &str) {
+    let mut width = 0;
+    for c in content.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(day: u8, name: Option<&str>) -> CalendarEpisode {
+        CalendarEpisode {
+            kind: CatalogKind::Series,
+            show_id: 7,
+            show_title: "Simpsonovi".to_string(),
+            show_slug: "simpsonovi".to_string(),
+            season: 37,
+            episode: i16::from(day),
+            episode_name: name.map(str::to_string),
+            air_date: Date::new(2026, 10, day).unwrap(),
+            path: None,
+        }
+    }
+
+    #[test]
+    fn week_keeps_empty_days_and_upcoming_drops_them() {
+        let monday = Date::new(2026, 10, 19).unwrap();
+        let entries = [episode(21, None), episode(21, None), episode(30, None)];
+        let week = group_by_day(&entries, monday, monday.add_days(6), monday, true);
+        assert_eq!(week.len(), 7);
+        assert!(week[0].is_today);
+        assert_eq!(week[0].weekday, "pondělí");
+        assert_eq!(week[2].label, "21. 10.");
+        assert_eq!(week[2].episodes.len(), 2);
+        assert_eq!(week[6].weekday, "neděle");
+
+        let later = group_by_day(
+            &entries,
+            monday.add_days(7),
+            monday.add_days(20),
+            monday,
+            false,
+        );
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].weekday, "pátek");
+    }
+
+    #[test]
+    fn ics_has_all_day_events_with_escaped_text() {
+        let ics = render_ics(
+            "Simpsonovi",
+            &[episode(26, Some("Dobro, zlo; a Homer"))],
+            "https://ceskarepublika.wiki",
+            "20261019T080000Z",
+        );
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:series-7-s37e26@ceskarepublika.wiki\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261026\r\nDTEND;VALUE=DATE:20261027\r\n"));
+        assert!(ics.contains("SUMMARY:Simpsonovi — S37E26 Dobro\\, zlo\\; a Homer\r\n"));
+        assert!(ics.contains("URL:https://ceskarepublika.wiki/serialy-online/simpsonovi/\r\n"));
+    }
+
+    #[test]
+    fn long_lines_fold_on_char_boundaries() {
+        let mut out = String::new();
+        line(&mut out, &format!("SUMMARY:{}", "ž".repeat(60)));
+        for l in out.split("\r\n").filter(|l| !l.is_empty()) {
+            assert!(l.len() <= 75, "{l}");
+        }
+        assert_eq!(
+            out.replace("\r\n ", ""),
+            format!("SUMMARY:{}\r\n", "ž".repeat(60))
+        );
+    }
+}
@@ -0,0 +1,634 @@
+//! Catalog (films, series, TV pořady) read use-cases.
+//!
+//! Handlers translate query-string params into the typed queries from
+//! `cr_domain::catalog` and call these; everything here works against the
+//! repository traits, so listings can be exercised without HTTP or SQL.
+
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::search::CatalogSearch;
+use cr_domain::catalog::{
+    CatalogKind, FilmListQuery, LegacyEpisodeCode, PageCursors, SeriesListQuery, ShowListing,
+    TitleSearch, TvShowListQuery, search_term,
+};
+use cr_domain::repository::*;
+
+pub(crate) fn repo_err(e: impl std::fmt::Debug) -> AppError {
+    AppError::Repository(format!("{e:?}"))
+}
+
+/// Short-lived store for listing totals. Counting filtered catalog rows
+/// is the expensive half of a listing page; the rows themselves are a
+/// cheap `LIMIT 24`.
+#[allow(async_fn_in_trait)]
+pub trait CountCache {
+    async fn get(&self, key: &str) -> Option<i64>;
+    async fn put(&self, key: String, count: i64);
+}
+
+/// A [`CountCache`] that never hits.
+pub struct NoCountCache;
+
+impl CountCache for NoCountCache {
+    async fn get(&self, _key: &str) -> Option<i64> {
+        None
+    }
+
+    async fn put(&self, _key: String, _count: i64) {}
+}
+
+async fn cached_count<F>(cache: &impl CountCache, key: String, count: F) -> Result<i64, AppError>
+where
+    F: std::future::Future<Output = Result<i64, AppError>>,
+{
+    if let Some(hit) = cache.get(&key).await {
+        return Ok(hit);
+    }
+    let total = count.await?;
+    cache.put(key, total).await;
+    Ok(total)
+}
+
+/// One page of a film listing plus the total across all pages.
+#[derive(Debug, Clone)]
+pub struct FilmPage {
+    pub total: i64,
+    pub films: Vec<FilmRecord>,
+    pub cursors: PageCursors,
+}
+
+/// List films. A title search that matches nothing is retried once
+/// against "title year", so "Mstitel (1989)" still finds the film.
+pub async fn list_films<R: FilmRepository>(
+    repo: &R,
+    query: &FilmListQuery,
+    cache: &impl CountCache,
+) -> Result<FilmPage, AppError> {
+    let total = cached_count(cache, query.count_key(), async {
+        repo.count(query).await.map_err(repo_err)
+    })
+    .await?;
+    if total == 0
+        && let Some(retry) = query.title_year_fallback()
+    {
+        return Box::pin(list_films(repo, &retry, cache)).await;
+    }
+    let (films, cursors) = repo.list(query).await.map_err(repo_err)?;
+    Ok(FilmPage {
+        total,
+        films,
+        cursors,
+    })
+}
+
+/// Film autocomplete. Served from the full-text index once it is built;
+/// before that, the SQL title match with the same title+year retry as
+/// [`list_films`]. Terms shorter than the search minimum return nothing.
+pub async fn suggest_films<R: FilmRepository>(
+    repo: &R,
+    index: &CatalogSearch,
+    q: &str,
+) -> Result<Vec<TitleSuggestion>, AppError> {
+    let Some(term) = search_term(Some(q)) else {
+        return Ok(Vec::new());
+    };
+    if let Some(hits) = index.suggest(CatalogKind::Film, &term) {
+        return Ok(hits);
+    }
+    let search = TitleSearch::new(term);
+    let rows = repo.suggest(&search).await.map_err(repo_err)?;
+    if !rows.is_empty() {
+        return Ok(rows);
+    }
+    match search.title_year_fallback() {
+        Some(retry) => repo.suggest(&retry).await.map_err(repo_err),
+        None => Ok(rows),
+    }
+}
+
+/// Series autocomplete: the full-text index, or the SQL title match
+/// while the index is still being built.
+pub async fn suggest_series<R: SeriesRepository>(
+    repo: &R,
+    index: &CatalogSearch,
+    q: &str,
+) -> Result<Vec<TitleSuggestion>, AppError> {
+    let Some(term) = search_term(Some(q)) else {
+        return Ok(Vec::new());
+    };
+    match index.suggest(CatalogKind::Series, &term) {
+        Some(hits) => Ok(hits),
+        None => repo.suggest(&term).await.map_err(repo_err),
+    }
+}
+
+/// TV pořad autocomplete; see [`suggest_series`].
+pub async fn suggest_tv_shows<R: TvShowRepository>(
+    repo: &R,
+    index: &CatalogSearch,
+    q: &str,
+) -> Result<Vec<TitleSuggestion>, AppError> {
+    let Some(term) = search_term(Some(q)) else {
+        return Ok(Vec::new());
+    };
+    match index.suggest(CatalogKind::TvShow, &term) {
+        Some(hits) => Ok(hits),
+        None => repo.suggest(&term).await.map_err(repo_err),
+    }
+}
+
+/// One page of a series or TV pořad listing. Depending on the
+/// [`ShowListing`], either `shows` (search / sorted) or `latest` (the
+/// latest-episode grid) is filled; the other stays empty. `cursors` are
+/// those of the filled list (none for TV pořady, which page by offset).
+#[derive(Debug, Clone)]
+pub struct ShowPage<S, E> {
+    pub total: i64,
+    pub shows: Vec<S>,
+    pub latest: Vec<E>,
+    pub cursors: PageCursors,
+}
+
+pub async fn list_series<R: SeriesRepository>(
+    repo: &R,
+    query: &SeriesListQuery,
+    cache: &impl CountCache,
+) -> Result<ShowPage<SeriesRecord, EpisodeCard>, AppError> {
+    let total = cached_count(cache, query.count_key(), async {
+        repo.count(query).await.map_err(repo_err)
+    })
+    .await?;
+    let (shows, latest, cursors) = match query.listing() {
+        ShowListing::Search | ShowListing::Sorted => {
+            let (shows, cursors) = repo.list(query).await.map_err(repo_err)?;
+            (shows, Vec::new(), cursors)
+        }
+        ShowListing::LatestEpisodes => {
+            let (latest, cursors) = repo.latest_episodes(query).await.map_err(repo_err)?;
+            (Vec::new(), latest, cursors)
+        }
+    };
+    Ok(ShowPage {
+        total,
+        shows,
+        latest,
+        cursors,
+    })
+}
+
+pub async fn list_tv_shows<R: TvShowRepository>(
+    repo: &R,
+    query: &TvShowListQuery,
+    cache: &impl CountCache,
+) -> Result<ShowPage<TvShowRecord, TvEpisodeCard>, AppError> {
+    let total = cached_count(cache, query.count_key(), async {
+        repo.count(query).await.map_err(repo_err)
+    })
+    .await?;
+    let (shows, latest) = match query.listing() {
+        ShowListing::Search | ShowListing::Sorted => {
+            (repo.list(query).await.map_err(repo_err)?, Vec::new())
+        }
+        ShowListing::LatestEpisodes => (
+            Vec::new(),
+            repo.latest_episodes(query).await.map_err(repo_err)?,
+        ),
+    };
+    Ok(ShowPage {
+        total,
+        shows,
+        latest,
+        cursors: PageCursors::default(),
+    })
+}
+
+/// Group `(owner id, genre)` pairs into per-owner chip lists, keeping the
+/// repository's order within each owner.
+pub fn genre_chips(pairs: Vec<(i32, GenreRecord)>) -> HashMap<i32, Vec<GenreRecord>> {
+    let mut map: HashMap<i32, Vec<GenreRecord>> = HashMap::new();
+    for (owner, genre) in pairs {
+        map.entry(owner).or_default().push(genre);
+    }
+    map
+}
+
+/// Outcome of resolving the episode segment of a show URL.
+#[derive(Debug, Clone)]
+pub enum EpisodeLookup<E> {
+    Found(E),
+    /// A legacy `/1x5/` URL — 301 to the episode's slug.
+    Redirect(String),
+    NotFound,
+    /// Looked like a legacy code but didn't parse.
+    InvalidPath,
+}
+
+fn legacy_outcome<E>(found: Option<E>, slug: impl Fn(&E) -> Option<&String>) -> EpisodeLookup<E> {
+    match found {
+        Some(ep) => match slug(&ep) {
+            Some(s) => EpisodeLookup::Redirect(s.clone()),
+            // Slug not backfilled yet — serve the episode directly.
+            None => EpisodeLookup::Found(ep),
+        },
+        None => EpisodeLookup::NotFound,
+    }
+}
+
+/// Resolve `path` (an episode slug or legacy `NxM` code) within a series.
+pub async fn find_series_episode<R: EpisodeRepository>(
+    repo: &R,
+    series_id: i32,
+    path: &str,
+) -> Result<EpisodeLookup<EpisodeRecord>, AppError> {
+    if let Some(ep) = repo.find_by_slug(series_id, path).await.map_err(repo_err)? {
+        return Ok(EpisodeLookup::Found(ep));
+    }
+    match LegacyEpisodeCode::parse(path) {
+        LegacyEpisodeCode::Code { season, episode } => {
+            let found = repo
+                .find_by_number(series_id, season, episode)
+                .await
+                .map_err(repo_err)?;
+            Ok(legacy_outcome(found, |e| e.slug.as_ref()))
+        }
+        LegacyEpisodeCode::Invalid => Ok(EpisodeLookup::InvalidPath),
+        LegacyEpisodeCode::NotACode => Ok(EpisodeLookup::NotFound),
+    }
+}
+
+/// Resolve `path` (an episode slug or legacy `NxM` code) within a TV pořad.
+pub async fn find_tv_episode<R: TvShowRepository>(
+    repo: &R,
+    show_id: i32,
+    path: &str,
+) -> Result<EpisodeLookup<TvEpisodeRecord>, AppError> {
+    if let Some(ep) = repo
+        .find_episode_by_slug(show_id, path)
+        .await
+        .map_err(repo_err)?
+    {
+        return Ok(EpisodeLookup::Found(ep));
+    }
+    match LegacyEpisodeCode::parse(path) {
+        LegacyEpisodeCode::Code { season, episode } => {
+            let found = repo
+                .find_episode_by_number(show_id, season, episode)
+                .await
+                .map_err(repo_err)?;
+            Ok(legacy_outcome(found, |e| e.slug.as_ref()))
+        }
+        LegacyEpisodeCode::Invalid => Ok(EpisodeLookup::InvalidPath),
+        LegacyEpisodeCode::NotACode => Ok(EpisodeLookup::NotFound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cr_domain::catalog::{
+        CatalogSort, FilmFilter, Paging, SeriesFilter, SortDirection, TitleMatch,
+    };
+    use std::cell::RefCell;
+
+    fn film(id: i32, title: &str) -> FilmRecord {
+        FilmRecord {
+            id,
+            title: title.into(),
+            slug: title.to_lowercase(),
+            year: Some(1989),
+            description: None,
+            original_title: None,
+            tmdb_rating: None,
+            imdb_rating: None,
+            csfd_rating: None,
+            runtime_min: None,
+            sktorrent_video_id: None,
+            sktorrent_cdn: None,
+            sktorrent_qualities: None,
+            prehrajto_url: None,
+            prehrajto_has_dub: false,
+            prehrajto_has_subs: false,
+            tmdb_poster_path: None,
+            sledujteto_primary_file_id: None,
+        }
+    }
+
+    /// Matches only the title+year form of the search, and records every
+    /// search it was asked to run.
+    #[derive(Default)]
+    struct FakeFilms {
+        searches: RefCell<Vec<TitleSearch>>,
+    }
+
+    impl FakeFilms {
+        fn matches(&self, query: &FilmListQuery) -> Vec<FilmRecord> {
+            match &query.filter.search {
+                Some(s) => {
+                    self.searches.borrow_mut().push(s.clone());
+                    if s.mode == TitleMatch::TitleYear && s.term == "Mstitel 1989" {
+                        vec![film(1, "Mstitel")]
+                    } else {
+                        Vec::new()
+                    }
+                }
+                None => vec![film(1, "Mstitel"), film(2, "Pelíšky")],
+            }
+        }
+    }
+
+    impl FilmRepository for FakeFilms {
+        type Error = ();
+        async fn list(&self, query: &FilmListQuery) -> Result<(Vec<FilmRecord>, PageCursors), ()> {
+            Ok((self.matches(query), PageCursors::default()))
+        }
+        async fn count(&self, query: &FilmListQuery) -> Result<i64, ()> {
+            Ok(self.matches(query).len() as i64)
+        }
+        async fn find_by_slug(&self, _: &str) -> Result<Option<FilmRecord>, ()> {
+            Ok(None)
+        }
+        async fn find_genre(&self, _: &str) -> Result<Option<GenreRecord>, ()> {
+            Ok(None)
+        }
+        async fn genres(&self) -> Result<Vec<GenreRecord>, ()> {
+            Ok(Vec::new())
+        }
+        async fn genres_of(&self, _: &[i32]) -> Result<Vec<(i32, GenreRecord)>, ()> {
+            Ok(Vec::new())
+        }
+        async fn cast(&self, _: i32, _: i64) -> Result<Vec<PersonCredit>, ()> {
+            Ok(Vec::new())
+        }
+        async fn directors(&self, _: i32) -> Result<Vec<PersonCredit>, ()> {
+            Ok(Vec::new())
+        }
+        async fn suggest(&self, search: &TitleSearch) -> Result<Vec<TitleSuggestion>, ()> {
+            self.searches.borrow_mut().push(search.clone());
+            Ok(Vec::new())
+        }
+    }
+
+    #[derive(Default)]
+    struct MapCache(RefCell<HashMap<String, i64>>);
+
+    impl CountCache for MapCache {
+        async fn get(&self, key: &str) -> Option<i64> {
+            self.0.borrow().get(key).copied()
+        }
+        async fn put(&self, key: String, count: i64) {
+            self.0.borrow_mut().insert(key, count);
+        }
+    }
+
+    fn film_query(search: Option<&str>) -> FilmListQuery {
+        FilmListQuery {
+            filter: FilmFilter {
+                search: search.map(TitleSearch::new),
+                ..FilmFilter::default()
+            },
+            sort: CatalogSort::Added,
+            direction: SortDirection::Desc,
+            paging: Paging::new(None, 24),
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_search_retries_with_title_year() {
+        let repo = FakeFilms::default();
+        let page = list_films(&repo, &film_query(Some("Mstitel (1989)")), &NoCountCache)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.films[0].title, "Mstitel");
+        let searches = repo.searches.borrow();
+        assert_eq!(searches[0].mode, TitleMatch::Title);
+        assert_eq!(searches.last().unwrap().term, "Mstitel 1989");
+    }
+
+    #[tokio::test]
+    async fn cached_count_skips_the_count_query() {
+        let repo = FakeFilms::default();
+        let cache = MapCache::default();
+        let query = film_query(None);
+        cache.put(query.count_key(), 999).await;
+        let page = list_films(&repo, &query, &cache).await.unwrap();
+        assert_eq!(page.total, 999);
+        assert_eq!(page.films.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn count_is_stored_under_the_query_key() {
+        let repo = FakeFilms::default();
+        let cache = MapCache::default();
+        let query = film_query(None);
+        list_films(&repo, &query, &cache).await.unwrap();
+        assert_eq!(cache.get(&query.count_key()).await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn short_suggest_terms_skip_the_repository() {
+        let repo = FakeFilms::default();
+        // Index not built yet, so these go to the SQL fallback.
+        let index = CatalogSearch::new();
+        assert!(
+            suggest_films(&repo, &index, " a ")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(repo.searches.borrow().is_empty());
+
+        suggest_films(&repo, &index, "Marvinův pokoj (1996")
+            .await
+            .unwrap();
+        let searches = repo.searches.borrow();
+        assert_eq!(searches.len(), 2);
+        assert_eq!(searches[1].term, "Marvinův pokoj 1996");
+    }
+
+    fn episode(id: i32, season: i16, number: i16, slug: Option<&str>) -> EpisodeRecord {
+        EpisodeRecord {
+            id,
+            season,
+            episode: number,
+            title: None,
+            sktorrent_video_id: None,
+            sktorrent_cdn: None,
+            sktorrent_qualities: None,
+            episode_name: None,
+            overview: None,
+            air_date: None,
+            runtime: None,
+            still_filename: None,
+            prehrajto_url: None,
+            prehrajto_has_dub: false,
+            prehrajto_has_subs: false,
+            sledujteto_external_id: None,
+            slug: slug.map(str::to_string),
+        }
+    }
+
+    struct FakeEpisodes(Vec<EpisodeRecord>);
+
+    impl EpisodeRepository for FakeEpisodes {
+        type Error = ();
+        async fn list_by_series(&self, _: i32) -> Result<Vec<EpisodeRecord>, ()> {
+            Ok(self.0.clone())
+        }
+        async fn find_by_slug(&self, _: i32, slug: &str) -> Result<Option<EpisodeRecord>, ()> {
+            Ok(self
+                .0
+                .iter()
+                .find(|e| e.slug.as_deref() == Some(slug))
+                .cloned())
+        }
+        async fn find_by_number(
+            &self,
+            _: i32,
+            season: i16,
+            number: i16,
+        ) -> Result<Option<EpisodeRecord>, ()> {
+            Ok(self
+                .0
+                .iter()
+                .find(|e| e.season == season && e.episode == number)
+                .cloned())
+        }
+        async fn navigation(&self, _: i32) -> Result<Vec<EpisodeNav>, ()> {
+            Ok(Vec::new())
+        }
+        async fn directors(&self, _: i32) -> Result<Vec<PersonCredit>, ()> {
+            Ok(Vec::new())
+        }
+        async fn writers(&self, _: i32) -> Result<Vec<PersonCredit>, ()> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn episode_lookup_covers_slugs_and_legacy_codes() {
+        let repo = FakeEpisodes(vec![
+            episode(1, 1, 5, Some("pilot-s01e05")),
+            episode(2, 2, 1, None),
+        ]);
+        let found = find_series_episode(&repo, 7, "pilot-s01e05").await.unwrap();
+        assert!(matches!(found, EpisodeLookup::Found(e) if e.id == 1));
+        assert!(matches!(
+            find_series_episode(&repo, 7, "1x5").await.unwrap(),
+            EpisodeLookup::Redirect(slug) if slug == "pilot-s01e05"
+        ));
+        let unslugged = find_series_episode(&repo, 7, "2x1").await.unwrap();
+        assert!(matches!(unslugged, EpisodeLookup::Found(e) if e.id == 2));
+        assert!(matches!(
+            find_series_episode(&repo, 7, "9x9").await.unwrap(),
+            EpisodeLookup::NotFound
+        ));
+        assert!(matches!(
+            find_series_episode(&repo, 7, "1xfoo").await.unwrap(),
+            EpisodeLookup::InvalidPath
+        ));
+        assert!(matches!(
+            find_series_episode(&repo, 7, "neznama-epizoda")
+                .await
+                .unwrap(),
+            EpisodeLookup::NotFound
+        ));
+    }
+
+    fn card(series_id: i32) -> EpisodeCard {
+        EpisodeCard {
+            id: series_id * 10,
+            series_id,
+            series_slug: format!("s{series_id}"),
+            series_title: format!("S{series_id}"),
+            series_original_title: None,
+            series_first_air_year: None,
+            series_tmdb_rating: None,
+            series_imdb_rating: None,
+            series_csfd_rating: None,
+            series_description: None,
+            season: 1,
+            episode: 1,
+            has_subtitles: None,
+            has_dub: None,
+            episode_slug: None,
+            episode_name: None,
+        }
+    }
+
+    struct FakeSeries;
+
+    impl SeriesRepository for FakeSeries {
+        type Error = ();
+        async fn list(&self, _: &SeriesListQuery) -> Result<(Vec<SeriesRecord>, PageCursors), ()> {
+            panic!("latest-episode listing must not list series rows")
+        }
+        async fn latest_episodes(
+            &self,
+            _: &SeriesListQuery,
+        ) -> Result<(Vec<EpisodeCard>, PageCursors), ()> {
+            Ok((vec![card(1), card(2)], PageCursors::default()))
+        }
+        async fn count(&self, _: &SeriesListQuery) -> Result<i64, ()> {
+            Ok(2)
+        }
+        async fn find_by_slug(&self, _: &str) -> Result<Option<SeriesRecord>, ()> {
+            Ok(None)
+        }
+        async fn find_by_old_slug(&self, _: &str) -> Result<Option<SeriesRecord>, ()> {
+            Ok(None)
+        }
+        async fn find_genre(&self, _: &str) -> Result<Option<GenreRecord>, ()> {
+            Ok(None)
+        }
+        async fn genres(&self) -> Result<Vec<GenreRecord>, ()> {
+            Ok(Vec::new())
+        }
+        async fn genres_of(&self, _: &[i32]) -> Result<Vec<(i32, GenreRecord)>, ()> {
+            Ok(Vec::new())
+        }
+        async fn cast(&self, _: i32, _: i64) -> Result<Vec<PersonCredit>, ()> {
+            Ok(Vec::new())
+        }
+        async fn creators(&self, _: i32) -> Result<Vec<PersonCredit>, ()> {
+            Ok(Vec::new())
+        }
+        async fn suggest(&self, _: &str) -> Result<Vec<TitleSuggestion>, ()> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn default_series_listing_fills_latest_episodes() {
+        let query = SeriesListQuery {
+            filter: SeriesFilter::default(),
+            sort: CatalogSort::Added,
+            direction: SortDirection::Desc,
+            paging: Paging::new(Some(1), 24),
+        };
+        let page = list_series(&FakeSeries, &query, &NoCountCache)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+        assert!(page.shows.is_empty());
+        assert_eq!(page.latest.len(), 2);
+    }
+
+    #[test]
+    fn genre_chips_group_by_owner() {
+        let g = |id: i32, slug: &str| GenreRecord {
+            id,
+            slug: slug.into(),
+            name_cs: slug.into(),
+        };
+        let map = genre_chips(vec![
+            (1, g(1, "drama")),
+            (2, g(2, "krimi")),
+            (1, g(3, "horor")),
+        ]);
+        let slugs: Vec<&str> = map[&1].iter().map(|g| g.slug.as_str()).collect();
+        assert_eq!(slugs, ["drama", "horor"]);
+        assert_eq!(map[&2].len(), 1);
+    }
+}
@@ -0,0 +1,241 @@
+//! NDJSON and CSV writers for the `/api/export/{dataset}` open-data feeds.
+//!
+//! The web layer pages through [`ExportRepository`] and feeds each page
+//! through [`write_record`] into the response stream, so a full pull never
+//! holds a dataset in memory. Columns come from
+//! [`ExportDataset::columns`], behind the fixed `id` and `updated_at`.
+//!
+//! [`ExportRepository`]: cr_domain::repository::ExportRepository
+
+use std::fmt::Write as _;
+
+use cr_domain::export::{ExportDataset, ExportRecord, ExportValue};
+
+/// Rows fetched per round trip while streaming an export.
+pub const EXPORT_PAGE_SIZE: i64 = 1000;
+
+/// Columns every dataset starts with.
+pub const KEY_COLUMNS: [&str; 2] = ["id", "updated_at"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Ndjson,
+    Csv,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 2] = [Self::Ndjson, Self::Csv];
+
+    /// `?format=` value; NDJSON when absent, `None` when unknown.
+    pub fn from_key(key: Option<&str>) -> Option<Self> {
+        match key {
+            None | Some("ndjson") => Some(Self::Ndjson),
+            Some("csv") => Some(Self::Csv),
+            Some(_) => None,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson; charset=utf-8",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    /// What precedes the first row: the header line for CSV.
+    pub fn preamble(self, dataset: ExportDataset) -> String {
+        match self {
+            Self::Ndjson => String::new(),
+            Self::Csv => {
+                let names: Vec<&str> = KEY_COLUMNS
+                    .into_iter()
+                    .chain(dataset.columns().iter().map(|c| c.name))
+                    .collect();
+                format!("{}\n", names.join(","))
+            }
+        }
+    }
+}
+
+/// Append `record` as one line of `format`.
+pub fn write_record(
+    format: ExportFormat,
+    dataset: ExportDataset,
+    record: &ExportRecord,
+    out: &mut String,
+) {
+    match format {
+        ExportFormat::Ndjson => {
+            let _ = write!(out, "{{\"id\":{},\"updated_at\":", record.id);
+            json_string(&record.updated_at, out);
+            for (column, value) in dataset.columns().iter().zip(&record.values) {
+                out.push(',');
+                json_string(column.name, out);
+                out.push(':');
+                json_value(value, out);
+            }
+            out.push_str("}\n");
+        }
+        ExportFormat::Csv => {
+            let _ = write!(out, "{},{}", record.id, record.updated_at);
+            for value in &record.values {
+                out.push(',');
+                csv_value(value, out);
+            }
+            out.push('\n');
+        }
+    }
+}
+
+fn json_value(value: &ExportValue, out: &mut String) {
+    match value {
+        ExportValue::Null => out.push_str("null"),
+        ExportValue::Int(n) => {
+            let _ = write!(out, "{n}");
+        }
+        ExportValue::Float(f) if f.is_finite() => {
+            let _ = write!(out, "{f}");
+        }
+        ExportValue::Float(_) => out.push_str("null"),
+        ExportValue::Bool(b) => {
+            let _ = write!(out, "{b}");
+        }
+        ExportValue::Text(s) | ExportValue::Timestamp(s) => json_string(s, out),
+        ExportValue::TextList(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                json_string(item, out);
+            }
+            out.push(']');
+        }
+    }
+}
+
+fn json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Empty for null; lists are joined with `|`.
+fn csv_value(value: &ExportValue, out: &mut String) {
+    match value {
+        ExportValue::Null => {}
+        ExportValue::Int(n) => {
+            let _ = write!(out, "{n}");
+        }
+        ExportValue::Float(f) if f.is_finite() => {
+            let _ = write!(out, "{f}");
+        }
+        ExportValue::Float(_) => {}
+        ExportValue::Bool(b) => {
+            let _ = write!(out, "{b}");
+        }
+        ExportValue::Text(s) | ExportValue::Timestamp(s) => csv_cell(s, out),
+        ExportValue::TextList(items) => csv_cell(&items.join("|"), out),
+    }
+}
+
+/// RFC 4180 quoting, only where needed.
+fn csv_cell(s: &str, out: &mut String) {
+    if s.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&s.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(name: &str) -> ExportRecord {
+        ExportRecord {
+            id: 7,
+            updated_at: "2026-10-01T08:00:00.000001Z".to_string(),
+            values: vec![
+                ExportValue::Text("jan-werich".to_string()),
+                ExportValue::Text("/osobnosti/jan-werich/".to_string()),
+                ExportValue::Text(name.to_string()),
+                ExportValue::Null,
+                ExportValue::Timestamp("2026-04-21T00:00:00.000000Z".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn ndjson_lines_are_escaped_json_objects() {
+        let mut out = String::new();
+        write_record(
+            ExportFormat::Ndjson,
+            ExportDataset::People,
+            &person("Jan \"Honza\" Werich\n"),
+            &mut out,
+        );
+        assert_eq!(
+            out,
+            "{\"id\":7,\"updated_at\":\"2026-10-01T08:00:00.000001Z\",\
+             \"slug\":\"jan-werich\",\"url\":\"/osobnosti/jan-werich/\",\
+             \"name\":\"Jan \\\"Honza\\\" Werich\\n\",\"tmdb_id\":null,\
+             \"created_at\":\"2026-04-21T00:00:00.000000Z\"}\n"
+        );
+    }
+
+    #[test]
+    fn csv_has_a_header_and_quotes_only_when_needed() {
+        assert_eq!(
+            ExportFormat::Csv.preamble(ExportDataset::People),
+            "id,updated_at,slug,url,name,tmdb_id,created_at\n"
+        );
+        let mut out = String::new();
+        write_record(
+            ExportFormat::Csv,
+            ExportDataset::People,
+            &person("Werich, Jan"),
+            &mut out,
+        );
+        assert_eq!(
+            out,
+            "7,2026-10-01T08:00:00.000001Z,jan-werich,/osobnosti/jan-werich/,\
+             \"Werich, Jan\",,2026-04-21T00:00:00.000000Z\n"
+        );
+
+        let mut out = String::new();
+        csv_value(
+            &ExportValue::TextList(vec!["cs".into(), "en".into()]),
+            &mut out,
+        );
+        assert_eq!(out, "cs|en");
+    }
+
+    #[test]
+    fn unknown_formats_are_rejected() {
+        assert_eq!(ExportFormat::from_key(None), Some(ExportFormat::Ndjson));
+        assert_eq!(ExportFormat::from_key(Some("csv")), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_key(Some("xml")), None);
+    }
+}
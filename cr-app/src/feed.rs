@@ -0,0 +1,338 @@
+//! Atom and RSS rendering for the site feeds.
+//!
+//! The web layer fetches [`FeedEntry`]s through
+//! [`FeedRepository`](cr_domain::repository::FeedRepository) and wraps them
+//! in a [`Feed`]; this module only turns that into XML. Atom is the default;
+//! `?format=rss` gets RSS 2.0 for readers that still insist on it.
+//!
+//! Entry ids are `tag:` URIs built from [`FeedEntry::key`], so a renamed
+//! film or landmark is not re-announced under its new slug.
+
+use cr_domain::dto::FeedEntry;
+
+/// Entries per feed. Readers poll often enough that older entries would
+/// only be re-downloaded, never read.
+pub const FEED_LEN: i64 = 50;
+
+/// Longest summary, in chars, before it is cut at a word boundary.
+const SUMMARY_CHARS: usize = 400;
+
+/// Year part of the `tag:` URIs. Fixed for good — changing it would
+/// re-announce every entry.
+const TAG_YEAR: &str = "2026";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    /// `?format=` value; anything but `rss` is Atom.
+    pub fn from_key(key: Option<&str>) -> Self {
+        match key {
+            Some("rss") => Self::Rss,
+            _ => Self::Atom,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Rss => "application/rss+xml; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub title: String,
+    pub subtitle: String,
+    /// Site-relative URL of the feed itself, query string included.
+    pub self_path: String,
+    /// Site-relative URL of the HTML page the feed mirrors.
+    pub alternate_path: String,
+    pub entries: Vec<FeedEntry>,
+}
+
+impl Feed {
+    /// Render for `origin` (`https://ceskarepublika.wiki`).
+    pub fn render(&self, format: FeedFormat, origin: &str) -> String {
+        match format {
+            FeedFormat::Atom => self.atom(origin),
+            FeedFormat::Rss => self.rss(origin),
+        }
+    }
+
+    /// Newest entry `updated`, or the epoch for an empty feed (a fixed
+    /// value keeps empty feeds cacheable).
+    fn updated(&self) -> &str {
+        self.entries
+            .iter()
+            .map(|e| e.updated.as_str())
+            .max()
+            .unwrap_or("1970-01-01T00:00:00Z")
+    }
+
+    fn atom(&self, origin: &str) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"cs\">\n",
+        );
+        xml.push_str(&format!(
+            "<id>{}</id>\n<title>{}</title>\n<subtitle>{}</subtitle>\n<updated>{}</updated>\n\
+             <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n\
+             <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n\
+             <author><name>ceskarepublika.wiki</name></author>\n",
+            escape(&format!("{origin}{}", self.self_path)),
+            escape(&self.title),
+            escape(&self.subtitle),
+            self.updated(),
+            escape(&format!("{origin}{}", self.self_path)),
+            escape(&format!("{origin}{}", self.alternate_path)),
+        ));
+        for e in &self.entries {
+            xml.push_str(&format!(
+                "<entry>\n<id>{}</id>\n<title>{}</title>\n\
+                 <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n\
+                 <published>{}</published>\n<updated>{}</updated>\n",
+                escape(&tag_uri(origin, &e.key)),
+                escape(&e.title),
+                escape(&format!("{origin}{}", e.path)),
+                e.published,
+                e.updated,
+            ));
+            if let Some(summary) = e.summary.as_deref().map(shorten) {
+                xml.push_str(&format!("<summary>{}</summary>\n", escape(&summary)));
+            }
+            if let Some(html) = content_html(e, origin) {
+                xml.push_str(&format!(
+                    "<content type=\"html\">{}</content>\n",
+                    escape(&html)
+                ));
+            }
+            xml.push_str("</entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    fn rss(&self, origin: &str) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n",
+        );
+        xml.push_str(&format!(
+            "<title>{}</title>\n<link>{}</link>\n<description>{}</description>\n\
+             <language>cs</language>\n\
+             <atom:link rel=\"self\" type=\"application/rss+xml\" href=\"{}\"/>\n",
+            escape(&self.title),
+            escape(&format!("{origin}{}", self.alternate_path)),
+            escape(&self.subtitle),
+            escape(&format!("{origin}{}", self.self_path)),
+        ));
+        if let Some(date) = rfc822(self.updated()) {
+            xml.push_str(&format!("<lastBuildDate>{date}</lastBuildDate>\n"));
+        }
+        for e in &self.entries {
+            xml.push_str(&format!(
+                "<item>\n<title>{}</title>\n<link>{}</link>\n\
+                 <guid isPermaLink=\"false\">{}</guid>\n",
+                escape(&e.title),
+                escape(&format!("{origin}{}", e.path)),
+                escape(&tag_uri(origin, &e.key)),
+            ));
+            if let Some(date) = rfc822(&e.published) {
+                xml.push_str(&format!("<pubDate>{date}</pubDate>\n"));
+            }
+            if let Some(html) = content_html(e, origin) {
+                xml.push_str(&format!("<description>{}</description>\n", escape(&html)));
+            }
+            xml.push_str("</item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+}
+
+/// `tag:ceskarepublika.wiki,2026:film/123`
+fn tag_uri(origin: &str, key: &str) -> String {
+    let host = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .trim_end_matches('/');
+    format!("tag:{host},{TAG_YEAR}:{key}")
+}
+
+/// Cover plus summary as an HTML fragment; `None` when there's neither.
+fn content_html(e: &FeedEntry, origin: &str) -> Option<String> {
+    let mut html = String::new();
+    if let Some(image) = &e.image_path {
+        html.push_str(&format!(
+            "<p><img src=\"{}\" alt=\"{}\" width=\"200\"></p>",
+            escape(&format!("{origin}{image}")),
+            escape(&e.title)
+        ));
+    }
+    if let Some(summary) = &e.summary {
+        html.push_str(&format!("<p>{}</p>", escape(&shorten(summary))));
+    }
+    (!html.is_empty()).then_some(html)
+}
+
+/// Trim to [`SUMMARY_CHARS`], cutting at the last space and adding `…`.
+fn shorten(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= SUMMARY_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(SUMMARY_CHARS).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    format!("{}…", cut.trim_end_matches([',', '.', ';', ':']))
+}
+
+/// XML text and attribute escaping.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters are invalid in XML 1.0; scraped
+            // descriptions occasionally carry them.
+            c if c.is_control() && !matches!(c, '\n' | '\t' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// RFC 3339 UTC (`2026-06-24T08:15:00Z`) → RFC 822 as RSS wants it
+/// (`Wed, 24 Jun 2026 08:15:00 +0000`).
+fn rfc822(ts: &str) -> Option<String> {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let year: i64 = ts.get(0..4)?.parse().ok()?;
+    let month: u32 = ts.get(5..7)?.parse().ok()?;
+    let day: u32 = ts.get(8..10)?.parse().ok()?;
+    let time = ts.get(11..19)?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    let weekday = DAYS[days.rem_euclid(7) as usize];
+    Some(format!(
+        "{weekday}, {day:02} {} {year} {time} +0000",
+        MONTHS[month as usize - 1]
+    ))
+}
+
+/// Days since 1970-01-01 (Howard Hinnant's `days_from_civil`).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: &str = "https://ceskarepublika.wiki";
+
+    fn entry(key: &str, updated: &str) -> FeedEntry {
+        FeedEntry {
+            key: key.to_string(),
+            path: format!("/filmy-online/{key}/"),
+            title: "Tom & Jerry <1>".to_string(),
+            summary: Some("Kočka a myš.".to_string()),
+            image_path: Some("/filmy-online/tom.webp".to_string()),
+            published: "2026-06-01T10:00:00Z".to_string(),
+            updated: updated.to_string(),
+        }
+    }
+
+    fn feed(entries: Vec<FeedEntry>) -> Feed {
+        Feed {
+            title: "Nové filmy".to_string(),
+            subtitle: "Komedie".to_string(),
+            self_path: "/filmy-online/feed.xml?zanry=komedie&audio=cs".to_string(),
+            alternate_path: "/filmy-online/?zanry=komedie&audio=cs".to_string(),
+            entries,
+        }
+    }
+
+    #[test]
+    fn atom_escapes_and_uses_newest_update() {
+        let xml = feed(vec![
+            entry("film/1", "2026-06-02T10:00:00Z"),
+            entry("film/2", "2026-06-05T10:00:00Z"),
+        ])
+        .render(FeedFormat::Atom, ORIGIN);
+        assert!(xml.contains("<updated>2026-06-05T10:00:00Z</updated>\n<link rel=\"self\""));
+        assert!(xml.contains("feed.xml?zanry=komedie&amp;audio=cs"));
+        assert!(xml.contains("<title>Tom &amp; Jerry &lt;1&gt;</title>"));
+        assert!(xml.contains("<id>tag:ceskarepublika.wiki,2026:film/2</id>"));
+        // The HTML content is escaped once more as element text.
+        assert!(
+            xml.contains("&lt;img src=&quot;https://ceskarepublika.wiki/filmy-online/tom.webp")
+        );
+        assert_eq!(xml.matches("<entry>").count(), 2);
+    }
+
+    #[test]
+    fn empty_feed_has_a_fixed_update_time() {
+        let xml = feed(Vec::new()).render(FeedFormat::Atom, ORIGIN);
+        assert!(xml.contains("<updated>1970-01-01T00:00:00Z</updated>"));
+        assert!(!xml.contains("<entry>"));
+    }
+
+    #[test]
+    fn rss_uses_rfc822_dates_and_tag_guids() {
+        let xml =
+            feed(vec![entry("film/1", "2026-06-02T10:00:00Z")]).render(FeedFormat::Rss, ORIGIN);
+        assert!(xml.contains("<pubDate>Mon, 01 Jun 2026 10:00:00 +0000</pubDate>"));
+        assert!(xml.contains("<lastBuildDate>Tue, 02 Jun 2026 10:00:00 +0000</lastBuildDate>"));
+        assert!(
+            xml.contains("<guid isPermaLink=\"false\">tag:ceskarepublika.wiki,2026:film/1</guid>")
+        );
+    }
+
+    #[test]
+    fn rfc822_weekdays() {
+        assert_eq!(
+            rfc822("1970-01-01T00:00:00Z").as_deref(),
+            Some("Thu, 01 Jan 1970 00:00:00 +0000")
+        );
+        assert_eq!(
+            rfc822("2024-02-29T23:59:59Z").as_deref(),
+            Some("Thu, 29 Feb 2024 23:59:59 +0000")
+        );
+        assert_eq!(rfc822("garbage"), None);
+    }
+
+    #[test]
+    fn long_summaries_are_cut_at_a_word() {
+        let long = "slovo ".repeat(100);
+        let short = shorten(&long);
+        assert!(short.ends_with("slovo…"), "{short}");
+        assert!(short.chars().count() <= SUMMARY_CHARS + 1);
+        assert_eq!(shorten(" krátké "), "krátké");
+    }
+
+    #[test]
+    fn format_defaults_to_atom() {
+        assert_eq!(FeedFormat::from_key(None), FeedFormat::Atom);
+        assert_eq!(FeedFormat::from_key(Some("atom")), FeedFormat::Atom);
+        assert_eq!(FeedFormat::from_key(Some("rss")), FeedFormat::Rss);
+    }
+}
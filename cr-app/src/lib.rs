@@ -7,10 +7,24 @@
 //!
 //! ## Modules
 //!
+//! - `calendar` - Episode release calendar and its iCalendar export
+//! - `catalog` - Film, series and TV pořad listings, lookups and autocomplete
 //! - `error` - Application-layer error types
+//! - `export` - NDJSON and CSV writers for the open-data exports
+//! - `feed` - Atom and RSS rendering for the site feeds
 //! - `queries` - Read operations (homepage, region detail, etc.)
+//! - `search` - In-process full-text index behind catalog autocomplete
+//! - `similar` - "Similar titles" scoring for the detail-page carousels
+//! - `suggest` - Prefix index behind the site-wide search suggestions
 //! - `services` - Use-case orchestration (video publishing, stream resolution, etc.)
 
+pub mod calendar;
+pub mod catalog;
 pub mod error;
+pub mod export;
+pub mod feed;
 pub mod queries;
+pub mod search;
 pub mod services;
+pub mod similar;
+pub mod suggest;
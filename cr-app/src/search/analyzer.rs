@@ -0,0 +1,191 @@
+//! Text analysis shared by indexing and querying.
+//!
+//! Both sides go through the same pipeline — lower-case, fold diacritics,
+//! split on anything that isn't a letter or digit — so "Pelíšky",
+//! "PELISKY" and "pelíšky" all produce the token `pelisky`. The stemmer
+//! runs on folded tokens only; it doesn't have to be linguistically exact,
+//! just consistent between the document and the query.
+
+/// Lower-case `text` and strip Czech and Slovak diacritics, plus the
+/// common Western European ones that show up in original titles.
+/// Characters outside Latin script (Cyrillic, CJK, …) pass through
+/// lower-cased.
+pub fn fold(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match c {
+            'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' | 'ą' => out.push('a'),
+            'č' | 'ć' | 'ç' => out.push('c'),
+            'ď' => out.push('d'),
+            'é' | 'ě' | 'è' | 'ê' | 'ë' | 'ę' => out.push('e'),
+            'í' | 'ì' | 'î' | 'ï' => out.push('i'),
+            'ľ' | 'ĺ' | 'ł' => out.push('l'),
+            'ň' | 'ñ' | 'ń' => out.push('n'),
+            'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'ø' | 'ő' => out.push('o'),
+            'ř' | 'ŕ' => out.push('r'),
+            'š' | 'ś' => out.push('s'),
+            'ť' => out.push('t'),
+            'ú' | 'ů' | 'ù' | 'û' | 'ü' | 'ű' => out.push('u'),
+            'ý' | 'ÿ' => out.push('y'),
+            'ž' | 'ź' | 'ż' => out.push('z'),
+            'ß' => out.push_str("ss"),
+            'æ' => out.push_str("ae"),
+            'œ' => out.push_str("oe"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Folded tokens of `text`, in order. Apostrophes are dropped rather than
+/// split on, so "Schindler's" stays one token.
+pub fn tokenize(text: &str) -> Vec<String> {
+    fold(text)
+        .replace(['\'', '’'], "")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Light Czech stemmer over a folded token: strips case endings and
+/// possessive suffixes, then undoes the palatalisation those endings cause
+/// ("vlcich" → "vlk"). A folded port of the Dolamic–Savoy light stemmer;
+/// tokens with digits are returned unchanged.
+pub fn stem(token: &str) -> String {
+    if !token.chars().all(char::is_alphabetic) {
+        return token.to_string();
+    }
+    let without_case = remove_case(token);
+    remove_possessives(&without_case)
+}
+
+fn len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// `word` minus its last `n` bytes (every suffix here is ASCII).
+fn cut(word: &str, n: usize) -> &str {
+    &word[..word.len() - n]
+}
+
+fn remove_case(word: &str) -> String {
+    let n = len(word);
+    if n > 7 && word.ends_with("atech") {
+        return cut(word, 5).to_string();
+    }
+    if n > 6 {
+        if word.ends_with("etem") {
+            return palatalise(cut(word, 3));
+        }
+        if word.ends_with("atum") {
+            return cut(word, 4).to_string();
+        }
+    }
+    if n > 5 {
+        const PALATAL: [&str; 10] = [
+            "ech", "ich", "eho", "emi", "emu", "ete", "eti", "iho", "imi", "imu",
+        ];
+        if PALATAL.iter().any(|s| word.ends_with(s)) {
+            return palatalise(cut(word, 2));
+        }
+        const HARD: [&str; 9] = [
+            "ach", "ata", "aty", "ych", "ama", "ami", "ove", "ovi", "ymi",
+        ];
+        if HARD.iter().any(|s| word.ends_with(s)) {
+            return cut(word, 3).to_string();
+        }
+    }
+    if n > 4 {
+        if word.ends_with("em") {
+            return palatalise(cut(word, 1));
+        }
+        if word.ends_with("es") || word.ends_with("im") {
+            return palatalise(cut(word, 2));
+        }
+        const HARD: [&str; 8] = ["um", "at", "am", "os", "us", "ym", "mi", "ou"];
+        if HARD.iter().any(|s| word.ends_with(s)) {
+            return cut(word, 2).to_string();
+        }
+    }
+    if n > 3 {
+        if word.ends_with('e') || word.ends_with('i') {
+            return palatalise(word);
+        }
+        if ['u', 'y', 'a', 'o'].iter().any(|&c| word.ends_with(c)) {
+            return cut(word, 1).to_string();
+        }
+    }
+    word.to_string()
+}
+
+fn remove_possessives(word: &str) -> String {
+    if len(word) > 5 {
+        if word.ends_with("ov") || word.ends_with("uv") {
+            return cut(word, 2).to_string();
+        }
+        if word.ends_with("in") {
+            return palatalise(cut(word, 1));
+        }
+    }
+    word.to_string()
+}
+
+/// Drop the final vowel of `word`, restoring the consonant a palatal
+/// ending softened: `ci`/`ce` → `k`, `zi`/`ze` → `h`, `cte`/`cti` → `ck`,
+/// `ste`/`sti` → `sk`.
+fn palatalise(word: &str) -> String {
+    for (suffixes, replacement) in [
+        (["cte", "cti"], "ck"),
+        (["ste", "sti"], "sk"),
+        (["ci", "ce"], "k"),
+        (["zi", "ze"], "h"),
+    ] {
+        if let Some(s) = suffixes.iter().find(|s| word.ends_with(*s)) {
+            return format!("{}{replacement}", cut(word, s.len()));
+        }
+    }
+    let mut chars = word.chars();
+    chars.next_back();
+    chars.as_str().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_strips_czech_diacritics_and_case() {
+        assert_eq!(fold("Příliš žluťoučký kůň"), "prilis zlutoucky kun");
+        assert_eq!(fold("ĽUDOVÁ Pieseň"), "ludova piesen");
+        assert_eq!(fold("Amélie"), "amelie");
+        assert_eq!(fold("Straße"), "strasse");
+    }
+
+    #[test]
+    fn tokenize_splits_on_punctuation_and_keeps_digits() {
+        assert_eq!(
+            tokenize("Mstitel (1989): Návrat!"),
+            vec!["mstitel", "1989", "navrat"]
+        );
+        assert_eq!(tokenize("Schindler's List"), vec!["schindlers", "list"]);
+        assert!(tokenize(" - ").is_empty());
+    }
+
+    #[test]
+    fn stem_conflates_inflected_forms() {
+        // Nominative and the common oblique cases reduce to one stem.
+        for form in ["pelisky", "peliskach", "peliskum", "peliskami"] {
+            assert_eq!(stem(form), "pelisk", "{form}");
+        }
+        assert_eq!(stem("vlci"), stem("vlk"));
+        assert_eq!(stem("hrdinove"), stem("hrdina"));
+    }
+
+    #[test]
+    fn stem_leaves_short_and_numeric_tokens_alone() {
+        assert_eq!(stem("les"), "les");
+        assert_eq!(stem("1989"), "1989");
+        assert_eq!(stem("k19"), "k19");
+    }
+}
@@ -0,0 +1,79 @@
+//! Typo tolerance: bounded edit distance between folded tokens.
+
+/// Edits a query token of `len` chars may be away from an indexed term.
+/// Short tokens must match exactly — at three letters a single edit
+/// already reaches half the vocabulary.
+pub fn max_edits(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Optimal-string-alignment distance between `a` and `b` (insertions,
+/// deletions, substitutions and adjacent transpositions), or `None` once
+/// it is certain to exceed `max`.
+pub fn distance_within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    // Three rolling rows: two back for transpositions, one back, current.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev_min = 0;
+    for i in 1..=a.len() {
+        cur[0] = i;
+        let mut row_min = cur[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(before[j - 2] + 1);
+            }
+            cur[j] = d;
+            row_min = row_min.min(d);
+        }
+        // A transposition can reach back two rows, so only give up once
+        // two consecutive rows are over budget.
+        if row_min > max && prev_min > max {
+            return None;
+        }
+        prev_min = row_min;
+        std::mem::swap(&mut before, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    let d = prev[b.len()];
+    (d <= max).then_some(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_single_edits() {
+        assert_eq!(distance_within("vetrelec", "vetrelec", 2), Some(0));
+        assert_eq!(distance_within("vetrelc", "vetrelec", 2), Some(1));
+        assert_eq!(distance_within("vetrelex", "vetrelec", 2), Some(1));
+        // Adjacent transposition is one edit, not two.
+        assert_eq!(distance_within("vetrleec", "vetrelec", 2), Some(1));
+    }
+
+    #[test]
+    fn gives_up_beyond_the_bound() {
+        assert_eq!(distance_within("pelisky", "pelikan", 1), None);
+        assert_eq!(distance_within("kolja", "kolja-extra", 2), None);
+        assert_eq!(distance_within("abc", "xyz", 3), Some(3));
+    }
+
+    #[test]
+    fn edit_budget_grows_with_length() {
+        assert_eq!(max_edits(3), 0);
+        assert_eq!(max_edits(5), 1);
+        assert_eq!(max_edits(9), 2);
+    }
+}
@@ -0,0 +1,523 @@
+//! Inverted index over [`SearchDocument`]s.
+//!
+//! Every analysed token maps to a posting list of `(document, field)`
+//! pairs, keeping only the strongest field a token appears in for each
+//! document. Tokens live in a `BTreeMap` so the last word of a query can be
+//! matched as a prefix ("vetre" → "vetrelec") and typo candidates can be
+//! scanned by first letter; stems sit in a separate map so "pelíškách"
+//! finds "Pelíšky".
+//!
+//! Slots of removed documents are reused, so the index can be updated in
+//! place without ever being rebuilt.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+use cr_domain::catalog::CatalogKind;
+use cr_domain::dto::{SearchDocument, TitleSuggestion};
+
+use super::analyzer::{stem, tokenize};
+use super::fuzzy::{distance_within, max_edits};
+
+/// Where in a document a token was found. Declared weakest first so the
+/// derived `Ord` picks the strongest field when a token repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Field {
+    Description,
+    Year,
+    People,
+    OriginalTitle,
+    Title,
+}
+
+impl Field {
+    fn boost(self) -> f32 {
+        match self {
+            Self::Title => 8.0,
+            Self::OriginalTitle => 5.0,
+            Self::People => 3.0,
+            Self::Year => 2.0,
+            Self::Description => 1.0,
+        }
+    }
+}
+
+/// How closely a query token matched an indexed one.
+const EXACT: f32 = 1.0;
+const STEM: f32 = 0.85;
+const PREFIX: f32 = 0.75;
+const FUZZY: f32 = 0.6;
+
+/// Cap on the terms one prefix or typo may expand to. A two-letter prefix
+/// of a description word can otherwise touch most of the vocabulary.
+const MAX_EXPANSIONS: usize = 256;
+
+/// Description words shorter than this are mostly prepositions and
+/// conjunctions ("na", "se", "a") and would only add noise.
+const MIN_DESCRIPTION_TOKEN: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    doc: u32,
+    field: Field,
+}
+
+#[derive(Debug)]
+struct StoredDoc {
+    kind: CatalogKind,
+    rev: i64,
+    slug: String,
+    title: String,
+    year: Option<i16>,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+    votes: i64,
+    /// Folded title tokens joined by spaces, for the whole-title bonus.
+    title_key: String,
+    original_title_key: String,
+    /// Keys this document was posted under, for removal.
+    terms: Vec<String>,
+    stems: Vec<String>,
+}
+
+impl StoredDoc {
+    fn suggestion(&self) -> TitleSuggestion {
+        TitleSuggestion {
+            slug: self.slug.clone(),
+            title: self.title.clone(),
+            year: self.year,
+            tmdb_rating: self.tmdb_rating,
+            imdb_rating: self.imdb_rating,
+        }
+    }
+
+    /// Ranking multiplier for how well known the title is: 1.0 with no
+    /// votes, ~2.1 at a million. Enough to order namesakes, not enough to
+    /// lift a description match over an obscure title match.
+    fn popularity(&self) -> f32 {
+        1.0 + (self.votes.max(0) as f32).ln_1p() / 12.0
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    docs: Vec<Option<StoredDoc>>,
+    free: Vec<u32>,
+    slots: HashMap<(CatalogKind, i32), u32>,
+    terms: BTreeMap<String, Vec<Posting>>,
+    stems: HashMap<String, Vec<Posting>>,
+    rev: i64,
+}
+
+impl SearchIndex {
+    pub fn from_documents(docs: impl IntoIterator<Item = SearchDocument>) -> Self {
+        let mut index = Self::default();
+        for doc in docs {
+            index.upsert(doc);
+        }
+        index
+    }
+
+    /// Number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Highest `search_rev` seen — the cursor for the next incremental
+    /// refresh.
+    pub fn rev(&self) -> i64 {
+        self.rev
+    }
+
+    /// Whether the index already holds this revision of `doc`.
+    pub fn is_current(&self, doc: &SearchDocument) -> bool {
+        self.slots
+            .get(&(doc.kind, doc.id))
+            .and_then(|&slot| self.docs[slot as usize].as_ref())
+            .is_some_and(|stored| stored.rev == doc.rev)
+    }
+
+    /// Add `doc`, replacing any earlier version of the same title.
+    pub fn upsert(&mut self, doc: SearchDocument) {
+        self.rev = self.rev.max(doc.rev);
+        self.remove(doc.kind, doc.id);
+
+        let mut fields: HashMap<String, Field> = HashMap::new();
+        let mut post = |text: &str, field: Field, min_len: usize| {
+            for token in tokenize(text) {
+                if token.chars().count() < min_len {
+                    continue;
+                }
+                let best = fields.entry(token).or_insert(field);
+                *best = (*best).max(field);
+            }
+        };
+        post(&doc.title, Field::Title, 1);
+        if let Some(original) = &doc.original_title {
+            post(original, Field::OriginalTitle, 1);
+        }
+        if let Some(year) = doc.year {
+            post(&year.to_string(), Field::Year, 1);
+        }
+        for person in &doc.people {
+            post(person, Field::People, 1);
+        }
+        if let Some(description) = &doc.description {
+            post(description, Field::Description, MIN_DESCRIPTION_TOKEN);
+        }
+
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                self.docs.push(None);
+                (self.docs.len() - 1) as u32
+            }
+        };
+        let mut stems: HashMap<String, Field> = HashMap::new();
+        for (term, &field) in &fields {
+            let best = stems.entry(stem(term)).or_insert(field);
+            *best = (*best).max(field);
+        }
+        for (term, &field) in &fields {
+            self.terms
+                .entry(term.clone())
+                .or_default()
+                .push(Posting { doc: slot, field });
+        }
+        for (s, &field) in &stems {
+            self.stems
+                .entry(s.clone())
+                .or_default()
+                .push(Posting { doc: slot, field });
+        }
+
+        self.docs[slot as usize] = Some(StoredDoc {
+            kind: doc.kind,
+            rev: doc.rev,
+            title_key: tokenize(&doc.title).join(" "),
+            original_title_key: doc
+                .original_title
+                .as_deref()
+                .map(|t| tokenize(t).join(" "))
+                .unwrap_or_default(),
+            slug: doc.slug,
+            title: doc.title,
+            year: doc.year,
+            tmdb_rating: doc.tmdb_rating,
+            imdb_rating: doc.imdb_rating,
+            votes: doc.votes,
+            terms: fields.into_keys().collect(),
+            stems: stems.into_keys().collect(),
+        });
+        self.slots.insert((doc.kind, doc.id), slot);
+    }
+
+    /// Drop a title from the index. No-op when it isn't indexed.
+    pub fn remove(&mut self, kind: CatalogKind, id: i32) {
+        let Some(slot) = self.slots.remove(&(kind, id)) else {
+            return;
+        };
+        let Some(old) = self.docs[slot as usize].take() else {
+            return;
+        };
+        for term in old.terms {
+            if let Some(list) = self.terms.get_mut(&term) {
+                list.retain(|p| p.doc != slot);
+                if list.is_empty() {
+                    self.terms.remove(&term);
+                }
+            }
+        }
+        for s in old.stems {
+            if let Some(list) = self.stems.get_mut(&s) {
+                list.retain(|p| p.doc != slot);
+                if list.is_empty() {
+                    self.stems.remove(&s);
+                }
+            }
+        }
+        self.free.push(slot);
+    }
+
+    /// Best `limit` titles of `kind` for `query`. Every query word has to
+    /// match somewhere — exactly, by stem, as a typo, or (for the word
+    /// still being typed) as a prefix.
+    pub fn search(&self, kind: CatalogKind, query: &str, limit: usize) -> Vec<TitleSuggestion> {
+        let tokens = tokenize(query);
+        let Some(last) = tokens.len().checked_sub(1) else {
+            return Vec::new();
+        };
+
+        let mut totals: Option<HashMap<u32, f32>> = None;
+        for (i, token) in tokens.iter().enumerate() {
+            let mut scores = self.token_scores(kind, token, i == last);
+            if let Some(prev) = totals {
+                scores.retain(|doc, _| prev.contains_key(doc));
+                for (doc, score) in scores.iter_mut() {
+                    *score += prev[doc];
+                }
+            }
+            if scores.is_empty() {
+                return Vec::new();
+            }
+            totals = Some(scores);
+        }
+
+        let query_key = tokens.join(" ");
+        let mut ranked: Vec<(f32, &StoredDoc)> = totals
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(slot, score)| {
+                let doc = self.docs[slot as usize].as_ref()?;
+                Some((
+                    score * whole_title_bonus(doc, &query_key) * doc.popularity(),
+                    doc,
+                ))
+            })
+            .collect();
+        ranked.sort_by(|(a, da), (b, db)| {
+            b.total_cmp(a)
+                .then(db.votes.cmp(&da.votes))
+                .then_with(|| da.title.cmp(&db.title))
+        });
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, doc)| doc.suggestion())
+            .collect()
+    }
+
+    /// Best score per document of `kind` for one query token.
+    fn token_scores(&self, kind: CatalogKind, token: &str, is_last: bool) -> HashMap<u32, f32> {
+        let mut scores: HashMap<u32, f32> = HashMap::new();
+        let mut add = |postings: &[Posting], quality: f32| {
+            for p in postings {
+                if self.docs[p.doc as usize].as_ref().map(|d| d.kind) != Some(kind) {
+                    continue;
+                }
+                let score = quality * p.field.boost();
+                let best = scores.entry(p.doc).or_insert(score);
+                *best = best.max(score);
+            }
+        };
+
+        if let Some(postings) = self.terms.get(token) {
+            add(postings, EXACT);
+        }
+        if let Some(postings) = self.stems.get(&stem(token)) {
+            add(postings, STEM);
+        }
+        if is_last {
+            for (_, postings) in self
+                .terms
+                .range::<str, _>((Bound::Included(token), Bound::Unbounded))
+                .take_while(|(term, _)| term.starts_with(token))
+                .take(MAX_EXPANSIONS)
+            {
+                add(postings, PREFIX);
+            }
+        }
+
+        // Years and numbers in titles must match as typed: "1991" is not
+        // a typo of "1990".
+        let budget = if token.chars().all(char::is_alphabetic) {
+            max_edits(token.chars().count())
+        } else {
+            0
+        };
+        if budget > 0 {
+            // Candidates share the first letter: typos there are rare and
+            // scanning one letter's slice keeps the lookup cheap.
+            let mut buf = [0; 4];
+            let first: &str = token
+                .chars()
+                .next()
+                .unwrap_or_default()
+                .encode_utf8(&mut buf);
+            let mut expanded = 0;
+            for (term, postings) in self
+                .terms
+                .range::<str, _>((Bound::Included(first), Bound::Unbounded))
+                .take_while(|(term, _)| term.starts_with(first))
+            {
+                let Some(d) = distance_within(token, term, budget) else {
+                    continue;
+                };
+                if d == 0 {
+                    continue;
+                }
+                add(postings, FUZZY / d as f32);
+                expanded += 1;
+                if expanded == MAX_EXPANSIONS {
+                    break;
+                }
+            }
+        }
+        scores
+    }
+}
+
+/// Extra weight when the query is the whole title (×2) or its start
+/// (×1.5), so "Vetřelec" ranks the film itself above "Vetřelec 3".
+fn whole_title_bonus(doc: &StoredDoc, query_key: &str) -> f32 {
+    let keys = [doc.title_key.as_str(), doc.original_title_key.as_str()];
+    if keys.contains(&query_key) {
+        2.0
+    } else if keys
+        .iter()
+        .any(|k| !k.is_empty() && k.starts_with(query_key))
+    {
+        1.5
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn film(id: i32, title: &str, original: Option<&str>, votes: i64) -> SearchDocument {
+        SearchDocument {
+            kind: CatalogKind::Film,
+            id,
+            rev: i64::from(id),
+            slug: format!("film-{id}"),
+            title: title.to_string(),
+            original_title: original.map(str::to_string),
+            year: Some(1990),
+            people: Vec::new(),
+            description: None,
+            tmdb_rating: None,
+            imdb_rating: None,
+            votes,
+        }
+    }
+
+    fn slugs(hits: Vec<TitleSuggestion>) -> Vec<String> {
+        hits.into_iter().map(|h| h.slug).collect()
+    }
+
+    #[test]
+    fn matches_without_diacritics_and_by_prefix() {
+        let index = SearchIndex::from_documents([
+            film(1, "Vetřelec", Some("Alien"), 900_000),
+            film(2, "Pelíšky", None, 40_000),
+        ]);
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "vetrelec", 10)),
+            ["film-1"]
+        );
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "VETŘE", 10)),
+            ["film-1"]
+        );
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "alien", 10)),
+            ["film-1"]
+        );
+        // Stemming: an inflected form finds the nominative title.
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "pelíškách", 10)),
+            ["film-2"]
+        );
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        let index = SearchIndex::from_documents([film(1, "Vetřelec", None, 0)]);
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "vetrelc", 10)),
+            ["film-1"]
+        );
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "vetrleec", 10)),
+            ["film-1"]
+        );
+        assert!(index.search(CatalogKind::Film, "vxtrxlxc", 10).is_empty());
+    }
+
+    #[test]
+    fn every_query_word_must_match() {
+        let index = SearchIndex::from_documents([
+            film(1, "Mstitel", None, 0),
+            film(2, "Mstitel z Bronxu", None, 0),
+        ]);
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "mstitel bronx", 10)),
+            ["film-2"]
+        );
+        // The year is searchable too, so "Title (1990)" needs no fallback.
+        assert_eq!(
+            index.search(CatalogKind::Film, "Mstitel (1990)", 10).len(),
+            2
+        );
+        assert!(
+            index
+                .search(CatalogKind::Film, "mstitel 1991", 10)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn title_outranks_cast_and_description() {
+        let mut by_cast = film(1, "Obecná škola", None, 0);
+        by_cast.people = vec!["Jan Kolja".to_string()];
+        let mut by_description = film(2, "Kolja", None, 0);
+        by_description.title = "Zcela jiný film".to_string();
+        by_description.description = Some("Příběh o chlapci jménem Kolja.".to_string());
+        let by_title = film(3, "Kolja", None, 0);
+        let index = SearchIndex::from_documents([by_cast, by_description, by_title]);
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "kolja", 10)),
+            ["film-3", "film-1", "film-2"]
+        );
+    }
+
+    #[test]
+    fn whole_title_and_popularity_order_namesakes() {
+        let index = SearchIndex::from_documents([
+            film(1, "Vetřelec 3", None, 300_000),
+            film(2, "Vetřelec", None, 900_000),
+            film(3, "Vetřelec: Romulus", None, 50_000),
+        ]);
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "vetřelec", 10)),
+            ["film-2", "film-1", "film-3"]
+        );
+    }
+
+    #[test]
+    fn upsert_replaces_and_remove_forgets() {
+        let mut index = SearchIndex::from_documents([film(1, "Pelíšky", None, 0)]);
+        let mut renamed = film(1, "Pupendo", None, 0);
+        renamed.rev = 7;
+        index.upsert(renamed);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.rev(), 7);
+        assert!(index.search(CatalogKind::Film, "pelisky", 10).is_empty());
+        assert_eq!(
+            slugs(index.search(CatalogKind::Film, "pupendo", 10)),
+            ["film-1"]
+        );
+
+        index.remove(CatalogKind::Film, 1);
+        assert!(index.is_empty());
+        assert!(index.search(CatalogKind::Film, "pupendo", 10).is_empty());
+    }
+
+    #[test]
+    fn kinds_are_searched_separately() {
+        let mut series = film(1, "Přátelé", None, 0);
+        series.kind = CatalogKind::Series;
+        let index = SearchIndex::from_documents([series, film(1, "Přátelé do deště", None, 0)]);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.search(CatalogKind::Series, "pratele", 10).len(), 1);
+        assert_eq!(index.search(CatalogKind::Film, "pratele", 10).len(), 1);
+        assert!(index.search(CatalogKind::TvShow, "pratele", 10).is_empty());
+    }
+}
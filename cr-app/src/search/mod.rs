@@ -0,0 +1,229 @@
+//! In-process full-text search over the film, series and TV pořad catalog.
+//!
+//! Backs the `/api/*/search` autocomplete endpoints. The index is built
+//! from [`SearchDocumentRepository`] in the background after startup and
+//! kept current by polling for rows with a newer `search_rev`; until the
+//! first build finishes, [`CatalogSearch::suggest`] returns `None` and the
+//! handlers fall back to the SQL `suggest` queries.
+//!
+//! Matching folds diacritics, stems Czech case endings, tolerates typos
+//! and weights hits by field (title > original title > cast > description)
+//! and by popularity.
+
+pub(crate) mod analyzer;
+mod fuzzy;
+mod index;
+
+use std::sync::{PoisonError, RwLock};
+
+use cr_domain::catalog::CatalogKind;
+use cr_domain::dto::TitleSuggestion;
+use cr_domain::repository::SearchDocumentRepository;
+
+use crate::catalog::repo_err;
+use crate::error::AppError;
+
+pub use index::SearchIndex;
+
+/// Hits returned per autocomplete request, matching the SQL fallback.
+pub const SUGGEST_LIMIT: usize = 10;
+
+/// Revisions below the cursor that every refresh reads again. `search_rev`
+/// comes from a sequence, so a transaction can commit after one that drew
+/// a later revision; its rows then sit behind the cursor. Re-reading this
+/// window picks them up on the next poll, and rows already indexed at the
+/// same revision are skipped. A transaction that stays open while more
+/// revisions than this are drawn elsewhere waits for the hourly rebuild.
+const REV_OVERLAP: i64 = 1_000;
+
+/// Shared handle to the catalog index. Searches take a read lock for the
+/// few hundred microseconds a lookup needs; refreshes swap or patch the
+/// index under the write lock once the database round-trip is done.
+#[derive(Debug, Default)]
+pub struct CatalogSearch {
+    index: RwLock<Option<SearchIndex>>,
+}
+
+impl CatalogSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True once the first full build has finished.
+    pub fn is_ready(&self) -> bool {
+        self.index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    /// Load the whole catalog into a fresh index and swap it in. Also how
+    /// deleted titles leave the index — the change cursor can't see them.
+    pub async fn rebuild<R: SearchDocumentRepository>(&self, repo: &R) -> Result<usize, AppError> {
+        let docs = repo.changed_since(0).await.map_err(repo_err)?;
+        let index = SearchIndex::from_documents(docs);
+        let len = index.len();
+        *self.index.write().unwrap_or_else(PoisonError::into_inner) = Some(index);
+        Ok(len)
+    }
+
+    /// Re-index titles changed since the last build or refresh, reading
+    /// [`REV_OVERLAP`] revisions back. Falls back to [`Self::rebuild`]
+    /// before the first build. Returns how many documents were
+    /// (re)indexed.
+    pub async fn refresh<R: SearchDocumentRepository>(&self, repo: &R) -> Result<usize, AppError> {
+        // The read guard must be gone before `rebuild` takes the write lock.
+        let since = self
+            .index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map(SearchIndex::rev);
+        let Some(since) = since else {
+            return self.rebuild(repo).await;
+        };
+        let docs = repo
+            .changed_since((since - REV_OVERLAP).max(0))
+            .await
+            .map_err(repo_err)?;
+        let mut changed = 0;
+        if !docs.is_empty()
+            && let Some(index) = self
+                .index
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .as_mut()
+        {
+            for doc in docs {
+                if !index.is_current(&doc) {
+                    index.upsert(doc);
+                    changed += 1;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Autocomplete hits for `query`, or `None` while the index is still
+    /// being built.
+    pub fn suggest(&self, kind: CatalogKind, query: &str) -> Option<Vec<TitleSuggestion>> {
+        self.index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map(|index| index.search(kind, query, SUGGEST_LIMIT))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cr_domain::dto::SearchDocument;
+    use std::sync::Mutex;
+
+    /// Serves every document with `rev > since`, like the Pg source.
+    struct FakeSource(Mutex<Vec<SearchDocument>>);
+
+    impl SearchDocumentRepository for FakeSource {
+        type Error = ();
+
+        async fn changed_since(&self, since: i64) -> Result<Vec<SearchDocument>, ()> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|d| d.rev > since)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn doc(id: i32, rev: i64, title: &str) -> SearchDocument {
+        SearchDocument {
+            kind: CatalogKind::Series,
+            id,
+            rev,
+            slug: format!("serial-{id}"),
+            title: title.to_string(),
+            original_title: None,
+            year: None,
+            people: Vec::new(),
+            description: None,
+            tmdb_rating: None,
+            imdb_rating: None,
+            votes: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_picks_up_only_newer_revisions() {
+        let source = FakeSource(Mutex::new(vec![doc(1, 1, "Přátelé")]));
+        let search = CatalogSearch::new();
+        assert!(search.suggest(CatalogKind::Series, "pratele").is_none());
+
+        // The first refresh is a full build.
+        assert_eq!(search.refresh(&source).await.unwrap(), 1);
+        assert!(search.is_ready());
+        assert_eq!(
+            search
+                .suggest(CatalogKind::Series, "pratele")
+                .unwrap()
+                .len(),
+            1
+        );
+
+        source.0.lock().unwrap().push(doc(2, 5, "Vyvolení"));
+        assert_eq!(search.refresh(&source).await.unwrap(), 1);
+        assert_eq!(search.refresh(&source).await.unwrap(), 0);
+        let hits = search.suggest(CatalogKind::Series, "vyvoleni").unwrap();
+        assert_eq!(hits[0].slug, "serial-2");
+    }
+
+    #[tokio::test]
+    async fn refresh_catches_rows_committed_behind_the_cursor() {
+        let source = FakeSource(Mutex::new(vec![
+            doc(1, 1, "Přátelé"),
+            doc(2, 9, "Vyvolení"),
+        ]));
+        let search = CatalogSearch::new();
+        assert_eq!(search.refresh(&source).await.unwrap(), 2);
+
+        // Revision 5 was drawn before 9 but committed after it.
+        source.0.lock().unwrap().push(doc(3, 5, "Ordinace"));
+        assert_eq!(search.refresh(&source).await.unwrap(), 1);
+        assert_eq!(search.refresh(&source).await.unwrap(), 0);
+        assert!(
+            !search
+                .suggest(CatalogKind::Series, "ordinace")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn rebuild_drops_deleted_titles() {
+        let source = FakeSource(Mutex::new(vec![
+            doc(1, 1, "Přátelé"),
+            doc(2, 2, "Vyvolení"),
+        ]));
+        let search = CatalogSearch::new();
+        assert_eq!(search.rebuild(&source).await.unwrap(), 2);
+
+        source.0.lock().unwrap().retain(|d| d.id != 1);
+        assert_eq!(search.refresh(&source).await.unwrap(), 0);
+        assert!(
+            !search
+                .suggest(CatalogKind::Series, "pratele")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(search.rebuild(&source).await.unwrap(), 1);
+        assert!(
+            search
+                .suggest(CatalogKind::Series, "pratele")
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! "Similar titles" scoring for the film and series detail carousels.
+//!
+//! Pure computation over [`SimilarityProfile`]s; the daily job in cr-web
+//! loads the profiles, runs [`compute_similar`] off the async runtime and
+//! stores the result through [`SimilarTitlesRepository`].
+//!
+//! A pair is only considered when the titles share a person or their
+//! rarest genre — comparing all 28 k films against each other would be
+//! 400 M pairs, while the candidates this yields are a few hundred per
+//! title.
+//!
+//! [`SimilarTitlesRepository`]: cr_domain::repository::SimilarTitlesRepository
+
+use std::collections::{HashMap, HashSet};
+
+use cr_domain::dto::{SimilarTitles, SimilarityProfile};
+
+/// Matches kept per title; the carousel shows fewer after dropping titles
+/// that lost their video source since the run.
+pub const SIMILAR_PER_TITLE: usize = 20;
+
+/// Weight of the genre Jaccard index (0–1).
+const GENRE_WEIGHT: f32 = 4.0;
+/// Per shared director, at most [`MAX_SHARED_DIRECTORS`] counted.
+const DIRECTOR_WEIGHT: f32 = 3.0;
+const MAX_SHARED_DIRECTORS: usize = 2;
+/// Per shared actor, at most [`MAX_SHARED_ACTORS`] counted.
+const ACTOR_WEIGHT: f32 = 1.0;
+const MAX_SHARED_ACTORS: usize = 4;
+/// Year proximity, falling linearly to zero at [`YEAR_SPAN`] years apart.
+const YEAR_WEIGHT: f32 = 1.5;
+const YEAR_SPAN: f32 = 15.0;
+/// Rating proximity on a 0–10 scale, zero at [`RATING_SPAN`] points apart.
+const RATING_WEIGHT: f32 = 1.0;
+const RATING_SPAN: f32 = 3.0;
+
+/// Genre-only candidates further apart than this are skipped before
+/// scoring — the year term alone couldn't rescue them.
+const GENRE_CANDIDATE_YEARS: i16 = 12;
+
+/// Pairs below this are noise (one loosely shared genre and nothing else).
+const MIN_SCORE: f32 = 3.0;
+
+/// Consensus rating on a 0–10 scale: the mean of whichever of TMDB, IMDb
+/// and ČSFD (scaled from percent) the title has.
+pub fn consensus_rating(p: &SimilarityProfile) -> Option<f32> {
+    let ratings = [
+        p.tmdb_rating,
+        p.imdb_rating,
+        p.csfd_rating.map(|r| f32::from(r) / 10.0),
+    ];
+    let known: Vec<f32> = ratings.into_iter().flatten().collect();
+    (!known.is_empty()).then(|| known.iter().sum::<f32>() / known.len() as f32)
+}
+
+fn shared(a: &[i32], b: &[i32]) -> usize {
+    // Both sorted: linear merge.
+    let (mut i, mut j, mut n) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                n += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    n
+}
+
+/// Similarity of two titles. Zero unless they share a genre or a person.
+pub fn score(a: &SimilarityProfile, b: &SimilarityProfile) -> f32 {
+    let genres = shared(&a.genre_ids, &b.genre_ids);
+    let directors = shared(&a.director_ids, &b.director_ids);
+    let actors = shared(&a.actor_ids, &b.actor_ids);
+    if genres == 0 && directors == 0 && actors == 0 {
+        return 0.0;
+    }
+
+    let union = a.genre_ids.len() + b.genre_ids.len() - genres;
+    let mut total = if union > 0 {
+        GENRE_WEIGHT * genres as f32 / union as f32
+    } else {
+        0.0
+    };
+    total += DIRECTOR_WEIGHT * directors.min(MAX_SHARED_DIRECTORS) as f32;
+    total += ACTOR_WEIGHT * actors.min(MAX_SHARED_ACTORS) as f32;
+    if let (Some(ya), Some(yb)) = (a.year, b.year) {
+        let gap = f32::from((ya - yb).abs());
+        total += YEAR_WEIGHT * (1.0 - gap / YEAR_SPAN).max(0.0);
+    }
+    if let (Some(ra), Some(rb)) = (consensus_rating(a), consensus_rating(b)) {
+        total += RATING_WEIGHT * (1.0 - (ra - rb).abs() / RATING_SPAN).max(0.0);
+    }
+    total
+}
+
+/// Best `per_title` matches for every profile that has any, strongest
+/// first. Titles with no match above the noise floor are left out.
+pub fn compute_similar(profiles: &[SimilarityProfile], per_title: usize) -> Vec<SimilarTitles> {
+    let mut by_genre: HashMap<i32, Vec<usize>> = HashMap::new();
+    let mut by_person: HashMap<i32, Vec<usize>> = HashMap::new();
+    for (idx, p) in profiles.iter().enumerate() {
+        for &g in &p.genre_ids {
+            by_genre.entry(g).or_default().push(idx);
+        }
+        // Directors and actors share the `people` id space.
+        for &person in p.director_ids.iter().chain(&p.actor_ids) {
+            by_person.entry(person).or_default().push(idx);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut candidates: HashSet<usize> = HashSet::new();
+    for (idx, p) in profiles.iter().enumerate() {
+        candidates.clear();
+        for person in p.director_ids.iter().chain(&p.actor_ids) {
+            candidates.extend(&by_person[person]);
+        }
+        let rarest = p
+            .genre_ids
+            .iter()
+            .map(|g| &by_genre[g])
+            .min_by_key(|titles| titles.len());
+        if let Some(titles) = rarest {
+            candidates.extend(titles.iter().copied().filter(|&other| {
+                match (p.year, profiles[other].year) {
+                    (Some(a), Some(b)) => (a - b).abs() <= GENRE_CANDIDATE_YEARS,
+                    _ => true,
+                }
+            }));
+        }
+        candidates.remove(&idx);
+
+        let mut scored: Vec<(i32, f32)> = candidates
+            .iter()
+            .map(|&other| (profiles[other].id, score(p, &profiles[other])))
+            .filter(|&(_, s)| s >= MIN_SCORE)
+            .collect();
+        if scored.is_empty() {
+            continue;
+        }
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scored.truncate(per_title);
+        out.push(SimilarTitles {
+            title_id: p.id,
+            similar: scored,
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(id: i32, genres: &[i32], year: i16) -> SimilarityProfile {
+        SimilarityProfile {
+            id,
+            genre_ids: genres.to_vec(),
+            year: Some(year),
+            ..Default::default()
+        }
+    }
+
+    fn ids(set: &SimilarTitles) -> Vec<i32> {
+        set.similar.iter().map(|&(id, _)| id).collect()
+    }
+
+    #[test]
+    fn consensus_rating_averages_known_sources() {
+        let mut p = profile(1, &[], 2000);
+        assert_eq!(consensus_rating(&p), None);
+        p.imdb_rating = Some(8.0);
+        p.csfd_rating = Some(90);
+        assert_eq!(consensus_rating(&p), Some(8.5));
+    }
+
+    #[test]
+    fn unrelated_titles_score_zero() {
+        let a = profile(1, &[1], 1990);
+        let b = profile(2, &[2], 1990);
+        assert_eq!(score(&a, &b), 0.0);
+    }
+
+    #[test]
+    fn shared_people_outweigh_shared_genres() {
+        let mut base = profile(1, &[1, 2], 1990);
+        base.director_ids = vec![100];
+        let same_genres = profile(2, &[1, 2], 1990);
+        let mut same_director = profile(3, &[1, 3], 1990);
+        same_director.director_ids = vec![100];
+        assert!(score(&base, &same_director) > score(&base, &same_genres));
+    }
+
+    #[test]
+    fn closer_years_and_ratings_rank_higher() {
+        let base = profile(1, &[1], 1990);
+        let near = profile(2, &[1], 1992);
+        let far = profile(3, &[1], 2015);
+        assert!(score(&base, &near) > score(&base, &far));
+
+        let mut rated = base.clone();
+        rated.imdb_rating = Some(8.0);
+        let mut close = near.clone();
+        close.imdb_rating = Some(7.8);
+        let mut distant = near.clone();
+        distant.imdb_rating = Some(4.0);
+        assert!(score(&rated, &close) > score(&rated, &distant));
+    }
+
+    #[test]
+    fn compute_ranks_candidates_and_skips_loners() {
+        let mut alien = profile(1, &[1, 2], 1979);
+        alien.director_ids = vec![10];
+        alien.actor_ids = vec![20, 21];
+        let mut aliens = profile(2, &[1, 2], 1986);
+        aliens.actor_ids = vec![20, 22];
+        let mut blade_runner = profile(3, &[2], 1982);
+        blade_runner.director_ids = vec![10];
+        let comedy = profile(4, &[3], 1979);
+        let western = profile(5, &[4], 1960);
+
+        let sets = compute_similar(&[alien, aliens, blade_runner, comedy, western], 20);
+        let alien_set = sets.iter().find(|s| s.title_id == 1).unwrap();
+        // The shared director beats two shared genres plus one actor.
+        assert_eq!(ids(alien_set), vec![3, 2]);
+        assert!(sets.iter().all(|s| s.title_id != 4 && s.title_id != 5));
+        assert!(sets.iter().all(|s| !ids(s).contains(&s.title_id)));
+    }
+
+    #[test]
+    fn compute_truncates_to_per_title() {
+        let profiles: Vec<_> = (1..=10).map(|id| profile(id, &[1], 2000)).collect();
+        let sets = compute_similar(&profiles, 3);
+        assert_eq!(sets.len(), 10);
+        assert!(sets.iter().all(|s| s.similar.len() == 3));
+    }
+}
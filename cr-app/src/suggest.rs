@@ -0,0 +1,257 @@
+//! Prefix index behind `/api/suggest` — the mixed autocomplete of the
+//! site-wide search box and the browser search-engine integration.
+//!
+//! Unlike the catalog full-text index ([`crate::search`]) this one only
+//! answers "which names start like this", across films, series, TV pořady,
+//! municipalities and landmarks, and does it with a binary search over a
+//! sorted key list. Every name is indexed from each word on, so "kost"
+//! finds "Hrad Kost" and "hrad ko" finds it too. Keys are folded the same
+//! way as the full-text index, so diacritics and case don't matter.
+//!
+//! The whole index is rebuilt from [`SuggestSourceRepository`] in the
+//! background; names change rarely enough that there is no incremental
+//! path.
+
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+use cr_domain::dto::{SuggestEntry, SuggestKind};
+use cr_domain::repository::SuggestSourceRepository;
+
+use crate::catalog::repo_err;
+use crate::error::AppError;
+use crate::search::analyzer::tokenize;
+
+/// Hits per `/api/suggest` request unless the caller asks for fewer.
+pub const SUGGEST_LIMIT: usize = 8;
+
+/// Shortest folded query that is looked up; one letter matches half the
+/// country.
+pub const MIN_QUERY_CHARS: usize = 2;
+
+/// Keys are cut to this many bytes. Nobody types further than that into a
+/// search box, and long landmark names would otherwise dominate memory.
+const MAX_KEY_BYTES: usize = 48;
+
+/// Most keys examined per lookup. A two-letter prefix can match tens of
+/// thousands of keys; past this the query is too short to be useful and
+/// the next keystroke narrows it anyway.
+const MAX_SCAN: usize = 20_000;
+
+/// Sorted `(folded key, entry)` pairs over the entries' names.
+#[derive(Debug, Default)]
+pub struct PrefixIndex {
+    entries: Vec<SuggestEntry>,
+    /// `(key, entry index, key starts at the first word)`, sorted by key.
+    keys: Vec<(Box<str>, u32, bool)>,
+}
+
+impl PrefixIndex {
+    pub fn from_entries(entries: Vec<SuggestEntry>) -> Self {
+        let mut keys = Vec::with_capacity(entries.len() * 2);
+        for (idx, entry) in entries.iter().enumerate() {
+            let tokens = tokenize(&entry.title);
+            for start in 0..tokens.len() {
+                let key = truncate(tokens[start..].join(" "));
+                keys.push((key.into_boxed_str(), idx as u32, start == 0));
+            }
+        }
+        keys.sort_unstable();
+        keys.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+        Self { entries, keys }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Up to `limit` entries whose name has a word sequence starting with
+    /// `query`. Names that start with the query rank above mid-name
+    /// matches, and exact names above the rest. Within that, weights are
+    /// only compared inside a kind: the kinds are interleaved by their own
+    /// ranking, so "Praha" lists the city next to the films named after
+    /// it rather than weighing population against votes.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&SuggestEntry> {
+        let query = normalize_query(query);
+        if query.chars().count() < MIN_QUERY_CHARS || limit == 0 {
+            return Vec::new();
+        }
+        let query = truncate(query);
+
+        // Best key per entry: a first-word match beats a mid-name one.
+        let first = self.keys.partition_point(|(k, _, _)| &**k < query.as_str());
+        let mut best: HashMap<u32, bool> = HashMap::new();
+        for (key, idx, at_start) in self.keys[first..].iter().take(MAX_SCAN) {
+            if !key.starts_with(query.as_str()) {
+                break;
+            }
+            *best.entry(*idx).or_default() |= *at_start;
+        }
+
+        // (tier, entry index); lower tiers first.
+        let mut hits: Vec<(u8, u32)> = best
+            .into_iter()
+            .map(|(idx, at_start)| {
+                let exact = normalize_query(&self.entries[idx as usize].title) == query;
+                (u8::from(!at_start) * 2 + u8::from(!exact), idx)
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            let (ea, eb) = (&self.entries[a.1 as usize], &self.entries[b.1 as usize]);
+            a.0.cmp(&b.0)
+                .then(eb.weight.cmp(&ea.weight))
+                .then(ea.title.len().cmp(&eb.title.len()))
+                .then(a.1.cmp(&b.1))
+        });
+
+        // Rank within (tier, kind), then interleave the kinds by that rank.
+        let mut seen: HashMap<(u8, SuggestKind), usize> = HashMap::new();
+        let mut ranked: Vec<(u8, usize, SuggestKind, &SuggestEntry)> = hits
+            .into_iter()
+            .map(|(tier, idx)| {
+                let entry = &self.entries[idx as usize];
+                let rank = seen.entry((tier, entry.kind)).or_default();
+                *rank += 1;
+                (tier, *rank, entry.kind, entry)
+            })
+            .collect();
+        ranked.sort_by_key(|&(tier, rank, kind, _)| (tier, rank, kind));
+        ranked.into_iter().take(limit).map(|(.., e)| e).collect()
+    }
+}
+
+/// Folded tokens joined by single spaces; keeps a trailing word prefix
+/// ("hrad ko") intact.
+fn normalize_query(query: &str) -> String {
+    tokenize(query).join(" ")
+}
+
+fn truncate(mut key: String) -> String {
+    if key.len() > MAX_KEY_BYTES {
+        let mut end = MAX_KEY_BYTES;
+        while !key.is_char_boundary(end) {
+            end -= 1;
+        }
+        key.truncate(end);
+    }
+    key
+}
+
+/// Shared handle to the suggestion index, swapped whole on rebuild.
+#[derive(Debug, Default)]
+pub struct Suggester {
+    index: RwLock<Option<PrefixIndex>>,
+}
+
+impl Suggester {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every suggestible name into a fresh index and swap it in.
+    pub async fn rebuild<R: SuggestSourceRepository>(&self, repo: &R) -> Result<usize, AppError> {
+        let entries = repo.suggest_entries().await.map_err(repo_err)?;
+        let index = PrefixIndex::from_entries(entries);
+        let len = index.len();
+        *self.index.write().unwrap_or_else(PoisonError::into_inner) = Some(index);
+        Ok(len)
+    }
+
+    /// Suggestions for `query`, or `None` until the first build finishes.
+    pub fn suggest(&self, query: &str, limit: usize) -> Option<Vec<SuggestEntry>> {
+        self.index
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map(|index| index.search(query, limit).into_iter().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: SuggestKind, title: &str, weight: i64) -> SuggestEntry {
+        SuggestEntry {
+            kind,
+            title: title.to_string(),
+            context: None,
+            path: format!("/{}/", title.to_lowercase().replace(' ', "-")),
+            thumb_path: None,
+            weight,
+        }
+    }
+
+    fn titles(hits: Vec<&SuggestEntry>) -> Vec<&str> {
+        hits.into_iter().map(|e| e.title.as_str()).collect()
+    }
+
+    #[test]
+    fn matches_any_word_prefix_ignoring_diacritics() {
+        let index = PrefixIndex::from_entries(vec![
+            entry(SuggestKind::Landmark, "Hrad Kost", 3),
+            entry(SuggestKind::Municipality, "Kostelec nad Orlicí", 6000),
+            entry(SuggestKind::Film, "Pelíšky", 90_000),
+        ]);
+        // First-word matches rank above mid-name matches regardless of weight.
+        assert_eq!(
+            titles(index.search("kost", 10)),
+            ["Kostelec nad Orlicí", "Hrad Kost"]
+        );
+        assert_eq!(titles(index.search("HRAD ko", 10)), ["Hrad Kost"]);
+        assert_eq!(titles(index.search("pelis", 10)), ["Pelíšky"]);
+        assert_eq!(titles(index.search("orlici", 10)), ["Kostelec nad Orlicí"]);
+        assert!(index.search("k", 10).is_empty());
+        assert!(index.search("zzz", 10).is_empty());
+    }
+
+    #[test]
+    fn exact_names_then_weight_decide_the_order() {
+        let index = PrefixIndex::from_entries(vec![
+            entry(SuggestKind::Film, "Praha 2000", 50),
+            entry(SuggestKind::Film, "Pražská pětka", 500),
+            entry(SuggestKind::Film, "Praha", 10),
+        ]);
+        assert_eq!(titles(index.search("praha", 10)), ["Praha", "Praha 2000"]);
+        assert_eq!(
+            titles(index.search("pra", 10)),
+            ["Pražská pětka", "Praha 2000", "Praha"]
+        );
+    }
+
+    #[test]
+    fn kinds_are_interleaved_by_their_own_rank() {
+        let mut entries: Vec<SuggestEntry> = (0..6)
+            .map(|n| entry(SuggestKind::Film, &format!("Most {n}"), 1000 + n))
+            .collect();
+        entries.push(entry(SuggestKind::Landmark, "Most u Rožmberka", 1));
+        let index = PrefixIndex::from_entries(entries);
+
+        // The landmark's one vote doesn't bury it under the films: it is
+        // the best landmark, so it sits next to the best film.
+        assert_eq!(
+            titles(index.search("most", 4)),
+            ["Most 5", "Most u Rožmberka", "Most 4", "Most 3"]
+        );
+        assert_eq!(titles(index.search("most 1", 4)), ["Most 1"]);
+    }
+
+    #[tokio::test]
+    async fn suggester_is_empty_until_built() {
+        struct Source;
+        impl SuggestSourceRepository for Source {
+            type Error = ();
+            async fn suggest_entries(&self) -> Result<Vec<SuggestEntry>, ()> {
+                Ok(vec![entry(SuggestKind::Series, "Přátelé", 1)])
+            }
+        }
+        let suggester = Suggester::new();
+        assert!(suggester.suggest("pratele", 5).is_none());
+        assert_eq!(suggester.rebuild(&Source).await.unwrap(), 1);
+        assert_eq!(suggester.suggest("prat", 5).unwrap()[0].title, "Přátelé");
+    }
+}
@@ -0,0 +1,943 @@
+//! Typed catalog queries for films, series and TV pořady.
+//!
+//! Listing pages take their filters from Czech query-string keys
+//! (`razeni`, `zanry`, `titulky`, …). The web layer parses those into the
+//! objects below and hands them to the catalog repositories, so the
+//! parsing rules and the listing-mode decisions are testable without HTTP
+//! or a database. SQL for each object lives next to the Pg repositories in
+//! `cr-infra`.
+
+use crate::dto::{CollectionFilm, EpisodeNav};
+
+/// Shortest search term that triggers a title search. Measured in bytes,
+/// not chars: a single multibyte char like `á` is a real search (#675).
+pub const MIN_SEARCH_LEN: usize = 2;
+
+/// Trimmed search term, or `None` when it's shorter than [`MIN_SEARCH_LEN`].
+/// Handlers use the same gate to decide whether the response is a
+/// (privately cached) search page, so the two can't drift apart.
+pub fn search_term(raw: Option<&str>) -> Option<String> {
+    raw.map(str::trim)
+        .filter(|t| t.len() >= MIN_SEARCH_LEN)
+        .map(str::to_string)
+}
+
+/// Normalize a search query for the title+year fallback: strip parentheses
+/// and collapse whitespace runs to single spaces. Handles malformed paren
+/// pairs: "Title (1996" and "Title 1996)" both normalize to "Title 1996",
+/// which then matches against `CONCAT_WS(' ', title, year)`.
+pub fn normalize_title_year(q: &str) -> String {
+    q.chars()
+        .filter(|c| *c != '(' && *c != ')')
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a comma-separated slug list (`zanry=`, `bez=`). Trimmed, empties
+/// dropped, de-duplicated so AND-mode `HAVING COUNT(DISTINCT g.slug) = n`
+/// stays correct.
+pub fn parse_slug_list(raw: Option<&str>) -> Vec<String> {
+    let mut slugs: Vec<String> = Vec::new();
+    for s in raw
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        if !slugs.iter().any(|x| x == s) {
+            slugs.push(s.to_string());
+        }
+    }
+    slugs
+}
+
+/// Parse `cs,sk,en` into lowercase ISO 639 codes, dropping anything that
+/// isn't 1–3 ASCII letters. The binding is parameterised anyway; keeping
+/// the array canonical lets the GIN index fire and simplifies chip
+/// rendering.
+pub fn parse_lang_list(raw: Option<&str>) -> Vec<String> {
+    raw.unwrap_or("")
+        .split(',')
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty() && s.len() <= 3 && s.chars().all(|c| c.is_ascii_lowercase()))
+        .collect()
+}
+
+/// Extension for the large-cover URL of a film, series or TV pořad.
+/// Derived from the TMDB `poster_path` when the title has been backfilled;
+/// otherwise `webp`, so the R2-backed `-large.webp` route keeps serving.
+///
+/// Only `jpg` and `png` are whitelisted — the detail handlers dispatch
+/// exactly those two large-cover extensions to the TMDB proxy, and TMDB's
+/// in-practice storage is always JPG. Unknown suffixes (a future `jpeg`)
+/// are normalized to `jpg` rather than falling through to the HTML handler.
+pub fn large_cover_ext(tmdb_poster_path: Option<&str>) -> &'static str {
+    match tmdb_poster_path {
+        Some(p) => match p.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("png") => "png",
+            Some(_) | None => "jpg",
+        },
+        None => "webp",
+    }
+}
+
+/// One page of a listing.
+///
+/// `page` is what the pagination shows; `seek` is how the page is found.
+/// Without a seek the repository skips `(page - 1) * per_page` rows, which
+/// gets slower the deeper the page. The «/» links carry the sort key of the
+/// row next to them instead (`?po=` / `?pred=`), and the repository
+/// continues right after (or before) that row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paging {
+    pub page: i64,
+    pub per_page: i64,
+    pub seek: Option<Seek>,
+}
+
+impl Paging {
+    /// `strana=` is 1-based; missing, zero and negative values mean page 1.
+    pub fn new(page: Option<i64>, per_page: i64) -> Self {
+        Self {
+            page: page.unwrap_or(1).max(1),
+            per_page,
+            seek: None,
+        }
+    }
+
+    /// Continue from `po=` (after) or `pred=` (before). Ignored on page 1,
+    /// which is always read from the top.
+    pub fn with_seek(mut self, after: Option<&str>, before: Option<&str>) -> Self {
+        if self.page > 1 {
+            self.seek = Seek::parse(after, before);
+        }
+        self
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+
+    pub fn total_pages(&self, total: i64) -> i64 {
+        if total <= 0 {
+            0
+        } else {
+            (total + self.per_page - 1) / self.per_page
+        }
+    }
+}
+
+/// A row's position in a listing order: the value of every ORDER BY key as
+/// text, the row id last. `None` is SQL NULL (an unknown year or rating).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey(pub Vec<Option<String>>);
+
+impl SortKey {
+    /// `2019,Vetřelec,123`. Commas and backslashes inside a value are
+    /// escaped with `\`; an empty value is NULL.
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        for (i, value) in self.0.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            for c in value.as_deref().unwrap_or("").chars() {
+                if c == ',' || c == '\\' {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    /// Inverse of [`SortKey::encode`]; `None` for an empty string or a
+    /// dangling escape.
+    pub fn decode(raw: &str) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+        let mut values = Vec::new();
+        let mut current = String::new();
+        let mut chars = raw.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => current.push(chars.next()?),
+                ',' => values.push(std::mem::take(&mut current)),
+                c => current.push(c),
+            }
+        }
+        values.push(current);
+        Some(Self(
+            values
+                .into_iter()
+                .map(|v| (!v.is_empty()).then_some(v))
+                .collect(),
+        ))
+    }
+}
+
+/// Where a keyset page starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Seek {
+    /// `po=` — the rows right after this key (the » link).
+    After(SortKey),
+    /// `pred=` — the rows right before this key (the « link).
+    Before(SortKey),
+}
+
+impl Seek {
+    /// `po=` wins when both are present; unparseable values mean no seek.
+    pub fn parse(after: Option<&str>, before: Option<&str>) -> Option<Self> {
+        if let Some(key) = after.and_then(SortKey::decode) {
+            return Some(Self::After(key));
+        }
+        before.and_then(SortKey::decode).map(Self::Before)
+    }
+}
+
+/// Sort keys of the first and last row of a page, for the «/» links.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageCursors {
+    pub first: Option<SortKey>,
+    pub last: Option<SortKey>,
+}
+
+/// `smer=` — only an explicit `asc` flips the default descending order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    pub fn from_key(smer: Option<&str>) -> Self {
+        if smer == Some("asc") {
+            Self::Asc
+        } else {
+            Self::Desc
+        }
+    }
+
+    pub fn is_desc(self) -> bool {
+        self == Self::Desc
+    }
+}
+
+/// `razeni=` — which column a listing is ordered by.
+///
+/// `imdb` and `tmdb` are sibling keys, each sorting by its own rating
+/// column (#701). Unknown keys fall back to [`CatalogSort::Added`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CatalogSort {
+    /// `pridano` — most recently added first.
+    #[default]
+    Added,
+    /// `rok`
+    Year,
+    /// `imdb`
+    Imdb,
+    /// `tmdb`
+    Tmdb,
+    /// `csfd` — films only.
+    Csfd,
+    /// `nazev`
+    Title,
+}
+
+impl CatalogSort {
+    pub fn from_key(razeni: Option<&str>) -> Self {
+        match razeni {
+            Some("rok") => Self::Year,
+            Some("imdb") => Self::Imdb,
+            Some("tmdb") => Self::Tmdb,
+            Some("csfd") => Self::Csfd,
+            Some("nazev") => Self::Title,
+            _ => Self::Added,
+        }
+    }
+
+    /// Series and TV pořady have no ČSFD sort; `razeni=csfd` there keeps
+    /// the default order, as it always has.
+    pub fn without_csfd(self) -> Self {
+        if self == Self::Csfd {
+            Self::Added
+        } else {
+            self
+        }
+    }
+
+    /// Credibility threshold for rating sorts (#704). 10/10 with 5 voters
+    /// used to outrank Shawshank's 3M voters at the top of `?razeni=imdb`;
+    /// the minimum vote count keeps low-sample outliers off the leaderboard.
+    pub fn min_votes(self) -> Option<i32> {
+        match self {
+            Self::Imdb => Some(500),
+            Self::Tmdb => Some(50),
+            _ => None,
+        }
+    }
+}
+
+/// Genre include/exclude filter (`zanry=`, `bez=`, `rezim=`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenreFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// `rezim=and` — a title must have every included genre. Default is
+    /// any of them.
+    pub match_all: bool,
+}
+
+impl GenreFilter {
+    pub fn parse(zanry: Option<&str>, bez: Option<&str>, rezim: Option<&str>) -> Self {
+        Self {
+            include: parse_slug_list(zanry),
+            exclude: parse_slug_list(bez),
+            match_all: rezim == Some("and"),
+        }
+    }
+
+    /// Genre sub-pages (`/filmy-online/horor/`) always include the path
+    /// genre, ahead of any `?zanry=` extras.
+    pub fn require(&mut self, slug: &str) {
+        if !self.include.iter().any(|s| s == slug) {
+            self.include.insert(0, slug.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+}
+
+/// Legacy `jazyk=` film filter, kept so shared links keep working. "Dub"
+/// means Czech or Slovak audio; "subtitles" means at least one subtitle
+/// track in any language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalLanguage {
+    Dubbed,
+    Subtitled,
+    DubbedOrSubtitled,
+}
+
+impl LocalLanguage {
+    pub fn parse(jazyk: Option<&str>) -> Option<Self> {
+        let val = jazyk.map(str::trim).unwrap_or("");
+        if val.is_empty() || val == "vse" {
+            return None;
+        }
+        let parts: Vec<&str> = val.split(',').map(str::trim).collect();
+        let dub = parts.iter().any(|p| matches!(*p, "dub" | "cz" | "sk"));
+        let sub = parts.iter().any(|p| matches!(*p, "sub" | "titulky"));
+        match (dub, sub) {
+            (true, false) => Some(Self::Dubbed),
+            (false, true) => Some(Self::Subtitled),
+            (true, true) => Some(Self::DubbedOrSubtitled),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Film subtitle filter (`titulky=`, #612).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubtitleFilter {
+    /// `titulky=1` / `any` / `ano` — at least one subtitle track.
+    Any,
+    /// `titulky=cs,en` — every listed language as a subtitle track.
+    Languages(Vec<String>),
+}
+
+impl SubtitleFilter {
+    /// `None` when absent, empty or `0`. A list with no valid codes is
+    /// treated as [`SubtitleFilter::Any`].
+    pub fn parse(titulky: Option<&str>) -> Option<Self> {
+        let raw = titulky?.trim();
+        if raw.is_empty() || raw == "0" {
+            return None;
+        }
+        if matches!(raw, "1" | "any" | "ano") {
+            return Some(Self::Any);
+        }
+        let langs = parse_lang_list(Some(raw));
+        if langs.is_empty() {
+            Some(Self::Any)
+        } else {
+            Some(Self::Languages(langs))
+        }
+    }
+
+    /// Languages the filter requires; empty for [`SubtitleFilter::Any`].
+    pub fn languages(&self) -> &[String] {
+        match self {
+            Self::Any => &[],
+            Self::Languages(langs) => langs,
+        }
+    }
+}
+
+/// Which film columns a title search matches against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleMatch {
+    /// `title` / `original_title` — the primary path.
+    Title,
+    /// `title year` / `original_title year` — lets "Mstitel (1989)" and
+    /// the partial "Marvinův pokoj 19" resolve.
+    TitleYear,
+}
+
+/// A film title search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleSearch {
+    pub term: String,
+    pub mode: TitleMatch,
+}
+
+impl TitleSearch {
+    pub fn new(term: impl Into<String>) -> Self {
+        Self {
+            term: term.into(),
+            mode: TitleMatch::Title,
+        }
+    }
+
+    /// The title+year retry for a search that found nothing: the term with
+    /// parentheses stripped. `None` when that collapses to fewer than two
+    /// chars ("(1" → "1") — `%1%` would match half the catalog.
+    pub fn title_year_fallback(&self) -> Option<Self> {
+        if self.mode != TitleMatch::Title {
+            return None;
+        }
+        let normalized = normalize_title_year(&self.term);
+        (normalized.chars().count() >= 2).then_some(Self {
+            term: normalized,
+            mode: TitleMatch::TitleYear,
+        })
+    }
+}
+
+/// The three catalogs that share listing, search and detail machinery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CatalogKind {
+    Film,
+    Series,
+    TvShow,
+}
+
+/// Everything that narrows the film listing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilmFilter {
+    pub search: Option<TitleSearch>,
+    pub genres: GenreFilter,
+    pub year: Option<i16>,
+    pub local_language: Option<LocalLanguage>,
+    /// `audio=cs,en` — the film must have every listed audio language.
+    pub audio_langs: Vec<String>,
+    pub subtitles: Option<SubtitleFilter>,
+    /// Skip the "has an alive video source" gate. Genre sub-pages list
+    /// every film of the genre.
+    pub include_unavailable: bool,
+}
+
+/// A page of `/filmy-online/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilmListQuery {
+    pub filter: FilmFilter,
+    pub sort: CatalogSort,
+    pub direction: SortDirection,
+    pub paging: Paging,
+}
+
+impl FilmListQuery {
+    /// Key for caching the total count: the filter plus the rating-sort
+    /// vote threshold, which is the only part of the ordering that changes
+    /// which rows match.
+    pub fn count_key(&self) -> String {
+        format!("films|{:?}|{:?}", self.filter, self.sort.min_votes())
+    }
+
+    /// The same query retried with the title+year search, if the current
+    /// search has one.
+    pub fn title_year_fallback(&self) -> Option<Self> {
+        let search = self.filter.search.as_ref()?.title_year_fallback()?;
+        let mut retry = self.clone();
+        retry.filter.search = Some(search);
+        Some(retry)
+    }
+}
+
+/// How a series or TV pořad listing page is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowListing {
+    /// Title search — show rows ordered by match quality, then the sort.
+    Search,
+    /// A non-default sort — show rows ordered by that column.
+    Sorted,
+    /// The default home grid — the latest episode of each show, by when it
+    /// was added. Can't honour other sorts, hence [`ShowListing::Sorted`].
+    LatestEpisodes,
+}
+
+fn show_listing(search: bool, sort: CatalogSort) -> ShowListing {
+    if search {
+        ShowListing::Search
+    } else if sort != CatalogSort::Added {
+        ShowListing::Sorted
+    } else {
+        ShowListing::LatestEpisodes
+    }
+}
+
+/// Audio filter coverage for series (#716). Language is a property of each
+/// episode's sources, so a series can be partly or fully in a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioCoverage {
+    /// At least one episode has every selected language.
+    #[default]
+    AnyEpisode,
+    /// `audio_mode=all` — every episode has every selected language.
+    EveryEpisode,
+}
+
+impl AudioCoverage {
+    pub fn from_key(audio_mode: Option<&str>) -> Self {
+        if audio_mode == Some("all") {
+            Self::EveryEpisode
+        } else {
+            Self::AnyEpisode
+        }
+    }
+}
+
+/// Everything that narrows the series listing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeriesFilter {
+    pub search: Option<String>,
+    pub genres: GenreFilter,
+    /// Matches `first_air_year`.
+    pub year: Option<i16>,
+    pub audio_langs: Vec<String>,
+    pub audio_coverage: AudioCoverage,
+}
+
+/// A page of `/serialy-online/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesListQuery {
+    pub filter: SeriesFilter,
+    pub sort: CatalogSort,
+    pub direction: SortDirection,
+    pub paging: Paging,
+}
+
+impl SeriesListQuery {
+    pub fn listing(&self) -> ShowListing {
+        show_listing(self.filter.search.is_some(), self.sort)
+    }
+
+    /// Key for caching the total count. Includes the listing mode because
+    /// the latest-episode grid only counts series with a playable episode.
+    pub fn count_key(&self) -> String {
+        format!(
+            "series|{:?}|{:?}|{:?}",
+            self.listing(),
+            self.filter,
+            self.sort.min_votes()
+        )
+    }
+}
+
+/// A page of `/tv-porady/`. TV pořady have no genre or language filters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TvShowListQuery {
+    pub search: Option<String>,
+    pub sort: CatalogSort,
+    pub direction: SortDirection,
+    pub paging: Paging,
+}
+
+impl TvShowListQuery {
+    pub fn listing(&self) -> ShowListing {
+        show_listing(self.search.is_some(), self.sort)
+    }
+
+    pub fn count_key(&self) -> String {
+        format!(
+            "tv_shows|{:?}|{:?}|{:?}",
+            self.listing(),
+            self.search,
+            self.sort.min_votes()
+        )
+    }
+}
+
+/// Second path segment of an episode URL that didn't match an episode slug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyEpisodeCode {
+    /// Old `/1x5/` URL — redirected to the episode's slug.
+    Code { season: i16, episode: i16 },
+    /// Contains an `x` but isn't `NxM`.
+    Invalid,
+    /// Not a legacy code at all.
+    NotACode,
+}
+
+impl LegacyEpisodeCode {
+    pub fn parse(path: &str) -> Self {
+        match path.split_once('x') {
+            Some((s, e)) => match (s.parse::<i16>(), e.parse::<i16>()) {
+                (Ok(season), Ok(episode)) => Self::Code { season, episode },
+                _ => Self::Invalid,
+            },
+            None => Self::NotACode,
+        }
+    }
+}
+
+/// Episodes of one season, as rendered on a show's detail page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Season<E> {
+    pub number: i16,
+    pub episodes: Vec<E>,
+}
+
+/// Group episodes (sorted by season, episode) into seasons, keeping the
+/// first row of each episode number — several uploads of the same episode
+/// collapse into one entry.
+pub fn group_into_seasons<E>(
+    episodes: Vec<E>,
+    number: impl Fn(&E) -> (i16, i16),
+) -> Vec<Season<E>> {
+    let mut seasons: Vec<Season<E>> = Vec::new();
+    for ep in episodes {
+        let (season, episode) = number(&ep);
+        match seasons.last_mut() {
+            Some(current) if current.number == season => {
+                if !current.episodes.iter().any(|e| number(e).1 == episode) {
+                    current.episodes.push(ep);
+                }
+            }
+            _ => seasons.push(Season {
+                number: season,
+                episodes: vec![ep],
+            }),
+        }
+    }
+    seasons
+}
+
+/// Previous and next entries around `(season, episode)` in a navigation
+/// list sorted by season and episode. Both `None` when the episode isn't
+/// in the list.
+pub fn episode_neighbours(
+    nav: &[EpisodeNav],
+    season: i16,
+    episode: i16,
+) -> (Option<EpisodeNav>, Option<EpisodeNav>) {
+    let Some(idx) = nav
+        .iter()
+        .position(|n| n.season == season && n.episode == episode)
+    else {
+        return (None, None);
+    };
+    let prev = idx.checked_sub(1).and_then(|i| nav.get(i)).cloned();
+    let next = nav.get(idx + 1).cloned();
+    (prev, next)
+}
+
+/// Previous and next film around `film_id` in a collection's film list
+/// (already in collection order). Both `None` when the film isn't listed,
+/// e.g. it lost its last video source.
+pub fn collection_neighbours(
+    films: &[CollectionFilm],
+    film_id: i32,
+) -> (Option<CollectionFilm>, Option<CollectionFilm>) {
+    let Some(idx) = films.iter().position(|f| f.id == film_id) else {
+        return (None, None);
+    };
+    let prev = idx.checked_sub(1).and_then(|i| films.get(i)).cloned();
+    let next = films.get(idx + 1).cloned();
+    (prev, next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_term_gate_uses_byte_length() {
+        // Must stay byte length: a single multibyte char like `á` (2 bytes,
+        // 1 char) is a real search, and handlers cache search pages on the
+        // same gate (#675 review).
+        assert_eq!(search_term(None), None);
+        assert_eq!(search_term(Some("   ")), None);
+        assert_eq!(search_term(Some("  a  ")), None);
+        assert_eq!(search_term(Some("  ab  ")).as_deref(), Some("ab"));
+        assert_eq!(search_term(Some("á")).as_deref(), Some("á"));
+    }
+
+    #[test]
+    fn normalize_title_year_strips_parens_and_whitespace() {
+        assert_eq!(normalize_title_year("Mstitel (1989)"), "Mstitel 1989");
+        assert_eq!(
+            normalize_title_year("Marvinův pokoj (1996"),
+            "Marvinův pokoj 1996"
+        );
+        assert_eq!(
+            normalize_title_year("Marvinův pokoj 1996)"),
+            "Marvinův pokoj 1996"
+        );
+        assert_eq!(normalize_title_year("  Mstitel  (1989)  "), "Mstitel 1989");
+        assert_eq!(
+            normalize_title_year("Marvinův pokoj 19"),
+            "Marvinův pokoj 19"
+        );
+        assert_eq!(
+            normalize_title_year("2001: A Space Odyssey"),
+            "2001: A Space Odyssey"
+        );
+        assert_eq!(normalize_title_year("(  )"), "");
+    }
+
+    #[test]
+    fn title_year_fallback_skips_collapsed_terms() {
+        let fb = TitleSearch::new("Mstitel (1989)").title_year_fallback();
+        assert_eq!(
+            fb,
+            Some(TitleSearch {
+                term: "Mstitel 1989".into(),
+                mode: TitleMatch::TitleYear
+            })
+        );
+        assert_eq!(TitleSearch::new("(1").title_year_fallback(), None);
+        // Only one retry: a title+year search has no further fallback.
+        assert_eq!(fb.unwrap().title_year_fallback(), None);
+    }
+
+    #[test]
+    fn slug_list_is_trimmed_and_deduplicated() {
+        assert_eq!(
+            parse_slug_list(Some(" horor, drama,,horor ")),
+            vec!["horor", "drama"]
+        );
+        assert!(parse_slug_list(None).is_empty());
+    }
+
+    #[test]
+    fn lang_list_keeps_only_iso_shaped_codes() {
+        assert_eq!(
+            parse_lang_list(Some("CS, sk,english,,1x")),
+            vec!["cs", "sk"]
+        );
+    }
+
+    #[test]
+    fn genre_filter_puts_path_genre_first() {
+        let mut g = GenreFilter::parse(Some("drama,horor"), None, Some("and"));
+        g.require("horor");
+        assert_eq!(g.include, vec!["drama", "horor"]);
+        g.require("krimi");
+        assert_eq!(g.include, vec!["krimi", "drama", "horor"]);
+        assert!(g.match_all);
+    }
+
+    #[test]
+    fn local_language_parses_legacy_jazyk_values() {
+        assert_eq!(
+            LocalLanguage::parse(Some("dub")),
+            Some(LocalLanguage::Dubbed)
+        );
+        assert_eq!(
+            LocalLanguage::parse(Some("cz")),
+            Some(LocalLanguage::Dubbed)
+        );
+        assert_eq!(
+            LocalLanguage::parse(Some("titulky")),
+            Some(LocalLanguage::Subtitled)
+        );
+        assert_eq!(
+            LocalLanguage::parse(Some("dub,sub")),
+            Some(LocalLanguage::DubbedOrSubtitled)
+        );
+        assert_eq!(LocalLanguage::parse(Some("vse")), None);
+        assert_eq!(LocalLanguage::parse(Some("   ")), None);
+        assert_eq!(LocalLanguage::parse(None), None);
+    }
+
+    #[test]
+    fn subtitle_filter_modes() {
+        assert_eq!(SubtitleFilter::parse(None), None);
+        assert_eq!(SubtitleFilter::parse(Some("0")), None);
+        assert_eq!(
+            SubtitleFilter::parse(Some("ano")),
+            Some(SubtitleFilter::Any)
+        );
+        assert_eq!(SubtitleFilter::parse(Some("!!")), Some(SubtitleFilter::Any));
+        assert_eq!(
+            SubtitleFilter::parse(Some("cs,en")),
+            Some(SubtitleFilter::Languages(vec!["cs".into(), "en".into()]))
+        );
+    }
+
+    #[test]
+    fn paging_clamps_and_rounds_up() {
+        let p = Paging::new(Some(0), 24);
+        assert_eq!(p.page, 1);
+        assert_eq!(p.offset(), 0);
+        assert_eq!(Paging::new(Some(3), 24).offset(), 48);
+        assert_eq!(p.total_pages(0), 0);
+        assert_eq!(p.total_pages(24), 1);
+        assert_eq!(p.total_pages(25), 2);
+    }
+
+    #[test]
+    fn sort_key_round_trips_nulls_and_escapes() {
+        let key = SortKey(vec![
+            None,
+            Some("Hodný, zlý a \\ ošklivý".into()),
+            Some("123".into()),
+        ]);
+        let encoded = key.encode();
+        assert_eq!(encoded, ",Hodný\\, zlý a \\\\ ošklivý,123");
+        assert_eq!(SortKey::decode(&encoded), Some(key));
+        assert_eq!(SortKey::decode(""), None);
+        assert_eq!(SortKey::decode("2019\\"), None);
+    }
+
+    #[test]
+    fn seek_is_ignored_on_the_first_page() {
+        let p = Paging::new(Some(3), 24).with_seek(Some("2019,7"), Some("2020,8"));
+        assert_eq!(
+            p.seek,
+            Some(Seek::After(SortKey(vec![
+                Some("2019".into()),
+                Some("7".into())
+            ])))
+        );
+        let p = Paging::new(Some(2), 24).with_seek(None, Some("2020,8"));
+        assert!(matches!(p.seek, Some(Seek::Before(_))));
+        assert_eq!(
+            Paging::new(None, 24).with_seek(Some("2019,7"), None).seek,
+            None
+        );
+    }
+
+    #[test]
+    fn show_listing_mode_follows_search_then_sort() {
+        let mut q = SeriesListQuery {
+            filter: SeriesFilter::default(),
+            sort: CatalogSort::Added,
+            direction: SortDirection::Desc,
+            paging: Paging::new(None, 24),
+        };
+        assert_eq!(q.listing(), ShowListing::LatestEpisodes);
+        q.sort = CatalogSort::from_key(Some("csfd")).without_csfd();
+        assert_eq!(q.listing(), ShowListing::LatestEpisodes);
+        q.sort = CatalogSort::Tmdb;
+        assert_eq!(q.listing(), ShowListing::Sorted);
+        q.filter.search = Some("dr".into());
+        assert_eq!(q.listing(), ShowListing::Search);
+    }
+
+    #[test]
+    fn film_count_key_ignores_page_and_plain_sorts() {
+        let q = FilmListQuery {
+            filter: FilmFilter::default(),
+            sort: CatalogSort::Year,
+            direction: SortDirection::Asc,
+            paging: Paging::new(Some(4), 24),
+        };
+        let mut other = q.clone();
+        other.sort = CatalogSort::Title;
+        other.paging = Paging::new(None, 24);
+        assert_eq!(q.count_key(), other.count_key());
+        other.sort = CatalogSort::Imdb;
+        assert_ne!(q.count_key(), other.count_key());
+        other.sort = CatalogSort::Year;
+        other.filter.year = Some(1999);
+        assert_ne!(q.count_key(), other.count_key());
+    }
+
+    #[test]
+    fn legacy_episode_code_parses_nxm() {
+        assert_eq!(
+            LegacyEpisodeCode::parse("1x5"),
+            LegacyEpisodeCode::Code {
+                season: 1,
+                episode: 5
+            }
+        );
+        assert_eq!(
+            LegacyEpisodeCode::parse("box-office"),
+            LegacyEpisodeCode::Invalid
+        );
+        assert_eq!(
+            LegacyEpisodeCode::parse("pilot-s01e01"),
+            LegacyEpisodeCode::NotACode
+        );
+    }
+
+    #[test]
+    fn seasons_keep_first_upload_of_each_episode() {
+        let eps = vec![(1, 1, "a"), (1, 1, "b"), (1, 2, "c"), (2, 1, "d")];
+        let seasons = group_into_seasons(eps, |e| (e.0, e.1));
+        assert_eq!(seasons.len(), 2);
+        assert_eq!(seasons[0].number, 1);
+        let names: Vec<&str> = seasons[0].episodes.iter().map(|e| e.2).collect();
+        assert_eq!(names, vec!["a", "c"]);
+        assert_eq!(seasons[1].episodes.len(), 1);
+    }
+
+    #[test]
+    fn neighbours_cross_season_boundaries() {
+        let nav: Vec<EpisodeNav> = [(1, 1), (1, 2), (2, 1)]
+            .into_iter()
+            .map(|(season, episode)| EpisodeNav {
+                season,
+                episode,
+                episode_name: None,
+                slug: None,
+            })
+            .collect();
+        let (prev, next) = episode_neighbours(&nav, 1, 2);
+        assert_eq!(prev.map(|n| (n.season, n.episode)), Some((1, 1)));
+        assert_eq!(next.map(|n| (n.season, n.episode)), Some((2, 1)));
+        let (prev, next) = episode_neighbours(&nav, 1, 1);
+        assert!(prev.is_none());
+        assert!(next.is_some());
+        assert_eq!(episode_neighbours(&nav, 9, 9), (None, None));
+    }
+
+    #[test]
+    fn collection_neighbours_follow_list_order() {
+        let films: Vec<CollectionFilm> = [(10, 1), (11, 2), (12, 4)]
+            .into_iter()
+            .map(|(id, position)| CollectionFilm {
+                id,
+                slug: format!("film-{id}"),
+                title: format!("Film {id}"),
+                year: None,
+                position,
+                tmdb_rating: None,
+                imdb_rating: None,
+            })
+            .collect();
+        let id = |f: Option<CollectionFilm>| f.map(|f| f.id);
+        let (prev, next) = collection_neighbours(&films, 11);
+        assert_eq!((id(prev), id(next)), (Some(10), Some(12)));
+        let (prev, next) = collection_neighbours(&films, 12);
+        assert_eq!((id(prev), id(next)), (Some(11), None));
+        let (prev, next) = collection_neighbours(&films, 99);
+        assert_eq!((id(prev), id(next)), (None, None));
+    }
+
+    #[test]
+    fn large_cover_ext_whitelists_jpg_and_png() {
+        assert_eq!(large_cover_ext(None), "webp");
+        assert_eq!(large_cover_ext(Some("/abc.png")), "png");
+        assert_eq!(large_cover_ext(Some("/abc.jpeg")), "jpg");
+        assert_eq!(large_cover_ext(Some("noext")), "jpg");
+    }
+}
@@ -0,0 +1,145 @@
+//! Calendar date value object.
+//!
+//! The domain crate has no dependencies, so dates coming out of the DB
+//! (`episodes.air_date` and friends) are carried as a plain validated
+//! year/month/day triple. Infra converts from `chrono::NaiveDate`.
+
+use std::fmt;
+
+/// A validated Gregorian calendar date. Orders chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Build a date, or `None` when the day doesn't exist (`2023-02-29`).
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    pub fn month(self) -> u8 {
+        self.month
+    }
+
+    pub fn day(self) -> u8 {
+        self.day
+    }
+
+    /// Day of the week, Monday = 0 … Sunday = 6.
+    pub fn weekday(self) -> u8 {
+        // 1970-01-01 was a Thursday.
+        (self.days_since_epoch() + 3).rem_euclid(7) as u8
+    }
+
+    /// The date `days` later (earlier when negative).
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// Monday of this date's week.
+    pub fn week_start(self) -> Self {
+        self.add_days(-i64::from(self.weekday()))
+    }
+
+    /// Days from 1970-01-01 (Howard Hinnant's `days_from_civil`).
+    fn days_since_epoch(self) -> i64 {
+        let y = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = i64::from(self.month);
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// ISO 8601 (`2024-03-07`) — the same shape templates rendered when the
+/// field was a `chrono::NaiveDate`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_impossible_days() {
+        assert!(Date::new(2023, 2, 29).is_none());
+        assert!(Date::new(2024, 2, 29).is_some());
+        assert!(Date::new(1900, 2, 29).is_none());
+        assert!(Date::new(2000, 2, 29).is_some());
+        assert!(Date::new(2024, 4, 31).is_none());
+        assert!(Date::new(2024, 13, 1).is_none());
+        assert!(Date::new(2024, 1, 0).is_none());
+    }
+
+    #[test]
+    fn day_arithmetic_crosses_months_and_leap_days() {
+        let d = Date::new(2024, 2, 28).unwrap();
+        assert_eq!(d.add_days(1).to_string(), "2024-02-29");
+        assert_eq!(d.add_days(2).to_string(), "2024-03-01");
+        assert_eq!(d.add_days(-59).to_string(), "2023-12-31");
+        assert_eq!(d.add_days(366).to_string(), "2025-02-28");
+        assert_eq!(
+            Date::new(1969, 12, 31).unwrap().add_days(1).to_string(),
+            "1970-01-01"
+        );
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2026-10-19 is a Monday.
+        let monday = Date::new(2026, 10, 19).unwrap();
+        assert_eq!(monday.weekday(), 0);
+        assert_eq!(Date::new(2026, 10, 25).unwrap().weekday(), 6);
+        assert_eq!(Date::new(2026, 10, 25).unwrap().week_start(), monday);
+        assert_eq!(Date::new(1970, 1, 1).unwrap().weekday(), 3);
+    }
+
+    #[test]
+    fn displays_as_iso_and_orders_chronologically() {
+        let a = Date::new(2024, 3, 7).unwrap();
+        let b = Date::new(2024, 11, 1).unwrap();
+        assert_eq!(a.to_string(), "2024-03-07");
+        assert!(a < b);
+    }
+}
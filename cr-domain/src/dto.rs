@@ -8,6 +8,8 @@
 //! Entities with invariants live in `entities/`; DTOs here carry only
 //! column-shaped data.
 
+use crate::catalog::CatalogKind;
+
 // --- Record types returned by repositories ---
 // These are plain data records (not domain entities) for query results.
 
@@ -190,3 +192,408 @@ pub struct NewVideo {
     /// [`VideoRecord::resolution`] for rationale.
     pub resolution: Option<String>,
 }
+
+// --- Catalog records (films, series, TV pořady) ---
+
+/// Genre as used by catalog filters and chips.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenreRecord {
+    pub id: i32,
+    pub slug: String,
+    pub name_cs: String,
+}
+
+/// Film row for listings and the detail page.
+///
+/// The provider fields are read from the primary alive `video_sources` row
+/// of each provider (#607 / #611) but keep the legacy column names, so
+/// templates and the sktorrent resolver didn't need to change.
+#[derive(Debug, Clone)]
+pub struct FilmRecord {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub year: Option<i16>,
+    pub description: Option<String>,
+    pub original_title: Option<String>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+    pub csfd_rating: Option<i16>,
+    pub runtime_min: Option<i16>,
+    pub sktorrent_video_id: Option<i32>,
+    /// First-try CDN node for the sktorrent resolver (see
+    /// docs/sktorrent-cdn-stability.md).
+    pub sktorrent_cdn: Option<i16>,
+    pub sktorrent_qualities: Option<String>,
+    pub prehrajto_url: Option<String>,
+    pub prehrajto_has_dub: bool,
+    pub prehrajto_has_subs: bool,
+    /// TMDB `poster_path` (e.g. `/mqlg…uJ.jpg`); drives [`Self::large_url_ext`].
+    pub tmdb_poster_path: Option<String>,
+    /// Best www-CDN sledujteto upload for this film.
+    pub sledujteto_primary_file_id: Option<i32>,
+}
+
+impl FilmRecord {
+    pub fn large_url_ext(&self) -> &'static str {
+        crate::catalog::large_cover_ext(self.tmdb_poster_path.as_deref())
+    }
+}
+
+/// Series row for listings, the detail page and episode pages.
+#[derive(Debug, Clone)]
+pub struct SeriesRecord {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub first_air_year: Option<i16>,
+    pub last_air_year: Option<i16>,
+    pub description: Option<String>,
+    pub original_title: Option<String>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+    pub csfd_rating: Option<i16>,
+    pub season_count: Option<i16>,
+    pub episode_count: Option<i16>,
+    pub tmdb_poster_path: Option<String>,
+}
+
+impl SeriesRecord {
+    pub fn large_url_ext(&self) -> &'static str {
+        crate::catalog::large_cover_ext(self.tmdb_poster_path.as_deref())
+    }
+}
+
+/// TV pořad row (`tv_shows`) — same shape as [`SeriesRecord`], separate
+/// catalog.
+#[derive(Debug, Clone)]
+pub struct TvShowRecord {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub first_air_year: Option<i16>,
+    pub last_air_year: Option<i16>,
+    pub description: Option<String>,
+    pub original_title: Option<String>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+    pub csfd_rating: Option<i16>,
+    pub season_count: Option<i16>,
+    pub episode_count: Option<i16>,
+    pub tmdb_poster_path: Option<String>,
+}
+
+impl TvShowRecord {
+    pub fn large_url_ext(&self) -> &'static str {
+        crate::catalog::large_cover_ext(self.tmdb_poster_path.as_deref())
+    }
+}
+
+/// Series episode with its playable sources, for the season list and the
+/// episode page.
+#[derive(Debug, Clone)]
+pub struct EpisodeRecord {
+    pub id: i32,
+    pub season: i16,
+    pub episode: i16,
+    pub title: Option<String>,
+    pub sktorrent_video_id: Option<i32>,
+    pub sktorrent_cdn: Option<i16>,
+    pub sktorrent_qualities: Option<String>,
+    pub episode_name: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<crate::date::Date>,
+    pub runtime: Option<i16>,
+    pub still_filename: Option<String>,
+    pub prehrajto_url: Option<String>,
+    pub prehrajto_has_dub: bool,
+    pub prehrajto_has_subs: bool,
+    /// First alive www-CDN sledujteto upload (#751). `data{N}` uploads are
+    /// blocked from datacenter clients, so they never show up here.
+    pub sledujteto_external_id: Option<String>,
+    pub slug: Option<String>,
+}
+
+/// TV pořad episode (`tv_episodes`) with its playable sources.
+#[derive(Debug, Clone)]
+pub struct TvEpisodeRecord {
+    pub id: i32,
+    pub season: i16,
+    pub episode: i16,
+    pub title: Option<String>,
+    pub sktorrent_video_id: Option<i32>,
+    pub sktorrent_cdn: Option<i16>,
+    pub sktorrent_qualities: Option<String>,
+    pub episode_name: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<crate::date::Date>,
+    pub runtime: Option<i16>,
+    pub still_filename: Option<String>,
+    pub prehrajto_url: Option<String>,
+    pub prehrajto_has_dub: bool,
+    pub prehrajto_has_subs: bool,
+    pub slug: Option<String>,
+}
+
+/// Card on the series home grid — the latest playable episode of a series.
+#[derive(Debug, Clone)]
+pub struct EpisodeCard {
+    pub id: i32,
+    pub series_id: i32,
+    pub series_slug: String,
+    pub series_title: String,
+    pub series_original_title: Option<String>,
+    pub series_first_air_year: Option<i16>,
+    pub series_tmdb_rating: Option<f32>,
+    pub series_imdb_rating: Option<f32>,
+    pub series_csfd_rating: Option<i16>,
+    pub series_description: Option<String>,
+    pub season: i16,
+    pub episode: i16,
+    pub has_subtitles: Option<bool>,
+    pub has_dub: Option<bool>,
+    pub episode_slug: Option<String>,
+    pub episode_name: Option<String>,
+}
+
+/// Card on the TV pořady home grid — the latest episode of a pořad.
+#[derive(Debug, Clone)]
+pub struct TvEpisodeCard {
+    pub id: i32,
+    pub tv_show_slug: String,
+    pub tv_show_title: String,
+    pub tv_show_original_title: Option<String>,
+    pub tv_show_first_air_year: Option<i16>,
+    pub tv_show_tmdb_rating: Option<f32>,
+    pub tv_show_imdb_rating: Option<f32>,
+    pub tv_show_csfd_rating: Option<i16>,
+    pub tv_show_description: Option<String>,
+    pub season: i16,
+    pub episode: i16,
+    pub has_subtitles: Option<bool>,
+    pub has_dub: Option<bool>,
+    pub episode_slug: Option<String>,
+    pub episode_name: Option<String>,
+}
+
+/// Previous/next link on an episode page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeNav {
+    pub season: i16,
+    pub episode: i16,
+    pub episode_name: Option<String>,
+    pub slug: Option<String>,
+}
+
+/// Cast or crew member of a title. `character_name` is set for cast only.
+#[derive(Debug, Clone)]
+pub struct PersonCredit {
+    pub id: i32,
+    pub slug: String,
+    pub name: String,
+    pub profile_filename: Option<String>,
+    pub character_name: Option<String>,
+}
+
+/// Autocomplete hit for `/api/*/search`.
+#[derive(Debug, Clone)]
+pub struct TitleSuggestion {
+    pub slug: String,
+    pub title: String,
+    pub year: Option<i16>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+}
+
+/// A film, series or TV pořad as the catalog search index sees it.
+#[derive(Debug, Clone)]
+pub struct SearchDocument {
+    pub kind: CatalogKind,
+    pub id: i32,
+    /// `search_rev` of the row — the index's change cursor.
+    pub rev: i64,
+    pub slug: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub year: Option<i16>,
+    /// Directors followed by the top-billed cast.
+    pub people: Vec<String>,
+    pub description: Option<String>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+    /// TMDB plus IMDb vote count — the popularity signal for ranking.
+    pub votes: i64,
+}
+
+/// What the similar-titles job compares a film or series on. Id lists are
+/// sorted and de-duplicated.
+#[derive(Debug, Clone, Default)]
+pub struct SimilarityProfile {
+    pub id: i32,
+    pub genre_ids: Vec<i32>,
+    pub director_ids: Vec<i32>,
+    /// Top-billed cast only.
+    pub actor_ids: Vec<i32>,
+    pub year: Option<i16>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+    /// ČSFD percentage, 0–100.
+    pub csfd_rating: Option<i16>,
+}
+
+/// The best matches for one title, strongest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarTitles {
+    pub title_id: i32,
+    /// `(similar title id, score)`.
+    pub similar: Vec<(i32, f32)>,
+}
+
+/// Card in the "Podobné tituly" carousel.
+#[derive(Debug, Clone)]
+pub struct SimilarTitle {
+    pub slug: String,
+    pub title: String,
+    pub year: Option<i16>,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+}
+
+/// Franchise or sequel series grouping films (`/kolekce/{slug}/`).
+#[derive(Debug, Clone)]
+pub struct FilmCollection {
+    pub id: i32,
+    pub slug: String,
+    pub name: String,
+    pub overview: Option<String>,
+}
+
+/// Film inside a [`FilmCollection`], in collection order.
+#[derive(Debug, Clone)]
+pub struct CollectionFilm {
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub year: Option<i16>,
+    /// 1-based part number within the whole collection; parts missing from
+    /// the catalog leave gaps.
+    pub position: i16,
+    pub tmdb_rating: Option<f32>,
+    pub imdb_rating: Option<f32>,
+}
+
+/// One entry of an Atom/RSS feed, newest first.
+///
+/// Timestamps are RFC 3339 UTC strings (`2026-06-24T08:15:00Z`), for the
+/// same zero-dependency reason as [`VideoRecord::created_at`].
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    /// Stable key such as `film/123`; the feed turns it into the entry id,
+    /// so it must survive slug changes.
+    pub key: String,
+    /// Site-relative page URL (`/filmy-online/vetrelec/`).
+    pub path: String,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Site-relative image URL (film cover, series cover).
+    pub image_path: Option<String>,
+    pub published: String,
+    pub updated: String,
+}
+
+/// One episode on the release calendar (`/serialy-online/kalendar/`,
+/// the series "Připravované epizody" block and its `.ics` export).
+///
+/// Comes from the TMDB air dates the importers store, so it also covers
+/// episodes nobody has a source for yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEpisode {
+    /// `Series` or `TvShow`.
+    pub kind: CatalogKind,
+    pub show_id: i32,
+    pub show_title: String,
+    pub show_slug: String,
+    pub season: i16,
+    pub episode: i16,
+    pub episode_name: Option<String>,
+    pub air_date: crate::date::Date,
+    /// Site-relative episode page, once the episode is playable.
+    pub path: Option<String>,
+}
+
+impl CalendarEpisode {
+    /// Site-relative URL of the show's detail page.
+    pub fn show_path(&self) -> String {
+        match self.kind {
+            CatalogKind::TvShow => format!("/tv-porady/{}/", self.show_slug),
+            _ => format!("/serialy-online/{}/", self.show_slug),
+        }
+    }
+
+    /// `S02E05`.
+    pub fn code(&self) -> String {
+        format!("S{:02}E{:02}", self.season, self.episode)
+    }
+}
+
+/// Narrows the landmark and municipality feeds. All slugs are the ones in
+/// the database (`landmark_types.slug`, not the plural URL form).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaceFeedFilter {
+    pub region_slug: Option<String>,
+    pub orp_slug: Option<String>,
+    /// Landmarks only.
+    pub landmark_type: Option<String>,
+}
+
+/// What an `/api/suggest` hit points at. The declaration order breaks
+/// ties between equally ranked hits of different kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SuggestKind {
+    Film,
+    Series,
+    TvShow,
+    Municipality,
+    Landmark,
+}
+
+impl SuggestKind {
+    /// Stable key used in the API response (`"film"`, `"landmark"`).
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Film => "film",
+            Self::Series => "series",
+            Self::TvShow => "tv_show",
+            Self::Municipality => "municipality",
+            Self::Landmark => "landmark",
+        }
+    }
+
+    /// Czech label shown next to the hit.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Film => "Film",
+            Self::Series => "Seriál",
+            Self::TvShow => "TV pořad",
+            Self::Municipality => "Obec",
+            Self::Landmark => "Památka",
+        }
+    }
+}
+
+/// One row of the in-memory suggestion index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestEntry {
+    pub kind: SuggestKind,
+    pub title: String,
+    /// Year for titles; region (and municipality, for landmarks) for places.
+    pub context: Option<String>,
+    /// Site-relative page URL.
+    pub path: String,
+    /// Site-relative thumbnail URL (cover, coat of arms).
+    pub thumb_path: Option<String>,
+    /// Popularity within `kind` — votes for titles, population for
+    /// municipalities, photo count for landmarks. Only compared between
+    /// entries of the same kind.
+    pub weight: i64,
+}
@@ -0,0 +1,334 @@
+//! Open-data export datasets (`/api/export/{dataset}`).
+//!
+//! Each dataset publishes a fixed column list. The list is the contract
+//! with downstream consumers: the manifest announces it together with
+//! [`ExportDataset::schema_version`], the repository selects exactly these
+//! columns and the writers in `cr_app::export` emit them in this order.
+
+use ExportColumnType as T;
+
+/// A dataset published under `/api/export/{dataset}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportDataset {
+    Films,
+    Series,
+    TvShows,
+    People,
+    Municipalities,
+    Landmarks,
+    Pools,
+}
+
+/// Value type of an export column, as announced in the manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportColumnType {
+    Int,
+    Float,
+    Bool,
+    Text,
+    /// Array of strings; CSV joins it with `|`.
+    TextList,
+    /// RFC 3339, UTC.
+    Timestamp,
+}
+
+impl ExportColumnType {
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Int => "integer",
+            Self::Float => "number",
+            Self::Bool => "boolean",
+            Self::Text => "string",
+            Self::TextList => "string[]",
+            Self::Timestamp => "timestamp",
+        }
+    }
+}
+
+/// One published column. Every dataset also starts with the fixed
+/// `id` and `updated_at` columns, which aren't listed here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportColumn {
+    pub name: &'static str,
+    pub ty: ExportColumnType,
+    pub description: &'static str,
+}
+
+const fn col(name: &'static str, ty: ExportColumnType, description: &'static str) -> ExportColumn {
+    ExportColumn {
+        name,
+        ty,
+        description,
+    }
+}
+
+const FILM_COLUMNS: &[ExportColumn] = &[
+    col("slug", T::Text, "URL slug, unique among films"),
+    col("url", T::Text, "Site-relative page URL"),
+    col("title", T::Text, "Czech title"),
+    col("original_title", T::Text, "Original-language title"),
+    col("year", T::Int, "Release year"),
+    col("runtime_min", T::Int, "Runtime in minutes"),
+    col("imdb_id", T::Text, "IMDb id (tt…)"),
+    col("tmdb_id", T::Int, "TMDB movie id"),
+    col("csfd_id", T::Int, "ČSFD film id"),
+    col("imdb_rating", T::Float, "IMDb rating, 0–10"),
+    col("imdb_votes", T::Int, "IMDb vote count"),
+    col("csfd_rating", T::Int, "ČSFD rating, 0–100"),
+    col("tmdb_rating", T::Float, "TMDB rating, 0–10"),
+    col("tmdb_vote_count", T::Int, "TMDB vote count"),
+    col(
+        "audio_langs",
+        T::TextList,
+        "Audio languages of the video sources",
+    ),
+    col(
+        "subtitle_langs",
+        T::TextList,
+        "Subtitle languages of the video sources",
+    ),
+    col(
+        "added_at",
+        T::Timestamp,
+        "When the film became watchable on the site",
+    ),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+const SERIES_COLUMNS: &[ExportColumn] = &[
+    col("slug", T::Text, "URL slug, unique among series"),
+    col("url", T::Text, "Site-relative page URL"),
+    col("title", T::Text, "Czech title"),
+    col("original_title", T::Text, "Original-language title"),
+    col("first_air_year", T::Int, "Year of the first episode"),
+    col("last_air_year", T::Int, "Year of the last episode"),
+    col("season_count", T::Int, "Number of seasons"),
+    col("episode_count", T::Int, "Number of episodes"),
+    col("imdb_id", T::Text, "IMDb id (tt…)"),
+    col("tmdb_id", T::Int, "TMDB TV id"),
+    col("csfd_id", T::Int, "ČSFD id"),
+    col("imdb_rating", T::Float, "IMDb rating, 0–10"),
+    col("imdb_votes", T::Int, "IMDb vote count"),
+    col("csfd_rating", T::Int, "ČSFD rating, 0–100"),
+    col("tmdb_rating", T::Float, "TMDB rating, 0–10"),
+    col("tmdb_vote_count", T::Int, "TMDB vote count"),
+    col(
+        "audio_langs",
+        T::TextList,
+        "Audio languages of at least one episode",
+    ),
+    col(
+        "subtitle_langs",
+        T::TextList,
+        "Subtitle languages of at least one episode",
+    ),
+    col(
+        "added_at",
+        T::Timestamp,
+        "When the series became watchable on the site",
+    ),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+const TV_SHOW_COLUMNS: &[ExportColumn] = &[
+    col("slug", T::Text, "URL slug, unique among TV pořady"),
+    col("url", T::Text, "Site-relative page URL"),
+    col("title", T::Text, "Czech title"),
+    col("original_title", T::Text, "Original-language title"),
+    col("first_air_year", T::Int, "Year of the first episode"),
+    col("last_air_year", T::Int, "Year of the last episode"),
+    col("season_count", T::Int, "Number of seasons"),
+    col("episode_count", T::Int, "Number of episodes"),
+    col("imdb_id", T::Text, "IMDb id (tt…)"),
+    col("tmdb_id", T::Int, "TMDB TV id"),
+    col("csfd_id", T::Int, "ČSFD id"),
+    col("imdb_rating", T::Float, "IMDb rating, 0–10"),
+    col("imdb_votes", T::Int, "IMDb vote count"),
+    col("csfd_rating", T::Int, "ČSFD rating, 0–100"),
+    col("tmdb_rating", T::Float, "TMDB rating, 0–10"),
+    col("tmdb_vote_count", T::Int, "TMDB vote count"),
+    col(
+        "added_at",
+        T::Timestamp,
+        "When the show became watchable on the site",
+    ),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+const PEOPLE_COLUMNS: &[ExportColumn] = &[
+    col("slug", T::Text, "URL slug, unique among people"),
+    col("url", T::Text, "Site-relative page URL"),
+    col("name", T::Text, "Name as credited"),
+    col("tmdb_id", T::Int, "TMDB person id"),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+const MUNICIPALITY_COLUMNS: &[ExportColumn] = &[
+    col("municipality_code", T::Text, "ČSÚ municipality code"),
+    col("slug", T::Text, "URL slug, unique within the ORP"),
+    col("url", T::Text, "Site-relative page URL"),
+    col("name", T::Text, "Official name"),
+    col(
+        "orp_code",
+        T::Text,
+        "ČSÚ code of the ORP (municipality with extended powers)",
+    ),
+    col("region_code", T::Text, "ČSÚ code of the region"),
+    col("population", T::Int, "Population"),
+    col("elevation", T::Float, "Elevation in metres"),
+    col("latitude", T::Float, "WGS 84 latitude"),
+    col("longitude", T::Float, "WGS 84 longitude"),
+    col("wikipedia_url", T::Text, "Czech Wikipedia article"),
+    col("official_website", T::Text, "Official website"),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+const LANDMARK_COLUMNS: &[ExportColumn] = &[
+    col("slug", T::Text, "URL slug, unique within the municipality"),
+    col(
+        "url",
+        T::Text,
+        "Site-relative page URL; null while unassigned to a municipality",
+    ),
+    col("name", T::Text, "Name"),
+    col(
+        "type",
+        T::Text,
+        "Landmark type (castle, chateau, church, …)",
+    ),
+    col(
+        "municipality_code",
+        T::Text,
+        "ČSÚ code of the municipality it stands in",
+    ),
+    col("wikidata_id", T::Text, "Wikidata item (Q…)"),
+    col("npu_catalog_id", T::Text, "NPÚ heritage catalogue number"),
+    col("latitude", T::Float, "WGS 84 latitude"),
+    col("longitude", T::Float, "WGS 84 longitude"),
+    col("wikipedia_url", T::Text, "Czech Wikipedia article"),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+const POOL_COLUMNS: &[ExportColumn] = &[
+    col("slug", T::Text, "URL slug, unique among pools"),
+    col(
+        "url",
+        T::Text,
+        "Site-relative page URL; null while unassigned to an ORP",
+    ),
+    col("name", T::Text, "Name"),
+    col("address", T::Text, "Postal address"),
+    col("municipality_code", T::Text, "ČSÚ code of the municipality"),
+    col("latitude", T::Float, "WGS 84 latitude"),
+    col("longitude", T::Float, "WGS 84 longitude"),
+    col("website", T::Text, "Website"),
+    col("pool_length_m", T::Int, "Length of the main pool in metres"),
+    col("is_aquapark", T::Bool, "Aquapark"),
+    col("is_indoor", T::Bool, "Indoor pool"),
+    col("is_outdoor", T::Bool, "Outdoor pool"),
+    col("is_natural", T::Bool, "Natural swimming site"),
+    col("created_at", T::Timestamp, "When the row was created"),
+];
+
+impl ExportDataset {
+    pub const ALL: [Self; 7] = [
+        Self::Films,
+        Self::Series,
+        Self::TvShows,
+        Self::People,
+        Self::Municipalities,
+        Self::Landmarks,
+        Self::Pools,
+    ];
+
+    /// The `{dataset}` path segment.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Films => "films",
+            Self::Series => "series",
+            Self::TvShows => "tv_shows",
+            Self::People => "people",
+            Self::Municipalities => "municipalities",
+            Self::Landmarks => "landmarks",
+            Self::Pools => "pools",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.key() == key)
+    }
+
+    /// Bumped whenever [`columns`](Self::columns) changes in a way a
+    /// consumer has to notice — a column renamed, retyped or removed.
+    /// Appending a column doesn't count.
+    pub fn schema_version(self) -> u32 {
+        1
+    }
+
+    pub fn columns(self) -> &'static [ExportColumn] {
+        match self {
+            Self::Films => FILM_COLUMNS,
+            Self::Series => SERIES_COLUMNS,
+            Self::TvShows => TV_SHOW_COLUMNS,
+            Self::People => PEOPLE_COLUMNS,
+            Self::Municipalities => MUNICIPALITY_COLUMNS,
+            Self::Landmarks => LANDMARK_COLUMNS,
+            Self::Pools => POOL_COLUMNS,
+        }
+    }
+}
+
+/// One cell of an export row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportValue {
+    Null,
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+    TextList(Vec<String>),
+    /// RFC 3339, UTC, microsecond precision.
+    Timestamp(String),
+}
+
+/// One exported row: the fixed key columns, then one value per
+/// [`ExportDataset::columns`] entry, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRecord {
+    pub id: i64,
+    /// RFC 3339, UTC, microsecond precision — exact enough to resume from.
+    pub updated_at: String,
+    pub values: Vec<ExportValue>,
+}
+
+/// Position in an export: rows strictly after `(updated_at, id)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportCursor {
+    /// RFC 3339.
+    pub updated_at: String,
+    pub id: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dataset_keys_round_trip() {
+        for dataset in ExportDataset::ALL {
+            assert_eq!(ExportDataset::from_key(dataset.key()), Some(dataset));
+        }
+        assert_eq!(ExportDataset::from_key("users"), None);
+    }
+
+    #[test]
+    fn column_names_are_unique_and_leave_the_key_columns_alone() {
+        for dataset in ExportDataset::ALL {
+            let mut names: Vec<&str> = dataset.columns().iter().map(|c| c.name).collect();
+            assert!(!names.contains(&"id") && !names.contains(&"updated_at"));
+            names.sort_unstable();
+            names.dedup();
+            assert_eq!(names.len(), dataset.columns().len(), "{}", dataset.key());
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Export presets: re-encode a download so it can be sent through a
+//! particular platform.
+//!
+//! Each platform is a row of data — size and duration limits, codecs,
+//! resolution and bitrate caps, and what to do with a video that doesn't
+//! fit. [`plan`] turns a preset and a video's duration into the encode to
+//! run: how many parts, how long each, and the video bitrate that makes
+//! each part land just under the size limit with two-pass encoding.
+
+/// What to do with a video too big or too long for one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizePolicy {
+    /// Cut it into parts that each fit (served as `ready_parts`).
+    Split,
+    /// Lower the bitrate until one file fits; too long a video for
+    /// [`ExportPreset::min_video_kbps`] is refused.
+    Compress,
+}
+
+/// Limits and encoding settings of one target platform. The output is
+/// always MP4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportPreset {
+    /// Shown in the UI and in filenames.
+    pub label: &'static str,
+    /// Largest file the platform accepts.
+    pub max_size_bytes: u64,
+    /// Longest clip the platform accepts, if it limits that.
+    pub max_duration_secs: Option<u32>,
+    /// ffmpeg encoders.
+    pub video_codec: &'static str,
+    pub audio_codec: &'static str,
+    /// The picture is scaled down, never up, to fit this box.
+    pub max_width: u32,
+    pub max_height: u32,
+    /// Video bitrate cap (kbit/s); short clips don't need more.
+    pub max_video_kbps: u32,
+    /// Below this the picture isn't worth sending.
+    pub min_video_kbps: u32,
+    pub audio_kbps: u32,
+    pub oversize: OversizePolicy,
+}
+
+/// Platforms a download can be exported for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportTarget {
+    WhatsApp,
+    Telegram,
+    Discord,
+    Email,
+    InstagramStory,
+}
+
+const MB: u64 = 1024 * 1024;
+
+const WHATSAPP: ExportPreset = ExportPreset {
+    label: "WhatsApp",
+    max_size_bytes: 16 * MB,
+    max_duration_secs: None,
+    video_codec: "libx264",
+    audio_codec: "aac",
+    max_width: 854,
+    max_height: 480,
+    max_video_kbps: 1_000,
+    min_video_kbps: 200,
+    audio_kbps: 128,
+    oversize: OversizePolicy::Split,
+};
+
+const TELEGRAM: ExportPreset = ExportPreset {
+    label: "Telegram",
+    max_size_bytes: 2_000 * MB,
+    max_duration_secs: None,
+    video_codec: "libx264",
+    audio_codec: "aac",
+    max_width: 1920,
+    max_height: 1080,
+    max_video_kbps: 4_500,
+    min_video_kbps: 300,
+    audio_kbps: 160,
+    oversize: OversizePolicy::Split,
+};
+
+const DISCORD: ExportPreset = ExportPreset {
+    label: "Discord",
+    // Free accounts.
+    max_size_bytes: 10 * MB,
+    max_duration_secs: None,
+    video_codec: "libx264",
+    audio_codec: "aac",
+    max_width: 1280,
+    max_height: 720,
+    max_video_kbps: 2_500,
+    min_video_kbps: 150,
+    audio_kbps: 96,
+    oversize: OversizePolicy::Compress,
+};
+
+const EMAIL: ExportPreset = ExportPreset {
+    label: "E-mail",
+    // Attachments grow by a third in base64; this stays under the common
+    // 25 MB message limit.
+    max_size_bytes: 18 * MB,
+    max_duration_secs: None,
+    video_codec: "libx264",
+    audio_codec: "aac",
+    max_width: 1280,
+    max_height: 720,
+    max_video_kbps: 2_000,
+    min_video_kbps: 150,
+    audio_kbps: 96,
+    oversize: OversizePolicy::Compress,
+};
+
+const INSTAGRAM_STORY: ExportPreset = ExportPreset {
+    label: "Instagram Story",
+    max_size_bytes: 250 * MB,
+    max_duration_secs: Some(60),
+    video_codec: "libx264",
+    audio_codec: "aac",
+    // Portrait.
+    max_width: 1080,
+    max_height: 1920,
+    max_video_kbps: 3_500,
+    min_video_kbps: 500,
+    audio_kbps: 128,
+    oversize: OversizePolicy::Split,
+};
+
+impl ExportTarget {
+    pub const ALL: [Self; 5] = [
+        Self::WhatsApp,
+        Self::Telegram,
+        Self::Discord,
+        Self::Email,
+        Self::InstagramStory,
+    ];
+
+    /// Value of the `export_preset` column, the `quality` the download
+    /// page sends, and the library quality of the output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WhatsApp => "whatsapp",
+            Self::Telegram => "telegram",
+            Self::Discord => "discord",
+            Self::Email => "email",
+            Self::InstagramStory => "instagram_story",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == s)
+    }
+
+    pub fn preset(self) -> &'static ExportPreset {
+        match self {
+            Self::WhatsApp => &WHATSAPP,
+            Self::Telegram => &TELEGRAM,
+            Self::Discord => &DISCORD,
+            Self::Email => &EMAIL,
+            Self::InstagramStory => &INSTAGRAM_STORY,
+        }
+    }
+}
+
+/// Share of the size limit the audio and video bitrates may fill; the
+/// rest is left for the container and for the encoder missing its target.
+const SIZE_HEADROOM: f64 = 0.95;
+
+/// How to encode a video for a preset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportPlan {
+    /// 1 for a single file.
+    pub parts: u32,
+    /// Length of each part; the last one may come out a little shorter.
+    pub part_secs: f64,
+    /// Two-pass target bitrate for every part.
+    pub video_kbps: u32,
+}
+
+/// Why a video can't be exported with a preset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanError {
+    /// Compressing into one file would need less than the preset's
+    /// minimum bitrate; `max_secs` is the longest video that fits.
+    TooLong { max_secs: u32 },
+}
+
+/// Plan the export of a `duration_secs` long video.
+pub fn plan(preset: &ExportPreset, duration_secs: f64) -> Result<ExportPlan, PlanError> {
+    let duration = duration_secs.max(1.0);
+    let parts = match preset.oversize {
+        OversizePolicy::Compress => 1,
+        OversizePolicy::Split => {
+            let by_duration = preset
+                .max_duration_secs
+                .map_or(1.0, |max| (duration / max as f64).ceil());
+            // At the bitrate cap, how many files of the size limit it takes.
+            let capped_bytes =
+                (preset.max_video_kbps + preset.audio_kbps) as f64 * 1000.0 / 8.0 * duration;
+            let by_size = (capped_bytes / budget_bytes(preset)).ceil();
+            by_duration.max(by_size).max(1.0) as u32
+        }
+    };
+    let part_secs = duration / parts as f64;
+    let video_kbps = fitting_video_kbps(preset, part_secs).min(preset.max_video_kbps as f64);
+    if video_kbps < preset.min_video_kbps as f64 {
+        let per_sec = (preset.min_video_kbps + preset.audio_kbps) as f64 * 1000.0 / 8.0;
+        return Err(PlanError::TooLong {
+            max_secs: (budget_bytes(preset) / per_sec) as u32,
+        });
+    }
+    Ok(ExportPlan {
+        parts,
+        part_secs,
+        video_kbps: video_kbps as u32,
+    })
+}
+
+/// Video bitrate at which `secs` of video, with the preset's audio, fills
+/// the size budget.
+fn fitting_video_kbps(preset: &ExportPreset, secs: f64) -> f64 {
+    budget_bytes(preset) * 8.0 / 1000.0 / secs - preset.audio_kbps as f64
+}
+
+fn budget_bytes(preset: &ExportPreset) -> f64 {
+    preset.max_size_bytes as f64 * SIZE_HEADROOM
+}
+
+/// Video bitrate for a second try after a part came out at `actual_bytes`
+/// despite targeting `video_kbps`: scaled down by how far it overshot,
+/// plus a little more.
+pub fn retry_video_kbps(preset: &ExportPreset, video_kbps: u32, actual_bytes: u64) -> u32 {
+    let overshoot = actual_bytes as f64 / preset.max_size_bytes as f64;
+    ((video_kbps as f64 / overshoot) * 0.95) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_round_trip_through_their_id() {
+        for target in ExportTarget::ALL {
+            assert_eq!(ExportTarget::parse(target.as_str()), Some(target));
+            assert!(target.preset().min_video_kbps < target.preset().max_video_kbps);
+        }
+        assert_eq!(ExportTarget::parse("1080p"), None);
+    }
+
+    #[test]
+    fn short_clips_stay_in_one_file_at_the_bitrate_cap() {
+        let plan = plan(ExportTarget::WhatsApp.preset(), 60.0).unwrap();
+        assert_eq!(plan.parts, 1);
+        assert_eq!(plan.video_kbps, 1_000);
+    }
+
+    #[test]
+    fn split_presets_cut_long_videos_into_fitting_parts() {
+        let preset = ExportTarget::WhatsApp.preset();
+        // 10 min at 1128 kbit/s ≈ 84.6 MB against a 15.9 MB budget.
+        let plan = plan(preset, 600.0).unwrap();
+        assert_eq!(plan.parts, 6);
+        assert_eq!(plan.part_secs, 100.0);
+        let bytes = (plan.video_kbps + preset.audio_kbps) as f64 * 1000.0 / 8.0 * plan.part_secs;
+        assert!(bytes <= preset.max_size_bytes as f64);
+    }
+
+    #[test]
+    fn duration_limits_split_even_small_files() {
+        let plan = plan(ExportTarget::InstagramStory.preset(), 150.0).unwrap();
+        assert_eq!(plan.parts, 3);
+        assert_eq!(plan.part_secs, 50.0);
+    }
+
+    #[test]
+    fn compress_presets_lower_the_bitrate_to_fit() {
+        let preset = ExportTarget::Discord.preset();
+        let plan = plan(preset, 120.0).unwrap();
+        assert_eq!(plan.parts, 1);
+        // 9.5 MiB over 2 min, minus 96 kbit/s of audio.
+        assert_eq!(plan.video_kbps, 568);
+        assert_eq!(
+            super::plan(preset, 1800.0),
+            Err(PlanError::TooLong { max_secs: 323 })
+        );
+    }
+
+    #[test]
+    fn retry_bitrate_undercuts_the_overshoot() {
+        let preset = ExportTarget::Discord.preset();
+        let retry = retry_video_kbps(preset, 1_000, preset.max_size_bytes * 11 / 10);
+        assert_eq!(retry, 863);
+    }
+}
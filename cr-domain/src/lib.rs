@@ -8,22 +8,33 @@
 //! ## Modules
 //!
 //! - `entities` - Region, District, Orp, Municipality structs
+//! - `catalog` - Typed film / series / TV pořad listing queries
 //! - `coordinates` - Coordinates value object with validation
+//! - `date` - Calendar date value object
 //! - `dto` - Plain row-shaped records returned by repositories
 //! - `error` - Domain-specific error types
+//! - `export` - Open-data export datasets and their column schemas
+//! - `export_preset` - Target-platform presets for re-encoding video downloads
 //! - `id` - Strongly-typed ID wrappers
 //! - `repository` - Repository trait definitions (ports)
 //! - `slug` - URL slug generation from Czech names
+//! - `video_job` - Persistent video download jobs
 
+pub mod catalog;
 pub mod coordinates;
+pub mod date;
 pub mod dto;
 pub mod entities;
 pub mod error;
+pub mod export;
+pub mod export_preset;
 pub mod id;
 pub mod repository;
 pub mod slug;
+pub mod video_job;
 
 pub use coordinates::Coordinates;
+pub use date::Date;
 pub use error::DomainError;
 pub use id::*;
 pub use slug::slug_from_name;
@@ -7,7 +7,13 @@
 //! …) live in `cr-domain::dto` — split out of this module in #446 so the
 //! trait file stays short and focused on ports.
 
+use crate::catalog::{
+    CatalogKind, FilmListQuery, PageCursors, SeriesListQuery, TitleSearch, TvShowListQuery,
+};
+use crate::date::Date;
+use crate::export::{ExportCursor, ExportDataset, ExportRecord};
 use crate::id::*;
+use crate::video_job::{NewVideoJob, VideoJob, VideoJobOutcome, VideoJobState};
 
 // Back-compat re-exports so existing `cr_domain::repository::{RegionRecord, …}`
 // import paths keep working after the DTO split (#446).
@@ -117,3 +123,298 @@ pub trait VideoRepository {
     /// library grid — see #366.
     async fn touch(&self, id: i32) -> Result<(), Self::Error>;
 }
+
+/// Persistent queue of video download jobs (`video_download_jobs`).
+///
+/// Calls that act on a claimed job take the claiming `worker` and do
+/// nothing (returning `false`) once the job has been requeued or deleted
+/// behind its back.
+#[allow(async_fn_in_trait)]
+pub trait VideoJobRepository {
+    type Error: std::fmt::Debug;
+    /// Queue a new job, or join the queued or running one with the same
+    /// [`NewVideoJob::dedup_key`]. Returns the token of the job that now
+    /// carries the request: `job.token` when it was queued.
+    async fn enqueue(&self, job: &NewVideoJob) -> Result<String, Self::Error>;
+    /// Store a job that the hosted library already answered, as `Ready`.
+    async fn record_library_hit(
+        &self,
+        job: &NewVideoJob,
+        library_id: i32,
+        size_bytes: i64,
+    ) -> Result<(), Self::Error>;
+    async fn find(&self, token: &str) -> Result<Option<VideoJob>, Self::Error>;
+    /// Take the oldest due queued job, skipping rows other workers hold,
+    /// and mark it `Downloading` under `worker`.
+    async fn claim(&self, worker: &str) -> Result<Option<VideoJob>, Self::Error>;
+    /// Record the state and progress of a running job; doubles as its
+    /// heartbeat.
+    async fn report_progress(
+        &self,
+        id: i64,
+        worker: &str,
+        state: VideoJobState,
+        progress: u8,
+    ) -> Result<bool, Self::Error>;
+    async fn complete(
+        &self,
+        id: i64,
+        worker: &str,
+        outcome: &VideoJobOutcome,
+    ) -> Result<bool, Self::Error>;
+    /// Put the job back on the queue after `retry_in`, or fail it for good
+    /// when `None`.
+    async fn fail(
+        &self,
+        id: i64,
+        worker: &str,
+        error: &str,
+        retry_in: Option<std::time::Duration>,
+    ) -> Result<bool, Self::Error>;
+    /// Requeue running jobs without a heartbeat for `stale_after` — their
+    /// worker died with the process. Returns how many.
+    async fn requeue_stale(&self, stale_after: std::time::Duration) -> Result<u64, Self::Error>;
+    /// Delete jobs in one of the finished `states` last updated more than
+    /// `max_age` ago. Returns how many.
+    async fn delete_finished(
+        &self,
+        states: &[VideoJobState],
+        max_age: std::time::Duration,
+    ) -> Result<u64, Self::Error>;
+    /// The `limit` most recently updated jobs, unfinished first.
+    async fn list_recent(&self, limit: i64) -> Result<Vec<VideoJob>, Self::Error>;
+    /// Number of jobs per state.
+    async fn state_counts(&self) -> Result<Vec<(VideoJobState, i64)>, Self::Error>;
+}
+
+/// Repository for the film catalog (`films`).
+#[allow(async_fn_in_trait)]
+pub trait FilmRepository {
+    type Error: std::fmt::Debug;
+    /// One page of films matching the query, with the sort keys of its
+    /// first and last film. Only films with at least one alive video
+    /// source are listed.
+    async fn list(
+        &self,
+        query: &FilmListQuery,
+    ) -> Result<(Vec<FilmRecord>, PageCursors), Self::Error>;
+    /// Total number of films [`list`](Self::list) pages through.
+    async fn count(&self, query: &FilmListQuery) -> Result<i64, Self::Error>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<FilmRecord>, Self::Error>;
+    /// Any genre by slug — genre sub-pages share the `/filmy-online/{slug}/`
+    /// namespace with films.
+    async fn find_genre(&self, slug: &str) -> Result<Option<GenreRecord>, Self::Error>;
+    /// Genres that have at least one film, by Czech name.
+    async fn genres(&self) -> Result<Vec<GenreRecord>, Self::Error>;
+    /// `(film_id, genre)` pairs for the given films, by Czech name.
+    async fn genres_of(&self, film_ids: &[i32]) -> Result<Vec<(i32, GenreRecord)>, Self::Error>;
+    /// Top-billed cast, in billing order.
+    async fn cast(&self, film_id: i32, limit: i64) -> Result<Vec<PersonCredit>, Self::Error>;
+    async fn directors(&self, film_id: i32) -> Result<Vec<PersonCredit>, Self::Error>;
+    /// Autocomplete: best ten title matches, diacritic-insensitive.
+    async fn suggest(&self, search: &TitleSearch) -> Result<Vec<TitleSuggestion>, Self::Error>;
+}
+
+/// Repository for the series catalog (`series`).
+#[allow(async_fn_in_trait)]
+pub trait SeriesRepository {
+    type Error: std::fmt::Debug;
+    /// One page of series for the `Search` and `Sorted` listings, with the
+    /// sort keys of its first and last series.
+    async fn list(
+        &self,
+        query: &SeriesListQuery,
+    ) -> Result<(Vec<SeriesRecord>, PageCursors), Self::Error>;
+    /// One page of `LatestEpisodes` cards — the newest
+    /// playable episode of each matching series.
+    async fn latest_episodes(
+        &self,
+        query: &SeriesListQuery,
+    ) -> Result<(Vec<EpisodeCard>, PageCursors), Self::Error>;
+    /// Total for whichever listing `query.listing()` selects.
+    async fn count(&self, query: &SeriesListQuery) -> Result<i64, Self::Error>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<SeriesRecord>, Self::Error>;
+    /// Series whose slug changed (e.g. the year was dropped) keep the old
+    /// one for 301 redirects.
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<SeriesRecord>, Self::Error>;
+    async fn find_genre(&self, slug: &str) -> Result<Option<GenreRecord>, Self::Error>;
+    /// Genres that have at least one series, by Czech name.
+    async fn genres(&self) -> Result<Vec<GenreRecord>, Self::Error>;
+    /// `(series_id, genre)` pairs for the given series, by Czech name.
+    async fn genres_of(&self, series_ids: &[i32]) -> Result<Vec<(i32, GenreRecord)>, Self::Error>;
+    async fn cast(&self, series_id: i32, limit: i64) -> Result<Vec<PersonCredit>, Self::Error>;
+    /// Creators and directors.
+    async fn creators(&self, series_id: i32) -> Result<Vec<PersonCredit>, Self::Error>;
+    async fn suggest(&self, term: &str) -> Result<Vec<TitleSuggestion>, Self::Error>;
+}
+
+/// Repository for series episodes (`episodes`). Only episodes with at
+/// least one alive video source are returned.
+#[allow(async_fn_in_trait)]
+pub trait EpisodeRepository {
+    type Error: std::fmt::Debug;
+    /// Every playable episode of a series, by season and episode.
+    async fn list_by_series(&self, series_id: i32) -> Result<Vec<EpisodeRecord>, Self::Error>;
+    async fn find_by_slug(
+        &self,
+        series_id: i32,
+        slug: &str,
+    ) -> Result<Option<EpisodeRecord>, Self::Error>;
+    async fn find_by_number(
+        &self,
+        series_id: i32,
+        season: i16,
+        episode: i16,
+    ) -> Result<Option<EpisodeRecord>, Self::Error>;
+    /// One entry per distinct (season, episode), in order.
+    async fn navigation(&self, series_id: i32) -> Result<Vec<EpisodeNav>, Self::Error>;
+    async fn directors(&self, episode_id: i32) -> Result<Vec<PersonCredit>, Self::Error>;
+    async fn writers(&self, episode_id: i32) -> Result<Vec<PersonCredit>, Self::Error>;
+}
+
+/// Repository for the TV pořady catalog (`tv_shows` + `tv_episodes`).
+#[allow(async_fn_in_trait)]
+pub trait TvShowRepository {
+    type Error: std::fmt::Debug;
+    async fn list(&self, query: &TvShowListQuery) -> Result<Vec<TvShowRecord>, Self::Error>;
+    async fn latest_episodes(
+        &self,
+        query: &TvShowListQuery,
+    ) -> Result<Vec<TvEpisodeCard>, Self::Error>;
+    async fn count(&self, query: &TvShowListQuery) -> Result<i64, Self::Error>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<TvShowRecord>, Self::Error>;
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<TvShowRecord>, Self::Error>;
+    /// Current slug of the pořad that has `slug` as its slug or old slug.
+    /// Series that moved to `/tv-porady/` redirect through this.
+    async fn current_slug(&self, slug: &str) -> Result<Option<String>, Self::Error>;
+    async fn suggest(&self, term: &str) -> Result<Vec<TitleSuggestion>, Self::Error>;
+    /// Every playable episode of a pořad, by season and episode.
+    async fn episodes(&self, show_id: i32) -> Result<Vec<TvEpisodeRecord>, Self::Error>;
+    async fn find_episode_by_slug(
+        &self,
+        show_id: i32,
+        slug: &str,
+    ) -> Result<Option<TvEpisodeRecord>, Self::Error>;
+    async fn find_episode_by_number(
+        &self,
+        show_id: i32,
+        season: i16,
+        episode: i16,
+    ) -> Result<Option<TvEpisodeRecord>, Self::Error>;
+    async fn episode_navigation(&self, show_id: i32) -> Result<Vec<EpisodeNav>, Self::Error>;
+}
+
+/// Source rows for the in-process catalog search index.
+#[allow(async_fn_in_trait)]
+pub trait SearchDocumentRepository {
+    type Error: std::fmt::Debug;
+    /// Every film, series and TV pořad whose `search_rev` is above
+    /// `since`. `0` loads the whole catalog.
+    async fn changed_since(&self, since: i64) -> Result<Vec<SearchDocument>, Self::Error>;
+}
+
+/// Precomputed similar titles for films and series. `kind` is
+/// [`CatalogKind::Film`] or [`CatalogKind::Series`]; TV pořady have no
+/// credits to compare and are not covered.
+#[allow(async_fn_in_trait)]
+pub trait SimilarTitlesRepository {
+    type Error: std::fmt::Debug;
+    async fn profiles(&self, kind: CatalogKind) -> Result<Vec<SimilarityProfile>, Self::Error>;
+    /// Swap every stored entry of `kind` for `sets` atomically.
+    async fn replace(&self, kind: CatalogKind, sets: &[SimilarTitles]) -> Result<(), Self::Error>;
+    async fn similar_to(
+        &self,
+        kind: CatalogKind,
+        title_id: i32,
+        limit: i64,
+    ) -> Result<Vec<SimilarTitle>, Self::Error>;
+}
+
+/// Film collections (franchises) and their ordered membership.
+#[allow(async_fn_in_trait)]
+pub trait FilmCollectionRepository {
+    type Error: std::fmt::Debug;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<FilmCollection>, Self::Error>;
+    /// The collection `film_id` belongs to, if any.
+    async fn of_film(&self, film_id: i32) -> Result<Option<FilmCollection>, Self::Error>;
+    /// Members with a live video source, in collection order.
+    async fn films(&self, collection_id: i32) -> Result<Vec<CollectionFilm>, Self::Error>;
+}
+
+/// Newest-first entries for the Atom/RSS feeds. The catalog feeds take the
+/// listing pages' typed queries so the same filters apply; sort, search
+/// and paging in them are ignored.
+#[allow(async_fn_in_trait)]
+pub trait FeedRepository {
+    type Error: std::fmt::Debug;
+    /// Films by `added_at`.
+    async fn films(&self, query: &FilmListQuery, limit: i64)
+    -> Result<Vec<FeedEntry>, Self::Error>;
+    /// Sourced episodes of all matching series, by `created_at`.
+    async fn episodes(
+        &self,
+        query: &SeriesListQuery,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error>;
+    /// Sourced episodes of one series, by `created_at`.
+    async fn series_episodes(
+        &self,
+        series_id: i32,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error>;
+    /// Sourced TV pořad episodes, by `created_at`.
+    async fn tv_episodes(&self, limit: i64) -> Result<Vec<FeedEntry>, Self::Error>;
+    /// Landmarks by `content_updated_at`.
+    async fn landmarks(
+        &self,
+        filter: &PlaceFeedFilter,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error>;
+    /// Municipalities by `content_updated_at`.
+    async fn municipalities(
+        &self,
+        filter: &PlaceFeedFilter,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error>;
+}
+
+/// Source rows for the `/api/suggest` prefix index.
+#[allow(async_fn_in_trait)]
+pub trait SuggestSourceRepository {
+    type Error: std::fmt::Debug;
+    /// Every film, series, TV pořad, municipality and landmark that has
+    /// a page.
+    async fn suggest_entries(&self) -> Result<Vec<SuggestEntry>, Self::Error>;
+}
+
+/// Episodes by TMDB air date, for the release calendar.
+#[allow(async_fn_in_trait)]
+pub trait EpisodeCalendarRepository {
+    type Error: std::fmt::Debug;
+    /// Series and TV pořad episodes airing `from..=to`, by date then show.
+    async fn between(&self, from: Date, to: Date) -> Result<Vec<CalendarEpisode>, Self::Error>;
+    /// Up to `limit` episodes of one series airing on or after `from`.
+    async fn series_schedule(
+        &self,
+        series_id: i32,
+        from: Date,
+        limit: i64,
+    ) -> Result<Vec<CalendarEpisode>, Self::Error>;
+}
+
+/// Rows of the `/api/export/{dataset}` open-data feeds, oldest change
+/// first.
+#[allow(async_fn_in_trait)]
+pub trait ExportRepository {
+    type Error: std::fmt::Debug;
+    /// Up to `limit` rows of `dataset` ordered by `(updated_at, id)` and
+    /// strictly after `after`; the whole dataset when `after` is `None`.
+    async fn export_page(
+        &self,
+        dataset: ExportDataset,
+        after: Option<&ExportCursor>,
+        limit: i64,
+    ) -> Result<Vec<ExportRecord>, Self::Error>;
+    /// The database clock, RFC 3339 — the reference for the next `since=`.
+    async fn now(&self) -> Result<String, Self::Error>;
+}
@@ -0,0 +1,570 @@
+//! Persistent download jobs behind `/api/video/prepare`.
+//!
+//! A job is created when the user asks for a download and lives in the
+//! `video_download_jobs` table, so it survives deploys and crashes. Workers
+//! claim queued jobs one at a time, report progress while yt-dlp / ffmpeg
+//! run, and either finish the job or put it back on the queue with a
+//! backoff. The states mirror what `/api/video/status/{token}` reports.
+
+use std::time::Duration;
+
+use crate::export_preset::ExportTarget;
+
+/// Attempts a job gets before it is failed for good.
+pub const MAX_ATTEMPTS: i32 = 3;
+
+/// Lifecycle of a download job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VideoJobState {
+    /// Waiting for a worker (new, or retrying after a failure).
+    Queued,
+    /// yt-dlp is fetching the source.
+    Downloading,
+    /// ffmpeg is re-encoding for an export preset or converting to audio.
+    Converting,
+    /// One file ready to download.
+    Ready,
+    /// Export output split into several parts.
+    ReadyParts,
+    /// Gave up; `error` says why.
+    Failed,
+}
+
+impl VideoJobState {
+    pub const ALL: [Self; 6] = [
+        Self::Queued,
+        Self::Downloading,
+        Self::Converting,
+        Self::Ready,
+        Self::ReadyParts,
+        Self::Failed,
+    ];
+
+    /// Value of the `state` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Downloading => "downloading",
+            Self::Converting => "converting",
+            Self::Ready => "ready",
+            Self::ReadyParts => "ready_parts",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|state| state.as_str() == s)
+    }
+
+    /// A worker holds the job.
+    pub fn is_running(self) -> bool {
+        matches!(self, Self::Downloading | Self::Converting)
+    }
+
+    /// Nothing will happen to the job any more.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Ready | Self::ReadyParts | Self::Failed)
+    }
+}
+
+/// Output of an audio-only download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Mp3,
+    /// AAC in an MP4 audio container.
+    M4a,
+    /// Opus in Ogg.
+    Opus,
+    /// Lossless; takes no bitrate.
+    Flac,
+}
+
+impl AudioFormat {
+    pub const ALL: [Self; 4] = [Self::Mp3, Self::M4a, Self::Opus, Self::Flac];
+
+    /// File extension and value of the `audio_format` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::M4a => "m4a",
+            Self::Opus => "opus",
+            Self::Flac => "flac",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == s)
+    }
+
+    pub fn is_lossless(self) -> bool {
+        self == Self::Flac
+    }
+}
+
+/// Bitrates (kbit/s) offered for the lossy audio formats.
+pub const AUDIO_BITRATES: [u32; 5] = [96, 128, 192, 256, 320];
+
+/// Bitrate used when the request doesn't name one.
+pub const DEFAULT_AUDIO_BITRATE: u32 = 192;
+
+/// An audio-only download: format and, for lossy formats, the bitrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioOptions {
+    pub format: AudioFormat,
+    /// kbit/s; `None` for lossless formats.
+    pub bitrate_kbps: Option<u32>,
+}
+
+impl AudioOptions {
+    /// Validate a requested format and bitrate. The bitrate must be one of
+    /// [`AUDIO_BITRATES`] and is dropped for lossless formats.
+    pub fn new(format: AudioFormat, bitrate_kbps: Option<u32>) -> Option<Self> {
+        if format.is_lossless() {
+            return Some(Self {
+                format,
+                bitrate_kbps: None,
+            });
+        }
+        let bitrate = bitrate_kbps.unwrap_or(DEFAULT_AUDIO_BITRATE);
+        AUDIO_BITRATES.contains(&bitrate).then_some(Self {
+            format,
+            bitrate_kbps: Some(bitrate),
+        })
+    }
+}
+
+/// Part of a video to keep, in seconds from its start.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRange {
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+impl ClipRange {
+    /// A range that starts at or after zero and ends after it starts.
+    pub fn new(start_secs: f64, end_secs: f64) -> Option<Self> {
+        (start_secs.is_finite()
+            && end_secs.is_finite()
+            && start_secs >= 0.0
+            && end_secs > start_secs)
+            .then_some(Self {
+                start_secs,
+                end_secs,
+            })
+    }
+
+    pub fn len_secs(self) -> f64 {
+        self.end_secs - self.start_secs
+    }
+
+    /// `1m05s-1m25s`, whole seconds; ASCII so it can go in filenames.
+    pub fn label(self) -> String {
+        format!(
+            "{}-{}",
+            short_timestamp(self.start_secs),
+            short_timestamp(self.end_secs)
+        )
+    }
+}
+
+fn short_timestamp(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m{s:02}s"),
+        (h, m, s) => format!("{h}h{m:02}m{s:02}s"),
+    }
+}
+
+/// Parse a timestamp as typed into the download form: plain seconds
+/// (`75`, `75.5`), `m:ss` or `h:mm:ss`, with a decimal comma allowed.
+pub fn parse_timestamp(s: &str) -> Option<f64> {
+    let s = s.trim().replace(',', ".");
+    if s.is_empty() {
+        return None;
+    }
+    let mut secs = 0.0;
+    let fields: Vec<&str> = s.split(':').collect();
+    if fields.len() > 3 {
+        return None;
+    }
+    for (i, field) in fields.iter().enumerate() {
+        let last = i + 1 == fields.len();
+        // Only the last field may have a fraction; the others are whole
+        // minutes and hours.
+        if field.is_empty() || (!last && field.contains('.')) {
+            return None;
+        }
+        let value: f64 = field.parse().ok()?;
+        if value < 0.0 || (i > 0 && value >= 60.0) {
+            return None;
+        }
+        secs = secs * 60.0 + value;
+    }
+    secs.is_finite().then_some(secs)
+}
+
+/// Animated image made from a clip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationFormat {
+    Gif,
+    /// Animated WebP: smaller than GIF and not limited to 256 colours.
+    WebP,
+}
+
+impl AnimationFormat {
+    pub const ALL: [Self; 2] = [Self::Gif, Self::WebP];
+
+    /// File extension and value of the `animation_format` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::WebP => "webp",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == s)
+    }
+}
+
+/// Longest clip that can become an animation; GIFs grow fast.
+pub const MAX_ANIMATION_SECS: f64 = 30.0;
+
+/// Frame rates an animation may have.
+pub const ANIMATION_FPS: std::ops::RangeInclusive<u32> = 5..=30;
+pub const DEFAULT_ANIMATION_FPS: u32 = 12;
+
+/// Widths (pixels) an animation may have; it is never scaled up.
+pub const ANIMATION_WIDTHS: std::ops::RangeInclusive<u32> = 120..=1280;
+pub const DEFAULT_ANIMATION_WIDTH: u32 = 480;
+
+/// A GIF / WebP export: format, frame rate and width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationOptions {
+    pub format: AnimationFormat,
+    pub fps: u32,
+    pub width: u32,
+}
+
+impl AnimationOptions {
+    /// Validate the requested frame rate and width against
+    /// [`ANIMATION_FPS`] and [`ANIMATION_WIDTHS`], defaulting the ones
+    /// not given.
+    pub fn new(format: AnimationFormat, fps: Option<u32>, width: Option<u32>) -> Option<Self> {
+        let fps = fps.unwrap_or(DEFAULT_ANIMATION_FPS);
+        let width = width.unwrap_or(DEFAULT_ANIMATION_WIDTH);
+        (ANIMATION_FPS.contains(&fps) && ANIMATION_WIDTHS.contains(&width)).then_some(Self {
+            format,
+            fps,
+            width,
+        })
+    }
+}
+
+/// A subtitle track to add to a video download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleOptions {
+    /// Language code of the track as the source lists it (`cs`, `en-US`).
+    pub lang: String,
+    /// Where the track is fetched from: WebVTT, SubRip or ASS.
+    pub url: String,
+    /// Burn the text into the picture instead of adding a track the
+    /// player can switch off.
+    pub burn: bool,
+}
+
+impl SubtitleOptions {
+    /// ISO 639-2 code for the container's language tag: MP4 only takes
+    /// three-letter codes. Unknown codes pass through as given.
+    pub fn iso639_2(&self) -> String {
+        let primary = self
+            .lang
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let code = match primary.as_str() {
+            "cs" => "cze",
+            "sk" => "slo",
+            "en" => "eng",
+            "de" => "ger",
+            "fr" => "fre",
+            "es" => "spa",
+            "it" => "ita",
+            "pl" => "pol",
+            "hu" => "hun",
+            "ru" => "rus",
+            "uk" => "ukr",
+            "pt" => "por",
+            "nl" => "dut",
+            "ja" => "jpn",
+            "zh" => "chi",
+            other => return other.to_string(),
+        };
+        code.to_string()
+    }
+}
+
+/// What the user asked for, fixed when the job is enqueued. The metadata
+/// fields come from the extraction `video_prepare` already ran and are
+/// what the library publish needs afterwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewVideoJob {
+    /// Public handle of the job (UUID), used in the status and file URLs.
+    pub token: String,
+    pub source_url: String,
+    /// Format picked from the extracted list (`720p`).
+    pub format_id: String,
+    pub resolution: String,
+    /// Output container: `mp4`, `webm` or `mkv`; for audio and animation
+    /// jobs the output format's extension.
+    pub container: String,
+    /// Re-encode (and possibly split) for a platform after the download.
+    pub export: Option<ExportTarget>,
+    /// Audio-only download: fetch the best audio stream and convert it.
+    pub audio: Option<AudioOptions>,
+    /// Keep only this part of the video (or audio).
+    pub clip: Option<ClipRange>,
+    /// Turn the clip into an animated GIF / WebP instead of a video.
+    pub animation: Option<AnimationOptions>,
+    /// Add this subtitle track to the video (video jobs only).
+    pub subtitles: Option<SubtitleOptions>,
+    /// Publish the result to the hosted library when done.
+    pub publish: bool,
+    /// Download filename offered to the user.
+    pub filename: String,
+    pub title: String,
+    pub duration_sec: Option<i32>,
+    pub uploader: Option<String>,
+    pub thumbnail_url: Option<String>,
+}
+
+impl NewVideoJob {
+    /// Everything that decides the output file, in one string: two jobs
+    /// with the same key produce the same result, so a request for one
+    /// while the other is still queued or running joins it instead.
+    /// `source_url` is expected in canonical form.
+    pub fn dedup_key(&self) -> String {
+        let mut key = format!("{}|{}", self.format_id, self.container);
+        if let Some(export) = self.export {
+            key += &format!("|export={}", export.as_str());
+        }
+        if let Some(audio) = self.audio {
+            key += &format!("|audio={}", audio.format.as_str());
+            if let Some(kbps) = audio.bitrate_kbps {
+                key += &format!("@{kbps}");
+            }
+        }
+        if let Some(clip) = self.clip {
+            key += &format!("|clip={}-{}", clip.start_secs, clip.end_secs);
+        }
+        if let Some(a) = self.animation {
+            key += &format!("|animation={}@{}x{}", a.format.as_str(), a.fps, a.width);
+        }
+        if let Some(subtitles) = &self.subtitles {
+            let mode = if subtitles.burn { "burn" } else { "track" };
+            key += &format!("|subtitles={}:{mode}", subtitles.lang);
+        }
+        if self.publish {
+            key += "|publish";
+        }
+        // Last, so a `|` in the URL can't make two keys collide.
+        key + "|" + &self.source_url
+    }
+}
+
+/// One part of a split export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoJobPart {
+    pub index: i32,
+    pub filename: String,
+    pub size_bytes: i64,
+    pub file_path: String,
+}
+
+/// How a finished job turned out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VideoJobOutcome {
+    File {
+        file_path: String,
+        filename: String,
+        size_bytes: i64,
+    },
+    Parts(Vec<VideoJobPart>),
+}
+
+/// A job as stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoJob {
+    pub id: i64,
+    pub state: VideoJobState,
+    /// 0–100 within the current state.
+    pub progress: u8,
+    /// Attempts started so far, the current one included.
+    pub attempts: i32,
+    pub params: NewVideoJob,
+    /// Filename offered for download; an export renames it.
+    pub filename: String,
+    /// Local file of a `Ready` job. `None` for library hits, which are
+    /// served from the library instead.
+    pub file_path: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub parts: Vec<VideoJobPart>,
+    /// Set when the download was answered from the hosted library.
+    pub library_id: Option<i32>,
+    /// Last error: the final one of a `Failed` job, the one being retried
+    /// for a `Queued` job with `attempts > 0`.
+    pub error: Option<String>,
+    /// Worker holding a running job.
+    pub worker: Option<String>,
+    /// Jobs ahead of this one in the queue; 0 when not queued.
+    pub queue_position: i64,
+    // ISO 8601 + offset, like `VideoRecord`.
+    pub created_at: String,
+    pub updated_at: String,
+    pub next_attempt_at: String,
+}
+
+/// How long a job that failed its `attempts`-th try waits before the next
+/// one; `None` when it has used up [`MAX_ATTEMPTS`].
+pub fn retry_delay(attempts: i32) -> Option<Duration> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    // 30 s, 2 min, 8 min, …
+    let exp = attempts.clamp(1, 8) as u32 - 1;
+    Some(Duration::from_secs(30 * 4u64.pow(exp)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_job(source_url: &str) -> NewVideoJob {
+        NewVideoJob {
+            token: "t".to_string(),
+            source_url: source_url.to_string(),
+            format_id: "720p".to_string(),
+            resolution: "720p".to_string(),
+            container: "mp4".to_string(),
+            export: None,
+            audio: None,
+            clip: None,
+            animation: None,
+            subtitles: None,
+            publish: true,
+            filename: "a.mp4".to_string(),
+            title: "A".to_string(),
+            duration_sec: Some(60),
+            uploader: None,
+            thumbnail_url: None,
+        }
+    }
+
+    #[test]
+    fn dedup_key_covers_the_output_but_not_the_labels() {
+        let job = new_job("https://www.youtube.com/watch?v=x");
+        let mut renamed = new_job("https://www.youtube.com/watch?v=x");
+        renamed.token = "other".to_string();
+        renamed.title = "B".to_string();
+        renamed.filename = "b.mp4".to_string();
+        assert_eq!(job.dedup_key(), renamed.dedup_key());
+
+        let mut clipped = new_job("https://www.youtube.com/watch?v=x");
+        clipped.clip = ClipRange::new(10.0, 20.0);
+        clipped.publish = false;
+        assert_ne!(job.dedup_key(), clipped.dedup_key());
+        assert_eq!(
+            clipped.dedup_key(),
+            "720p|mp4|clip=10-20|https://www.youtube.com/watch?v=x"
+        );
+
+        let mut burned = new_job("https://www.youtube.com/watch?v=x");
+        burned.subtitles = Some(SubtitleOptions {
+            lang: "cs".to_string(),
+            url: "https://example.com/cs.vtt".to_string(),
+            burn: true,
+        });
+        let mut soft = burned.clone();
+        soft.subtitles.as_mut().unwrap().burn = false;
+        assert_ne!(burned.dedup_key(), soft.dedup_key());
+        assert_ne!(job.dedup_key(), new_job("https://youtu.be/x").dedup_key());
+    }
+
+    #[test]
+    fn states_round_trip_through_their_column_value() {
+        for state in VideoJobState::ALL {
+            assert_eq!(VideoJobState::parse(state.as_str()), Some(state));
+        }
+        assert_eq!(VideoJobState::parse("running"), None);
+        assert!(VideoJobState::Converting.is_running());
+        assert!(!VideoJobState::Queued.is_running());
+        assert!(VideoJobState::ReadyParts.is_finished());
+        assert!(!VideoJobState::Downloading.is_finished());
+    }
+
+    #[test]
+    fn audio_options_validate_bitrate() {
+        for format in AudioFormat::ALL {
+            assert_eq!(AudioFormat::parse(format.as_str()), Some(format));
+        }
+        let mp3 = AudioOptions::new(AudioFormat::Mp3, None).unwrap();
+        assert_eq!(mp3.bitrate_kbps, Some(DEFAULT_AUDIO_BITRATE));
+        assert!(AudioOptions::new(AudioFormat::Opus, Some(100)).is_none());
+        let flac = AudioOptions::new(AudioFormat::Flac, Some(320)).unwrap();
+        assert_eq!(flac.bitrate_kbps, None);
+    }
+
+    #[test]
+    fn timestamps_parse_in_the_usual_spellings() {
+        assert_eq!(parse_timestamp("75"), Some(75.0));
+        assert_eq!(parse_timestamp(" 1:15,5 "), Some(75.5));
+        assert_eq!(parse_timestamp("1:02:03"), Some(3723.0));
+        assert_eq!(parse_timestamp("1:75"), None);
+        assert_eq!(parse_timestamp("1.5:00"), None);
+        assert_eq!(parse_timestamp("1::0"), None);
+        assert_eq!(parse_timestamp("-3"), None);
+        assert_eq!(parse_timestamp(""), None);
+
+        assert!(ClipRange::new(10.0, 30.0).is_some_and(|c| c.len_secs() == 20.0));
+        assert!(ClipRange::new(30.0, 30.0).is_none());
+        assert!(ClipRange::new(-1.0, 30.0).is_none());
+        let clip = ClipRange::new(65.4, 3725.0).unwrap();
+        assert_eq!(clip.label(), "1m05s-1h02m05s");
+        assert_eq!(ClipRange::new(0.0, 9.6).unwrap().label(), "0s-10s");
+    }
+
+    #[test]
+    fn animation_options_default_and_validate() {
+        let gif = AnimationOptions::new(AnimationFormat::Gif, None, None).unwrap();
+        assert_eq!(
+            (gif.fps, gif.width),
+            (DEFAULT_ANIMATION_FPS, DEFAULT_ANIMATION_WIDTH)
+        );
+        assert!(AnimationOptions::new(AnimationFormat::WebP, Some(60), None).is_none());
+        assert!(AnimationOptions::new(AnimationFormat::WebP, None, Some(4000)).is_none());
+        assert_eq!(AnimationFormat::parse("webp"), Some(AnimationFormat::WebP));
+    }
+
+    #[test]
+    fn subtitle_languages_map_to_three_letter_codes() {
+        let subs = |lang: &str| SubtitleOptions {
+            lang: lang.to_string(),
+            url: String::new(),
+            burn: false,
+        };
+        assert_eq!(subs("cs").iso639_2(), "cze");
+        assert_eq!(subs("en-US").iso639_2(), "eng");
+        assert_eq!(subs("pt_BR").iso639_2(), "por");
+        assert_eq!(subs("fin").iso639_2(), "fin");
+    }
+
+    #[test]
+    fn retries_back_off_until_attempts_run_out() {
+        assert_eq!(retry_delay(1), Some(Duration::from_secs(30)));
+        assert_eq!(retry_delay(2), Some(Duration::from_secs(120)));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+        assert_eq!(retry_delay(MAX_ATTEMPTS + 1), None);
+    }
+}
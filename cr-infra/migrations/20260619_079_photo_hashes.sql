@@ -0,0 +1,39 @@
+-- =============================================================================
+-- photo_hashes — perceptual-hash index over every image we store on R2.
+--
+-- Landmarks and municipalities frequently reuse the same Wikimedia photo,
+-- and the NPÚ importer has uploaded re-encoded crops of the same file under
+-- different `photo_index` values. A 64-bit difference hash (dHash, see
+-- `cr-infra::photo_hash`) survives re-encoding and resizing, so two rows
+-- whose hashes differ in only a few bits are almost certainly the same
+-- picture.
+--
+-- Keyed by `r2_key` rather than by (entity_type, entity_id, index) because
+-- the same object can be referenced from several source tables
+-- (photo_metadata, landmark_photos, municipality_photos, id-keyed covers).
+-- `entity_type` / `entity_ref` record where the key was first seen so the
+-- duplicate report can say *which* landmark or film the image belongs to:
+--
+--   entity_type     entity_ref
+--   'landmark'      landmarks.id  (photo_metadata index 1 + landmark_photos)
+--   'pool'          pools.id
+--   'municipality'  municipalities.municipality_code
+--   'film'          films.id      (films/{id}/cover.webp)
+--   'series'        series.id     (series/{id}/cover.webp)
+--   'tv_show'       tv_shows.id   (tv-shows/{id}/cover.webp)
+--
+-- `dhash` is the 64-bit hash stored as BIGINT (bit-cast from u64).
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS photo_hashes (
+    r2_key      TEXT PRIMARY KEY,
+    entity_type TEXT NOT NULL,
+    entity_ref  TEXT NOT NULL,
+    dhash       BIGINT NOT NULL,
+    width       INTEGER NOT NULL,
+    height      INTEGER NOT NULL,
+    hashed_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_photo_hashes_entity
+    ON photo_hashes (entity_type, entity_ref);
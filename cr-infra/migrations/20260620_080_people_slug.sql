@@ -0,0 +1,61 @@
+-- =============================================================================
+-- people.slug — URL key for the `/osobnosti/{slug}/` person pages.
+--
+-- The slug is the unaccented, lower-cased name with every run of
+-- non-alphanumerics collapsed to a hyphen ("Zdeněk Svěrák" →
+-- "zdenek-sverak"). Namesakes are common in the TMDB credits, so only the
+-- lowest id keeps the bare slug and every later namesake gets its id
+-- appended ("jan-novak", "jan-novak-4812"). Names that unaccent can't
+-- transliterate (CJK, Cyrillic, …) fall back to "osobnost-{id}".
+--
+-- The cast importers (scripts/backfill-*-cast.py) insert people without a
+-- slug; the BEFORE INSERT trigger fills it in. An UPSERT that hits the
+-- `tmdb_id` conflict keeps the existing slug, so renaming a person on TMDB
+-- does not break their URL.
+-- =============================================================================
+
+ALTER TABLE people ADD COLUMN IF NOT EXISTS slug TEXT;
+
+CREATE OR REPLACE FUNCTION people_base_slug(name TEXT) RETURNS TEXT AS $$
+    SELECT NULLIF(
+        trim(BOTH '-' FROM regexp_replace(lower(unaccent(name)), '[^a-z0-9]+', '-', 'g')),
+        ''
+    );
+$$ LANGUAGE sql STABLE;
+
+WITH ranked AS (
+    SELECT id,
+           people_base_slug(name) AS base,
+           row_number() OVER (PARTITION BY people_base_slug(name) ORDER BY id) AS n
+    FROM people
+    WHERE slug IS NULL
+)
+UPDATE people p
+SET slug = CASE
+    WHEN r.base IS NULL THEN 'osobnost-' || p.id
+    WHEN r.n = 1 THEN r.base
+    ELSE r.base || '-' || p.id
+END
+FROM ranked r
+WHERE r.id = p.id;
+
+CREATE OR REPLACE FUNCTION people_set_slug() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.slug IS NULL THEN
+        NEW.slug := people_base_slug(NEW.name);
+        IF NEW.slug IS NULL THEN
+            NEW.slug := 'osobnost-' || NEW.id;
+        ELSIF EXISTS (SELECT 1 FROM people WHERE slug = NEW.slug) THEN
+            NEW.slug := NEW.slug || '-' || NEW.id;
+        END IF;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_people_set_slug
+    BEFORE INSERT ON people
+    FOR EACH ROW EXECUTE FUNCTION people_set_slug();
+
+ALTER TABLE people ALTER COLUMN slug SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_people_slug ON people (slug);
@@ -0,0 +1,113 @@
+-- =============================================================================
+-- search_rev — change cursor for the in-process catalog search index.
+--
+-- cr-web builds an inverted index over films, series and TV pořady at
+-- startup and then polls for rows whose `search_rev` is above the highest
+-- revision it has already indexed. Every insert or update of a column the
+-- index reads (titles, description, year, slug, ratings and vote counts)
+-- draws a new value from one shared sequence, so a single cursor covers
+-- all three tables.
+--
+-- Cast and director changes bump the owning title through statement-level
+-- triggers with transition tables — the cast backfills insert hundreds of
+-- thousands of rows, and a row-level trigger would rewrite the same film
+-- once per credit.
+--
+-- Deletes are not tracked; the index is rebuilt from scratch periodically
+-- and that pass drops rows that no longer exist.
+-- =============================================================================
+
+CREATE SEQUENCE IF NOT EXISTS catalog_search_rev_seq;
+
+ALTER TABLE films ADD COLUMN IF NOT EXISTS search_rev BIGINT NOT NULL
+    DEFAULT nextval('catalog_search_rev_seq');
+ALTER TABLE series ADD COLUMN IF NOT EXISTS search_rev BIGINT NOT NULL
+    DEFAULT nextval('catalog_search_rev_seq');
+ALTER TABLE tv_shows ADD COLUMN IF NOT EXISTS search_rev BIGINT NOT NULL
+    DEFAULT nextval('catalog_search_rev_seq');
+
+CREATE INDEX IF NOT EXISTS idx_films_search_rev ON films (search_rev);
+CREATE INDEX IF NOT EXISTS idx_series_search_rev ON series (search_rev);
+CREATE INDEX IF NOT EXISTS idx_tv_shows_search_rev ON tv_shows (search_rev);
+
+CREATE OR REPLACE FUNCTION bump_search_rev() RETURNS trigger AS $$
+BEGIN
+    NEW.search_rev := nextval('catalog_search_rev_seq');
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS films_search_rev ON films;
+CREATE TRIGGER films_search_rev
+    BEFORE UPDATE OF title, original_title, slug, year, description,
+        tmdb_rating, imdb_rating, tmdb_vote_count, imdb_votes
+    ON films
+    FOR EACH ROW EXECUTE FUNCTION bump_search_rev();
+
+DROP TRIGGER IF EXISTS series_search_rev ON series;
+CREATE TRIGGER series_search_rev
+    BEFORE UPDATE OF title, original_title, slug, first_air_year, description,
+        tmdb_rating, imdb_rating, tmdb_vote_count, imdb_votes
+    ON series
+    FOR EACH ROW EXECUTE FUNCTION bump_search_rev();
+
+DROP TRIGGER IF EXISTS tv_shows_search_rev ON tv_shows;
+CREATE TRIGGER tv_shows_search_rev
+    BEFORE UPDATE OF title, original_title, slug, first_air_year, description,
+        tmdb_rating, imdb_rating, tmdb_vote_count, imdb_votes
+    ON tv_shows
+    FOR EACH ROW EXECUTE FUNCTION bump_search_rev();
+
+-- Credit tables: `changed` is the transition table of the statement.
+CREATE OR REPLACE FUNCTION bump_film_search_rev_from_credits() RETURNS trigger AS $$
+BEGIN
+    UPDATE films SET search_rev = nextval('catalog_search_rev_seq')
+    WHERE id IN (SELECT DISTINCT film_id FROM changed);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION bump_series_search_rev_from_credits() RETURNS trigger AS $$
+BEGIN
+    UPDATE series SET search_rev = nextval('catalog_search_rev_seq')
+    WHERE id IN (SELECT DISTINCT series_id FROM changed);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Transition tables allow only one event per trigger, hence the pairs.
+DROP TRIGGER IF EXISTS film_actors_search_rev_ins ON film_actors;
+CREATE TRIGGER film_actors_search_rev_ins
+    AFTER INSERT ON film_actors REFERENCING NEW TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_film_search_rev_from_credits();
+DROP TRIGGER IF EXISTS film_actors_search_rev_del ON film_actors;
+CREATE TRIGGER film_actors_search_rev_del
+    AFTER DELETE ON film_actors REFERENCING OLD TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_film_search_rev_from_credits();
+
+DROP TRIGGER IF EXISTS film_directors_search_rev_ins ON film_directors;
+CREATE TRIGGER film_directors_search_rev_ins
+    AFTER INSERT ON film_directors REFERENCING NEW TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_film_search_rev_from_credits();
+DROP TRIGGER IF EXISTS film_directors_search_rev_del ON film_directors;
+CREATE TRIGGER film_directors_search_rev_del
+    AFTER DELETE ON film_directors REFERENCING OLD TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_film_search_rev_from_credits();
+
+DROP TRIGGER IF EXISTS series_actors_search_rev_ins ON series_actors;
+CREATE TRIGGER series_actors_search_rev_ins
+    AFTER INSERT ON series_actors REFERENCING NEW TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_series_search_rev_from_credits();
+DROP TRIGGER IF EXISTS series_actors_search_rev_del ON series_actors;
+CREATE TRIGGER series_actors_search_rev_del
+    AFTER DELETE ON series_actors REFERENCING OLD TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_series_search_rev_from_credits();
+
+DROP TRIGGER IF EXISTS series_directors_search_rev_ins ON series_directors;
+CREATE TRIGGER series_directors_search_rev_ins
+    AFTER INSERT ON series_directors REFERENCING NEW TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_series_search_rev_from_credits();
+DROP TRIGGER IF EXISTS series_directors_search_rev_del ON series_directors;
+CREATE TRIGGER series_directors_search_rev_del
+    AFTER DELETE ON series_directors REFERENCING OLD TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_series_search_rev_from_credits();
@@ -0,0 +1,24 @@
+-- =============================================================================
+-- similar_titles — precomputed "Podobné tituly" carousel on film and series
+-- detail pages.
+--
+-- Filled by the daily background job in cr-web (`similar.rs`), which scores
+-- every pair of titles sharing a genre or a person on shared genres, shared
+-- directors and actors, year proximity and rating band, and keeps the best
+-- 20 per title. The job replaces all rows of one `kind` in a single
+-- transaction, so the detail page never sees a half-written set.
+--
+-- `title_id` / `similar_id` point into `films` or `series` depending on
+-- `kind`, so there is no foreign key; the detail query joins the catalog
+-- table, which drops titles deleted since the last run.
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS similar_titles (
+    kind        TEXT NOT NULL CHECK (kind IN ('film', 'series')),
+    title_id    INT NOT NULL,
+    rank        SMALLINT NOT NULL,
+    similar_id  INT NOT NULL,
+    score       REAL NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (kind, title_id, rank)
+);
@@ -0,0 +1,34 @@
+-- =============================================================================
+-- film_collections — franchises and sequel series ("Vetřelec kolekce") for
+-- the `/kolekce/{slug}/` pages and the "Součást kolekce" block with
+-- previous/next navigation on film detail pages.
+--
+-- Loaded by `import-collections` (cr-infra/src/bin) from a file of TMDB
+-- `/collection/{id}` responses, one JSON object per line. Parts are matched
+-- on `films.tmdb_id`; parts we don't have are skipped but still count
+-- towards `position`, so "3. díl" stays true when part 2 is missing here.
+--
+-- A film belongs to at most one collection (TMDB's `belongs_to_collection`
+-- is single-valued), hence `film_id` as the primary key of the membership
+-- table. The slug is assigned on first import and kept when TMDB renames
+-- the collection, the same as `people.slug`.
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS film_collections (
+    id          SERIAL      PRIMARY KEY,
+    tmdb_id     INT         UNIQUE,
+    slug        TEXT        NOT NULL UNIQUE,
+    name        TEXT        NOT NULL,
+    overview    TEXT,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS film_collection_films (
+    film_id       INT      PRIMARY KEY REFERENCES films(id) ON DELETE CASCADE,
+    collection_id INT      NOT NULL REFERENCES film_collections(id) ON DELETE CASCADE,
+    -- 1-based, in release order across the whole TMDB collection.
+    position      SMALLINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_film_collection_films_collection
+    ON film_collection_films (collection_id, position);
@@ -0,0 +1,94 @@
+-- =============================================================================
+-- Atom/RSS feeds — newest-first reads for `/filmy-online/feed.xml`,
+-- `/serialy-online/feed.xml`, `/tv-porady/feed.xml`, `/pamatky/feed.xml`
+-- and `/obce/feed.xml` (cr-web/src/handlers/feeds.rs).
+--
+-- 1. Episode feeds order every sourced episode by `created_at`. The
+--    existing `idx_episodes_series_created` only serves the per-series feed;
+--    the site-wide feeds get their own `created_at` indexes so a feed poll
+--    walks the newest rows instead of sorting ~104 k episodes.
+--
+-- 2. Landmarks and municipalities had no modification time at all. The
+--    place feeds announce both new pages and rewritten ones, so
+--    `content_updated_at` is bumped by a BEFORE UPDATE trigger whenever a
+--    column that shows up on the page changes — descriptions, names, links,
+--    photos and emblems. Maintenance columns (coordinates re-geocoded by
+--    `assign-municipalities`, `municipality_id`, NPÚ ids) don't count: a
+--    subscriber would see nothing new on the page. A new municipality photo
+--    bumps the municipality too.
+--
+--    Existing rows start at `created_at`, so the first feed poll after the
+--    deploy doesn't report the whole country as updated.
+-- =============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_episodes_created ON episodes (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_tv_episodes_created ON tv_episodes (created_at DESC);
+
+ALTER TABLE landmarks ADD COLUMN IF NOT EXISTS content_updated_at TIMESTAMPTZ;
+ALTER TABLE municipalities ADD COLUMN IF NOT EXISTS content_updated_at TIMESTAMPTZ;
+UPDATE landmarks SET content_updated_at = COALESCE(created_at, now())
+    WHERE content_updated_at IS NULL;
+UPDATE municipalities SET content_updated_at = COALESCE(created_at, now())
+    WHERE content_updated_at IS NULL;
+ALTER TABLE landmarks
+    ALTER COLUMN content_updated_at SET DEFAULT now(),
+    ALTER COLUMN content_updated_at SET NOT NULL;
+ALTER TABLE municipalities
+    ALTER COLUMN content_updated_at SET DEFAULT now(),
+    ALTER COLUMN content_updated_at SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_landmarks_content_updated
+    ON landmarks (content_updated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_municipalities_content_updated
+    ON municipalities (content_updated_at DESC);
+
+CREATE OR REPLACE FUNCTION bump_content_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.content_updated_at := now();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_landmarks_content_updated ON landmarks;
+CREATE TRIGGER trg_landmarks_content_updated
+    BEFORE UPDATE OF name, description, npu_description, wikipedia_url, image_ext, photo_count
+    ON landmarks
+    FOR EACH ROW
+    WHEN (
+        OLD.name IS DISTINCT FROM NEW.name
+        OR OLD.description IS DISTINCT FROM NEW.description
+        OR OLD.npu_description IS DISTINCT FROM NEW.npu_description
+        OR OLD.wikipedia_url IS DISTINCT FROM NEW.wikipedia_url
+        OR OLD.image_ext IS DISTINCT FROM NEW.image_ext
+        OR OLD.photo_count IS DISTINCT FROM NEW.photo_count
+    )
+    EXECUTE FUNCTION bump_content_updated_at();
+
+DROP TRIGGER IF EXISTS trg_municipalities_content_updated ON municipalities;
+CREATE TRIGGER trg_municipalities_content_updated
+    BEFORE UPDATE OF name, description, wikipedia_url, official_website, coat_of_arms_ext, flag_ext
+    ON municipalities
+    FOR EACH ROW
+    WHEN (
+        OLD.name IS DISTINCT FROM NEW.name
+        OR OLD.description IS DISTINCT FROM NEW.description
+        OR OLD.wikipedia_url IS DISTINCT FROM NEW.wikipedia_url
+        OR OLD.official_website IS DISTINCT FROM NEW.official_website
+        OR OLD.coat_of_arms_ext IS DISTINCT FROM NEW.coat_of_arms_ext
+        OR OLD.flag_ext IS DISTINCT FROM NEW.flag_ext
+    )
+    EXECUTE FUNCTION bump_content_updated_at();
+
+CREATE OR REPLACE FUNCTION bump_municipality_on_photo() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE municipalities m SET content_updated_at = now()
+    WHERE m.municipality_code IN (SELECT DISTINCT municipality_code FROM changed);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_municipality_photos_bump ON municipality_photos;
+CREATE TRIGGER trg_municipality_photos_bump
+    AFTER INSERT ON municipality_photos
+    REFERENCING NEW TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_municipality_on_photo();
@@ -0,0 +1,174 @@
+-- =============================================================================
+-- Open-data exports — change cursor for `/api/export/{dataset}`
+-- (cr-web/src/handlers/export.rs).
+--
+-- Consumers pull a dataset once and then only rows changed `since=` their
+-- last pull. Every exported table gets `export_updated_at`, bumped by a
+-- BEFORE UPDATE trigger whenever one of the columns the export publishes
+-- changes (the list in `cr_domain::export`). Rating syncs, search
+-- revisions and source flags the export doesn't carry leave it alone, so
+-- an incremental pull isn't the whole catalog again after every sync run.
+--
+-- Landmark URLs include the municipality's slug and ORP, so renaming or
+-- re-assigning a municipality bumps its landmarks as well.
+--
+-- Existing rows start at `created_at`. The export pages on
+-- `(export_updated_at, id)`, hence the composite indexes.
+--
+-- Deletes are not tracked; a full pull (no `since=`) is the way to drop
+-- rows that disappeared.
+-- =============================================================================
+
+ALTER TABLE films ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+ALTER TABLE series ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+ALTER TABLE tv_shows ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+ALTER TABLE people ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+ALTER TABLE municipalities ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+ALTER TABLE landmarks ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+ALTER TABLE pools ADD COLUMN IF NOT EXISTS export_updated_at TIMESTAMPTZ;
+
+UPDATE films SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+UPDATE series SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+UPDATE tv_shows SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+UPDATE people SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+UPDATE municipalities SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+UPDATE landmarks SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+UPDATE pools SET export_updated_at = created_at WHERE export_updated_at IS NULL;
+
+ALTER TABLE films
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+ALTER TABLE series
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+ALTER TABLE tv_shows
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+ALTER TABLE people
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+ALTER TABLE municipalities
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+ALTER TABLE landmarks
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+ALTER TABLE pools
+    ALTER COLUMN export_updated_at SET DEFAULT now(),
+    ALTER COLUMN export_updated_at SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_films_export ON films (export_updated_at, id);
+CREATE INDEX IF NOT EXISTS idx_series_export ON series (export_updated_at, id);
+CREATE INDEX IF NOT EXISTS idx_tv_shows_export ON tv_shows (export_updated_at, id);
+CREATE INDEX IF NOT EXISTS idx_people_export ON people (export_updated_at, id);
+CREATE INDEX IF NOT EXISTS idx_municipalities_export ON municipalities (export_updated_at, id);
+CREATE INDEX IF NOT EXISTS idx_landmarks_export ON landmarks (export_updated_at, id);
+CREATE INDEX IF NOT EXISTS idx_pools_export ON pools (export_updated_at, id);
+
+CREATE OR REPLACE FUNCTION bump_export_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.export_updated_at := now();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_films_export_updated ON films;
+CREATE TRIGGER trg_films_export_updated
+    BEFORE UPDATE ON films
+    FOR EACH ROW
+    WHEN ((OLD.title, OLD.original_title, OLD.slug, OLD.year, OLD.runtime_min,
+           OLD.imdb_id, OLD.tmdb_id, OLD.csfd_id, OLD.imdb_rating, OLD.imdb_votes,
+           OLD.csfd_rating, OLD.tmdb_rating, OLD.tmdb_vote_count,
+           OLD.audio_langs, OLD.subtitle_langs, OLD.added_at)
+        IS DISTINCT FROM
+          (NEW.title, NEW.original_title, NEW.slug, NEW.year, NEW.runtime_min,
+           NEW.imdb_id, NEW.tmdb_id, NEW.csfd_id, NEW.imdb_rating, NEW.imdb_votes,
+           NEW.csfd_rating, NEW.tmdb_rating, NEW.tmdb_vote_count,
+           NEW.audio_langs, NEW.subtitle_langs, NEW.added_at))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+DROP TRIGGER IF EXISTS trg_series_export_updated ON series;
+CREATE TRIGGER trg_series_export_updated
+    BEFORE UPDATE ON series
+    FOR EACH ROW
+    WHEN ((OLD.title, OLD.original_title, OLD.slug, OLD.first_air_year, OLD.last_air_year,
+           OLD.season_count, OLD.episode_count, OLD.imdb_id, OLD.tmdb_id, OLD.csfd_id,
+           OLD.imdb_rating, OLD.imdb_votes, OLD.csfd_rating, OLD.tmdb_rating,
+           OLD.tmdb_vote_count, OLD.audio_langs_any, OLD.subtitle_langs_any, OLD.added_at)
+        IS DISTINCT FROM
+          (NEW.title, NEW.original_title, NEW.slug, NEW.first_air_year, NEW.last_air_year,
+           NEW.season_count, NEW.episode_count, NEW.imdb_id, NEW.tmdb_id, NEW.csfd_id,
+           NEW.imdb_rating, NEW.imdb_votes, NEW.csfd_rating, NEW.tmdb_rating,
+           NEW.tmdb_vote_count, NEW.audio_langs_any, NEW.subtitle_langs_any, NEW.added_at))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+DROP TRIGGER IF EXISTS trg_tv_shows_export_updated ON tv_shows;
+CREATE TRIGGER trg_tv_shows_export_updated
+    BEFORE UPDATE ON tv_shows
+    FOR EACH ROW
+    WHEN ((OLD.title, OLD.original_title, OLD.slug, OLD.first_air_year, OLD.last_air_year,
+           OLD.season_count, OLD.episode_count, OLD.imdb_id, OLD.tmdb_id, OLD.csfd_id,
+           OLD.imdb_rating, OLD.imdb_votes, OLD.csfd_rating, OLD.tmdb_rating,
+           OLD.tmdb_vote_count, OLD.added_at)
+        IS DISTINCT FROM
+          (NEW.title, NEW.original_title, NEW.slug, NEW.first_air_year, NEW.last_air_year,
+           NEW.season_count, NEW.episode_count, NEW.imdb_id, NEW.tmdb_id, NEW.csfd_id,
+           NEW.imdb_rating, NEW.imdb_votes, NEW.csfd_rating, NEW.tmdb_rating,
+           NEW.tmdb_vote_count, NEW.added_at))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+DROP TRIGGER IF EXISTS trg_people_export_updated ON people;
+CREATE TRIGGER trg_people_export_updated
+    BEFORE UPDATE ON people
+    FOR EACH ROW
+    WHEN ((OLD.slug, OLD.name, OLD.tmdb_id) IS DISTINCT FROM (NEW.slug, NEW.name, NEW.tmdb_id))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+DROP TRIGGER IF EXISTS trg_municipalities_export_updated ON municipalities;
+CREATE TRIGGER trg_municipalities_export_updated
+    BEFORE UPDATE ON municipalities
+    FOR EACH ROW
+    WHEN ((OLD.name, OLD.slug, OLD.orp_id, OLD.population, OLD.elevation,
+           OLD.latitude, OLD.longitude, OLD.wikipedia_url, OLD.official_website)
+        IS DISTINCT FROM
+          (NEW.name, NEW.slug, NEW.orp_id, NEW.population, NEW.elevation,
+           NEW.latitude, NEW.longitude, NEW.wikipedia_url, NEW.official_website))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+DROP TRIGGER IF EXISTS trg_landmarks_export_updated ON landmarks;
+CREATE TRIGGER trg_landmarks_export_updated
+    BEFORE UPDATE ON landmarks
+    FOR EACH ROW
+    WHEN ((OLD.slug, OLD.name, OLD.type_id, OLD.municipality_id, OLD.wikidata_id,
+           OLD.npu_catalog_id, OLD.latitude, OLD.longitude, OLD.wikipedia_url)
+        IS DISTINCT FROM
+          (NEW.slug, NEW.name, NEW.type_id, NEW.municipality_id, NEW.wikidata_id,
+           NEW.npu_catalog_id, NEW.latitude, NEW.longitude, NEW.wikipedia_url))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+DROP TRIGGER IF EXISTS trg_pools_export_updated ON pools;
+CREATE TRIGGER trg_pools_export_updated
+    BEFORE UPDATE ON pools
+    FOR EACH ROW
+    WHEN ((OLD.slug, OLD.name, OLD.address, OLD.municipality_id, OLD.orp_id,
+           OLD.latitude, OLD.longitude, OLD.website, OLD.pool_length_m,
+           OLD.is_aquapark, OLD.is_indoor, OLD.is_outdoor, OLD.is_natural)
+        IS DISTINCT FROM
+          (NEW.slug, NEW.name, NEW.address, NEW.municipality_id, NEW.orp_id,
+           NEW.latitude, NEW.longitude, NEW.website, NEW.pool_length_m,
+           NEW.is_aquapark, NEW.is_indoor, NEW.is_outdoor, NEW.is_natural))
+    EXECUTE FUNCTION bump_export_updated_at();
+
+CREATE OR REPLACE FUNCTION bump_landmarks_export_on_municipality() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE landmarks SET export_updated_at = now() WHERE municipality_id = NEW.id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_municipalities_export_landmarks ON municipalities;
+CREATE TRIGGER trg_municipalities_export_landmarks
+    AFTER UPDATE OF slug, orp_id ON municipalities
+    FOR EACH ROW
+    WHEN ((OLD.slug, OLD.orp_id) IS DISTINCT FROM (NEW.slug, NEW.orp_id))
+    EXECUTE FUNCTION bump_landmarks_export_on_municipality();
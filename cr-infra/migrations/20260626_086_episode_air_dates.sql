@@ -0,0 +1,16 @@
+-- =============================================================================
+-- Episode release calendar — `/serialy-online/kalendar/`, the series
+-- "Připravované epizody" block and `/serialy-online/{slug}/kalendar.ics`
+-- (cr-web/src/handlers/calendar.rs).
+--
+-- Air dates come from TMDB: series_enricher.py sets them when it creates
+-- episodes, and scripts/sync-episode-air-dates.py keeps currently airing
+-- shows up to date nightly, adding source-less stub rows for announced
+-- episodes. The calendar reads episodes by date across all shows, hence
+-- the indexes; the per-series schedule uses the existing series_id ones.
+-- =============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_episodes_air_date
+    ON episodes (air_date) WHERE air_date IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tv_episodes_air_date
+    ON tv_episodes (air_date) WHERE air_date IS NOT NULL;
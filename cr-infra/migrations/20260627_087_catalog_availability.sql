@@ -0,0 +1,282 @@
+-- =============================================================================
+-- Per-title availability summaries for the catalog listings, plus indexes
+-- for their keyset (`?po=` / `?pred=`) pagination.
+--
+-- `film_availability` holds what a film card needs from `video_sources`:
+-- the alive-source count behind the anti-zombie filter and the primary
+-- upload of each provider (the source badges and player ids). Before this
+-- the listing resolved those with four correlated subqueries per row.
+--
+-- `series_availability` does the same one hop further for series: how many
+-- episodes are playable and which one was added last. The default
+-- `/serialy-online/` grid orders by that episode, so with it precomputed the
+-- grid is a plain index walk instead of a LATERAL lookup per series.
+--
+-- Both are pure derived state kept by triggers, in the spirit of the
+-- language rollups (migrations 058 and 071). The source of truth stays
+-- `video_sources`; nothing writes these tables directly.
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS film_availability (
+    film_id                    INTEGER PRIMARY KEY REFERENCES films(id) ON DELETE CASCADE,
+    alive_sources              INTEGER NOT NULL DEFAULT 0,
+    sktorrent_video_id         INTEGER,
+    sktorrent_cdn              SMALLINT,
+    sktorrent_qualities        TEXT,
+    prehrajto_url              TEXT,
+    sledujteto_primary_file_id INTEGER,
+    updated_at                 TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_film_availability_alive
+    ON film_availability (film_id) WHERE alive_sources > 0;
+
+CREATE TABLE IF NOT EXISTS series_availability (
+    series_id         INTEGER PRIMARY KEY REFERENCES series(id) ON DELETE CASCADE,
+    playable_episodes INTEGER NOT NULL DEFAULT 0,
+    latest_episode_id INTEGER,
+    latest_episode_at TIMESTAMPTZ,
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_series_availability_latest
+    ON series_availability (latest_episode_at DESC, series_id)
+    WHERE playable_episodes > 0;
+
+-- =============================================================================
+-- Recompute functions — one upsert per title, same shape as migration 058's
+-- recompute_video_rollups_for_parent().
+--
+--   * The `EXISTS` guard skips titles that are being deleted: a cascading
+--     DELETE of a film fires the video_sources trigger after the film row
+--     is gone, and inserting a summary for it would violate the FK.
+--   * `IS DISTINCT FROM` on the conflict branch keeps no-op recomputes
+--     (a source re-checked and still alive) from writing a new row version.
+--   * Provider fields mirror what the listing used to compute inline:
+--     primary alive upload per provider, prehraj.to falling back to the
+--     most recently updated alive upload, sledujteto only on the `www` CDN
+--     (data{N} is blocked from datacenter ASNs). Non-numeric ids become
+--     NULL instead of failing the write that fired the trigger.
+-- =============================================================================
+
+CREATE OR REPLACE FUNCTION recompute_film_availability(p_film_id INTEGER)
+RETURNS VOID AS $$
+BEGIN
+    INSERT INTO film_availability AS a (
+        film_id, alive_sources, sktorrent_video_id, sktorrent_cdn,
+        sktorrent_qualities, prehrajto_url, sledujteto_primary_file_id, updated_at
+    )
+    SELECT
+        p_film_id,
+        (SELECT COUNT(*) FROM video_sources vs
+          WHERE vs.film_id = p_film_id AND vs.is_alive),
+        (SELECT CASE WHEN vs.external_id ~ '^[0-9]{1,9}$' THEN vs.external_id::INTEGER END
+           FROM video_sources vs
+           JOIN video_providers p ON p.id = vs.provider_id
+          WHERE vs.film_id = p_film_id AND p.slug = 'sktorrent'
+            AND vs.is_primary AND vs.is_alive
+          LIMIT 1),
+        (SELECT CASE WHEN vs.cdn ~ '^[0-9]{1,4}$' THEN vs.cdn::SMALLINT END
+           FROM video_sources vs
+           JOIN video_providers p ON p.id = vs.provider_id
+          WHERE vs.film_id = p_film_id AND p.slug = 'sktorrent'
+            AND vs.is_primary AND vs.is_alive
+          LIMIT 1),
+        (SELECT vs.metadata->>'qualities'
+           FROM video_sources vs
+           JOIN video_providers p ON p.id = vs.provider_id
+          WHERE vs.film_id = p_film_id AND p.slug = 'sktorrent'
+            AND vs.is_primary AND vs.is_alive
+          LIMIT 1),
+        (SELECT COALESCE(vs.metadata->>'url', 'https://prehraj.to/' || vs.external_id)
+           FROM video_sources vs
+           JOIN video_providers p ON p.id = vs.provider_id
+          WHERE vs.film_id = p_film_id AND p.slug = 'prehrajto' AND vs.is_alive
+          ORDER BY vs.is_primary DESC, vs.updated_at DESC
+          LIMIT 1),
+        (SELECT CASE WHEN vs.external_id ~ '^[0-9]{1,9}$' THEN vs.external_id::INTEGER END
+           FROM video_sources vs
+           JOIN video_providers p ON p.id = vs.provider_id
+          WHERE vs.film_id = p_film_id AND p.slug = 'sledujteto'
+            AND vs.is_primary AND vs.is_alive AND vs.cdn = 'www'
+          LIMIT 1),
+        NOW()
+    WHERE EXISTS (SELECT 1 FROM films WHERE id = p_film_id)
+    ON CONFLICT (film_id) DO UPDATE SET
+        alive_sources              = EXCLUDED.alive_sources,
+        sktorrent_video_id         = EXCLUDED.sktorrent_video_id,
+        sktorrent_cdn              = EXCLUDED.sktorrent_cdn,
+        sktorrent_qualities        = EXCLUDED.sktorrent_qualities,
+        prehrajto_url              = EXCLUDED.prehrajto_url,
+        sledujteto_primary_file_id = EXCLUDED.sledujteto_primary_file_id,
+        updated_at                 = EXCLUDED.updated_at
+    WHERE (a.alive_sources, a.sktorrent_video_id, a.sktorrent_cdn, a.sktorrent_qualities,
+           a.prehrajto_url, a.sledujteto_primary_file_id)
+          IS DISTINCT FROM
+          (EXCLUDED.alive_sources, EXCLUDED.sktorrent_video_id, EXCLUDED.sktorrent_cdn,
+           EXCLUDED.sktorrent_qualities, EXCLUDED.prehrajto_url,
+           EXCLUDED.sledujteto_primary_file_id);
+END;
+$$ LANGUAGE plpgsql;
+
+-- The latest playable episode is the newest by `created_at` (id breaks
+-- ties), the same pick the LATERAL query made. The aggregate yields a row
+-- even without playable episodes, so the count drops back to 0; the guard
+-- sits in HAVING for that reason.
+CREATE OR REPLACE FUNCTION recompute_series_availability(p_series_id INTEGER)
+RETURNS VOID AS $$
+BEGIN
+    INSERT INTO series_availability AS a (
+        series_id, playable_episodes, latest_episode_id, latest_episode_at, updated_at
+    )
+    SELECT
+        p_series_id,
+        COUNT(*),
+        (array_agg(e.id ORDER BY e.created_at DESC, e.id DESC))[1],
+        MAX(e.created_at),
+        NOW()
+      FROM episodes e
+     WHERE e.series_id = p_series_id
+       AND EXISTS (SELECT 1 FROM video_sources vs
+                    WHERE vs.episode_id = e.id AND vs.is_alive)
+    HAVING EXISTS (SELECT 1 FROM series WHERE id = p_series_id)
+    ON CONFLICT (series_id) DO UPDATE SET
+        playable_episodes = EXCLUDED.playable_episodes,
+        latest_episode_id = EXCLUDED.latest_episode_id,
+        latest_episode_at = EXCLUDED.latest_episode_at,
+        updated_at        = EXCLUDED.updated_at
+    WHERE (a.playable_episodes, a.latest_episode_id, a.latest_episode_at)
+          IS DISTINCT FROM
+          (EXCLUDED.playable_episodes, EXCLUDED.latest_episode_id,
+           EXCLUDED.latest_episode_at);
+END;
+$$ LANGUAGE plpgsql;
+
+-- =============================================================================
+-- Triggers.
+--
+-- video_sources: any change that can move a summary — parent, liveness,
+-- primary flag, the id / CDN / metadata the provider fields read. The WHEN
+-- clause keeps the frequent `last_seen` / `last_checked` touches by the
+-- liveness checkers from recomputing anything.
+--
+-- episodes: a deleted or re-parented episode changes its series' count
+-- even though no source row of it was touched by the statement itself;
+-- `created_at` moves the latest pick. TV pořady aren't covered — their
+-- listing doesn't read a summary.
+-- =============================================================================
+
+CREATE OR REPLACE FUNCTION recompute_availability_for_source(
+    p_film_id INTEGER, p_episode_id INTEGER
+) RETURNS VOID AS $$
+DECLARE
+    sid INTEGER;
+BEGIN
+    IF p_film_id IS NOT NULL THEN
+        PERFORM recompute_film_availability(p_film_id);
+    ELSIF p_episode_id IS NOT NULL THEN
+        SELECT series_id INTO sid FROM episodes WHERE id = p_episode_id;
+        IF sid IS NOT NULL THEN
+            PERFORM recompute_series_availability(sid);
+        END IF;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION trg_video_sources_availability() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        PERFORM recompute_availability_for_source(OLD.film_id, OLD.episode_id);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE')
+       AND (TG_OP = 'INSERT'
+            OR NEW.film_id IS DISTINCT FROM OLD.film_id
+            OR NEW.episode_id IS DISTINCT FROM OLD.episode_id) THEN
+        PERFORM recompute_availability_for_source(NEW.film_id, NEW.episode_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_video_sources_availability_aid ON video_sources;
+CREATE TRIGGER trg_video_sources_availability_aid
+    AFTER INSERT OR DELETE ON video_sources
+    FOR EACH ROW EXECUTE FUNCTION trg_video_sources_availability();
+
+DROP TRIGGER IF EXISTS trg_video_sources_availability_au ON video_sources;
+CREATE TRIGGER trg_video_sources_availability_au
+    AFTER UPDATE ON video_sources
+    FOR EACH ROW
+    WHEN ((OLD.film_id, OLD.episode_id, OLD.provider_id, OLD.external_id, OLD.cdn,
+           OLD.is_primary, OLD.is_alive, OLD.metadata)
+          IS DISTINCT FROM
+          (NEW.film_id, NEW.episode_id, NEW.provider_id, NEW.external_id, NEW.cdn,
+           NEW.is_primary, NEW.is_alive, NEW.metadata))
+    EXECUTE FUNCTION trg_video_sources_availability();
+
+CREATE OR REPLACE FUNCTION trg_episodes_availability() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM recompute_series_availability(OLD.series_id);
+    ELSE  -- UPDATE
+        PERFORM recompute_series_availability(NEW.series_id);
+        IF OLD.series_id IS DISTINCT FROM NEW.series_id THEN
+            PERFORM recompute_series_availability(OLD.series_id);
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_episodes_availability_ad ON episodes;
+CREATE TRIGGER trg_episodes_availability_ad
+    AFTER DELETE ON episodes
+    FOR EACH ROW EXECUTE FUNCTION trg_episodes_availability();
+
+DROP TRIGGER IF EXISTS trg_episodes_availability_au ON episodes;
+CREATE TRIGGER trg_episodes_availability_au
+    AFTER UPDATE OF series_id, created_at ON episodes
+    FOR EACH ROW EXECUTE FUNCTION trg_episodes_availability();
+
+-- Row triggers don't fire on TRUNCATE; see migration 058.
+CREATE OR REPLACE FUNCTION trg_video_sources_availability_truncate() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM film_availability;
+    DELETE FROM series_availability;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_video_sources_availability_truncate_s ON video_sources;
+CREATE TRIGGER trg_video_sources_availability_truncate_s
+    AFTER TRUNCATE ON video_sources
+    FOR EACH STATEMENT EXECUTE FUNCTION trg_video_sources_availability_truncate();
+
+-- =============================================================================
+-- Keyset indexes for the default orders: films by date added (then title),
+-- films A–Z, and the series latest-episode grid (partial index above). The
+-- rating sorts are narrowed by their vote thresholds and the remaining ones
+-- are rare enough to stay on the single-column indexes.
+-- =============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_films_keyset_added
+    ON films (added_at DESC NULLS LAST, title, id);
+CREATE INDEX IF NOT EXISTS idx_films_keyset_title
+    ON films (title, year DESC NULLS LAST, id);
+
+-- =============================================================================
+-- One-shot backfill. After this the triggers keep both tables current.
+-- =============================================================================
+
+DO $$
+DECLARE
+    rid INTEGER;
+BEGIN
+    FOR rid IN SELECT DISTINCT film_id FROM video_sources WHERE film_id IS NOT NULL LOOP
+        PERFORM recompute_film_availability(rid);
+    END LOOP;
+    FOR rid IN SELECT DISTINCT e.series_id
+                 FROM video_sources vs JOIN episodes e ON e.id = vs.episode_id LOOP
+        PERFORM recompute_series_availability(rid);
+    END LOOP;
+END $$;
@@ -0,0 +1,61 @@
+-- =============================================================================
+-- Persistent queue for /stahnout-video/ downloads
+-- (cr-web/src/handlers/video_api/queue.rs).
+--
+-- Replaces the in-memory token map: `video_prepare` inserts a `queued`
+-- row, workers claim rows with FOR UPDATE SKIP LOCKED, and
+-- `/api/video/status/{token}` reads the row, so a deploy or crash no
+-- longer loses jobs. Running jobs refresh `updated_at` as a heartbeat;
+-- rows whose heartbeat stops are put back on the queue at startup and by
+-- the periodic sweep. Failed attempts are retried with a backoff via
+-- `next_attempt_at` until `attempts` reaches the limit in
+-- cr_domain::video_job::MAX_ATTEMPTS.
+--
+-- `library_id` deliberately has no FK to `videos`: a ready-link keeps
+-- redirecting to the library proxy, which answers 404 once the entry is
+-- gone.
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS video_download_jobs (
+    id               BIGSERIAL   PRIMARY KEY,
+    token            TEXT        NOT NULL UNIQUE,
+    state            TEXT        NOT NULL DEFAULT 'queued'
+        CHECK (state IN ('queued', 'downloading', 'converting', 'ready', 'ready_parts', 'failed')),
+    progress         SMALLINT    NOT NULL DEFAULT 0 CHECK (progress BETWEEN 0 AND 100),
+    attempts         INTEGER     NOT NULL DEFAULT 0,
+
+    -- What the user asked for, fixed at enqueue time.
+    source_url       TEXT        NOT NULL,
+    format_id        TEXT        NOT NULL,
+    resolution       TEXT        NOT NULL,
+    container        TEXT        NOT NULL,
+    whatsapp         BOOLEAN     NOT NULL DEFAULT FALSE,
+    publish          BOOLEAN     NOT NULL DEFAULT TRUE,
+    title            TEXT        NOT NULL,
+    duration_sec     INTEGER,
+    uploader         TEXT,
+    thumbnail_url    TEXT,
+
+    -- Result.
+    filename         TEXT        NOT NULL,
+    file_path        TEXT,
+    size_bytes       BIGINT,
+    parts            JSONB       NOT NULL DEFAULT '[]',     -- [{index, filename, size_bytes, file_path}]
+    library_id       INTEGER,
+    error            TEXT,
+
+    worker           TEXT,                                  -- claiming worker while running
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),    -- heartbeat while running
+    next_attempt_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Claim order.
+CREATE INDEX IF NOT EXISTS idx_video_download_jobs_queue
+    ON video_download_jobs (next_attempt_at, id) WHERE state = 'queued';
+-- Stale-heartbeat sweep.
+CREATE INDEX IF NOT EXISTS idx_video_download_jobs_running
+    ON video_download_jobs (updated_at) WHERE state IN ('downloading', 'converting');
+-- Retention sweep and the admin listing.
+CREATE INDEX IF NOT EXISTS idx_video_download_jobs_updated_at
+    ON video_download_jobs (updated_at DESC);
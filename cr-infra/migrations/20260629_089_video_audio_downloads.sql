@@ -0,0 +1,16 @@
+-- =============================================================================
+-- Audio-only downloads on /stahnout-video/.
+--
+-- An audio job fetches only the best audio stream and converts it with
+-- ffmpeg to the requested format, tagged from the extracted metadata and
+-- with the thumbnail as cover art. `audio_format` NULL means a video job;
+-- `audio_bitrate_kbps` is NULL for lossless FLAC. `container` carries the
+-- audio format's extension for audio jobs so filenames stay uniform.
+-- Values mirror cr_domain::video_job::{AudioFormat, AUDIO_BITRATES}.
+-- =============================================================================
+
+ALTER TABLE video_download_jobs
+    ADD COLUMN IF NOT EXISTS audio_format TEXT
+        CHECK (audio_format IN ('mp3', 'm4a', 'opus', 'flac')),
+    ADD COLUMN IF NOT EXISTS audio_bitrate_kbps INTEGER
+        CHECK (audio_bitrate_kbps IN (96, 128, 192, 256, 320));
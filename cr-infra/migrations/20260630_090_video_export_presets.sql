@@ -0,0 +1,16 @@
+-- =============================================================================
+-- Export presets beyond WhatsApp.
+--
+-- The `whatsapp` flag becomes `export_preset`: the platform the download is
+-- re-encoded for after it finishes, NULL for a plain download. Values
+-- mirror cr_domain::export_preset::ExportTarget. Queued WhatsApp jobs keep
+-- their meaning through the backfill.
+-- =============================================================================
+
+ALTER TABLE video_download_jobs
+    ADD COLUMN IF NOT EXISTS export_preset TEXT
+        CHECK (export_preset IN ('whatsapp', 'telegram', 'discord', 'email', 'instagram_story'));
+
+UPDATE video_download_jobs SET export_preset = 'whatsapp' WHERE whatsapp;
+
+ALTER TABLE video_download_jobs DROP COLUMN IF EXISTS whatsapp;
@@ -0,0 +1,26 @@
+-- =============================================================================
+-- Clip trimming and GIF / animated WebP export on /stahnout-video/.
+--
+-- `clip_start_secs` / `clip_end_secs` keep only that part of the download
+-- (both NULL for the whole video). `animation_format` turns the clip into
+-- an animated image at `animation_fps` frames per second, `animation_width`
+-- pixels wide; NULL for video and audio jobs. An animation always has a
+-- clip. Limits mirror
+-- cr_domain::video_job::{ANIMATION_FPS, ANIMATION_WIDTHS}.
+-- =============================================================================
+
+ALTER TABLE video_download_jobs
+    ADD COLUMN IF NOT EXISTS clip_start_secs DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS clip_end_secs DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS animation_format TEXT
+        CHECK (animation_format IN ('gif', 'webp')),
+    ADD COLUMN IF NOT EXISTS animation_fps INTEGER
+        CHECK (animation_fps BETWEEN 5 AND 30),
+    ADD COLUMN IF NOT EXISTS animation_width INTEGER
+        CHECK (animation_width BETWEEN 120 AND 1280);
+
+ALTER TABLE video_download_jobs
+    ADD CONSTRAINT video_download_jobs_clip_check
+        CHECK ((clip_start_secs IS NULL) = (clip_end_secs IS NULL)
+               AND (clip_start_secs IS NULL OR clip_end_secs > clip_start_secs)
+               AND (animation_format IS NULL OR clip_start_secs IS NOT NULL));
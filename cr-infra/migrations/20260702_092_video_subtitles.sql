@@ -0,0 +1,18 @@
+-- =============================================================================
+-- Subtitles for video downloads on /stahnout-video/.
+--
+-- `subtitle_lang` / `subtitle_url` name the track to add (both NULL for
+-- none). `subtitle_burn` burns it into the picture instead of muxing a soft
+-- track. Only video jobs carry subtitles.
+-- =============================================================================
+
+ALTER TABLE video_download_jobs
+    ADD COLUMN IF NOT EXISTS subtitle_lang TEXT,
+    ADD COLUMN IF NOT EXISTS subtitle_url TEXT,
+    ADD COLUMN IF NOT EXISTS subtitle_burn BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE video_download_jobs
+    ADD CONSTRAINT video_download_jobs_subtitle_check
+        CHECK ((subtitle_lang IS NULL) = (subtitle_url IS NULL)
+               AND (subtitle_lang IS NULL
+                    OR (audio_format IS NULL AND animation_format IS NULL)));
@@ -0,0 +1,16 @@
+-- =============================================================================
+-- Duplicate-job coalescing for /stahnout-video/.
+--
+-- `dedup_key` (NewVideoJob::dedup_key in cr-domain) sums up everything
+-- that decides the output file, canonical source URL included. At most one
+-- queued or running job holds a given key; an identical prepare joins that
+-- job instead of downloading the video again. The index is on md5() since
+-- the key embeds the URL, and tokenized manifest URLs can outgrow a btree
+-- entry. Jobs from before this migration have no key and never coalesce.
+-- =============================================================================
+
+ALTER TABLE video_download_jobs ADD COLUMN IF NOT EXISTS dedup_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_video_download_jobs_active_dedup
+    ON video_download_jobs (md5(dedup_key))
+    WHERE state IN ('queued', 'downloading', 'converting');
@@ -0,0 +1,36 @@
+-- =============================================================================
+-- Recompute film availability when a source's `updated_at` changes.
+--
+-- `film_availability.prehrajto_url` falls back to the most recently updated
+-- alive upload (`ORDER BY vs.is_primary DESC, vs.updated_at DESC`, as on the
+-- detail page), but the update trigger from migration 087 ignored
+-- `updated_at`, so a touch that only reordered the uploads left the summary
+-- pointing at the old one. Nothing bumps `updated_at` implicitly, so adding
+-- it to the WHEN list only fires on writes that set it.
+-- =============================================================================
+
+DROP TRIGGER IF EXISTS trg_video_sources_availability_au ON video_sources;
+CREATE TRIGGER trg_video_sources_availability_au
+    AFTER UPDATE ON video_sources
+    FOR EACH ROW
+    WHEN ((OLD.film_id, OLD.episode_id, OLD.provider_id, OLD.external_id, OLD.cdn,
+           OLD.is_primary, OLD.is_alive, OLD.metadata, OLD.updated_at)
+          IS DISTINCT FROM
+          (NEW.film_id, NEW.episode_id, NEW.provider_id, NEW.external_id, NEW.cdn,
+           NEW.is_primary, NEW.is_alive, NEW.metadata, NEW.updated_at))
+    EXECUTE FUNCTION trg_video_sources_availability();
+
+-- Films with several alive prehraj.to uploads may have a stale pick.
+DO $$
+DECLARE
+    rid INTEGER;
+BEGIN
+    FOR rid IN SELECT vs.film_id
+                 FROM video_sources vs
+                 JOIN video_providers p ON p.id = vs.provider_id
+                WHERE p.slug = 'prehrajto' AND vs.is_alive AND vs.film_id IS NOT NULL
+                GROUP BY vs.film_id
+               HAVING count(*) > 1 LOOP
+        PERFORM recompute_film_availability(rid);
+    END LOOP;
+END $$;
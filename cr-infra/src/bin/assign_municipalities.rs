@@ -0,0 +1,243 @@
+//! Validate or fill `municipality_id` of landmarks and pools from their
+//! coordinates, using the same boundary index as `/api/reverse`.
+//!
+//! Usage:
+//!
+//!     assign-municipalities check [landmarks|pools]
+//!         Report rows whose coordinates fall into a different
+//!         municipality than the one they're assigned to, plus rows
+//!         without an assignment. Read-only.
+//!
+//!     assign-municipalities apply [landmarks|pools] [--overwrite]
+//!         Set `municipality_id` (and `orp_id` to match) where it is
+//!         NULL. With `--overwrite` also correct mismatched rows.
+//!         Landmarks whose slug is already taken in the target ORP
+//!         (`idx_landmarks_slug_orp`, the `/{orp}/{slug}/` URL) are
+//!         reported as COLLISION and left alone.
+//!
+//!     assign-municipalities resolve
+//!         Read `key<TAB>lat<TAB>lon` lines on stdin and print
+//!         `key<TAB>municipality<TAB>orp<TAB>district<TAB>region` codes
+//!         (empty when unresolved). Used by the staging importers in
+//!         `data/scripts/` that have no `municipalities` table to join.
+//!
+//! Boundaries are read from `GEOJSON_DATA_DIR` (default `data/geojson`);
+//! `obce_simple.geojson` must be present.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use anyhow::{Context, Result, bail};
+use cr_domain::Coordinates;
+use cr_infra::boundaries::ReverseGeocoder;
+use sqlx::PgPool;
+
+#[derive(sqlx::FromRow)]
+struct PlaceRow {
+    id: i32,
+    slug: String,
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    municipality_id: Option<i32>,
+}
+
+#[derive(Default)]
+struct Summary {
+    ok: usize,
+    assigned: usize,
+    mismatched: usize,
+    unresolved: usize,
+    collisions: usize,
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    dotenvy::dotenv().ok();
+
+    let data_dir = std::env::var("GEOJSON_DATA_DIR").unwrap_or_else(|_| "data/geojson".to_string());
+    let geocoder = ReverseGeocoder::load(&data_dir)?;
+    if geocoder.municipalities.is_empty() {
+        bail!("{data_dir}/obce_simple.geojson is missing — municipality polygons are required");
+    }
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let overwrite = args.iter().any(|a| a == "--overwrite");
+    let positional: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|a| !a.starts_with("--"))
+        .collect();
+    let tables: Vec<&str> = match positional.get(1).copied() {
+        None => vec!["landmarks", "pools"],
+        Some(t @ ("landmarks" | "pools")) => vec![t],
+        Some(other) => bail!("unknown table {other:?} (expected landmarks or pools)"),
+    };
+
+    match positional.first().copied() {
+        Some("resolve") => resolve(&geocoder),
+        Some(mode @ ("check" | "apply")) => {
+            let runtime = tokio::runtime::Runtime::new()?;
+            runtime.block_on(async {
+                let database_url =
+                    std::env::var("DATABASE_URL").context("DATABASE_URL must be set in .env")?;
+                let pool = PgPool::connect(&database_url)
+                    .await
+                    .context("Failed to connect to database")?;
+                for table in tables {
+                    run(&pool, &geocoder, table, mode == "apply", overwrite).await?;
+                }
+                Ok(())
+            })
+        }
+        _ => bail!(
+            "usage: assign-municipalities <check [TABLE] | apply [TABLE] [--overwrite] | resolve>"
+        ),
+    }
+}
+
+fn resolve(geocoder: &ReverseGeocoder) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let mut parts = line.split('\t');
+        let (Some(key), Some(lat), Some(lon)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let location = lat
+            .trim()
+            .parse()
+            .ok()
+            .zip(lon.trim().parse().ok())
+            .and_then(|(lat, lon)| Coordinates::new(lat, lon).ok())
+            .map(|at| geocoder.lookup(at))
+            .unwrap_or_default();
+        let code = |u: &Option<cr_infra::boundaries::Unit>| {
+            u.as_ref().map(|u| u.code.clone()).unwrap_or_default()
+        };
+        writeln!(
+            out,
+            "{key}\t{}\t{}\t{}\t{}",
+            code(&location.municipality),
+            code(&location.orp),
+            code(&location.district),
+            code(&location.region)
+        )?;
+    }
+    Ok(())
+}
+
+async fn run(
+    pool: &PgPool,
+    geocoder: &ReverseGeocoder,
+    table: &str,
+    apply: bool,
+    overwrite: bool,
+) -> Result<()> {
+    let ids: HashMap<String, i32> =
+        sqlx::query_as::<_, (String, i32)>("SELECT municipality_code, id FROM municipalities")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    // `table` is one of two literals checked in main().
+    let rows = sqlx::query_as::<_, PlaceRow>(&format!(
+        "SELECT id, slug, name, latitude, longitude, municipality_id FROM {table} \
+         WHERE latitude IS NOT NULL AND longitude IS NOT NULL ORDER BY id"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut summary = Summary::default();
+    for row in rows {
+        let resolved = Coordinates::new(row.latitude, row.longitude)
+            .ok()
+            .and_then(|at| geocoder.lookup(at).municipality)
+            .and_then(|m| ids.get(&m.code).copied().map(|id| (id, m)));
+        let Some((resolved_id, unit)) = resolved else {
+            summary.unresolved += 1;
+            println!(
+                "{table}\t{}\tUNRESOLVED\t{}, {}\t{}",
+                row.id, row.latitude, row.longitude, row.name
+            );
+            continue;
+        };
+        let write = match row.municipality_id {
+            Some(current) if current == resolved_id => {
+                summary.ok += 1;
+                false
+            }
+            Some(current) => {
+                summary.mismatched += 1;
+                println!(
+                    "{table}\t{}\tMISMATCH\tassigned {current}, coordinates in {} ({} #{resolved_id})\t{}",
+                    row.id, unit.name, unit.code, row.name
+                );
+                overwrite
+            }
+            None => {
+                summary.assigned += 1;
+                println!(
+                    "{table}\t{}\tUNASSIGNED\t→ {} ({} #{resolved_id})\t{}",
+                    row.id, unit.name, unit.code, row.name
+                );
+                true
+            }
+        };
+        if apply && write {
+            if table == "landmarks" && slug_taken(pool, &row, resolved_id).await? {
+                summary.collisions += 1;
+                println!(
+                    "{table}\t{}\tCOLLISION\tslug {:?} already used in the ORP of {} ({} #{resolved_id})\t{}",
+                    row.id, row.slug, unit.name, unit.code, row.name
+                );
+                continue;
+            }
+            // `orp_id` follows the municipality: it's part of the public
+            // URL and of the landmarks' unique slug index.
+            sqlx::query(&format!(
+                "UPDATE {table} SET municipality_id = $1, \
+                 orp_id = (SELECT orp_id FROM municipalities WHERE id = $1) \
+                 WHERE id = $2"
+            ))
+            .bind(resolved_id)
+            .bind(row.id)
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    tracing::info!(
+        "{table}: {} ok, {} unassigned{}, {} mismatched{}, {} unresolved, {} slug collisions",
+        summary.ok,
+        summary.assigned,
+        if apply { " (assigned)" } else { "" },
+        summary.mismatched,
+        if apply && overwrite {
+            " (corrected)"
+        } else {
+            ""
+        },
+        summary.unresolved,
+        summary.collisions
+    );
+    Ok(())
+}
+
+/// Whether another landmark already has `row.slug` in the ORP of
+/// municipality `target`, so moving `row` there would violate
+/// `idx_landmarks_slug_orp (slug, orp_id)` and abort the run.
+async fn slug_taken(pool: &PgPool, row: &PlaceRow, target: i32) -> Result<bool> {
+    Ok(sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM landmarks \
+         WHERE slug = $1 AND id <> $3 \
+         AND orp_id = (SELECT orp_id FROM municipalities WHERE id = $2))",
+    )
+    .bind(&row.slug)
+    .bind(target)
+    .bind(row.id)
+    .fetch_one(pool)
+    .await?)
+}
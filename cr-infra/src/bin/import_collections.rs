@@ -0,0 +1,221 @@
+//! Load film collections (franchises) for `/kolekce/{slug}/`.
+//!
+//! Usage:
+//!
+//!     import-collections FILE
+//!
+//! FILE holds TMDB `/collection/{id}?language=cs-CZ` responses, one JSON
+//! object per line (`id`, `name`, `overview`, `parts[].id`,
+//! `parts[].release_date`). Parts are ordered by release date and matched
+//! to `films.tmdb_id`; collections with fewer than two matched films are
+//! skipped, since a one-film "collection" has nothing to navigate to.
+//! Re-running the import updates names and membership in place and keeps
+//! existing slugs; a collection imported earlier that has dropped below
+//! two films — in the file, or because its films moved to another
+//! collection — is deleted along with its membership.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use anyhow::{Context, Result};
+use cr_domain::slug_from_name;
+use sqlx::PgPool;
+
+#[derive(Debug, serde::Deserialize)]
+struct TmdbCollection {
+    id: i32,
+    name: String,
+    #[serde(default)]
+    overview: Option<String>,
+    #[serde(default)]
+    parts: Vec<TmdbPart>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TmdbPart {
+    id: i32,
+    #[serde(default)]
+    release_date: Option<String>,
+}
+
+#[derive(Default)]
+struct Summary {
+    imported: usize,
+    skipped: usize,
+    removed: usize,
+    films: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    dotenvy::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set in .env")?;
+    let path = std::env::args()
+        .nth(1)
+        .context("usage: import-collections FILE")?;
+
+    let pool = PgPool::connect(&database_url)
+        .await
+        .context("Failed to connect to database")?;
+
+    let films: HashMap<i32, i32> =
+        sqlx::query_as::<_, (i32, i32)>("SELECT tmdb_id, id FROM films WHERE tmdb_id IS NOT NULL")
+            .fetch_all(&pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    let file = std::fs::File::open(&path).with_context(|| format!("Failed to open {path}"))?;
+    let mut summary = Summary::default();
+    for (n, line) in std::io::BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let collection: TmdbCollection = serde_json::from_str(&line)
+            .with_context(|| format!("{path}:{}: invalid collection JSON", n + 1))?;
+
+        // (film id, 1-based position in the whole collection)
+        let members: Vec<(i32, i16)> = ordered_parts(&collection.parts)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, tmdb_id)| films.get(&tmdb_id).map(|&id| (id, idx as i16 + 1)))
+            .collect();
+        if members.len() < 2 {
+            summary.skipped += 1;
+            // Membership rows cascade with the collection.
+            let removed = sqlx::query("DELETE FROM film_collections WHERE tmdb_id = $1")
+                .bind(collection.id)
+                .execute(&pool)
+                .await?
+                .rows_affected();
+            if removed > 0 {
+                summary.removed += 1;
+                tracing::info!(
+                    "collection {} ({}) removed: fewer than two matched films",
+                    collection.id,
+                    collection.name
+                );
+            }
+            continue;
+        }
+        import(&pool, &collection, &members)
+            .await
+            .with_context(|| format!("collection {} ({})", collection.id, collection.name))?;
+        summary.imported += 1;
+        summary.films += members.len();
+    }
+
+    tracing::info!(
+        "{} collections imported ({} films), {} skipped with fewer than two matched films \
+         ({} of them removed)",
+        summary.imported,
+        summary.films,
+        summary.skipped,
+        summary.removed
+    );
+    Ok(())
+}
+
+/// TMDB ids of the parts in release order; undated parts (announced
+/// sequels) go last.
+fn ordered_parts(parts: &[TmdbPart]) -> Vec<i32> {
+    let mut sorted: Vec<(&str, i32)> = parts
+        .iter()
+        .map(|p| {
+            let date = p.release_date.as_deref().unwrap_or_default();
+            (if date.is_empty() { "9999" } else { date }, p.id)
+        })
+        .collect();
+    sorted.sort();
+    sorted.into_iter().map(|(_, id)| id).collect()
+}
+
+async fn import(pool: &PgPool, collection: &TmdbCollection, members: &[(i32, i16)]) -> Result<()> {
+    let overview = collection
+        .overview
+        .as_deref()
+        .map(str::trim)
+        .filter(|o| !o.is_empty());
+
+    let mut tx = pool.begin().await?;
+    let existing: Option<i32> =
+        sqlx::query_scalar("SELECT id FROM film_collections WHERE tmdb_id = $1")
+            .bind(collection.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    let collection_id = match existing {
+        Some(id) => {
+            sqlx::query(
+                "UPDATE film_collections SET name = $2, overview = $3, updated_at = now() \
+                 WHERE id = $1",
+            )
+            .bind(id)
+            .bind(&collection.name)
+            .bind(overview)
+            .execute(&mut *tx)
+            .await?;
+            id
+        }
+        None => {
+            let mut slug = slug_from_name(&collection.name);
+            if slug.is_empty() {
+                slug = format!("kolekce-{}", collection.id);
+            } else {
+                let taken: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (SELECT 1 FROM film_collections WHERE slug = $1)",
+                )
+                .bind(&slug)
+                .fetch_one(&mut *tx)
+                .await?;
+                if taken {
+                    slug = format!("{slug}-{}", collection.id);
+                }
+            }
+            sqlx::query_scalar(
+                "INSERT INTO film_collections (tmdb_id, slug, name, overview) \
+                 VALUES ($1, $2, $3, $4) RETURNING id",
+            )
+            .bind(collection.id)
+            .bind(&slug)
+            .bind(&collection.name)
+            .bind(overview)
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    let film_ids: Vec<i32> = members.iter().map(|m| m.0).collect();
+    let positions: Vec<i16> = members.iter().map(|m| m.1).collect();
+    // Also drops the films from whatever collection they were in before.
+    let left: Vec<i32> = sqlx::query_scalar(
+        "DELETE FROM film_collection_films WHERE collection_id = $1 OR film_id = ANY($2) \
+         RETURNING collection_id",
+    )
+    .bind(collection_id)
+    .bind(&film_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    // A collection those films left may be down to fewer than two.
+    sqlx::query(
+        "DELETE FROM film_collections c WHERE c.id = ANY($2) AND c.id <> $1 \
+         AND (SELECT count(*) FROM film_collection_films f WHERE f.collection_id = c.id) < 2",
+    )
+    .bind(collection_id)
+    .bind(&left)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO film_collection_films (film_id, collection_id, position) \
+         SELECT film_id, $1, position FROM UNNEST($2::int[], $3::smallint[]) AS m(film_id, position)",
+    )
+    .bind(collection_id)
+    .bind(&film_ids)
+    .bind(&positions)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,254 @@
+//! Maintain the perceptual-hash index (`photo_hashes`, migration 079).
+//!
+//! Usage:
+//!
+//!     photo-hash-index build [--all]
+//!         Hash every photo/cover not yet in the index (or all of them with
+//!         `--all`). Images are fetched through the public `/img/{key}`
+//!         Worker route, same as the cover proxy.
+//!
+//!     photo-hash-index report [--max-distance N]
+//!         Print duplicate clusters, one block per cluster.
+//!
+//!     photo-hash-index check FILE [R2_KEY] [--max-distance N]
+//!         Hash a local file and list indexed near-duplicates, other than
+//!         `R2_KEY` itself (the upload's destination). Exits with status 2
+//!         when at least one match exists so the upload scripts in
+//!         `data/scripts/` can skip the file.
+//!
+//!     photo-hash-index add FILE R2_KEY [ENTITY_TYPE ENTITY_REF]
+//!         Register a freshly uploaded file so later `check` calls see it
+//!         without waiting for the next `build`. Without an entity, the
+//!         owner is looked up in the same source tables `build` reads.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use cr_infra::photo_hash::{self, PhotoHashIndex, PhotoSource};
+use sqlx::PgPool;
+
+/// Parallel R2 fetches during `build`. The Worker happily serves more,
+/// but decoding is CPU-bound and the prod box has four cores.
+const FETCH_CONCURRENCY: usize = 8;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    dotenvy::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set in .env")?;
+
+    let pool = PgPool::connect(&database_url)
+        .await
+        .context("Failed to connect to database")?;
+    let index = PhotoHashIndex::new(pool);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let max_distance = flag_value(&args, "--max-distance")
+        .map(|v| v.parse::<u32>())
+        .transpose()
+        .context("--max-distance must be a number")?
+        .unwrap_or(photo_hash::DEFAULT_MAX_DISTANCE);
+    let positional: Vec<&str> = positional_args(&args);
+
+    match positional.first().copied() {
+        Some("build") => build(&index, args.iter().any(|a| a == "--all")).await,
+        Some("report") => report(&index, max_distance).await,
+        Some("check") => {
+            let file = positional.get(1).context("check needs a FILE argument")?;
+            let key = positional.get(2).copied();
+            let found = check(&index, file, key, max_distance).await?;
+            if found {
+                std::process::exit(2);
+            }
+            Ok(())
+        }
+        Some("add") => {
+            let (file, key, entity) = match positional[..] {
+                [_, file, key] => (file, key, None),
+                [_, file, key, entity_type, entity_ref] => {
+                    (file, key, Some((entity_type, entity_ref)))
+                }
+                _ => bail!("usage: photo-hash-index add FILE R2_KEY [ENTITY_TYPE ENTITY_REF]"),
+            };
+            add(&index, file, key, entity).await
+        }
+        _ => {
+            bail!("usage: photo-hash-index <build [--all] | report | check FILE [R2_KEY] | add …>")
+        }
+    }
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut skip_next = false;
+    for a in args {
+        if skip_next {
+            skip_next = false;
+        } else if a == "--max-distance" {
+            skip_next = true;
+        } else if !a.starts_with("--") {
+            out.push(a.as_str());
+        }
+    }
+    out
+}
+
+async fn build(index: &PhotoHashIndex, all: bool) -> Result<()> {
+    let sources = index.sources(!all).await.context("Failed to list photos")?;
+    tracing::info!("Hashing {} images", sources.len());
+
+    let base = std::env::var("IMAGE_BASE_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "https://ceskarepublika.wiki".to_string());
+    let base = Arc::new(base);
+    let http = reqwest::Client::new();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(FETCH_CONCURRENCY));
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for source in sources {
+        let permit = semaphore.clone().acquire_owned().await?;
+        let (http, base, index) = (http.clone(), base.clone(), index.clone());
+        tasks.spawn(async move {
+            let _permit = permit;
+            let result = hash_remote(&http, &base, &source.r2_key).await;
+            match result {
+                Ok((hash, w, h)) => index
+                    .upsert(&source, hash, w, h)
+                    .await
+                    .map(|_| true)
+                    .map_err(|e| format!("{}: {e}", source.r2_key)),
+                Err(e) => Err(format!("{}: {e}", source.r2_key)),
+            }
+        });
+    }
+
+    let (mut ok, mut failed) = (0usize, 0usize);
+    while let Some(joined) = tasks.join_next().await {
+        match joined? {
+            Ok(_) => ok += 1,
+            Err(e) => {
+                // Missing covers are expected (not every catalog row has
+                // one yet) — keep this at debug so the log stays readable.
+                tracing::debug!("skip {e}");
+                failed += 1;
+            }
+        }
+    }
+    tracing::info!("Hashed {ok} images, skipped {failed}");
+    Ok(())
+}
+
+async fn hash_remote(
+    http: &reqwest::Client,
+    base: &str,
+    key: &str,
+) -> Result<(u64, u32, u32), photo_hash::PhotoHashError> {
+    let url = format!("{}/img/{key}", base.trim_end_matches('/'));
+    let resp = http
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| photo_hash::PhotoHashError::Fetch(e.to_string()))?;
+    if !resp.status().is_success() {
+        return Err(photo_hash::PhotoHashError::Fetch(format!(
+            "HTTP {}",
+            resp.status()
+        )));
+    }
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| photo_hash::PhotoHashError::Fetch(e.to_string()))?;
+    tokio::task::spawn_blocking(move || photo_hash::dhash_bytes(&bytes))
+        .await
+        .map_err(|e| photo_hash::PhotoHashError::Fetch(e.to_string()))?
+}
+
+async fn report(index: &PhotoHashIndex, max_distance: u32) -> Result<()> {
+    let rows = index.load_all().await.context("Failed to load index")?;
+    let hashes: Vec<u64> = rows.iter().map(|r| r.hash()).collect();
+    let clusters = photo_hash::find_clusters(&hashes, max_distance);
+
+    let cross_entity = clusters
+        .iter()
+        .filter(|c| {
+            let first = &rows[c[0]];
+            c.iter().any(|&i| {
+                rows[i].entity_type != first.entity_type || rows[i].entity_ref != first.entity_ref
+            })
+        })
+        .count();
+    println!(
+        "{} indexed images, {} duplicate clusters ({} spanning several entities)",
+        rows.len(),
+        clusters.len(),
+        cross_entity
+    );
+    for cluster in clusters {
+        println!();
+        for i in cluster {
+            let r = &rows[i];
+            println!(
+                "  {:<10} {:<8} {}x{}  {}",
+                r.entity_type, r.entity_ref, r.width, r.height, r.r2_key
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn check(
+    index: &PhotoHashIndex,
+    file: &str,
+    key: Option<&str>,
+    max_distance: u32,
+) -> Result<bool> {
+    let bytes = tokio::fs::read(file)
+        .await
+        .with_context(|| format!("Failed to read {file}"))?;
+    let (hash, _, _) = photo_hash::dhash_bytes(&bytes)?;
+    let matches = index.find_near(hash, max_distance, key).await?;
+    for m in &matches {
+        println!(
+            "{file}\t{}\t{}\t{}\t{}",
+            m.r2_key, m.entity_type, m.entity_ref, m.distance
+        );
+    }
+    Ok(!matches.is_empty())
+}
+
+async fn add(
+    index: &PhotoHashIndex,
+    file: &str,
+    key: &str,
+    entity: Option<(&str, &str)>,
+) -> Result<()> {
+    let source = match entity {
+        Some((entity_type, entity_ref)) => PhotoSource {
+            r2_key: key.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_ref: entity_ref.to_string(),
+        },
+        None => index
+            .source_for_key(key)
+            .await?
+            .with_context(|| format!("{key} is not referenced by any photo table"))?,
+    };
+    let bytes = tokio::fs::read(file)
+        .await
+        .with_context(|| format!("Failed to read {file}"))?;
+    let (hash, w, h) = photo_hash::dhash_bytes(&bytes)?;
+    index.upsert(&source, hash, w, h).await?;
+    tracing::info!("Indexed {key} ({hash:016x})");
+    Ok(())
+}
@@ -0,0 +1,443 @@
+//! Territorial boundary polygons and reverse geocoding.
+//!
+//! Reads the simplified ArcČR GeoJSON files from `GEOJSON_DATA_DIR`
+//! (`kraje_simple`, `okresy_simple`, `orp_simple`, `obce_simple`; CRS84
+//! lon/lat) into plain ring lists. [`ReverseGeocoder`] puts every layer
+//! behind a packed R-tree and answers "which municipality / ORP /
+//! district / region contains this point" with an even-odd
+//! point-in-polygon test.
+//!
+//! Used by the `/api/reverse` endpoint, the vector tiles in cr-web and
+//! the `assign-municipalities` binary that validates landmark and pool
+//! coordinates.
+
+mod rtree;
+
+use cr_domain::Coordinates;
+
+pub use rtree::RTree;
+
+/// `[x, y]` — lon/lat as loaded; callers may project in place.
+pub type Point = [f64; 2];
+
+/// Open ring (the closing vertex that GeoJSON repeats is dropped).
+pub type Ring = Vec<Point>;
+
+/// Exterior ring followed by its holes.
+pub type Polygon = Vec<Ring>;
+
+/// Axis-aligned bounding box `[min_x, min_y, max_x, max_y]`.
+pub type BBox = [f64; 4];
+
+/// A boundary feature: its identifying properties and geometry.
+#[derive(Debug, Clone)]
+pub struct Feature {
+    /// Official code, matching the `*_code` columns of the territorial
+    /// tables (`region_code`, `district_code`, `orp_code`,
+    /// `municipality_code`).
+    pub code: String,
+    pub name: String,
+    /// Code of the enclosing unit when the source file carries it
+    /// (region code for districts and ORPs, ORP code for municipalities).
+    pub parent_code: Option<String>,
+    pub polygons: Vec<Polygon>,
+    pub bbox: BBox,
+}
+
+/// Which properties identify a feature in one boundary file.
+#[derive(Debug, Clone, Copy)]
+pub struct LayerSource {
+    pub file: &'static str,
+    pub code_key: &'static str,
+    pub name_key: &'static str,
+    pub parent_key: Option<&'static str>,
+}
+
+pub const REGIONS: LayerSource = LayerSource {
+    file: "kraje_simple.geojson",
+    code_key: "kod_kraj",
+    name_key: "naz_kraj",
+    parent_key: None,
+};
+
+/// Districts are keyed by their LAU code (`CZ0100`), which is what the
+/// ČSÚ import stores in `districts.district_code`.
+pub const DISTRICTS: LayerSource = LayerSource {
+    file: "okresy_simple.geojson",
+    code_key: "lau1_p",
+    name_key: "naz_okres",
+    parent_key: Some("kod_kraj"),
+};
+
+pub const ORP: LayerSource = LayerSource {
+    file: "orp_simple.geojson",
+    code_key: "kod_orp_p",
+    name_key: "naz_orp_p",
+    parent_key: Some("kod_kraj"),
+};
+
+pub const MUNICIPALITIES: LayerSource = LayerSource {
+    file: "obce_simple.geojson",
+    code_key: "kod_obec_p",
+    name_key: "naz_obec_p",
+    parent_key: Some("kod_orp_p"),
+};
+
+impl LayerSource {
+    /// Load this layer from `data_dir`. A missing file yields an empty
+    /// list (with a warning) so a dev checkout without
+    /// `obce_simple.geojson` still starts.
+    pub fn load(&self, data_dir: &str) -> anyhow::Result<Vec<Feature>> {
+        let path = format!("{data_dir}/{}", self.file);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            tracing::warn!("Boundary GeoJSON not found at {path}");
+            return Ok(Vec::new());
+        };
+        let data: serde_json::Value = serde_json::from_str(&content)?;
+        Ok(self.parse(&data))
+    }
+
+    /// Features without a code or with an unsupported geometry type are
+    /// skipped.
+    pub fn parse(&self, collection: &serde_json::Value) -> Vec<Feature> {
+        let mut out = Vec::new();
+        for feat in collection["features"].as_array().into_iter().flatten() {
+            let props = &feat["properties"];
+            let Some(code) = props[self.code_key].as_str() else {
+                continue;
+            };
+            let polygons = parse_geometry(&feat["geometry"]);
+            if polygons.is_empty() {
+                continue;
+            }
+            out.push(Feature {
+                code: code.to_string(),
+                name: props[self.name_key]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                parent_code: self
+                    .parent_key
+                    .and_then(|k| props[k].as_str())
+                    .map(str::to_string),
+                bbox: bbox_of(&polygons),
+                polygons,
+            });
+        }
+        out
+    }
+}
+
+/// Convert a GeoJSON `Polygon` / `MultiPolygon` into our ring lists.
+pub fn parse_geometry(geometry: &serde_json::Value) -> Vec<Polygon> {
+    let coords = &geometry["coordinates"];
+    match geometry["type"].as_str() {
+        Some("Polygon") => parse_polygon(coords).into_iter().collect(),
+        Some("MultiPolygon") => coords
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(parse_polygon)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_polygon(value: &serde_json::Value) -> Option<Polygon> {
+    let rings: Vec<Ring> = value
+        .as_array()?
+        .iter()
+        .filter_map(|ring| {
+            let mut pts: Ring = ring
+                .as_array()?
+                .iter()
+                .filter_map(|p| Some([p[0].as_f64()?, p[1].as_f64()?]))
+                .collect();
+            if pts.len() > 1 && pts.first() == pts.last() {
+                pts.pop();
+            }
+            (pts.len() >= 3).then_some(pts)
+        })
+        .collect();
+    (!rings.is_empty()).then_some(rings)
+}
+
+pub fn bbox_of(polygons: &[Polygon]) -> BBox {
+    let mut b = [f64::MAX, f64::MAX, f64::MIN, f64::MIN];
+    for p in polygons.iter().flatten().flatten() {
+        b[0] = b[0].min(p[0]);
+        b[1] = b[1].min(p[1]);
+        b[2] = b[2].max(p[0]);
+        b[3] = b[3].max(p[1]);
+    }
+    b
+}
+
+pub fn bbox_intersects(a: &BBox, b: &BBox) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
+
+/// Even-odd test over every ring of the polygon, so a point inside a hole
+/// counts as outside.
+pub fn polygon_contains(polygon: &[Ring], p: Point) -> bool {
+    let mut inside = false;
+    for ring in polygon {
+        let n = ring.len();
+        let mut j = n - 1;
+        for i in 0..n {
+            let (a, b) = (ring[i], ring[j]);
+            if (a[1] > p[1]) != (b[1] > p[1])
+                && p[0] < (b[0] - a[0]) * (p[1] - a[1]) / (b[1] - a[1]) + a[0]
+            {
+                inside = !inside;
+            }
+            j = i;
+        }
+    }
+    inside
+}
+
+pub fn feature_contains(feature: &Feature, p: Point) -> bool {
+    p[0] >= feature.bbox[0]
+        && p[0] <= feature.bbox[2]
+        && p[1] >= feature.bbox[1]
+        && p[1] <= feature.bbox[3]
+        && feature
+            .polygons
+            .iter()
+            .any(|poly| polygon_contains(poly, p))
+}
+
+/// Squared planar distance from `p` to the nearest boundary edge.
+fn boundary_dist2(feature: &Feature, p: Point) -> f64 {
+    let mut best = f64::MAX;
+    for ring in feature.polygons.iter().flatten() {
+        let n = ring.len();
+        for i in 0..n {
+            best = best.min(seg_dist2(p, ring[i], ring[(i + 1) % n]));
+        }
+    }
+    best
+}
+
+fn seg_dist2(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len2).clamp(0.0, 1.0)
+    };
+    let (qx, qy) = (a[0] + t * dx - p[0], a[1] + t * dy - p[1]);
+    qx * qx + qy * qy
+}
+
+/// How far (in degrees) outside every polygon a point may lie and still
+/// be snapped to the nearest one. The `_simple` files leave slivers of a
+/// few hundred metres between neighbours and along the state border;
+/// 0.003° is ~200–330 m at Czech latitudes.
+const SNAP_DEGREES: f64 = 0.003;
+
+/// One boundary layer behind an R-tree.
+pub struct BoundaryLayer {
+    features: Vec<Feature>,
+    tree: RTree,
+}
+
+impl BoundaryLayer {
+    pub fn new(features: Vec<Feature>) -> Self {
+        let tree = RTree::new(features.iter().map(|f| f.bbox).collect());
+        Self { features, tree }
+    }
+
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// The feature containing `p`, or — when `p` falls into a
+    /// simplification gap — the one whose boundary is nearest within
+    /// [`SNAP_DEGREES`].
+    pub fn locate(&self, p: Point) -> Option<&Feature> {
+        let point_box = [p[0], p[1], p[0], p[1]];
+        if let Some(i) = self
+            .tree
+            .search(&point_box)
+            .into_iter()
+            .find(|&i| feature_contains(&self.features[i], p))
+        {
+            return Some(&self.features[i]);
+        }
+        let snap_box = [
+            p[0] - SNAP_DEGREES,
+            p[1] - SNAP_DEGREES,
+            p[0] + SNAP_DEGREES,
+            p[1] + SNAP_DEGREES,
+        ];
+        self.tree
+            .search(&snap_box)
+            .into_iter()
+            .map(|i| (i, boundary_dist2(&self.features[i], p)))
+            .filter(|(_, d)| *d <= SNAP_DEGREES * SNAP_DEGREES)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| &self.features[i])
+    }
+}
+
+/// A territorial unit a point resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Unit {
+    pub code: String,
+    pub name: String,
+}
+
+impl From<&Feature> for Unit {
+    fn from(f: &Feature) -> Self {
+        Self {
+            code: f.code.clone(),
+            name: f.name.clone(),
+        }
+    }
+}
+
+/// Every unit containing a point. Fields are `None` outside the country
+/// or when the corresponding boundary file isn't deployed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct Location {
+    pub municipality: Option<Unit>,
+    pub orp: Option<Unit>,
+    pub district: Option<Unit>,
+    pub region: Option<Unit>,
+}
+
+/// Point → municipality / ORP / district / region lookup over the four
+/// boundary layers.
+pub struct ReverseGeocoder {
+    pub municipalities: BoundaryLayer,
+    pub orp: BoundaryLayer,
+    pub districts: BoundaryLayer,
+    pub regions: BoundaryLayer,
+}
+
+impl ReverseGeocoder {
+    pub fn load(data_dir: &str) -> anyhow::Result<Self> {
+        let geocoder = Self {
+            municipalities: BoundaryLayer::new(MUNICIPALITIES.load(data_dir)?),
+            orp: BoundaryLayer::new(ORP.load(data_dir)?),
+            districts: BoundaryLayer::new(DISTRICTS.load(data_dir)?),
+            regions: BoundaryLayer::new(REGIONS.load(data_dir)?),
+        };
+        tracing::info!(
+            "Reverse geocoder: {} municipalities, {} ORP, {} districts, {} regions",
+            geocoder.municipalities.len(),
+            geocoder.orp.len(),
+            geocoder.districts.len(),
+            geocoder.regions.len()
+        );
+        Ok(geocoder)
+    }
+
+    pub fn lookup(&self, at: Coordinates) -> Location {
+        let p = [at.longitude(), at.latitude()];
+        Location {
+            municipality: self.municipalities.locate(p).map(Unit::from),
+            orp: self.orp.locate(p).map(Unit::from),
+            district: self.districts.locate(p).map(Unit::from),
+            region: self.regions.locate(p).map(Unit::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square_feature(code: &str, lo: f64, hi: f64) -> Feature {
+        let polygons = vec![vec![vec![[lo, lo], [hi, lo], [hi, hi], [lo, hi]]]];
+        Feature {
+            code: code.into(),
+            name: code.into(),
+            parent_code: None,
+            bbox: bbox_of(&polygons),
+            polygons,
+        }
+    }
+
+    #[test]
+    fn parses_polygon_and_multipolygon() {
+        let collection = serde_json::json!({
+            "features": [
+                {
+                    "properties": {"kod_kraj": "3018", "naz_kraj": "Praha"},
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+                    }
+                },
+                {
+                    "properties": {"kod_kraj": "3026", "naz_kraj": "Středočeský"},
+                    "geometry": {
+                        "type": "MultiPolygon",
+                        "coordinates": [
+                            [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]],
+                            [[[2.0, 2.0], [3.0, 2.0], [3.0, 3.0], [2.0, 2.0]]]
+                        ]
+                    }
+                },
+                {"properties": {"naz_kraj": "no code"}, "geometry": null}
+            ]
+        });
+        let features = REGIONS.parse(&collection);
+        assert_eq!(features.len(), 2);
+        assert_eq!(
+            features[0].polygons[0][0].len(),
+            3,
+            "closing vertex dropped"
+        );
+        assert_eq!(features[1].polygons.len(), 2);
+        assert_eq!(features[1].bbox, [0.0, 0.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn point_in_polygon_respects_holes() {
+        let polygon = vec![
+            vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]],
+            vec![[4.0, 4.0], [6.0, 4.0], [6.0, 6.0], [4.0, 6.0]],
+        ];
+        assert!(polygon_contains(&polygon, [2.0, 2.0]));
+        assert!(!polygon_contains(&polygon, [5.0, 5.0]));
+        assert!(!polygon_contains(&polygon, [11.0, 5.0]));
+    }
+
+    #[test]
+    fn locate_picks_containing_feature_and_snaps_across_gaps() {
+        let layer = BoundaryLayer::new(vec![
+            square_feature("a", 0.0, 1.0),
+            square_feature("b", 1.002, 2.0),
+        ]);
+        assert_eq!(layer.locate([0.5, 0.5]).unwrap().code, "a");
+        assert_eq!(layer.locate([1.5, 0.5]).map(|f| f.code.as_str()), None);
+        assert_eq!(layer.locate([1.5, 1.5]).unwrap().code, "b");
+        // In the 0.002° sliver between the two, nearer to `b`.
+        assert_eq!(layer.locate([1.0015, 1.5]).unwrap().code, "b");
+        // Far outside everything.
+        assert!(layer.locate([5.0, 5.0]).is_none());
+    }
+
+    #[test]
+    fn real_boundaries_resolve_prague_and_brno() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/geojson");
+        let geocoder = ReverseGeocoder::load(dir).unwrap();
+        let prague = geocoder.lookup(Coordinates::new(50.0875, 14.4213).unwrap());
+        assert_eq!(prague.region.unwrap().code, "3018");
+        assert_eq!(prague.district.unwrap().code, "CZ0100");
+        assert_eq!(prague.orp.unwrap().name, "Praha");
+        let brno = geocoder.lookup(Coordinates::new(49.1951, 16.6068).unwrap());
+        assert_eq!(brno.region.unwrap().name, "Jihomoravský kraj");
+        let vienna = geocoder.lookup(Coordinates::new(48.2082, 16.3738).unwrap());
+        assert_eq!(vienna, Location::default());
+    }
+}
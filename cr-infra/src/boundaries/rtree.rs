@@ -0,0 +1,137 @@
+//! Static R-tree over bounding boxes, bulk-loaded with Sort-Tile-Recursive
+//! packing. The boundary layers never change after startup, so there is
+//! no insert/delete — just build once and query.
+
+use super::{BBox, bbox_intersects};
+
+/// Children per node. 16 keeps the tree three levels deep for the ~6 000
+/// municipalities while each node still fits in a couple of cache lines.
+const NODE_CAPACITY: usize = 16;
+
+struct Node {
+    bbox: BBox,
+    /// Indices into the level below (or into the item list for leaves).
+    children: Vec<usize>,
+}
+
+pub struct RTree {
+    items: Vec<BBox>,
+    /// `levels[0]` are leaves; the last level is the root level.
+    levels: Vec<Vec<Node>>,
+}
+
+impl RTree {
+    pub fn new(items: Vec<BBox>) -> Self {
+        let mut levels = Vec::new();
+        let mut current: Vec<(usize, BBox)> = items.iter().copied().enumerate().collect();
+        while !current.is_empty() {
+            let nodes = pack(current);
+            let done = nodes.len() <= 1;
+            current = nodes.iter().map(|n| n.bbox).enumerate().collect();
+            levels.push(nodes);
+            if done {
+                break;
+            }
+        }
+        Self { items, levels }
+    }
+
+    /// Indices of all items whose bbox intersects `query`.
+    pub fn search(&self, query: &BBox) -> Vec<usize> {
+        let mut out = Vec::new();
+        let Some(root_level) = self.levels.len().checked_sub(1) else {
+            return out;
+        };
+        let mut stack: Vec<(usize, usize)> = (0..self.levels[root_level].len())
+            .map(|i| (root_level, i))
+            .collect();
+        while let Some((level, idx)) = stack.pop() {
+            let node = &self.levels[level][idx];
+            if !bbox_intersects(&node.bbox, query) {
+                continue;
+            }
+            if level == 0 {
+                out.extend(
+                    node.children
+                        .iter()
+                        .copied()
+                        .filter(|&i| bbox_intersects(&self.items[i], query)),
+                );
+            } else {
+                stack.extend(node.children.iter().map(|&c| (level - 1, c)));
+            }
+        }
+        out
+    }
+}
+
+/// Group entries into nodes: sort by x centre, cut into vertical slices,
+/// sort each slice by y centre and chunk it.
+fn pack(mut entries: Vec<(usize, BBox)>) -> Vec<Node> {
+    let node_count = entries.len().div_ceil(NODE_CAPACITY);
+    let slices = (node_count as f64).sqrt().ceil() as usize;
+    let slice_len = slices * NODE_CAPACITY;
+    let centre = |b: &BBox, axis: usize| b[axis] + b[axis + 2];
+
+    entries.sort_by(|a, b| centre(&a.1, 0).total_cmp(&centre(&b.1, 0)));
+    let mut nodes = Vec::with_capacity(node_count);
+    for slice in entries.chunks_mut(slice_len.max(1)) {
+        slice.sort_by(|a, b| centre(&a.1, 1).total_cmp(&centre(&b.1, 1)));
+        for chunk in slice.chunks(NODE_CAPACITY) {
+            let mut bbox = [f64::MAX, f64::MAX, f64::MIN, f64::MIN];
+            for (_, b) in chunk {
+                bbox = [
+                    bbox[0].min(b[0]),
+                    bbox[1].min(b[1]),
+                    bbox[2].max(b[2]),
+                    bbox[3].max(b[3]),
+                ];
+            }
+            nodes.push(Node {
+                bbox,
+                children: chunk.iter().map(|(i, _)| *i).collect(),
+            });
+        }
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_matches_brute_force() {
+        // 40×40 grid of unit boxes → three levels with capacity 16.
+        let items: Vec<BBox> = (0..1600)
+            .map(|i| {
+                let (x, y) = ((i % 40) as f64, (i / 40) as f64);
+                [x, y, x + 0.9, y + 0.9]
+            })
+            .collect();
+        let tree = RTree::new(items.clone());
+        assert!(tree.levels.len() >= 3);
+        for query in [
+            [5.5, 5.5, 5.5, 5.5],
+            [0.0, 0.0, 3.0, 2.0],
+            [38.95, 0.0, 39.5, 39.5],
+            [100.0, 100.0, 101.0, 101.0],
+        ] {
+            let mut got = tree.search(&query);
+            got.sort_unstable();
+            let want: Vec<usize> = (0..items.len())
+                .filter(|&i| bbox_intersects(&items[i], &query))
+                .collect();
+            assert_eq!(got, want, "query {query:?}");
+        }
+    }
+
+    #[test]
+    fn empty_tree_finds_nothing() {
+        assert!(
+            RTree::new(Vec::new())
+                .search(&[0.0, 0.0, 1.0, 1.0])
+                .is_empty()
+        );
+    }
+}
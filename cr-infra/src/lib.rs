@@ -7,11 +7,15 @@
 //!
 //! ## Modules
 //!
+//! - `boundaries` - Territorial boundary polygons and reverse geocoding
+//! - `photo_hash` - Perceptual-hash index for duplicate photo detection
 //! - `repositories` - SQLx-based repository implementations
 //! - `db` (planned) - SQLx queries, migrations
 //! - `import` (planned) - CSV importer for ČSÚ territorial data
 //! - `github` (planned) - GitHub integration (Octocrab)
 
+pub mod boundaries;
+pub mod photo_hash;
 pub mod r2;
 pub mod repositories;
 pub mod streamtape;
@@ -0,0 +1,457 @@
+//! Perceptual-hash index over every photo and cover stored on R2.
+//!
+//! Many landmarks and municipalities share the same Wikimedia photo, and
+//! the NPÚ importer has uploaded re-encoded crops of one file under
+//! several indexes. Byte-level dedup (SHA-256) misses all of those, so we
+//! keep a 64-bit **difference hash** (dHash) per R2 object in the
+//! `photo_hashes` table (migration 079) and treat two images whose hashes
+//! differ in at most [`DEFAULT_MAX_DISTANCE`] bits as the same picture.
+//!
+//! dHash is deliberately simple: downscale to 9×8 grayscale and record
+//! whether each pixel is brighter than its right neighbour. It survives
+//! re-encoding (JPEG → WebP), resizing and mild colour grading, which is
+//! exactly the noise the Wikimedia / NPÚ pipelines introduce. It does
+//! *not* survive heavy crops or mirroring — those stay separate entries.
+//!
+//! Consumers:
+//! - `photo-hash-index` binary — builds the index, prints the duplicate
+//!   report and lets the import shell scripts `check` a file before
+//!   uploading it.
+//! - `/admin/photos/duplicates` — cluster report across entities.
+//! - `fetch_photos` / `fetch_municipality_gallery` in cr-web — optional
+//!   suppression of near-duplicates inside one gallery
+//!   (`GALLERY_SUPPRESS_DUPLICATES=1`).
+
+use std::collections::HashMap;
+
+use image::DynamicImage;
+use image::imageops::FilterType;
+
+/// Hamming distance at or below which two hashes are reported as
+/// near-duplicates. Six bits out of 64 keeps re-encodes and resizes
+/// together while leaving visually similar but different photos (two
+/// shots of the same church from different angles) apart.
+pub const DEFAULT_MAX_DISTANCE: u32 = 6;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PhotoHashError {
+    #[error("image decode failed: {0}")]
+    Decode(#[from] image::ImageError),
+    #[error("database query failed: {0}")]
+    Db(#[from] sqlx::Error),
+    #[error("fetch failed: {0}")]
+    Fetch(String),
+}
+
+/// Compute the 64-bit difference hash of an already decoded image.
+pub fn dhash_image(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    hash
+}
+
+/// Decode `bytes` (any format the `image` crate understands — WebP, JPEG,
+/// PNG) and return `(hash, width, height)`.
+pub fn dhash_bytes(bytes: &[u8]) -> Result<(u64, u32, u32), PhotoHashError> {
+    let img = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| PhotoHashError::Fetch(format!("format sniffing failed: {e}")))?
+        .decode()?;
+    Ok((dhash_image(&img), img.width(), img.height()))
+}
+
+/// Number of differing bits between two hashes.
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Postgres has no unsigned 64-bit type; hashes are stored bit-cast into
+/// a `BIGINT`.
+pub fn to_db(hash: u64) -> i64 {
+    hash as i64
+}
+
+/// Inverse of [`to_db`].
+pub fn from_db(value: i64) -> u64 {
+    value as u64
+}
+
+/// Group `hashes` into clusters of near-duplicates (transitively: if A~B
+/// and B~C, all three land in one cluster). Returns only clusters with at
+/// least two members, each as a sorted list of indexes into `hashes`.
+///
+/// Uses multi-index hashing to avoid the O(n²) pairwise scan over ~50 k
+/// photos: split the 64 bits into `max_distance + 1` chunks — by the
+/// pigeonhole principle two hashes within `max_distance` bits share at
+/// least one chunk verbatim, so only hashes colliding on some chunk are
+/// compared.
+pub fn find_clusters(hashes: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    let chunks = (max_distance + 1).min(64);
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut offset = 0u32;
+    for c in 0..chunks {
+        // Spread the remainder over the first chunks so widths differ by
+        // at most one bit and always add up to 64.
+        let width = 64 / chunks + u32::from(c < 64 % chunks);
+        let mask = if width == 64 {
+            u64::MAX
+        } else {
+            (1u64 << width) - 1
+        };
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, h) in hashes.iter().enumerate() {
+            buckets.entry((h >> offset) & mask).or_default().push(i);
+        }
+        for members in buckets.values().filter(|m| m.len() > 1) {
+            for (pos, &a) in members.iter().enumerate() {
+                for &b in &members[pos + 1..] {
+                    if hamming(hashes[a], hashes[b]) <= max_distance {
+                        let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                        if ra != rb {
+                            parent[ra.max(rb)] = ra.min(rb);
+                        }
+                    }
+                }
+            }
+        }
+        offset += width;
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..hashes.len() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(i);
+    }
+    let mut clusters: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    for c in &mut clusters {
+        c.sort_unstable();
+    }
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    clusters
+}
+
+/// Decide which gallery entries to keep when near-duplicates should be
+/// suppressed. Walks the gallery in display order and keeps an entry
+/// unless it is within `max_distance` of an entry already kept, so the
+/// first occurrence (usually the primary photo) always wins. Entries
+/// without a known hash (`None` — not indexed yet) are always kept.
+pub fn keep_mask(hashes: &[Option<u64>], max_distance: u32) -> Vec<bool> {
+    let mut kept: Vec<u64> = Vec::with_capacity(hashes.len());
+    hashes
+        .iter()
+        .map(|h| match h {
+            Some(h) if kept.iter().any(|k| hamming(*k, *h) <= max_distance) => false,
+            Some(h) => {
+                kept.push(*h);
+                true
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// One indexed R2 object.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PhotoHashRow {
+    pub r2_key: String,
+    pub entity_type: String,
+    pub entity_ref: String,
+    pub dhash: i64,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl PhotoHashRow {
+    pub fn hash(&self) -> u64 {
+        from_db(self.dhash)
+    }
+}
+
+/// An R2 object that should be present in the index, as enumerated from
+/// the source tables by [`PhotoHashIndex::sources`].
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PhotoSource {
+    pub r2_key: String,
+    pub entity_type: String,
+    pub entity_ref: String,
+}
+
+/// A stored hash within `distance` bits of a probed one.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct NearMatch {
+    pub r2_key: String,
+    pub entity_type: String,
+    pub entity_ref: String,
+    pub distance: i32,
+}
+
+/// Every photo/cover key the site serves, unioned across the source
+/// tables. Covers are id-keyed on R2 (see `cover_proxy::new_r2_key` in
+/// cr-web), so they are synthesised from the catalog ids; keys that turn
+/// out not to exist on R2 are simply skipped by the indexer.
+const SOURCES_SQL: &str = "\
+    SELECT pm.r2_key, pm.entity_type, pm.entity_id::text AS entity_ref \
+      FROM photo_metadata pm \
+    UNION ALL \
+    SELECT lp.r2_key, 'landmark', l.id::text \
+      FROM landmark_photos lp JOIN landmarks l ON l.npu_catalog_id = lp.npu_catalog_id \
+    UNION ALL \
+    SELECT mp.r2_key, 'municipality', mp.municipality_code \
+      FROM municipality_photos mp \
+    UNION ALL \
+    SELECT 'films/' || id || '/cover.webp', 'film', id::text FROM films \
+    UNION ALL \
+    SELECT 'series/' || id || '/cover.webp', 'series', id::text FROM series \
+    UNION ALL \
+    SELECT 'tv-shows/' || id || '/cover.webp', 'tv_show', id::text FROM tv_shows";
+
+/// Thin `PgPool` wrapper around the `photo_hashes` table.
+#[derive(Clone)]
+pub struct PhotoHashIndex {
+    pool: sqlx::PgPool,
+}
+
+impl PhotoHashIndex {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// All keys the index should cover. With `missing_only`, keys already
+    /// hashed are filtered out so an incremental run only fetches new
+    /// uploads.
+    pub async fn sources(&self, missing_only: bool) -> Result<Vec<PhotoSource>, sqlx::Error> {
+        let sql = if missing_only {
+            format!(
+                "SELECT DISTINCT ON (s.r2_key) s.r2_key, s.entity_type, s.entity_ref \
+                 FROM ({SOURCES_SQL}) s \
+                 WHERE NOT EXISTS (SELECT 1 FROM photo_hashes h WHERE h.r2_key = s.r2_key) \
+                 ORDER BY s.r2_key"
+            )
+        } else {
+            format!(
+                "SELECT DISTINCT ON (s.r2_key) s.r2_key, s.entity_type, s.entity_ref \
+                 FROM ({SOURCES_SQL}) s ORDER BY s.r2_key"
+            )
+        };
+        sqlx::query_as::<_, PhotoSource>(&sql)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Entity owning `r2_key` according to the source tables, `None` when
+    /// no table references the key (yet).
+    pub async fn source_for_key(&self, r2_key: &str) -> Result<Option<PhotoSource>, sqlx::Error> {
+        sqlx::query_as::<_, PhotoSource>(&format!(
+            "SELECT s.r2_key, s.entity_type, s.entity_ref FROM ({SOURCES_SQL}) s \
+             WHERE s.r2_key = $1 LIMIT 1"
+        ))
+        .bind(r2_key)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Insert or refresh the hash for one R2 object.
+    pub async fn upsert(
+        &self,
+        source: &PhotoSource,
+        hash: u64,
+        width: u32,
+        height: u32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO photo_hashes (r2_key, entity_type, entity_ref, dhash, width, height) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (r2_key) DO UPDATE SET \
+               entity_type = EXCLUDED.entity_type, entity_ref = EXCLUDED.entity_ref, \
+               dhash = EXCLUDED.dhash, width = EXCLUDED.width, height = EXCLUDED.height, \
+               hashed_at = NOW()",
+        )
+        .bind(&source.r2_key)
+        .bind(&source.entity_type)
+        .bind(&source.entity_ref)
+        .bind(to_db(hash))
+        .bind(width as i32)
+        .bind(height as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Every indexed row — input for [`find_clusters`].
+    pub async fn load_all(&self) -> Result<Vec<PhotoHashRow>, sqlx::Error> {
+        sqlx::query_as::<_, PhotoHashRow>(
+            "SELECT r2_key, entity_type, entity_ref, dhash, width, height \
+             FROM photo_hashes ORDER BY r2_key",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Indexed objects within `max_distance` bits of `hash`, closest
+    /// first, leaving out `exclude_key` — the object about to be
+    /// (re)written, which would otherwise match its own indexed hash. A
+    /// sequential scan with `bit_count` is fine at our size (~50 k rows)
+    /// and keeps the table free of exotic index types.
+    pub async fn find_near(
+        &self,
+        hash: u64,
+        max_distance: u32,
+        exclude_key: Option<&str>,
+    ) -> Result<Vec<NearMatch>, sqlx::Error> {
+        sqlx::query_as::<_, NearMatch>(
+            "SELECT r2_key, entity_type, entity_ref, distance FROM ( \
+               SELECT r2_key, entity_type, entity_ref, \
+                      bit_count((dhash # $1)::bit(64))::int AS distance \
+               FROM photo_hashes \
+               WHERE r2_key IS DISTINCT FROM $3) h \
+             WHERE distance <= $2 ORDER BY distance, r2_key",
+        )
+        .bind(to_db(hash))
+        .bind(max_distance as i32)
+        .bind(exclude_key)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Hashes for the given keys; keys that are not indexed are absent
+    /// from the map.
+    pub async fn hashes_for_keys(
+        &self,
+        keys: &[String],
+    ) -> Result<HashMap<String, u64>, sqlx::Error> {
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT r2_key, dhash FROM photo_hashes WHERE r2_key = ANY($1)",
+        )
+        .bind(keys)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(k, h)| (k, from_db(h))).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    /// Horizontal gradient with a dark block — enough structure for the
+    /// hash to be non-trivial.
+    fn sample(width: u32, height: u32, block_at: u32) -> DynamicImage {
+        let mut img = GrayImage::new(width, height);
+        for (x, y, px) in img.enumerate_pixels_mut() {
+            let mut v = (x * 255 / width) as u8;
+            if x >= block_at && x < block_at + width / 4 && y < height / 2 {
+                v = 10;
+            }
+            *px = Luma([v]);
+        }
+        DynamicImage::ImageLuma8(img)
+    }
+
+    #[test]
+    fn hamming_counts_differing_bits() {
+        assert_eq!(hamming(0, 0), 0);
+        assert_eq!(hamming(0b1011, 0b0001), 2);
+        assert_eq!(hamming(u64::MAX, 0), 64);
+    }
+
+    #[test]
+    fn db_round_trip_preserves_high_bit() {
+        let h = 0xF000_0000_0000_0001u64;
+        assert!(to_db(h) < 0);
+        assert_eq!(from_db(to_db(h)), h);
+    }
+
+    #[test]
+    fn resized_copy_hashes_close_to_original() {
+        let original = sample(800, 600, 200);
+        let resized = original.resize_exact(400, 300, FilterType::Lanczos3);
+        let d = hamming(dhash_image(&original), dhash_image(&resized));
+        assert!(d <= DEFAULT_MAX_DISTANCE, "distance {d}");
+    }
+
+    #[test]
+    fn different_pictures_hash_far_apart() {
+        let a = sample(800, 600, 0);
+        let b = sample(800, 600, 600);
+        let d = hamming(dhash_image(&a), dhash_image(&b));
+        assert!(d > DEFAULT_MAX_DISTANCE, "distance {d}");
+    }
+
+    #[test]
+    fn dhash_bytes_decodes_encoded_image() {
+        let img = sample(120, 90, 30);
+        let mut buf = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+            .unwrap();
+        let (hash, w, h) = dhash_bytes(&buf).unwrap();
+        assert_eq!((w, h), (120, 90));
+        assert_eq!(hash, dhash_image(&img));
+    }
+
+    #[test]
+    fn clusters_are_transitive_and_skip_singletons() {
+        let a = 0u64;
+        let b = 0b111; // 3 bits from a
+        let c = 0b111_111; // 3 bits from b, 6 from a
+        let far = u64::MAX;
+        let clusters = find_clusters(&[far, a, b, c], 3);
+        assert_eq!(clusters, vec![vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn clusters_match_brute_force() {
+        // Pseudo-random hashes plus a few planted near-copies; the
+        // multi-index result must equal the naive pairwise scan.
+        let mut x = 0x9E37_79B9_7F4A_7C15u64;
+        let mut hashes = Vec::new();
+        for i in 0..200 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            hashes.push(x);
+            if i % 20 == 0 {
+                hashes.push(x ^ (1 << (i % 64)) ^ (1 << ((i + 31) % 64)));
+            }
+        }
+        let fast = find_clusters(&hashes, 4);
+
+        let mut naive: Vec<Vec<usize>> = Vec::new();
+        for i in 0..hashes.len() {
+            for j in i + 1..hashes.len() {
+                if hamming(hashes[i], hashes[j]) <= 4 {
+                    naive.push(vec![i, j]);
+                }
+            }
+        }
+        assert_eq!(fast.len(), naive.len());
+        for pair in &naive {
+            assert!(fast.contains(pair), "missing {pair:?}");
+        }
+    }
+
+    #[test]
+    fn keep_mask_keeps_first_occurrence_and_unknowns() {
+        let mask = keep_mask(&[Some(0), None, Some(1), Some(u64::MAX), None], 2);
+        assert_eq!(mask, vec![true, true, false, true, true]);
+    }
+}
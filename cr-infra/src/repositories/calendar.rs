@@ -0,0 +1,180 @@
+use cr_domain::Date;
+use cr_domain::catalog::CatalogKind;
+use cr_domain::repository::{CalendarEpisode, EpisodeCalendarRepository};
+
+use super::catalog_sql::{from_date, to_date};
+use super::episode::EPISODE_HAS_SOURCE_PREDICATE;
+use super::tv_show::TV_EPISODE_HAS_SOURCE_PREDICATE;
+
+/// One row per episode number of a show: the same episode imported from
+/// several sources (or as a TMDB stub next to a sourced row) collapses
+/// into one calendar entry, preferring a playable row for the link.
+/// `window` narrows the episodes (`e`) and shows (`s`).
+fn calendar_select(kind: CatalogKind, window: &str) -> String {
+    let (tag, show, table, fk, has_source) = match kind {
+        CatalogKind::TvShow => (
+            "tv",
+            "tv_shows",
+            "tv_episodes",
+            "tv_show_id",
+            TV_EPISODE_HAS_SOURCE_PREDICATE,
+        ),
+        _ => (
+            "series",
+            "series",
+            "episodes",
+            "series_id",
+            EPISODE_HAS_SOURCE_PREDICATE,
+        ),
+    };
+    format!(
+        "SELECT DISTINCT ON (s.id, e.season, e.episode) \
+                '{tag}' AS kind, s.id AS show_id, s.title AS show_title, \
+                s.slug AS show_slug, e.season, e.episode, e.episode_name, e.air_date, \
+                e.slug, {has_source} AS playable \
+           FROM {table} e \
+           JOIN {show} s ON s.id = e.{fk} \
+          WHERE {window} \
+          ORDER BY s.id, e.season, e.episode, playable DESC, (e.slug IS NOT NULL) DESC, e.id"
+    )
+}
+
+/// PostgreSQL implementation of [`EpisodeCalendarRepository`].
+pub struct PgEpisodeCalendarRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgEpisodeCalendarRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CalendarRow {
+    kind: String,
+    show_id: i32,
+    show_title: String,
+    show_slug: String,
+    season: i16,
+    episode: i16,
+    episode_name: Option<String>,
+    air_date: chrono::NaiveDate,
+    slug: Option<String>,
+    playable: bool,
+}
+
+impl CalendarRow {
+    fn into_episode(self) -> Option<CalendarEpisode> {
+        let (kind, section) = match self.kind.as_str() {
+            "tv" => (CatalogKind::TvShow, "tv-porady"),
+            _ => (CatalogKind::Series, "serialy-online"),
+        };
+        // Same fallback as the feeds: the legacy `1x5` URL redirects to
+        // the slugged one.
+        let path = self.playable.then(|| {
+            let segment = self
+                .slug
+                .unwrap_or_else(|| format!("{}x{}", self.season, self.episode));
+            format!("/{section}/{}/{segment}/", self.show_slug)
+        });
+        Some(CalendarEpisode {
+            kind,
+            show_id: self.show_id,
+            show_title: self.show_title,
+            show_slug: self.show_slug,
+            season: self.season,
+            episode: self.episode,
+            episode_name: self.episode_name.filter(|n| !n.trim().is_empty()),
+            air_date: to_date(self.air_date)?,
+            path,
+        })
+    }
+}
+
+impl EpisodeCalendarRepository for PgEpisodeCalendarRepository {
+    type Error = sqlx::Error;
+
+    async fn between(&self, from: Date, to: Date) -> Result<Vec<CalendarEpisode>, Self::Error> {
+        const WINDOW: &str = "e.air_date BETWEEN $1 AND $2";
+        let series = calendar_select(CatalogKind::Series, WINDOW);
+        let tv = calendar_select(CatalogKind::TvShow, WINDOW);
+        let rows: Vec<CalendarRow> = sqlx::query_as(&format!(
+            "SELECT * FROM (({series}) UNION ALL ({tv})) c \
+              ORDER BY c.air_date, c.show_title, c.season, c.episode"
+        ))
+        .bind(from_date(from))
+        .bind(from_date(to))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(CalendarRow::into_episode)
+            .collect())
+    }
+
+    async fn series_schedule(
+        &self,
+        series_id: i32,
+        from: Date,
+        limit: i64,
+    ) -> Result<Vec<CalendarEpisode>, Self::Error> {
+        let series = calendar_select(CatalogKind::Series, "s.id = $1 AND e.air_date >= $2");
+        let rows: Vec<CalendarRow> = sqlx::query_as(&format!(
+            "SELECT * FROM ({series}) c \
+              ORDER BY c.air_date, c.season, c.episode \
+              LIMIT $3"
+        ))
+        .bind(series_id)
+        .bind(from_date(from))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(CalendarRow::into_episode)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(kind: &str, slug: Option<&str>, playable: bool) -> CalendarRow {
+        CalendarRow {
+            kind: kind.to_string(),
+            show_id: 7,
+            show_title: "Simpsonovi".to_string(),
+            show_slug: "simpsonovi".to_string(),
+            season: 37,
+            episode: 5,
+            episode_name: Some(" ".to_string()),
+            air_date: chrono::NaiveDate::from_ymd_opt(2026, 10, 26).unwrap(),
+            slug: slug.map(str::to_string),
+            playable,
+        }
+    }
+
+    #[test]
+    fn only_playable_episodes_link_to_their_page() {
+        let e = row("series", Some("pilot-s37e05"), false)
+            .into_episode()
+            .unwrap();
+        assert_eq!(e.path, None);
+        assert_eq!(e.episode_name, None);
+        assert_eq!(e.air_date.to_string(), "2026-10-26");
+
+        let e = row("series", Some("pilot-s37e05"), true)
+            .into_episode()
+            .unwrap();
+        assert_eq!(
+            e.path.as_deref(),
+            Some("/serialy-online/simpsonovi/pilot-s37e05/")
+        );
+
+        let e = row("tv", None, true).into_episode().unwrap();
+        assert_eq!(e.kind, CatalogKind::TvShow);
+        assert_eq!(e.path.as_deref(), Some("/tv-porady/simpsonovi/37x5/"));
+    }
+}
@@ -0,0 +1,644 @@
+//! SQL building blocks shared by the catalog repositories (films, series,
+//! episodes, TV pořady).
+
+use cr_domain::catalog::{
+    CatalogSort, GenreFilter, PageCursors, Paging, Seek, SortDirection, SortKey,
+};
+use cr_domain::repository::{GenreRecord, PersonCredit, TitleSuggestion};
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::query::{QueryAs, QueryScalar};
+use sqlx::{Postgres, Row};
+
+/// A value bound to a `$N` placeholder.
+pub(crate) enum Bind {
+    Text(String),
+    TextArray(Vec<String>),
+    SmallInt(i16),
+    BigInt(i64),
+}
+
+/// WHERE-clause accumulator that numbers `$N` placeholders as values are
+/// bound, so a count query and its page query can't disagree on indexes.
+#[derive(Default)]
+pub(crate) struct Predicates {
+    parts: Vec<String>,
+    binds: Vec<Bind>,
+}
+
+impl Predicates {
+    /// Bind `value` and return its placeholder (`$3`).
+    pub(crate) fn bind(&mut self, value: Bind) -> String {
+        self.binds.push(value);
+        format!("${}", self.binds.len())
+    }
+
+    pub(crate) fn push(&mut self, predicate: impl Into<String>) {
+        self.parts.push(predicate.into());
+    }
+
+    /// `WHERE a AND b`, or an empty string when nothing was pushed.
+    pub(crate) fn where_clause(&self) -> String {
+        if self.parts.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.parts.join(" AND "))
+        }
+    }
+
+    pub(crate) fn apply<'q, O>(
+        &self,
+        mut q: QueryAs<'q, Postgres, O, PgArguments>,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        for b in &self.binds {
+            q = match b {
+                Bind::Text(v) => q.bind(v.clone()),
+                Bind::TextArray(v) => q.bind(v.clone()),
+                Bind::SmallInt(v) => q.bind(*v),
+                Bind::BigInt(v) => q.bind(*v),
+            };
+        }
+        q
+    }
+
+    pub(crate) fn apply_scalar<'q, O>(
+        &self,
+        mut q: QueryScalar<'q, Postgres, O, PgArguments>,
+    ) -> QueryScalar<'q, Postgres, O, PgArguments> {
+        for b in &self.binds {
+            q = match b {
+                Bind::Text(v) => q.bind(v.clone()),
+                Bind::TextArray(v) => q.bind(v.clone()),
+                Bind::SmallInt(v) => q.bind(*v),
+                Bind::BigInt(v) => q.bind(*v),
+            };
+        }
+        q
+    }
+}
+
+/// How a catalog table links to `genres`.
+pub(crate) struct GenreLink {
+    /// Id column of the catalog row in the outer query (`f.id`).
+    pub(crate) id: &'static str,
+    /// Junction table (`film_genres`).
+    pub(crate) table: &'static str,
+    /// Catalog foreign key in the junction table (`film_id`).
+    pub(crate) fk: &'static str,
+}
+
+/// Include/exclude genre predicates. AND mode relies on the slugs being
+/// de-duplicated (`parse_slug_list`) for the `HAVING COUNT` to be right.
+pub(crate) fn push_genre_predicates(p: &mut Predicates, genres: &GenreFilter, link: &GenreLink) {
+    let GenreLink { id, table, fk } = link;
+    if !genres.include.is_empty() {
+        let n = genres.include.len();
+        let ph = p.bind(Bind::TextArray(genres.include.clone()));
+        if genres.match_all {
+            p.push(format!(
+                "{id} IN (SELECT gl.{fk} FROM {table} gl \
+                 JOIN genres g ON g.id = gl.genre_id \
+                 WHERE g.slug = ANY({ph}) \
+                 GROUP BY gl.{fk} HAVING COUNT(DISTINCT g.slug) = {n})"
+            ));
+        } else {
+            p.push(format!(
+                "{id} IN (SELECT gl.{fk} FROM {table} gl \
+                 JOIN genres g ON g.id = gl.genre_id \
+                 WHERE g.slug = ANY({ph}))"
+            ));
+        }
+    }
+    if !genres.exclude.is_empty() {
+        let ph = p.bind(Bind::TextArray(genres.exclude.clone()));
+        p.push(format!(
+            "{id} NOT IN (SELECT gx.{fk} FROM {table} gx \
+             JOIN genres g2 ON g2.id = gx.genre_id \
+             WHERE g2.slug = ANY({ph}))"
+        ));
+    }
+}
+
+/// Rating-sort vote threshold (#704) on the table aliased `alias`. NULL
+/// votes evaluate to false, so no explicit `IS NOT NULL` is needed.
+pub(crate) fn votes_predicate(alias: &str, sort: CatalogSort) -> Option<String> {
+    let min = sort.min_votes()?;
+    match sort {
+        CatalogSort::Imdb => Some(format!("{alias}.imdb_votes >= {min}")),
+        CatalogSort::Tmdb => Some(format!("{alias}.tmdb_vote_count >= {min}")),
+        _ => None,
+    }
+}
+
+/// How a keyset column reads back from its text in a cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyType {
+    Int,
+    Real,
+    Text,
+    Timestamp,
+}
+
+impl KeyType {
+    /// `expr` rendered as cursor text. Timestamps keep microseconds and an
+    /// explicit UTC zone so the text casts back to the very same instant.
+    fn render(self, expr: &str) -> String {
+        match self {
+            Self::Timestamp => {
+                format!("to_char(({expr}) AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"')")
+            }
+            _ => format!("({expr})::TEXT"),
+        }
+    }
+
+    fn cast(self) -> &'static str {
+        match self {
+            Self::Int => "BIGINT",
+            Self::Real => "REAL",
+            Self::Text => "TEXT",
+            Self::Timestamp => "TIMESTAMPTZ",
+        }
+    }
+
+    /// Whether `value` is something this column could have rendered —
+    /// checked before binding, so a hand-edited cursor can't fail the cast.
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            Self::Int => value.parse::<i64>().is_ok(),
+            Self::Real => value.parse::<f32>().is_ok_and(f32::is_finite),
+            Self::Text => true,
+            Self::Timestamp => chrono::DateTime::parse_from_rfc3339(value).is_ok(),
+        }
+    }
+}
+
+struct KeyColumn {
+    expr: String,
+    ty: KeyType,
+    desc: bool,
+    nullable: bool,
+}
+
+/// ORDER BY of a listing that pages by keyset (`?po=` / `?pred=`). Keys
+/// compare left to right; the row id goes last so every position is
+/// unique. Nullable keys sort NULLs last in either direction, like the
+/// listings always have.
+#[derive(Default)]
+pub(crate) struct Keyset {
+    columns: Vec<KeyColumn>,
+}
+
+/// How one listing page is read, from [`Keyset::page`].
+pub(crate) struct PagePlan {
+    /// ORDER BY list — reversed when reading backwards.
+    pub(crate) order: String,
+    /// `LIMIT $n` or `LIMIT $n OFFSET $m`.
+    pub(crate) limit: String,
+    /// Rows come back last-first and must be flipped.
+    pub(crate) reversed: bool,
+}
+
+impl Keyset {
+    pub(crate) fn key(mut self, expr: impl Into<String>, ty: KeyType, desc: bool) -> Self {
+        self.columns.push(KeyColumn {
+            expr: expr.into(),
+            ty,
+            desc,
+            nullable: false,
+        });
+        self
+    }
+
+    pub(crate) fn nullable(mut self, expr: impl Into<String>, ty: KeyType, desc: bool) -> Self {
+        self.columns.push(KeyColumn {
+            expr: expr.into(),
+            ty,
+            desc,
+            nullable: true,
+        });
+        self
+    }
+
+    /// Put a key in front of the others — the search rank.
+    pub(crate) fn prepend(&mut self, expr: impl Into<String>, ty: KeyType) {
+        self.columns.insert(
+            0,
+            KeyColumn {
+                expr: expr.into(),
+                ty,
+                desc: false,
+                nullable: false,
+            },
+        );
+    }
+
+    /// ORDER BY list; `reverse` flips every key, NULL placement included.
+    pub(crate) fn order_by(&self, reverse: bool) -> String {
+        self.columns
+            .iter()
+            .map(|c| {
+                let dir = if c.desc != reverse { "DESC" } else { "ASC" };
+                match (c.nullable, reverse) {
+                    (false, _) => format!("{} {dir}", c.expr),
+                    (true, false) => format!("{} {dir} NULLS LAST", c.expr),
+                    (true, true) => format!("{} {dir} NULLS FIRST", c.expr),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// SELECT item with the row's cursor (`sort_key`, read by [`Keyed`]).
+    pub(crate) fn select(&self) -> String {
+        let parts: Vec<String> = self.columns.iter().map(|c| c.ty.render(&c.expr)).collect();
+        format!("ARRAY[{}]::TEXT[] AS sort_key", parts.join(", "))
+    }
+
+    /// Predicate for the rows strictly after (`Seek::After`) or before
+    /// `key` in this order, binding its values into `p`. `None` when the
+    /// key doesn't fit the order — a cursor from another sort, or edited.
+    pub(crate) fn seek_predicate(&self, p: &mut Predicates, seek: &Seek) -> Option<String> {
+        let (key, reverse) = match seek {
+            Seek::After(key) => (key, false),
+            Seek::Before(key) => (key, true),
+        };
+        if key.0.len() != self.columns.len() {
+            return None;
+        }
+        let fits = self.columns.iter().zip(&key.0).all(|(c, v)| match v {
+            Some(v) => c.ty.accepts(v),
+            None => c.nullable,
+        });
+        if !fits {
+            return None;
+        }
+        let values: Vec<Option<String>> = self
+            .columns
+            .iter()
+            .zip(&key.0)
+            .map(|(c, v)| {
+                v.as_ref()
+                    .map(|v| format!("{}::{}", p.bind(Bind::Text(v.clone())), c.ty.cast()))
+            })
+            .collect();
+
+        // (k1 past v1) OR (k1 = v1 AND k2 past v2) OR …
+        let mut terms = Vec::new();
+        let mut equal: Vec<String> = Vec::new();
+        for (c, v) in self.columns.iter().zip(&values) {
+            let desc = c.desc != reverse;
+            // Reading backwards puts the NULLs first.
+            let nulls_last = c.nullable && !reverse;
+            let past = match v {
+                Some(v) => {
+                    let cmp = format!("{} {} {v}", c.expr, if desc { "<" } else { ">" });
+                    if nulls_last {
+                        Some(format!("({cmp} OR {} IS NULL)", c.expr))
+                    } else {
+                        Some(cmp)
+                    }
+                }
+                // Past a NULL: nothing when NULLs come last, every
+                // non-NULL when they come first.
+                None if nulls_last => None,
+                None => Some(format!("{} IS NOT NULL", c.expr)),
+            };
+            if let Some(past) = past {
+                let mut term = equal.clone();
+                term.push(past);
+                terms.push(format!("({})", term.join(" AND ")));
+            }
+            equal.push(match v {
+                Some(v) => format!("{} = {v}", c.expr),
+                None => format!("{} IS NULL", c.expr),
+            });
+        }
+        if terms.is_empty() {
+            return Some("FALSE".to_string());
+        }
+        Some(format!("({})", terms.join(" OR ")))
+    }
+
+    /// Plan one page. A seek that fits becomes a predicate on `p` and the
+    /// page is read from there; anything else pages by OFFSET. Call after
+    /// the filters are pushed and before rendering the WHERE clause.
+    pub(crate) fn page(&self, p: &mut Predicates, paging: &Paging) -> PagePlan {
+        if let Some(seek) = &paging.seek
+            && let Some(predicate) = self.seek_predicate(p, seek)
+        {
+            p.push(predicate);
+            let reversed = matches!(seek, Seek::Before(_));
+            return PagePlan {
+                order: self.order_by(reversed),
+                limit: format!("LIMIT {}", p.bind(Bind::BigInt(paging.per_page))),
+                reversed,
+            };
+        }
+        let limit = p.bind(Bind::BigInt(paging.per_page));
+        let offset = p.bind(Bind::BigInt(paging.offset()));
+        PagePlan {
+            order: self.order_by(false),
+            limit: format!("LIMIT {limit} OFFSET {offset}"),
+            reversed: false,
+        }
+    }
+}
+
+/// A listing row plus its cursor ([`Keyset::select`]).
+pub(crate) struct Keyed<R> {
+    row: R,
+    sort_key: Vec<Option<String>>,
+}
+
+impl<'r, R: sqlx::FromRow<'r, PgRow>> sqlx::FromRow<'r, PgRow> for Keyed<R> {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            row: R::from_row(row)?,
+            sort_key: row.try_get("sort_key")?,
+        })
+    }
+}
+
+/// Rows of a page in listing order, with the cursors of the first and last.
+pub(crate) fn keyed_page<R, T: From<R>>(
+    mut rows: Vec<Keyed<R>>,
+    reversed: bool,
+) -> (Vec<T>, PageCursors) {
+    if reversed {
+        rows.reverse();
+    }
+    let cursors = PageCursors {
+        first: rows.first().map(|r| SortKey(r.sort_key.clone())),
+        last: rows.last().map(|r| SortKey(r.sort_key.clone())),
+    };
+    (rows.into_iter().map(|r| T::from(r.row)).collect(), cursors)
+}
+
+/// ORDER BY keys for `series` / `tv_shows` listings (aliased `s`).
+pub(crate) fn show_keyset(sort: CatalogSort, direction: SortDirection) -> Keyset {
+    use KeyType::{Int, Real, Text, Timestamp};
+    let desc = direction.is_desc();
+    let keys = Keyset::default();
+    let keys = match sort {
+        CatalogSort::Year => keys
+            .nullable("s.first_air_year", Int, desc)
+            .key("s.title", Text, false),
+        CatalogSort::Imdb => keys
+            .nullable("s.imdb_rating", Real, desc)
+            .key("s.title", Text, false),
+        CatalogSort::Tmdb => keys
+            .nullable("s.tmdb_rating", Real, desc)
+            .key("s.title", Text, false),
+        CatalogSort::Title => keys.key("s.title", Text, desc),
+        // Shows have no ČSFD sort — it falls back to the default order.
+        CatalogSort::Added | CatalogSort::Csfd => keys
+            .nullable("s.added_at", Timestamp, desc)
+            .key("s.title", Text, false),
+    };
+    keys.key("s.id", Int, false)
+}
+
+/// Title search on `series` / `tv_shows` (aliased `s`). Returns the WHERE
+/// predicate and the ORDER BY prefix.
+///
+/// Matching goes through `unaccent()` so "laska nebeska" finds "Láska
+/// nebeská" (#673); the raw ILIKE in the rank pushes rows that literally
+/// contain the user's diacritics ahead of unaccent-only hits.
+pub(crate) fn show_search(p: &mut Predicates, term: &str) -> (String, String) {
+    let ph = p.bind(Bind::Text(format!("%{term}%")));
+    (
+        format!(
+            "(unaccent(s.title) ILIKE unaccent({ph}) \
+             OR unaccent(s.original_title) ILIKE unaccent({ph}))"
+        ),
+        format!("(CASE WHEN s.title ILIKE {ph} OR s.original_title ILIKE {ph} THEN 0 ELSE 1 END)"),
+    )
+}
+
+/// Autocomplete query over `table`. `title` / `original_title` are the
+/// expressions matched — plain columns, or `CONCAT_WS(' ', title, year)`
+/// for the film title+year fallback. `$1` is `%q%`, `$2` is `q%`.
+///
+/// WHERE goes through `unaccent()` (#673); the CASE keeps raw ILIKE so
+/// rows with the user's exact diacritics rank in buckets 0–2 and
+/// unaccent-only matches drop to bucket 3.
+pub(crate) fn suggest_sql(table: &str, year: &str, title: &str, original_title: &str) -> String {
+    format!(
+        "SELECT slug, title, {year} AS year, tmdb_rating, imdb_rating \
+         FROM {table} \
+         WHERE unaccent({title}) ILIKE unaccent($1) \
+            OR unaccent({original_title}) ILIKE unaccent($1) \
+         ORDER BY \
+           CASE WHEN {title} ILIKE $2 THEN 0 \
+                WHEN {title} ILIKE $1 THEN 1 \
+                WHEN {original_title} ILIKE $2 THEN 2 \
+                ELSE 3 END, \
+           tmdb_rating DESC NULLS LAST \
+         LIMIT 10"
+    )
+}
+
+/// SELECT list for [`PersonCreditRow`] from `people p`. `character` is the
+/// cast column, or `NULL::varchar` for crew.
+pub(crate) fn person_columns(character: &str) -> String {
+    format!("p.id, p.slug, p.name, p.profile_filename, {character} AS character_name")
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct GenreRow {
+    id: i32,
+    slug: String,
+    name_cs: String,
+}
+
+impl From<GenreRow> for GenreRecord {
+    fn from(r: GenreRow) -> Self {
+        Self {
+            id: r.id,
+            slug: r.slug,
+            name_cs: r.name_cs,
+        }
+    }
+}
+
+/// Genre tagged with the id of the catalog row it belongs to.
+#[derive(sqlx::FromRow)]
+pub(crate) struct OwnedGenreRow {
+    owner_id: i32,
+    id: i32,
+    slug: String,
+    name_cs: String,
+}
+
+impl From<OwnedGenreRow> for (i32, GenreRecord) {
+    fn from(r: OwnedGenreRow) -> Self {
+        (
+            r.owner_id,
+            GenreRecord {
+                id: r.id,
+                slug: r.slug,
+                name_cs: r.name_cs,
+            },
+        )
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct PersonCreditRow {
+    id: i32,
+    slug: String,
+    name: String,
+    profile_filename: Option<String>,
+    character_name: Option<String>,
+}
+
+impl From<PersonCreditRow> for PersonCredit {
+    fn from(r: PersonCreditRow) -> Self {
+        Self {
+            id: r.id,
+            slug: r.slug,
+            name: r.name,
+            profile_filename: r.profile_filename,
+            character_name: r.character_name,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct SuggestionRow {
+    slug: String,
+    title: String,
+    year: Option<i16>,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+}
+
+impl From<SuggestionRow> for TitleSuggestion {
+    fn from(r: SuggestionRow) -> Self {
+        Self {
+            slug: r.slug,
+            title: r.title,
+            year: r.year,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+        }
+    }
+}
+
+/// `chrono::NaiveDate` → domain [`cr_domain::Date`].
+pub(crate) fn to_date(d: chrono::NaiveDate) -> Option<cr_domain::Date> {
+    use chrono::Datelike;
+    cr_domain::Date::new(d.year(), d.month() as u8, d.day() as u8)
+}
+
+/// Domain [`cr_domain::Date`] → `chrono::NaiveDate`, for binds.
+pub(crate) fn from_date(d: cr_domain::Date) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(d.year(), u32::from(d.month()), u32::from(d.day()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_number_in_bind_order() {
+        let mut p = Predicates::default();
+        let genres = GenreFilter::parse(Some("horor,drama"), Some("komedie"), Some("and"));
+        push_genre_predicates(
+            &mut p,
+            &genres,
+            &GenreLink {
+                id: "f.id",
+                table: "film_genres",
+                fk: "film_id",
+            },
+        );
+        let year = p.bind(Bind::SmallInt(1999));
+        p.push(format!("f.year = {year}"));
+        let sql = p.where_clause();
+        assert!(sql.starts_with("WHERE f.id IN"), "{sql}");
+        assert!(sql.contains("ANY($1)"), "{sql}");
+        assert!(sql.contains("HAVING COUNT(DISTINCT g.slug) = 2"), "{sql}");
+        assert!(sql.contains("f.id NOT IN"), "{sql}");
+        assert!(sql.contains("ANY($2)"), "{sql}");
+        assert!(sql.ends_with("f.year = $3"), "{sql}");
+    }
+
+    #[test]
+    fn empty_predicates_render_no_where() {
+        assert_eq!(Predicates::default().where_clause(), "");
+    }
+
+    fn key(values: &[Option<&str>]) -> SortKey {
+        SortKey(values.iter().map(|v| v.map(str::to_string)).collect())
+    }
+
+    #[test]
+    fn seek_continues_past_nulls_last_keys() {
+        let keys = Keyset::default()
+            .nullable("f.year", KeyType::Int, true)
+            .key("f.id", KeyType::Int, false);
+        let mut p = Predicates::default();
+        let after = keys
+            .seek_predicate(&mut p, &Seek::After(key(&[Some("1999"), Some("7")])))
+            .unwrap();
+        assert_eq!(
+            after,
+            "(((f.year < $1::BIGINT OR f.year IS NULL)) OR (f.year = $1::BIGINT AND f.id > $2::BIGINT))"
+        );
+
+        // Backwards from a NULL year: every dated film, then the undated
+        // ones with a smaller id.
+        let mut p = Predicates::default();
+        let before = keys
+            .seek_predicate(&mut p, &Seek::Before(key(&[None, Some("7")])))
+            .unwrap();
+        assert_eq!(
+            before,
+            "((f.year IS NOT NULL) OR (f.year IS NULL AND f.id < $1::BIGINT))"
+        );
+        assert_eq!(keys.order_by(true), "f.year ASC NULLS FIRST, f.id DESC");
+    }
+
+    #[test]
+    fn foreign_cursor_falls_back_to_offset() {
+        let keys = Keyset::default()
+            .nullable("f.added_at", KeyType::Timestamp, true)
+            .key("f.id", KeyType::Int, false);
+        let mut paging = Paging::new(Some(3), 24);
+        // A year-sort cursor on the date-added order.
+        paging.seek = Some(Seek::After(key(&[Some("1999"), Some("7")])));
+        let mut p = Predicates::default();
+        let plan = keys.page(&mut p, &paging);
+        assert_eq!(plan.limit, "LIMIT $1 OFFSET $2");
+        assert!(!plan.reversed);
+        assert_eq!(p.where_clause(), "");
+
+        paging.seek = Some(Seek::Before(key(&[
+            Some("2026-10-19T08:00:00.000000Z"),
+            Some("7"),
+        ])));
+        let mut p = Predicates::default();
+        let plan = keys.page(&mut p, &paging);
+        assert_eq!(plan.limit, "LIMIT $3");
+        assert!(plan.reversed);
+        assert!(p.where_clause().contains("$1::TIMESTAMPTZ"));
+        assert!(
+            keys.select()
+                .starts_with("ARRAY[to_char((f.added_at) AT TIME ZONE 'UTC'")
+        );
+    }
+
+    #[test]
+    fn votes_predicate_only_for_rating_sorts() {
+        assert_eq!(
+            votes_predicate("s", CatalogSort::Imdb).as_deref(),
+            Some("s.imdb_votes >= 500")
+        );
+        assert_eq!(
+            votes_predicate("f", CatalogSort::Tmdb).as_deref(),
+            Some("f.tmdb_vote_count >= 50")
+        );
+        assert_eq!(votes_predicate("f", CatalogSort::Csfd), None);
+    }
+}
@@ -0,0 +1,116 @@
+use cr_domain::repository::{CollectionFilm, FilmCollection, FilmCollectionRepository};
+
+use super::film::FILM_HAS_SOURCE_PREDICATE;
+
+#[derive(sqlx::FromRow)]
+struct CollectionRow {
+    id: i32,
+    slug: String,
+    name: String,
+    overview: Option<String>,
+}
+
+impl From<CollectionRow> for FilmCollection {
+    fn from(r: CollectionRow) -> Self {
+        Self {
+            id: r.id,
+            slug: r.slug,
+            name: r.name,
+            overview: r.overview,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CollectionFilmRow {
+    id: i32,
+    slug: String,
+    title: String,
+    year: Option<i16>,
+    position: i16,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+}
+
+impl From<CollectionFilmRow> for CollectionFilm {
+    fn from(r: CollectionFilmRow) -> Self {
+        Self {
+            id: r.id,
+            slug: r.slug,
+            title: r.title,
+            year: r.year,
+            position: r.position,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+        }
+    }
+}
+
+fn films_sql() -> String {
+    format!(
+        "SELECT f.id, f.slug, f.title, f.year, cf.position, f.tmdb_rating, f.imdb_rating \
+         FROM film_collection_films cf \
+         JOIN films f ON f.id = cf.film_id \
+         WHERE cf.collection_id = $1 AND {FILM_HAS_SOURCE_PREDICATE} \
+         ORDER BY cf.position, f.year NULLS LAST, f.id"
+    )
+}
+
+/// PostgreSQL implementation of [`FilmCollectionRepository`]
+/// (`film_collections`, migration 083).
+pub struct PgFilmCollectionRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgFilmCollectionRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl FilmCollectionRepository for PgFilmCollectionRepository {
+    type Error = sqlx::Error;
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<FilmCollection>, Self::Error> {
+        let row = sqlx::query_as::<_, CollectionRow>(
+            "SELECT id, slug, name, overview FROM film_collections WHERE slug = $1",
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(FilmCollection::from))
+    }
+
+    async fn of_film(&self, film_id: i32) -> Result<Option<FilmCollection>, Self::Error> {
+        let row = sqlx::query_as::<_, CollectionRow>(
+            "SELECT c.id, c.slug, c.name, c.overview \
+             FROM film_collection_films cf \
+             JOIN film_collections c ON c.id = cf.collection_id \
+             WHERE cf.film_id = $1",
+        )
+        .bind(film_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(FilmCollection::from))
+    }
+
+    async fn films(&self, collection_id: i32) -> Result<Vec<CollectionFilm>, Self::Error> {
+        let rows = sqlx::query_as::<_, CollectionFilmRow>(&films_sql())
+            .bind(collection_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(CollectionFilm::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_are_gated_on_a_live_source() {
+        let sql = films_sql();
+        assert!(sql.contains(FILM_HAS_SOURCE_PREDICATE), "{sql}");
+        assert!(sql.contains("ORDER BY cf.position"), "{sql}");
+    }
+}
@@ -0,0 +1,228 @@
+use cr_domain::repository::{EpisodeNav, EpisodeRecord, EpisodeRepository, PersonCredit};
+
+use super::catalog_sql::{PersonCreditRow, person_columns, to_date};
+
+/// Column list for `EpisodeRow` queries. After the #611 reader switch this
+/// projects the same field names as the legacy `episodes` columns but reads
+/// each provider attribute from `video_sources`. The film listing used the
+/// same correlated subqueries until it moved to the `film_availability`
+/// summary (migration 087); episode lists are per series and short, and
+/// every subquery is a primary-key-sized lookup on `idx_vs_episode_alive`.
+///
+/// Scope: series episodes from sktorrent / prehrajto / sledujteto. The
+/// sledujteto subquery (#751) only returns `cdn='www'` rows because
+/// `data{N}.sledujteto.cz` files are blocked from Hetzner ASNs and the
+/// client-side player would 4xx on them anyway — better to hide them
+/// entirely than expose dead links.
+const EPISODE_COLUMNS: &str = "e.id, e.season, e.episode, e.title, \
+    (SELECT vs.external_id::INTEGER \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.episode_id = e.id AND p.slug = 'sktorrent' \
+        AND vs.is_primary AND vs.is_alive \
+      LIMIT 1) AS sktorrent_video_id, \
+    (SELECT vs.cdn::SMALLINT \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.episode_id = e.id AND p.slug = 'sktorrent' \
+        AND vs.is_primary AND vs.is_alive \
+      LIMIT 1) AS sktorrent_cdn, \
+    (SELECT vs.metadata->>'qualities' \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.episode_id = e.id AND p.slug = 'sktorrent' \
+        AND vs.is_primary AND vs.is_alive \
+      LIMIT 1) AS sktorrent_qualities, \
+    e.episode_name, e.overview, e.air_date, e.runtime, e.still_filename, \
+    (SELECT REPLACE(COALESCE(vs.metadata->>'url', 'https://prehraj.to/' || vs.external_id), \
+                    'https://prehrajto.cz/', 'https://prehraj.to/') \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.episode_id = e.id AND p.slug = 'prehrajto' AND vs.is_alive \
+      ORDER BY vs.is_primary DESC, vs.updated_at DESC \
+      LIMIT 1) AS prehrajto_url, \
+    false AS prehrajto_has_dub, \
+    false AS prehrajto_has_subs, \
+    (SELECT vs.external_id \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.episode_id = e.id AND p.slug = 'sledujteto' \
+        AND vs.is_alive AND vs.cdn = 'www' \
+      ORDER BY vs.is_primary DESC, vs.updated_at DESC \
+      LIMIT 1) AS sledujteto_external_id, \
+    e.slug";
+
+/// Predicate that gates whether an episode has any playable source at all.
+/// Prevents zombie episodes (TMDB stubs without any source) from rendering.
+pub(crate) const EPISODE_HAS_SOURCE_PREDICATE: &str =
+    "EXISTS (SELECT 1 FROM video_sources vs WHERE vs.episode_id = e.id AND vs.is_alive)";
+
+/// PostgreSQL implementation of [`EpisodeRepository`].
+pub struct PgEpisodeRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgEpisodeRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EpisodeRow {
+    id: i32,
+    season: i16,
+    episode: i16,
+    title: Option<String>,
+    sktorrent_video_id: Option<i32>,
+    sktorrent_cdn: Option<i16>,
+    sktorrent_qualities: Option<String>,
+    episode_name: Option<String>,
+    overview: Option<String>,
+    air_date: Option<chrono::NaiveDate>,
+    runtime: Option<i16>,
+    still_filename: Option<String>,
+    prehrajto_url: Option<String>,
+    prehrajto_has_dub: bool,
+    prehrajto_has_subs: bool,
+    sledujteto_external_id: Option<String>,
+    slug: Option<String>,
+}
+
+impl From<EpisodeRow> for EpisodeRecord {
+    fn from(r: EpisodeRow) -> Self {
+        Self {
+            id: r.id,
+            season: r.season,
+            episode: r.episode,
+            title: r.title,
+            sktorrent_video_id: r.sktorrent_video_id,
+            sktorrent_cdn: r.sktorrent_cdn,
+            sktorrent_qualities: r.sktorrent_qualities,
+            episode_name: r.episode_name,
+            overview: r.overview,
+            air_date: r.air_date.and_then(to_date),
+            runtime: r.runtime,
+            still_filename: r.still_filename,
+            prehrajto_url: r.prehrajto_url,
+            prehrajto_has_dub: r.prehrajto_has_dub,
+            prehrajto_has_subs: r.prehrajto_has_subs,
+            sledujteto_external_id: r.sledujteto_external_id,
+            slug: r.slug,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct EpisodeNavRow {
+    season: i16,
+    episode: i16,
+    episode_name: Option<String>,
+    slug: Option<String>,
+}
+
+impl From<EpisodeNavRow> for EpisodeNav {
+    fn from(r: EpisodeNavRow) -> Self {
+        Self {
+            season: r.season,
+            episode: r.episode,
+            episode_name: r.episode_name,
+            slug: r.slug,
+        }
+    }
+}
+
+impl PgEpisodeRepository {
+    async fn crew(&self, table: &str, episode_id: i32) -> Result<Vec<PersonCredit>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PersonCreditRow>(&format!(
+            "SELECT {} FROM people p JOIN {table} c ON c.person_id = p.id \
+             WHERE c.episode_id = $1 ORDER BY p.name",
+            person_columns("NULL::varchar")
+        ))
+        .bind(episode_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(PersonCredit::from).collect())
+    }
+}
+
+impl EpisodeRepository for PgEpisodeRepository {
+    type Error = sqlx::Error;
+
+    async fn list_by_series(&self, series_id: i32) -> Result<Vec<EpisodeRecord>, Self::Error> {
+        let rows = sqlx::query_as::<_, EpisodeRow>(&format!(
+            "SELECT {EPISODE_COLUMNS} \
+               FROM episodes e \
+              WHERE e.series_id = $1 \
+                AND {EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.season, e.episode, e.id"
+        ))
+        .bind(series_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(EpisodeRecord::from).collect())
+    }
+
+    async fn find_by_slug(
+        &self,
+        series_id: i32,
+        slug: &str,
+    ) -> Result<Option<EpisodeRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, EpisodeRow>(&format!(
+            "SELECT {EPISODE_COLUMNS} \
+               FROM episodes e \
+              WHERE e.series_id = $1 AND e.slug = $2 \
+                AND {EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.id LIMIT 1"
+        ))
+        .bind(series_id)
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(EpisodeRecord::from))
+    }
+
+    async fn find_by_number(
+        &self,
+        series_id: i32,
+        season: i16,
+        episode: i16,
+    ) -> Result<Option<EpisodeRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, EpisodeRow>(&format!(
+            "SELECT {EPISODE_COLUMNS} \
+               FROM episodes e \
+              WHERE e.series_id = $1 AND e.season = $2 AND e.episode = $3 \
+                AND {EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.id LIMIT 1"
+        ))
+        .bind(series_id)
+        .bind(season)
+        .bind(episode)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(EpisodeRecord::from))
+    }
+
+    async fn navigation(&self, series_id: i32) -> Result<Vec<EpisodeNav>, Self::Error> {
+        let rows = sqlx::query_as::<_, EpisodeNavRow>(&format!(
+            "SELECT DISTINCT ON (e.season, e.episode) \
+                    e.season, e.episode, e.episode_name, e.slug \
+               FROM episodes e \
+              WHERE e.series_id = $1 \
+                AND {EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.season, e.episode"
+        ))
+        .bind(series_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(EpisodeNav::from).collect())
+    }
+
+    async fn directors(&self, episode_id: i32) -> Result<Vec<PersonCredit>, Self::Error> {
+        self.crew("episode_directors", episode_id).await
+    }
+
+    async fn writers(&self, episode_id: i32) -> Result<Vec<PersonCredit>, Self::Error> {
+        self.crew("episode_writers", episode_id).await
+    }
+}
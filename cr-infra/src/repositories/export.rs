@@ -0,0 +1,303 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use cr_domain::export::{ExportColumnType, ExportCursor, ExportDataset, ExportRecord, ExportValue};
+use cr_domain::repository::ExportRepository;
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+
+/// PostgreSQL implementation of [`ExportRepository`].
+pub struct PgExportRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgExportRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// SQL twin of [`super::municipality::municipality_path`] over the
+/// `o` (ORP) and `m` (municipality) aliases.
+const MUNICIPALITY_PATH_SQL: &str = "CASE WHEN m.slug = o.slug THEN '/' || o.slug || '/' \
+     ELSE '/' || o.slug || '/' || m.slug || '/' END";
+
+/// The landmark under [`MUNICIPALITY_PATH_SQL`]; NULL when the landmark
+/// has no municipality yet (the outer joins leave `m` and `o` NULL).
+const LANDMARK_PATH_SQL: &str = "CASE WHEN m.slug = o.slug THEN '/' || o.slug || '/' \
+     ELSE '/' || o.slug || '/' || m.slug || '/' END || l.slug || '/'";
+
+/// Where a dataset's rows come from: `alias` is the exported table's alias
+/// in `from`, and `columns` pairs every [`ExportDataset::columns`] name
+/// with the expression that produces it, in the same order.
+struct DatasetSql {
+    from: &'static str,
+    alias: &'static str,
+    columns: &'static [(&'static str, &'static str)],
+}
+
+fn dataset_sql(dataset: ExportDataset) -> DatasetSql {
+    match dataset {
+        ExportDataset::Films => DatasetSql {
+            from: "films f",
+            alias: "f",
+            columns: &[
+                ("slug", "f.slug"),
+                ("url", "'/filmy-online/' || f.slug || '/'"),
+                ("title", "f.title"),
+                ("original_title", "f.original_title"),
+                ("year", "f.year"),
+                ("runtime_min", "f.runtime_min"),
+                ("imdb_id", "f.imdb_id"),
+                ("tmdb_id", "f.tmdb_id"),
+                ("csfd_id", "f.csfd_id"),
+                ("imdb_rating", "f.imdb_rating"),
+                ("imdb_votes", "f.imdb_votes"),
+                ("csfd_rating", "f.csfd_rating"),
+                ("tmdb_rating", "f.tmdb_rating"),
+                ("tmdb_vote_count", "f.tmdb_vote_count"),
+                ("audio_langs", "f.audio_langs"),
+                ("subtitle_langs", "f.subtitle_langs"),
+                ("added_at", "f.added_at"),
+                ("created_at", "f.created_at"),
+            ],
+        },
+        ExportDataset::Series => DatasetSql {
+            from: "series s",
+            alias: "s",
+            columns: &[
+                ("slug", "s.slug"),
+                ("url", "'/serialy-online/' || s.slug || '/'"),
+                ("title", "s.title"),
+                ("original_title", "s.original_title"),
+                ("first_air_year", "s.first_air_year"),
+                ("last_air_year", "s.last_air_year"),
+                ("season_count", "s.season_count"),
+                ("episode_count", "s.episode_count"),
+                ("imdb_id", "s.imdb_id"),
+                ("tmdb_id", "s.tmdb_id"),
+                ("csfd_id", "s.csfd_id"),
+                ("imdb_rating", "s.imdb_rating"),
+                ("imdb_votes", "s.imdb_votes"),
+                ("csfd_rating", "s.csfd_rating"),
+                ("tmdb_rating", "s.tmdb_rating"),
+                ("tmdb_vote_count", "s.tmdb_vote_count"),
+                ("audio_langs", "s.audio_langs_any"),
+                ("subtitle_langs", "s.subtitle_langs_any"),
+                ("added_at", "s.added_at"),
+                ("created_at", "s.created_at"),
+            ],
+        },
+        ExportDataset::TvShows => DatasetSql {
+            from: "tv_shows s",
+            alias: "s",
+            columns: &[
+                ("slug", "s.slug"),
+                ("url", "'/tv-porady/' || s.slug || '/'"),
+                ("title", "s.title"),
+                ("original_title", "s.original_title"),
+                ("first_air_year", "s.first_air_year"),
+                ("last_air_year", "s.last_air_year"),
+                ("season_count", "s.season_count"),
+                ("episode_count", "s.episode_count"),
+                ("imdb_id", "s.imdb_id"),
+                ("tmdb_id", "s.tmdb_id"),
+                ("csfd_id", "s.csfd_id"),
+                ("imdb_rating", "s.imdb_rating"),
+                ("imdb_votes", "s.imdb_votes"),
+                ("csfd_rating", "s.csfd_rating"),
+                ("tmdb_rating", "s.tmdb_rating"),
+                ("tmdb_vote_count", "s.tmdb_vote_count"),
+                ("added_at", "s.added_at"),
+                ("created_at", "s.created_at"),
+            ],
+        },
+        ExportDataset::People => DatasetSql {
+            from: "people p",
+            alias: "p",
+            columns: &[
+                ("slug", "p.slug"),
+                ("url", "'/osobnosti/' || p.slug || '/'"),
+                ("name", "p.name"),
+                ("tmdb_id", "p.tmdb_id"),
+                ("created_at", "p.created_at"),
+            ],
+        },
+        ExportDataset::Municipalities => DatasetSql {
+            from: "municipalities m \
+                   JOIN orp o ON o.id = m.orp_id \
+                   JOIN districts d ON d.id = o.district_id \
+                   JOIN regions r ON r.id = d.region_id",
+            alias: "m",
+            columns: &[
+                ("municipality_code", "m.municipality_code"),
+                ("slug", "m.slug"),
+                ("url", MUNICIPALITY_PATH_SQL),
+                ("name", "m.name"),
+                ("orp_code", "o.orp_code"),
+                ("region_code", "r.region_code"),
+                ("population", "m.population"),
+                ("elevation", "m.elevation"),
+                ("latitude", "m.latitude"),
+                ("longitude", "m.longitude"),
+                ("wikipedia_url", "m.wikipedia_url"),
+                ("official_website", "m.official_website"),
+                ("created_at", "m.created_at"),
+            ],
+        },
+        ExportDataset::Landmarks => DatasetSql {
+            from: "landmarks l \
+                   JOIN landmark_types t ON t.id = l.type_id \
+                   LEFT JOIN municipalities m ON m.id = l.municipality_id \
+                   LEFT JOIN orp o ON o.id = m.orp_id",
+            alias: "l",
+            columns: &[
+                ("slug", "l.slug"),
+                ("url", LANDMARK_PATH_SQL),
+                ("name", "l.name"),
+                ("type", "t.slug"),
+                ("municipality_code", "m.municipality_code"),
+                ("wikidata_id", "l.wikidata_id"),
+                ("npu_catalog_id", "l.npu_catalog_id"),
+                ("latitude", "l.latitude"),
+                ("longitude", "l.longitude"),
+                ("wikipedia_url", "l.wikipedia_url"),
+                ("created_at", "l.created_at"),
+            ],
+        },
+        ExportDataset::Pools => DatasetSql {
+            from: "pools p \
+                   LEFT JOIN orp o ON o.id = p.orp_id \
+                   LEFT JOIN municipalities m ON m.id = p.municipality_id",
+            alias: "p",
+            columns: &[
+                ("slug", "p.slug"),
+                ("url", "'/' || o.slug || '/' || p.slug || '/'"),
+                ("name", "p.name"),
+                ("address", "p.address"),
+                ("municipality_code", "m.municipality_code"),
+                ("latitude", "p.latitude"),
+                ("longitude", "p.longitude"),
+                ("website", "p.website"),
+                ("pool_length_m", "p.pool_length_m"),
+                ("is_aquapark", "p.is_aquapark"),
+                ("is_indoor", "p.is_indoor"),
+                ("is_outdoor", "p.is_outdoor"),
+                ("is_natural", "p.is_natural"),
+                ("created_at", "p.created_at"),
+            ],
+        },
+    }
+}
+
+/// Page query over `dataset`; `$1`/`$2` are the cursor when `after` is
+/// set, the last placeholder is the limit. Every column is cast to the
+/// type [`decode`] reads for its [`ExportColumnType`].
+fn page_sql(dataset: ExportDataset, after: bool) -> String {
+    let sql = dataset_sql(dataset);
+    let a = sql.alias;
+    let mut select = format!("{a}.id::BIGINT AS id, {a}.export_updated_at AS updated_at");
+    for (column, (name, expr)) in dataset.columns().iter().zip(sql.columns) {
+        let cast = match column.ty {
+            ExportColumnType::Int => "BIGINT",
+            // Via NUMERIC so REAL ratings come out as 7.3, not 7.300000190734863.
+            ExportColumnType::Float => "NUMERIC::FLOAT8",
+            ExportColumnType::Bool => "BOOLEAN",
+            ExportColumnType::Text => "TEXT",
+            ExportColumnType::TextList => "TEXT[]",
+            ExportColumnType::Timestamp => "TIMESTAMPTZ",
+        };
+        select.push_str(&format!(", ({expr})::{cast} AS \"{name}\""));
+    }
+    let (where_clause, limit) = if after {
+        (
+            format!("WHERE ({a}.export_updated_at, {a}.id) > ($1::TIMESTAMPTZ, $2::BIGINT)"),
+            "$3",
+        )
+    } else {
+        (String::new(), "$1")
+    };
+    format!(
+        "SELECT {select} FROM {} {where_clause} \
+         ORDER BY {a}.export_updated_at, {a}.id LIMIT {limit}",
+        sql.from
+    )
+}
+
+fn timestamp(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn decode(dataset: ExportDataset, row: &PgRow) -> Result<ExportRecord, sqlx::Error> {
+    let mut values = Vec::with_capacity(dataset.columns().len());
+    for column in dataset.columns() {
+        let name = column.name;
+        let value = match column.ty {
+            ExportColumnType::Int => row.try_get::<Option<i64>, _>(name)?.map(ExportValue::Int),
+            ExportColumnType::Float => row.try_get::<Option<f64>, _>(name)?.map(ExportValue::Float),
+            ExportColumnType::Bool => row.try_get::<Option<bool>, _>(name)?.map(ExportValue::Bool),
+            ExportColumnType::Text => row
+                .try_get::<Option<String>, _>(name)?
+                .map(ExportValue::Text),
+            ExportColumnType::TextList => row
+                .try_get::<Option<Vec<String>>, _>(name)?
+                .map(ExportValue::TextList),
+            ExportColumnType::Timestamp => row
+                .try_get::<Option<DateTime<Utc>>, _>(name)?
+                .map(|t| ExportValue::Timestamp(timestamp(t))),
+        };
+        values.push(value.unwrap_or(ExportValue::Null));
+    }
+    Ok(ExportRecord {
+        id: row.try_get("id")?,
+        updated_at: timestamp(row.try_get("updated_at")?),
+        values,
+    })
+}
+
+impl ExportRepository for PgExportRepository {
+    type Error = sqlx::Error;
+
+    async fn export_page(
+        &self,
+        dataset: ExportDataset,
+        after: Option<&ExportCursor>,
+        limit: i64,
+    ) -> Result<Vec<ExportRecord>, Self::Error> {
+        let sql = page_sql(dataset, after.is_some());
+        let mut query = sqlx::query(&sql);
+        if let Some(cursor) = after {
+            query = query.bind(&cursor.updated_at).bind(cursor.id);
+        }
+        let rows = query.bind(limit).fetch_all(&self.pool).await?;
+        rows.iter().map(|row| decode(dataset, row)).collect()
+    }
+
+    async fn now(&self) -> Result<String, Self::Error> {
+        let now: DateTime<Utc> = sqlx::query_scalar("SELECT now()")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(timestamp(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_published_column_has_an_expression() {
+        for dataset in ExportDataset::ALL {
+            let names: Vec<&str> = dataset_sql(dataset).columns.iter().map(|c| c.0).collect();
+            let published: Vec<&str> = dataset.columns().iter().map(|c| c.name).collect();
+            assert_eq!(names, published, "{}", dataset.key());
+        }
+    }
+
+    #[test]
+    fn pages_resume_after_the_cursor() {
+        let sql = page_sql(ExportDataset::People, true);
+        assert!(sql.contains("WHERE (p.export_updated_at, p.id) > ($1::TIMESTAMPTZ, $2::BIGINT)"));
+        assert!(sql.contains("(p.tmdb_id)::BIGINT AS \"tmdb_id\""));
+        assert!(sql.ends_with("ORDER BY p.export_updated_at, p.id LIMIT $3"));
+        assert!(page_sql(ExportDataset::People, false).ends_with("LIMIT $1"));
+    }
+}
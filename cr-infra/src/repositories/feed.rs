@@ -0,0 +1,374 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use cr_domain::catalog::{FilmListQuery, SeriesListQuery};
+use cr_domain::repository::{FeedEntry, FeedRepository, PlaceFeedFilter};
+
+use super::catalog_sql::{Bind, Predicates};
+use super::episode::EPISODE_HAS_SOURCE_PREDICATE;
+use super::film::film_predicates;
+use super::municipality::municipality_path;
+use super::series::series_predicates;
+use super::tv_show::TV_EPISODE_HAS_SOURCE_PREDICATE;
+
+/// Episode columns shared by the series and TV pořad feeds; the show is
+/// aliased `s`, the episode `e`.
+const EPISODE_FEED_COLUMNS: &str = "e.id, s.slug AS show_slug, s.title AS show_title, \
+    e.season, e.episode, e.slug, e.episode_name, \
+    COALESCE(e.description, e.overview) AS summary, e.created_at";
+
+/// Landmarks and municipalities join up to the region so both the URL
+/// (`/{orp}/{obec}/…`) and the `kraj=` filter come from one query.
+const PLACE_JOINS: &str = "JOIN orp o ON o.id = m.orp_id \
+    JOIN districts d ON d.id = o.district_id \
+    JOIN regions r ON r.id = d.region_id";
+
+/// PostgreSQL implementation of [`FeedRepository`].
+pub struct PgFeedRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgFeedRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn rfc3339(ts: DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[derive(sqlx::FromRow)]
+struct FilmFeedRow {
+    id: i32,
+    slug: String,
+    title: String,
+    year: Option<i16>,
+    description: Option<String>,
+    added_at: DateTime<Utc>,
+}
+
+impl From<FilmFeedRow> for FeedEntry {
+    fn from(r: FilmFeedRow) -> Self {
+        let added = rfc3339(r.added_at);
+        Self {
+            key: format!("film/{}", r.id),
+            path: format!("/filmy-online/{}/", r.slug),
+            title: match r.year {
+                Some(year) => format!("{} ({year})", r.title),
+                None => r.title,
+            },
+            summary: r.description,
+            image_path: Some(format!("/filmy-online/{}.webp", r.slug)),
+            published: added.clone(),
+            updated: added,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EpisodeFeedRow {
+    id: i32,
+    show_slug: String,
+    show_title: String,
+    season: i16,
+    episode: i16,
+    slug: Option<String>,
+    episode_name: Option<String>,
+    summary: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl EpisodeFeedRow {
+    /// `section` is the URL prefix (`serialy-online`, `tv-porady`) and
+    /// doubles as the key namespace.
+    fn into_entry(self, section: &str) -> FeedEntry {
+        let segment = self
+            .slug
+            .unwrap_or_else(|| format!("{}x{}", self.season, self.episode));
+        let code = format!("S{:02}E{:02}", self.season, self.episode);
+        let title = match self.episode_name.filter(|n| !n.trim().is_empty()) {
+            Some(name) => format!("{} — {code} {name}", self.show_title),
+            None => format!("{} — {code}", self.show_title),
+        };
+        let created = rfc3339(self.created_at);
+        FeedEntry {
+            key: format!("{section}/episode/{}", self.id),
+            path: format!("/{section}/{}/{segment}/", self.show_slug),
+            title,
+            summary: self.summary,
+            image_path: Some(format!("/{section}/{}.webp", self.show_slug)),
+            published: created.clone(),
+            updated: created,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PlaceFeedRow {
+    id: i32,
+    slug: String,
+    name: String,
+    summary: Option<String>,
+    orp_slug: String,
+    municipality_slug: String,
+    municipality_name: String,
+    /// Coat of arms extension (municipalities only).
+    emblem_ext: Option<String>,
+    created_at: DateTime<Utc>,
+    content_updated_at: DateTime<Utc>,
+}
+
+impl PlaceFeedRow {
+    fn municipality_path(&self) -> String {
+        municipality_path(&self.orp_slug, &self.municipality_slug)
+    }
+
+    fn into_landmark_entry(self) -> FeedEntry {
+        let path = format!("{}{}/", self.municipality_path(), self.slug);
+        FeedEntry {
+            key: format!("landmark/{}", self.id),
+            path,
+            title: format!("{} ({})", self.name, self.municipality_name),
+            summary: self.summary,
+            image_path: None,
+            published: rfc3339(self.created_at),
+            updated: rfc3339(self.content_updated_at),
+        }
+    }
+
+    fn into_municipality_entry(self) -> FeedEntry {
+        let image_path = self.emblem_ext.as_ref().map(|ext| {
+            format!(
+                "/{}/{}/znak-{}.{ext}",
+                self.orp_slug, self.municipality_slug, self.municipality_slug
+            )
+        });
+        FeedEntry {
+            key: format!("municipality/{}", self.id),
+            path: self.municipality_path(),
+            title: self.name,
+            summary: self.summary,
+            image_path,
+            published: rfc3339(self.created_at),
+            updated: rfc3339(self.content_updated_at),
+        }
+    }
+}
+
+/// `kraj=` / `orp=` / `typ=` predicates for the place feeds. `type_column`
+/// is `None` for municipalities, which have no type.
+fn place_predicates(filter: &PlaceFeedFilter, type_column: Option<&str>) -> Predicates {
+    let mut p = Predicates::default();
+    if let Some(region) = &filter.region_slug {
+        let ph = p.bind(Bind::Text(region.clone()));
+        p.push(format!("r.slug = {ph}"));
+    }
+    if let Some(orp) = &filter.orp_slug {
+        let ph = p.bind(Bind::Text(orp.clone()));
+        p.push(format!("o.slug = {ph}"));
+    }
+    if let (Some(kind), Some(column)) = (&filter.landmark_type, type_column) {
+        let ph = p.bind(Bind::Text(kind.clone()));
+        p.push(format!("{column} = {ph}"));
+    }
+    p
+}
+
+fn landmarks_sql(p: &mut Predicates, limit: i64) -> String {
+    let where_clause = p.where_clause();
+    let limit = p.bind(Bind::BigInt(limit));
+    format!(
+        "SELECT l.id, l.slug, l.name, COALESCE(l.description, l.npu_description) AS summary, \
+                o.slug AS orp_slug, m.slug AS municipality_slug, m.name AS municipality_name, \
+                NULL::TEXT AS emblem_ext, COALESCE(l.created_at, l.content_updated_at) AS created_at, \
+                l.content_updated_at \
+         FROM landmarks l \
+         JOIN landmark_types t ON t.id = l.type_id \
+         JOIN municipalities m ON m.id = l.municipality_id \
+         {PLACE_JOINS} {where_clause} \
+         ORDER BY l.content_updated_at DESC, l.id DESC LIMIT {limit}"
+    )
+}
+
+impl FeedRepository for PgFeedRepository {
+    type Error = sqlx::Error;
+
+    async fn films(
+        &self,
+        query: &FilmListQuery,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error> {
+        // Same filters as the listing; the listing's ORDER BY is replaced
+        // by "newest first".
+        let (mut p, _) = film_predicates(query);
+        let where_clause = p.where_clause();
+        let limit = p.bind(Bind::BigInt(limit));
+        let sql = format!(
+            "SELECT f.id, f.slug, f.title, f.year, f.description, \
+                    COALESCE(f.added_at, f.created_at) AS added_at \
+             FROM films f {where_clause} \
+             ORDER BY COALESCE(f.added_at, f.created_at) DESC, f.id DESC LIMIT {limit}"
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, FilmFeedRow>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(FeedEntry::from).collect())
+    }
+
+    async fn episodes(
+        &self,
+        query: &SeriesListQuery,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error> {
+        let (mut p, _) = series_predicates(query);
+        p.push(EPISODE_HAS_SOURCE_PREDICATE);
+        let where_clause = p.where_clause();
+        let limit = p.bind(Bind::BigInt(limit));
+        let sql = format!(
+            "SELECT {EPISODE_FEED_COLUMNS} \
+             FROM episodes e JOIN series s ON s.id = e.series_id {where_clause} \
+             ORDER BY e.created_at DESC, e.id DESC LIMIT {limit}"
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, EpisodeFeedRow>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| r.into_entry("serialy-online"))
+            .collect())
+    }
+
+    async fn series_episodes(
+        &self,
+        series_id: i32,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error> {
+        let rows = sqlx::query_as::<_, EpisodeFeedRow>(&format!(
+            "SELECT {EPISODE_FEED_COLUMNS} \
+             FROM episodes e JOIN series s ON s.id = e.series_id \
+             WHERE e.series_id = $1 AND {EPISODE_HAS_SOURCE_PREDICATE} \
+             ORDER BY e.created_at DESC, e.id DESC LIMIT $2"
+        ))
+        .bind(series_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| r.into_entry("serialy-online"))
+            .collect())
+    }
+
+    async fn tv_episodes(&self, limit: i64) -> Result<Vec<FeedEntry>, Self::Error> {
+        let rows = sqlx::query_as::<_, EpisodeFeedRow>(&format!(
+            "SELECT {EPISODE_FEED_COLUMNS} \
+             FROM tv_episodes e JOIN tv_shows s ON s.id = e.tv_show_id \
+             WHERE {TV_EPISODE_HAS_SOURCE_PREDICATE} \
+             ORDER BY e.created_at DESC, e.id DESC LIMIT $1"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| r.into_entry("tv-porady"))
+            .collect())
+    }
+
+    async fn landmarks(
+        &self,
+        filter: &PlaceFeedFilter,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error> {
+        let mut p = place_predicates(filter, Some("t.slug"));
+        let sql = landmarks_sql(&mut p, limit);
+        let rows = p
+            .apply(sqlx::query_as::<_, PlaceFeedRow>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(PlaceFeedRow::into_landmark_entry)
+            .collect())
+    }
+
+    async fn municipalities(
+        &self,
+        filter: &PlaceFeedFilter,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, Self::Error> {
+        let mut p = place_predicates(filter, None);
+        let where_clause = p.where_clause();
+        let limit = p.bind(Bind::BigInt(limit));
+        let sql = format!(
+            "SELECT m.id, m.slug, m.name, m.description AS summary, \
+                    o.slug AS orp_slug, m.slug AS municipality_slug, m.name AS municipality_name, \
+                    m.coat_of_arms_ext AS emblem_ext, \
+                    COALESCE(m.created_at, m.content_updated_at) AS created_at, \
+                    m.content_updated_at \
+             FROM municipalities m {PLACE_JOINS} {where_clause} \
+             ORDER BY m.content_updated_at DESC, m.id DESC LIMIT {limit}"
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, PlaceFeedRow>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(PlaceFeedRow::into_municipality_entry)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(municipality_slug: &str) -> PlaceFeedRow {
+        PlaceFeedRow {
+            id: 7,
+            slug: "hrad-kost".to_string(),
+            name: "Hrad Kost".to_string(),
+            summary: None,
+            orp_slug: "sobotka".to_string(),
+            municipality_slug: municipality_slug.to_string(),
+            municipality_name: "Libošovice".to_string(),
+            emblem_ext: Some("svg".to_string()),
+            created_at: DateTime::from_timestamp(0, 0).unwrap(),
+            content_updated_at: DateTime::from_timestamp(86_400, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn place_paths_follow_the_orp_seat_rule() {
+        let entry = place("libosovice").into_landmark_entry();
+        assert_eq!(entry.path, "/sobotka/libosovice/hrad-kost/");
+        assert_eq!(entry.updated, "1970-01-02T00:00:00Z");
+        let entry = place("sobotka").into_landmark_entry();
+        assert_eq!(entry.path, "/sobotka/hrad-kost/");
+
+        let entry = place("sobotka").into_municipality_entry();
+        assert_eq!(entry.path, "/sobotka/");
+        assert_eq!(
+            entry.image_path.as_deref(),
+            Some("/sobotka/sobotka/znak-sobotka.svg")
+        );
+    }
+
+    #[test]
+    fn place_filters_number_their_placeholders() {
+        let filter = PlaceFeedFilter {
+            region_slug: Some("kralovehradecky-kraj".to_string()),
+            orp_slug: None,
+            landmark_type: Some("castle".to_string()),
+        };
+        let mut p = place_predicates(&filter, Some("t.slug"));
+        let sql = landmarks_sql(&mut p, 50);
+        assert!(sql.contains("WHERE r.slug = $1 AND t.slug = $2"), "{sql}");
+        assert!(sql.contains("LIMIT $3"), "{sql}");
+        // Municipalities ignore the type filter.
+        let p = place_predicates(&filter, None);
+        assert_eq!(p.where_clause(), "WHERE r.slug = $1");
+    }
+}
@@ -0,0 +1,460 @@
+use cr_domain::catalog::{
+    CatalogSort, FilmListQuery, LocalLanguage, PageCursors, SortDirection, SubtitleFilter,
+    TitleMatch, TitleSearch,
+};
+use cr_domain::repository::{
+    FilmRecord, FilmRepository, GenreRecord, PersonCredit, TitleSuggestion,
+};
+
+use super::catalog_sql::{
+    Bind, GenreLink, GenreRow, KeyType, Keyed, Keyset, OwnedGenreRow, PersonCreditRow, Predicates,
+    SuggestionRow, keyed_page, person_columns, push_genre_predicates, suggest_sql, votes_predicate,
+};
+
+/// SELECT column list for `FilmRow` queries, read from [`FILM_FROM`].
+///
+/// The provider fields come from `film_availability`, a per-film summary
+/// of `video_sources` that triggers keep current (migration 087). The
+/// output shape is still that of the legacy `films` columns (#607 / #611),
+/// so templates didn't need any change:
+/// - `sktorrent_video_id` / `sktorrent_cdn` / `sktorrent_qualities`: the
+///   primary alive sktorrent upload.
+/// - `prehrajto_url`: the primary alive upload, falling back to the most
+///   recently updated alive one. URL from `metadata->>'url'`, synthesised
+///   from `external_id` as a last resort.
+/// - `sledujteto_primary_file_id`: only set when the primary upload's CDN
+///   is `www`, because data{N} is blocked from datacenter ASNs.
+const FILM_COLUMNS: &str = "f.id, f.title, f.slug, f.year, f.description, f.original_title, \
+    f.tmdb_rating, f.imdb_rating, f.csfd_rating, NULLIF(f.runtime_min, 0) AS runtime_min, \
+    f.tmdb_poster_path, \
+    av.sktorrent_video_id, av.sktorrent_cdn, av.sktorrent_qualities, av.prehrajto_url, \
+    false AS prehrajto_has_dub, \
+    false AS prehrajto_has_subs, \
+    av.sledujteto_primary_file_id";
+
+/// FROM clause for [`FILM_COLUMNS`]. A film without a summary row has
+/// never had a source; its provider fields read as NULL.
+const FILM_FROM: &str = "films f LEFT JOIN film_availability av ON av.film_id = f.id";
+
+/// Anti-zombie filter: only list films that actually have at least one
+/// alive video source. Reads the availability summary, so it stays a cheap
+/// index probe (`idx_film_availability_alive`) in any query over `films f`.
+pub(crate) const FILM_HAS_SOURCE_PREDICATE: &str = "EXISTS (SELECT 1 FROM film_availability fav \
+     WHERE fav.film_id = f.id AND fav.alive_sources > 0)";
+
+const FILM_GENRES: GenreLink = GenreLink {
+    id: "f.id",
+    table: "film_genres",
+    fk: "film_id",
+};
+
+/// PostgreSQL implementation of [`FilmRepository`].
+pub struct PgFilmRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgFilmRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct FilmRow {
+    id: i32,
+    title: String,
+    slug: String,
+    year: Option<i16>,
+    description: Option<String>,
+    original_title: Option<String>,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+    csfd_rating: Option<i16>,
+    runtime_min: Option<i16>,
+    tmdb_poster_path: Option<String>,
+    sktorrent_video_id: Option<i32>,
+    sktorrent_cdn: Option<i16>,
+    sktorrent_qualities: Option<String>,
+    prehrajto_url: Option<String>,
+    prehrajto_has_dub: bool,
+    prehrajto_has_subs: bool,
+    sledujteto_primary_file_id: Option<i32>,
+}
+
+impl From<FilmRow> for FilmRecord {
+    fn from(r: FilmRow) -> Self {
+        Self {
+            id: r.id,
+            title: r.title,
+            slug: r.slug,
+            year: r.year,
+            description: r.description,
+            original_title: r.original_title,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+            csfd_rating: r.csfd_rating,
+            runtime_min: r.runtime_min,
+            sktorrent_video_id: r.sktorrent_video_id,
+            sktorrent_cdn: r.sktorrent_cdn,
+            sktorrent_qualities: r.sktorrent_qualities,
+            prehrajto_url: r.prehrajto_url,
+            prehrajto_has_dub: r.prehrajto_has_dub,
+            prehrajto_has_subs: r.prehrajto_has_subs,
+            tmdb_poster_path: r.tmdb_poster_path,
+            sledujteto_primary_file_id: r.sledujteto_primary_file_id,
+        }
+    }
+}
+
+fn film_keyset(sort: CatalogSort, direction: SortDirection) -> Keyset {
+    use KeyType::{Int, Real, Text, Timestamp};
+    let desc = direction.is_desc();
+    let keys = Keyset::default();
+    let keys = match sort {
+        CatalogSort::Year => keys
+            .nullable("f.year", Int, desc)
+            .key("f.title", Text, false),
+        CatalogSort::Imdb => keys
+            .nullable("f.imdb_rating", Real, desc)
+            .key("f.title", Text, false),
+        CatalogSort::Tmdb => keys
+            .nullable("f.tmdb_rating", Real, desc)
+            .key("f.title", Text, false),
+        CatalogSort::Csfd => keys
+            .nullable("f.csfd_rating", Int, desc)
+            .key("f.title", Text, false),
+        CatalogSort::Title => keys
+            .key("f.title", Text, desc)
+            .nullable("f.year", Int, true),
+        CatalogSort::Added => keys
+            .nullable("f.added_at", Timestamp, desc)
+            .key("f.title", Text, false),
+    };
+    keys.key("f.id", Int, false)
+}
+
+/// `(title, original_title)` expressions a search matches against.
+fn title_exprs(mode: TitleMatch) -> (&'static str, &'static str) {
+    match mode {
+        TitleMatch::Title => ("f.title", "f.original_title"),
+        TitleMatch::TitleYear => (
+            "CONCAT_WS(' ', f.title, f.year::text)",
+            "CONCAT_WS(' ', f.original_title, f.year::text)",
+        ),
+    }
+}
+
+/// Diacritic-insensitive search predicate (#673): `unaccent()` on both the
+/// column and the bound pattern.
+fn title_predicate(mode: TitleMatch, ph: &str) -> String {
+    let (title, original) = title_exprs(mode);
+    format!("(unaccent({title}) ILIKE unaccent({ph}) OR unaccent({original}) ILIKE unaccent({ph}))")
+}
+
+/// Same shape as [`title_predicate`] without `unaccent()` — matches only
+/// when the column literally contains the user's diacritics. Used in ORDER
+/// BY to push diacritic-exact hits in front of unaccent-only hits.
+fn raw_title_predicate(mode: TitleMatch, ph: &str) -> String {
+    let (title, original) = title_exprs(mode);
+    format!("({title} ILIKE {ph} OR {original} ILIKE {ph})")
+}
+
+/// Legacy `jazyk=` filter over the `audio_langs` / `subtitle_langs` rollup
+/// arrays, which a trigger on `video_sources` keeps current (#607 / #611).
+fn local_language_predicate(lang: LocalLanguage) -> &'static str {
+    match lang {
+        LocalLanguage::Dubbed => "f.audio_langs && ARRAY['cs','sk']::TEXT[]",
+        LocalLanguage::Subtitled => "cardinality(f.subtitle_langs) > 0",
+        LocalLanguage::DubbedOrSubtitled => {
+            "(f.audio_langs && ARRAY['cs','sk']::TEXT[] \
+              OR cardinality(f.subtitle_langs) > 0)"
+        }
+    }
+}
+
+/// WHERE predicates and ORDER BY keys for a film listing.
+pub(crate) fn film_predicates(query: &FilmListQuery) -> (Predicates, Keyset) {
+    let filter = &query.filter;
+    let mut p = Predicates::default();
+    let mut keys = film_keyset(query.sort, query.direction);
+
+    if let Some(search) = &filter.search {
+        let ph = p.bind(Bind::Text(format!("%{}%", search.term)));
+        p.push(title_predicate(search.mode, &ph));
+        keys.prepend(
+            format!(
+                "(CASE WHEN {} THEN 0 ELSE 1 END)",
+                raw_title_predicate(search.mode, &ph)
+            ),
+            KeyType::Int,
+        );
+    }
+    push_genre_predicates(&mut p, &filter.genres, &FILM_GENRES);
+    if let Some(year) = filter.year {
+        let ph = p.bind(Bind::SmallInt(year));
+        p.push(format!("f.year = {ph}"));
+    }
+    if let Some(lang) = filter.local_language {
+        p.push(local_language_predicate(lang));
+    }
+    // `@>` asks "is every selected lang in the film's rollup array" — AND
+    // semantics per #612. GIN on `audio_langs` makes it a bitmap intersection.
+    if !filter.audio_langs.is_empty() {
+        let ph = p.bind(Bind::TextArray(filter.audio_langs.clone()));
+        p.push(format!("f.audio_langs @> {ph}::TEXT[]"));
+    }
+    match &filter.subtitles {
+        Some(SubtitleFilter::Any) => p.push("cardinality(f.subtitle_langs) > 0"),
+        Some(SubtitleFilter::Languages(langs)) => {
+            let ph = p.bind(Bind::TextArray(langs.clone()));
+            p.push(format!("f.subtitle_langs @> {ph}::TEXT[]"));
+        }
+        None => {}
+    }
+    if !filter.include_unavailable {
+        p.push(FILM_HAS_SOURCE_PREDICATE);
+    }
+    if let Some(votes) = votes_predicate("f", query.sort) {
+        p.push(votes);
+    }
+    (p, keys)
+}
+
+impl FilmRepository for PgFilmRepository {
+    type Error = sqlx::Error;
+
+    async fn list(
+        &self,
+        query: &FilmListQuery,
+    ) -> Result<(Vec<FilmRecord>, PageCursors), Self::Error> {
+        let (mut p, keys) = film_predicates(query);
+        let plan = keys.page(&mut p, &query.paging);
+        let sql = format!(
+            "SELECT {FILM_COLUMNS}, {} FROM {FILM_FROM} {} ORDER BY {} {}",
+            keys.select(),
+            p.where_clause(),
+            plan.order,
+            plan.limit
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, Keyed<FilmRow>>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(keyed_page(rows, plan.reversed))
+    }
+
+    async fn count(&self, query: &FilmListQuery) -> Result<i64, Self::Error> {
+        let (p, _) = film_predicates(query);
+        let sql = format!("SELECT count(*) FROM films f {}", p.where_clause());
+        p.apply_scalar(sqlx::query_scalar::<_, i64>(&sql))
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<FilmRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, FilmRow>(&format!(
+            "SELECT {FILM_COLUMNS} FROM {FILM_FROM} WHERE f.slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(FilmRecord::from))
+    }
+
+    async fn find_genre(&self, slug: &str) -> Result<Option<GenreRecord>, Self::Error> {
+        let row =
+            sqlx::query_as::<_, GenreRow>("SELECT id, slug, name_cs FROM genres WHERE slug = $1")
+                .bind(slug)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(GenreRecord::from))
+    }
+
+    async fn genres(&self) -> Result<Vec<GenreRecord>, Self::Error> {
+        let rows = sqlx::query_as::<_, GenreRow>(
+            "SELECT g.id, g.slug, g.name_cs \
+             FROM genres g \
+             JOIN film_genres fg ON g.id = fg.genre_id \
+             GROUP BY g.id, g.slug, g.name_cs \
+             ORDER BY g.name_cs",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(GenreRecord::from).collect())
+    }
+
+    async fn genres_of(&self, film_ids: &[i32]) -> Result<Vec<(i32, GenreRecord)>, Self::Error> {
+        if film_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query_as::<_, OwnedGenreRow>(
+            "SELECT fg.film_id AS owner_id, g.id, g.slug, g.name_cs \
+             FROM film_genres fg JOIN genres g ON g.id = fg.genre_id \
+             WHERE fg.film_id = ANY($1) \
+             ORDER BY fg.film_id, g.name_cs",
+        )
+        .bind(film_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn cast(&self, film_id: i32, limit: i64) -> Result<Vec<PersonCredit>, Self::Error> {
+        let rows = sqlx::query_as::<_, PersonCreditRow>(&format!(
+            "SELECT {} FROM people p JOIN film_actors fa ON fa.person_id = p.id \
+             WHERE fa.film_id = $1 ORDER BY fa.order_index LIMIT $2",
+            person_columns("fa.character_name")
+        ))
+        .bind(film_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(PersonCredit::from).collect())
+    }
+
+    async fn directors(&self, film_id: i32) -> Result<Vec<PersonCredit>, Self::Error> {
+        let rows = sqlx::query_as::<_, PersonCreditRow>(&format!(
+            "SELECT {} FROM people p JOIN film_directors fd ON fd.person_id = p.id \
+             WHERE fd.film_id = $1 ORDER BY p.name",
+            person_columns("NULL::varchar")
+        ))
+        .bind(film_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(PersonCredit::from).collect())
+    }
+
+    async fn suggest(&self, search: &TitleSearch) -> Result<Vec<TitleSuggestion>, Self::Error> {
+        let sql = match search.mode {
+            TitleMatch::Title => suggest_sql("films", "year", "title", "original_title"),
+            TitleMatch::TitleYear => suggest_sql(
+                "films",
+                "year",
+                "CONCAT_WS(' ', title, year::text)",
+                "CONCAT_WS(' ', original_title, year::text)",
+            ),
+        };
+        let rows = sqlx::query_as::<_, SuggestionRow>(&sql)
+            .bind(format!("%{}%", search.term))
+            .bind(format!("{}%", search.term))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(TitleSuggestion::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cr_domain::catalog::{FilmFilter, Paging};
+
+    #[test]
+    fn predicate_wraps_columns_in_unaccent() {
+        // Both modes must run unaccent() on the column AND on the bound
+        // pattern — that's how diacritics-insensitive matching works
+        // (#673). Lock the shape so we don't accidentally drop the
+        // wrapper from one side of an ILIKE in a future refactor.
+        let primary = title_predicate(TitleMatch::Title, "$1");
+        assert!(
+            primary.contains("unaccent(f.title) ILIKE unaccent($1)"),
+            "{primary}"
+        );
+        assert!(
+            primary.contains("unaccent(f.original_title) ILIKE unaccent($1)"),
+            "{primary}"
+        );
+
+        let title_year = title_predicate(TitleMatch::TitleYear, "$1");
+        assert!(
+            title_year
+                .contains("unaccent(CONCAT_WS(' ', f.title, f.year::text)) ILIKE unaccent($1)"),
+            "{title_year}"
+        );
+        assert!(
+            title_year.contains(
+                "unaccent(CONCAT_WS(' ', f.original_title, f.year::text)) ILIKE unaccent($1)"
+            ),
+            "{title_year}"
+        );
+    }
+
+    #[test]
+    fn raw_predicate_does_not_use_unaccent() {
+        // The raw predicate is the ORDER BY tiebreaker that keeps
+        // diacritic-exact hits in front. If unaccent() leaks in here,
+        // every row becomes "exact" and the priority bucket collapses.
+        let primary = raw_title_predicate(TitleMatch::Title, "$1");
+        assert!(!primary.contains("unaccent"), "{primary}");
+        assert!(primary.contains("f.title ILIKE $1"), "{primary}");
+        assert!(primary.contains("f.original_title ILIKE $1"), "{primary}");
+
+        let title_year = raw_title_predicate(TitleMatch::TitleYear, "$1");
+        assert!(!title_year.contains("unaccent"), "{title_year}");
+        assert!(
+            title_year.contains("CONCAT_WS(' ', f.title, f.year::text) ILIKE $1"),
+            "{title_year}"
+        );
+    }
+
+    #[test]
+    fn local_language_dub_matches_audio_langs_rollup() {
+        let sql = local_language_predicate(LocalLanguage::Dubbed);
+        assert!(
+            sql.contains("f.audio_langs && ARRAY['cs','sk']"),
+            "sql = {sql}"
+        );
+        assert!(!sql.contains("subtitle_langs"), "sql = {sql}");
+    }
+
+    #[test]
+    fn local_language_sub_checks_subtitle_langs_rollup() {
+        let sql = local_language_predicate(LocalLanguage::Subtitled);
+        assert!(sql.contains("cardinality(f.subtitle_langs)"), "sql = {sql}");
+        assert!(!sql.contains("audio_langs"), "sql = {sql}");
+    }
+
+    #[test]
+    fn local_language_dub_and_sub_unions_both_rollups() {
+        let sql = local_language_predicate(LocalLanguage::DubbedOrSubtitled);
+        assert!(
+            sql.contains("f.audio_langs && ARRAY['cs','sk']"),
+            "sql = {sql}"
+        );
+        assert!(sql.contains("cardinality(f.subtitle_langs)"), "sql = {sql}");
+    }
+
+    #[test]
+    fn search_ranks_exact_diacritics_first_and_keeps_source_gate() {
+        let query = FilmListQuery {
+            filter: FilmFilter {
+                search: Some(TitleSearch::new("laska")),
+                ..FilmFilter::default()
+            },
+            sort: CatalogSort::Imdb,
+            direction: SortDirection::Desc,
+            paging: Paging::new(None, 24),
+        };
+        let (p, keys) = film_predicates(&query);
+        let sql = p.where_clause();
+        let order = keys.order_by(false);
+        assert!(sql.contains(FILM_HAS_SOURCE_PREDICATE), "{sql}");
+        assert!(sql.ends_with("f.imdb_votes >= 500"), "{sql}");
+        assert!(order.starts_with("(CASE WHEN (f.title ILIKE $1"), "{order}");
+        assert!(order.ends_with("f.imdb_rating DESC NULLS LAST, f.title ASC, f.id ASC"));
+    }
+
+    #[test]
+    fn include_unavailable_drops_source_gate() {
+        let query = FilmListQuery {
+            filter: FilmFilter {
+                include_unavailable: true,
+                ..FilmFilter::default()
+            },
+            sort: CatalogSort::Added,
+            direction: SortDirection::Desc,
+            paging: Paging::new(None, 24),
+        };
+        let (p, _) = film_predicates(&query);
+        assert!(!p.where_clause().contains(FILM_HAS_SOURCE_PREDICATE));
+    }
+}
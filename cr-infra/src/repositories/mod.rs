@@ -3,18 +3,43 @@
 //! Each struct wraps a `sqlx::PgPool` and implements the corresponding
 //! trait from `cr_domain::repository`.
 
+mod calendar;
+mod catalog_sql;
+mod collection;
+mod episode;
+mod export;
+mod feed;
+mod film;
 mod landmark;
 mod municipality;
 mod orp;
 mod photo;
 mod pool;
 mod region;
+mod search;
+mod series;
+mod similar;
+mod suggest;
+mod tv_show;
+mod video_job;
 mod video_library;
 
+pub use calendar::PgEpisodeCalendarRepository;
+pub use collection::PgFilmCollectionRepository;
+pub use episode::PgEpisodeRepository;
+pub use export::PgExportRepository;
+pub use feed::PgFeedRepository;
+pub use film::PgFilmRepository;
 pub use landmark::PgLandmarkRepository;
 pub use municipality::PgMunicipalityRepository;
 pub use orp::PgOrpRepository;
 pub use photo::PgPhotoRepository;
 pub use pool::PgPoolRepository;
 pub use region::PgRegionRepository;
+pub use search::PgSearchDocumentRepository;
+pub use series::PgSeriesRepository;
+pub use similar::PgSimilarTitlesRepository;
+pub use suggest::PgSuggestSourceRepository;
+pub use tv_show::PgTvShowRepository;
+pub use video_job::PgVideoJobRepository;
 pub use video_library::PgVideoRepository;
@@ -7,6 +7,16 @@ pub(crate) const MUNICIPALITY_COLUMNS: &str = "id, name, slug, municipality_code
     latitude, longitude, wikipedia_url, official_website, coat_of_arms_ext, \
     flag_ext, population, elevation";
 
+/// Site-relative municipality URL: `/{orp}/{obec}/`, or `/{orp}/` for the
+/// ORP's seat, which shares its slug and lives at the ORP URL.
+pub(crate) fn municipality_path(orp_slug: &str, municipality_slug: &str) -> String {
+    if municipality_slug == orp_slug {
+        format!("/{orp_slug}/")
+    } else {
+        format!("/{orp_slug}/{municipality_slug}/")
+    }
+}
+
 /// PostgreSQL implementation of [`MunicipalityRepository`].
 pub struct PgMunicipalityRepository {
     pool: sqlx::PgPool,
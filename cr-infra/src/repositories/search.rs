@@ -0,0 +1,135 @@
+use cr_domain::catalog::CatalogKind;
+use cr_domain::repository::{SearchDocument, SearchDocumentRepository};
+
+/// Top-billed actors indexed per title. Further down the credits are
+/// extras nobody searches for, and they'd bloat the index.
+const CAST_LIMIT: u32 = 10;
+
+/// PostgreSQL implementation of [`SearchDocumentRepository`]: films,
+/// series and TV pořady keyed by the shared `search_rev` cursor
+/// (migration 081).
+pub struct PgSearchDocumentRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgSearchDocumentRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SearchDocumentRow {
+    id: i32,
+    rev: i64,
+    slug: String,
+    title: String,
+    original_title: Option<String>,
+    year: Option<i16>,
+    people: Vec<String>,
+    description: Option<String>,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+    votes: i64,
+}
+
+impl SearchDocumentRow {
+    fn into_document(self, kind: CatalogKind) -> SearchDocument {
+        SearchDocument {
+            kind,
+            id: self.id,
+            rev: self.rev,
+            slug: self.slug,
+            title: self.title,
+            original_title: self.original_title,
+            year: self.year,
+            people: self.people,
+            description: self.description,
+            tmdb_rating: self.tmdb_rating,
+            imdb_rating: self.imdb_rating,
+            votes: self.votes,
+        }
+    }
+}
+
+/// Document query over `table`. `credits` names the `(directors, actors,
+/// owner column)` tables for catalogs that have TMDB credits; `$1` is the
+/// `search_rev` cursor.
+fn documents_sql(table: &str, year: &str, credits: Option<(&str, &str, &str)>) -> String {
+    let people = match credits {
+        Some((directors, actors, owner)) => format!(
+            "ARRAY(SELECT p.name::text FROM {directors} d JOIN people p ON p.id = d.person_id \
+                   WHERE d.{owner} = t.id ORDER BY p.name) \
+             || ARRAY(SELECT p.name::text FROM {actors} a JOIN people p ON p.id = a.person_id \
+                      WHERE a.{owner} = t.id ORDER BY a.order_index LIMIT {CAST_LIMIT})"
+        ),
+        None => "'{}'::text[]".to_string(),
+    };
+    format!(
+        "SELECT t.id, t.search_rev AS rev, t.slug, t.title, t.original_title, \
+                t.{year} AS year, {people} AS people, t.description, \
+                t.tmdb_rating, t.imdb_rating, \
+                (COALESCE(t.tmdb_vote_count, 0) + COALESCE(t.imdb_votes, 0))::BIGINT AS votes \
+         FROM {table} t \
+         WHERE t.search_rev > $1"
+    )
+}
+
+impl SearchDocumentRepository for PgSearchDocumentRepository {
+    type Error = sqlx::Error;
+
+    async fn changed_since(&self, since: i64) -> Result<Vec<SearchDocument>, Self::Error> {
+        let sources = [
+            (
+                CatalogKind::Film,
+                documents_sql(
+                    "films",
+                    "year",
+                    Some(("film_directors", "film_actors", "film_id")),
+                ),
+            ),
+            (
+                CatalogKind::Series,
+                documents_sql(
+                    "series",
+                    "first_air_year",
+                    Some(("series_directors", "series_actors", "series_id")),
+                ),
+            ),
+            (
+                CatalogKind::TvShow,
+                documents_sql("tv_shows", "first_air_year", None),
+            ),
+        ];
+        let mut docs = Vec::new();
+        for (kind, sql) in sources {
+            let rows = sqlx::query_as::<_, SearchDocumentRow>(&sql)
+                .bind(since)
+                .fetch_all(&self.pool)
+                .await?;
+            docs.extend(rows.into_iter().map(|r| r.into_document(kind)));
+        }
+        Ok(docs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credits_are_optional() {
+        let films = documents_sql(
+            "films",
+            "year",
+            Some(("film_directors", "film_actors", "film_id")),
+        );
+        assert!(films.contains("FROM film_actors a"), "{films}");
+        assert!(films.contains("a.film_id = t.id"), "{films}");
+        assert!(films.contains("t.year AS year"), "{films}");
+
+        let shows = documents_sql("tv_shows", "first_air_year", None);
+        assert!(shows.contains("'{}'::text[] AS people"), "{shows}");
+        assert!(!shows.contains("people p"), "{shows}");
+    }
+}
@@ -0,0 +1,392 @@
+use cr_domain::catalog::{AudioCoverage, PageCursors, SeriesListQuery, ShowListing};
+use cr_domain::repository::{
+    EpisodeCard, GenreRecord, PersonCredit, SeriesRecord, SeriesRepository, TitleSuggestion,
+};
+
+use super::catalog_sql::{
+    Bind, GenreLink, GenreRow, KeyType, Keyed, Keyset, OwnedGenreRow, PersonCreditRow, Predicates,
+    SuggestionRow, keyed_page, person_columns, push_genre_predicates, show_keyset, show_search,
+    suggest_sql, votes_predicate,
+};
+
+/// SELECT column list for `series` rows, shared with the TV pořady
+/// repository (`tv_shows` has the same shape).
+pub(crate) const SHOW_COLUMNS: &str = "s.id, s.title, s.slug, s.first_air_year, \
+    s.last_air_year, s.description, s.original_title, s.tmdb_rating, s.imdb_rating, \
+    s.csfd_rating, s.season_count, s.episode_count, s.tmdb_poster_path";
+
+const SERIES_GENRES: GenreLink = GenreLink {
+    id: "s.id",
+    table: "series_genres",
+    fk: "series_id",
+};
+
+/// PostgreSQL implementation of [`SeriesRepository`].
+pub struct PgSeriesRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgSeriesRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct ShowRow {
+    pub(crate) id: i32,
+    pub(crate) title: String,
+    pub(crate) slug: String,
+    pub(crate) first_air_year: Option<i16>,
+    pub(crate) last_air_year: Option<i16>,
+    pub(crate) description: Option<String>,
+    pub(crate) original_title: Option<String>,
+    pub(crate) tmdb_rating: Option<f32>,
+    pub(crate) imdb_rating: Option<f32>,
+    pub(crate) csfd_rating: Option<i16>,
+    pub(crate) season_count: Option<i16>,
+    pub(crate) episode_count: Option<i16>,
+    pub(crate) tmdb_poster_path: Option<String>,
+}
+
+impl From<ShowRow> for SeriesRecord {
+    fn from(r: ShowRow) -> Self {
+        Self {
+            id: r.id,
+            title: r.title,
+            slug: r.slug,
+            first_air_year: r.first_air_year,
+            last_air_year: r.last_air_year,
+            description: r.description,
+            original_title: r.original_title,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+            csfd_rating: r.csfd_rating,
+            season_count: r.season_count,
+            episode_count: r.episode_count,
+            tmdb_poster_path: r.tmdb_poster_path,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EpisodeCardRow {
+    id: i32,
+    series_id: i32,
+    series_slug: String,
+    series_title: String,
+    series_original_title: Option<String>,
+    series_first_air_year: Option<i16>,
+    series_tmdb_rating: Option<f32>,
+    series_imdb_rating: Option<f32>,
+    series_csfd_rating: Option<i16>,
+    series_description: Option<String>,
+    season: i16,
+    episode: i16,
+    has_subtitles: Option<bool>,
+    has_dub: Option<bool>,
+    episode_slug: Option<String>,
+    episode_name: Option<String>,
+}
+
+impl From<EpisodeCardRow> for EpisodeCard {
+    fn from(r: EpisodeCardRow) -> Self {
+        Self {
+            id: r.id,
+            series_id: r.series_id,
+            series_slug: r.series_slug,
+            series_title: r.series_title,
+            series_original_title: r.series_original_title,
+            series_first_air_year: r.series_first_air_year,
+            series_tmdb_rating: r.series_tmdb_rating,
+            series_imdb_rating: r.series_imdb_rating,
+            series_csfd_rating: r.series_csfd_rating,
+            series_description: r.series_description,
+            season: r.season,
+            episode: r.episode,
+            has_subtitles: r.has_subtitles,
+            has_dub: r.has_dub,
+            episode_slug: r.episode_slug,
+            episode_name: r.episode_name,
+        }
+    }
+}
+
+/// Series with at least one playable episode — the latest-episode grid
+/// can't render a card for TMDB-only stubs, so its count mustn't include
+/// them either. Reads the `series_availability` summary (migration 087).
+const SERIES_PLAYABLE_PREDICATE: &str = "EXISTS (SELECT 1 FROM series_availability sav \
+     WHERE sav.series_id = s.id AND sav.playable_episodes > 0)";
+
+/// Order of the latest-episode grid: by when each series' newest playable
+/// episode was added.
+fn latest_keyset(query: &SeriesListQuery) -> Keyset {
+    Keyset::default()
+        .key(
+            "sa.latest_episode_at",
+            KeyType::Timestamp,
+            query.direction.is_desc(),
+        )
+        .key("s.id", KeyType::Int, false)
+}
+
+/// WHERE predicates and ORDER BY keys for a series listing. The keys only
+/// apply to the `Search` and `Sorted` listings.
+pub(crate) fn series_predicates(query: &SeriesListQuery) -> (Predicates, Keyset) {
+    let filter = &query.filter;
+    let mut p = Predicates::default();
+    let mut keys = show_keyset(query.sort, query.direction);
+
+    if let Some(term) = &filter.search {
+        let (predicate, rank) = show_search(&mut p, term);
+        p.push(predicate);
+        keys.prepend(rank, KeyType::Int);
+    } else {
+        // Title search spans every genre and year; only the audio filter
+        // and the vote threshold narrow it.
+        push_genre_predicates(&mut p, &filter.genres, &SERIES_GENRES);
+        if let Some(year) = filter.year {
+            let ph = p.bind(Bind::SmallInt(year));
+            p.push(format!("s.first_air_year = {ph}"));
+        }
+    }
+    // #716 — language is a property of each episode's sources; the two
+    // rollup arrays answer "some episode has it" and "every episode has it".
+    if !filter.audio_langs.is_empty() {
+        let column = match filter.audio_coverage {
+            AudioCoverage::AnyEpisode => "s.audio_langs_any",
+            AudioCoverage::EveryEpisode => "s.audio_langs_all",
+        };
+        let ph = p.bind(Bind::TextArray(filter.audio_langs.clone()));
+        p.push(format!("{column} @> {ph}::TEXT[]"));
+    }
+    if let Some(votes) = votes_predicate("s", query.sort) {
+        p.push(votes);
+    }
+    (p, keys)
+}
+
+impl SeriesRepository for PgSeriesRepository {
+    type Error = sqlx::Error;
+
+    async fn list(
+        &self,
+        query: &SeriesListQuery,
+    ) -> Result<(Vec<SeriesRecord>, PageCursors), Self::Error> {
+        let (mut p, keys) = series_predicates(query);
+        let plan = keys.page(&mut p, &query.paging);
+        let sql = format!(
+            "SELECT {SHOW_COLUMNS}, {} FROM series s {} ORDER BY {} {}",
+            keys.select(),
+            p.where_clause(),
+            plan.order,
+            plan.limit
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, Keyed<ShowRow>>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(keyed_page(rows, plan.reversed))
+    }
+
+    async fn latest_episodes(
+        &self,
+        query: &SeriesListQuery,
+    ) -> Result<(Vec<EpisodeCard>, PageCursors), Self::Error> {
+        let (mut p, _) = series_predicates(query);
+        let keys = latest_keyset(query);
+        let plan = keys.page(&mut p, &query.paging);
+        // The summary names each series' newest playable episode, so the
+        // grid is a walk over `idx_series_availability_latest` joined to
+        // the two rows it needs, with no per-series episode lookup.
+        let sql = format!(
+            "SELECT latest.id, \
+                s.id AS series_id, \
+                s.slug AS series_slug, \
+                s.title AS series_title, \
+                s.original_title AS series_original_title, \
+                s.first_air_year AS series_first_air_year, \
+                s.tmdb_rating AS series_tmdb_rating, \
+                s.imdb_rating AS series_imdb_rating, \
+                s.csfd_rating AS series_csfd_rating, \
+                s.description AS series_description, \
+                latest.season, latest.episode, latest.has_subtitles, latest.has_dub, \
+                latest.slug AS episode_slug, \
+                latest.episode_name, \
+                {} \
+             FROM series s \
+             JOIN series_availability sa \
+               ON sa.series_id = s.id AND sa.playable_episodes > 0 \
+             JOIN episodes latest ON latest.id = sa.latest_episode_id \
+             {} \
+             ORDER BY {} {}",
+            keys.select(),
+            p.where_clause(),
+            plan.order,
+            plan.limit
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, Keyed<EpisodeCardRow>>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(keyed_page(rows, plan.reversed))
+    }
+
+    async fn count(&self, query: &SeriesListQuery) -> Result<i64, Self::Error> {
+        let (mut p, _) = series_predicates(query);
+        if query.listing() == ShowListing::LatestEpisodes {
+            p.push(SERIES_PLAYABLE_PREDICATE);
+        }
+        let sql = format!("SELECT count(*) FROM series s {}", p.where_clause());
+        p.apply_scalar(sqlx::query_scalar::<_, i64>(&sql))
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<SeriesRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, ShowRow>(&format!(
+            "SELECT {SHOW_COLUMNS} FROM series s WHERE s.slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(SeriesRecord::from))
+    }
+
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<SeriesRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, ShowRow>(&format!(
+            "SELECT {SHOW_COLUMNS} FROM series s WHERE s.old_slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(SeriesRecord::from))
+    }
+
+    async fn find_genre(&self, slug: &str) -> Result<Option<GenreRecord>, Self::Error> {
+        let row =
+            sqlx::query_as::<_, GenreRow>("SELECT id, slug, name_cs FROM genres WHERE slug = $1")
+                .bind(slug)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(GenreRecord::from))
+    }
+
+    async fn genres(&self) -> Result<Vec<GenreRecord>, Self::Error> {
+        let rows = sqlx::query_as::<_, GenreRow>(
+            "SELECT g.id, g.slug, g.name_cs FROM genres g \
+             JOIN series_genres sg ON g.id = sg.genre_id \
+             GROUP BY g.id, g.slug, g.name_cs ORDER BY g.name_cs",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(GenreRecord::from).collect())
+    }
+
+    async fn genres_of(&self, series_ids: &[i32]) -> Result<Vec<(i32, GenreRecord)>, Self::Error> {
+        if series_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query_as::<_, OwnedGenreRow>(
+            "SELECT sg.series_id AS owner_id, g.id, g.slug, g.name_cs \
+             FROM series_genres sg JOIN genres g ON g.id = sg.genre_id \
+             WHERE sg.series_id = ANY($1) \
+             ORDER BY sg.series_id, g.name_cs",
+        )
+        .bind(series_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn cast(&self, series_id: i32, limit: i64) -> Result<Vec<PersonCredit>, Self::Error> {
+        let rows = sqlx::query_as::<_, PersonCreditRow>(&format!(
+            "SELECT {} FROM people p JOIN series_actors sa ON sa.person_id = p.id \
+             WHERE sa.series_id = $1 ORDER BY sa.order_index LIMIT $2",
+            person_columns("sa.character_name")
+        ))
+        .bind(series_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(PersonCredit::from).collect())
+    }
+
+    async fn creators(&self, series_id: i32) -> Result<Vec<PersonCredit>, Self::Error> {
+        let rows = sqlx::query_as::<_, PersonCreditRow>(&format!(
+            "SELECT {} FROM people p JOIN series_directors sd ON sd.person_id = p.id \
+             WHERE sd.series_id = $1 ORDER BY p.name",
+            person_columns("NULL::varchar")
+        ))
+        .bind(series_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(PersonCredit::from).collect())
+    }
+
+    async fn suggest(&self, term: &str) -> Result<Vec<TitleSuggestion>, Self::Error> {
+        let rows = sqlx::query_as::<_, SuggestionRow>(&suggest_sql(
+            "series",
+            "first_air_year",
+            "title",
+            "original_title",
+        ))
+        .bind(format!("%{term}%"))
+        .bind(format!("{term}%"))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(TitleSuggestion::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cr_domain::catalog::{CatalogSort, GenreFilter, Paging, SeriesFilter, SortDirection};
+
+    fn query(filter: SeriesFilter, sort: CatalogSort) -> SeriesListQuery {
+        SeriesListQuery {
+            filter,
+            sort,
+            direction: SortDirection::Desc,
+            paging: Paging::new(None, 24),
+        }
+    }
+
+    #[test]
+    fn audio_coverage_picks_rollup_column() {
+        let mut filter = SeriesFilter {
+            audio_langs: vec!["cs".into()],
+            ..SeriesFilter::default()
+        };
+        let (p, _) = series_predicates(&query(filter.clone(), CatalogSort::Added));
+        assert!(p.where_clause().contains("s.audio_langs_any @> $1::TEXT[]"));
+
+        filter.audio_coverage = AudioCoverage::EveryEpisode;
+        let (p, _) = series_predicates(&query(filter, CatalogSort::Added));
+        assert!(p.where_clause().contains("s.audio_langs_all @> $1::TEXT[]"));
+    }
+
+    #[test]
+    fn search_ignores_genre_and_year_and_ranks_exact_matches_first() {
+        let filter = SeriesFilter {
+            search: Some("cernobyl".into()),
+            genres: GenreFilter::parse(Some("drama"), None, None),
+            year: Some(2019),
+            audio_langs: vec!["cs".into()],
+            ..SeriesFilter::default()
+        };
+        let (p, keys) = series_predicates(&query(filter, CatalogSort::Tmdb));
+        let sql = p.where_clause();
+        let order = keys.order_by(false);
+        assert!(
+            sql.contains("unaccent(s.title) ILIKE unaccent($1)"),
+            "{sql}"
+        );
+        assert!(!sql.contains("series_genres"), "{sql}");
+        assert!(!sql.contains("first_air_year"), "{sql}");
+        assert!(sql.contains("s.audio_langs_any @>"), "{sql}");
+        assert!(sql.ends_with("s.tmdb_vote_count >= 50"), "{sql}");
+        assert!(order.starts_with("(CASE WHEN s.title ILIKE $1"), "{order}");
+    }
+}
@@ -0,0 +1,253 @@
+use cr_domain::catalog::CatalogKind;
+use cr_domain::repository::{
+    SimilarTitle, SimilarTitles, SimilarTitlesRepository, SimilarityProfile,
+};
+
+/// Top-billed actors compared per title; further down the credits two
+/// unrelated films share bit-part actors far too often.
+const PROFILE_CAST_LIMIT: u32 = 8;
+
+/// Rows per INSERT when replacing a kind — keeps the UNNEST arrays
+/// comfortably small while 28 k films × 20 matches go in.
+const INSERT_CHUNK: usize = 20_000;
+
+/// Catalog tables behind one similar-titles `kind`.
+struct Source {
+    kind: &'static str,
+    table: &'static str,
+    year: &'static str,
+    genres: &'static str,
+    directors: &'static str,
+    actors: &'static str,
+    owner: &'static str,
+    /// Extra condition on `t` for both profiles and carousel cards.
+    visible: &'static str,
+}
+
+const FILMS: Source = Source {
+    kind: "film",
+    table: "films",
+    year: "year",
+    genres: "film_genres",
+    directors: "film_directors",
+    actors: "film_actors",
+    owner: "film_id",
+    // Same live-source gate as the film listing: never recommend a film
+    // that can't be played.
+    visible: "EXISTS (SELECT 1 FROM video_sources vs WHERE vs.film_id = t.id AND vs.is_alive)",
+};
+
+const SERIES: Source = Source {
+    kind: "series",
+    table: "series",
+    year: "first_air_year",
+    genres: "series_genres",
+    directors: "series_directors",
+    actors: "series_actors",
+    owner: "series_id",
+    visible: "TRUE",
+};
+
+fn source(kind: CatalogKind) -> Option<&'static Source> {
+    match kind {
+        CatalogKind::Film => Some(&FILMS),
+        CatalogKind::Series => Some(&SERIES),
+        CatalogKind::TvShow => None,
+    }
+}
+
+fn profiles_sql(s: &Source) -> String {
+    let Source {
+        table,
+        year,
+        genres,
+        directors,
+        actors,
+        owner,
+        visible,
+        ..
+    } = s;
+    format!(
+        "SELECT t.id, t.{year} AS year, t.tmdb_rating, t.imdb_rating, t.csfd_rating, \
+                ARRAY(SELECT genre_id FROM {genres} WHERE {owner} = t.id ORDER BY genre_id) \
+                    AS genre_ids, \
+                ARRAY(SELECT person_id FROM {directors} WHERE {owner} = t.id ORDER BY person_id) \
+                    AS director_ids, \
+                ARRAY(SELECT person_id FROM (SELECT person_id FROM {actors} \
+                                             WHERE {owner} = t.id \
+                                             ORDER BY order_index LIMIT {PROFILE_CAST_LIMIT}) top \
+                      ORDER BY person_id) AS actor_ids \
+         FROM {table} t \
+         WHERE {visible}"
+    )
+}
+
+fn similar_to_sql(s: &Source) -> String {
+    let Source {
+        kind,
+        table,
+        year,
+        visible,
+        ..
+    } = s;
+    format!(
+        "SELECT t.slug, t.title, t.{year} AS year, t.tmdb_rating, t.imdb_rating \
+         FROM similar_titles st \
+         JOIN {table} t ON t.id = st.similar_id \
+         WHERE st.kind = '{kind}' AND st.title_id = $1 AND {visible} \
+         ORDER BY st.rank \
+         LIMIT $2"
+    )
+}
+
+#[derive(sqlx::FromRow)]
+struct ProfileRow {
+    id: i32,
+    year: Option<i16>,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+    csfd_rating: Option<i16>,
+    genre_ids: Vec<i32>,
+    director_ids: Vec<i32>,
+    actor_ids: Vec<i32>,
+}
+
+impl From<ProfileRow> for SimilarityProfile {
+    fn from(r: ProfileRow) -> Self {
+        Self {
+            id: r.id,
+            genre_ids: r.genre_ids,
+            director_ids: r.director_ids,
+            actor_ids: r.actor_ids,
+            year: r.year,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+            csfd_rating: r.csfd_rating,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SimilarTitleRow {
+    slug: String,
+    title: String,
+    year: Option<i16>,
+    tmdb_rating: Option<f32>,
+    imdb_rating: Option<f32>,
+}
+
+impl From<SimilarTitleRow> for SimilarTitle {
+    fn from(r: SimilarTitleRow) -> Self {
+        Self {
+            slug: r.slug,
+            title: r.title,
+            year: r.year,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+        }
+    }
+}
+
+/// PostgreSQL implementation of [`SimilarTitlesRepository`]
+/// (`similar_titles`, migration 082).
+pub struct PgSimilarTitlesRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgSimilarTitlesRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl SimilarTitlesRepository for PgSimilarTitlesRepository {
+    type Error = sqlx::Error;
+
+    async fn profiles(&self, kind: CatalogKind) -> Result<Vec<SimilarityProfile>, Self::Error> {
+        let Some(s) = source(kind) else {
+            return Ok(Vec::new());
+        };
+        let rows = sqlx::query_as::<_, ProfileRow>(&profiles_sql(s))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(SimilarityProfile::from).collect())
+    }
+
+    async fn replace(&self, kind: CatalogKind, sets: &[SimilarTitles]) -> Result<(), Self::Error> {
+        let Some(s) = source(kind) else {
+            return Ok(());
+        };
+        let rows: Vec<(i32, i16, i32, f32)> = sets
+            .iter()
+            .flat_map(|set| {
+                set.similar
+                    .iter()
+                    .enumerate()
+                    .map(move |(rank, &(id, score))| (set.title_id, rank as i16, id, score))
+            })
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM similar_titles WHERE kind = $1")
+            .bind(s.kind)
+            .execute(&mut *tx)
+            .await?;
+        for chunk in rows.chunks(INSERT_CHUNK) {
+            let title_ids: Vec<i32> = chunk.iter().map(|r| r.0).collect();
+            let ranks: Vec<i16> = chunk.iter().map(|r| r.1).collect();
+            let similar_ids: Vec<i32> = chunk.iter().map(|r| r.2).collect();
+            let scores: Vec<f32> = chunk.iter().map(|r| r.3).collect();
+            sqlx::query(
+                "INSERT INTO similar_titles (kind, title_id, rank, similar_id, score) \
+                 SELECT $1, * FROM UNNEST($2::int[], $3::smallint[], $4::int[], $5::real[])",
+            )
+            .bind(s.kind)
+            .bind(&title_ids)
+            .bind(&ranks)
+            .bind(&similar_ids)
+            .bind(&scores)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    async fn similar_to(
+        &self,
+        kind: CatalogKind,
+        title_id: i32,
+        limit: i64,
+    ) -> Result<Vec<SimilarTitle>, Self::Error> {
+        let Some(s) = source(kind) else {
+            return Ok(Vec::new());
+        };
+        let rows = sqlx::query_as::<_, SimilarTitleRow>(&similar_to_sql(s))
+            .bind(title_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(SimilarTitle::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn films_are_gated_on_a_live_source() {
+        // Both sides of the feature: a dead film neither gets nor appears
+        // in recommendations.
+        for sql in [profiles_sql(&FILMS), similar_to_sql(&FILMS)] {
+            assert!(sql.contains("vs.film_id = t.id AND vs.is_alive"), "{sql}");
+        }
+        assert!(similar_to_sql(&FILMS).contains("st.kind = 'film'"));
+        assert!(!profiles_sql(&SERIES).contains("video_sources"));
+    }
+
+    #[test]
+    fn tv_shows_are_not_covered() {
+        assert!(source(CatalogKind::TvShow).is_none());
+        assert!(profiles_sql(&SERIES).contains("series_genres WHERE series_id = t.id"));
+    }
+}
@@ -0,0 +1,193 @@
+use cr_domain::repository::{SuggestEntry, SuggestKind, SuggestSourceRepository};
+
+use super::film::FILM_HAS_SOURCE_PREDICATE;
+use super::municipality::municipality_path;
+
+/// PostgreSQL implementation of [`SuggestSourceRepository`].
+pub struct PgSuggestSourceRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgSuggestSourceRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TitleRow {
+    slug: String,
+    title: String,
+    year: Option<i16>,
+    weight: i64,
+}
+
+impl TitleRow {
+    /// `section` is the catalog's URL prefix (`filmy-online`).
+    fn into_entry(self, kind: SuggestKind, section: &str) -> SuggestEntry {
+        SuggestEntry {
+            kind,
+            context: self.year.map(|y| y.to_string()),
+            path: format!("/{section}/{}/", self.slug),
+            thumb_path: Some(format!("/{section}/{}.webp", self.slug)),
+            title: self.title,
+            weight: self.weight,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct MunicipalityRow {
+    slug: String,
+    name: String,
+    orp_slug: String,
+    region_name: String,
+    coat_of_arms_ext: Option<String>,
+    population: Option<i32>,
+}
+
+impl From<MunicipalityRow> for SuggestEntry {
+    fn from(r: MunicipalityRow) -> Self {
+        let thumb_path = r
+            .coat_of_arms_ext
+            .as_ref()
+            .map(|ext| format!("/{}/{}/znak-{}.{ext}", r.orp_slug, r.slug, r.slug));
+        Self {
+            kind: SuggestKind::Municipality,
+            path: municipality_path(&r.orp_slug, &r.slug),
+            title: r.name,
+            context: Some(r.region_name),
+            thumb_path,
+            weight: r.population.unwrap_or_default().into(),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct LandmarkRow {
+    slug: String,
+    name: String,
+    orp_slug: String,
+    municipality_slug: String,
+    municipality_name: String,
+    region_name: String,
+    photo_count: i16,
+}
+
+impl From<LandmarkRow> for SuggestEntry {
+    fn from(r: LandmarkRow) -> Self {
+        Self {
+            kind: SuggestKind::Landmark,
+            path: format!(
+                "{}{}/",
+                municipality_path(&r.orp_slug, &r.municipality_slug),
+                r.slug
+            ),
+            title: r.name,
+            context: Some(format!("{}, {}", r.municipality_name, r.region_name)),
+            thumb_path: None,
+            weight: r.photo_count.into(),
+        }
+    }
+}
+
+/// Series and TV pořad query; both list every row, sourced or not.
+fn shows_sql(table: &str) -> String {
+    format!(
+        "SELECT s.slug, s.title, s.first_air_year AS year, \
+                (COALESCE(s.tmdb_vote_count, 0) + COALESCE(s.imdb_votes, 0))::BIGINT AS weight \
+         FROM {table} s"
+    )
+}
+
+impl SuggestSourceRepository for PgSuggestSourceRepository {
+    type Error = sqlx::Error;
+
+    async fn suggest_entries(&self) -> Result<Vec<SuggestEntry>, Self::Error> {
+        // Films without a live source drop out of the listing, so they
+        // don't get suggested either. `FILM_HAS_SOURCE_PREDICATE` expects
+        // the alias `f`.
+        let films = format!(
+            "SELECT f.slug, f.title, f.year, \
+                    (COALESCE(f.tmdb_vote_count, 0) + COALESCE(f.imdb_votes, 0))::BIGINT AS weight \
+             FROM films f WHERE {FILM_HAS_SOURCE_PREDICATE}"
+        );
+        let titles = [
+            (SuggestKind::Film, "filmy-online", films),
+            (SuggestKind::Series, "serialy-online", shows_sql("series")),
+            (SuggestKind::TvShow, "tv-porady", shows_sql("tv_shows")),
+        ];
+        let mut entries = Vec::new();
+        for (kind, section, sql) in titles {
+            let rows = sqlx::query_as::<_, TitleRow>(&sql)
+                .fetch_all(&self.pool)
+                .await?;
+            entries.extend(rows.into_iter().map(|r| r.into_entry(kind, section)));
+        }
+
+        let municipalities = sqlx::query_as::<_, MunicipalityRow>(
+            "SELECT m.slug, m.name, o.slug AS orp_slug, r.name AS region_name, \
+                    m.coat_of_arms_ext, m.population \
+             FROM municipalities m \
+             JOIN orp o ON o.id = m.orp_id \
+             JOIN districts d ON d.id = o.district_id \
+             JOIN regions r ON r.id = d.region_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        entries.extend(municipalities.into_iter().map(SuggestEntry::from));
+
+        // Landmarks without a municipality have no page of their own.
+        let landmarks = sqlx::query_as::<_, LandmarkRow>(
+            "SELECT l.slug, l.name, o.slug AS orp_slug, m.slug AS municipality_slug, \
+                    m.name AS municipality_name, r.name AS region_name, l.photo_count \
+             FROM landmarks l \
+             JOIN municipalities m ON m.id = l.municipality_id \
+             JOIN orp o ON o.id = m.orp_id \
+             JOIN districts d ON d.id = o.district_id \
+             JOIN regions r ON r.id = d.region_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        entries.extend(landmarks.into_iter().map(SuggestEntry::from));
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_entries_link_to_their_pages() {
+        let landmark = SuggestEntry::from(LandmarkRow {
+            slug: "hrad-kost".to_string(),
+            name: "Hrad Kost".to_string(),
+            orp_slug: "sobotka".to_string(),
+            municipality_slug: "libosovice".to_string(),
+            municipality_name: "Libošovice".to_string(),
+            region_name: "Královéhradecký kraj".to_string(),
+            photo_count: 4,
+        });
+        assert_eq!(landmark.path, "/sobotka/libosovice/hrad-kost/");
+        assert_eq!(
+            landmark.context.as_deref(),
+            Some("Libošovice, Královéhradecký kraj")
+        );
+
+        let seat = SuggestEntry::from(MunicipalityRow {
+            slug: "sobotka".to_string(),
+            name: "Sobotka".to_string(),
+            orp_slug: "sobotka".to_string(),
+            region_name: "Královéhradecký kraj".to_string(),
+            coat_of_arms_ext: Some("svg".to_string()),
+            population: Some(2300),
+        });
+        assert_eq!(seat.path, "/sobotka/");
+        assert_eq!(
+            seat.thumb_path.as_deref(),
+            Some("/sobotka/sobotka/znak-sobotka.svg")
+        );
+        assert_eq!(seat.weight, 2300);
+    }
+}
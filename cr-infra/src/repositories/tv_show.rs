@@ -0,0 +1,369 @@
+use cr_domain::catalog::{ShowListing, TvShowListQuery};
+use cr_domain::repository::{
+    EpisodeNav, TitleSuggestion, TvEpisodeCard, TvEpisodeRecord, TvShowRecord, TvShowRepository,
+};
+
+use super::catalog_sql::{
+    Bind, Predicates, SuggestionRow, show_keyset, show_search, suggest_sql, to_date,
+    votes_predicate,
+};
+use super::episode::EpisodeNavRow;
+use super::series::{SHOW_COLUMNS, ShowRow};
+
+/// Column list for `TvEpisodeRow` queries. Provider attributes come from
+/// `video_sources` joined on `tv_episode_id`; same shape as the series
+/// `EPISODE_COLUMNS` minus the sledujteto column.
+const TV_EPISODE_COLUMNS: &str = "e.id, e.season, e.episode, e.title, \
+    (SELECT vs.external_id::INTEGER \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.tv_episode_id = e.id AND p.slug = 'sktorrent' \
+        AND vs.is_primary AND vs.is_alive \
+      LIMIT 1) AS sktorrent_video_id, \
+    (SELECT vs.cdn::SMALLINT \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.tv_episode_id = e.id AND p.slug = 'sktorrent' \
+        AND vs.is_primary AND vs.is_alive \
+      LIMIT 1) AS sktorrent_cdn, \
+    (SELECT vs.metadata->>'qualities' \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.tv_episode_id = e.id AND p.slug = 'sktorrent' \
+        AND vs.is_primary AND vs.is_alive \
+      LIMIT 1) AS sktorrent_qualities, \
+    e.episode_name, e.overview, e.air_date, e.runtime, e.still_filename, \
+    (SELECT REPLACE(COALESCE(vs.metadata->>'url', 'https://prehraj.to/' || vs.external_id), \
+                    'https://prehrajto.cz/', 'https://prehraj.to/') \
+       FROM video_sources vs \
+       JOIN video_providers p ON p.id = vs.provider_id \
+      WHERE vs.tv_episode_id = e.id AND p.slug = 'prehrajto' AND vs.is_alive \
+      ORDER BY vs.is_primary DESC, vs.updated_at DESC \
+      LIMIT 1) AS prehrajto_url, \
+    false AS prehrajto_has_dub, \
+    false AS prehrajto_has_subs, \
+    e.slug";
+
+pub(crate) const TV_EPISODE_HAS_SOURCE_PREDICATE: &str =
+    "EXISTS (SELECT 1 FROM video_sources vs WHERE vs.tv_episode_id = e.id AND vs.is_alive)";
+
+/// PostgreSQL implementation of [`TvShowRepository`].
+pub struct PgTvShowRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgTvShowRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl From<ShowRow> for TvShowRecord {
+    fn from(r: ShowRow) -> Self {
+        Self {
+            id: r.id,
+            title: r.title,
+            slug: r.slug,
+            first_air_year: r.first_air_year,
+            last_air_year: r.last_air_year,
+            description: r.description,
+            original_title: r.original_title,
+            tmdb_rating: r.tmdb_rating,
+            imdb_rating: r.imdb_rating,
+            csfd_rating: r.csfd_rating,
+            season_count: r.season_count,
+            episode_count: r.episode_count,
+            tmdb_poster_path: r.tmdb_poster_path,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TvEpisodeRow {
+    id: i32,
+    season: i16,
+    episode: i16,
+    title: Option<String>,
+    sktorrent_video_id: Option<i32>,
+    sktorrent_cdn: Option<i16>,
+    sktorrent_qualities: Option<String>,
+    episode_name: Option<String>,
+    overview: Option<String>,
+    air_date: Option<chrono::NaiveDate>,
+    runtime: Option<i16>,
+    still_filename: Option<String>,
+    prehrajto_url: Option<String>,
+    prehrajto_has_dub: bool,
+    prehrajto_has_subs: bool,
+    slug: Option<String>,
+}
+
+impl From<TvEpisodeRow> for TvEpisodeRecord {
+    fn from(r: TvEpisodeRow) -> Self {
+        Self {
+            id: r.id,
+            season: r.season,
+            episode: r.episode,
+            title: r.title,
+            sktorrent_video_id: r.sktorrent_video_id,
+            sktorrent_cdn: r.sktorrent_cdn,
+            sktorrent_qualities: r.sktorrent_qualities,
+            episode_name: r.episode_name,
+            overview: r.overview,
+            air_date: r.air_date.and_then(to_date),
+            runtime: r.runtime,
+            still_filename: r.still_filename,
+            prehrajto_url: r.prehrajto_url,
+            prehrajto_has_dub: r.prehrajto_has_dub,
+            prehrajto_has_subs: r.prehrajto_has_subs,
+            slug: r.slug,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TvEpisodeCardRow {
+    id: i32,
+    tv_show_slug: String,
+    tv_show_title: String,
+    tv_show_original_title: Option<String>,
+    tv_show_first_air_year: Option<i16>,
+    tv_show_tmdb_rating: Option<f32>,
+    tv_show_imdb_rating: Option<f32>,
+    tv_show_csfd_rating: Option<i16>,
+    tv_show_description: Option<String>,
+    season: i16,
+    episode: i16,
+    has_subtitles: Option<bool>,
+    has_dub: Option<bool>,
+    episode_slug: Option<String>,
+    episode_name: Option<String>,
+}
+
+impl From<TvEpisodeCardRow> for TvEpisodeCard {
+    fn from(r: TvEpisodeCardRow) -> Self {
+        Self {
+            id: r.id,
+            tv_show_slug: r.tv_show_slug,
+            tv_show_title: r.tv_show_title,
+            tv_show_original_title: r.tv_show_original_title,
+            tv_show_first_air_year: r.tv_show_first_air_year,
+            tv_show_tmdb_rating: r.tv_show_tmdb_rating,
+            tv_show_imdb_rating: r.tv_show_imdb_rating,
+            tv_show_csfd_rating: r.tv_show_csfd_rating,
+            tv_show_description: r.tv_show_description,
+            season: r.season,
+            episode: r.episode,
+            has_subtitles: r.has_subtitles,
+            has_dub: r.has_dub,
+            episode_slug: r.episode_slug,
+            episode_name: r.episode_name,
+        }
+    }
+}
+
+/// WHERE predicates and ORDER BY for the `Search` and `Sorted` listings.
+/// TV pořady have no genre or language filters, only the #704 vote gate.
+/// They still page by OFFSET — the listing is a few hundred shows.
+fn tv_show_predicates(query: &TvShowListQuery) -> (Predicates, String) {
+    let mut p = Predicates::default();
+    let mut order = show_keyset(query.sort, query.direction).order_by(false);
+    if let Some(term) = &query.search {
+        let (predicate, rank) = show_search(&mut p, term);
+        p.push(predicate);
+        order = format!("{rank}, {order}");
+    }
+    if let Some(votes) = votes_predicate("s", query.sort) {
+        p.push(votes);
+    }
+    (p, order)
+}
+
+impl TvShowRepository for PgTvShowRepository {
+    type Error = sqlx::Error;
+
+    async fn list(&self, query: &TvShowListQuery) -> Result<Vec<TvShowRecord>, Self::Error> {
+        let (mut p, order) = tv_show_predicates(query);
+        let where_clause = p.where_clause();
+        let limit = p.bind(Bind::BigInt(query.paging.per_page));
+        let offset = p.bind(Bind::BigInt(query.paging.offset()));
+        let sql = format!(
+            "SELECT {SHOW_COLUMNS} FROM tv_shows s {where_clause} \
+             ORDER BY {order} LIMIT {limit} OFFSET {offset}"
+        );
+        let rows = p
+            .apply(sqlx::query_as::<_, ShowRow>(&sql))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(TvShowRecord::from).collect())
+    }
+
+    async fn latest_episodes(
+        &self,
+        query: &TvShowListQuery,
+    ) -> Result<Vec<TvEpisodeCard>, Self::Error> {
+        let direction = if query.direction.is_desc() {
+            "DESC"
+        } else {
+            "ASC"
+        };
+        let sql = format!(
+            "WITH per_show AS ( \
+                SELECT DISTINCT ON (e.tv_show_id) \
+                    e.id, e.tv_show_id, e.season, e.episode, e.has_subtitles, e.has_dub, \
+                    e.created_at \
+                FROM tv_episodes e \
+                ORDER BY e.tv_show_id, e.created_at DESC \
+             ) \
+             SELECT ps.id, \
+                s.slug AS tv_show_slug, \
+                s.title AS tv_show_title, \
+                s.original_title AS tv_show_original_title, \
+                s.first_air_year AS tv_show_first_air_year, \
+                s.tmdb_rating AS tv_show_tmdb_rating, \
+                s.imdb_rating AS tv_show_imdb_rating, \
+                s.csfd_rating AS tv_show_csfd_rating, \
+                s.description AS tv_show_description, \
+                ps.season, ps.episode, ps.has_subtitles, ps.has_dub, \
+                (SELECT e2.slug FROM tv_episodes e2 WHERE e2.id = ps.id) AS episode_slug, \
+                (SELECT e2.episode_name FROM tv_episodes e2 WHERE e2.id = ps.id) AS episode_name \
+             FROM per_show ps \
+             JOIN tv_shows s ON s.id = ps.tv_show_id \
+             ORDER BY ps.created_at {direction} NULLS LAST \
+             LIMIT $1 OFFSET $2"
+        );
+        let rows = sqlx::query_as::<_, TvEpisodeCardRow>(&sql)
+            .bind(query.paging.per_page)
+            .bind(query.paging.offset())
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(TvEpisodeCard::from).collect())
+    }
+
+    async fn count(&self, query: &TvShowListQuery) -> Result<i64, Self::Error> {
+        if query.listing() == ShowListing::LatestEpisodes {
+            return sqlx::query_scalar::<_, i64>(
+                "SELECT count(DISTINCT e.tv_show_id) FROM tv_episodes e",
+            )
+            .fetch_one(&self.pool)
+            .await;
+        }
+        let (p, _) = tv_show_predicates(query);
+        let sql = format!("SELECT count(*) FROM tv_shows s {}", p.where_clause());
+        p.apply_scalar(sqlx::query_scalar::<_, i64>(&sql))
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<TvShowRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, ShowRow>(&format!(
+            "SELECT {SHOW_COLUMNS} FROM tv_shows s WHERE s.slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(TvShowRecord::from))
+    }
+
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<TvShowRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, ShowRow>(&format!(
+            "SELECT {SHOW_COLUMNS} FROM tv_shows s WHERE s.old_slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(TvShowRecord::from))
+    }
+
+    async fn current_slug(&self, slug: &str) -> Result<Option<String>, Self::Error> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT slug FROM tv_shows WHERE slug = $1 OR old_slug = $1 LIMIT 1",
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn suggest(&self, term: &str) -> Result<Vec<TitleSuggestion>, Self::Error> {
+        let rows = sqlx::query_as::<_, SuggestionRow>(&suggest_sql(
+            "tv_shows",
+            "first_air_year",
+            "title",
+            "original_title",
+        ))
+        .bind(format!("%{term}%"))
+        .bind(format!("{term}%"))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(TitleSuggestion::from).collect())
+    }
+
+    async fn episodes(&self, show_id: i32) -> Result<Vec<TvEpisodeRecord>, Self::Error> {
+        let rows = sqlx::query_as::<_, TvEpisodeRow>(&format!(
+            "SELECT {TV_EPISODE_COLUMNS} \
+               FROM tv_episodes e \
+              WHERE e.tv_show_id = $1 \
+                AND {TV_EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.season, e.episode, e.id"
+        ))
+        .bind(show_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(TvEpisodeRecord::from).collect())
+    }
+
+    async fn find_episode_by_slug(
+        &self,
+        show_id: i32,
+        slug: &str,
+    ) -> Result<Option<TvEpisodeRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, TvEpisodeRow>(&format!(
+            "SELECT {TV_EPISODE_COLUMNS} \
+               FROM tv_episodes e \
+              WHERE e.tv_show_id = $1 AND e.slug = $2 \
+                AND {TV_EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.id LIMIT 1"
+        ))
+        .bind(show_id)
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(TvEpisodeRecord::from))
+    }
+
+    async fn find_episode_by_number(
+        &self,
+        show_id: i32,
+        season: i16,
+        episode: i16,
+    ) -> Result<Option<TvEpisodeRecord>, Self::Error> {
+        let row = sqlx::query_as::<_, TvEpisodeRow>(&format!(
+            "SELECT {TV_EPISODE_COLUMNS} \
+               FROM tv_episodes e \
+              WHERE e.tv_show_id = $1 AND e.season = $2 AND e.episode = $3 \
+                AND {TV_EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.id LIMIT 1"
+        ))
+        .bind(show_id)
+        .bind(season)
+        .bind(episode)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(TvEpisodeRecord::from))
+    }
+
+    async fn episode_navigation(&self, show_id: i32) -> Result<Vec<EpisodeNav>, Self::Error> {
+        let rows = sqlx::query_as::<_, EpisodeNavRow>(&format!(
+            "SELECT DISTINCT ON (e.season, e.episode) \
+                    e.season, e.episode, e.episode_name, e.slug \
+               FROM tv_episodes e \
+              WHERE e.tv_show_id = $1 \
+                AND {TV_EPISODE_HAS_SOURCE_PREDICATE} \
+              ORDER BY e.season, e.episode"
+        ))
+        .bind(show_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(EpisodeNav::from).collect())
+    }
+}
@@ -0,0 +1,473 @@
+//! PostgreSQL implementation of [`VideoJobRepository`].
+//!
+//! Backed by `video_download_jobs` from
+//! `migrations/20260628_088_video_download_jobs.sql`. Workers claim with
+//! `FOR UPDATE SKIP LOCKED`, so any number of them (in one process or
+//! several) can poll the same queue without handing out a job twice.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cr_domain::export_preset::ExportTarget;
+use cr_domain::repository::VideoJobRepository;
+use cr_domain::video_job::{
+    AnimationFormat, AnimationOptions, AudioFormat, AudioOptions, ClipRange, MAX_ATTEMPTS,
+    NewVideoJob, SubtitleOptions, VideoJob, VideoJobOutcome, VideoJobPart, VideoJobState,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use sqlx::types::Json;
+
+/// Error stored on jobs whose worker disappeared mid-download.
+const INTERRUPTED_ERROR: &str = "Stahování přerušil restart serveru.";
+
+/// PostgreSQL implementation of [`VideoJobRepository`].
+pub struct PgVideoJobRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgVideoJobRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PartJson {
+    index: i32,
+    filename: String,
+    size_bytes: i64,
+    file_path: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct JobRow {
+    id: i64,
+    token: String,
+    state: String,
+    progress: i16,
+    attempts: i32,
+    source_url: String,
+    format_id: String,
+    resolution: String,
+    container: String,
+    export_preset: Option<String>,
+    audio_format: Option<String>,
+    audio_bitrate_kbps: Option<i32>,
+    clip_start_secs: Option<f64>,
+    clip_end_secs: Option<f64>,
+    animation_format: Option<String>,
+    animation_fps: Option<i32>,
+    animation_width: Option<i32>,
+    subtitle_lang: Option<String>,
+    subtitle_url: Option<String>,
+    subtitle_burn: bool,
+    publish: bool,
+    title: String,
+    duration_sec: Option<i32>,
+    uploader: Option<String>,
+    thumbnail_url: Option<String>,
+    filename: String,
+    file_path: Option<String>,
+    size_bytes: Option<i64>,
+    parts: Json<Vec<PartJson>>,
+    library_id: Option<i32>,
+    error: Option<String>,
+    worker: Option<String>,
+    queue_position: i64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    next_attempt_at: DateTime<Utc>,
+}
+
+impl From<JobRow> for VideoJob {
+    fn from(r: JobRow) -> Self {
+        Self {
+            id: r.id,
+            // The CHECK constraint keeps `state` to the known values.
+            state: VideoJobState::parse(&r.state).unwrap_or(VideoJobState::Failed),
+            progress: r.progress.clamp(0, 100) as u8,
+            attempts: r.attempts,
+            params: NewVideoJob {
+                token: r.token,
+                source_url: r.source_url,
+                format_id: r.format_id,
+                resolution: r.resolution,
+                container: r.container,
+                export: r.export_preset.as_deref().and_then(ExportTarget::parse),
+                audio: r
+                    .audio_format
+                    .as_deref()
+                    .and_then(AudioFormat::parse)
+                    .map(|format| AudioOptions {
+                        format,
+                        bitrate_kbps: r.audio_bitrate_kbps.map(|b| b as u32),
+                    }),
+                clip: r
+                    .clip_start_secs
+                    .zip(r.clip_end_secs)
+                    .and_then(|(start, end)| ClipRange::new(start, end)),
+                animation: r
+                    .animation_format
+                    .as_deref()
+                    .and_then(AnimationFormat::parse)
+                    .map(|format| AnimationOptions {
+                        format,
+                        fps: r.animation_fps.unwrap_or_default() as u32,
+                        width: r.animation_width.unwrap_or_default() as u32,
+                    }),
+                subtitles: r
+                    .subtitle_lang
+                    .zip(r.subtitle_url)
+                    .map(|(lang, url)| SubtitleOptions {
+                        lang,
+                        url,
+                        burn: r.subtitle_burn,
+                    }),
+                publish: r.publish,
+                filename: r.filename.clone(),
+                title: r.title,
+                duration_sec: r.duration_sec,
+                uploader: r.uploader,
+                thumbnail_url: r.thumbnail_url,
+            },
+            filename: r.filename,
+            file_path: r.file_path,
+            size_bytes: r.size_bytes,
+            parts: r
+                .parts
+                .0
+                .into_iter()
+                .map(|p| VideoJobPart {
+                    index: p.index,
+                    filename: p.filename,
+                    size_bytes: p.size_bytes,
+                    file_path: p.file_path,
+                })
+                .collect(),
+            library_id: r.library_id,
+            error: r.error,
+            worker: r.worker,
+            queue_position: r.queue_position,
+            created_at: r.created_at.to_rfc3339(),
+            updated_at: r.updated_at.to_rfc3339(),
+            next_attempt_at: r.next_attempt_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Columns of [`JobRow`] for a job aliased `j`. `queue_position` is an
+/// expression since it's only worth computing where it's shown.
+fn job_columns(queue_position: &str) -> String {
+    format!(
+        "j.id, j.token, j.state, j.progress, j.attempts, j.source_url, j.format_id, \
+         j.resolution, j.container, j.export_preset, j.audio_format, j.audio_bitrate_kbps, \
+         j.clip_start_secs, j.clip_end_secs, j.animation_format, j.animation_fps, \
+         j.animation_width, j.subtitle_lang, j.subtitle_url, j.subtitle_burn, j.publish, \
+         j.title, j.duration_sec, \
+         j.uploader, j.thumbnail_url, j.filename, j.file_path, j.size_bytes, j.parts, \
+         j.library_id, j.error, j.worker, {queue_position} AS queue_position, \
+         j.created_at, j.updated_at, j.next_attempt_at"
+    )
+}
+
+/// Queued jobs due before `j`.
+const QUEUE_POSITION: &str = "CASE WHEN j.state = 'queued' THEN \
+     (SELECT COUNT(*) FROM video_download_jobs q \
+       WHERE q.state = 'queued' AND (q.next_attempt_at, q.id) < (j.next_attempt_at, j.id)) \
+     ELSE 0 END";
+
+const INSERT_COLUMNS: &str = "token, source_url, format_id, resolution, container, export_preset, \
+                              publish, filename, title, duration_sec, uploader, thumbnail_url, \
+                              audio_format, audio_bitrate_kbps, clip_start_secs, clip_end_secs, \
+                              animation_format, animation_fps, animation_width, \
+                              subtitle_lang, subtitle_url, subtitle_burn, dedup_key";
+
+fn bind_new<'q>(
+    query: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    job: &'q NewVideoJob,
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    query
+        .bind(&job.token)
+        .bind(&job.source_url)
+        .bind(&job.format_id)
+        .bind(&job.resolution)
+        .bind(&job.container)
+        .bind(job.export.map(ExportTarget::as_str))
+        .bind(job.publish)
+        .bind(&job.filename)
+        .bind(&job.title)
+        .bind(job.duration_sec)
+        .bind(&job.uploader)
+        .bind(&job.thumbnail_url)
+        .bind(job.audio.map(|a| a.format.as_str()))
+        .bind(job.audio.and_then(|a| a.bitrate_kbps).map(|b| b as i32))
+        .bind(job.clip.map(|c| c.start_secs))
+        .bind(job.clip.map(|c| c.end_secs))
+        .bind(job.animation.map(|a| a.format.as_str()))
+        .bind(job.animation.map(|a| a.fps as i32))
+        .bind(job.animation.map(|a| a.width as i32))
+        .bind(job.subtitles.as_ref().map(|s| s.lang.as_str()))
+        .bind(job.subtitles.as_ref().map(|s| s.url.as_str()))
+        .bind(job.subtitles.as_ref().is_some_and(|s| s.burn))
+        .bind(job.dedup_key())
+}
+
+/// Jobs an identical request may join.
+const ACTIVE_STATES: &str = "state IN ('queued', 'downloading', 'converting')";
+
+impl VideoJobRepository for PgVideoJobRepository {
+    type Error = sqlx::Error;
+
+    async fn enqueue(&self, job: &NewVideoJob) -> Result<String, Self::Error> {
+        let insert = format!(
+            "INSERT INTO video_download_jobs ({INSERT_COLUMNS}) \
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17,$18,$19,$20,$21,$22,$23) \
+             ON CONFLICT ((md5(dedup_key))) WHERE {ACTIVE_STATES} DO NOTHING \
+             RETURNING token"
+        );
+        let existing = format!(
+            "SELECT token FROM video_download_jobs \
+              WHERE md5(dedup_key) = md5($1) AND {ACTIVE_STATES}"
+        );
+        // The conflicting job can finish between the two statements (or be
+        // committed too late for the second one's snapshot); go round again.
+        for _ in 0..3 {
+            let inserted = bind_new(sqlx::query(&insert), job)
+                .fetch_optional(&self.pool)
+                .await?;
+            if let Some(row) = inserted {
+                return row.try_get("token");
+            }
+            let joined: Option<String> = sqlx::query_scalar(&existing)
+                .bind(job.dedup_key())
+                .fetch_optional(&self.pool)
+                .await?;
+            if let Some(token) = joined {
+                return Ok(token);
+            }
+        }
+        Err(sqlx::Error::RowNotFound)
+    }
+
+    async fn record_library_hit(
+        &self,
+        job: &NewVideoJob,
+        library_id: i32,
+        size_bytes: i64,
+    ) -> Result<(), Self::Error> {
+        let sql = format!(
+            "INSERT INTO video_download_jobs ({INSERT_COLUMNS}, state, progress, library_id, size_bytes) \
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17,$18,$19,$20,$21,$22,$23,'ready',100,$24,$25)"
+        );
+        bind_new(sqlx::query(&sql), job)
+            .bind(library_id)
+            .bind(size_bytes)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn find(&self, token: &str) -> Result<Option<VideoJob>, Self::Error> {
+        let sql = format!(
+            "SELECT {} FROM video_download_jobs j WHERE j.token = $1",
+            job_columns(QUEUE_POSITION)
+        );
+        let row: Option<JobRow> = sqlx::query_as(&sql)
+            .bind(token)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(VideoJob::from))
+    }
+
+    async fn claim(&self, worker: &str) -> Result<Option<VideoJob>, Self::Error> {
+        let sql = format!(
+            "UPDATE video_download_jobs j \
+                SET state = 'downloading', progress = 0, attempts = j.attempts + 1, \
+                    worker = $1, updated_at = NOW() \
+              WHERE j.id = ( \
+                    SELECT id FROM video_download_jobs \
+                     WHERE state = 'queued' AND next_attempt_at <= NOW() \
+                     ORDER BY next_attempt_at, id \
+                     LIMIT 1 \
+                       FOR UPDATE SKIP LOCKED) \
+             RETURNING {}",
+            job_columns("0::bigint")
+        );
+        let row: Option<JobRow> = sqlx::query_as(&sql)
+            .bind(worker)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(VideoJob::from))
+    }
+
+    async fn report_progress(
+        &self,
+        id: i64,
+        worker: &str,
+        state: VideoJobState,
+        progress: u8,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query(
+            "UPDATE video_download_jobs \
+                SET state = $3, progress = $4, updated_at = NOW() \
+              WHERE id = $1 AND worker = $2 AND state IN ('downloading', 'converting')",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(state.as_str())
+        .bind(i16::from(progress.min(100)))
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn complete(
+        &self,
+        id: i64,
+        worker: &str,
+        outcome: &VideoJobOutcome,
+    ) -> Result<bool, Self::Error> {
+        let (state, file_path, filename, size_bytes, parts) = match outcome {
+            VideoJobOutcome::File {
+                file_path,
+                filename,
+                size_bytes,
+            } => (
+                VideoJobState::Ready,
+                Some(file_path.clone()),
+                Some(filename.clone()),
+                *size_bytes,
+                Vec::new(),
+            ),
+            VideoJobOutcome::Parts(parts) => (
+                VideoJobState::ReadyParts,
+                None,
+                None,
+                parts.iter().map(|p| p.size_bytes).sum(),
+                parts
+                    .iter()
+                    .map(|p| PartJson {
+                        index: p.index,
+                        filename: p.filename.clone(),
+                        size_bytes: p.size_bytes,
+                        file_path: p.file_path.clone(),
+                    })
+                    .collect(),
+            ),
+        };
+        let result = sqlx::query(
+            "UPDATE video_download_jobs \
+                SET state = $3, progress = 100, file_path = $4, \
+                    filename = COALESCE($5, filename), size_bytes = $6, parts = $7, \
+                    error = NULL, worker = NULL, updated_at = NOW() \
+              WHERE id = $1 AND worker = $2",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(state.as_str())
+        .bind(file_path)
+        .bind(filename)
+        .bind(size_bytes)
+        .bind(Json(parts))
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn fail(
+        &self,
+        id: i64,
+        worker: &str,
+        error: &str,
+        retry_in: Option<Duration>,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query(
+            "UPDATE video_download_jobs \
+                SET state = CASE WHEN $4::float8 IS NULL THEN 'failed' ELSE 'queued' END, \
+                    progress = 0, error = $3, worker = NULL, updated_at = NOW(), \
+                    next_attempt_at = NOW() + make_interval(secs => COALESCE($4, 0)) \
+              WHERE id = $1 AND worker = $2",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(error)
+        .bind(retry_in.map(|d| d.as_secs_f64()))
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn requeue_stale(&self, stale_after: Duration) -> Result<u64, Self::Error> {
+        // A job that keeps taking its worker down with it (say, ffmpeg
+        // running the box out of memory) must not loop forever, so the
+        // attempt limit applies here too.
+        let result = sqlx::query(
+            "UPDATE video_download_jobs \
+                SET state = CASE WHEN attempts >= $2 THEN 'failed' ELSE 'queued' END, \
+                    progress = 0, error = $3, worker = NULL, \
+                    updated_at = NOW(), next_attempt_at = NOW() \
+              WHERE state IN ('downloading', 'converting') \
+                AND updated_at < NOW() - make_interval(secs => $1)",
+        )
+        .bind(stale_after.as_secs_f64())
+        .bind(MAX_ATTEMPTS)
+        .bind(INTERRUPTED_ERROR)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_finished(
+        &self,
+        states: &[VideoJobState],
+        max_age: Duration,
+    ) -> Result<u64, Self::Error> {
+        let states: Vec<&str> = states
+            .iter()
+            .filter(|s| s.is_finished())
+            .map(|s| s.as_str())
+            .collect();
+        let result = sqlx::query(
+            "DELETE FROM video_download_jobs \
+              WHERE state = ANY($1) AND updated_at <= NOW() - make_interval(secs => $2)",
+        )
+        .bind(states)
+        .bind(max_age.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn list_recent(&self, limit: i64) -> Result<Vec<VideoJob>, Self::Error> {
+        let sql = format!(
+            "SELECT {} FROM video_download_jobs j \
+              ORDER BY j.state IN ('queued', 'downloading', 'converting') DESC, \
+                       j.updated_at DESC, j.id DESC \
+              LIMIT $1",
+            job_columns(QUEUE_POSITION)
+        );
+        let rows: Vec<JobRow> = sqlx::query_as(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(VideoJob::from).collect())
+    }
+
+    async fn state_counts(&self) -> Result<Vec<(VideoJobState, i64)>, Self::Error> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT state, COUNT(*) FROM video_download_jobs GROUP BY state")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(VideoJobState::ALL
+            .into_iter()
+            .map(|state| {
+                let count = rows
+                    .iter()
+                    .find(|(s, _)| s == state.as_str())
+                    .map_or(0, |(_, n)| *n);
+                (state, count)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,425 @@
+//! Audio-only downloads: conversion to MP3 / M4A / Opus / FLAC with tags
+//! and cover art.
+//!
+//! The extractor fetches the best audio stream it has (see
+//! [`download_audio_source`]); [`convert_audio`] then re-encodes it with
+//! ffmpeg, fills the tags from the extracted [`VideoInfo`](super::VideoInfo)
+//! and embeds the video thumbnail as the front cover. Tags go to ffmpeg as
+//! an `ffmetadata` file rather than `-metadata` arguments — the Opus cover
+//! is a base64 picture block that can outgrow the limit on a single
+//! command-line argument.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use cr_domain::video_job::{AudioFormat, AudioOptions, ClipRange};
+
+use super::ProgressTracker;
+use super::extractors;
+use super::transcode::{FfmpegTrack, ffprobe_duration, run_ffmpeg};
+
+/// Longest side of an embedded cover; larger thumbnails are scaled down.
+const COVER_MAX_SIDE: u32 = 800;
+
+/// Thumbnails larger than this aren't worth embedding.
+const COVER_MAX_DOWNLOAD: usize = 10 * 1024 * 1024;
+
+const COVER_JPEG_QUALITY: u8 = 88;
+
+/// Tags written to the audio file.
+#[derive(Debug, Clone, Default)]
+pub struct AudioTags {
+    pub title: String,
+    /// The uploader / channel.
+    pub artist: Option<String>,
+    pub duration_secs: Option<f64>,
+    /// Page the audio came from; stored as the comment.
+    pub source_url: Option<String>,
+}
+
+/// A cover image, always JPEG.
+#[derive(Debug, Clone)]
+pub struct CoverArt {
+    pub jpeg: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Download the audio of `url` to `output_path` as whatever the source
+/// has (no container check — [`convert_audio`] re-encodes it anyway).
+pub async fn download_audio_source(
+    client: &reqwest::Client,
+    url: &str,
+    format_id: &str,
+    output_path: &std::path::Path,
+    progress: Option<ProgressTracker>,
+) -> Result<u64> {
+    let req = extractors::DownloadRequest {
+        format_id,
+        resolution: "",
+        container: "",
+        output_path,
+        audio_only: true,
+    };
+    extractors::registry()
+        .download(client, url, &req, progress.as_ref())
+        .await?;
+    let meta = tokio::fs::metadata(output_path)
+        .await
+        .context("Audio source missing after download")?;
+    Ok(meta.len())
+}
+
+/// Fetch a thumbnail and turn it into a JPEG cover no larger than
+/// [`COVER_MAX_SIDE`]. Thumbnails are often WebP, which few players show.
+pub async fn fetch_cover_art(client: &reqwest::Client, url: &str) -> Result<CoverArt> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .context("Failed to fetch thumbnail")?
+        .error_for_status()
+        .context("Thumbnail request failed")?;
+    if resp
+        .content_length()
+        .is_some_and(|len| len as usize > COVER_MAX_DOWNLOAD)
+    {
+        anyhow::bail!("Thumbnail too large");
+    }
+    let bytes = resp.bytes().await.context("Failed to read thumbnail")?;
+    if bytes.len() > COVER_MAX_DOWNLOAD {
+        anyhow::bail!("Thumbnail too large");
+    }
+    tokio::task::spawn_blocking(move || cover_from_bytes(&bytes))
+        .await
+        .context("Cover conversion task failed")?
+}
+
+fn cover_from_bytes(bytes: &[u8]) -> Result<CoverArt> {
+    let mut img = image::load_from_memory(bytes).context("Unreadable thumbnail")?;
+    if img.width() > COVER_MAX_SIDE || img.height() > COVER_MAX_SIDE {
+        img = img.resize(
+            COVER_MAX_SIDE,
+            COVER_MAX_SIDE,
+            image::imageops::FilterType::Lanczos3,
+        );
+    }
+    // JPEG has no alpha channel.
+    let rgb = img.to_rgb8();
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, COVER_JPEG_QUALITY)
+        .encode_image(&rgb)
+        .context("Failed to encode cover")?;
+    Ok(CoverArt {
+        jpeg,
+        width: rgb.width(),
+        height: rgb.height(),
+    })
+}
+
+/// Convert `input` to `output` in the requested format, writing `tags`
+/// and, when given, `cover` as the front cover. With a `clip`, only that
+/// part of the input is converted. Reports ffmpeg's progress as 0–99 %
+/// into `progress`. Returns the size of the output.
+pub async fn convert_audio(
+    input: &std::path::Path,
+    output: &std::path::Path,
+    options: AudioOptions,
+    clip: Option<ClipRange>,
+    tags: &AudioTags,
+    cover: Option<&CoverArt>,
+    progress: Option<ProgressTracker>,
+) -> Result<u64> {
+    // The length tag describes the clip, not the whole source.
+    let clipped;
+    let tags = match clip {
+        Some(clip) => {
+            clipped = AudioTags {
+                duration_secs: Some(clip.len_secs()),
+                ..tags.clone()
+            };
+            &clipped
+        }
+        None => tags,
+    };
+    let parent = output.parent().unwrap_or_else(|| std::path::Path::new("."));
+    let stem = output
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("Output path has no stem")?;
+    let meta_path = parent.join(format!("{stem}.ffmeta"));
+    let cover_path = parent.join(format!("{stem}.cover.jpg"));
+
+    // Opus carries the cover inside its tags; the others as a picture stream.
+    let picture_block = match (options.format, cover) {
+        (AudioFormat::Opus, Some(cover)) => Some(metadata_block_picture(cover)),
+        _ => None,
+    };
+    let cover_stream = match (options.format, cover) {
+        (AudioFormat::Opus, _) | (_, None) => None,
+        (_, Some(cover)) => {
+            tokio::fs::write(&cover_path, &cover.jpeg)
+                .await
+                .context("Failed to write cover")?;
+            Some(cover_path.to_str().context("Invalid cover path")?)
+        }
+    };
+    tokio::fs::write(
+        &meta_path,
+        ffmetadata(tags, options.format, picture_block.as_deref()),
+    )
+    .await
+    .context("Failed to write tags")?;
+
+    let args = ffmpeg_args(
+        input.to_str().context("Invalid input path")?,
+        meta_path.to_str().context("Invalid tags path")?,
+        cover_stream,
+        output.to_str().context("Invalid output path")?,
+        options,
+        clip,
+    );
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let duration = match tags.duration_secs {
+        Some(d) => Some(d),
+        None => ffprobe_duration(input).await,
+    };
+    let track = progress
+        .as_ref()
+        .map(|p| FfmpegTrack::new(p, duration, 0, 99));
+    let result = run_ffmpeg(&args, track).await;
+
+    let _ = tokio::fs::remove_file(&meta_path).await;
+    if cover_stream.is_some() {
+        let _ = tokio::fs::remove_file(&cover_path).await;
+    }
+
+    let (status, stderr) = result.context("Failed to run ffmpeg for audio conversion")?;
+    if !status.success() {
+        let _ = tokio::fs::remove_file(output).await;
+        anyhow::bail!("ffmpeg audio conversion failed: {stderr}");
+    }
+    let meta = tokio::fs::metadata(output)
+        .await
+        .context("Audio output file not found")?;
+    Ok(meta.len())
+}
+
+/// ffmpeg arguments: input 0 is the source, input 1 the tags, input 2 the
+/// cover (if any). Everything the source itself carries besides its first
+/// audio stream — video, subtitles, tags — is dropped.
+fn ffmpeg_args(
+    input: &str,
+    meta: &str,
+    cover: Option<&str>,
+    output: &str,
+    options: AudioOptions,
+    clip: Option<ClipRange>,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into()];
+    if let Some(clip) = clip {
+        args.extend([
+            "-ss".into(),
+            format!("{:.3}", clip.start_secs),
+            "-t".into(),
+            format!("{:.3}", clip.len_secs()),
+        ]);
+    }
+    args.extend(["-i".into(), input.into()]);
+    args.extend(["-f".into(), "ffmetadata".into(), "-i".into(), meta.into()]);
+    if let Some(cover) = cover {
+        args.extend(["-i".into(), cover.into()]);
+    }
+    args.extend(["-map".into(), "0:a:0".into()]);
+    if cover.is_some() {
+        args.extend([
+            "-map".into(),
+            "2:v:0".into(),
+            "-c:v".into(),
+            "copy".into(),
+            "-disposition:v:0".into(),
+            "attached_pic".into(),
+            "-metadata:s:v".into(),
+            "title=Album cover".into(),
+            "-metadata:s:v".into(),
+            "comment=Cover (front)".into(),
+        ]);
+    }
+    args.extend(["-map_metadata".into(), "1".into()]);
+
+    let codec = match options.format {
+        AudioFormat::Mp3 => "libmp3lame",
+        AudioFormat::M4a => "aac",
+        AudioFormat::Opus => "libopus",
+        AudioFormat::Flac => "flac",
+    };
+    args.extend(["-c:a".into(), codec.into()]);
+    if let Some(kbps) = options.bitrate_kbps {
+        args.extend(["-b:a".into(), format!("{kbps}k")]);
+    }
+    match options.format {
+        // ID3v2.3 is what most players and car stereos still read best.
+        AudioFormat::Mp3 => args.extend(["-id3v2_version".into(), "3".into()]),
+        AudioFormat::M4a => args.extend(["-movflags".into(), "+faststart".into()]),
+        // Ogg keeps Vorbis comments per stream.
+        AudioFormat::Opus => args.extend(["-map_metadata:s:a".into(), "1:g".into()]),
+        AudioFormat::Flac => {}
+    }
+    args.push(output.into());
+    args
+}
+
+/// The tags as an ffmpeg `ffmetadata` file.
+fn ffmetadata(tags: &AudioTags, format: AudioFormat, picture_block: Option<&str>) -> String {
+    let mut out = String::from(";FFMETADATA1\n");
+    let mut field = |key: &str, value: &str| {
+        out.push_str(key);
+        out.push('=');
+        out.push_str(&escape_ffmetadata(value));
+        out.push('\n');
+    };
+    field("title", &tags.title);
+    if let Some(artist) = &tags.artist {
+        field("artist", artist);
+    }
+    if let Some(url) = &tags.source_url {
+        field("comment", url);
+    }
+    // Only ID3 has a length tag; the other containers keep the duration
+    // in their own headers.
+    if let (AudioFormat::Mp3, Some(secs)) = (format, tags.duration_secs) {
+        field("TLEN", &((secs * 1000.0).round() as u64).to_string());
+    }
+    if let Some(block) = picture_block {
+        field("METADATA_BLOCK_PICTURE", block);
+    }
+    out
+}
+
+/// `=`, `;`, `#`, `\` and newlines are special in `ffmetadata`.
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// A FLAC `PICTURE` block, base64-encoded — how Vorbis comments (Opus,
+/// Ogg Vorbis) carry cover art.
+fn metadata_block_picture(cover: &CoverArt) -> String {
+    const FRONT_COVER: u32 = 3;
+    const MIME: &[u8] = b"image/jpeg";
+    let mut block = Vec::with_capacity(32 + MIME.len() + cover.jpeg.len());
+    block.extend(FRONT_COVER.to_be_bytes());
+    block.extend((MIME.len() as u32).to_be_bytes());
+    block.extend(MIME);
+    // Empty description.
+    block.extend(0u32.to_be_bytes());
+    block.extend(cover.width.to_be_bytes());
+    block.extend(cover.height.to_be_bytes());
+    // Colour depth (24-bit RGB) and palette size (none).
+    block.extend(24u32.to_be_bytes());
+    block.extend(0u32.to_be_bytes());
+    block.extend((cover.jpeg.len() as u32).to_be_bytes());
+    block.extend(&cover.jpeg);
+    base64::engine::general_purpose::STANDARD.encode(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags() -> AudioTags {
+        AudioTags {
+            title: "Song; live = great".to_string(),
+            artist: Some("Kanál #1".to_string()),
+            duration_secs: Some(61.5),
+            source_url: Some("https://example.com/watch?v=1".to_string()),
+        }
+    }
+
+    #[test]
+    fn mp3_args_embed_the_cover_as_id3_picture() {
+        let opts = AudioOptions::new(AudioFormat::Mp3, Some(320)).unwrap();
+        let args = ffmpeg_args("in.webm", "t.ffmeta", Some("c.jpg"), "out.mp3", opts, None);
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-y -i in.webm -f ffmetadata -i t.ffmeta -i c.jpg"));
+        assert!(joined.contains("-map 0:a:0 -map 2:v:0 -c:v copy -disposition:v:0 attached_pic"));
+        assert!(joined.contains("-map_metadata 1 -c:a libmp3lame -b:a 320k -id3v2_version 3"));
+        assert_eq!(args.last().map(String::as_str), Some("out.mp3"));
+
+        let clip = ClipRange::new(30.0, 45.0);
+        let args = ffmpeg_args("in.webm", "t.ffmeta", None, "out.mp3", opts, clip);
+        assert!(
+            args.join(" ")
+                .starts_with("-y -ss 30.000 -t 15.000 -i in.webm -f ffmetadata")
+        );
+    }
+
+    #[test]
+    fn flac_args_take_no_bitrate_and_opus_no_picture_stream() {
+        let flac = AudioOptions::new(AudioFormat::Flac, None).unwrap();
+        let args = ffmpeg_args("in", "t", Some("c.jpg"), "out.flac", flac, None);
+        assert!(args.iter().any(|a| a == "flac"));
+        assert!(!args.iter().any(|a| a == "-b:a"));
+
+        let opus = AudioOptions::new(AudioFormat::Opus, Some(128)).unwrap();
+        let args = ffmpeg_args("in", "t", None, "out.opus", opus, None);
+        assert!(!args.iter().any(|a| a == "2:v:0"));
+        assert!(
+            args.join(" ")
+                .contains("-c:a libopus -b:a 128k -map_metadata:s:a 1:g")
+        );
+    }
+
+    #[test]
+    fn ffmetadata_escapes_values_and_adds_tlen_for_mp3() {
+        let meta = ffmetadata(&tags(), AudioFormat::Mp3, None);
+        assert_eq!(
+            meta,
+            ";FFMETADATA1\n\
+             title=Song\\; live \\= great\n\
+             artist=Kanál \\#1\n\
+             comment=https://example.com/watch?v\\=1\n\
+             TLEN=61500\n"
+        );
+        let opus = ffmetadata(&tags(), AudioFormat::Opus, Some("QUJD"));
+        assert!(!opus.contains("TLEN"));
+        assert!(opus.ends_with("METADATA_BLOCK_PICTURE=QUJD\n"));
+    }
+
+    #[test]
+    fn picture_block_follows_the_flac_layout() {
+        let cover = CoverArt {
+            jpeg: vec![0xFF, 0xD8, 0xFF, 0xD9],
+            width: 640,
+            height: 360,
+        };
+        let block = base64::engine::general_purpose::STANDARD
+            .decode(metadata_block_picture(&cover))
+            .unwrap();
+        let u32_at = |i: usize| u32::from_be_bytes(block[i..i + 4].try_into().unwrap());
+        assert_eq!(u32_at(0), 3);
+        assert_eq!(u32_at(4), 10);
+        assert_eq!(&block[8..18], b"image/jpeg");
+        assert_eq!(u32_at(18), 0);
+        assert_eq!((u32_at(22), u32_at(26)), (640, 360));
+        assert_eq!(u32_at(38), 4);
+        assert_eq!(&block[42..], &cover.jpeg[..]);
+    }
+
+    #[test]
+    fn covers_are_scaled_down_and_reencoded_as_jpeg() {
+        let img = image::DynamicImage::new_rgba8(1600, 900);
+        let mut png = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let cover = cover_from_bytes(&png).unwrap();
+        assert_eq!((cover.width, cover.height), (800, 450));
+        assert_eq!(&cover.jpeg[..2], &[0xFF, 0xD8]);
+    }
+}
@@ -0,0 +1,265 @@
+//! Re-encoding downloads for a target platform (see
+//! [`cr_domain::export_preset`]).
+//!
+//! The preset's [`plan`] fixes the number of parts and a video bitrate;
+//! every part is then encoded in two passes at that bitrate, which lands
+//! it just under the size limit. A part that still comes out too big is
+//! redone once at a bitrate scaled down by the overshoot.
+
+use anyhow::{Context, Result};
+use cr_domain::export_preset::{ExportPreset, ExportTarget, PlanError, plan, retry_video_kbps};
+
+use super::ProgressTracker;
+use super::transcode::{FfmpegTrack, ffprobe_duration, run_ffmpeg};
+
+/// Result of an export — single file or multiple parts.
+#[derive(Debug)]
+pub enum ExportResult {
+    Single { path: std::path::PathBuf, size: u64 },
+    Parts(Vec<ExportPart>),
+}
+
+#[derive(Debug)]
+pub struct ExportPart {
+    pub path: std::path::PathBuf,
+    pub size: u64,
+    pub index: usize,
+}
+
+/// Encode `input` for `target` into `output_dir`, as
+/// `{base_name}-{target}.mp4` or `{base_name}-{target}-part000.mp4`, … .
+/// Reports 0–99 % into `progress` across all passes of all parts.
+pub async fn export_for_target(
+    input: &std::path::Path,
+    output_dir: &std::path::Path,
+    base_name: &str,
+    target: ExportTarget,
+    progress: Option<ProgressTracker>,
+) -> Result<ExportResult> {
+    let preset = target.preset();
+    let input_str = input.to_str().context("Invalid input path")?;
+    let duration = ffprobe_duration(input)
+        .await
+        .context("Could not determine the video's duration")?;
+    let plan = plan(preset, duration).map_err(|e| match e {
+        PlanError::TooLong { max_secs } => anyhow::anyhow!(
+            "video too long for {}: at most {max_secs} s fits",
+            preset.label
+        ),
+    })?;
+    tracing::info!(
+        "export {}: {duration:.0} s as {} part(s) at {} kbit/s",
+        target.as_str(),
+        plan.parts,
+        plan.video_kbps
+    );
+
+    let passlog = output_dir.join(format!("{base_name}-{}-pass", target.as_str()));
+    let passlog_str = passlog.to_str().context("Invalid pass log path")?;
+    let steps = plan.parts * 2;
+    let step_range = |step: u32| ((step * 99 / steps) as u8, ((step + 1) * 99 / steps) as u8);
+
+    let mut parts: Vec<ExportPart> = Vec::new();
+    for index in 0..plan.parts {
+        let path = if plan.parts == 1 {
+            output_dir.join(format!("{base_name}-{}.mp4", target.as_str()))
+        } else {
+            output_dir.join(format!(
+                "{base_name}-{}-part{index:03}.mp4",
+                target.as_str()
+            ))
+        };
+        let path_str = path.to_str().context("Invalid output path")?;
+        let span = (plan.parts > 1).then_some((index as f64 * plan.part_secs, plan.part_secs));
+        let encode = |pass: u8, video_kbps: u32| {
+            let args = encode_args(&EncodeArgs {
+                input: input_str,
+                output: (pass == 2).then_some(path_str),
+                span,
+                preset,
+                video_kbps,
+                pass,
+                passlog: passlog_str,
+            });
+            let (from, to) = step_range(index * 2 + u32::from(pass) - 1);
+            let progress = progress.clone();
+            async move {
+                let track = progress
+                    .as_ref()
+                    .map(|p| FfmpegTrack::new(p, Some(plan.part_secs), from, to));
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                let (status, stderr) = run_ffmpeg(&args, track)
+                    .await
+                    .context("Failed to run ffmpeg")?;
+                if !status.success() {
+                    anyhow::bail!("ffmpeg pass {pass} failed: {stderr}");
+                }
+                Ok(())
+            }
+        };
+
+        let result = async {
+            encode(1, plan.video_kbps).await?;
+            encode(2, plan.video_kbps).await?;
+            let mut size = tokio::fs::metadata(&path).await?.len();
+            if size > preset.max_size_bytes {
+                let retry = retry_video_kbps(preset, plan.video_kbps, size);
+                tracing::info!(
+                    "export {}: part {index} is {size} B, redoing at {retry} kbit/s",
+                    target.as_str()
+                );
+                encode(2, retry).await?;
+                size = tokio::fs::metadata(&path).await?.len();
+                if size > preset.max_size_bytes {
+                    anyhow::bail!("part {index} still exceeds the {} limit", preset.label);
+                }
+            }
+            Ok(size)
+        }
+        .await;
+        let size = match result {
+            Ok(size) => size,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&path).await;
+                for part in &parts {
+                    let _ = tokio::fs::remove_file(&part.path).await;
+                }
+                remove_passlog(&passlog).await;
+                return Err(e);
+            }
+        };
+        parts.push(ExportPart {
+            path,
+            size,
+            index: index as usize,
+        });
+    }
+    remove_passlog(&passlog).await;
+
+    if let Some(p) = &progress {
+        p.set_percent(99);
+    }
+    if parts.len() == 1 {
+        let part = parts.remove(0);
+        return Ok(ExportResult::Single {
+            path: part.path,
+            size: part.size,
+        });
+    }
+    tracing::info!("export {}: {} parts created", target.as_str(), parts.len());
+    Ok(ExportResult::Parts(parts))
+}
+
+/// x264 writes its first-pass statistics next to `passlog`.
+async fn remove_passlog(passlog: &std::path::Path) {
+    let log = passlog.with_file_name(format!(
+        "{}-0.log",
+        passlog.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let _ = tokio::fs::remove_file(&log).await;
+    let mut mbtree = log.into_os_string();
+    mbtree.push(".mbtree");
+    let _ = tokio::fs::remove_file(mbtree).await;
+}
+
+/// One ffmpeg pass of a part.
+struct EncodeArgs<'a> {
+    input: &'a str,
+    /// `None` for the first pass, which only analyses.
+    output: Option<&'a str>,
+    /// Start and length of the part; `None` for the whole input.
+    span: Option<(f64, f64)>,
+    preset: &'a ExportPreset,
+    video_kbps: u32,
+    pass: u8,
+    passlog: &'a str,
+}
+
+fn encode_args(a: &EncodeArgs<'_>) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into()];
+    if let Some((start, len)) = a.span {
+        // Before `-i`: a fast seek, frame-accurate since we re-encode.
+        args.extend([
+            "-ss".into(),
+            format!("{start:.3}"),
+            "-t".into(),
+            format!("{len:.3}"),
+        ]);
+    }
+    args.extend(["-i".into(), a.input.into()]);
+    args.extend([
+        "-map".into(),
+        "0:v:0".into(),
+        // Some sources have no sound.
+        "-map".into(),
+        "0:a:0?".into(),
+        "-vf".into(),
+        format!(
+            "scale='min({w},iw)':'min({h},ih)':force_original_aspect_ratio=decrease:force_divisible_by=2",
+            w = a.preset.max_width,
+            h = a.preset.max_height
+        ),
+        "-c:v".into(),
+        a.preset.video_codec.into(),
+        "-preset".into(),
+        "veryfast".into(),
+        "-profile:v".into(),
+        "main".into(),
+        "-pix_fmt".into(),
+        "yuv420p".into(),
+        "-b:v".into(),
+        format!("{}k", a.video_kbps),
+        "-pass".into(),
+        a.pass.to_string(),
+        "-passlogfile".into(),
+        a.passlog.into(),
+    ]);
+    match a.output {
+        None => args.extend(["-an".into(), "-f".into(), "null".into(), "-".into()]),
+        Some(output) => args.extend([
+            "-c:a".into(),
+            a.preset.audio_codec.into(),
+            "-b:a".into(),
+            format!("{}k", a.preset.audio_kbps),
+            "-ac".into(),
+            "2".into(),
+            "-movflags".into(),
+            "+faststart".into(),
+            output.into(),
+        ]),
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_pass_analyses_only_and_second_writes_the_part() {
+        let preset = ExportTarget::WhatsApp.preset();
+        let mut a = EncodeArgs {
+            input: "in.mkv",
+            output: None,
+            span: Some((100.0, 50.5)),
+            preset,
+            video_kbps: 900,
+            pass: 1,
+            passlog: "/tmp/x-whatsapp-pass",
+        };
+        let first = encode_args(&a).join(" ");
+        assert!(first.starts_with("-y -ss 100.000 -t 50.500 -i in.mkv -map 0:v:0 -map 0:a:0?"));
+        assert!(first.contains("scale='min(854,iw)':'min(480,ih)':force_original_aspect_ratio"));
+        assert!(first.contains("-c:v libx264"));
+        assert!(first.contains("-b:v 900k -pass 1 -passlogfile /tmp/x-whatsapp-pass"));
+        assert!(first.ends_with("-an -f null -"));
+
+        a.output = Some("out.mp4");
+        a.pass = 2;
+        a.span = None;
+        let second = encode_args(&a).join(" ");
+        assert!(second.starts_with("-y -i in.mkv"));
+        assert!(second.contains("-pass 2"));
+        assert!(second.ends_with("-c:a aac -b:a 128k -ac 2 -movflags +faststart out.mp4"));
+    }
+}
@@ -1,7 +1,57 @@
 use anyhow::{Context, Result};
 
-use super::super::{VideoFormat, VideoInfo};
-use super::seznam::base64_decode;
+use super::super::{ProgressTracker, VideoFormat, VideoInfo};
+use super::seznam::{base64_decode, download_direct};
+use super::{DownloadRequest, Extractor, pick_format};
+
+/// Check if URL is an Instagram reel/video.
+pub(crate) fn is_instagram_url(url: &str) -> bool {
+    url.contains("instagram.com")
+}
+
+/// Instagram reels and videos, resolved to a CDN URL by the savegram API
+/// and downloaded directly.
+pub(crate) struct InstagramExtractor;
+
+impl Extractor for InstagramExtractor {
+    fn id(&self) -> &'static str {
+        "instagram"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        is_instagram_url(url)
+    }
+
+    /// `/reel/{code}/` for reels (also linked as `/reels/` and `/tv/`),
+    /// `/p/{code}/` for posts.
+    fn canonical_url(&self, url: &reqwest::Url) -> Option<String> {
+        let mut segments = url.path_segments()?;
+        let kind = match segments.next()? {
+            "reel" | "reels" | "tv" => "reel",
+            "p" => "p",
+            _ => return None,
+        };
+        let code = segments.next().filter(|code| !code.is_empty())?;
+        Some(format!("https://www.instagram.com/{kind}/{code}/"))
+    }
+
+    async fn extract_info(&self, client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        instagram_extract_info(client, url).await
+    }
+
+    async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        let info = instagram_extract_info(client, url).await?;
+        let fmt = pick_format(&info, req.format_id)?;
+        download_direct(client, &fmt.url, req.output_path, progress).await?;
+        Ok(())
+    }
+}
 
 /// Extract Instagram video using external API to get CDN URL.
 pub(crate) async fn instagram_extract_info(
@@ -18,7 +68,15 @@ pub(crate) async fn instagram_extract_info(
         .await
         .context("Failed to call Instagram extraction API")?;
 
-    let body: serde_json::Value = resp.json().await.context("Failed to parse API response")?;
+    let body = resp.text().await.context("Failed to parse API response")?;
+    parse_savegram_response(&body)
+}
+
+/// Video info from a savegram `ajaxSearch` response: the CDN URLs sit in
+/// the `url` claim of the JWTs in the returned download links.
+fn parse_savegram_response(body: &str) -> Result<VideoInfo> {
+    let body: serde_json::Value =
+        serde_json::from_str(body).context("Failed to parse API response")?;
 
     if body.get("status").and_then(|s| s.as_str()) != Some("ok") {
         anyhow::bail!("Instagram extraction failed — video may be private or unavailable");
@@ -85,5 +143,34 @@ pub(crate) async fn instagram_extract_info(
             url: video_url,
             filesize_approx: None,
         }],
+        subtitles: Vec::new(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_savegram_response() {
+        let info =
+            parse_savegram_response(include_str!("fixtures/instagram_savegram.json")).unwrap();
+        assert_eq!(info.title, "Sunset over Prague Castle 🌇");
+        assert_eq!(info.formats.len(), 1);
+        assert_eq!(
+            info.formats[0].url,
+            "https://scontent.cdninstagram.com/o1/v/t16/f2/m86/AQPx7reel.mp4?efg=eyJ2ZW5jb2RlX3RhZyI6InJlZWwifQ&_nc_ht=scontent.cdninstagram.com"
+        );
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://scontent.cdninstagram.com/v/t51.2885-15/465123_n.jpg?stp=dst-jpg_e15")
+        );
+        assert_eq!(info.uploader.as_deref(), Some("instagram.com"));
+    }
+
+    #[test]
+    fn private_video_is_an_error() {
+        let err = parse_savegram_response(r#"{"status":"error","mess":"private"}"#).unwrap_err();
+        assert!(err.to_string().contains("private or unavailable"));
+    }
+}
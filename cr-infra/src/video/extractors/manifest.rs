@@ -0,0 +1,122 @@
+use anyhow::Result;
+
+use super::super::segments::{self, StreamInfo};
+use super::super::{ProgressTracker, VideoFormat, VideoInfo};
+use super::{DownloadRequest, Extractor};
+
+/// Prefix of the format ids this extractor produces (`native-720p`).
+const NATIVE_FORMAT_PREFIX: &str = "native-";
+
+/// Direct links to HLS (`.m3u8`) and DASH (`.mpd`) manifests, downloaded
+/// by our own segment downloader. Falls through to yt-dlp, which knows
+/// the manifest variants we don't (live streams, DRM-free SAMPLE-AES).
+pub(crate) struct ManifestExtractor;
+
+impl Extractor for ManifestExtractor {
+    fn id(&self) -> &'static str {
+        "manifest"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        segments::is_manifest_url(url)
+    }
+
+    fn owns_format(&self, format_id: &str) -> bool {
+        format_id.starts_with(NATIVE_FORMAT_PREFIX)
+    }
+
+    /// Probes through the segment downloader's own guarded client.
+    async fn extract_info(&self, _client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        let stream = segments::probe(url).await?;
+        Ok(stream_info(url, &stream))
+    }
+
+    /// Formats from yt-dlp's info (when probing failed and it took over)
+    /// go back to yt-dlp.
+    async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        if !self.owns_format(req.format_id) {
+            return super::ytdlp::YtDlpExtractor
+                .download(client, url, req, progress)
+                .await;
+        }
+        let max_height = req.resolution.trim_end_matches('p').parse().ok();
+        segments::download_stream(url, max_height, req.audio_only, req.output_path, progress)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Video info for a probed manifest: one format per height, titled after
+/// the manifest's file name.
+fn stream_info(url: &str, stream: &StreamInfo) -> VideoInfo {
+    let title = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let name = u.path_segments()?.next_back()?.to_string();
+            let stem = name.rsplit_once('.').map_or(name.as_str(), |(s, _)| s);
+            (!stem.is_empty()).then(|| stem.to_string())
+        })
+        .unwrap_or_else(|| "Stream".to_string());
+    let formats = stream
+        .variants
+        .iter()
+        .map(|v| {
+            let resolution = v.height.map_or("best".to_string(), |h| format!("{h}p"));
+            VideoFormat {
+                format_id: format!("{NATIVE_FORMAT_PREFIX}{resolution}"),
+                resolution,
+                ext: "mp4".to_string(),
+                url: url.to_string(),
+                filesize_approx: stream
+                    .duration_secs
+                    .filter(|_| v.bandwidth > 0)
+                    .map(|d| (v.bandwidth as f64 / 8.0 * d) as u64),
+            }
+        })
+        .collect();
+    VideoInfo {
+        title,
+        thumbnail: None,
+        duration: stream.duration_secs,
+        uploader: None,
+        formats,
+        subtitles: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::segments::StreamVariant;
+    use super::*;
+
+    #[test]
+    fn formats_follow_the_variants() {
+        let stream = StreamInfo {
+            variants: vec![
+                StreamVariant {
+                    height: Some(360),
+                    bandwidth: 800_000,
+                },
+                StreamVariant {
+                    height: Some(720),
+                    bandwidth: 2_800_000,
+                },
+            ],
+            duration_secs: Some(10.0),
+        };
+        let info = stream_info("https://cdn.example/vod/Ulice-4512.m3u8?t=1", &stream);
+        assert_eq!(info.title, "Ulice-4512");
+        let ids: Vec<_> = info.formats.iter().map(|f| f.format_id.as_str()).collect();
+        assert_eq!(ids, ["native-360p", "native-720p"]);
+        assert_eq!(info.formats[1].resolution, "720p");
+        assert_eq!(info.formats[1].filesize_approx, Some(3_500_000));
+        assert!(ManifestExtractor.owns_format("native-720p"));
+        assert!(!ManifestExtractor.owns_format("22"));
+    }
+}
@@ -1,4 +1,474 @@
+//! Site extractors behind [`extract_video_info`](super::extract_video_info)
+//! and [`download_video_with_progress`](super::download_video_with_progress).
+//!
+//! Every extractor implements [`Extractor`]; the [`ExtractorRegistry`] keeps
+//! them ordered by priority and walks the enabled ones that match a URL.
+//! When an extractor fails and its config allows falling through, the next
+//! matching one gets the URL — that's how the Nova.cz proxy takes over
+//! when yt-dlp is geo-blocked.
+//!
+//! Configuration (read once, at first use):
+//! - `VIDEO_EXTRACTORS_DISABLED` — comma-separated ids to switch off
+//! - `VIDEO_EXTRACTOR_ORDER` — comma-separated ids to try first, in order
+//!
+//! Each site's parsing is kept apart from its HTTP calls so it can be
+//! tested against the page and API samples in `fixtures/`, trimmed to
+//! the parts the parsers read.
+
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+
+use super::{ProgressTracker, VideoFormat, VideoInfo};
+
 pub(crate) mod instagram;
+pub(crate) mod manifest;
 pub(crate) mod nova;
 pub(crate) mod seznam;
 pub(crate) mod ytdlp;
+
+/// What to download, as chosen by the user from the extracted formats.
+pub(crate) struct DownloadRequest<'a> {
+    pub format_id: &'a str,
+    pub resolution: &'a str,
+    pub container: &'a str,
+    /// Already carries the `.{container}` extension; the container itself
+    /// is enforced afterwards by `ensure_container`.
+    pub output_path: &'a std::path::Path,
+    /// Only the audio is wanted. Extractors that can fetch it alone do;
+    /// the others download the video and the audio is extracted later.
+    pub audio_only: bool,
+}
+
+/// One source of videos.
+#[allow(async_fn_in_trait)]
+pub(crate) trait Extractor {
+    /// Stable id used in configuration and logs.
+    fn id(&self) -> &'static str;
+
+    /// Whether this extractor can handle `url`.
+    fn matches(&self, url: &str) -> bool;
+
+    /// Whether `format_id` is a sentinel only this extractor produces (and
+    /// so only it can download), whatever the URL.
+    fn owns_format(&self, _format_id: &str) -> bool {
+        false
+    }
+
+    /// The one spelling of `url` this extractor treats as the same video,
+    /// for cache keys and duplicate detection. `url` has already been
+    /// through [`normalize_url`]; `None` keeps that.
+    fn canonical_url(&self, _url: &reqwest::Url) -> Option<String> {
+        None
+    }
+
+    async fn extract_info(&self, client: &reqwest::Client, url: &str) -> Result<VideoInfo>;
+
+    async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()>;
+}
+
+/// How the registry uses one extractor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ExtractorConfig {
+    pub enabled: bool,
+    /// Lower goes first.
+    pub priority: i32,
+    /// On failure, hand the URL to the next matching extractor instead of
+    /// giving up.
+    pub fall_through: bool,
+}
+
+/// The built-in extractors. A closed set keeps dispatch static (and the
+/// futures `Send`) without boxing.
+pub(crate) enum AnyExtractor {
+    Seznam(seznam::SeznamExtractor),
+    Instagram(instagram::InstagramExtractor),
+    Manifest(manifest::ManifestExtractor),
+    YtDlp(ytdlp::YtDlpExtractor),
+    NovaProxy(nova::NovaProxyExtractor),
+}
+
+impl Extractor for AnyExtractor {
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Seznam(e) => e.id(),
+            Self::Instagram(e) => e.id(),
+            Self::Manifest(e) => e.id(),
+            Self::YtDlp(e) => e.id(),
+            Self::NovaProxy(e) => e.id(),
+        }
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        match self {
+            Self::Seznam(e) => e.matches(url),
+            Self::Instagram(e) => e.matches(url),
+            Self::Manifest(e) => e.matches(url),
+            Self::YtDlp(e) => e.matches(url),
+            Self::NovaProxy(e) => e.matches(url),
+        }
+    }
+
+    fn owns_format(&self, format_id: &str) -> bool {
+        match self {
+            Self::Seznam(e) => e.owns_format(format_id),
+            Self::Instagram(e) => e.owns_format(format_id),
+            Self::Manifest(e) => e.owns_format(format_id),
+            Self::YtDlp(e) => e.owns_format(format_id),
+            Self::NovaProxy(e) => e.owns_format(format_id),
+        }
+    }
+
+    fn canonical_url(&self, url: &reqwest::Url) -> Option<String> {
+        match self {
+            Self::Seznam(e) => e.canonical_url(url),
+            Self::Instagram(e) => e.canonical_url(url),
+            Self::Manifest(e) => e.canonical_url(url),
+            Self::YtDlp(e) => e.canonical_url(url),
+            Self::NovaProxy(e) => e.canonical_url(url),
+        }
+    }
+
+    async fn extract_info(&self, client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        match self {
+            Self::Seznam(e) => e.extract_info(client, url).await,
+            Self::Instagram(e) => e.extract_info(client, url).await,
+            Self::Manifest(e) => e.extract_info(client, url).await,
+            Self::YtDlp(e) => e.extract_info(client, url).await,
+            Self::NovaProxy(e) => e.extract_info(client, url).await,
+        }
+    }
+
+    async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        match self {
+            Self::Seznam(e) => e.download(client, url, req, progress).await,
+            Self::Instagram(e) => e.download(client, url, req, progress).await,
+            Self::Manifest(e) => e.download(client, url, req, progress).await,
+            Self::YtDlp(e) => e.download(client, url, req, progress).await,
+            Self::NovaProxy(e) => e.download(client, url, req, progress).await,
+        }
+    }
+}
+
+/// Extractors in the order they're tried.
+pub(crate) struct ExtractorRegistry {
+    entries: Vec<(ExtractorConfig, AnyExtractor)>,
+}
+
+impl ExtractorRegistry {
+    /// The built-in extractors with their default configs: the site
+    /// extractors first and final, then our own HLS/DASH downloader for
+    /// direct manifest links, then yt-dlp for everything, then the Nova.cz
+    /// proxy as yt-dlp's fallback.
+    pub(crate) fn builtin(nova_proxy: nova::NovaProxyExtractor) -> Self {
+        let config = |priority, fall_through| ExtractorConfig {
+            enabled: true,
+            priority,
+            fall_through,
+        };
+        let mut registry = Self {
+            entries: vec![
+                (
+                    config(10, false),
+                    AnyExtractor::Seznam(seznam::SeznamExtractor),
+                ),
+                (
+                    config(20, false),
+                    AnyExtractor::Instagram(instagram::InstagramExtractor),
+                ),
+                (
+                    config(30, true),
+                    AnyExtractor::Manifest(manifest::ManifestExtractor),
+                ),
+                (config(50, true), AnyExtractor::YtDlp(ytdlp::YtDlpExtractor)),
+                (config(60, false), AnyExtractor::NovaProxy(nova_proxy)),
+            ],
+        };
+        registry.sort();
+        registry
+    }
+
+    /// [`builtin`](Self::builtin) adjusted by the environment (see the
+    /// module docs).
+    pub(crate) fn from_env() -> Self {
+        let mut registry = Self::builtin(nova::NovaProxyExtractor::from_env());
+        let list = |name: &str| -> Vec<String> {
+            std::env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_ascii_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        registry.apply(
+            &list("VIDEO_EXTRACTORS_DISABLED"),
+            &list("VIDEO_EXTRACTOR_ORDER"),
+        );
+        registry
+    }
+
+    /// Switch off `disabled`; move `order` to the front, in that order.
+    fn apply(&mut self, disabled: &[String], order: &[String]) {
+        for (config, extractor) in &mut self.entries {
+            let id = extractor.id();
+            if disabled.iter().any(|d| d == id) {
+                config.enabled = false;
+            }
+            if let Some(pos) = order.iter().position(|o| o == id) {
+                config.priority = pos as i32 - order.len() as i32;
+            }
+        }
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by_key(|(config, _)| config.priority);
+    }
+
+    fn enabled(&self) -> impl Iterator<Item = &(ExtractorConfig, AnyExtractor)> {
+        self.entries.iter().filter(|(config, _)| config.enabled)
+    }
+
+    /// The extractors that would be tried for `url`, in order, up to and
+    /// including the first one that doesn't fall through.
+    fn chain<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> impl Iterator<Item = &'a (ExtractorConfig, AnyExtractor)> + 'a {
+        let mut done = false;
+        self.enabled()
+            .filter(move |(_, e)| e.matches(url))
+            .take_while(move |(config, _)| {
+                let take = !done;
+                done = !config.fall_through;
+                take
+            })
+    }
+
+    /// Ids of the enabled extractors, in order, for the startup log.
+    pub(crate) fn describe(&self) -> String {
+        self.enabled()
+            .map(|(_, e)| e.id())
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
+    /// `url` in canonical form: [`normalize_url`], then the rewrite of the
+    /// first extractor that would handle it. Input that doesn't parse as
+    /// a URL comes back trimmed.
+    pub(crate) fn canonical_url(&self, url: &str) -> String {
+        let Some(parsed) = normalize_url(url) else {
+            return url.trim().to_string();
+        };
+        self.chain(parsed.as_str())
+            .find_map(|(_, e)| e.canonical_url(&parsed))
+            .unwrap_or_else(|| parsed.to_string())
+    }
+
+    /// Info from the first extractor in the chain that succeeds. When all
+    /// fail, the first error is returned: fallbacks cover edge cases, and
+    /// the primary extractor's error is the one worth showing.
+    pub(crate) async fn extract_info(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<VideoInfo> {
+        let mut first_err = None;
+        for (_, extractor) in self.chain(url) {
+            match extractor.extract_info(client, url).await {
+                Ok(info) => return Ok(info),
+                Err(e) => {
+                    tracing::warn!(
+                        "video: {} extractor failed for {url}: {e:#}",
+                        extractor.id()
+                    );
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_err.unwrap_or_else(|| anyhow::anyhow!("No video extractor handles {url}")))
+    }
+
+    /// Download with the extractor that owns `req.format_id`, or else the
+    /// first one matching `url`. No fallback here: the format id came from
+    /// a particular extractor's info and means nothing to the others.
+    pub(crate) async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        let (_, extractor) = self
+            .enabled()
+            .find(|(_, e)| e.owns_format(req.format_id))
+            .or_else(|| self.enabled().find(|(_, e)| e.matches(url)))
+            .with_context(|| format!("No video extractor handles {url}"))?;
+        extractor.download(client, url, req, progress).await
+    }
+}
+
+/// Query parameters that only track where a link was shared from.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "igsh", "igshid", "si", "feature"];
+
+/// Extractor-independent cleanup: scheme and host lowercased (by the
+/// parser), no fragment, no tracking parameters.
+fn normalize_url(url: &str) -> Option<reqwest::Url> {
+    let mut parsed = reqwest::Url::parse(url.trim()).ok()?;
+    parsed.set_fragment(None);
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else if parsed.query_pairs().count() != kept.len() {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(parsed)
+}
+
+/// `url` without its query, for sites whose pages are identified by
+/// the path alone.
+pub(super) fn without_query(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.to_string()
+}
+
+/// The registry used by the public video API.
+pub(crate) fn registry() -> &'static ExtractorRegistry {
+    static REGISTRY: LazyLock<ExtractorRegistry> = LazyLock::new(|| {
+        let registry = ExtractorRegistry::from_env();
+        tracing::info!("video extractors: {}", registry.describe());
+        registry
+    });
+    &REGISTRY
+}
+
+/// The format the user picked, or the best one (formats are sorted
+/// worst to best).
+pub(crate) fn pick_format<'a>(info: &'a VideoInfo, format_id: &str) -> Result<&'a VideoFormat> {
+    info.formats
+        .iter()
+        .find(|f| f.format_id == format_id)
+        .or(info.formats.last())
+        .context("No format available")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ExtractorRegistry {
+        ExtractorRegistry::builtin(nova::NovaProxyExtractor::with_proxy(
+            "https://proxy.example/fetch",
+            "key",
+        ))
+    }
+
+    fn chain_ids(registry: &ExtractorRegistry, url: &str) -> Vec<&'static str> {
+        registry.chain(url).map(|(_, e)| e.id()).collect()
+    }
+
+    #[test]
+    fn chains_follow_priority_and_fall_through() {
+        let r = registry();
+        assert_eq!(
+            chain_ids(&r, "https://www.novinky.cz/clanek/video-123"),
+            ["seznam"]
+        );
+        assert_eq!(
+            chain_ids(&r, "https://www.instagram.com/reel/abc/"),
+            ["instagram"]
+        );
+        assert_eq!(
+            chain_ids(&r, "https://tv.nova.cz/porad/ulice/video/1"),
+            ["ytdlp", "nova"]
+        );
+        assert_eq!(
+            chain_ids(&r, "https://www.youtube.com/watch?v=x"),
+            ["ytdlp"]
+        );
+        assert_eq!(
+            chain_ids(&r, "https://cdn.example/vod/master.m3u8?t=1"),
+            ["manifest", "ytdlp"]
+        );
+        // Host check, not substring.
+        assert_eq!(chain_ids(&r, "https://example.com/?u=nova.cz"), ["ytdlp"]);
+    }
+
+    #[test]
+    fn nova_proxy_needs_its_config() {
+        let r = ExtractorRegistry::builtin(nova::NovaProxyExtractor::default());
+        assert_eq!(chain_ids(&r, "https://tv.nova.cz/video/1"), ["ytdlp"]);
+    }
+
+    #[test]
+    fn environment_disables_and_reorders() {
+        let mut r = registry();
+        r.apply(&["ytdlp".to_string()], &[]);
+        assert_eq!(chain_ids(&r, "https://tv.nova.cz/video/1"), ["nova"]);
+        assert!(chain_ids(&r, "https://www.youtube.com/watch?v=x").is_empty());
+
+        let mut r = registry();
+        r.apply(&[], &["nova".to_string()]);
+        assert_eq!(r.describe(), "nova → seznam → instagram → manifest → ytdlp");
+        // The proxy doesn't fall through, so yt-dlp is no longer tried.
+        assert_eq!(chain_ids(&r, "https://tv.nova.cz/video/1"), ["nova"]);
+    }
+
+    #[test]
+    fn urls_are_canonicalized_per_extractor() {
+        let r = registry();
+        for url in [
+            "https://youtu.be/dQw4w9WgXcQ?si=abc",
+            "https://m.youtube.com/watch?v=dQw4w9WgXcQ&feature=share#t=10",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            " HTTPS://WWW.YOUTUBE.COM/watch?list=PL1&v=dQw4w9WgXcQ&utm_source=x",
+        ] {
+            assert_eq!(
+                r.canonical_url(url),
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+                "{url}"
+            );
+        }
+        assert_eq!(
+            r.canonical_url("https://www.instagram.com/reels/C8xYz12/?igsh=MWQ"),
+            "https://www.instagram.com/reel/C8xYz12/"
+        );
+        assert_eq!(
+            r.canonical_url("https://www.novinky.cz/clanek/video-123?utm_medium=x&ref=hp"),
+            "https://www.novinky.cz/clanek/video-123"
+        );
+        // Tokens in manifest URLs are kept; only tracking goes.
+        assert_eq!(
+            r.canonical_url("https://cdn.example/vod/master.m3u8?t=1&fbclid=z"),
+            "https://cdn.example/vod/master.m3u8?t=1"
+        );
+        assert_eq!(r.canonical_url("  not a url "), "not a url");
+    }
+
+    #[test]
+    fn sentinel_formats_route_to_their_owner() {
+        let r = registry();
+        let owner = r
+            .enabled()
+            .find(|(_, e)| e.owns_format("proxy-hls"))
+            .map(|(_, e)| e.id());
+        assert_eq!(owner, Some("nova"));
+    }
+}
@@ -1,61 +1,143 @@
 use anyhow::{Context, Result};
 
-use super::super::{VideoFormat, VideoInfo};
-
-/// Czech proxy URL and key for geo-blocked Nova.cz embeds (from env vars).
-pub(crate) fn cz_proxy_config() -> Option<(String, String)> {
-    let url = std::env::var("CZ_PROXY_URL").ok()?;
-    let key = std::env::var("CZ_PROXY_KEY").ok()?;
-    if url.is_empty() || key.is_empty() {
-        return None;
+use super::super::segments::download_stream;
+use super::super::{ProgressTracker, VideoFormat, VideoInfo};
+use super::{DownloadRequest, Extractor, without_query};
+
+/// Format id of the single HLS format the proxy route produces. Only
+/// [`NovaProxyExtractor`] can download it: the manifest URL is tokenized
+/// and has to be re-extracted.
+const PROXY_HLS_FORMAT: &str = "proxy-hls";
+
+/// Check if URL is Nova.cz (validates host, not substring).
+pub(crate) fn is_nova_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(|host| host.to_ascii_lowercase()))
+        .map(|host| host == "nova.cz" || host.ends_with(".nova.cz"))
+        .unwrap_or(false)
+}
+
+/// Nova.cz through the Czech proxy, for embeds geo-blocked from the VPS.
+/// Only takes part when the proxy is configured.
+#[derive(Default)]
+pub(crate) struct NovaProxyExtractor {
+    /// Proxy base URL and key.
+    proxy: Option<(String, String)>,
+}
+
+impl NovaProxyExtractor {
+    /// Proxy from `CZ_PROXY_URL` / `CZ_PROXY_KEY`.
+    pub(crate) fn from_env() -> Self {
+        let proxy = std::env::var("CZ_PROXY_URL")
+            .ok()
+            .zip(std::env::var("CZ_PROXY_KEY").ok())
+            .filter(|(url, key)| !url.is_empty() && !key.is_empty());
+        Self { proxy }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_proxy(url: &str, key: &str) -> Self {
+        Self {
+            proxy: Some((url.to_string(), key.to_string())),
+        }
+    }
+
+    /// Extract video info from Nova.cz via Czech proxy (for geo-blocked embeds).
+    /// 1. Fetch tv.nova.cz page to get embed ID
+    /// 2. Fetch embed page via Czech proxy
+    /// 3. Extract m3u8/mpd manifest URLs + metadata
+    async fn extract(&self, client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        // Step 1: Get embed ID from the Nova.cz page
+        let page_html = client
+            .get(url)
+            .header("User-Agent", "Mozilla/5.0")
+            .send()
+            .await?
+            .text()
+            .await?;
+        let embed_id = parse_embed_id(&page_html)?;
+
+        // Step 2: Fetch embed page via Czech proxy
+        let (proxy_base, proxy_key) = self
+            .proxy
+            .as_ref()
+            .context("CZ_PROXY_URL and CZ_PROXY_KEY env vars required")?;
+        let embed_url = format!("https://media.cms.nova.cz/embed/{embed_id}");
+        let proxy_url = format!(
+            "{}?url={}&key={}",
+            proxy_base,
+            urlencoding::encode(&embed_url),
+            proxy_key
+        );
+
+        let embed_html = client
+            .get(&proxy_url)
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        // Step 3: metadata and manifest
+        let info = parse_embed(&embed_html)?;
+        tracing::info!("Nova.cz proxy: extracted manifest for {}", info.title);
+        Ok(info)
     }
-    Some((url, key))
 }
 
-/// Extract video info from Nova.cz via Czech proxy (for geo-blocked embeds).
-/// 1. Fetch tv.nova.cz page to get embed ID
-/// 2. Fetch embed page via Czech proxy
-/// 3. Extract m3u8/mpd manifest URLs + metadata
-pub(crate) async fn nova_proxy_extract_info(
-    client: &reqwest::Client,
-    url: &str,
-) -> Result<VideoInfo> {
-    // Step 1: Get embed ID from the Nova.cz page
-    let page_html = client
-        .get(url)
-        .header("User-Agent", "Mozilla/5.0")
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    let embed_id = page_html
+impl Extractor for NovaProxyExtractor {
+    fn id(&self) -> &'static str {
+        "nova"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        self.proxy.is_some() && is_nova_url(url)
+    }
+
+    fn owns_format(&self, format_id: &str) -> bool {
+        format_id == PROXY_HLS_FORMAT
+    }
+
+    /// Episodes are identified by their path.
+    fn canonical_url(&self, url: &reqwest::Url) -> Option<String> {
+        Some(without_query(url))
+    }
+
+    async fn extract_info(&self, client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        self.extract(client, url).await
+    }
+
+    /// `format_id` is a sentinel, not the direct m3u8 URL: re-extract to
+    /// get a fresh tokenized manifest and fetch its segments ourselves.
+    async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        let info = self.extract(client, url).await?;
+        let m3u8 = &info.formats[0].url;
+        download_stream(m3u8, None, req.audio_only, req.output_path, progress).await?;
+        Ok(())
+    }
+}
+
+/// The embed id from a tv.nova.cz page.
+fn parse_embed_id(page_html: &str) -> Result<String> {
+    page_html
         .split("media.cms.nova.cz/embed/")
         .nth(1)
         .and_then(|s| s.split(['?', '"', '\'', ' '].as_ref()).next())
-        .context("Could not find Nova embed ID")?
-        .to_string();
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .context("Could not find Nova embed ID")
+}
 
-    // Step 2: Fetch embed page via Czech proxy
-    let (proxy_base, proxy_key) =
-        cz_proxy_config().context("CZ_PROXY_URL and CZ_PROXY_KEY env vars required")?;
-    let embed_url = format!("https://media.cms.nova.cz/embed/{embed_id}");
-    let proxy_url = format!(
-        "{}?url={}&key={}",
-        proxy_base,
-        urlencoding::encode(&embed_url),
-        proxy_key
-    );
-
-    let embed_html = client
-        .get(&proxy_url)
-        .timeout(std::time::Duration::from_secs(30))
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    // Step 3: Extract title from og:title
+/// Title, thumbnail, duration and the HLS manifest from an embed page.
+fn parse_embed(embed_html: &str) -> Result<VideoInfo> {
+    // Title from og:title
     let title = embed_html
         .split("og:title")
         .nth(1)
@@ -64,7 +146,7 @@ pub(crate) async fn nova_proxy_extract_info(
         .unwrap_or("Nova.cz Video")
         .to_string();
 
-    // Extract thumbnail from og:image
+    // Thumbnail from og:image
     let thumbnail = embed_html
         .split("og:image")
         .nth(1)
@@ -72,14 +154,14 @@ pub(crate) async fn nova_proxy_extract_info(
         .and_then(|s| s.split('"').next())
         .map(|s| s.to_string());
 
-    // Extract duration from programDuration
+    // Duration from the player config
     let duration = embed_html
         .split("\"duration\":")
         .nth(1)
         .and_then(|s| s.split([',', '}'].as_ref()).next())
         .and_then(|s| s.trim().parse::<f64>().ok());
 
-    // Extract m3u8 manifest URL
+    // m3u8 manifest URL
     let m3u8_url = embed_html
         .split("\"src\":\"")
         .filter_map(|s| {
@@ -93,21 +175,57 @@ pub(crate) async fn nova_proxy_extract_info(
         .next()
         .context("No m3u8 manifest found in Nova.cz embed")?;
 
-    tracing::info!("Nova.cz proxy: extracted manifest for {title}");
-
-    // Build formats — we pass the m3u8 URL as a single format.
-    // yt-dlp can download directly from this manifest URL.
+    // Build formats — we pass the m3u8 URL as a single format, downloaded
+    // at its best quality.
     Ok(VideoInfo {
         title,
         thumbnail,
         duration,
         uploader: Some("Nova.cz".to_string()),
         formats: vec![VideoFormat {
-            format_id: "proxy-hls".to_string(),
+            format_id: PROXY_HLS_FORMAT.to_string(),
             resolution: "best".to_string(),
             ext: "mp4".to_string(),
             url: m3u8_url,
             filesize_approx: None,
         }],
+        subtitles: Vec::new(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_embed_id_on_show_page() {
+        let id = parse_embed_id(include_str!("fixtures/nova_page.html")).unwrap();
+        assert_eq!(id, "nTxY3kQ4ZzP");
+        assert!(parse_embed_id("<html></html>").is_err());
+    }
+
+    #[test]
+    fn parses_embed_page() {
+        let info = parse_embed(include_str!("fixtures/nova_embed.html")).unwrap();
+        assert_eq!(info.title, "Ulice (4512)");
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://cdn.cms.nova.cz/images/ulice-4512-poster.jpg")
+        );
+        assert_eq!(info.duration, Some(2534.0));
+        // The DASH source comes first in the player config; HLS is picked.
+        assert_eq!(
+            info.formats[0].url,
+            "https://nova-vod.ssl.cdn.cra.cz/vod_nova/_definst_/0001/4512/cze-sd1-sd2-hd1-hd2.smil/playlist.m3u8?t=1760900000&h=3fa1c0"
+        );
+        assert_eq!(info.formats[0].format_id, "proxy-hls");
+    }
+
+    #[test]
+    fn nova_urls_are_matched_by_host() {
+        assert!(is_nova_url("https://tv.nova.cz/porad/ulice"));
+        assert!(is_nova_url("https://NOVA.cz/"));
+        assert!(!is_nova_url("https://example.com/?ref=nova.cz"));
+        assert!(!is_nova_url("https://notnova.cz/"));
+    }
+}
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
-use super::super::{VideoFormat, VideoInfo};
+use super::super::progress::RateMeter;
+use super::super::{ProgressTracker, VideoFormat, VideoInfo};
+use super::{DownloadRequest, Extractor, pick_format, without_query};
 
 // Consent cookie value for bypassing Seznam CMP consent wall
 const CONSENT_COOKIE: &str = "euconsent-v2=CPzqWAAPzqWAAAGABCCSC5CgAP_gAEPgACiQKZNB9G7WTXFneXp2YPskOYUX0VBJ4CUAAwgBwAIAIBoBKBECAAAAAKAAEIIAAAABBAAICIAAgBIBAAMBAgMNAEAMgAYCASgBIAKIEACEAAOECAAAJAgCBDAQIJCgBMATEACAAJAQEBBQBUCgAAAACAAAAAmAUYmAgAILAAiKAGAAQAAoACAAAABIAAAAAIgAAAAYAAAAYiAAAAAAAAAAAAAABAAAAAAAAAAAAgAAAAAQAAAIAAAAAAAIAAAAAAAAAAAAAAAAIAGAgAAAAABDQAEBAAIABgIAAAAAAAAAAAAAAAAAAAAAABAAAAAAIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAEAAAIAIAAAAAIAAAAYgAAAAAAAAAAAAAAEAAAAKAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAgAAAABAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQ";
@@ -14,6 +16,42 @@ pub(crate) fn is_seznam_url(url: &str) -> bool {
     SEZNAM_DOMAINS.iter().any(|d| url.contains(d))
 }
 
+/// Novinky.cz and Seznam Zprávy: page scrape for the SDN URL, then the
+/// SDN manifest for the MP4 renditions, downloaded directly.
+pub(crate) struct SeznamExtractor;
+
+impl Extractor for SeznamExtractor {
+    fn id(&self) -> &'static str {
+        "seznam"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        is_seznam_url(url)
+    }
+
+    /// Articles are identified by their path.
+    fn canonical_url(&self, url: &reqwest::Url) -> Option<String> {
+        Some(without_query(url))
+    }
+
+    async fn extract_info(&self, client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        seznam_extract_info(client, url).await
+    }
+
+    async fn download(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        let info = seznam_extract_info(client, url).await?;
+        let fmt = pick_format(&info, req.format_id)?;
+        download_direct(client, &fmt.url, req.output_path, progress).await?;
+        Ok(())
+    }
+}
+
 /// SDN manifest response structure.
 #[derive(Deserialize)]
 struct SdnManifest {
@@ -51,29 +89,62 @@ pub(crate) async fn seznam_extract_info(client: &reqwest::Client, url: &str) ->
         .await
         .context("Failed to read page body")?;
 
-    let sdn_url = extract_sdn_url(&html)
-        .context("No video found on this page — could not find SDN video URL")?;
-
-    let title = extract_title(&html);
-    let thumbnail = extract_thumbnail(&html);
-    let duration = extract_duration(&html);
-    let uploader = extract_domain(url);
+    let page = parse_page(&html, url)?;
 
-    let manifest_url = format!("{sdn_url}spl2,2,VOD");
-    let manifest: SdnManifest = client
+    let manifest_url = format!("{}spl2,2,VOD", page.sdn_url);
+    let manifest = client
         .get(&manifest_url)
         .header("User-Agent", "Mozilla/5.0")
         .send()
         .await
         .context("Failed to fetch video manifest")?
-        .json()
+        .text()
         .await
-        .context("Failed to parse video manifest JSON")?;
+        .context("Failed to fetch video manifest")?;
+    let formats = parse_manifest(&page.sdn_url, &manifest)?;
+
+    Ok(VideoInfo {
+        title: page.title,
+        thumbnail: page.thumbnail,
+        duration: page.duration,
+        uploader: Some(page.uploader),
+        formats,
+        subtitles: Vec::new(),
+    })
+}
+
+/// What an article page tells us about its video.
+#[derive(Debug)]
+struct SeznamPage {
+    sdn_url: String,
+    title: String,
+    thumbnail: Option<String>,
+    duration: Option<f64>,
+    uploader: String,
+}
+
+fn parse_page(html: &str, url: &str) -> Result<SeznamPage> {
+    let sdn_url = extract_sdn_url(html)
+        .context("No video found on this page — could not find SDN video URL")?;
+    Ok(SeznamPage {
+        sdn_url,
+        title: extract_title(html),
+        thumbnail: extract_thumbnail(html),
+        duration: extract_duration(html),
+        uploader: extract_domain(url),
+    })
+}
+
+/// MP4 formats from the SDN manifest, worst to best, with their URLs made
+/// absolute against `sdn_url`.
+fn parse_manifest(sdn_url: &str, manifest: &str) -> Result<Vec<VideoFormat>> {
+    let manifest: SdnManifest =
+        serde_json::from_str(manifest).context("Failed to parse video manifest JSON")?;
 
     let base_url = sdn_url
         .rfind("/vmd")
         .map(|i| &sdn_url[..i])
-        .unwrap_or(&sdn_url);
+        .unwrap_or(sdn_url);
 
     let mut formats: Vec<VideoFormat> = manifest
         .data
@@ -116,14 +187,7 @@ pub(crate) async fn seznam_extract_info(client: &reqwest::Client, url: &str) ->
     if formats.is_empty() {
         anyhow::bail!("No MP4 formats found in video manifest");
     }
-
-    Ok(VideoInfo {
-        title,
-        thumbnail,
-        duration,
-        uploader: Some(uploader),
-        formats,
-    })
+    Ok(formats)
 }
 
 /// Simple base64 decode helper.
@@ -135,13 +199,18 @@ pub(crate) fn base64_decode(input: &str) -> Result<String> {
     String::from_utf8(bytes).context("UTF-8 decode failed")
 }
 
-/// Download a file directly via HTTP.
+/// Download a file directly via HTTP, streaming it to disk. Reports bytes,
+/// speed and ETA to `progress` as chunks arrive (percentage only when the
+/// server sends a `Content-Length`).
 pub(crate) async fn download_direct(
     client: &reqwest::Client,
     video_url: &str,
     output_path: &std::path::Path,
+    progress: Option<&ProgressTracker>,
 ) -> Result<u64> {
-    let resp = client
+    use tokio::io::AsyncWriteExt;
+
+    let mut resp = client
         .get(video_url)
         .header("User-Agent", "Mozilla/5.0")
         .send()
@@ -152,16 +221,29 @@ pub(crate) async fn download_direct(
         anyhow::bail!("Video download returned HTTP {}", resp.status());
     }
 
-    let bytes = resp
-        .bytes()
-        .await
-        .context("Failed to read video response body")?;
-
-    let size = bytes.len() as u64;
-    tokio::fs::write(output_path, &bytes)
+    let meter = RateMeter::new(resp.content_length());
+    let mut file = tokio::fs::File::create(output_path)
         .await
         .context("Failed to write video file")?;
+    let mut size = 0u64;
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .context("Failed to read video response body")?
+    {
+        file.write_all(&chunk)
+            .await
+            .context("Failed to write video file")?;
+        size += chunk.len() as u64;
+        if let Some(p) = progress {
+            p.update(meter.reading(size));
+        }
+    }
+    file.flush().await.context("Failed to write video file")?;
 
+    if let Some(p) = progress {
+        p.set_percent(100);
+    }
     Ok(size)
 }
 
@@ -256,3 +338,53 @@ fn extract_json_string(html: &str, key: &str) -> Option<String> {
         .replace("\\n", "\n");
     Some(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = include_str!("fixtures/seznam_article.html");
+    const MANIFEST: &str = include_str!("fixtures/seznam_manifest.json");
+    const URL: &str = "https://www.novinky.cz/clanek/domaci-povoden-na-morave-video-40512345";
+
+    #[test]
+    fn parses_article_page() {
+        let page = parse_page(ARTICLE, URL).unwrap();
+        // The `~SEC1~…~` signature segment is dropped.
+        assert_eq!(
+            page.sdn_url,
+            "https://v39-a.sdn.cz/d_39/c_15A1_2/vmd/5f3f0b2c/"
+        );
+        assert_eq!(page.title, "Povodeň na Moravě: záběry z dronu");
+        assert_eq!(
+            page.thumbnail.as_deref(),
+            Some("https://d39-a.sdn.cz/d_39/c_img_QN_y/nO1Bh.jpeg")
+        );
+        assert_eq!(page.duration, Some(134.0));
+        assert_eq!(page.uploader, "novinky.cz");
+    }
+
+    #[test]
+    fn page_without_video_is_an_error() {
+        let err = parse_page("<html><title>Bez videa</title></html>", URL).unwrap_err();
+        assert!(err.to_string().contains("No video found"));
+    }
+
+    #[test]
+    fn parses_manifest_into_sorted_absolute_formats() {
+        let page = parse_page(ARTICLE, URL).unwrap();
+        let formats = parse_manifest(&page.sdn_url, MANIFEST).unwrap();
+        let ids: Vec<&str> = formats.iter().map(|f| f.format_id.as_str()).collect();
+        assert_eq!(ids, ["360p", "720p", "1080p"]);
+        assert_eq!(
+            formats[2].url,
+            "https://v39-a.sdn.cz/d_39/c_15A1_2/tmp/b6e8a1d7_1080p.mp4?fl=mdk,4b1ae9c3|"
+        );
+        assert_eq!(
+            formats[0].url,
+            "https://v39-a.sdn.cz/d_39/c_15A1_2/vmd/5f3f0b2c/360p.mp4"
+        );
+        // 1.2 Mbit/s × 134 s
+        assert_eq!(formats[0].filesize_approx, Some(20_100_000));
+    }
+}
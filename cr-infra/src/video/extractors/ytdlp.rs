@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
-use super::super::{VideoFormat, VideoInfo};
+use super::super::{ProgressTracker, ProgressUpdate, SubtitleTrack, VideoFormat, VideoInfo};
+use super::{DownloadRequest, Extractor};
 
 /// yt-dlp JSON output structure (subset of fields we need).
 #[derive(Deserialize)]
@@ -14,6 +15,17 @@ struct YtDlpInfo {
     url: Option<String>,
     ext: Option<String>,
     height: Option<u32>,
+    /// Uploaded subtitles by language. `automatic_captions` is left out:
+    /// it machine-translates into a hundred languages.
+    #[serde(default)]
+    subtitles: std::collections::HashMap<String, Vec<YtDlpSubtitle>>,
+}
+
+#[derive(Deserialize)]
+struct YtDlpSubtitle {
+    ext: Option<String>,
+    url: Option<String>,
+    name: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -31,6 +43,8 @@ struct YtDlpFormat {
 /// Build yt-dlp command with optional proxy from YTDLP_PROXY env var.
 pub(crate) fn ytdlp_command() -> tokio::process::Command {
     let mut cmd = tokio::process::Command::new("yt-dlp");
+    // A download abandoned by the queue worker must not keep writing.
+    cmd.kill_on_drop(true);
     // Enable EJS challenge solver for YouTube (requires deno)
     cmd.arg("--remote-components").arg("ejs:github");
     if let Ok(proxy) = std::env::var("YTDLP_PROXY") {
@@ -63,6 +77,51 @@ pub(crate) fn ytdlp_command() -> tokio::process::Command {
     cmd
 }
 
+/// Everything else, through the yt-dlp binary. Matches any URL.
+pub(crate) struct YtDlpExtractor;
+
+impl Extractor for YtDlpExtractor {
+    fn id(&self) -> &'static str {
+        "ytdlp"
+    }
+
+    fn matches(&self, _url: &str) -> bool {
+        true
+    }
+
+    /// YouTube's many link forms collapse to the watch URL; other sites
+    /// keep the normalized URL.
+    fn canonical_url(&self, url: &reqwest::Url) -> Option<String> {
+        youtube_id(url).map(|id| format!("https://www.youtube.com/watch?v={id}"))
+    }
+
+    async fn extract_info(&self, _client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
+        ytdlp_extract_info(url).await
+    }
+
+    async fn download(
+        &self,
+        _client: &reqwest::Client,
+        url: &str,
+        req: &DownloadRequest<'_>,
+        progress: Option<&ProgressTracker>,
+    ) -> Result<()> {
+        if req.audio_only {
+            ytdlp_download_audio(url, req.output_path, progress.cloned()).await?;
+        } else {
+            ytdlp_download(
+                url,
+                req.resolution,
+                req.container,
+                req.output_path,
+                progress.cloned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
 /// Extract video info using yt-dlp subprocess.
 pub(crate) async fn ytdlp_extract_info(url: &str) -> Result<VideoInfo> {
     let output = ytdlp_command()
@@ -138,9 +197,41 @@ pub(crate) async fn ytdlp_extract_info(url: &str) -> Result<VideoInfo> {
         duration: raw.duration,
         uploader: raw.uploader,
         formats,
+        subtitles: subtitle_tracks(raw.subtitles),
     })
 }
 
+/// One track per language, in a format we can convert — WebVTT first,
+/// then SubRip, then ASS — sorted by language.
+fn subtitle_tracks(
+    subtitles: std::collections::HashMap<String, Vec<YtDlpSubtitle>>,
+) -> Vec<SubtitleTrack> {
+    const EXTS: [&str; 3] = ["vtt", "srt", "ass"];
+    let mut tracks: Vec<SubtitleTrack> = subtitles
+        .into_iter()
+        .filter(|(lang, _)| lang != "live_chat")
+        .filter_map(|(lang, entries)| {
+            let entry = entries
+                .into_iter()
+                .filter(|e| e.url.is_some())
+                .filter_map(|e| {
+                    let rank = EXTS.iter().position(|x| Some(*x) == e.ext.as_deref())?;
+                    Some((rank, e))
+                })
+                .min_by_key(|(rank, _)| *rank)?
+                .1;
+            Some(SubtitleTrack {
+                label: entry.name.filter(|n| !n.is_empty()).unwrap_or(lang.clone()),
+                lang,
+                url: entry.url.unwrap_or_default(),
+                ext: entry.ext.unwrap_or_default(),
+            })
+        })
+        .collect();
+    tracks.sort_by(|a, b| a.lang.cmp(&b.lang));
+    tracks
+}
+
 /// Build a yt-dlp `-f` selector that prefers the requested container
 /// and then falls back to the generic `bestvideo+bestaudio` chain if
 /// no container-native stream is available.
@@ -194,21 +285,8 @@ pub(crate) async fn ytdlp_download(
     resolution: &str,
     container: &str,
     output_path: &std::path::Path,
-    progress: Option<std::sync::Arc<std::sync::atomic::AtomicU8>>,
+    progress: Option<ProgressTracker>,
 ) -> Result<u64> {
-    use std::sync::atomic::Ordering;
-    use tokio::io::{AsyncBufReadExt, BufReader};
-
-    let parent = output_path.parent().context("No parent directory")?;
-    let stem = output_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .context("No file stem")?;
-    let output_template = parent.join(format!("{stem}.%(ext)s"));
-    let output_template_str = output_template
-        .to_str()
-        .context("Invalid output template path")?;
-
     let height: String = resolution
         .chars()
         .take_while(|c| c.is_ascii_digit())
@@ -220,11 +298,47 @@ pub(crate) async fn ytdlp_download(
     //   best[ext=mp4][height<=720]/
     //   bestvideo[height<=720]+bestaudio/best[height<=720]/best
     let format_selector = build_format_selector(&height, container);
+    ytdlp_fetch(url, &format_selector, output_path, progress).await
+}
+
+/// Download only the best audio stream (or the best combined format when
+/// the site has no separate audio) into `output_path`, whatever its
+/// container; the caller converts it.
+pub(crate) async fn ytdlp_download_audio(
+    url: &str,
+    output_path: &std::path::Path,
+    progress: Option<ProgressTracker>,
+) -> Result<u64> {
+    ytdlp_fetch(url, AUDIO_FORMAT_SELECTOR, output_path, progress).await
+}
+
+/// yt-dlp format selector for audio-only downloads.
+const AUDIO_FORMAT_SELECTOR: &str = "bestaudio/best";
+
+/// Run yt-dlp with `format_selector`, report its progress and rename the
+/// result to `output_path`.
+async fn ytdlp_fetch(
+    url: &str,
+    format_selector: &str,
+    output_path: &std::path::Path,
+    progress: Option<ProgressTracker>,
+) -> Result<u64> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let parent = output_path.parent().context("No parent directory")?;
+    let stem = output_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("No file stem")?;
+    let output_template = parent.join(format!("{stem}.%(ext)s"));
+    let output_template_str = output_template
+        .to_str()
+        .context("Invalid output template path")?;
 
     let mut child = ytdlp_command()
         .args([
             "-f",
-            &format_selector,
+            format_selector,
             "--newline",
             "-o",
             output_template_str,
@@ -241,17 +355,19 @@ pub(crate) async fn ytdlp_download(
     let stderr = child.stderr.take();
     let progress_clone = progress.clone();
 
-    // Parse progress from stdout (fragment count and percentage)
+    // Parse progress from stdout (fragment count, percentage, size, speed,
+    // ETA). The percentage never goes backwards: video and audio streams
+    // are downloaded one after the other and each restarts at 0 %.
     let stdout_handle = tokio::spawn(async move {
         if let Some(stdout) = stdout {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
             while let Ok(Some(line)) = lines.next_line().await {
                 if let Some(progress) = &progress_clone
-                    && let Some(pct) = parse_ytdlp_progress(&line)
-                    && pct > progress.load(Ordering::Relaxed)
+                    && let Some(mut reading) = parse_ytdlp_reading(&line)
                 {
-                    progress.store(pct, Ordering::Relaxed);
+                    reading.percent = reading.percent.max(progress.percent());
+                    progress.update(reading);
                 }
             }
         }
@@ -282,7 +398,7 @@ pub(crate) async fn ytdlp_download(
     }
 
     if let Some(progress) = &progress {
-        progress.store(100, Ordering::Relaxed);
+        progress.set_percent(100);
     }
 
     // yt-dlp wrote `{stem}.{whatever}` — find it, rename to the
@@ -340,3 +456,136 @@ pub(crate) fn parse_ytdlp_progress(line: &str) -> Option<u8> {
     }
     None
 }
+
+/// Parse a yt-dlp progress line into a full reading:
+/// `[download]  45.2% of ~ 123.45MiB at 2.34MiB/s ETA 00:31 (frag 12/100)`.
+/// The percentage comes from [`parse_ytdlp_progress`]; size, speed and ETA
+/// are filled in when the line carries them (yt-dlp prints `Unknown` until
+/// it knows).
+pub(crate) fn parse_ytdlp_reading(line: &str) -> Option<ProgressUpdate> {
+    let percent = parse_ytdlp_progress(line)?;
+    let mut reading = ProgressUpdate {
+        percent,
+        ..ProgressUpdate::default()
+    };
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let after = |key: &str| {
+        let pos = tokens.iter().position(|t| *t == key)?;
+        tokens[pos + 1..]
+            .iter()
+            .copied()
+            .find(|t| *t != "~")
+            .map(|t| t.trim_start_matches('~'))
+    };
+    reading.total_bytes = after("of").and_then(parse_size).map(|b| b as u64);
+    reading.speed_bps = after("at").and_then(|t| parse_size(t.strip_suffix("/s")?));
+    reading.eta_secs = after("ETA").and_then(parse_clock);
+    let raw_percent = tokens
+        .iter()
+        .find_map(|t| t.strip_suffix('%')?.parse::<f64>().ok());
+    if let (Some(total), Some(pct)) = (reading.total_bytes, raw_percent) {
+        reading.downloaded_bytes = Some((total as f64 * pct.clamp(0.0, 100.0) / 100.0) as u64);
+    }
+    Some(reading)
+}
+
+/// `123.45MiB` → bytes. yt-dlp prints binary units; decimal ones are
+/// accepted too.
+fn parse_size(s: &str) -> Option<f64> {
+    let split = s.find(|c: char| c.is_ascii_alphabetic())?;
+    let (num, unit) = s.split_at(split);
+    let value: f64 = num.parse().ok()?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "KB" | "kB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        _ => return None,
+    };
+    Some(value * multiplier)
+}
+
+/// `00:31` / `1:02:03` → seconds.
+fn parse_clock(s: &str) -> Option<u64> {
+    s.split(':')
+        .try_fold(0u64, |acc, part| Some(acc * 60 + part.parse::<u64>().ok()?))
+}
+
+/// Video id of a YouTube watch, shorts, embed, live or youtu.be link.
+fn youtube_id(url: &reqwest::Url) -> Option<String> {
+    let host = url.host_str()?;
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .unwrap_or(host);
+    let mut segments = url.path_segments()?;
+    let id = match (host, segments.next()?) {
+        ("youtu.be", id) => id.to_string(),
+        ("youtube.com", "watch") => url.query_pairs().find(|(k, _)| k == "v")?.1.into_owned(),
+        ("youtube.com", "shorts" | "embed" | "live") => segments.next()?.to_string(),
+        _ => return None,
+    };
+    let valid = id.len() == 11
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    valid.then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_progress_line() {
+        let r = parse_ytdlp_reading(
+            "[download]  50.0% of ~  10.00MiB at    2.00MiB/s ETA 00:05 (frag 5/10)",
+        )
+        .unwrap();
+        assert_eq!(r.percent, 49); // fragment count wins, scaled to 0-99
+        assert_eq!(r.total_bytes, Some(10 * 1024 * 1024));
+        assert_eq!(r.downloaded_bytes, Some(5 * 1024 * 1024));
+        assert_eq!(r.speed_bps, Some(2.0 * 1024.0 * 1024.0));
+        assert_eq!(r.eta_secs, Some(5));
+    }
+
+    #[test]
+    fn tolerates_unknown_fields() {
+        let r =
+            parse_ytdlp_reading("[download]   0.0% of 1.50GiB at Unknown B/s ETA Unknown").unwrap();
+        assert_eq!(r.percent, 0);
+        assert_eq!(r.total_bytes, Some(1_610_612_736));
+        assert_eq!(r.speed_bps, None);
+        assert_eq!(r.eta_secs, None);
+
+        assert!(parse_ytdlp_reading("[info] Downloading 1 format(s): 137+140").is_none());
+        assert_eq!(parse_clock("1:02:03"), Some(3723));
+    }
+
+    #[test]
+    fn subtitles_prefer_vtt_and_skip_live_chat() {
+        let raw: YtDlpInfo = serde_json::from_str(
+            r#"{"subtitles": {
+                "en": [{"ext": "json3", "url": "https://s/en.json3"},
+                       {"ext": "srt", "url": "https://s/en.srt"},
+                       {"ext": "vtt", "url": "https://s/en.vtt", "name": "English"}],
+                "cs": [{"ext": "srv3", "url": "https://s/cs.srv3"},
+                       {"ext": "srt", "url": "https://s/cs.srt"}],
+                "de": [{"ext": "ttml", "url": "https://s/de.ttml"}],
+                "live_chat": [{"ext": "json", "url": "https://s/chat"}]
+            }}"#,
+        )
+        .unwrap();
+        let tracks = subtitle_tracks(raw.subtitles);
+        let summary: Vec<_> = tracks
+            .iter()
+            .map(|t| (t.lang.as_str(), t.label.as_str(), t.ext.as_str()))
+            .collect();
+        assert_eq!(summary, [("cs", "cs", "srt"), ("en", "English", "vtt")]);
+        assert_eq!(tracks[1].url, "https://s/en.vtt");
+    }
+}
@@ -1,13 +1,19 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+mod audio;
+mod export;
 mod extractors;
+mod progress;
+mod segments;
+mod subtitles;
 mod transcode;
 
-pub use transcode::{
-    WhatsAppPart, WhatsAppResult, convert_for_whatsapp, ensure_container, estimate_whatsapp_parts,
-    extract_audio,
-};
+pub use audio::{AudioTags, CoverArt, convert_audio, download_audio_source, fetch_cover_art};
+pub use export::{ExportPart, ExportResult, export_for_target};
+pub use progress::{ProgressTracker, ProgressUpdate};
+pub use subtitles::{Cue, SubtitleFormat, Subtitles, add_subtitles, fetch_subtitles};
+pub use transcode::{ensure_container, export_animation, trim_clip};
 
 /// Information about a video extracted from a URL.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +23,10 @@ pub struct VideoInfo {
     pub duration: Option<f64>,
     pub uploader: Option<String>,
     pub formats: Vec<VideoFormat>,
+    /// Subtitle tracks the source offers; empty when it has none or the
+    /// extractor can't tell.
+    #[serde(default)]
+    pub subtitles: Vec<SubtitleTrack>,
 }
 
 /// A single downloadable format/quality of a video.
@@ -29,42 +39,36 @@ pub struct VideoFormat {
     pub filesize_approx: Option<u64>,
 }
 
-/// Check if URL is an Instagram reel/video.
-fn is_instagram_url(url: &str) -> bool {
-    url.contains("instagram.com")
-}
-
-/// Check if URL is Nova.cz (validates host, not substring).
-fn is_nova_url(url: &str) -> bool {
-    reqwest::Url::parse(url)
-        .ok()
-        .and_then(|parsed| parsed.host_str().map(|host| host.to_ascii_lowercase()))
-        .map(|host| host == "nova.cz" || host.ends_with(".nova.cz"))
-        .unwrap_or(false)
+/// A subtitle track of a video.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    /// Language code as the source gives it (`cs`, `en-US`).
+    pub lang: String,
+    /// Human-readable name (`Čeština`), or the code when there is none.
+    pub label: String,
+    pub url: String,
+    /// `vtt`, `srt` or `ass`.
+    pub ext: String,
 }
 
 // ─── Public API ─────────────────────────────────────────────────────
 
-/// Extract video info. Tries our extractors first, falls back to yt-dlp.
+/// Extract video info. Tries our extractors first, falls back to yt-dlp
+/// (see [`extractors`] for the order and its configuration).
 pub async fn extract_video_info(client: &reqwest::Client, url: &str) -> Result<VideoInfo> {
-    if extractors::seznam::is_seznam_url(url) {
-        extractors::seznam::seznam_extract_info(client, url).await
-    } else if is_instagram_url(url) {
-        extractors::instagram::instagram_extract_info(client, url).await
-    } else {
-        let result = extractors::ytdlp::ytdlp_extract_info(url).await;
-        // If yt-dlp fails for Nova.cz, try Czech proxy to bypass geo-block
-        if result.is_err() && is_nova_url(url) {
-            tracing::warn!("yt-dlp failed for Nova.cz URL, trying Czech proxy fallback");
-            if let Ok(info) = extractors::nova::nova_proxy_extract_info(client, url).await {
-                return Ok(info);
-            }
-        }
-        result
-    }
+    extractors::registry().extract_info(client, url).await
+}
+
+/// The canonical form of a video URL: tracking parameters dropped and the
+/// matching extractor's preferred spelling (`youtu.be/ID` →
+/// `youtube.com/watch?v=ID`). Two URLs with the same canonical form are
+/// the same video, so it keys the info cache and duplicate-job checks.
+pub fn canonical_video_url(url: &str) -> String {
+    extractors::registry().canonical_url(url)
 }
 
-/// Download a video file. Uses direct HTTP for Seznam/Instagram, yt-dlp for others.
+/// Download a video file. Uses direct HTTP for Seznam/Instagram, our own
+/// segment downloader for HLS/DASH manifests, yt-dlp for others.
 /// After the download, always enforces the requested container format
 /// via [`ensure_container`] so the caller gets exactly `.{container}`.
 pub async fn download_video(
@@ -102,47 +106,22 @@ pub async fn download_video_with_progress(
     resolution: &str,
     container: &str,
     output_path: &std::path::Path,
-    progress: Option<std::sync::Arc<std::sync::atomic::AtomicU8>>,
+    progress: Option<ProgressTracker>,
 ) -> Result<u64> {
     // Raw-bytes extractors (Seznam / Instagram) and the Nova proxy
     // hand us arbitrary containers — yt-dlp's container-level flags
     // don't apply here, so the post-download `ensure_container` step
     // is the single source of truth for the final file format.
-    if extractors::seznam::is_seznam_url(url) {
-        let info = extractors::seznam::seznam_extract_info(client, url).await?;
-        let fmt = info
-            .formats
-            .iter()
-            .find(|f| f.format_id == format_id)
-            .or(info.formats.last())
-            .context("No format available")?;
-        extractors::seznam::download_direct(client, &fmt.url, output_path).await?;
-    } else if is_instagram_url(url) {
-        let info = extractors::instagram::instagram_extract_info(client, url).await?;
-        let fmt = info
-            .formats
-            .iter()
-            .find(|f| f.format_id == format_id)
-            .or(info.formats.last())
-            .context("No format available")?;
-        extractors::seznam::download_direct(client, &fmt.url, output_path).await?;
-    } else if format_id == "proxy-hls" {
-        // Nova.cz proxy fallback — `format_id` is a sentinel, not the direct m3u8 URL.
-        // Re-extract to obtain a fresh tokenized manifest URL from `info.formats[0].url`.
-        let info = extractors::nova::nova_proxy_extract_info(client, url).await?;
-        let m3u8 = &info.formats[0].url;
-        extractors::ytdlp::ytdlp_download(m3u8, "best", container, output_path, progress.clone())
-            .await?;
-    } else {
-        extractors::ytdlp::ytdlp_download(
-            url,
-            resolution,
-            container,
-            output_path,
-            progress.clone(),
-        )
+    let req = extractors::DownloadRequest {
+        format_id,
+        resolution,
+        container,
+        output_path,
+        audio_only: false,
+    };
+    extractors::registry()
+        .download(client, url, &req, progress.as_ref())
         .await?;
-    }
 
     // Single source of truth for the final container (#366).
     ensure_container(output_path, container, progress.clone()).await?;
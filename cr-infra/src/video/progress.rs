@@ -0,0 +1,178 @@
+//! Live progress of a download or transcode.
+//!
+//! The extractors and the ffmpeg wrappers write into a [`ProgressTracker`];
+//! the caller reads snapshots from it or subscribes for changes (the SSE
+//! stream behind `/api/video/status/{token}/events`). Backed by a
+//! `tokio::sync::watch` channel, so a slow reader only ever sees the latest
+//! state and writers never block.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::watch;
+
+/// One progress reading. Everything but `percent` is optional because not
+/// every source knows it: yt-dlp reports sizes only for non-fragmented
+/// downloads, ffmpeg knows no total size, and so on.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProgressUpdate {
+    /// 0–100 within the current phase.
+    pub percent: u8,
+    /// Bytes fetched so far; for ffmpeg, bytes of output written so far.
+    pub downloaded_bytes: Option<u64>,
+    /// Expected size; an estimate for fragmented downloads.
+    pub total_bytes: Option<u64>,
+    /// Bytes per second for downloads. For ffmpeg, which reports speed as
+    /// a multiple of real time, this stays `None`.
+    pub speed_bps: Option<f64>,
+    pub eta_secs: Option<u64>,
+}
+
+/// Shared handle to the progress of one job. Cloning is cheap; all clones
+/// see the same state.
+#[derive(Clone)]
+pub struct ProgressTracker {
+    tx: Arc<watch::Sender<ProgressUpdate>>,
+}
+
+impl Default for ProgressTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressTracker {
+    pub fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::Sender::new(ProgressUpdate::default())),
+        }
+    }
+
+    pub fn snapshot(&self) -> ProgressUpdate {
+        *self.tx.borrow()
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.tx.borrow().percent
+    }
+
+    /// Receiver that wakes on every change.
+    pub fn subscribe(&self) -> watch::Receiver<ProgressUpdate> {
+        self.tx.subscribe()
+    }
+
+    /// Replace the whole reading.
+    pub fn update(&self, update: ProgressUpdate) {
+        self.tx.send_if_modified(|current| {
+            let changed = *current != update;
+            *current = update;
+            changed
+        });
+    }
+
+    /// Set the percentage, keeping the rest of the reading. Used for the
+    /// coarse milestones of steps that can't measure themselves.
+    pub fn set_percent(&self, percent: u8) {
+        self.tx.send_if_modified(|current| {
+            let changed = current.percent != percent;
+            current.percent = percent;
+            changed
+        });
+    }
+
+    /// Start a new phase: percentage back to zero, sizes and rates
+    /// forgotten.
+    pub fn reset(&self) {
+        self.update(ProgressUpdate::default());
+    }
+}
+
+/// Turns a running byte count into a [`ProgressUpdate`] with speed and ETA,
+/// for downloads we stream ourselves.
+pub(crate) struct RateMeter {
+    started: Instant,
+    total: Option<u64>,
+}
+
+impl RateMeter {
+    pub(crate) fn new(total: Option<u64>) -> Self {
+        Self {
+            started: Instant::now(),
+            total: total.filter(|&t| t > 0),
+        }
+    }
+
+    pub(crate) fn reading(&self, downloaded: u64) -> ProgressUpdate {
+        rate_reading(downloaded, self.total, self.started.elapsed().as_secs_f64())
+    }
+
+    /// For downloads in `count` pieces of unknown size, `done` of which
+    /// are in: the total is extrapolated from the pieces so far.
+    pub(crate) fn pieces_reading(
+        &self,
+        downloaded: u64,
+        done: usize,
+        count: usize,
+    ) -> ProgressUpdate {
+        let total = estimated_total(downloaded, done, count);
+        rate_reading(downloaded, total, self.started.elapsed().as_secs_f64())
+    }
+}
+
+fn estimated_total(downloaded: u64, done: usize, count: usize) -> Option<u64> {
+    (done > 0 && count > 0).then(|| downloaded * count as u64 / done as u64)
+}
+
+/// Progress after `downloaded` of `total` bytes in `elapsed` seconds.
+/// Capped at 99 % — the caller marks completion.
+fn rate_reading(downloaded: u64, total: Option<u64>, elapsed: f64) -> ProgressUpdate {
+    let speed = (elapsed > 0.0).then(|| downloaded as f64 / elapsed);
+    let percent = total.map_or(0, |t| {
+        (downloaded as f64 / t as f64 * 100.0).min(99.0) as u8
+    });
+    let eta_secs = match (total, speed) {
+        (Some(t), Some(s)) if s > 0.0 => Some((t.saturating_sub(downloaded) as f64 / s) as u64),
+        _ => None,
+    };
+    ProgressUpdate {
+        percent,
+        downloaded_bytes: Some(downloaded),
+        total_bytes: total,
+        speed_bps: speed,
+        eta_secs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_reading_derives_speed_and_eta() {
+        let r = rate_reading(25 * 1024, Some(100 * 1024), 5.0);
+        assert_eq!(r.percent, 25);
+        assert_eq!(r.speed_bps, Some(5.0 * 1024.0));
+        assert_eq!(r.eta_secs, Some(15));
+
+        assert_eq!(estimated_total(3000, 3, 10), Some(10_000));
+        assert_eq!(estimated_total(0, 0, 10), None);
+
+        let unknown = rate_reading(1024, None, 0.0);
+        assert_eq!(unknown.percent, 0);
+        assert_eq!(unknown.speed_bps, None);
+        assert_eq!(unknown.eta_secs, None);
+    }
+
+    #[tokio::test]
+    async fn subscribers_see_changes_but_not_repeats() {
+        let tracker = ProgressTracker::new();
+        let mut rx = tracker.subscribe();
+        tracker.set_percent(40);
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(rx.borrow_and_update().percent, 40);
+        tracker.set_percent(40);
+        assert!(!rx.has_changed().unwrap());
+        tracker.reset();
+        assert_eq!(rx.borrow_and_update().percent, 0);
+    }
+}
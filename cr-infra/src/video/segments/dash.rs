@@ -0,0 +1,478 @@
+//! DASH manifests (MPD). Only static ones, and only their first period:
+//! the video and audio representations with their segments, from a
+//! `SegmentTemplate` (numbered or with a `SegmentTimeline`), a
+//! `SegmentList`, or a single file behind the `BaseURL`.
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+use roxmltree::Node;
+
+use super::{ByteRange, Segment, Track};
+
+/// Guards against manifests that would expand to absurd segment counts.
+const MAX_SEGMENTS: u64 = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Representation {
+    pub id: String,
+    pub bandwidth: u64,
+    pub height: Option<u32>,
+    pub track: Track,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Manifest {
+    pub duration_secs: Option<f64>,
+    pub video: Vec<Representation>,
+    pub audio: Vec<Representation>,
+}
+
+/// Parse the MPD fetched from `base`.
+pub(crate) fn parse(text: &str, base: &Url) -> Result<Manifest> {
+    let doc = roxmltree::Document::parse(text).context("Invalid DASH manifest")?;
+    let mpd = doc.root_element();
+    if mpd.tag_name().name() != "MPD" {
+        anyhow::bail!("Not a DASH manifest");
+    }
+    if mpd.attribute("type") == Some("dynamic") {
+        anyhow::bail!("Live DASH streams are not supported");
+    }
+    let duration_secs = mpd
+        .attribute("mediaPresentationDuration")
+        .map(parse_duration)
+        .transpose()?;
+    let base = with_base_url(mpd, base)?;
+    let period = child(mpd, "Period").context("DASH manifest has no period")?;
+    let period_secs = period
+        .attribute("duration")
+        .map(parse_duration)
+        .transpose()?
+        .or(duration_secs);
+    let base = with_base_url(period, &base)?;
+
+    let mut manifest = Manifest {
+        duration_secs,
+        video: Vec::new(),
+        audio: Vec::new(),
+    };
+    for set in children(period, "AdaptationSet") {
+        let set_base = with_base_url(set, &base)?;
+        for rep in children(set, "Representation") {
+            let list = match content_type(set, rep) {
+                Some("video") => &mut manifest.video,
+                Some("audio") => &mut manifest.audio,
+                // Subtitles, thumbnails.
+                _ => continue,
+            };
+            let id = rep.attribute("id").unwrap_or_default();
+            let bandwidth = rep
+                .attribute("bandwidth")
+                .and_then(|b| b.parse().ok())
+                .unwrap_or(0);
+            let rep_base = with_base_url(rep, &set_base)?;
+            let track = track(set, rep, &rep_base, id, bandwidth, period_secs)
+                .with_context(|| format!("DASH representation {id}"))?;
+            list.push(Representation {
+                id: id.to_string(),
+                bandwidth,
+                height: rep
+                    .attribute("height")
+                    .or_else(|| set.attribute("height"))
+                    .and_then(|h| h.parse().ok()),
+                track,
+            });
+        }
+    }
+    if manifest.video.is_empty() && manifest.audio.is_empty() {
+        anyhow::bail!("DASH manifest lists no audio or video");
+    }
+    Ok(manifest)
+}
+
+/// `video`, `audio`, `text`, … from `contentType` or the MIME type.
+fn content_type<'a>(set: Node<'a, 'a>, rep: Node<'a, 'a>) -> Option<&'a str> {
+    set.attribute("contentType").or_else(|| {
+        rep.attribute("mimeType")
+            .or_else(|| set.attribute("mimeType"))
+            .and_then(|m| m.split('/').next())
+    })
+}
+
+fn track(
+    set: Node,
+    rep: Node,
+    base: &Url,
+    id: &str,
+    bandwidth: u64,
+    period_secs: Option<f64>,
+) -> Result<Track> {
+    let template = Template {
+        rep: child(rep, "SegmentTemplate"),
+        set: child(set, "SegmentTemplate"),
+    };
+    if template.rep.is_some() || template.set.is_some() {
+        return template.expand(base, id, bandwidth, period_secs);
+    }
+    if let Some(list) = child(rep, "SegmentList").or_else(|| child(set, "SegmentList")) {
+        return segment_list(list, base);
+    }
+    // One file with everything; a `SegmentBase` index only matters to
+    // players that seek.
+    Ok(Track {
+        init: None,
+        segments: vec![Segment {
+            url: base.clone(),
+            range: None,
+            key: None,
+        }],
+    })
+}
+
+/// A `SegmentTemplate` on the representation, its adaptation set, or both
+/// — the representation's attributes win.
+struct Template<'a, 'input> {
+    rep: Option<Node<'a, 'input>>,
+    set: Option<Node<'a, 'input>>,
+}
+
+impl Template<'_, '_> {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.rep
+            .and_then(|n| n.attribute(name))
+            .or_else(|| self.set.and_then(|n| n.attribute(name)))
+    }
+
+    fn number(&self, name: &str, default: u64) -> Result<u64> {
+        self.attr(name)
+            .map_or(Ok(default), str::parse)
+            .with_context(|| format!("Invalid SegmentTemplate@{name}"))
+    }
+
+    fn expand(
+        &self,
+        base: &Url,
+        id: &str,
+        bandwidth: u64,
+        period_secs: Option<f64>,
+    ) -> Result<Track> {
+        let media = self
+            .attr("media")
+            .context("SegmentTemplate without media")?;
+        let start_number = self.number("startNumber", 1)?;
+        let timescale = self.number("timescale", 1)?.max(1);
+        let url = |template: &str, number: u64, time: u64| -> Result<Url> {
+            let path = substitute(template, id, bandwidth, number, time)?;
+            base.join(&path).context("Invalid segment URL")
+        };
+        let init = self
+            .attr("initialization")
+            .map(|i| url(i, start_number, 0))
+            .transpose()?
+            .map(|url| Segment {
+                url,
+                range: None,
+                key: None,
+            });
+
+        // (number, time) of every segment.
+        let mut times: Vec<(u64, u64)> = Vec::new();
+        let timeline = self
+            .rep
+            .and_then(|n| child(n, "SegmentTimeline"))
+            .or_else(|| self.set.and_then(|n| child(n, "SegmentTimeline")));
+        let period_end = period_secs.map(|s| (s * timescale as f64).round() as u64);
+        if let Some(timeline) = timeline {
+            let entries: Vec<Node> = children(timeline, "S").collect();
+            let mut time = 0u64;
+            for (i, s) in entries.iter().enumerate() {
+                if let Some(t) = s.attribute("t") {
+                    time = t.parse().context("Invalid S@t")?;
+                }
+                let d: u64 = s
+                    .attribute("d")
+                    .context("S without d")?
+                    .parse()
+                    .context("Invalid S@d")?;
+                if d == 0 {
+                    anyhow::bail!("S@d must not be 0");
+                }
+                let r: i64 = s.attribute("r").map_or(Ok(0), str::parse)?;
+                let repeats = if r >= 0 {
+                    r as u64
+                } else {
+                    // Repeat up to the next entry's start or the period end.
+                    let end = entries
+                        .get(i + 1)
+                        .and_then(|n| n.attribute("t"))
+                        .and_then(|t| t.parse().ok())
+                        .or(period_end)
+                        .context("Open-ended S@r without a period duration")?;
+                    end.saturating_sub(time).div_ceil(d).saturating_sub(1)
+                };
+                for _ in 0..=repeats {
+                    times.push((start_number + times.len() as u64, time));
+                    time += d;
+                    if times.len() as u64 > MAX_SEGMENTS {
+                        anyhow::bail!("Too many DASH segments");
+                    }
+                }
+            }
+        } else {
+            let duration = self.number("duration", 0)?;
+            if duration == 0 {
+                anyhow::bail!("SegmentTemplate without duration or timeline");
+            }
+            let end = period_end.context("Numbered segments without a period duration")?;
+            let count = end.div_ceil(duration);
+            if count > MAX_SEGMENTS {
+                anyhow::bail!("Too many DASH segments");
+            }
+            times.extend((0..count).map(|n| (start_number + n, n * duration)));
+        }
+
+        let segments = times
+            .into_iter()
+            .map(|(number, time)| {
+                Ok(Segment {
+                    url: url(media, number, time)?,
+                    range: None,
+                    key: None,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Track { init, segments })
+    }
+}
+
+/// Fill in `$RepresentationID$`, `$Number$`, `$Time$` and `$Bandwidth$`,
+/// the numeric ones optionally zero-padded (`$Number%05d$`).
+fn substitute(template: &str, id: &str, bandwidth: u64, number: u64, time: u64) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after.find('$').context("Unclosed $ in SegmentTemplate")?;
+        let token = &after[..end];
+        let (name, format) = token.split_once('%').unwrap_or((token, ""));
+        let width: usize = match format.strip_suffix('d') {
+            Some(w) => w.parse().context("Invalid width in SegmentTemplate")?,
+            None => 0,
+        };
+        match name {
+            "" => out.push('$'),
+            "RepresentationID" => out.push_str(id),
+            "Number" => out.push_str(&format!("{number:0width$}")),
+            "Time" => out.push_str(&format!("{time:0width$}")),
+            "Bandwidth" => out.push_str(&format!("{bandwidth:0width$}")),
+            other => anyhow::bail!("Unknown SegmentTemplate identifier ${other}$"),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn segment_list(list: Node, base: &Url) -> Result<Track> {
+    let segment = |url: Option<&str>, range: Option<&str>| -> Result<Segment> {
+        Ok(Segment {
+            url: match url {
+                Some(u) => base.join(u).context("Invalid segment URL")?,
+                None => base.clone(),
+            },
+            range: range.map(parse_range).transpose()?,
+            key: None,
+        })
+    };
+    let init = child(list, "Initialization")
+        .map(|i| segment(i.attribute("sourceURL"), i.attribute("range")))
+        .transpose()?;
+    let segments = children(list, "SegmentURL")
+        .map(|s| segment(s.attribute("media"), s.attribute("mediaRange")))
+        .collect::<Result<Vec<_>>>()?;
+    if segments.is_empty() {
+        anyhow::bail!("SegmentList without segments");
+    }
+    Ok(Track { init, segments })
+}
+
+/// `first-last`, inclusive.
+fn parse_range(s: &str) -> Result<ByteRange> {
+    let (first, last) = s.split_once('-').context("Invalid byte range")?;
+    let first: u64 = first.parse().context("Invalid byte range")?;
+    let last: u64 = last.parse().context("Invalid byte range")?;
+    if last < first {
+        anyhow::bail!("Invalid byte range {s}");
+    }
+    Ok(ByteRange {
+        offset: first,
+        length: last - first + 1,
+    })
+}
+
+/// ISO 8601 duration (`PT1H2M3.5S`, `P1DT2H`) in seconds.
+fn parse_duration(s: &str) -> Result<f64> {
+    let rest = s
+        .strip_prefix('P')
+        .with_context(|| format!("Invalid duration {s}"))?;
+    let (date, time) = rest.split_once('T').unwrap_or((rest, ""));
+    let mut secs = 0.0;
+    for (part, units) in [
+        (
+            date,
+            &[
+                ('Y', 31_536_000.0),
+                ('M', 2_592_000.0),
+                ('W', 604_800.0),
+                ('D', 86_400.0),
+            ][..],
+        ),
+        (time, &[('H', 3_600.0), ('M', 60.0), ('S', 1.0)][..]),
+    ] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || c == '.' {
+                number.push(c);
+                continue;
+            }
+            let (_, unit) = units
+                .iter()
+                .find(|(u, _)| *u == c)
+                .with_context(|| format!("Invalid duration {s}"))?;
+            let value: f64 = number
+                .parse()
+                .with_context(|| format!("Invalid duration {s}"))?;
+            secs += value * unit;
+            number.clear();
+        }
+        if !number.is_empty() {
+            anyhow::bail!("Invalid duration {s}");
+        }
+    }
+    Ok(secs)
+}
+
+/// `node`'s base URL: its `BaseURL` child resolved against `base`.
+fn with_base_url(node: Node, base: &Url) -> Result<Url> {
+    match child(node, "BaseURL").and_then(|n| n.text()) {
+        Some(url) => base.join(url.trim()).context("Invalid BaseURL"),
+        None => Ok(base.clone()),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &'a str) -> Option<Node<'a, 'input>> {
+    children(node, name).next()
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://origin.example/vod/123/manifest.mpd").unwrap()
+    }
+
+    #[test]
+    fn templates_expand_by_number_and_timeline() {
+        let m = parse(include_str!("fixtures/dash_manifest.mpd"), &base()).unwrap();
+        assert_eq!(m.duration_secs, Some(20.0));
+        let heights: Vec<_> = m.video.iter().map(|r| r.height).collect();
+        assert_eq!(heights, [Some(360), Some(720)]);
+        // Subtitles are skipped.
+        assert_eq!(m.audio.len(), 1);
+
+        let v720 = &m.video[1].track;
+        assert_eq!(
+            v720.init.as_ref().unwrap().url.as_str(),
+            "https://cdn.example/dash/v720/init.mp4"
+        );
+        // 20 s in 4 s segments.
+        let urls: Vec<_> = v720.segments.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls.len(), 5);
+        assert_eq!(urls[0], "https://cdn.example/dash/v720/seg-00001.m4s");
+        assert_eq!(urls[4], "https://cdn.example/dash/v720/seg-00005.m4s");
+
+        // t=0 d=96000 r=2, then r=-1 fills up to the 20 s period end.
+        let audio = &m.audio[0].track;
+        let urls: Vec<_> = audio.segments.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls.len(), 6);
+        assert_eq!(urls[1], "https://cdn.example/dash/audio/a128/96000.m4s");
+        assert_eq!(urls[5], "https://cdn.example/dash/audio/a128/768000.m4s");
+    }
+
+    #[test]
+    fn segment_lists_and_single_files() {
+        let mpd = r#"<?xml version="1.0"?>
+<MPD xmlns="urn:mpeg:dash:schema:mpd:2011" type="static" mediaPresentationDuration="PT1M0.5S">
+  <Period>
+    <AdaptationSet mimeType="video/mp4">
+      <Representation id="v" bandwidth="900000" height="480">
+        <BaseURL>video.mp4</BaseURL>
+        <SegmentList>
+          <Initialization range="0-799"/>
+          <SegmentURL mediaRange="800-1799"/>
+          <SegmentURL mediaRange="1800-2499"/>
+        </SegmentList>
+      </Representation>
+    </AdaptationSet>
+    <AdaptationSet mimeType="audio/mp4">
+      <Representation id="a" bandwidth="128000"><BaseURL>https://audio.example/a.m4a</BaseURL></Representation>
+    </AdaptationSet>
+  </Period>
+</MPD>"#;
+        let m = parse(mpd, &base()).unwrap();
+        assert_eq!(m.duration_secs, Some(60.5));
+        let video = &m.video[0].track;
+        assert_eq!(
+            video.init.as_ref().unwrap().range,
+            Some(ByteRange {
+                offset: 0,
+                length: 800
+            })
+        );
+        assert_eq!(
+            video.segments[1].range,
+            Some(ByteRange {
+                offset: 1800,
+                length: 700
+            })
+        );
+        assert_eq!(
+            video.segments[1].url.as_str(),
+            "https://origin.example/vod/123/video.mp4"
+        );
+        assert_eq!(
+            m.audio[0].track.segments[0].url.as_str(),
+            "https://audio.example/a.m4a"
+        );
+    }
+
+    #[test]
+    fn durations_templates_and_live_streams() {
+        assert_eq!(parse_duration("PT1H2M3.5S").unwrap(), 3723.5);
+        assert_eq!(parse_duration("P1DT1S").unwrap(), 86_401.0);
+        assert!(parse_duration("1H").is_err());
+        assert_eq!(
+            substitute(
+                "$RepresentationID$/$Time$-$Number%03d$$$.m4s",
+                "v1",
+                0,
+                7,
+                9000
+            )
+            .unwrap(),
+            "v1/9000-007$.m4s"
+        );
+        assert!(substitute("$Foo$", "v", 0, 1, 0).is_err());
+        let live = r#"<MPD type="dynamic"><Period/></MPD>"#;
+        assert!(parse(live, &base()).is_err());
+    }
+}
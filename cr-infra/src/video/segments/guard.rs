@@ -0,0 +1,165 @@
+//! Outbound request policy of the segment downloader.
+//!
+//! The manifest URL is user input and every playlist, key and segment URL
+//! comes from a document that URL served, so each of them could point the
+//! server at its own network. Requests go out only over http(s), to hosts
+//! whose every address is public: IP literals are checked here, host names
+//! by [`PublicResolver`] at connect time (which also covers redirects and
+//! DNS answers that change between check and use), and redirect targets by
+//! the client's redirect policy.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, LazyLock};
+
+use anyhow::Result;
+use reqwest::Url;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// Redirects followed per request, as reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+
+/// HTTP access for the segment downloader.
+pub(crate) struct Http {
+    client: reqwest::Client,
+    guarded: bool,
+}
+
+static PUBLIC: LazyLock<Http> = LazyLock::new(|| Http {
+    client: reqwest::Client::builder()
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if let Err(e) = check_url(attempt.url()) {
+                attempt.error(e.to_string())
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .unwrap_or_default(),
+    guarded: true,
+});
+
+impl Http {
+    /// The guarded client every download uses.
+    pub(crate) fn public() -> &'static Self {
+        &PUBLIC
+    }
+
+    /// `client` as is, for tests against a loopback fixture server.
+    #[cfg(test)]
+    pub(crate) fn unguarded(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            guarded: false,
+        }
+    }
+
+    /// GET `url`, refused up front when it fails [`check_url`].
+    pub(crate) fn get(&self, url: &Url) -> Result<reqwest::RequestBuilder> {
+        if self.guarded {
+            check_url(url)?;
+        }
+        Ok(self
+            .client
+            .get(url.clone())
+            .header("User-Agent", "Mozilla/5.0"))
+    }
+}
+
+/// http(s) only, and no IP literal outside the public address space.
+/// Host names are left to [`PublicResolver`].
+pub(crate) fn check_url(url: &Url) -> Result<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("Refusing {} URL", url.scheme());
+    }
+    let Some(host) = url.host_str() else {
+        anyhow::bail!("URL has no host");
+    };
+    // IPv6 literals keep their brackets in `host_str`.
+    match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) if !is_public(ip) => anyhow::bail!("Refusing non-public address {ip}"),
+        _ => Ok(()),
+    }
+}
+
+/// Not loopback, private, link-local, unspecified, shared (CGNAT),
+/// broadcast or multicast.
+pub(crate) fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_v4(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(v6),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)))
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7 unique local, fe80::/10 link-local
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80)
+}
+
+/// System resolver that fails the lookup when any address of the host is
+/// not public, rather than connecting to the ones that are.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|a| !is_public(a.ip())) {
+                return Err(format!("{host} resolves to non-public address {}", addr.ip()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_http_urls_pass() {
+        let ok = |u: &str| check_url(&Url::parse(u).unwrap()).is_ok();
+        assert!(ok("https://cdn.example.com/a.m3u8"));
+        assert!(ok("http://93.184.216.34/a.ts"));
+        assert!(!ok("file:///etc/passwd"));
+        assert!(!ok("ftp://example.com/a.ts"));
+        assert!(!ok("http://127.0.0.1:8080/a.m3u8"));
+        assert!(!ok("http://10.1.2.3/a.ts"));
+        assert!(!ok("http://169.254.169.254/latest/meta-data/"));
+        assert!(!ok("http://0.0.0.0/"));
+        assert!(!ok("http://[::1]/"));
+        assert!(!ok("http://[::ffff:192.168.1.1]/"));
+        assert!(!ok("http://[fd00::1]/"));
+        assert!(!ok("http://[fe80::1]/"));
+    }
+
+    #[test]
+    fn shared_address_space_is_not_public() {
+        assert!(!is_public("100.64.0.1".parse().unwrap()));
+        assert!(is_public("100.128.0.1".parse().unwrap()));
+        assert!(is_public("2606:4700::1111".parse().unwrap()));
+    }
+}
@@ -0,0 +1,329 @@
+//! HLS playlists (RFC 8216): master playlists list the variants, media
+//! playlists the segments of one of them.
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+
+use super::{ByteRange, Segment, SegmentKey, Track};
+
+/// One `#EXT-X-STREAM-INF` entry of a master playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HlsVariant {
+    pub uri: Url,
+    pub bandwidth: u64,
+    pub height: Option<u32>,
+    /// `AUDIO` group whose renditions carry the sound separately.
+    pub audio_group: Option<String>,
+}
+
+/// An `#EXT-X-MEDIA:TYPE=AUDIO` rendition with its own playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HlsAudio {
+    pub group_id: String,
+    pub uri: Url,
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MasterPlaylist {
+    pub variants: Vec<HlsVariant>,
+    pub audio: Vec<HlsAudio>,
+}
+
+impl MasterPlaylist {
+    /// The playlist of the rendition that carries `variant`'s sound, if
+    /// it isn't muxed into the variant itself.
+    pub(crate) fn audio_for(&self, variant: &HlsVariant) -> Option<&Url> {
+        let group = variant.audio_group.as_deref()?;
+        let renditions = || self.audio.iter().filter(|a| a.group_id == group);
+        renditions()
+            .find(|a| a.default)
+            .or_else(|| renditions().next())
+            .map(|a| &a.uri)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MediaPlaylist {
+    pub track: Track,
+    pub duration_secs: f64,
+    /// `#EXT-X-ENDLIST` seen; without it the playlist is a live stream.
+    pub ended: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Playlist {
+    Master(MasterPlaylist),
+    Media(Box<MediaPlaylist>),
+}
+
+/// Parse the playlist fetched from `base`, resolving its URIs against it.
+pub(crate) fn parse(text: &str, base: &Url) -> Result<Playlist> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some("#EXTM3U") {
+        anyhow::bail!("Not an HLS playlist");
+    }
+    if text.contains("#EXT-X-STREAM-INF") {
+        parse_master(lines, base).map(Playlist::Master)
+    } else {
+        parse_media(lines, base).map(|m| Playlist::Media(Box::new(m)))
+    }
+}
+
+fn parse_master<'a>(lines: impl Iterator<Item = &'a str>, base: &Url) -> Result<MasterPlaylist> {
+    let mut variants = Vec::new();
+    let mut audio = Vec::new();
+    let mut pending: Option<Vec<(String, String)>> = None;
+    for line in lines {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            pending = Some(parse_attributes(attrs));
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MEDIA:") {
+            let attrs = parse_attributes(attrs);
+            // Renditions without a URI are muxed into the variants.
+            if attr(&attrs, "TYPE") == Some("AUDIO")
+                && let (Some(group_id), Some(uri)) = (attr(&attrs, "GROUP-ID"), attr(&attrs, "URI"))
+            {
+                audio.push(HlsAudio {
+                    group_id: group_id.to_string(),
+                    uri: base.join(uri).context("Invalid audio rendition URI")?,
+                    default: attr(&attrs, "DEFAULT") == Some("YES"),
+                });
+            }
+        } else if !line.starts_with('#')
+            && let Some(attrs) = pending.take()
+        {
+            variants.push(HlsVariant {
+                uri: base.join(line).context("Invalid variant URI")?,
+                bandwidth: attr(&attrs, "BANDWIDTH")
+                    .and_then(|b| b.parse().ok())
+                    .unwrap_or(0),
+                height: attr(&attrs, "RESOLUTION")
+                    .and_then(|r| r.split_once('x'))
+                    .and_then(|(_, h)| h.parse().ok()),
+                audio_group: attr(&attrs, "AUDIO").map(str::to_string),
+            });
+        }
+    }
+    if variants.is_empty() {
+        anyhow::bail!("HLS master playlist lists no variants");
+    }
+    Ok(MasterPlaylist { variants, audio })
+}
+
+fn parse_media<'a>(lines: impl Iterator<Item = &'a str>, base: &Url) -> Result<MediaPlaylist> {
+    let mut track = Track::default();
+    let mut duration_secs = 0.0;
+    let mut ended = false;
+    let mut sequence: u64 = 0;
+    let mut key: Option<KeyState> = None;
+    let mut range: Option<ByteRange> = None;
+    // Where the next byte range without an offset starts.
+    let mut range_end: u64 = 0;
+    for line in lines {
+        if let Some(n) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = n.parse().context("Invalid media sequence")?;
+        } else if let Some(d) = line.strip_prefix("#EXTINF:") {
+            let d = d.split(',').next().unwrap_or_default();
+            duration_secs += d.parse::<f64>().unwrap_or(0.0);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            key = parse_key(&parse_attributes(attrs), base)?;
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            let attrs = parse_attributes(attrs);
+            let uri = attr(&attrs, "URI").context("EXT-X-MAP without a URI")?;
+            track.init = Some(Segment {
+                url: base.join(uri).context("Invalid init segment URI")?,
+                range: attr(&attrs, "BYTERANGE")
+                    .map(|r| parse_byte_range(r, 0))
+                    .transpose()?,
+                key: None,
+            });
+        } else if let Some(r) = line.strip_prefix("#EXT-X-BYTERANGE:") {
+            let parsed = parse_byte_range(r, range_end)?;
+            range_end = parsed.offset + parsed.length;
+            range = Some(parsed);
+        } else if line == "#EXT-X-ENDLIST" {
+            ended = true;
+        } else if !line.starts_with('#') {
+            track.segments.push(Segment {
+                url: base.join(line).context("Invalid segment URI")?,
+                range: range.take(),
+                key: key.as_ref().map(|k| SegmentKey {
+                    url: k.url.clone(),
+                    // Without an explicit IV, the media sequence number is.
+                    iv: k.iv.unwrap_or_else(|| u128::from(sequence).to_be_bytes()),
+                }),
+            });
+            sequence += 1;
+        }
+    }
+    if track.segments.is_empty() {
+        anyhow::bail!("HLS media playlist lists no segments");
+    }
+    Ok(MediaPlaylist {
+        track,
+        duration_secs,
+        ended,
+    })
+}
+
+/// The encryption in force after an `#EXT-X-KEY`.
+struct KeyState {
+    url: Url,
+    iv: Option<[u8; 16]>,
+}
+
+fn parse_key(attrs: &[(String, String)], base: &Url) -> Result<Option<KeyState>> {
+    match attr(attrs, "METHOD") {
+        Some("NONE") => Ok(None),
+        Some("AES-128") => {
+            let uri = attr(attrs, "URI").context("AES-128 key without a URI")?;
+            let iv = attr(attrs, "IV").map(parse_iv).transpose()?;
+            Ok(Some(KeyState {
+                url: base.join(uri).context("Invalid key URI")?,
+                iv,
+            }))
+        }
+        Some(method) => anyhow::bail!("HLS encryption {method} is not supported"),
+        None => anyhow::bail!("EXT-X-KEY without a METHOD"),
+    }
+}
+
+/// `0x` and 32 hex digits.
+fn parse_iv(s: &str) -> Result<[u8; 16]> {
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .context("IV must start with 0x")?;
+    let value = u128::from_str_radix(hex, 16).context("Invalid IV")?;
+    Ok(value.to_be_bytes())
+}
+
+/// `length[@offset]`; a missing offset continues from `next_offset`.
+fn parse_byte_range(s: &str, next_offset: u64) -> Result<ByteRange> {
+    let (length, offset) = match s.split_once('@') {
+        Some((length, offset)) => (length, offset.parse().context("Invalid byte range")?),
+        None => (s, next_offset),
+    };
+    Ok(ByteRange {
+        offset,
+        length: length.parse().context("Invalid byte range")?,
+    })
+}
+
+/// `KEY=value,KEY="quoted, value"` → pairs, quotes removed.
+fn parse_attributes(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = s;
+    while let Some((key, after)) = rest.split_once('=') {
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                let next = quoted.get(end + 1..).unwrap_or_default();
+                (&quoted[..end], next.strip_prefix(',').unwrap_or(next))
+            }
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        attrs.push((key.trim().to_string(), value.to_string()));
+        rest = next;
+    }
+    attrs
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://cdn.example/vod/show/master.m3u8?t=1").unwrap()
+    }
+
+    #[test]
+    fn master_lists_variants_and_audio_renditions() {
+        let Playlist::Master(master) =
+            parse(include_str!("fixtures/hls_master.m3u8"), &base()).unwrap()
+        else {
+            panic!("expected a master playlist");
+        };
+        let heights: Vec<_> = master.variants.iter().map(|v| v.height).collect();
+        assert_eq!(heights, [Some(360), Some(720), Some(1080)]);
+        assert_eq!(master.variants[1].bandwidth, 2_800_000);
+        assert_eq!(
+            master.variants[1].uri.as_str(),
+            "https://cdn.example/vod/show/720p/index.m3u8"
+        );
+        assert_eq!(
+            master.audio_for(&master.variants[2]).map(Url::as_str),
+            Some("https://cdn.example/vod/show/audio/cs.m3u8")
+        );
+        assert_eq!(master.audio_for(&master.variants[0]), None);
+    }
+
+    #[test]
+    fn media_playlist_carries_keys_ranges_and_init() {
+        let Playlist::Media(media) =
+            parse(include_str!("fixtures/hls_media.m3u8"), &base()).unwrap()
+        else {
+            panic!("expected a media playlist");
+        };
+        assert!(media.ended);
+        assert_eq!(media.duration_secs, 24.0);
+        let init = media.track.init.as_ref().unwrap();
+        assert_eq!(init.url.as_str(), "https://cdn.example/vod/show/init.mp4");
+        assert_eq!(
+            init.range,
+            Some(ByteRange {
+                offset: 0,
+                length: 720
+            })
+        );
+
+        let segs = &media.track.segments;
+        assert_eq!(segs.len(), 4);
+        // Byte ranges continue from the previous one.
+        assert_eq!(
+            segs[0].range,
+            Some(ByteRange {
+                offset: 720,
+                length: 1000
+            })
+        );
+        assert_eq!(
+            segs[1].range,
+            Some(ByteRange {
+                offset: 1720,
+                length: 1000
+            })
+        );
+        // The first key has an IV; the second falls back to the sequence.
+        let key = segs[0].key.as_ref().unwrap();
+        assert_eq!(key.url.as_str(), "https://keys.example/k1");
+        assert_eq!(key.iv[15], 0x2a);
+        let key = segs[2].key.as_ref().unwrap();
+        assert_eq!(key.url.as_str(), "https://cdn.example/vod/show/k2.bin");
+        assert_eq!(key.iv, 12u128.to_be_bytes());
+        assert_eq!(segs[3].key, None);
+    }
+
+    #[test]
+    fn attributes_keep_quoted_commas() {
+        let attrs =
+            parse_attributes(r#"BANDWIDTH=800000,CODECS="avc1.4d401e,mp4a.40.2",AUDIO="aac""#);
+        assert_eq!(attr(&attrs, "CODECS"), Some("avc1.4d401e,mp4a.40.2"));
+        assert_eq!(attr(&attrs, "AUDIO"), Some("aac"));
+        assert!(
+            parse(
+                "#EXTM3U\n#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"k\"\nseg.ts\n",
+                &base()
+            )
+            .is_err()
+        );
+        assert!(parse("<html>", &base()).is_err());
+    }
+}
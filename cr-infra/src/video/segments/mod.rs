@@ -0,0 +1,729 @@
+//! Native HLS and DASH downloads.
+//!
+//! The manifest is parsed here ([`hls`], [`dash`]), a variant picked by
+//! resolution, and its segments fetched [`SEGMENT_CONCURRENCY`] at a time
+//! and written out in order — AES-128 encrypted HLS segments are decrypted
+//! on the way. When the sound comes separately (DASH audio sets, HLS audio
+//! renditions) both tracks are downloaded and muxed into Matroska with
+//! ffmpeg; the caller's `ensure_container` then turns whatever landed on
+//! disk into the requested container.
+
+use std::collections::HashMap;
+
+use aes::cipher::{BlockDecryptMut, KeyIvInit, block_padding::Pkcs7};
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use reqwest::Url;
+use tokio::io::AsyncWriteExt;
+
+use super::ProgressTracker;
+use super::progress::RateMeter;
+use super::transcode::run_ffmpeg;
+
+mod dash;
+mod guard;
+mod hls;
+
+use guard::Http;
+
+/// Segments in flight at once per download.
+pub(crate) const SEGMENT_CONCURRENCY: usize = 6;
+
+/// Tries per segment before the download fails.
+const SEGMENT_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ByteRange {
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// AES-128-CBC key of an HLS segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SegmentKey {
+    pub url: Url,
+    pub iv: [u8; 16],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Segment {
+    pub url: Url,
+    /// Part of the resource at `url`; `None` for all of it.
+    pub range: Option<ByteRange>,
+    pub key: Option<SegmentKey>,
+}
+
+/// What makes up one audio or video stream: the initialization segment
+/// (fMP4) and the media segments, in playback order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Track {
+    pub init: Option<Segment>,
+    pub segments: Vec<Segment>,
+}
+
+impl Track {
+    fn pieces(&self) -> impl Iterator<Item = &Segment> {
+        self.init.iter().chain(&self.segments)
+    }
+}
+
+/// One quality a manifest offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StreamVariant {
+    pub height: Option<u32>,
+    pub bandwidth: u64,
+}
+
+/// What a manifest offers, for the format list.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StreamInfo {
+    /// Worst to best, one per height.
+    pub variants: Vec<StreamVariant>,
+    pub duration_secs: Option<f64>,
+}
+
+/// Whether `url` points straight at an http(s) HLS or DASH manifest.
+pub(crate) fn is_manifest_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|u| {
+        let path = u.path().to_ascii_lowercase();
+        matches!(u.scheme(), "http" | "https")
+            && (path.ends_with(".m3u8") || path.ends_with(".mpd"))
+    })
+}
+
+/// The variants of the manifest at `url`.
+pub(crate) async fn probe(url: &str) -> Result<StreamInfo> {
+    probe_with(Http::public(), url).await
+}
+
+async fn probe_with(client: &Http, url: &str) -> Result<StreamInfo> {
+    let url = Url::parse(url).context("Invalid manifest URL")?;
+    let (variants, duration_secs) = match fetch_manifest(client, &url).await? {
+        Manifest::Hls(hls::Playlist::Media(media)) => (
+            vec![StreamVariant {
+                height: None,
+                bandwidth: 0,
+            }],
+            Some(media.duration_secs),
+        ),
+        Manifest::Hls(hls::Playlist::Master(master)) => {
+            // The master playlist knows no durations; any variant does.
+            let duration = fetch_media_playlist(client, &master.variants[0].uri)
+                .await
+                .ok()
+                .map(|m| m.duration_secs);
+            let variants = master
+                .variants
+                .iter()
+                .map(|v| StreamVariant {
+                    height: v.height,
+                    bandwidth: v.bandwidth,
+                })
+                .collect();
+            (variants, duration)
+        }
+        Manifest::Dash(m) => {
+            let reps = if m.video.is_empty() {
+                &m.audio
+            } else {
+                &m.video
+            };
+            let variants = reps
+                .iter()
+                .map(|r| StreamVariant {
+                    height: r.height,
+                    bandwidth: r.bandwidth,
+                })
+                .collect();
+            (variants, m.duration_secs)
+        }
+    };
+    Ok(StreamInfo {
+        variants: distinct_heights(variants),
+        duration_secs,
+    })
+}
+
+/// Sorted worst to best, keeping the highest bandwidth of each height.
+fn distinct_heights(mut variants: Vec<StreamVariant>) -> Vec<StreamVariant> {
+    variants.sort_by_key(|v| (v.height, v.bandwidth));
+    let mut out: Vec<StreamVariant> = Vec::with_capacity(variants.len());
+    for v in variants {
+        match out.last_mut() {
+            Some(last) if last.height.is_some() && last.height == v.height => *last = v,
+            _ => out.push(v),
+        }
+    }
+    out
+}
+
+/// Download the stream behind the manifest at `url` to `output_path`: the
+/// best variant no taller than `max_height` (the best overall for `None`),
+/// or with `audio_only` just the sound when it comes separately. Returns
+/// the size written.
+pub(crate) async fn download_stream(
+    url: &str,
+    max_height: Option<u32>,
+    audio_only: bool,
+    output_path: &std::path::Path,
+    progress: Option<&ProgressTracker>,
+) -> Result<u64> {
+    download_with(
+        Http::public(),
+        url,
+        max_height,
+        audio_only,
+        output_path,
+        progress,
+    )
+    .await
+}
+
+async fn download_with(
+    client: &Http,
+    url: &str,
+    max_height: Option<u32>,
+    audio_only: bool,
+    output_path: &std::path::Path,
+    progress: Option<&ProgressTracker>,
+) -> Result<u64> {
+    let url = Url::parse(url).context("Invalid manifest URL")?;
+    let (main, audio) = select_tracks(client, &url, max_height, audio_only).await?;
+    let keys = fetch_keys(
+        client,
+        main.pieces().chain(audio.iter().flat_map(Track::pieces)),
+    )
+    .await?;
+    let count = main.pieces().count() + audio.as_ref().map_or(0, |a| a.pieces().count());
+    let mut counter = SegmentCounter::new(count, progress);
+
+    match &audio {
+        None => write_track(client, &main, &keys, output_path, &mut counter).await?,
+        Some(audio) => {
+            let video_path = output_path.with_extension("video");
+            let audio_path = output_path.with_extension("audio");
+            let result = async {
+                write_track(client, &main, &keys, &video_path, &mut counter).await?;
+                write_track(client, audio, &keys, &audio_path, &mut counter).await?;
+                mux(&video_path, &audio_path, output_path).await
+            }
+            .await;
+            let _ = tokio::fs::remove_file(&video_path).await;
+            let _ = tokio::fs::remove_file(&audio_path).await;
+            result?;
+        }
+    }
+    if let Some(p) = progress {
+        p.set_percent(100);
+    }
+    let meta = tokio::fs::metadata(output_path)
+        .await
+        .context("Stream output missing")?;
+    Ok(meta.len())
+}
+
+enum Manifest {
+    Hls(hls::Playlist),
+    Dash(dash::Manifest),
+}
+
+async fn fetch_manifest(client: &Http, url: &Url) -> Result<Manifest> {
+    let resp = client
+        .get(url)?
+        .send()
+        .await
+        .context("Failed to fetch manifest")?;
+    if !resp.status().is_success() {
+        anyhow::bail!("Manifest returned HTTP {}", resp.status());
+    }
+    // Relative URIs resolve against where redirects ended up.
+    let base = resp.url().clone();
+    let text = resp.text().await.context("Failed to read manifest")?;
+    if text.trim_start().starts_with("#EXTM3U") {
+        hls::parse(&text, &base).map(Manifest::Hls)
+    } else if text.contains("<MPD") {
+        dash::parse(&text, &base).map(Manifest::Dash)
+    } else {
+        anyhow::bail!("Not an HLS or DASH manifest")
+    }
+}
+
+async fn fetch_media_playlist(client: &Http, url: &Url) -> Result<hls::MediaPlaylist> {
+    match fetch_manifest(client, url).await? {
+        Manifest::Hls(hls::Playlist::Media(media)) => Ok(*media),
+        _ => anyhow::bail!("Expected an HLS media playlist at {url}"),
+    }
+}
+
+/// The track to download and, when the sound comes separately, the
+/// audio track to mux in.
+async fn select_tracks(
+    client: &Http,
+    url: &Url,
+    max_height: Option<u32>,
+    audio_only: bool,
+) -> Result<(Track, Option<Track>)> {
+    let vod = |media: hls::MediaPlaylist| -> Result<Track> {
+        if !media.ended {
+            anyhow::bail!("Live HLS streams are not supported");
+        }
+        Ok(media.track)
+    };
+    match fetch_manifest(client, url).await? {
+        Manifest::Hls(hls::Playlist::Media(media)) => Ok((vod(*media)?, None)),
+        Manifest::Hls(hls::Playlist::Master(master)) => {
+            let variant = if audio_only {
+                // Variants usually share one audio encoding; take the
+                // cheapest carrier.
+                master.variants.iter().min_by_key(|v| v.bandwidth)
+            } else {
+                pick(&master.variants, |v| v.height, |v| v.bandwidth, max_height)
+            }
+            .context("No HLS variant to download")?;
+            let audio_url = master.audio_for(variant);
+            tracing::info!(
+                "hls: {} ({:?}p, {} bit/s)",
+                variant.uri,
+                variant.height,
+                variant.bandwidth
+            );
+            match (audio_only, audio_url) {
+                (true, Some(audio)) => Ok((vod(fetch_media_playlist(client, audio).await?)?, None)),
+                (_, audio) => {
+                    let main = vod(fetch_media_playlist(client, &variant.uri).await?)?;
+                    let audio = match audio.filter(|_| !audio_only) {
+                        Some(a) => Some(vod(fetch_media_playlist(client, a).await?)?),
+                        None => None,
+                    };
+                    Ok((main, audio))
+                }
+            }
+        }
+        Manifest::Dash(m) => {
+            let audio = m.audio.iter().max_by_key(|r| r.bandwidth);
+            let video = pick(&m.video, |r| r.height, |r| r.bandwidth, max_height);
+            tracing::info!(
+                "dash: video {:?}, audio {:?}",
+                video.map(|r| &r.id),
+                audio.map(|r| &r.id)
+            );
+            match (video, audio) {
+                (_, Some(audio)) if audio_only => Ok((audio.track.clone(), None)),
+                (Some(video), audio) => Ok((video.track.clone(), audio.map(|a| a.track.clone()))),
+                (None, Some(audio)) => Ok((audio.track.clone(), None)),
+                (None, None) => anyhow::bail!("DASH manifest has nothing to download"),
+            }
+        }
+    }
+}
+
+/// The best item no taller than `max_height`, or the smallest when none
+/// fits; the best overall without a limit. Items of unknown height fit.
+fn pick<T>(
+    items: &[T],
+    height: impl Fn(&T) -> Option<u32>,
+    bandwidth: impl Fn(&T) -> u64,
+    max_height: Option<u32>,
+) -> Option<&T> {
+    let key = |t: &&T| (height(t), bandwidth(t));
+    items
+        .iter()
+        .filter(|t| match (max_height, height(t)) {
+            (Some(max), Some(h)) => h <= max,
+            _ => true,
+        })
+        .max_by_key(key)
+        .or_else(|| items.iter().min_by_key(key))
+}
+
+/// Every distinct AES-128 key the segments use, by URL.
+async fn fetch_keys(
+    client: &Http,
+    segments: impl Iterator<Item = &Segment>,
+) -> Result<HashMap<Url, [u8; 16]>> {
+    let mut keys = HashMap::new();
+    for key in segments.filter_map(|s| s.key.as_ref()) {
+        if keys.contains_key(&key.url) {
+            continue;
+        }
+        let bytes = client
+            .get(&key.url)?
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .context("Failed to fetch segment key")?
+            .bytes()
+            .await
+            .context("Failed to read segment key")?;
+        let key_bytes: [u8; 16] = bytes
+            .as_ref()
+            .try_into()
+            .context("Segment key is not 16 bytes")?;
+        keys.insert(key.url.clone(), key_bytes);
+    }
+    Ok(keys)
+}
+
+/// Download `track` into `path`, [`SEGMENT_CONCURRENCY`] segments at a
+/// time, writing them in order as they come in.
+async fn write_track(
+    client: &Http,
+    track: &Track,
+    keys: &HashMap<Url, [u8; 16]>,
+    path: &std::path::Path,
+    counter: &mut SegmentCounter<'_>,
+) -> Result<()> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .context("Failed to create stream file")?;
+    // The futures are built up front rather than in a `map` closure, whose
+    // higher-ranked borrow would keep the job future from being `Send`.
+    let fetches: Vec<_> = track
+        .pieces()
+        .map(|segment| fetch_segment(client, segment, keys))
+        .collect();
+    let mut segments = futures_util::stream::iter(fetches).buffered(SEGMENT_CONCURRENCY);
+    while let Some(data) = segments.next().await {
+        let data = data?;
+        file.write_all(&data)
+            .await
+            .context("Failed to write stream file")?;
+        counter.add(data.len());
+    }
+    file.flush().await.context("Failed to write stream file")?;
+    Ok(())
+}
+
+async fn fetch_segment(
+    client: &Http,
+    segment: &Segment,
+    keys: &HashMap<Url, [u8; 16]>,
+) -> Result<Vec<u8>> {
+    let mut attempt = 1;
+    let data = loop {
+        match fetch_segment_once(client, segment).await {
+            Ok(data) => break data,
+            Err(e) if attempt < SEGMENT_ATTEMPTS => {
+                tracing::warn!("segment {} failed (try {attempt}): {e:#}", segment.url);
+                tokio::time::sleep(std::time::Duration::from_millis(500 * u64::from(attempt)))
+                    .await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    match &segment.key {
+        None => Ok(data),
+        Some(key) => {
+            let secret = keys.get(&key.url).context("Segment key missing")?;
+            decrypt(&data, secret, &key.iv)
+        }
+    }
+}
+
+async fn fetch_segment_once(client: &Http, segment: &Segment) -> Result<Vec<u8>> {
+    let mut req = client.get(&segment.url)?;
+    if let Some(r) = segment.range {
+        req = req.header(
+            reqwest::header::RANGE,
+            format!("bytes={}-{}", r.offset, r.offset + r.length - 1),
+        );
+    }
+    let resp = req
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch segment {}", segment.url))?;
+    if !resp.status().is_success() {
+        anyhow::bail!("Segment {} returned HTTP {}", segment.url, resp.status());
+    }
+    let ranged = resp.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let body = resp.bytes().await.context("Failed to read segment")?;
+    match segment.range {
+        // The server ignored the range and sent everything.
+        Some(r) if !ranged => {
+            let start = r.offset as usize;
+            body.get(start..start + r.length as usize)
+                .map(<[u8]>::to_vec)
+                .context("Segment shorter than its byte range")
+        }
+        _ => Ok(body.to_vec()),
+    }
+}
+
+fn decrypt(data: &[u8], key: &[u8; 16], iv: &[u8; 16]) -> Result<Vec<u8>> {
+    cbc::Decryptor::<aes::Aes128>::new(key.into(), iv.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .map_err(|_| anyhow::anyhow!("Segment decryption failed"))
+}
+
+/// Mux separately downloaded video and audio into Matroska, which takes
+/// any codecs; `ensure_container` remuxes further when needed.
+async fn mux(
+    video: &std::path::Path,
+    audio: &std::path::Path,
+    output: &std::path::Path,
+) -> Result<()> {
+    let video = video.to_str().context("Invalid video path")?;
+    let audio = audio.to_str().context("Invalid audio path")?;
+    let output_str = output.to_str().context("Invalid output path")?;
+    let args = [
+        "-y", "-i", video, "-i", audio, "-map", "0:v:0", "-map", "1:a:0", "-c", "copy", "-f",
+        "matroska", output_str,
+    ];
+    let (status, stderr) = run_ffmpeg(&args, None)
+        .await
+        .context("Failed to run ffmpeg to mux the stream")?;
+    if !status.success() {
+        let _ = tokio::fs::remove_file(output).await;
+        anyhow::bail!("ffmpeg mux failed: {stderr}");
+    }
+    Ok(())
+}
+
+/// Download progress counted in segments, whose sizes aren't known up
+/// front.
+struct SegmentCounter<'a> {
+    tracker: Option<&'a ProgressTracker>,
+    meter: RateMeter,
+    count: usize,
+    done: usize,
+    bytes: u64,
+}
+
+impl<'a> SegmentCounter<'a> {
+    fn new(count: usize, tracker: Option<&'a ProgressTracker>) -> Self {
+        Self {
+            tracker,
+            meter: RateMeter::new(None),
+            count,
+            done: 0,
+            bytes: 0,
+        }
+    }
+
+    fn add(&mut self, len: usize) {
+        self.done += 1;
+        self.bytes += len as u64;
+        if let Some(tracker) = self.tracker {
+            tracker.update(self.meter.pieces_reading(self.bytes, self.done, self.count));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use aes::cipher::BlockEncryptMut;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode, Uri, header};
+    use axum::response::IntoResponse;
+
+    use super::*;
+
+    /// Files served by the fixture server, and how many requests it had in
+    /// flight at most.
+    struct Fixture {
+        files: HashMap<String, Vec<u8>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    async fn serve_file(
+        State(fixture): State<Arc<Fixture>>,
+        uri: Uri,
+        headers: HeaderMap,
+    ) -> axum::response::Response {
+        let now = fixture.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        fixture.max_in_flight.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        fixture.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let Some(body) = fixture.files.get(uri.path()) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        let range = headers
+            .get(header::RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("bytes="))
+            .and_then(|v| v.split_once('-'))
+            .and_then(|(a, b)| Some((a.parse::<usize>().ok()?, b.parse::<usize>().ok()?)));
+        match range {
+            Some((first, last)) => {
+                (StatusCode::PARTIAL_CONTENT, body[first..=last].to_vec()).into_response()
+            }
+            None => body.clone().into_response(),
+        }
+    }
+
+    /// Serve `files` on a local port; returns its base URL.
+    async fn serve(files: Vec<(&str, Vec<u8>)>) -> (String, Arc<Fixture>) {
+        let fixture = Arc::new(Fixture {
+            files: files
+                .into_iter()
+                .map(|(path, body)| (path.to_string(), body))
+                .collect(),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+        let app = axum::Router::new()
+            .fallback(serve_file)
+            .with_state(fixture.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}"), fixture)
+    }
+
+    fn encrypt(data: &[u8], key: &[u8; 16], iv: &[u8; 16]) -> Vec<u8> {
+        cbc::Encryptor::<aes::Aes128>::new(key.into(), iv.into())
+            .encrypt_padded_vec_mut::<Pkcs7>(data)
+    }
+
+    fn segment_body(name: &str) -> Vec<u8> {
+        format!("<{name}>").repeat(50).into_bytes()
+    }
+
+    #[tokio::test]
+    async fn hls_picks_the_variant_and_decrypts_aes_segments() {
+        let key = *b"0123456789abcdef";
+        let iv = [7u8; 16];
+        let mut files = vec![
+            (
+                "/show/master.m3u8",
+                "#EXTM3U\n\
+                 #EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360\nlow/index.m3u8\n\
+                 #EXT-X-STREAM-INF:BANDWIDTH=2800000,RESOLUTION=1280x720\nhigh/index.m3u8\n"
+                    .into(),
+            ),
+            (
+                "/show/low/index.m3u8",
+                "#EXTM3U\n#EXTINF:4,\nlow0.ts\n#EXT-X-ENDLIST\n".into(),
+            ),
+            ("/show/low/low0.ts", segment_body("low0")),
+            (
+                "/show/high/index.m3u8",
+                "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:3\n\
+                 #EXT-X-KEY:METHOD=AES-128,URI=\"/keys/k1\",IV=0x07070707070707070707070707070707\n\
+                 #EXTINF:4,\nseg0.ts\n\
+                 #EXT-X-KEY:METHOD=AES-128,URI=\"/keys/k1\"\n\
+                 #EXTINF:4,\nseg1.ts\n\
+                 #EXT-X-KEY:METHOD=NONE\n\
+                 #EXTINF:4,\nseg2.ts\n#EXT-X-ENDLIST\n"
+                    .into(),
+            ),
+            ("/keys/k1", key.to_vec()),
+            (
+                "/show/high/seg0.ts",
+                encrypt(&segment_body("seg0"), &key, &iv),
+            ),
+            // No IV: the media sequence number (3 + 1) is.
+            (
+                "/show/high/seg1.ts",
+                encrypt(&segment_body("seg1"), &key, &4u128.to_be_bytes()),
+            ),
+            ("/show/high/seg2.ts", segment_body("seg2")),
+        ];
+        files.sort_by_key(|(path, _)| *path);
+        let (base, _) = serve(files).await;
+        let client = Http::unguarded(reqwest::Client::new());
+        let url = format!("{base}/show/master.m3u8");
+
+        let info = probe_with(&client, &url).await.unwrap();
+        let heights: Vec<_> = info.variants.iter().map(|v| v.height).collect();
+        assert_eq!(heights, [Some(360), Some(720)]);
+        assert_eq!(info.duration_secs, Some(4.0));
+
+        let dir = std::env::temp_dir().join(format!("cr-hls-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let out = dir.join("high.ts");
+        let tracker = ProgressTracker::new();
+        let size = download_with(&client, &url, Some(1080), false, &out, Some(&tracker))
+            .await
+            .unwrap();
+        let expected = [
+            segment_body("seg0"),
+            segment_body("seg1"),
+            segment_body("seg2"),
+        ]
+        .concat();
+        assert_eq!(tokio::fs::read(&out).await.unwrap(), expected);
+        assert_eq!(size, expected.len() as u64);
+        assert_eq!(tracker.percent(), 100);
+
+        let out = dir.join("low.ts");
+        download_with(&client, &url, Some(480), false, &out, None)
+            .await
+            .unwrap();
+        assert_eq!(tokio::fs::read(&out).await.unwrap(), segment_body("low0"));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn dash_segments_download_in_parallel_and_in_order() {
+        let count = 12;
+        let mpd = format!(
+            r#"<MPD type="static" mediaPresentationDuration="PT{}S"><Period>
+  <AdaptationSet mimeType="video/mp4">
+    <SegmentTemplate media="$RepresentationID$/$Number$.m4s" initialization="$RepresentationID$/init.mp4" duration="2"/>
+    <Representation id="v1" bandwidth="500000" height="480"/>
+  </AdaptationSet>
+</Period></MPD>"#,
+            count * 2
+        );
+        let mut files = vec![
+            ("/vod/manifest.mpd".to_string(), mpd.into_bytes()),
+            ("/vod/v1/init.mp4".to_string(), segment_body("init")),
+        ];
+        for n in 1..=count {
+            files.push((format!("/vod/v1/{n}.m4s"), segment_body(&format!("s{n}"))));
+        }
+        let (base, fixture) =
+            serve(files.iter().map(|(p, b)| (p.as_str(), b.clone())).collect()).await;
+
+        let out = std::env::temp_dir().join(format!("cr-dash-{}.mp4", std::process::id()));
+        let client = Http::unguarded(reqwest::Client::new());
+        download_with(
+            &client,
+            &format!("{base}/vod/manifest.mpd"),
+            None,
+            false,
+            &out,
+            None,
+        )
+        .await
+        .unwrap();
+        let expected: Vec<u8> = std::iter::once(segment_body("init"))
+            .chain((1..=count).map(|n| segment_body(&format!("s{n}"))))
+            .flatten()
+            .collect();
+        assert_eq!(tokio::fs::read(&out).await.unwrap(), expected);
+        let max = fixture.max_in_flight.load(Ordering::SeqCst);
+        assert!(max > 1 && max <= SEGMENT_CONCURRENCY, "{max} in flight");
+        let _ = tokio::fs::remove_file(&out).await;
+    }
+
+    #[test]
+    fn variants_pick_by_height() {
+        let v = |height, bandwidth| StreamVariant {
+            height: Some(height),
+            bandwidth,
+        };
+        let variants = [v(360, 1), v(720, 3), v(720, 2), v(1080, 5)];
+        let pick = |max| pick(&variants, |v| v.height, |v| v.bandwidth, max).copied();
+        assert_eq!(pick(Some(720)), Some(v(720, 3)));
+        assert_eq!(pick(None), Some(v(1080, 5)));
+        // Nothing that small: the smallest there is.
+        assert_eq!(pick(Some(240)), Some(v(360, 1)));
+        assert_eq!(
+            distinct_heights(variants.to_vec()),
+            [v(360, 1), v(720, 3), v(1080, 5)]
+        );
+        assert!(is_manifest_url("https://cdn.example/a/Master.M3U8?token=1"));
+        assert!(!is_manifest_url(
+            "https://example.com/watch?v=playlist.m3u8"
+        ));
+        assert!(!is_manifest_url("file:///srv/media/index.m3u8"));
+    }
+}
@@ -0,0 +1,694 @@
+//! Subtitle files: reading WebVTT, SubRip and ASS into one cue list,
+//! writing any of them back, and adding a track to a downloaded video —
+//! muxed as a soft track, or burned into the picture.
+//!
+//! Only the formatting all three share survives a conversion: italics,
+//! bold and underline. Positioning, colours and styles are dropped.
+
+use anyhow::{Context, Result};
+use cr_domain::video_job::{ClipRange, SubtitleOptions};
+
+use super::ProgressTracker;
+use super::transcode::{FfmpegTrack, ffprobe_duration, run_ffmpeg, video_codec_args};
+
+/// A subtitle file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    /// WebVTT, what HTML5 `<track>` plays.
+    Vtt,
+    /// SubRip.
+    Srt,
+    /// Advanced SubStation Alpha.
+    Ass,
+}
+
+impl SubtitleFormat {
+    pub const ALL: [Self; 3] = [Self::Vtt, Self::Srt, Self::Ass];
+
+    /// File extension.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Vtt => "vtt",
+            Self::Srt => "srt",
+            Self::Ass => "ass",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == s)
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Vtt => "text/vtt; charset=utf-8",
+            Self::Srt => "application/x-subrip; charset=utf-8",
+            Self::Ass => "text/x-ssa; charset=utf-8",
+        }
+    }
+
+    /// Guess the format of a file from its content.
+    fn detect(text: &str) -> Self {
+        if text.trim_start().starts_with("WEBVTT") {
+            Self::Vtt
+        } else if text.contains("[Script Info]") || text.contains("[Events]") {
+            Self::Ass
+        } else {
+            Self::Srt
+        }
+    }
+}
+
+/// One caption: shown from `start_ms` to `end_ms`. `text` has its lines
+/// separated by `\n` and keeps `<i>`, `<b>` and `<u>` as the only markup;
+/// everything else is plain characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// A subtitle track, in playback order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subtitles {
+    pub cues: Vec<Cue>,
+}
+
+impl Subtitles {
+    /// Whether `bytes` are already UTF-8 WebVTT (with or without a BOM),
+    /// i.e. playable by `<track>` as they are.
+    pub fn is_utf8_vtt(bytes: &[u8]) -> bool {
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        std::str::from_utf8(bytes)
+            .is_ok_and(|text| SubtitleFormat::detect(text) == SubtitleFormat::Vtt)
+    }
+
+    /// Read a file as fetched: UTF-8 (with or without a BOM), falling back
+    /// to Windows-1250 for the older Czech releases that use it.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => std::borrow::Cow::Borrowed(text),
+            Err(_) => {
+                encoding_rs::WINDOWS_1250
+                    .decode_without_bom_handling(bytes)
+                    .0
+            }
+        };
+        Self::parse(&text)
+    }
+
+    /// Parse WebVTT, SubRip or ASS, whichever `text` is.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let mut cues = match SubtitleFormat::detect(&text) {
+            SubtitleFormat::Vtt | SubtitleFormat::Srt => parse_blocks(&text),
+            SubtitleFormat::Ass => parse_ass(&text),
+        };
+        if cues.is_empty() {
+            anyhow::bail!("Subtitle file has no cues");
+        }
+        cues.sort_by_key(|c| (c.start_ms, c.end_ms));
+        Ok(Self { cues })
+    }
+
+    pub fn render(&self, format: SubtitleFormat) -> String {
+        match format {
+            SubtitleFormat::Vtt => render_vtt(&self.cues),
+            SubtitleFormat::Srt => render_srt(&self.cues),
+            SubtitleFormat::Ass => render_ass(&self.cues),
+        }
+    }
+
+    /// The cues within `clip`, retimed to start with it.
+    pub fn clip(&self, clip: ClipRange) -> Self {
+        let start = (clip.start_secs * 1000.0).round() as u64;
+        let end = (clip.end_secs * 1000.0).round() as u64;
+        let cues = self
+            .cues
+            .iter()
+            .filter(|c| c.end_ms > start && c.start_ms < end)
+            .map(|c| Cue {
+                start_ms: c.start_ms.max(start) - start,
+                end_ms: c.end_ms.min(end) - start,
+                text: c.text.clone(),
+            })
+            .collect();
+        Self { cues }
+    }
+}
+
+/// Fetch and parse the subtitle file at `url`.
+pub async fn fetch_subtitles(client: &reqwest::Client, url: &str) -> Result<Subtitles> {
+    let resp = client
+        .get(url)
+        .header("User-Agent", "Mozilla/5.0")
+        .timeout(std::time::Duration::from_secs(30))
+        .send()
+        .await
+        .context("Failed to fetch subtitles")?;
+    if !resp.status().is_success() {
+        anyhow::bail!("Subtitles returned HTTP {}", resp.status());
+    }
+    let bytes = resp.bytes().await.context("Failed to read subtitles")?;
+    Subtitles::decode(&bytes)
+}
+
+/// Add the track `options` names to `path` (a `container` file), in
+/// place. `clip` is the part of the video `path` holds, if only a part.
+/// A soft track goes in as MP4 timed text, SubRip in MKV or WebVTT in
+/// WebM; burning re-encodes the video, reporting 0–99 % into `progress`.
+/// Returns the new file size.
+pub async fn add_subtitles(
+    client: &reqwest::Client,
+    path: &std::path::Path,
+    container: &str,
+    options: &SubtitleOptions,
+    clip: Option<ClipRange>,
+    progress: Option<ProgressTracker>,
+) -> Result<u64> {
+    let mut subtitles = fetch_subtitles(client, &options.url).await?;
+    if let Some(clip) = clip {
+        subtitles = subtitles.clip(clip);
+    }
+
+    let parent = path.parent().unwrap_or_else(|| std::path::Path::new("."));
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("Input path has no stem")?;
+    // Burning goes through libass, which keeps the ASS look; soft tracks
+    // start from the text format the container's own track type is
+    // closest to.
+    let format = match (options.burn, container) {
+        (true, _) => SubtitleFormat::Ass,
+        (false, "webm") => SubtitleFormat::Vtt,
+        (false, _) => SubtitleFormat::Srt,
+    };
+    let subs_path = parent.join(format!("{stem}.subs.{}", format.as_str()));
+    let tmp = parent.join(format!("{stem}.subtitled.{container}"));
+    tokio::fs::write(&subs_path, subtitles.render(format))
+        .await
+        .context("Failed to write the subtitle file")?;
+
+    let result = async {
+        let input = path.to_str().context("Invalid input path")?;
+        let subs = subs_path.to_str().context("Invalid subtitle path")?;
+        let output = tmp.to_str().context("Invalid output path")?;
+        let language = format!("language={}", options.iso639_2());
+        let args = if options.burn {
+            burn_args(input, subs, output, container)?
+        } else {
+            soft_args(input, subs, output, container, &language)
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let duration = ffprobe_duration(path).await;
+        let track = progress
+            .as_ref()
+            .map(|p| FfmpegTrack::new(p, duration, 0, 99));
+        let (status, stderr) = run_ffmpeg(&args, track)
+            .await
+            .context("Failed to run ffmpeg for the subtitles")?;
+        if !status.success() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            let tail: String = stderr.lines().rev().take(4).collect::<Vec<_>>().join(" | ");
+            anyhow::bail!("ffmpeg subtitles failed: {tail}");
+        }
+        tokio::fs::rename(&tmp, path)
+            .await
+            .context("Failed to swap the subtitled file into place")
+    }
+    .await;
+    let _ = tokio::fs::remove_file(&subs_path).await;
+    result?;
+
+    if let Some(p) = &progress {
+        p.set_percent(99);
+    }
+    let meta = tokio::fs::metadata(path)
+        .await
+        .context("Subtitled file not found")?;
+    Ok(meta.len())
+}
+
+/// Copy the video and audio, add the subtitles as a track of the
+/// container's own subtitle codec.
+fn soft_args(
+    input: &str,
+    subs: &str,
+    output: &str,
+    container: &str,
+    language: &str,
+) -> Vec<String> {
+    let codec = match container {
+        "mp4" => "mov_text",
+        "webm" => "webvtt",
+        _ => "srt",
+    };
+    let mut args = vec![
+        "-y",
+        "-i",
+        input,
+        "-i",
+        subs,
+        "-map",
+        "0:v",
+        "-map",
+        "0:a?",
+        "-map",
+        "1:0",
+        "-c",
+        "copy",
+        "-c:s",
+        codec,
+        "-metadata:s:s:0",
+        language,
+        "-disposition:s:0",
+        "default",
+    ];
+    if container == "mp4" {
+        args.extend(["-movflags", "+faststart"]);
+    }
+    args.push(output);
+    args.into_iter().map(String::from).collect()
+}
+
+/// Re-encode the video with the subtitles drawn on it; audio is copied.
+fn burn_args(input: &str, subs: &str, output: &str, container: &str) -> Result<Vec<String>> {
+    // Paths are ours (token-named under the temp dir) and need no
+    // filtergraph escaping.
+    let filter = format!("subtitles=filename={subs}");
+    let mut args = vec!["-y", "-i", input, "-vf", &filter];
+    args.extend(video_codec_args(container)?);
+    args.extend(["-c:a", "copy"]);
+    if container == "mp4" {
+        args.extend(["-movflags", "+faststart"]);
+    }
+    args.push(output);
+    Ok(args.into_iter().map(String::from).collect())
+}
+
+// ─── Reading ──────────────────────────────────────────────────────
+
+/// WebVTT and SubRip: blank-line separated blocks, each with a
+/// `start --> end` line followed by the text. Blocks without timing
+/// (the WebVTT header, `NOTE`, `STYLE`, `REGION`) are skipped.
+fn parse_blocks(text: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    for block in text.split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some((start, rest)) = timing.split_once("-->") else {
+            continue;
+        };
+        // WebVTT cue settings follow the end time.
+        let end = rest.split_whitespace().next().unwrap_or_default();
+        let (Some(start_ms), Some(end_ms)) = (parse_time(start.trim()), parse_time(end)) else {
+            continue;
+        };
+        let text = lines
+            .map(clean_markup)
+            .filter(|l| !l.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() && end_ms > start_ms {
+            cues.push(Cue {
+                start_ms,
+                end_ms,
+                text,
+            });
+        }
+    }
+    cues
+}
+
+/// `hh:mm:ss.mmm`, `mm:ss.mmm` or SubRip's `hh:mm:ss,mmm`.
+fn parse_time(s: &str) -> Option<u64> {
+    let s = s.replace(',', ".");
+    let (clock, fraction) = s.split_once('.').unwrap_or((&s, "0"));
+    let mut secs: u64 = 0;
+    let fields: Vec<&str> = clock.split(':').collect();
+    if !(2..=3).contains(&fields.len()) {
+        return None;
+    }
+    for field in fields {
+        secs = secs * 60 + field.trim().parse::<u64>().ok()?;
+    }
+    // `.5` is half a second, `.500` too.
+    let digits: String = fraction.chars().take(3).collect();
+    let ms = format!("{digits:0<3}").parse::<u64>().ok()?;
+    Some(secs * 1000 + ms)
+}
+
+/// One line of WebVTT / SubRip text in the cue markup: `<i>`, `<b>`,
+/// `<u>` kept, other tags (`<c.yellow>`, `<v Anna>`, `<font>`, karaoke
+/// timestamps) and ASS-style `{\an8}` overrides dropped, entities
+/// decoded.
+fn clean_markup(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(pos) = rest.find(['<', '{']) {
+        out.push_str(&rest[..pos]);
+        let close = if rest[pos..].starts_with('<') {
+            '>'
+        } else {
+            '}'
+        };
+        match rest[pos..].find(close) {
+            Some(end) => {
+                let tag = &rest[pos..=pos + end];
+                if is_kept_tag(tag) {
+                    out.push_str(&tag.to_ascii_lowercase());
+                } else if close == '}' && !tag.starts_with("{\\") {
+                    // Braces in the text itself.
+                    out.push_str(tag);
+                }
+                rest = &rest[pos + end + 1..];
+            }
+            None => {
+                out.push_str(&rest[pos..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    decode_entities(&out)
+}
+
+fn is_kept_tag(tag: &str) -> bool {
+    matches!(
+        tag.to_ascii_lowercase().as_str(),
+        "<i>" | "</i>" | "<b>" | "</b>" | "<u>" | "</u>"
+    )
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&")
+}
+
+/// ASS / SSA: the `Dialogue:` lines of `[Events]`, their fields named by
+/// the section's `Format:` line.
+fn parse_ass(text: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut in_events = false;
+    let mut format: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[Events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields
+                .split(',')
+                .map(|f| f.trim().to_ascii_lowercase())
+                .collect();
+        } else if let Some(fields) = line.strip_prefix("Dialogue:") {
+            if format.is_empty() {
+                continue;
+            }
+            // The text is last and may itself contain commas.
+            let values: Vec<&str> = fields.splitn(format.len(), ',').collect();
+            let field = |name: &str| {
+                let i = format.iter().position(|f| f == name)?;
+                values.get(i).map(|v| v.trim())
+            };
+            let (Some(start), Some(end), Some(text)) = (
+                field("start").and_then(parse_time),
+                field("end").and_then(parse_time),
+                field("text"),
+            ) else {
+                continue;
+            };
+            let text = ass_to_markup(text);
+            if !text.trim().is_empty() && end > start {
+                cues.push(Cue {
+                    start_ms: start,
+                    end_ms: end,
+                    text,
+                });
+            }
+        }
+    }
+    cues
+}
+
+/// ASS dialogue text → cue markup: `\N` breaks lines, `{\i1}` … `{\i0}`
+/// become `<i>` … `</i>` (bold and underline alike), other overrides go.
+fn ass_to_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('{') {
+        out.push_str(&rest[..pos]);
+        let Some(end) = rest[pos..].find('}') else {
+            out.push_str(&rest[pos..]);
+            rest = "";
+            break;
+        };
+        for tag in rest[pos + 1..pos + end].split('\\') {
+            for name in ["i", "b", "u"] {
+                // `\b700` is bold by weight; `\be` (blur) isn't bold.
+                if let Some(value) = tag.strip_prefix(name)
+                    && !value.is_empty()
+                    && value.chars().all(|c| c.is_ascii_digit())
+                {
+                    if value == "0" {
+                        out.push_str(&format!("</{name}>"));
+                    } else {
+                        out.push_str(&format!("<{name}>"));
+                    }
+                }
+            }
+        }
+        rest = &rest[pos + end + 1..];
+    }
+    out.push_str(rest);
+    out.replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", "\u{a0}")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ─── Writing ──────────────────────────────────────────────────────
+
+fn render_vtt(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n");
+    for cue in cues {
+        out.push_str(&format!(
+            "\n{} --> {}\n{}\n",
+            clock(cue.start_ms, '.'),
+            clock(cue.end_ms, '.'),
+            escape_vtt(&cue.text)
+        ));
+    }
+    out
+}
+
+fn render_srt(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            clock(cue.start_ms, ','),
+            clock(cue.end_ms, ','),
+            cue.text
+        ));
+    }
+    out
+}
+
+/// White text with a dark outline near the bottom, sized for a 1080p
+/// canvas — libass scales it to the video.
+const ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+PlayResX: 1920
+PlayResY: 1080
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,64,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+fn render_ass(cues: &[Cue]) -> String {
+    let mut out = String::from(ASS_HEADER);
+    for cue in cues {
+        out.push_str(&format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{}\n",
+            ass_clock(cue.start_ms),
+            ass_clock(cue.end_ms),
+            markup_to_ass(&cue.text)
+        ));
+    }
+    out
+}
+
+/// `01:02:03.450` (or with a comma for SubRip).
+fn clock(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// ASS counts in centiseconds: `1:02:03.45`.
+fn ass_clock(ms: u64) -> String {
+    let cs = ms / 10;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        cs / 360_000,
+        cs / 6000 % 60,
+        cs / 100 % 60,
+        cs % 100
+    )
+}
+
+/// Escape what WebVTT would read as markup, keeping the cue tags.
+fn escape_vtt(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(['<', '&', '>']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        match tail.find('>').map(|end| &tail[..=end]) {
+            Some(tag) if tail.starts_with('<') && is_kept_tag(tag) => {
+                out.push_str(tag);
+                rest = &tail[tag.len()..];
+                continue;
+            }
+            _ => {}
+        }
+        out.push_str(match &tail[..1] {
+            "<" => "&lt;",
+            ">" => "&gt;",
+            _ => "&amp;",
+        });
+        rest = &tail[1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn markup_to_ass(text: &str) -> String {
+    text.replace("<i>", "{\\i1}")
+        .replace("</i>", "{\\i0}")
+        .replace("<b>", "{\\b1}")
+        .replace("</b>", "{\\b0}")
+        .replace("<u>", "{\\u1}")
+        .replace("</u>", "{\\u0}")
+        .replace('\n', "\\N")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VTT: &str = "\u{feff}WEBVTT - Ulice 4512\r\n\r\nNOTE hand-made\r\n\r\nSTYLE\r\n::cue { color: yellow }\r\n\r\n1\r\n00:00:01.000 --> 00:00:03.500 align:start line:90%\r\n<v Anna><i>Ahoj</i> &amp; vítej</v>\r\n<c.yellow>Druhý</c> řádek\r\n\r\n01:05.250 --> 01:07.000\r\nx &lt; y\r\n";
+
+    #[test]
+    fn vtt_reads_cues_and_drops_what_others_cannot_show() {
+        let subs = Subtitles::parse(VTT).unwrap();
+        assert_eq!(
+            subs.cues,
+            [
+                Cue {
+                    start_ms: 1000,
+                    end_ms: 3500,
+                    text: "<i>Ahoj</i> & vítej\nDruhý řádek".into(),
+                },
+                Cue {
+                    start_ms: 65_250,
+                    end_ms: 67_000,
+                    text: "x < y".into(),
+                },
+            ]
+        );
+        let vtt = subs.render(SubtitleFormat::Vtt);
+        assert!(
+            vtt.starts_with("WEBVTT\n\n00:00:01.000 --> 00:00:03.500\n<i>Ahoj</i> &amp; vítej\n")
+        );
+        assert!(vtt.contains("x &lt; y"));
+        assert_eq!(Subtitles::parse(&vtt).unwrap(), subs);
+    }
+
+    #[test]
+    fn srt_and_ass_round_trip_through_each_other() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,500\n{\\an8}<font color=\"red\"><b>Pozor!</b></font>\n\n2\n00:01:00,040 --> 00:01:02,000\nDva\nřádky\n";
+        let subs = Subtitles::parse(srt).unwrap();
+        assert_eq!(subs.cues[0].text, "<b>Pozor!</b>");
+        assert_eq!(
+            subs.render(SubtitleFormat::Srt),
+            "1\n00:00:01,000 --> 00:00:02,500\n<b>Pozor!</b>\n\n2\n00:01:00,040 --> 00:01:02,000\nDva\nřádky\n\n"
+        );
+
+        let ass = subs.render(SubtitleFormat::Ass);
+        assert!(
+            ass.contains("Dialogue: 0,0:00:01.00,0:00:02.50,Default,,0,0,0,,{\\b1}Pozor!{\\b0}\n")
+        );
+        assert!(ass.contains("0:01:00.04,0:01:02.00,Default,,0,0,0,,Dva\\Nřádky"));
+        assert_eq!(Subtitles::parse(&ass).unwrap(), subs);
+    }
+
+    #[test]
+    fn ass_reads_dialogue_by_the_format_line() {
+        let ass = "[Script Info]\nTitle: x\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nComment: 0,0:00:00.00,0:00:09.00,Default,,0,0,0,,skip\nDialogue: 0,0:00:02.00,0:00:04.00,Default,,0,0,0,,{\\pos(10,10)\\i1}Tak, jo{\\i0}\\Nkonec\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\be1}První\n";
+        let subs = Subtitles::parse(ass).unwrap();
+        let texts: Vec<_> = subs.cues.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["První", "<i>Tak, jo</i>\nkonec"]);
+    }
+
+    #[test]
+    fn windows_1250_files_decode_and_clips_retime() {
+        // "Žluťoučký kůň" in Windows-1250.
+        let bytes = b"1\n00:00:10,000 --> 00:00:14,000\n\x8Elu\x9Dou\xE8k\xFD k\xF9\xF2\n\n2\n00:00:20,000 --> 00:00:21,000\nkonec\n";
+        let subs = Subtitles::decode(bytes).unwrap();
+        assert_eq!(subs.cues[0].text, "Žluťoučký kůň");
+        assert!(!Subtitles::is_utf8_vtt(bytes));
+        assert!(!Subtitles::is_utf8_vtt(
+            b"WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n\x8Elu\n"
+        ));
+        assert!(Subtitles::is_utf8_vtt(VTT.as_bytes()));
+
+        let clip = subs.clip(ClipRange::new(12.0, 20.5).unwrap());
+        assert_eq!((clip.cues[0].start_ms, clip.cues[0].end_ms), (0, 2000));
+        assert_eq!((clip.cues[1].start_ms, clip.cues[1].end_ms), (8000, 8500));
+        assert!(Subtitles::parse("WEBVTT\n\nno cues here\n").is_err());
+    }
+
+    #[test]
+    fn soft_tracks_use_the_container_codec() {
+        let args = soft_args("in.mp4", "s.srt", "out.mp4", "mp4", "language=cze").join(" ");
+        assert!(args.contains("-map 0:v -map 0:a? -map 1:0 -c copy -c:s mov_text"));
+        assert!(args.contains("-metadata:s:s:0 language=cze"));
+        assert!(args.ends_with("-movflags +faststart out.mp4"));
+        let args = soft_args("in.mkv", "s.srt", "out.mkv", "mkv", "language=eng").join(" ");
+        assert!(args.contains("-c:s srt"));
+
+        let args = burn_args("in.webm", "/tmp/t.subs.ass", "out.webm", "webm")
+            .unwrap()
+            .join(" ");
+        assert!(args.contains("-vf subtitles=filename=/tmp/t.subs.ass -c:v libvpx-vp9"));
+        assert!(args.ends_with("-c:a copy out.webm"));
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::{Context, Result};
 
-use super::extractors::ytdlp::ytdlp_command;
+use cr_domain::video_job::{AnimationFormat, AnimationOptions, ClipRange};
+
+use super::ProgressTracker;
+use super::progress::ProgressUpdate;
 
 /// Force `path` to be in `container` format (`"mp4"`, `"webm"`, or `"mkv"`). If
 /// ffprobe reports the file is already in that container, returns
@@ -9,15 +12,14 @@ use super::extractors::ytdlp::ytdlp_command;
 /// codecs are compatible), then a full codec re-encode fallback when
 /// the remux fails due to codec incompatibility.
 ///
-/// Bumps the shared progress atomic during transcode so the UI's
-/// existing progress bar keeps moving instead of looking frozen (#366).
+/// Feeds ffmpeg's own progress into `progress` during the transcode —
+/// 40–50 % for the remux, 50–95 % for the re-encode — so the UI's
+/// progress bar keeps moving instead of looking frozen (#366).
 pub async fn ensure_container(
     path: &std::path::Path,
     container: &str,
-    progress: Option<std::sync::Arc<std::sync::atomic::AtomicU8>>,
+    progress: Option<ProgressTracker>,
 ) -> Result<()> {
-    use std::sync::atomic::Ordering;
-
     let actual = probe_container(path).await.unwrap_or_default();
     if container_matches(&actual, container) {
         return Ok(());
@@ -25,8 +27,18 @@ pub async fn ensure_container(
 
     tracing::info!("ensure_container: {path:?} is {actual:?}, transcoding to {container}");
     if let Some(p) = &progress {
-        p.store(40, Ordering::Relaxed);
+        p.update(ProgressUpdate {
+            percent: 40,
+            ..ProgressUpdate::default()
+        });
     }
+    let duration = ffprobe_duration(path).await;
+    let remux_track = progress
+        .as_ref()
+        .map(|p| FfmpegTrack::new(p, duration, 40, 50));
+    let recode_track = progress
+        .as_ref()
+        .map(|p| FfmpegTrack::new(p, duration, 50, 95));
 
     let parent = path.parent().unwrap_or_else(|| std::path::Path::new("."));
     let stem = path
@@ -38,17 +50,29 @@ pub async fn ensure_container(
     // 1) Fast path — remux with -c copy. Works whenever the existing
     //    codecs can live in the target container (e.g. H.264/AAC → mp4,
     //    VP9/Opus → webm). Fails fast on mismatched codecs.
-    let remux = run_ffmpeg_transcode(path, &tmp, container, /*recode*/ false).await;
+    let remux = run_ffmpeg_transcode(
+        path,
+        &tmp,
+        container,
+        /*recode*/ false,
+        None,
+        remux_track,
+    )
+    .await;
     let transcoded = match remux {
         Ok(()) => true,
         Err(e) => {
             tracing::warn!("ensure_container: remux failed ({e}), falling back to full re-encode");
-            if let Some(p) = &progress {
-                p.store(50, Ordering::Relaxed);
-            }
-            run_ffmpeg_transcode(path, &tmp, container, /*recode*/ true)
-                .await
-                .context("ffmpeg full re-encode failed")?;
+            run_ffmpeg_transcode(
+                path,
+                &tmp,
+                container,
+                /*recode*/ true,
+                None,
+                recode_track,
+            )
+            .await
+            .context("ffmpeg full re-encode failed")?;
             true
         }
     };
@@ -60,7 +84,7 @@ pub async fn ensure_container(
     }
 
     if let Some(p) = &progress {
-        p.store(95, Ordering::Relaxed);
+        p.set_percent(95);
     }
     Ok(())
 }
@@ -70,58 +94,49 @@ pub async fn ensure_container(
 /// that fails when the source codecs can't live in the target
 /// container. When `recode` is true, video and audio are re-encoded
 /// with container-appropriate codecs: H.264/AAC for MP4, VP9/Opus
-/// for WebM.
+/// for WebM. With a `span` (start, length in seconds) only that part
+/// of the input is written.
 async fn run_ffmpeg_transcode(
     input: &std::path::Path,
     output: &std::path::Path,
     container: &str,
     recode: bool,
+    span: Option<(f64, f64)>,
+    track: Option<FfmpegTrack<'_>>,
 ) -> Result<()> {
     let input_str = input.to_str().context("Invalid input path")?;
     let output_str = output.to_str().context("Invalid output path")?;
 
-    let mut args: Vec<&str> = vec!["-y", "-i", input_str];
+    let span_args = span.map(|(start, len)| [format!("{start:.3}"), format!("{len:.3}")]);
+    let mut args: Vec<&str> = vec!["-y"];
+    if let Some([start, len]) = &span_args {
+        // Before `-i`: seeks by index. Copying starts at the keyframe
+        // `start` names; re-encoding is frame-accurate.
+        args.extend(["-ss", start, "-t", len]);
+    }
+    args.extend(["-i", input_str]);
 
     if recode {
+        // H.264/AAC is compatible with both MP4 and MKV and plays
+        // everywhere; MKV just wraps it in the Matroska container
+        // instead of ISO/BMFF. WebM gets VP9/Opus.
+        args.extend(video_codec_args(container)?);
         match container {
-            "mp4" | "mkv" => {
-                // H.264/AAC is compatible with both MP4 and MKV and
-                // plays everywhere; MKV just wraps it in the Matroska
-                // container instead of ISO/BMFF. The `+faststart`
-                // flag is MP4-only — skip it for MKV.
-                args.extend([
-                    "-c:v", "libx264", "-preset", "veryfast", "-crf", "23", "-pix_fmt", "yuv420p",
-                    "-c:a", "aac", "-b:a", "128k",
-                ]);
-                if container == "mp4" {
-                    args.extend(["-movflags", "+faststart"]);
-                }
-            }
-            "webm" => {
-                args.extend([
-                    "-c:v",
-                    "libvpx-vp9",
-                    "-b:v",
-                    "0",
-                    "-crf",
-                    "32",
-                    "-deadline",
-                    "good",
-                    "-cpu-used",
-                    "4",
-                    "-c:a",
-                    "libopus",
-                    "-b:a",
-                    "128k",
-                ]);
-            }
-            other => anyhow::bail!("Unsupported container for re-encode: {other}"),
+            "webm" => args.extend(["-c:a", "libopus", "-b:a", "128k"]),
+            _ => args.extend(["-c:a", "aac", "-b:a", "128k"]),
+        }
+        // The `+faststart` flag is MP4-only — skip it for MKV.
+        if container == "mp4" {
+            args.extend(["-movflags", "+faststart"]);
         }
     } else {
         // Fast-path remux — copy streams, let ffmpeg error out if the
         // target container can't hold them. MKV accepts essentially
         // any codec so this path almost always succeeds for MKV.
         args.extend(["-c", "copy"]);
+        if span.is_some() {
+            args.extend(["-avoid_negative_ts", "make_zero"]);
+        }
         if container == "mp4" {
             args.extend(["-movflags", "+faststart"]);
         }
@@ -129,24 +144,229 @@ async fn run_ffmpeg_transcode(
 
     args.push(output_str);
 
-    let out = tokio::process::Command::new("ffmpeg")
-        .args(&args)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
-        .output()
+    let (status, stderr) = run_ffmpeg(&args, track)
         .await
         .context("Failed to spawn ffmpeg")?;
 
-    if !out.status.success() {
+    if !status.success() {
         // Clean up any partial output so the fallback re-encode starts clean.
         let _ = tokio::fs::remove_file(output).await;
-        let stderr = String::from_utf8_lossy(&out.stderr);
         let tail: String = stderr.lines().rev().take(4).collect::<Vec<_>>().join(" | ");
-        anyhow::bail!("ffmpeg exited with {}: {}", out.status, tail);
+        anyhow::bail!("ffmpeg exited with {}: {}", status, tail);
+    }
+    Ok(())
+}
+
+/// Video encoder settings for re-encoding into `container`: H.264 for
+/// MP4 and MKV, VP9 for WebM.
+pub(super) fn video_codec_args(container: &str) -> Result<&'static [&'static str]> {
+    match container {
+        "mp4" | "mkv" => Ok(&[
+            "-c:v", "libx264", "-preset", "veryfast", "-crf", "23", "-pix_fmt", "yuv420p",
+        ]),
+        "webm" => Ok(&[
+            "-c:v",
+            "libvpx-vp9",
+            "-b:v",
+            "0",
+            "-crf",
+            "32",
+            "-deadline",
+            "good",
+            "-cpu-used",
+            "4",
+        ]),
+        other => anyhow::bail!("Unsupported container for re-encode: {other}"),
+    }
+}
+
+// ─── Clips and animations ─────────────────────────────────────────
+
+/// How far from the requested start a keyframe may be for the clip to be
+/// cut without re-encoding.
+const KEYFRAME_TOLERANCE_SECS: f64 = 0.25;
+
+/// Cut `path` (a `container` file) down to `clip`, in place. Stream-copies
+/// when a keyframe sits at the start of the clip, re-encodes otherwise —
+/// a copy can only start on a keyframe. Reports 0–99 % into `progress`.
+pub async fn trim_clip(
+    path: &std::path::Path,
+    container: &str,
+    clip: ClipRange,
+    progress: Option<ProgressTracker>,
+) -> Result<()> {
+    let parent = path.parent().unwrap_or_else(|| std::path::Path::new("."));
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("Input path has no stem")?;
+    let tmp = parent.join(format!("{stem}.clip.{container}"));
+    let track = |len: f64| {
+        progress
+            .as_ref()
+            .map(|p| FfmpegTrack::new(p, Some(len), 0, 99))
+    };
+
+    // An unreadable file says nothing about its keyframes — re-encode
+    // rather than take it for audio only and copy from an arbitrary point.
+    let copy_from = match video_keyframes(path).await {
+        Ok(keyframes) => copy_start(&keyframes, clip.start_secs),
+        Err(e) => {
+            tracing::warn!("trim_clip: keyframe probe failed ({e}), re-encoding");
+            None
+        }
+    };
+    let copied = match copy_from {
+        Some(start) => {
+            let len = clip.end_secs - start;
+            run_ffmpeg_transcode(path, &tmp, container, false, Some((start, len)), track(len))
+                .await
+                .inspect_err(|e| tracing::warn!("trim_clip: copy failed ({e}), re-encoding"))
+                .is_ok()
+        }
+        None => false,
+    };
+    if !copied {
+        let span = (clip.start_secs, clip.len_secs());
+        run_ffmpeg_transcode(path, &tmp, container, true, Some(span), track(span.1))
+            .await
+            .context("ffmpeg clip re-encode failed")?;
+    }
+
+    tokio::fs::rename(&tmp, path)
+        .await
+        .context("Failed to swap the clip into place")?;
+    if let Some(p) = &progress {
+        p.set_percent(99);
     }
     Ok(())
 }
 
+/// Where a stream copy of a clip starting at `start` can begin: the
+/// keyframe within [`KEYFRAME_TOLERANCE_SECS`] of it. No video stream (no
+/// keyframes listed) means audio only, which can be cut anywhere.
+fn copy_start(keyframes: &[f64], start: f64) -> Option<f64> {
+    if keyframes.is_empty() {
+        return Some(start);
+    }
+    keyframes
+        .iter()
+        .copied()
+        .filter(|kf| (kf - start).abs() <= KEYFRAME_TOLERANCE_SECS)
+        .min_by(|a, b| (a - start).abs().total_cmp(&(b - start).abs()))
+}
+
+/// Timestamps of the keyframes of the first video stream. Reads packet
+/// flags only, so it doesn't decode anything.
+async fn video_keyframes(path: &std::path::Path) -> Result<Vec<f64>> {
+    let path_str = path.to_str().context("Invalid path for ffprobe")?;
+    let out = tokio::process::Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "packet=pts_time,flags",
+            "-of",
+            "csv=print_section=0",
+            path_str,
+        ])
+        .output()
+        .await
+        .context("Failed to spawn ffprobe")?;
+    if !out.status.success() {
+        anyhow::bail!("ffprobe failed for {path:?}");
+    }
+    Ok(parse_keyframes(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// `pts_time,flags` lines → times of the packets flagged `K`.
+fn parse_keyframes(csv: &str) -> Vec<f64> {
+    csv.lines()
+        .filter_map(|line| {
+            let (pts, flags) = line.trim().split_once(',')?;
+            flags.starts_with('K').then(|| pts.parse().ok())?
+        })
+        .collect()
+}
+
+/// Turn `clip` of `input` into an animated GIF / WebP at `output`.
+/// Reports 0–99 % into `progress`; returns the output's size.
+pub async fn export_animation(
+    input: &std::path::Path,
+    output: &std::path::Path,
+    clip: ClipRange,
+    options: AnimationOptions,
+    progress: Option<ProgressTracker>,
+) -> Result<u64> {
+    let args = animation_args(
+        input.to_str().context("Invalid input path")?,
+        output.to_str().context("Invalid output path")?,
+        clip,
+        options,
+    );
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let track = progress
+        .as_ref()
+        .map(|p| FfmpegTrack::new(p, Some(clip.len_secs()), 0, 99));
+    let (status, stderr) = run_ffmpeg(&args, track)
+        .await
+        .context("Failed to run ffmpeg for the animation")?;
+    if !status.success() {
+        let _ = tokio::fs::remove_file(output).await;
+        anyhow::bail!("ffmpeg animation failed: {stderr}");
+    }
+    let meta = tokio::fs::metadata(output)
+        .await
+        .context("Animation output file not found")?;
+    Ok(meta.len())
+}
+
+fn animation_args(
+    input: &str,
+    output: &str,
+    clip: ClipRange,
+    options: AnimationOptions,
+) -> Vec<String> {
+    // Never wider than the source.
+    let scale = format!(
+        "fps={},scale='min({},iw)':-2:flags=lanczos",
+        options.fps, options.width
+    );
+    let (filter, codec): (String, &[&str]) = match options.format {
+        // A palette built from the clip itself instead of GIF's default
+        // one; `diff` weights the colours of what moves.
+        AnimationFormat::Gif => (
+            format!(
+                "{scale},split[a][b];[a]palettegen=stats_mode=diff[p];\
+                 [b][p]paletteuse=dither=bayer:bayer_scale=5"
+            ),
+            &[],
+        ),
+        AnimationFormat::WebP => (
+            scale,
+            &["-c:v", "libwebp", "-lossless", "0", "-quality", "75"],
+        ),
+    };
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-ss".into(),
+        format!("{:.3}", clip.start_secs),
+        "-t".into(),
+        format!("{:.3}", clip.len_secs()),
+        "-i".into(),
+        input.into(),
+        "-an".into(),
+        "-filter_complex".into(),
+        filter,
+    ];
+    args.extend(codec.iter().map(|a| a.to_string()));
+    // Loop forever.
+    args.extend(["-loop".into(), "0".into(), output.into()]);
+    args
+}
+
 /// Probe the real container of a video file using `ffprobe`. Returns
 /// the first entry from the comma-separated `format_name` list
 /// (e.g. `"mov,mp4,m4a,3gp,3g2,mj2"` → `"mov"`). Returns an empty
@@ -207,243 +427,216 @@ fn container_matches(ffprobe_format: &str, container: &str) -> bool {
     }
 }
 
-/// Extract audio from a downloaded video file using yt-dlp + ffmpeg.
-pub async fn extract_audio(
-    input_path: &std::path::Path,
-    output_path: &std::path::Path,
-) -> Result<u64> {
-    let input_str = input_path.to_str().context("Invalid input path")?;
-    let output_str = output_path.to_str().context("Invalid output path")?;
-
-    let output = ytdlp_command()
-        .args(["-x", "--audio-format", "mp3", "-o", output_str, input_str])
+/// Get video duration using ffprobe.
+pub(super) async fn ffprobe_duration(path: &std::path::Path) -> Option<f64> {
+    let path_str = path.to_str()?;
+    let output = tokio::process::Command::new("ffprobe")
+        .args([
+            "-v",
+            "quiet",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+            path_str,
+        ])
         .output()
         .await
-        .context("Failed to run yt-dlp for audio extraction")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Audio extraction failed: {stderr}");
-    }
-
-    let metadata = tokio::fs::metadata(output_str)
-        .await
-        .context("Audio output file not found")?;
-    Ok(metadata.len())
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.trim().parse::<f64>().ok()
 }
 
-// ─── WhatsApp conversion ──────────────────────────────────────────
-
-/// WhatsApp video limits.
-const WHATSAPP_MAX_SIZE: u64 = 16 * 1024 * 1024; // 16 MB
-const WHATSAPP_SEGMENT_SECS: u32 = 180; // 3 minutes default
+// ─── ffmpeg progress ──────────────────────────────────────────────
 
-/// Result of WhatsApp conversion — single file or multiple parts.
-#[derive(Debug)]
-pub enum WhatsAppResult {
-    Single { path: std::path::PathBuf, size: u64 },
-    Parts(Vec<WhatsAppPart>),
+/// Where a tracked ffmpeg run reports: `tracker`, with the run mapped onto
+/// `from..=to` percent of the current phase. `duration` is the input's
+/// length; without it only sizes and the ETA-less reading are reported.
+pub(super) struct FfmpegTrack<'a> {
+    tracker: &'a ProgressTracker,
+    duration: Option<f64>,
+    from: u8,
+    to: u8,
 }
 
-#[derive(Debug)]
-pub struct WhatsAppPart {
-    pub path: std::path::PathBuf,
-    pub size: u64,
-    pub index: usize,
+impl<'a> FfmpegTrack<'a> {
+    pub(super) fn new(
+        tracker: &'a ProgressTracker,
+        duration: Option<f64>,
+        from: u8,
+        to: u8,
+    ) -> Self {
+        Self {
+            tracker,
+            duration: duration.filter(|d| *d > 0.0),
+            from,
+            to,
+        }
+    }
 }
 
-/// Convert a downloaded video to WhatsApp-compatible format.
-/// Strategy: transcode to H.264/AAC MP4 with veryfast preset, then split if > 16 MB.
-pub async fn convert_for_whatsapp(
-    input_path: &std::path::Path,
-    output_dir: &std::path::Path,
-    base_name: &str,
-    progress: Option<std::sync::Arc<std::sync::atomic::AtomicU8>>,
-) -> Result<WhatsAppResult> {
-    use std::sync::atomic::Ordering;
-
-    let input_str = input_path.to_str().context("Invalid input path")?;
-    let converted_path = output_dir.join(format!("{base_name}-wa.mp4"));
-    let converted_str = converted_path.to_str().context("Invalid output path")?;
-
-    if let Some(p) = &progress {
-        p.store(40, Ordering::Relaxed);
+/// Run ffmpeg with `args`, returning its exit status and stderr. With a
+/// `track`, ffmpeg is asked for machine-readable progress on stdout
+/// (`-progress pipe:1`) and every block of it updates the tracker.
+pub(super) async fn run_ffmpeg(
+    args: &[&str],
+    track: Option<FfmpegTrack<'_>>,
+) -> std::io::Result<(std::process::ExitStatus, String)> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+    let mut cmd = tokio::process::Command::new("ffmpeg");
+    cmd.kill_on_drop(true);
+    if track.is_some() {
+        cmd.args(["-progress", "pipe:1", "-nostats"])
+            .stdout(std::process::Stdio::piped());
+    } else {
+        cmd.stdout(std::process::Stdio::null());
     }
-
-    // Transcode to a WhatsApp-compatible MP4 with H.264 video and AAC audio.
-    // The input is always re-encoded here to normalize codec/container compatibility.
-    let output = tokio::process::Command::new("ffmpeg")
-        .args([
-            "-i",
-            input_str,
-            "-c:v",
-            "libx264",
-            "-preset",
-            "veryfast",
-            "-profile:v",
-            "main",
-            "-level",
-            "3.1",
-            "-pix_fmt",
-            "yuv420p",
-            "-crf",
-            "30",
-            "-vf",
-            "scale=-2:'trunc(min(480,ih)/2)*2'",
-            "-c:a",
-            "aac",
-            "-b:a",
-            "128k",
-            "-ac",
-            "2",
-            "-movflags",
-            "+faststart",
-            "-y",
-            converted_str,
-        ])
-        .stdout(std::process::Stdio::null())
+    let mut child = cmd
+        .args(args)
         .stderr(std::process::Stdio::piped())
-        .output()
-        .await
-        .context("Failed to run ffmpeg")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("ffmpeg conversion failed: {stderr}");
-    }
-
-    if let Some(p) = &progress {
-        p.store(80, Ordering::Relaxed);
-    }
-
-    let meta = tokio::fs::metadata(&converted_path).await?;
-    let size = meta.len();
-
-    if size <= WHATSAPP_MAX_SIZE {
-        if let Some(p) = &progress {
-            p.store(99, Ordering::Relaxed);
+        .spawn()?;
+
+    let stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    // Drained concurrently so a chatty ffmpeg can't block on a full pipe.
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut buf).await;
+        }
+        buf
+    });
+
+    if let (Some(track), Some(stdout)) = (&track, stdout) {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut block = FfmpegProgressBlock::default();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if block.feed(&line) {
+                track
+                    .tracker
+                    .update(block.reading(track.duration, track.from, track.to));
+            }
         }
-        return Ok(WhatsAppResult::Single {
-            path: converted_path,
-            size,
-        });
     }
 
-    // File too large — split into segments
-    tracing::info!(
-        "WhatsApp: {:.1} MB exceeds 16 MB, splitting",
-        size as f64 / (1024.0 * 1024.0)
-    );
-
-    let duration = ffprobe_duration(&converted_path).await.unwrap_or(0.0);
-    // Calculate segment duration to produce ~12 MB chunks
-    let target_segment_bytes = 12.0 * 1024.0 * 1024.0;
-    let segment_secs = if duration > 0.0 && size > 0 {
-        (target_segment_bytes / (size as f64 / duration)) as u32
-    } else {
-        WHATSAPP_SEGMENT_SECS
-    };
-    let segment_secs = segment_secs.max(10); // minimum 10s to avoid degenerate splits
-
-    let segment_pattern = output_dir.join(format!("{base_name}-wa-part%03d.mp4"));
-    let pattern_str = segment_pattern.to_str().context("Invalid segment path")?;
-    let seg_time = format!("{segment_secs}");
-
-    let split_output = tokio::process::Command::new("ffmpeg")
-        .args([
-            "-i",
-            converted_str,
-            "-c",
-            "copy",
-            "-f",
-            "segment",
-            "-segment_time",
-            &seg_time,
-            "-reset_timestamps",
-            "1",
-            "-movflags",
-            "+faststart",
-            "-y",
-            pattern_str,
-        ])
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::piped())
-        .output()
-        .await
-        .context("Failed to run ffmpeg segment")?;
+    let status = child.wait().await?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    Ok((status, stderr))
+}
 
-    if !split_output.status.success() {
-        let stderr = String::from_utf8_lossy(&split_output.stderr);
-        anyhow::bail!("ffmpeg split failed: {stderr}");
-    }
+/// One block of `ffmpeg -progress` output: `key=value` lines ending with
+/// `progress=continue` (or `progress=end`).
+#[derive(Debug, Default)]
+struct FfmpegProgressBlock {
+    out_time_secs: Option<f64>,
+    total_size: Option<u64>,
+    /// Multiple of real time, `1.5x` → 1.5.
+    speed: Option<f64>,
+}
 
-    // Clean up the single converted file
-    let _ = tokio::fs::remove_file(&converted_path).await;
-
-    // Collect parts
-    let mut parts = Vec::new();
-    for idx in 0usize.. {
-        let part_path = output_dir.join(format!("{base_name}-wa-part{idx:03}.mp4"));
-        match tokio::fs::metadata(&part_path).await {
-            Ok(m) => parts.push(WhatsAppPart {
-                path: part_path,
-                size: m.len(),
-                index: idx,
-            }),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
-            Err(e) => {
-                return Err(e).context(format!(
-                    "Failed to read metadata for split part {}",
-                    part_path.display()
-                ));
+impl FfmpegProgressBlock {
+    /// Take one line; true when it closed a block.
+    fn feed(&mut self, line: &str) -> bool {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            return false;
+        };
+        match key {
+            // Both are microseconds — `out_time_ms` is misnamed upstream.
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(us) = value.parse::<i64>() {
+                    self.out_time_secs = Some(us.max(0) as f64 / 1_000_000.0);
+                }
             }
+            "total_size" => self.total_size = value.parse().ok(),
+            "speed" => {
+                self.speed = value
+                    .trim()
+                    .trim_end_matches('x')
+                    .parse()
+                    .ok()
+                    .filter(|s: &f64| *s > 0.0);
+            }
+            "progress" => return true,
+            _ => {}
         }
+        false
     }
 
-    if parts.is_empty() {
-        anyhow::bail!("No split parts were created");
-    }
-
-    if let Some(p) = &progress {
-        p.store(99, Ordering::Relaxed);
+    /// The block as a reading within `from..=to` percent, given the input
+    /// `duration` in seconds.
+    fn reading(&self, duration: Option<f64>, from: u8, to: u8) -> ProgressUpdate {
+        let done = match (self.out_time_secs, duration) {
+            (Some(t), Some(d)) => (t / d).clamp(0.0, 1.0),
+            _ => 0.0,
+        };
+        let eta_secs = match (self.out_time_secs, duration, self.speed) {
+            (Some(t), Some(d), Some(speed)) => Some(((d - t).max(0.0) / speed) as u64),
+            _ => None,
+        };
+        ProgressUpdate {
+            percent: from + ((to.saturating_sub(from)) as f64 * done) as u8,
+            downloaded_bytes: self.total_size,
+            total_bytes: None,
+            speed_bps: None,
+            eta_secs,
+        }
     }
-
-    tracing::info!("WhatsApp split: {} parts created", parts.len());
-    Ok(WhatsAppResult::Parts(parts))
 }
 
-/// Estimate number of WhatsApp parts based on video duration.
-/// With CRF 30 at 480p, typical bitrate is ~1.1 Mbps.
-/// 12 MB target segment / 1.1 Mbps = 87 seconds per segment.
-pub fn estimate_whatsapp_parts(duration_secs: f64) -> u32 {
-    if duration_secs <= 0.0 {
-        return 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clips_copy_only_from_a_nearby_keyframe() {
+        let csv = "0.000000,K__\n0.040000,___\n2.002000,K__\n4.004000,K_\nN/A,K__\n";
+        let keyframes = parse_keyframes(csv);
+        assert_eq!(keyframes, vec![0.0, 2.002, 4.004]);
+        assert_eq!(copy_start(&keyframes, 2.1), Some(2.002));
+        assert_eq!(copy_start(&keyframes, 3.0), None);
+        // Audio only.
+        assert_eq!(copy_start(&[], 3.0), Some(3.0));
     }
-    // Estimate total size: ~1.1 Mbps = 137.5 KB/s for 480p CRF 30
-    let estimated_bytes = duration_secs * 137.5 * 1024.0;
-    if estimated_bytes <= WHATSAPP_MAX_SIZE as f64 {
-        return 1;
+
+    #[test]
+    fn gif_builds_its_own_palette_and_webp_uses_libwebp() {
+        let clip = ClipRange::new(65.0, 80.5).unwrap();
+        let gif = AnimationOptions::new(AnimationFormat::Gif, Some(10), Some(320)).unwrap();
+        let args = animation_args("in.mp4", "out.gif", clip, gif).join(" ");
+        assert!(args.starts_with("-y -ss 65.000 -t 15.500 -i in.mp4 -an -filter_complex"));
+        assert!(args.contains("fps=10,scale='min(320,iw)':-2:flags=lanczos,split[a][b]"));
+        assert!(args.contains("paletteuse"));
+        assert!(args.ends_with("-loop 0 out.gif"));
+
+        let webp = AnimationOptions::new(AnimationFormat::WebP, None, None).unwrap();
+        let args = animation_args("in.mp4", "out.webp", clip, webp).join(" ");
+        assert!(!args.contains("palettegen"));
+        assert!(args.contains("-c:v libwebp -lossless 0 -quality 75 -loop 0 out.webp"));
     }
-    let target_segment_bytes = 12.0 * 1024.0 * 1024.0;
-    (estimated_bytes / target_segment_bytes).ceil() as u32
-}
 
-/// Get video duration using ffprobe.
-async fn ffprobe_duration(path: &std::path::Path) -> Option<f64> {
-    let path_str = path.to_str()?;
-    let output = tokio::process::Command::new("ffprobe")
-        .args([
-            "-v",
-            "quiet",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
-            path_str,
-        ])
-        .output()
-        .await
-        .ok()?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    stdout.trim().parse::<f64>().ok()
+    #[test]
+    fn ffmpeg_progress_block_maps_onto_range() {
+        let mut block = FfmpegProgressBlock::default();
+        let lines = [
+            "frame=240",
+            "total_size=1048576",
+            "out_time_us=30000000",
+            "out_time_ms=30000000",
+            "speed=2.5x",
+        ];
+        assert!(lines.iter().all(|l| !block.feed(l)));
+        assert!(block.feed("progress=continue"));
+
+        let r = block.reading(Some(120.0), 40, 80);
+        assert_eq!(r.percent, 50);
+        assert_eq!(r.downloaded_bytes, Some(1_048_576));
+        assert_eq!(r.eta_secs, Some(36));
+
+        // Before the first frame ffmpeg reports N/A; no duration, no ETA.
+        block.feed("speed=N/A");
+        let r = block.reading(None, 40, 80);
+        assert_eq!(r.percent, 40);
+        assert_eq!(r.eta_secs, None);
+    }
 }
@@ -79,6 +79,17 @@ where
     }
 }
 
+/// Listing-count cache for `cr_app::catalog` (`AppState::listing_count_cache`).
+impl cr_app::catalog::CountCache for BoundedTtlCache<String, i64> {
+    async fn get(&self, key: &str) -> Option<i64> {
+        BoundedTtlCache::get(self, &key.to_string()).await
+    }
+
+    async fn put(&self, key: String, count: i64) {
+        self.insert(key, count).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::BoundedTtlCache;
@@ -11,6 +11,8 @@
 use anyhow::Context;
 use std::sync::Arc;
 
+use crate::rate_limit::RateLimitConfig;
+
 /// Everything the web process learns from its environment.
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -79,10 +81,29 @@ pub struct AppConfig {
     /// flipping the flag back — the old search/validate handlers stay
     /// routed so the template's fallback path keeps working.
     pub prehrajto_sources_from_db: bool,
+    /// Hide near-duplicate photos inside a single landmark / pool /
+    /// municipality gallery, using the perceptual-hash index
+    /// (`photo_hashes`). Set `GALLERY_SUPPRESS_DUPLICATES=1` to enable.
+    /// Photos not yet indexed are always shown.
+    pub gallery_suppress_duplicates: bool,
     /// Cloudflare API token scoped to Zone.Cache Purge. Enables the admin
     /// `/admin/cache/` page to invalidate CDN cache on demand. `None` when the
     /// env vars aren't provisioned — UI hides the purge actions in that case.
     pub cf_cache_purge: Option<CfCachePurgeConfig>,
+    /// Per-client limits of the public video API, one `RATE_LIMIT_*` var
+    /// per route group (format in `RateLimitConfig::parse`).
+    pub rate_limits: RateLimits,
+}
+
+/// Route-group limits; `None` leaves a group unlimited.
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    /// `POST /api/video/info` — every call runs an extractor.
+    pub video_info: Option<RateLimitConfig>,
+    /// `POST /api/video/prepare` — the daily job quota lives here.
+    pub video_prepare: Option<RateLimitConfig>,
+    /// Prepared files and library streams — the daily byte quota.
+    pub video_files: Option<RateLimitConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -160,6 +181,11 @@ impl AppConfig {
             Ok("1")
         );
 
+        let gallery_suppress_duplicates = matches!(
+            std::env::var("GALLERY_SUPPRESS_DUPLICATES").as_deref(),
+            Ok("1")
+        );
+
         let sledujteto_poc_enabled =
             matches!(std::env::var("SLEDUJTETO_POC_ENABLED").as_deref(), Ok("1"));
 
@@ -173,6 +199,18 @@ impl AppConfig {
             _ => None,
         };
 
+        let rate_limits = RateLimits {
+            video_info: RateLimitConfig::from_env("RATE_LIMIT_VIDEO_INFO", "20/min,burst=10")?,
+            video_prepare: RateLimitConfig::from_env(
+                "RATE_LIMIT_VIDEO_PREPARE",
+                "6/min,burst=3,jobs=40",
+            )?,
+            video_files: RateLimitConfig::from_env(
+                "RATE_LIMIT_VIDEO_FILES",
+                "120/min,burst=60,bytes=20G",
+            )?,
+        };
+
         Ok(Arc::new(Self {
             database_url,
             port,
@@ -188,8 +226,10 @@ impl AppConfig {
             cz_proxy,
             sledujteto_proxy,
             prehrajto_sources_from_db,
+            gallery_suppress_duplicates,
             sledujteto_poc_enabled,
             cf_cache_purge,
+            rate_limits,
         }))
     }
 }
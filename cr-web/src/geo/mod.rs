@@ -0,0 +1,54 @@
+//! Web Mercator geometry for the vector tiles. Boundary parsing and the
+//! polygon types come from `cr_infra::boundaries`, shared with the
+//! reverse geocoder.
+
+pub mod mvt;
+pub mod tiles;
+
+pub use cr_infra::boundaries::{BBox, Feature, Point, Ring, bbox_intersects, bbox_of};
+
+/// Shoelace signed area. Positive for rings that run clockwise on screen
+/// (y pointing down), which is what MVT expects for exterior rings.
+pub fn signed_area(ring: &[Point]) -> f64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Project lon/lat to Web Mercator normalised to the unit square
+/// (`[0, 0]` = north-west corner of tile 0/0/0, y grows southwards).
+pub fn lonlat_to_mercator(p: Point) -> Point {
+    let x = (p[0] + 180.0) / 360.0;
+    let lat = p[1].clamp(-85.051_128_78, 85.051_128_78).to_radians();
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / std::f64::consts::PI) / 2.0;
+    [x, y]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mercator_maps_origin_to_centre() {
+        let [x, y] = lonlat_to_mercator([0.0, 0.0]);
+        assert!((x - 0.5).abs() < 1e-12);
+        assert!((y - 0.5).abs() < 1e-12);
+        // Prague sits in the north-east quadrant of the world tile.
+        let [x, y] = lonlat_to_mercator([14.42, 50.08]);
+        assert!(x > 0.5 && y < 0.5);
+    }
+
+    #[test]
+    fn signed_area_sign_follows_screen_winding() {
+        // Clockwise on screen: right, down, left.
+        let cw = vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]];
+        assert_eq!(signed_area(&cw), 4.0);
+        let ccw: Vec<Point> = cw.iter().rev().copied().collect();
+        assert_eq!(signed_area(&ccw), -4.0);
+    }
+}
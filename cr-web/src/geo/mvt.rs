@@ -0,0 +1,207 @@
+//! Minimal Mapbox Vector Tile (spec v2.1) encoder.
+//!
+//! Writes the protobuf wire format by hand — we only ever emit polygon
+//! layers with string properties, which needs five message fields and
+//! two wire types, so pulling in a protobuf code generator would be
+//! overkill.
+
+use std::collections::HashMap;
+
+/// Tile coordinate space. 4096 is what every renderer expects by default.
+pub const EXTENT: u32 = 4096;
+
+const WIRE_VARINT: u32 = 0;
+const WIRE_LEN: u32 = 2;
+
+const CMD_MOVE_TO: u32 = 1;
+const CMD_LINE_TO: u32 = 2;
+const CMD_CLOSE_PATH: u32 = 7;
+
+const GEOM_POLYGON: u64 = 3;
+
+fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+fn put_key(buf: &mut Vec<u8>, field: u32, wire: u32) {
+    put_varint(buf, u64::from((field << 3) | wire));
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    put_key(buf, field, WIRE_LEN);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn put_packed(buf: &mut Vec<u8>, field: u32, values: &[u32]) {
+    let mut inner = Vec::with_capacity(values.len() * 2);
+    for &v in values {
+        put_varint(&mut inner, u64::from(v));
+    }
+    put_bytes(buf, field, &inner);
+}
+
+fn zigzag(n: i32) -> u32 {
+    ((n << 1) ^ (n >> 31)) as u32
+}
+
+fn command(id: u32, count: u32) -> u32 {
+    (id & 0x7) | (count << 3)
+}
+
+/// One layer of a tile, built feature by feature.
+pub struct LayerBuilder {
+    name: String,
+    keys: Vec<String>,
+    key_index: HashMap<String, u32>,
+    values: Vec<String>,
+    value_index: HashMap<String, u32>,
+    features: Vec<Vec<u8>>,
+}
+
+impl LayerBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            keys: Vec::new(),
+            key_index: HashMap::new(),
+            values: Vec::new(),
+            value_index: HashMap::new(),
+            features: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    fn intern(list: &mut Vec<String>, index: &mut HashMap<String, u32>, s: &str) -> u32 {
+        if let Some(&i) = index.get(s) {
+            return i;
+        }
+        let i = list.len() as u32;
+        list.push(s.to_string());
+        index.insert(s.to_string(), i);
+        i
+    }
+
+    /// Add a polygon feature. `rings` are in tile units, already clipped
+    /// and wound per the spec (exterior positive area, holes negative);
+    /// each ring is open (no repeated closing vertex).
+    pub fn add_polygon(
+        &mut self,
+        id: Option<u64>,
+        props: &[(&str, &str)],
+        rings: &[Vec<[i32; 2]>],
+    ) {
+        let mut geometry = Vec::new();
+        let (mut cx, mut cy) = (0i32, 0i32);
+        for ring in rings.iter().filter(|r| r.len() >= 3) {
+            geometry.push(command(CMD_MOVE_TO, 1));
+            geometry.push(zigzag(ring[0][0] - cx));
+            geometry.push(zigzag(ring[0][1] - cy));
+            (cx, cy) = (ring[0][0], ring[0][1]);
+            geometry.push(command(CMD_LINE_TO, (ring.len() - 1) as u32));
+            for p in &ring[1..] {
+                geometry.push(zigzag(p[0] - cx));
+                geometry.push(zigzag(p[1] - cy));
+                (cx, cy) = (p[0], p[1]);
+            }
+            geometry.push(command(CMD_CLOSE_PATH, 1));
+        }
+        if geometry.is_empty() {
+            return;
+        }
+
+        let mut tags = Vec::with_capacity(props.len() * 2);
+        for (k, v) in props {
+            tags.push(Self::intern(&mut self.keys, &mut self.key_index, k));
+            tags.push(Self::intern(&mut self.values, &mut self.value_index, v));
+        }
+
+        let mut feature = Vec::new();
+        if let Some(id) = id {
+            put_key(&mut feature, 1, WIRE_VARINT);
+            put_varint(&mut feature, id);
+        }
+        put_packed(&mut feature, 2, &tags);
+        put_key(&mut feature, 3, WIRE_VARINT);
+        put_varint(&mut feature, GEOM_POLYGON);
+        put_packed(&mut feature, 4, &geometry);
+        self.features.push(feature);
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut layer = Vec::new();
+        put_key(&mut layer, 15, WIRE_VARINT);
+        put_varint(&mut layer, 2);
+        put_bytes(&mut layer, 1, self.name.as_bytes());
+        for f in &self.features {
+            put_bytes(&mut layer, 2, f);
+        }
+        for k in &self.keys {
+            put_bytes(&mut layer, 3, k.as_bytes());
+        }
+        for v in &self.values {
+            let mut value = Vec::new();
+            put_bytes(&mut value, 1, v.as_bytes());
+            put_bytes(&mut layer, 4, &value);
+        }
+        put_key(&mut layer, 5, WIRE_VARINT);
+        put_varint(&mut layer, u64::from(EXTENT));
+        layer
+    }
+}
+
+/// Serialise layers into a tile. Empty layers are left out; a tile with
+/// no features at all encodes to zero bytes, which is a valid empty tile.
+pub fn encode_tile(layers: &[LayerBuilder]) -> Vec<u8> {
+    let mut tile = Vec::new();
+    for layer in layers.iter().filter(|l| !l.is_empty()) {
+        put_bytes(&mut tile, 3, &layer.encode());
+    }
+    tile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zigzag_matches_protobuf() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(-2), 3);
+    }
+
+    #[test]
+    fn encodes_spec_example_polygon() {
+        // Geometry from MVT spec §4.3.5.3 ("Example Polygon").
+        let mut layer = LayerBuilder::new("t");
+        layer.add_polygon(None, &[], &[vec![[3, 6], [8, 12], [20, 34]]]);
+        let tile = encode_tile(&[layer]);
+        let expected: [u8; 9] = [9, 6, 12, 18, 10, 12, 24, 44, 15];
+        assert!(tile.windows(expected.len()).any(|w| w == expected));
+    }
+
+    #[test]
+    fn empty_layers_produce_empty_tile() {
+        assert!(encode_tile(&[LayerBuilder::new("x")]).is_empty());
+    }
+
+    #[test]
+    fn interns_repeated_property_values() {
+        let mut layer = LayerBuilder::new("t");
+        let ring = vec![[0, 0], [10, 0], [10, 10]];
+        layer.add_polygon(Some(1), &[("kraj", "CZ010")], std::slice::from_ref(&ring));
+        layer.add_polygon(Some(2), &[("kraj", "CZ010")], &[ring]);
+        assert_eq!(layer.keys.len(), 1);
+        assert_eq!(layer.values.len(), 1);
+        assert_eq!(layer.features.len(), 2);
+    }
+}
@@ -0,0 +1,386 @@
+//! Vector tiles for the territorial boundary layers.
+//!
+//! Every layer is projected to Web Mercator once at startup. A tile
+//! request then only has to pick the features whose bbox touches the
+//! tile, translate into tile units, clip against the buffered tile square
+//! and drop vertices closer than [`SIMPLIFY_TOLERANCE`] — which, because
+//! the tolerance is in tile units, gets coarser in ground distance the
+//! further out the map is zoomed.
+
+use cr_infra::boundaries::{self, LayerSource};
+
+use super::mvt::{EXTENT, LayerBuilder, encode_tile};
+use super::{BBox, Feature, Point, Ring, bbox_intersects, lonlat_to_mercator, signed_area};
+
+/// Deepest zoom we render. Past z14 the simplified source data has no
+/// more detail to show; clients overzoom the z14 tile.
+pub const MAX_ZOOM: u8 = 14;
+
+/// Tile-unit margin kept around the tile so polygon edges don't show
+/// seams when neighbouring tiles are stitched together.
+const BUFFER: f64 = 64.0;
+
+/// Douglas–Peucker tolerance in tile units — half a pixel on a 512 px
+/// tile.
+const SIMPLIFY_TOLERANCE: f64 = 4.0;
+
+/// Static description of one boundary layer.
+#[derive(Debug, Clone, Copy)]
+pub struct LayerSpec {
+    /// URL segment and MVT layer name.
+    pub name: &'static str,
+    pub source: LayerSource,
+    /// Below this zoom the layer is omitted — 6 000 municipalities in one
+    /// country-wide tile are neither legible nor cheap.
+    pub min_zoom: u8,
+}
+
+pub const LAYERS: &[LayerSpec] = &[
+    LayerSpec {
+        name: "regions",
+        source: boundaries::REGIONS,
+        min_zoom: 0,
+    },
+    LayerSpec {
+        name: "districts",
+        // Tiles keep the numeric RÚIAN `kod_okres` (also the MVT feature
+        // id); the reverse geocoder keys districts by LAU code instead.
+        source: LayerSource {
+            code_key: "kod_okres",
+            ..boundaries::DISTRICTS
+        },
+        min_zoom: 0,
+    },
+    LayerSpec {
+        name: "orp",
+        source: boundaries::ORP,
+        min_zoom: 5,
+    },
+    LayerSpec {
+        name: "municipalities",
+        source: boundaries::MUNICIPALITIES,
+        min_zoom: 7,
+    },
+];
+
+struct TileLayer {
+    spec: LayerSpec,
+    /// Features with geometry and bbox in unit-square Web Mercator.
+    features: Vec<Feature>,
+}
+
+/// All boundary layers, pre-projected and ready to be cut into tiles.
+pub struct TileLayers {
+    layers: Vec<TileLayer>,
+}
+
+impl TileLayers {
+    pub fn load(data_dir: &str) -> anyhow::Result<Self> {
+        let mut layers = Vec::with_capacity(LAYERS.len());
+        for spec in LAYERS {
+            let features = spec
+                .source
+                .load(data_dir)?
+                .into_iter()
+                .map(project_feature)
+                .collect::<Vec<_>>();
+            tracing::info!("Tile layer {}: {} features", spec.name, features.len());
+            layers.push(TileLayer {
+                spec: *spec,
+                features,
+            });
+        }
+        Ok(Self { layers })
+    }
+
+    /// Encode tile `z/x/y` of layer `name`. Returns `None` for an unknown
+    /// layer or out-of-range coordinates; an empty `Vec` is a valid empty
+    /// tile.
+    pub fn render(&self, name: &str, z: u8, x: u32, y: u32) -> Option<Vec<u8>> {
+        let layer = self.layers.iter().find(|l| l.spec.name == name)?;
+        if z > MAX_ZOOM || x >= 1 << z || y >= 1 << z {
+            return None;
+        }
+        let mut builder = LayerBuilder::new(layer.spec.name);
+        if z >= layer.spec.min_zoom {
+            render_layer(&mut builder, &layer.features, z, x, y);
+        }
+        Some(encode_tile(&[builder]))
+    }
+}
+
+fn project_feature(mut f: Feature) -> Feature {
+    for ring in f.polygons.iter_mut().flatten() {
+        for p in ring.iter_mut() {
+            *p = lonlat_to_mercator(*p);
+        }
+    }
+    f.bbox = super::bbox_of(&f.polygons);
+    f
+}
+
+fn render_layer(builder: &mut LayerBuilder, features: &[Feature], z: u8, x: u32, y: u32) {
+    let n = f64::from(1u32 << z);
+    let extent = f64::from(EXTENT);
+    let pad = BUFFER / extent / n;
+    let tile_bbox: BBox = [
+        f64::from(x) / n - pad,
+        f64::from(y) / n - pad,
+        f64::from(x + 1) / n + pad,
+        f64::from(y + 1) / n + pad,
+    ];
+    let to_tile = |p: &Point| -> Point {
+        [
+            (p[0] * n - f64::from(x)) * extent,
+            (p[1] * n - f64::from(y)) * extent,
+        ]
+    };
+
+    for feature in features {
+        if !bbox_intersects(&feature.bbox, &tile_bbox) {
+            continue;
+        }
+        let mut rings: Vec<Vec<[i32; 2]>> = Vec::new();
+        for polygon in &feature.polygons {
+            let mut exterior_kept = false;
+            for (i, ring) in polygon.iter().enumerate() {
+                if i > 0 && !exterior_kept {
+                    break;
+                }
+                let projected: Ring = ring.iter().map(to_tile).collect();
+                let clipped = clip_ring(&projected, -BUFFER, extent + BUFFER);
+                let simplified = simplify_ring(&clipped, SIMPLIFY_TOLERANCE);
+                let Some(mut quantised) = quantise_ring(&simplified) else {
+                    continue;
+                };
+                let area = signed_area(
+                    &quantised
+                        .iter()
+                        .map(|p| [f64::from(p[0]), f64::from(p[1])])
+                        .collect::<Vec<_>>(),
+                );
+                if area == 0.0 {
+                    continue;
+                }
+                // Exterior rings must have positive area, holes negative.
+                if (i == 0) != (area > 0.0) {
+                    quantised.reverse();
+                }
+                exterior_kept |= i == 0;
+                rings.push(quantised);
+            }
+        }
+        if rings.is_empty() {
+            continue;
+        }
+        let mut props: Vec<(&str, &str)> = vec![("code", &feature.code), ("name", &feature.name)];
+        if let Some(parent) = &feature.parent_code {
+            props.push(("parent_code", parent));
+        }
+        builder.add_polygon(feature.code.parse().ok(), &props, &rings);
+    }
+}
+
+/// Sutherland–Hodgman clip of a ring against the square `[lo, hi]²`.
+/// Polygons that straddle a corner come out with edges running along the
+/// clip border, which renderers draw without artefacts.
+pub fn clip_ring(ring: &[Point], lo: f64, hi: f64) -> Ring {
+    let mut out: Ring = ring.to_vec();
+    // (axis, bound, keep-if-greater)
+    for (axis, bound, keep_greater) in
+        [(0, lo, true), (0, hi, false), (1, lo, true), (1, hi, false)]
+    {
+        if out.is_empty() {
+            break;
+        }
+        let inside = |p: &Point| {
+            if keep_greater {
+                p[axis] >= bound
+            } else {
+                p[axis] <= bound
+            }
+        };
+        let input = std::mem::take(&mut out);
+        let mut prev = *input.last().expect("non-empty");
+        for &cur in &input {
+            match (inside(&prev), inside(&cur)) {
+                (true, true) => out.push(cur),
+                (true, false) => out.push(intersect(prev, cur, axis, bound)),
+                (false, true) => {
+                    out.push(intersect(prev, cur, axis, bound));
+                    out.push(cur);
+                }
+                (false, false) => {}
+            }
+            prev = cur;
+        }
+    }
+    out
+}
+
+fn intersect(a: Point, b: Point, axis: usize, bound: f64) -> Point {
+    let t = (bound - a[axis]) / (b[axis] - a[axis]);
+    let other = 1 - axis;
+    let mut p = [0.0; 2];
+    p[axis] = bound;
+    p[other] = a[other] + t * (b[other] - a[other]);
+    p
+}
+
+/// Douglas–Peucker on a closed ring. The ring is split at its first
+/// vertex and the vertex farthest from it so both halves have distinct
+/// endpoints.
+pub fn simplify_ring(ring: &[Point], tolerance: f64) -> Ring {
+    if ring.len() <= 4 {
+        return ring.to_vec();
+    }
+    let far = (1..ring.len())
+        .max_by(|&a, &b| dist2(ring[0], ring[a]).total_cmp(&dist2(ring[0], ring[b])))
+        .unwrap_or(ring.len() / 2);
+    let mut keep = vec![false; ring.len() + 1];
+    keep[0] = true;
+    keep[far] = true;
+    keep[ring.len()] = true;
+    let closed: Vec<Point> = ring
+        .iter()
+        .copied()
+        .chain(std::iter::once(ring[0]))
+        .collect();
+    let mut stack = vec![(0, far), (far, ring.len())];
+    let tol2 = tolerance * tolerance;
+    while let Some((a, b)) = stack.pop() {
+        if b <= a + 1 {
+            continue;
+        }
+        let (mut best, mut best_d) = (0, -1.0);
+        for i in a + 1..b {
+            let d = seg_dist2(closed[i], closed[a], closed[b]);
+            if d > best_d {
+                (best, best_d) = (i, d);
+            }
+        }
+        if best_d > tol2 {
+            keep[best] = true;
+            stack.push((a, best));
+            stack.push((best, b));
+        }
+    }
+    ring.iter()
+        .enumerate()
+        .filter(|(i, _)| keep[*i])
+        .map(|(_, p)| *p)
+        .collect()
+}
+
+fn dist2(a: Point, b: Point) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)
+}
+
+fn seg_dist2(p: Point, a: Point, b: Point) -> f64 {
+    let len2 = dist2(a, b);
+    if len2 == 0.0 {
+        return dist2(p, a);
+    }
+    let t =
+        (((p[0] - a[0]) * (b[0] - a[0]) + (p[1] - a[1]) * (b[1] - a[1])) / len2).clamp(0.0, 1.0);
+    dist2(p, [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])])
+}
+
+/// Round to integer tile units and drop the duplicate vertices rounding
+/// creates. `None` when fewer than three distinct vertices survive.
+fn quantise_ring(ring: &[Point]) -> Option<Vec<[i32; 2]>> {
+    let mut out: Vec<[i32; 2]> = Vec::with_capacity(ring.len());
+    for p in ring {
+        let q = [p[0].round() as i32, p[1].round() as i32];
+        if out.last() != Some(&q) {
+            out.push(q);
+        }
+    }
+    while out.len() > 1 && out.first() == out.last() {
+        out.pop();
+    }
+    (out.len() >= 3).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(lo: f64, hi: f64) -> Ring {
+        vec![[lo, lo], [hi, lo], [hi, hi], [lo, hi]]
+    }
+
+    #[test]
+    fn clip_keeps_inside_ring_untouched() {
+        let ring = square(10.0, 20.0);
+        assert_eq!(clip_ring(&ring, 0.0, 100.0), ring);
+    }
+
+    #[test]
+    fn clip_cuts_overhanging_ring_to_bounds() {
+        let clipped = clip_ring(&square(-50.0, 50.0), 0.0, 100.0);
+        let bbox = super::super::bbox_of(&[vec![clipped.clone()]]);
+        assert_eq!(bbox, [0.0, 0.0, 50.0, 50.0]);
+        assert!((signed_area(&clipped) - 2500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clip_drops_ring_outside_bounds() {
+        assert!(clip_ring(&square(200.0, 300.0), 0.0, 100.0).is_empty());
+    }
+
+    #[test]
+    fn simplify_removes_collinear_and_jitter_vertices() {
+        let ring = vec![
+            [0.0, 0.0],
+            [50.0, 0.5],
+            [100.0, 0.0],
+            [100.0, 100.0],
+            [50.0, 100.2],
+            [0.0, 100.0],
+        ];
+        let simplified = simplify_ring(&ring, 1.0);
+        assert_eq!(simplified, square(0.0, 100.0));
+    }
+
+    #[test]
+    fn quantise_rejects_degenerate_rings() {
+        assert!(quantise_ring(&[[0.1, 0.1], [0.2, 0.2], [0.3, 0.3]]).is_none());
+        assert_eq!(
+            quantise_ring(&[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.2, 0.1]]),
+            Some(vec![[0, 0], [10, 0], [10, 10]])
+        );
+    }
+
+    #[test]
+    fn renders_feature_into_covering_tile_only() {
+        let feature = project_feature(Feature {
+            code: "3018".into(),
+            name: "Hlavní město Praha".into(),
+            parent_code: None,
+            polygons: vec![vec![vec![
+                [14.3, 50.0],
+                [14.7, 50.0],
+                [14.7, 50.2],
+                [14.3, 50.2],
+            ]]],
+            bbox: [0.0; 4],
+        });
+        let layers = TileLayers {
+            layers: vec![TileLayer {
+                spec: LAYERS[0],
+                features: vec![feature],
+            }],
+        };
+        // z8 tile containing Prague: x = 138, y = 86.
+        let tile = layers.render("regions", 8, 138, 86).unwrap();
+        assert!(!tile.is_empty());
+        assert!(tile.windows(4).any(|w| w == b"3018"));
+        // A tile over the Atlantic is empty but valid.
+        assert_eq!(layers.render("regions", 8, 100, 86), Some(Vec::new()));
+        // Unknown layer and out-of-range coordinates.
+        assert_eq!(layers.render("states", 8, 138, 86), None);
+        assert_eq!(layers.render("regions", 2, 4, 0), None);
+        assert_eq!(layers.render("regions", MAX_ZOOM + 1, 0, 0), None);
+    }
+}
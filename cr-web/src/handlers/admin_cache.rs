@@ -26,6 +26,7 @@ use serde::Deserialize;
 
 use crate::config::CfCachePurgeConfig;
 use crate::error::WebResult;
+use crate::handlers::SITE_ORIGIN;
 use crate::state::AppState;
 
 const CF_PURGE_BATCH: usize = 30;
@@ -33,7 +34,6 @@ const CF_PURGE_TIMEOUT: Duration = Duration::from_secs(30);
 /// Hard cap on the "specific URLs" textarea so a fat-fingered paste can't
 /// kick off thousands of API calls. Admin who needs more can split the work.
 const MAX_URL_LIST: usize = 5000;
-const SITE_ORIGIN: &str = "https://ceskarepublika.wiki";
 
 #[derive(Template)]
 #[template(path = "admin_cache.html")]
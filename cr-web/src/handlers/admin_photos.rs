@@ -0,0 +1,129 @@
+//! Admin report of duplicate photos found by the perceptual-hash index.
+//!
+//! Routes:
+//!   GET /admin/photos/duplicates        — clusters spanning several entities
+//!   GET /admin/photos/duplicates?all=1  — every cluster, including repeats
+//!                                         inside one gallery
+//!
+//! Source data: `photo_hashes` (migration 079), filled by the
+//! `photo-hash-index build` binary. Clustering runs in-process on every
+//! request — the multi-index search in `cr_infra::photo_hash` handles the
+//! full ~50 k-row table in well under a second, and the page is visited
+//! rarely enough that caching would be premature.
+
+use askama::Template;
+use axum::extract::State;
+use axum::http::HeaderValue;
+use axum::response::{Html, IntoResponse, Response};
+use cr_infra::photo_hash::{self, PhotoHashRow};
+
+use crate::error::WebResult;
+use crate::state::AppState;
+
+/// Cap on rendered clusters so a badly tuned threshold can't produce a
+/// multi-megabyte page.
+const MAX_CLUSTERS: usize = 300;
+
+struct ClusterMember {
+    r2_key: String,
+    entity_type: String,
+    entity_ref: String,
+    width: i32,
+    height: i32,
+}
+
+impl From<&PhotoHashRow> for ClusterMember {
+    fn from(r: &PhotoHashRow) -> Self {
+        Self {
+            r2_key: r.r2_key.clone(),
+            entity_type: r.entity_type.clone(),
+            entity_ref: r.entity_ref.clone(),
+            width: r.width,
+            height: r.height,
+        }
+    }
+}
+
+struct DuplicateCluster {
+    members: Vec<ClusterMember>,
+    cross_entity: bool,
+}
+
+#[derive(Template)]
+#[template(path = "admin_photo_duplicates.html")]
+struct AdminPhotoDuplicatesTemplate {
+    img: String,
+    clusters: Vec<DuplicateCluster>,
+    indexed_count: usize,
+    cluster_count: usize,
+    cross_entity_count: usize,
+    max_distance: u32,
+    showing_all: bool,
+}
+
+#[derive(serde::Deserialize, Default)]
+pub struct DuplicatesQuery {
+    /// `?all=1` also lists clusters whose members all belong to the same
+    /// entity (the same photo uploaded twice into one gallery).
+    #[serde(default)]
+    all: Option<String>,
+}
+
+fn noindex(html: String) -> Response {
+    let mut resp = Html(html).into_response();
+    resp.headers_mut().insert(
+        "X-Robots-Tag",
+        HeaderValue::from_static("noindex, nofollow"),
+    );
+    resp
+}
+
+/// GET /admin/photos/duplicates — duplicate clusters report.
+pub async fn admin_photo_duplicates(
+    State(state): State<AppState>,
+    axum::extract::Query(q): axum::extract::Query<DuplicatesQuery>,
+) -> WebResult<Response> {
+    let showing_all = matches!(q.all.as_deref(), Some("1") | Some("true"));
+    let max_distance = photo_hash::DEFAULT_MAX_DISTANCE;
+
+    let rows = state.photo_hashes.load_all().await?;
+    let hashes: Vec<u64> = rows.iter().map(PhotoHashRow::hash).collect();
+    let raw = tokio::task::spawn_blocking(move || photo_hash::find_clusters(&hashes, max_distance))
+        .await?;
+
+    let all_clusters: Vec<DuplicateCluster> = raw
+        .into_iter()
+        .map(|idxs| {
+            let first = &rows[idxs[0]];
+            let cross_entity = idxs.iter().any(|&i| {
+                rows[i].entity_type != first.entity_type || rows[i].entity_ref != first.entity_ref
+            });
+            DuplicateCluster {
+                members: idxs
+                    .iter()
+                    .map(|&i| ClusterMember::from(&rows[i]))
+                    .collect(),
+                cross_entity,
+            }
+        })
+        .collect();
+
+    let cluster_count = all_clusters.len();
+    let cross_entity_count = all_clusters.iter().filter(|c| c.cross_entity).count();
+    let clusters = all_clusters
+        .into_iter()
+        .filter(|c| showing_all || c.cross_entity)
+        .take(MAX_CLUSTERS)
+        .collect();
+
+    let tmpl = AdminPhotoDuplicatesTemplate {
+        img: state.image_base_url.clone(),
+        clusters,
+        indexed_count: rows.len(),
+        cluster_count,
+        cross_entity_count,
+        max_distance,
+        showing_all,
+    };
+    Ok(noindex(tmpl.render()?))
+}
@@ -0,0 +1,168 @@
+//! Admin view of the video download queue.
+//!
+//! Routes:
+//!   GET /admin/video-queue/  — jobs per state and the most recent jobs
+//!
+//! Source data: `video_download_jobs` (migration 088), written by
+//! `/api/video/prepare` and the workers in `handlers::video_api`. Finished
+//! jobs disappear with the temp-file reaper: ready ones after 30 minutes,
+//! failed ones after a day.
+
+use askama::Template;
+use axum::extract::State;
+use axum::http::HeaderValue;
+use axum::response::{Html, IntoResponse, Response};
+use cr_domain::repository::VideoJobRepository;
+use cr_domain::video_job::{MAX_ATTEMPTS, VideoJob, VideoJobState};
+
+use crate::error::WebResult;
+use crate::handlers::video_api::{STALE_AFTER, VIDEO_DOWNLOAD_WORKERS, round_mb};
+use crate::state::AppState;
+
+/// Jobs listed below the counters.
+const LISTED_JOBS: i64 = 200;
+
+struct StateCount {
+    label: &'static str,
+    css_class: &'static str,
+    count: i64,
+}
+
+struct JobRow {
+    token: String,
+    label: &'static str,
+    css_class: &'static str,
+    progress: u8,
+    attempts: i32,
+    source_url: String,
+    filename: String,
+    /// `720p mp4`, `WhatsApp`, `knihovna`
+    variant: String,
+    size_mb: Option<f64>,
+    error: Option<String>,
+    worker: Option<String>,
+    queue_position: i64,
+    created_at: String,
+    updated_at: String,
+    next_attempt_at: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "admin_video_queue.html")]
+struct AdminVideoQueueTemplate {
+    img: String,
+    counts: Vec<StateCount>,
+    jobs: Vec<JobRow>,
+    workers: usize,
+    max_attempts: i32,
+    stale_after_secs: u64,
+}
+
+fn label(state: VideoJobState) -> (&'static str, &'static str) {
+    match state {
+        VideoJobState::Queued => ("Ve frontě", "status-queued"),
+        VideoJobState::Downloading => ("Stahuje se", "status-running"),
+        VideoJobState::Converting => ("Konvertuje se", "status-running"),
+        VideoJobState::Ready => ("Hotovo", "status-ok"),
+        VideoJobState::ReadyParts => ("Hotovo (části)", "status-ok"),
+        VideoJobState::Failed => ("Selhalo", "status-error"),
+    }
+}
+
+/// `2026-10-19T08:00:00.123+00:00` → `2026-10-19 08:00:00`
+fn short_time(rfc3339: &str) -> String {
+    rfc3339.get(..19).unwrap_or(rfc3339).replace('T', " ")
+}
+
+impl From<VideoJob> for JobRow {
+    fn from(job: VideoJob) -> Self {
+        let (label, css_class) = label(job.state);
+        let variant = if job.library_id.is_some() {
+            "knihovna".to_string()
+        } else if let Some(target) = job.params.export {
+            target.preset().label.to_string()
+        } else if let Some(animation) = job.params.animation {
+            format!(
+                "{} {} fps {} px",
+                animation.format.as_str().to_uppercase(),
+                animation.fps,
+                animation.width
+            )
+        } else if let Some(audio) = job.params.audio {
+            let format = audio.format.as_str().to_uppercase();
+            match audio.bitrate_kbps {
+                Some(kbps) => format!("{format} {kbps} kbps"),
+                None => format,
+            }
+        } else {
+            format!("{} {}", job.params.format_id, job.params.container)
+        };
+        let variant = match job.params.clip {
+            Some(clip) => format!("{variant}, {}", clip.label()),
+            None => variant,
+        };
+        let variant = match &job.params.subtitles {
+            Some(subs) if subs.burn => format!("{variant}, titulky {} (vypálené)", subs.lang),
+            Some(subs) => format!("{variant}, titulky {}", subs.lang),
+            None => variant,
+        };
+        let size_mb = job.size_bytes.map(round_mb);
+        let next_attempt_at = (job.state == VideoJobState::Queued && job.attempts > 0)
+            .then(|| short_time(&job.next_attempt_at));
+        Self {
+            token: job.params.token,
+            label,
+            css_class,
+            progress: job.progress,
+            attempts: job.attempts,
+            source_url: job.params.source_url,
+            filename: job.filename,
+            variant,
+            size_mb,
+            error: job.error,
+            worker: job.worker,
+            queue_position: job.queue_position,
+            created_at: short_time(&job.created_at),
+            updated_at: short_time(&job.updated_at),
+            next_attempt_at,
+        }
+    }
+}
+
+fn noindex(html: String) -> Response {
+    let mut resp = Html(html).into_response();
+    resp.headers_mut().insert(
+        "X-Robots-Tag",
+        HeaderValue::from_static("noindex, nofollow"),
+    );
+    resp
+}
+
+/// GET /admin/video-queue/
+pub async fn admin_video_queue(State(state): State<AppState>) -> WebResult<Response> {
+    let jobs = &state.video_queue.jobs;
+    let counts = jobs
+        .state_counts()
+        .await?
+        .into_iter()
+        .map(|(s, count)| {
+            let (label, css_class) = label(s);
+            StateCount {
+                label,
+                css_class,
+                count,
+            }
+        })
+        .collect();
+    let listed = jobs.list_recent(LISTED_JOBS).await?;
+
+    let tmpl = AdminVideoQueueTemplate {
+        img: state.image_base_url.clone(),
+        counts,
+        jobs: listed.into_iter().map(JobRow::from).collect(),
+        workers: VIDEO_DOWNLOAD_WORKERS,
+        max_attempts: MAX_ATTEMPTS,
+        stale_after_secs: STALE_AFTER.as_secs(),
+    };
+    Ok(noindex(tmpl.render()?))
+}
@@ -0,0 +1,116 @@
+//! Episode release calendar.
+//!
+//!     /serialy-online/kalendar/            — this week's and upcoming episodes
+//!     /serialy-online/{slug}/kalendar.ics  — one series as an iCalendar feed
+//!
+//! Air dates are TMDB's (see scripts/sync-episode-air-dates.py), so the
+//! calendar lists announced episodes whether or not they are playable yet;
+//! only playable ones link to an episode page. "Today" is the server's
+//! local date.
+
+use askama::Template;
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{Html, IntoResponse, Response};
+use chrono::Datelike;
+use cr_app::calendar::{
+    CalendarDay, ICS_LEN, ICS_PAST_DAYS, SERIES_UPCOMING_LEN, UPCOMING_WEEKS, group_by_day,
+    render_ics,
+};
+use cr_domain::Date;
+use cr_domain::dto::{CalendarEpisode, SeriesRecord};
+use cr_domain::repository::{EpisodeCalendarRepository, SeriesRepository};
+
+use crate::error::{WebError, WebResult};
+use crate::handlers::SITE_ORIGIN;
+use crate::state::AppState;
+
+/// Air dates change a few times a day at most.
+const CALENDAR_CACHE_CONTROL: &str = "public, max-age=3600";
+
+#[derive(Template)]
+#[template(path = "episode_calendar.html")]
+struct EpisodeCalendarTemplate {
+    img: String,
+    week: Vec<CalendarDay>,
+    upcoming: Vec<CalendarDay>,
+}
+
+/// The server's local date.
+pub(crate) fn today() -> Date {
+    let now = chrono::Local::now().date_naive();
+    Date::new(now.year(), now.month() as u8, now.day() as u8).expect("chrono dates are valid")
+}
+
+/// GET /serialy-online/kalendar/
+pub async fn episode_calendar(State(state): State<AppState>) -> WebResult<Response> {
+    let today = today();
+    let week_start = today.week_start();
+    let week_end = week_start.add_days(6);
+    let horizon = week_end.add_days(7 * UPCOMING_WEEKS);
+    let entries = state.calendar_repo.between(week_start, horizon).await?;
+
+    let tmpl = EpisodeCalendarTemplate {
+        img: state.image_base_url.clone(),
+        week: group_by_day(&entries, week_start, week_end, today, true),
+        upcoming: group_by_day(&entries, week_end.add_days(1), horizon, today, false),
+    };
+    Ok((
+        [(header::CACHE_CONTROL, CALENDAR_CACHE_CONTROL)],
+        Html(tmpl.render()?),
+    )
+        .into_response())
+}
+
+/// Episodes for the "Připravované epizody" block of a series page. Never
+/// fails the page; the block just disappears.
+pub(crate) async fn series_upcoming(
+    state: &AppState,
+    series: &SeriesRecord,
+) -> Vec<CalendarEpisode> {
+    state
+        .calendar_repo
+        .series_schedule(series.id, today(), SERIES_UPCOMING_LEN)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!(series_id = series.id, error = ?e, "series schedule query failed");
+            Vec::new()
+        })
+}
+
+/// GET /serialy-online/{slug}/kalendar.ics
+pub async fn series_ics(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> WebResult<Response> {
+    let series = state
+        .series_repo
+        .find_by_slug(&slug)
+        .await?
+        .ok_or_else(|| WebError::not_found("Seriál nenalezen"))?;
+    let entries = state
+        .calendar_repo
+        .series_schedule(series.id, today().add_days(-ICS_PAST_DAYS), ICS_LEN)
+        .await?;
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let body = render_ics(
+        &format!("{} — epizody", series.title),
+        &entries,
+        SITE_ORIGIN,
+        &stamp,
+    );
+    let disposition = format!("inline; filename=\"{}.ics\"", series.slug);
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/calendar; charset=utf-8".to_string(),
+            ),
+            (header::CACHE_CONTROL, CALENDAR_CACHE_CONTROL.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
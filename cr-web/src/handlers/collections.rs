@@ -0,0 +1,132 @@
+//! Film collections (franchises, sequel series).
+//!
+//!     /kolekce/{slug}/  — the collection's films in release order
+//!
+//! Film detail pages show a "Součást kolekce" block with previous/next
+//! links built by [`collection_nav`]. Both only list films with a live
+//! video source, the same as the film listing; data comes from
+//! `import-collections` (migration 083).
+
+use askama::Template;
+use axum::extract::{Path, State};
+use axum::response::{Html, IntoResponse, Response};
+use cr_domain::catalog::collection_neighbours;
+use cr_domain::dto::{CollectionFilm, FilmCollection};
+use cr_domain::repository::FilmCollectionRepository;
+
+use crate::error::{WebError, WebResult};
+use crate::state::AppState;
+
+#[derive(Template)]
+#[template(path = "collection_detail.html")]
+struct CollectionDetailTemplate {
+    img: String,
+    collection: FilmCollection,
+    films: Vec<CollectionFilm>,
+    /// "1979–2017" or a single year; empty when no film has a year.
+    years: String,
+}
+
+/// "Součást kolekce" block on a film detail page.
+pub(crate) struct CollectionNav {
+    pub collection: FilmCollection,
+    /// Part number of the current film within the whole collection.
+    pub position: i16,
+    /// Films of the collection available here, the current one included.
+    pub film_count: usize,
+    pub prev: Option<CollectionFilm>,
+    pub next: Option<CollectionFilm>,
+}
+
+/// GET /kolekce/{slug}/
+pub async fn collection_detail(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> WebResult<Response> {
+    let collection = state
+        .collection_repo
+        .find_by_slug(&slug)
+        .await?
+        .ok_or_else(|| WebError::not_found("Kolekce nenalezena"))?;
+    let films = state.collection_repo.films(collection.id).await?;
+    // Every film lost its source: nothing to show, same as a dead film.
+    if films.is_empty() {
+        return Err(WebError::not_found("Kolekce nenalezena"));
+    }
+    let years = year_span(&films);
+
+    let tmpl = CollectionDetailTemplate {
+        img: state.image_base_url.clone(),
+        collection,
+        films,
+        years,
+    };
+    Ok(Html(tmpl.render()?).into_response())
+}
+
+/// Collection block for the film detail page. `None` when the film isn't
+/// in a collection, is the only playable film of it, or the lookup fails
+/// (logged — the detail page renders without the block).
+pub(crate) async fn collection_nav(state: &AppState, film_id: i32) -> Option<CollectionNav> {
+    let repo = state.collection_repo.as_ref();
+    let lookup = async {
+        let Some(collection) = repo.of_film(film_id).await? else {
+            return Ok(None);
+        };
+        let films = repo.films(collection.id).await?;
+        Ok::<_, sqlx::Error>(Some((collection, films)))
+    };
+    let (collection, films) = match lookup.await {
+        Ok(found) => found?,
+        Err(e) => {
+            tracing::error!(film_id, error = ?e, "film collection query failed");
+            return None;
+        }
+    };
+    let position = films.iter().find(|f| f.id == film_id)?.position;
+    if films.len() < 2 {
+        return None;
+    }
+    let (prev, next) = collection_neighbours(&films, film_id);
+    Some(CollectionNav {
+        collection,
+        position,
+        film_count: films.len(),
+        prev,
+        next,
+    })
+}
+
+fn year_span(films: &[CollectionFilm]) -> String {
+    let years = films.iter().filter_map(|f| f.year);
+    match (years.clone().min(), years.max()) {
+        (Some(first), Some(last)) if first != last => format!("{first}–{last}"),
+        (Some(year), _) => year.to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn film(id: i32, year: Option<i16>) -> CollectionFilm {
+        CollectionFilm {
+            id,
+            slug: format!("film-{id}"),
+            title: format!("Film {id}"),
+            year,
+            position: id as i16,
+            tmdb_rating: None,
+            imdb_rating: None,
+        }
+    }
+
+    #[test]
+    fn year_span_covers_first_to_last_release() {
+        let films = [film(1, Some(1986)), film(2, None), film(3, Some(1979))];
+        assert_eq!(year_span(&films), "1979–1986");
+        assert_eq!(year_span(&[film(1, Some(2001))]), "2001");
+        assert_eq!(year_span(&[film(1, None)]), "");
+    }
+}
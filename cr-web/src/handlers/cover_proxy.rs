@@ -1,6 +1,7 @@
 //! Shared helpers for the `/filmy-online/{slug}.webp`,
 //! `/serialy-online/{slug}.webp` and `/tv-porady/{slug}.webp` cover
-//! routes (plus their `-large` variants).
+//! routes (plus their `-large` variants). The routing and fallback chain
+//! itself lives in `handlers::media_cover`.
 //!
 //! Covers live on R2 under an id-keyed layout:
 //!
@@ -37,6 +38,22 @@ pub fn immutable_webp(bytes: Vec<u8>) -> Response {
         .into_response()
 }
 
+/// `image/webp` bytes with a one-hour cache and no `immutable`, for covers
+/// we generated ourselves from the TMDB poster. The next import replaces
+/// them with the real file under the same URL, so browsers and CF must
+/// come back for it.
+pub fn generated_webp(bytes: Vec<u8>) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "image/webp"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
 /// `image/webp` bytes wrapped with `no-store`. Used when we serve
 /// *something* under a URL that may get a better answer soon — e.g.
 /// the `-large.webp` endpoint falling back to the small variant while
@@ -180,18 +197,6 @@ pub async fn fetch_cover(state: &AppState, key: &str) -> Response {
     placeholder_webp()
 }
 
-/// Strip the `.webp` / `-large.webp` suffix from an incoming path param
-/// and return `(slug, is_large)`.
-pub fn parse_cover_slug(slug_webp: &str) -> (String, bool) {
-    if let Some(s) = slug_webp.strip_suffix("-large.webp") {
-        (s.to_string(), true)
-    } else if let Some(s) = slug_webp.strip_suffix(".webp") {
-        (s.to_string(), false)
-    } else {
-        (slug_webp.to_string(), false)
-    }
-}
-
 /// Returns the R2 key for a cover given `table_prefix`, `id`, and
 /// `is_large`. `table_prefix` is the top-level R2 prefix
 /// (`films`, `series`, `tv-shows`).
@@ -200,6 +205,15 @@ pub fn new_r2_key(table_prefix: &str, id: i32, is_large: bool) -> String {
     format!("{table_prefix}/{id}/{variant}.webp")
 }
 
+/// R2 key of the cover built from the TMDB poster while the importer's
+/// cover is missing — next to [`new_r2_key`], never in its place, so a
+/// stopgap can't be mistaken for (and cached like) the real cover. The
+/// importer deletes it once it uploads the real one.
+pub fn stopgap_r2_key(table_prefix: &str, id: i32, is_large: bool) -> String {
+    let variant = if is_large { "cover-large" } else { "cover" };
+    format!("{table_prefix}/{id}/{variant}-stopgap.webp")
+}
+
 /// Person profile photo proxy. Takes a `p{tmdb_id}.webp` filename,
 /// strips/validates the `p` prefix and `.webp` suffix, fetches
 /// `cr-images:people/{tmdb_id}.webp` from R2, and returns the bytes
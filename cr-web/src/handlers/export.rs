@@ -0,0 +1,254 @@
+//! Open-data exports for downstream consumers such as csfd-data-hub.
+//!
+//!     /api/export                    — manifest: datasets, columns, schema versions
+//!     /api/export/{dataset}          — full dump as NDJSON (default) or `?format=csv`
+//!     /api/export/{dataset}?since=T  — only rows changed after T
+//!
+//! Datasets are `films`, `series`, `tv_shows`, `people`, `municipalities`,
+//! `landmarks` and `pools`; their columns are defined in
+//! `cr_domain::export`. Rows come oldest change first and are streamed
+//! page by page straight from the database, so a full dump never sits in
+//! memory.
+//!
+//! Incremental sync: every response carries `X-Export-Next-Since`; pass it
+//! back as `since=` on the next pull. It trails the database clock by
+//! [`SINCE_OVERLAP`] so rows committed by transactions still running at
+//! the time aren't missed — the overlap means some rows arrive twice, so
+//! consumers upsert by `id`. An interrupted pull resumes with the last
+//! row's `since=<updated_at>&after_id=<id>`. Deletions are not tracked; a
+//! periodic full pull drops them.
+//!
+//! `/api/csfd-watchlist.json` predates this and stays for its existing consumer.
+
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, SecondsFormat, Utc};
+use cr_app::export::{EXPORT_PAGE_SIZE, ExportFormat, KEY_COLUMNS, write_record};
+use cr_domain::export::{ExportCursor, ExportDataset};
+use cr_domain::repository::ExportRepository;
+use serde::Deserialize;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::error::{WebError, WebResult};
+use crate::handlers::SITE_ORIGIN;
+use crate::state::AppState;
+
+/// How far `X-Export-Next-Since` trails the database clock. Longer than
+/// any import transaction runs, so their rows are still ahead of the
+/// cursor once they commit.
+const SINCE_OVERLAP: Duration = Duration::from_secs(15 * 60);
+
+/// Consumers sync hourly or daily; ten minutes lets the edge absorb
+/// accidental hammering without serving noticeably stale data.
+const EXPORT_CACHE_CONTROL: &str = "public, max-age=600";
+
+/// Pages buffered ahead of a slow client.
+const STREAM_BUFFER_PAGES: usize = 2;
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    format: Option<String>,
+    /// RFC 3339; only rows changed after this.
+    since: Option<String>,
+    /// With `since=`, resume after this row of that instant.
+    after_id: Option<i64>,
+}
+
+impl ExportQuery {
+    fn cursor(&self) -> WebResult<Option<ExportCursor>> {
+        let Some(since) = self.since.as_deref().filter(|s| !s.is_empty()) else {
+            return match self.after_id {
+                Some(_) => Err(WebError::bad_request("after_id requires since")),
+                None => Ok(None),
+            };
+        };
+        let since = DateTime::parse_from_rfc3339(since)
+            .map_err(|_| WebError::bad_request("since must be an RFC 3339 timestamp"))?
+            .with_timezone(&Utc);
+        Ok(Some(ExportCursor {
+            updated_at: since.to_rfc3339_opts(SecondsFormat::Micros, true),
+            // Without `after_id`, every row of the `since` instant itself
+            // is already behind the cursor.
+            id: self.after_id.unwrap_or(i64::MAX),
+        }))
+    }
+}
+
+/// `now` (RFC 3339) minus [`SINCE_OVERLAP`].
+fn next_since(now: &str) -> WebResult<String> {
+    let now = DateTime::parse_from_rfc3339(now)
+        .map_err(|e| WebError::Internal(anyhow::anyhow!("database clock: {e}")))?;
+    let overlap = chrono::Duration::from_std(SINCE_OVERLAP).unwrap_or_default();
+    Ok((now.with_timezone(&Utc) - overlap).to_rfc3339_opts(SecondsFormat::Micros, true))
+}
+
+/// GET /api/export — what can be exported and in which shape.
+pub async fn export_manifest(State(state): State<AppState>) -> WebResult<Response> {
+    let generated_at = state.export_repo.now().await?;
+    let datasets: Vec<serde_json::Value> = ExportDataset::ALL
+        .into_iter()
+        .map(|dataset| {
+            let columns: Vec<serde_json::Value> = dataset
+                .columns()
+                .iter()
+                .map(|c| {
+                    serde_json::json!({
+                        "name": c.name,
+                        "type": c.ty.key(),
+                        "description": c.description,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "name": dataset.key(),
+                "url": format!("{SITE_ORIGIN}/api/export/{}", dataset.key()),
+                "schema_version": dataset.schema_version(),
+                "key_columns": KEY_COLUMNS,
+                "columns": columns,
+            })
+        })
+        .collect();
+    let formats: Vec<&str> = ExportFormat::ALL.iter().map(|f| f.key()).collect();
+    let body = serde_json::json!({
+        "generated_at": generated_at,
+        "formats": formats,
+        "incremental": {
+            "cursor_header": "X-Export-Next-Since",
+            "params": ["since", "after_id"],
+            "overlap_seconds": SINCE_OVERLAP.as_secs(),
+            "deletes_tracked": false,
+        },
+        "datasets": datasets,
+    });
+    Ok((
+        [
+            (header::CACHE_CONTROL, EXPORT_CACHE_CONTROL),
+            (header::CONTENT_TYPE, "application/json; charset=utf-8"),
+        ],
+        body.to_string(),
+    )
+        .into_response())
+}
+
+/// GET /api/export/{dataset}
+pub async fn export_dataset(
+    State(state): State<AppState>,
+    Path(dataset): Path<String>,
+    Query(params): Query<ExportQuery>,
+) -> WebResult<Response> {
+    let dataset = ExportDataset::from_key(&dataset)
+        .ok_or_else(|| WebError::not_found("Neznámá datová sada"))?;
+    let format = ExportFormat::from_key(params.format.as_deref())
+        .ok_or_else(|| WebError::bad_request("format must be ndjson or csv"))?;
+    let cursor = params.cursor()?;
+    // Taken before the first page, so nothing committed later is behind it.
+    let next_since = next_since(&state.export_repo.now().await?)?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Bytes, std::io::Error>>(STREAM_BUFFER_PAGES);
+    let repo = state.export_repo.clone();
+    tokio::spawn(async move {
+        let preamble = format.preamble(dataset);
+        if !preamble.is_empty() && tx.send(Ok(Bytes::from(preamble))).await.is_err() {
+            return;
+        }
+        let mut cursor = cursor;
+        loop {
+            let rows = match repo
+                .export_page(dataset, cursor.as_ref(), EXPORT_PAGE_SIZE)
+                .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    // Headers are out already; cutting the body short is
+                    // the only way left to tell the client.
+                    tracing::warn!("export {}: page failed: {e:?}", dataset.key());
+                    let _ = tx.send(Err(std::io::Error::other("export failed"))).await;
+                    return;
+                }
+            };
+            let mut chunk = String::with_capacity(rows.len() * 256);
+            for row in &rows {
+                write_record(format, dataset, row, &mut chunk);
+            }
+            if tx.send(Ok(Bytes::from(chunk))).await.is_err() {
+                return; // client went away
+            }
+            match rows.last() {
+                Some(last) if rows.len() as i64 == EXPORT_PAGE_SIZE => {
+                    cursor = Some(ExportCursor {
+                        updated_at: last.updated_at.clone(),
+                        id: last.id,
+                    });
+                }
+                _ => return,
+            }
+        }
+    });
+
+    let mut resp = Body::from_stream(ReceiverStream::new(rx)).into_response();
+    let headers = resp.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static(format.content_type()),
+    );
+    headers.insert(
+        header::CACHE_CONTROL,
+        header::HeaderValue::from_static(EXPORT_CACHE_CONTROL),
+    );
+    if let Ok(value) = header::HeaderValue::from_str(&next_since) {
+        headers.insert("X-Export-Next-Since", value);
+    }
+    if let Ok(value) = header::HeaderValue::from_str(&format!(
+        "inline; filename=\"{}.{}\"",
+        dataset.key(),
+        format.key()
+    )) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(since: Option<&str>, after_id: Option<i64>) -> Result<Option<ExportCursor>, ()> {
+        ExportQuery {
+            format: None,
+            since: since.map(str::to_string),
+            after_id,
+        }
+        .cursor()
+        .map_err(|_| ())
+    }
+
+    #[test]
+    fn since_becomes_a_cursor_past_that_instant() {
+        let c = cursor(Some("2026-10-01T10:00:00+02:00"), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(c.updated_at, "2026-10-01T08:00:00.000000Z");
+        assert_eq!(c.id, i64::MAX);
+
+        let c = cursor(Some("2026-10-01T08:00:00Z"), Some(42))
+            .unwrap()
+            .unwrap();
+        assert_eq!(c.id, 42);
+
+        assert_eq!(cursor(None, None), Ok(None));
+        assert!(cursor(Some("yesterday"), None).is_err());
+        assert!(cursor(None, Some(42)).is_err());
+    }
+
+    #[test]
+    fn next_since_trails_the_clock() {
+        assert_eq!(
+            next_since("2026-10-01T08:00:00.000001Z").ok().as_deref(),
+            Some("2026-10-01T07:45:00.000001Z")
+        );
+    }
+}
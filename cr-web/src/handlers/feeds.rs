@@ -0,0 +1,325 @@
+//! Atom/RSS feeds (migration 084).
+//!
+//!     /filmy-online/feed.xml            — newly added films
+//!     /filmy-online/{genre}/feed.xml    — the same, within a genre
+//!     /serialy-online/feed.xml          — new episodes across all series
+//!     /serialy-online/{slug}/feed.xml   — genre, or one series' episodes
+//!     /tv-porady/feed.xml               — new TV pořad episodes
+//!     /pamatky/feed.xml?typ=&kraj=&orp= — new and rewritten landmarks
+//!     /obce/feed.xml?kraj=&orp=         — new and rewritten municipalities
+//!
+//! The catalog feeds take the list pages' genre, year and language
+//! parameters (`?zanry=komedie&audio=cs` is "new Czech-dubbed comedies");
+//! the list pages link their feed with the same filters. Atom by default,
+//! `?format=rss` for RSS 2.0.
+
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use cr_app::feed::{FEED_LEN, Feed, FeedFormat};
+use cr_domain::repository::{FeedRepository, FilmRepository, PlaceFeedFilter, SeriesRepository};
+use serde::Deserialize;
+
+use super::films::FilmsQuery;
+use super::series::SeriesQuery;
+use crate::error::{WebError, WebResult};
+use crate::handlers::SITE_ORIGIN;
+use crate::state::AppState;
+
+/// Readers poll every few minutes; new titles arrive in batches a few
+/// times a day.
+const FEED_CACHE_CONTROL: &str = "public, max-age=900";
+
+/// `?format=`, read alongside each feed's filter parameters.
+#[derive(Deserialize)]
+pub struct FormatQuery {
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PlaceFeedQuery {
+    /// Landmark type as in the URLs (`hrady`, `rozhledny`).
+    typ: Option<String>,
+    kraj: Option<String>,
+    orp: Option<String>,
+}
+
+impl PlaceFeedQuery {
+    fn non_empty(value: &Option<String>) -> Option<String> {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    }
+
+    /// `None` when `typ=` names no landmark type.
+    fn to_filter(&self) -> Option<PlaceFeedFilter> {
+        let landmark_type = match Self::non_empty(&self.typ) {
+            Some(typ) => Some(super::url_slug_to_type_slug(&typ)?.to_string()),
+            None => None,
+        };
+        Some(PlaceFeedFilter {
+            landmark_type,
+            ..self.area_filter()
+        })
+    }
+
+    /// `kraj=` and `orp=` only, whatever `typ=` says.
+    fn area_filter(&self) -> PlaceFeedFilter {
+        PlaceFeedFilter {
+            region_slug: Self::non_empty(&self.kraj),
+            orp_slug: Self::non_empty(&self.orp),
+            landmark_type: None,
+        }
+    }
+
+    fn query_string(&self, with_type: bool) -> String {
+        let mut parts = Vec::new();
+        if with_type {
+            parts.push(("typ", Self::non_empty(&self.typ).unwrap_or_default()));
+        }
+        parts.push(("kraj", Self::non_empty(&self.kraj).unwrap_or_default()));
+        parts.push(("orp", Self::non_empty(&self.orp).unwrap_or_default()));
+        feed_query_string(&parts)
+    }
+}
+
+/// `?a=1&b=2` from the non-empty `parts`, or an empty string.
+pub(crate) fn feed_query_string(parts: &[(&str, String)]) -> String {
+    match super::build_pagination_qs(parts).strip_prefix('&') {
+        Some(qs) => format!("?{qs}"),
+        None => String::new(),
+    }
+}
+
+/// Render `feed`; `self_path` gains `format=rss` for RSS so the feed's
+/// self link points at itself.
+fn feed_response(mut feed: Feed, format: &FormatQuery) -> Response {
+    let format = FeedFormat::from_key(format.format.as_deref());
+    if format == FeedFormat::Rss {
+        let sep = if feed.self_path.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        feed.self_path = format!("{}{sep}format=rss", feed.self_path);
+    }
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type()),
+            (header::CACHE_CONTROL, FEED_CACHE_CONTROL),
+        ],
+        feed.render(format, SITE_ORIGIN),
+    )
+        .into_response()
+}
+
+/// GET /filmy-online/feed.xml
+pub async fn films_feed(
+    State(state): State<AppState>,
+    Query(params): Query<FilmsQuery>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    let qs = params.feed_query_string();
+    let entries = state
+        .feed_repo
+        .films(&params.to_feed_query(), FEED_LEN)
+        .await?;
+    let feed = Feed {
+        title: "Nové filmy online — ceskarepublika.wiki".to_string(),
+        subtitle: "Nově přidané filmy ke zhlédnutí online".to_string(),
+        self_path: format!("/filmy-online/feed.xml{qs}"),
+        alternate_path: format!("/filmy-online/{qs}"),
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+/// GET /filmy-online/{genre}/feed.xml
+pub async fn film_genre_feed(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<FilmsQuery>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    let genre = state
+        .film_repo
+        .find_genre(&slug)
+        .await?
+        .ok_or_else(|| WebError::not_found("Žánr nenalezen"))?;
+    let mut query = params.to_feed_query();
+    query.filter.genres.require(&genre.slug);
+    let qs = params.feed_query_string();
+    let entries = state.feed_repo.films(&query, FEED_LEN).await?;
+    let feed = Feed {
+        title: format!("Nové filmy: {} — ceskarepublika.wiki", genre.name_cs),
+        subtitle: format!("Nově přidané filmy v žánru {}", genre.name_cs),
+        self_path: format!("/filmy-online/{}/feed.xml{qs}", genre.slug),
+        alternate_path: format!("/filmy-online/{}/{qs}", genre.slug),
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+/// GET /serialy-online/feed.xml
+pub async fn series_feed(
+    State(state): State<AppState>,
+    Query(params): Query<SeriesQuery>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    let qs = params.feed_query_string();
+    let entries = state
+        .feed_repo
+        .episodes(&params.to_feed_query(), FEED_LEN)
+        .await?;
+    let feed = Feed {
+        title: "Nové epizody seriálů — ceskarepublika.wiki".to_string(),
+        subtitle: "Nově přidané epizody seriálů ke zhlédnutí online".to_string(),
+        self_path: format!("/serialy-online/feed.xml{qs}"),
+        alternate_path: format!("/serialy-online/{qs}"),
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+/// GET /serialy-online/{slug}/feed.xml — a genre's new episodes, or one
+/// series' episodes; resolved in the same order as the HTML page.
+pub async fn series_slug_feed(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<SeriesQuery>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    if let Some(genre) = state.series_repo.find_genre(&slug).await? {
+        let mut query = params.to_feed_query();
+        query.filter.genres.require(&genre.slug);
+        let qs = params.feed_query_string();
+        let entries = state.feed_repo.episodes(&query, FEED_LEN).await?;
+        let feed = Feed {
+            title: format!("Nové epizody: {} — ceskarepublika.wiki", genre.name_cs),
+            subtitle: format!("Nově přidané epizody seriálů v žánru {}", genre.name_cs),
+            self_path: format!("/serialy-online/{}/feed.xml{qs}", genre.slug),
+            alternate_path: format!("/serialy-online/{}/{qs}", genre.slug),
+            entries,
+        };
+        return Ok(feed_response(feed, &format));
+    }
+
+    let series = state
+        .series_repo
+        .find_by_slug(&slug)
+        .await?
+        .ok_or_else(|| WebError::not_found("Seriál nenalezen"))?;
+    let entries = state.feed_repo.series_episodes(series.id, FEED_LEN).await?;
+    let feed = Feed {
+        title: format!("{} — nové epizody", series.title),
+        subtitle: format!("Nově přidané epizody seriálu {}", series.title),
+        self_path: format!("/serialy-online/{}/feed.xml", series.slug),
+        alternate_path: format!("/serialy-online/{}/", series.slug),
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+/// GET /tv-porady/feed.xml
+pub async fn tv_porady_feed(
+    State(state): State<AppState>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    let entries = state.feed_repo.tv_episodes(FEED_LEN).await?;
+    let feed = Feed {
+        title: "Nové epizody TV pořadů — ceskarepublika.wiki".to_string(),
+        subtitle: "Nově přidané epizody TV pořadů ke zhlédnutí online".to_string(),
+        self_path: "/tv-porady/feed.xml".to_string(),
+        alternate_path: "/tv-porady/".to_string(),
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+/// GET /pamatky/feed.xml
+pub async fn landmarks_feed(
+    State(state): State<AppState>,
+    Query(params): Query<PlaceFeedQuery>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    let filter = params
+        .to_filter()
+        .ok_or_else(|| WebError::not_found("Neznámý typ památky"))?;
+    let entries = state.feed_repo.landmarks(&filter, FEED_LEN).await?;
+    let feed = Feed {
+        title: "Nové a aktualizované památky — ceskarepublika.wiki".to_string(),
+        subtitle: "Nově přidané a přepsané stránky památek".to_string(),
+        self_path: format!("/pamatky/feed.xml{}", params.query_string(true)),
+        alternate_path: "/pamatky/".to_string(),
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+/// GET /obce/feed.xml
+pub async fn municipalities_feed(
+    State(state): State<AppState>,
+    Query(params): Query<PlaceFeedQuery>,
+    Query(format): Query<FormatQuery>,
+) -> WebResult<Response> {
+    // `typ=` means nothing for municipalities; dropped rather than 404.
+    let filter = params.area_filter();
+    let alternate_path = match (&filter.orp_slug, &filter.region_slug) {
+        (Some(orp), _) => format!("/{orp}/"),
+        (None, Some(region)) => format!("/{region}/"),
+        (None, None) => "/".to_string(),
+    };
+    let entries = state.feed_repo.municipalities(&filter, FEED_LEN).await?;
+    let feed = Feed {
+        title: "Nové a aktualizované obce — ceskarepublika.wiki".to_string(),
+        subtitle: "Nově přidané a přepsané stránky obcí".to_string(),
+        self_path: format!("/obce/feed.xml{}", params.query_string(false)),
+        alternate_path,
+        entries,
+    };
+    Ok(feed_response(feed, &format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place_query(typ: Option<&str>) -> PlaceFeedQuery {
+        PlaceFeedQuery {
+            typ: typ.map(str::to_string),
+            kraj: Some(" jihocesky-kraj ".to_string()),
+            orp: Some(String::new()),
+        }
+    }
+
+    #[test]
+    fn place_query_maps_url_type_slugs() {
+        let filter = place_query(Some("hrady")).to_filter().unwrap();
+        assert_eq!(filter.landmark_type.as_deref(), Some("castle"));
+        assert_eq!(filter.region_slug.as_deref(), Some("jihocesky-kraj"));
+        assert_eq!(filter.orp_slug, None);
+        assert!(place_query(Some("neco")).to_filter().is_none());
+        let area = place_query(Some("neco")).area_filter();
+        assert_eq!(area.region_slug.as_deref(), Some("jihocesky-kraj"));
+        assert_eq!(area.landmark_type, None);
+        assert_eq!(
+            place_query(Some("hrady")).query_string(true),
+            "?typ=hrady&kraj=jihocesky-kraj"
+        );
+        assert_eq!(
+            place_query(None).query_string(false),
+            "?kraj=jihocesky-kraj"
+        );
+    }
+
+    #[test]
+    fn feed_query_string_skips_empty_values() {
+        assert_eq!(feed_query_string(&[("zanry", String::new())]), "");
+        assert_eq!(
+            feed_query_string(&[("zanry", "komedie,drama".into()), ("audio", "cs".into())]),
+            "?zanry=komedie%2Cdrama&audio=cs"
+        );
+    }
+}
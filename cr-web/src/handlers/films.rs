@@ -1,149 +1,32 @@
+use super::collections::{CollectionNav, collection_nav};
+use super::media_cover::{self, MediaKind};
 use super::*;
+use cr_domain::catalog::{
+    CatalogKind, CatalogSort, FilmFilter, FilmListQuery, GenreFilter, LocalLanguage, Paging,
+    SortDirection, SubtitleFilter, TitleSearch, parse_lang_list, search_term,
+};
+use cr_domain::dto::{FilmRecord, GenreRecord, PersonCredit, SimilarTitle, TitleSuggestion};
+use cr_domain::repository::{FilmRepository, SimilarTitlesRepository};
 use serde::{Deserialize, Serialize};
 
 const FILMS_PER_PAGE: i64 = 24;
 
-/// SELECT column list for `FilmRow` queries. Kept as a const to avoid
-/// duplication across `films_list`, `films_by_genre`, and `films_detail`.
-///
-/// Each provider column is sourced from the unified `video_sources` table
-/// (#607 / #611 reader switch). The output shape is byte-for-byte identical
-/// to the pre-refactor legacy reads, so templates and downstream handlers
-/// don't need any change.
-///
-/// Correlated subqueries drive each of the four provider fields. They're
-/// cheap in practice: `idx_vs_film_alive` is a partial index keyed on
-/// `film_id`, and every subquery filters by `is_primary` which the partial
-/// unique indexes (`uq_vs_primary_film`) keep at ≤ 1 row per provider. The
-/// listing query hits 4 subqueries × 24 rows = ≤ 96 index lookups per page,
-/// which EXPLAIN ANALYZE puts in the low-single-digit millisecond range.
-///
-/// Notes per field:
-/// - `sktorrent_video_id` / `sktorrent_cdn`: both INT in legacy; the new
-///   `external_id` is TEXT and `cdn` is VARCHAR(32). Casts to INTEGER /
-///   SMALLINT reproduce the old types for FilmRow. sktorrent CDN is always
-///   a numeric string ("22"), so the cast is safe.
-/// - `sktorrent_qualities`: pulled from JSONB `metadata->>'qualities'`.
-///   Backfill + dual-write both write `{"qualities": "720p,480p"}`.
-/// - `prehrajto_url`: legacy `films.prehrajto_url` is a single cached URL;
-///   new schema stores N uploads per film. We pick the primary alive row
-///   (fallback: most recently updated alive row). URL is reconstructed from
-///   `metadata->>'url'` (dual-write writes this) or synthesised from
-///   `external_id` as a last resort.
-/// - `sledujteto_primary_file_id`: legacy column had a CDN-gate (only set
-///   when the primary upload's CDN is `www`, because data{N} is blocked
-///   from datacenter ASNs). Preserved here via `cdn = 'www'` predicate.
-const FILM_COLUMNS: &str = "f.id, f.title, f.slug, f.year, f.description, f.original_title, \
-    f.tmdb_rating, f.imdb_rating, f.csfd_rating, NULLIF(f.runtime_min, 0) AS runtime_min, \
-    f.added_at, f.tmdb_poster_path, \
-    (SELECT vs.external_id::INTEGER \
-       FROM video_sources vs \
-       JOIN video_providers p ON p.id = vs.provider_id \
-      WHERE vs.film_id = f.id AND p.slug = 'sktorrent' \
-        AND vs.is_primary AND vs.is_alive \
-      LIMIT 1) AS sktorrent_video_id, \
-    (SELECT vs.cdn::SMALLINT \
-       FROM video_sources vs \
-       JOIN video_providers p ON p.id = vs.provider_id \
-      WHERE vs.film_id = f.id AND p.slug = 'sktorrent' \
-        AND vs.is_primary AND vs.is_alive \
-      LIMIT 1) AS sktorrent_cdn, \
-    (SELECT vs.metadata->>'qualities' \
-       FROM video_sources vs \
-       JOIN video_providers p ON p.id = vs.provider_id \
-      WHERE vs.film_id = f.id AND p.slug = 'sktorrent' \
-        AND vs.is_primary AND vs.is_alive \
-      LIMIT 1) AS sktorrent_qualities, \
-    (SELECT COALESCE(vs.metadata->>'url', 'https://prehraj.to/' || vs.external_id) \
-       FROM video_sources vs \
-       JOIN video_providers p ON p.id = vs.provider_id \
-      WHERE vs.film_id = f.id AND p.slug = 'prehrajto' AND vs.is_alive \
-      ORDER BY vs.is_primary DESC, vs.updated_at DESC \
-      LIMIT 1) AS prehrajto_url, \
-    false AS prehrajto_has_dub, \
-    false AS prehrajto_has_subs, \
-    (SELECT vs.external_id::INTEGER \
-       FROM video_sources vs \
-       JOIN video_providers p ON p.id = vs.provider_id \
-      WHERE vs.film_id = f.id AND p.slug = 'sledujteto' \
-        AND vs.is_primary AND vs.is_alive AND vs.cdn = 'www' \
-      LIMIT 1) AS sledujteto_primary_file_id";
-
-// --- DB row types ---
-
-#[derive(sqlx::FromRow)]
-struct FilmRow {
+struct GenreRow {
     id: i32,
-    title: String,
     slug: String,
-    year: Option<i16>,
-    description: Option<String>,
-    original_title: Option<String>,
-    tmdb_rating: Option<f32>,
-    imdb_rating: Option<f32>,
-    csfd_rating: Option<i16>,
-    runtime_min: Option<i16>,
-    sktorrent_video_id: Option<i32>,
-    // `sktorrent_cdn` is used as a first-try hint by `sktorrent_resolve`
-    // (see docs/sktorrent-cdn-stability.md) — ~80 % of films still live on
-    // the node stored here, so the fast path usually resolves after probing
-    // 4 labels (720p/480p/HD/SD) instead of falling back to scanning up to
-    // 25 nodes × 4 labels = 100 HEAD requests. FilmRow itself doesn't read
-    // it; the resolver queries the column directly.
-    #[allow(dead_code)]
-    sktorrent_cdn: Option<i16>,
-    #[allow(dead_code)]
-    sktorrent_qualities: Option<String>,
-    #[allow(dead_code)] // Needed in SELECT for ORDER BY; not rendered in templates
-    added_at: Option<chrono::DateTime<chrono::Utc>>,
-    #[allow(dead_code)]
-    // Template reads sources via `video_sources_for_badges`; column stays for legacy scripts
-    prehrajto_url: Option<String>,
-    #[allow(dead_code)] // Not rendered in current templates; kept for future dub/sub badges
-    prehrajto_has_dub: bool,
-    #[allow(dead_code)] // Not rendered in current templates; kept for future dub/sub badges
-    prehrajto_has_subs: bool,
-    /// TMDB poster_path (e.g. `/mqlg…uJ.jpg`), backfilled by
-    /// `scripts/backfill-tmdb-poster-paths.py`. When set, the large-cover
-    /// URL switches to the extension the path ends with (`.jpg`/`.png`) and
-    /// `films_cover_large_dynamic` proxies the TMDB image. When None, the
-    /// template keeps the legacy `-large.webp` URL served from R2.
-    tmdb_poster_path: Option<String>,
-    /// Best sledujteto upload for this film — written by the sledujteto
-    /// import script. Template reads sources via `video_sources_for_badges`;
-    /// the column stays for legacy scripts that still SELECT it.
-    #[allow(dead_code)]
-    pub sledujteto_primary_file_id: Option<i32>,
+    name_cs: String,
 }
 
-impl FilmRow {
-    /// Extension for the large-cover URL rendered in the detail template.
-    /// Derived from `tmdb_poster_path` when the film has been backfilled;
-    /// otherwise falls back to `webp` so the existing R2-backed route keeps
-    /// serving until the backfill completes.
-    ///
-    /// Only `jpg` and `png` are whitelisted — `films_detail` dispatches exactly
-    /// those two large-cover extensions to the dynamic proxy, and TMDB's
-    /// in-practice storage is always JPG. Unknown/unexpected suffixes get
-    /// normalized to `jpg` rather than falling through to the HTML handler.
-    pub fn large_url_ext(&self) -> &str {
-        match self.tmdb_poster_path.as_deref() {
-            Some(p) => match p.rsplit_once('.').map(|(_, ext)| ext) {
-                Some("png") => "png",
-                Some(_) | None => "jpg",
-            },
-            None => "webp",
+impl From<GenreRecord> for GenreRow {
+    fn from(g: GenreRecord) -> Self {
+        Self {
+            id: g.id,
+            slug: g.slug,
+            name_cs: g.name_cs,
         }
     }
 }
 
-#[derive(sqlx::FromRow)]
-struct GenreRow {
-    id: i32,
-    slug: String,
-    name_cs: String,
-}
-
 impl GenreRow {
     /// Pretty Czech plural title for headings and SEO (e.g. "Horory", "Dramata", "Televizní filmy").
     fn pretty_plural(&self) -> String {
@@ -177,27 +60,6 @@ impl GenreRow {
     }
 }
 
-#[derive(sqlx::FromRow)]
-struct FilmGenreNameRow {
-    name_cs: String,
-    slug: String,
-}
-
-#[derive(sqlx::FromRow)]
-#[allow(dead_code)] // Fetched in films_detail; template accesses via JS, not Askama
-struct FilmSourceRow {
-    provider: String,
-    code: String,
-    language: Option<String>,
-    audio_type: Option<String>,
-    stream_format: Option<String>,
-}
-
-#[derive(sqlx::FromRow)]
-struct CountRow {
-    count: Option<i64>,
-}
-
 /// Per-source badge row rendered on the film detail page (#613). One row
 /// per alive `video_sources` entry for the film, with subtitle languages
 /// aggregated from `video_source_subtitles` in the same query. Ordered by
@@ -275,6 +137,10 @@ impl VideoSourceBadgeRow {
 #[derive(Deserialize)]
 pub struct FilmsQuery {
     strana: Option<i64>,
+    /// Keyset cursor of the » link: continue after this row.
+    po: Option<String>,
+    /// Keyset cursor of the « link: continue before this row.
+    pred: Option<String>,
     razeni: Option<String>, // "rok", "imdb", "csfd", "nazev"
     zanry: Option<String>,  // comma-separated genre slugs to include
     bez: Option<String>,    // comma-separated genre slugs to exclude
@@ -298,156 +164,76 @@ pub struct FilmsQuery {
 }
 
 impl FilmsQuery {
-    fn genre_mode_and(&self) -> bool {
-        self.rezim.as_deref() == Some("and")
-    }
-
-    fn sort_desc(&self) -> bool {
-        self.smer.as_deref() != Some("asc")
-    }
-
-    // Audio / subtitle filtering queries the `audio_langs` / `subtitle_langs`
-    // rollup arrays (#607 / #611 reader switch). The arrays are maintained
-    // by a TRIGGER on `video_sources` so they always reflect the current
-    // per-source data without a join at query time. GIN indexes on both
-    // arrays keep the filter fast.
-    //
-    // "dub" means "film has Czech OR Slovak audio" (i.e. user hears a local
-    // language, not the original foreign track). "sub" means "film has at
-    // least one subtitle track" (not gated by language because a viewer
-    // with English audio + any CZ/SK subs is the canonical use case).
-    fn audio_filter(&self) -> Option<&'static str> {
-        let val = self.jazyk.as_deref().map(|s| s.trim()).unwrap_or("");
-        if val.is_empty() || val == "vse" {
-            return None;
-        }
-        let parts: Vec<&str> = val.split(',').map(|s| s.trim()).collect();
-        let has_dub = parts.contains(&"dub") || parts.contains(&"cz") || parts.contains(&"sk");
-        let has_sub = parts.contains(&"sub") || parts.contains(&"titulky");
-        match (has_dub, has_sub) {
-            (true, false) => Some("f.audio_langs && ARRAY['cs','sk']::TEXT[]"),
-            (false, true) => Some("cardinality(f.subtitle_langs) > 0"),
-            (true, true) => Some(
-                "(f.audio_langs && ARRAY['cs','sk']::TEXT[] \
-                  OR cardinality(f.subtitle_langs) > 0)",
-            ),
-            _ => None,
-        }
-    }
-
-    /// Parse the `audio=cs,sk,en` param into a Vec of validated ISO codes.
-    /// Returns None when absent/empty; Some(vec) with only `^[a-z]{2,3}$`
-    /// entries otherwise. The regex guard avoids SQL-binding garbage strings
-    /// (the binding itself is parameterised, but keeping the array contents
-    /// canonical lets the GIN index fire and simplifies chip rendering).
-    pub(crate) fn audio_langs_filter(&self) -> Option<Vec<String>> {
-        let raw = self.audio.as_deref()?.trim();
-        if raw.is_empty() {
-            return None;
-        }
-        let langs: Vec<String> = raw
-            .split(',')
-            .map(|s| s.trim().to_ascii_lowercase())
-            .filter(|s| !s.is_empty() && s.len() <= 3 && s.chars().all(|c| c.is_ascii_lowercase()))
-            .collect();
-        if langs.is_empty() { None } else { Some(langs) }
-    }
-
-    /// Parse the `titulky=...` param into a subtitle filter mode.
-    /// Returns:
-    ///   - None              — no filter (absent or empty)
-    ///   - Some((None, _))   — `titulky=1` / `titulky=any` → any subtitles present
-    ///   - Some((Some(vec))) — `titulky=cs,en` → film must have every listed lang
-    pub(crate) fn subs_filter(&self) -> Option<Option<Vec<String>>> {
-        let raw = self.titulky.as_deref()?.trim();
-        if raw.is_empty() || raw == "0" {
-            return None;
-        }
-        if raw == "1" || raw == "any" || raw == "ano" {
-            return Some(None);
-        }
-        let langs: Vec<String> = raw
-            .split(',')
-            .map(|s| s.trim().to_ascii_lowercase())
-            .filter(|s| !s.is_empty() && s.len() <= 3 && s.chars().all(|c| c.is_ascii_lowercase()))
-            .collect();
-        if langs.is_empty() {
-            Some(None)
-        } else {
-            Some(Some(langs))
-        }
-    }
-}
-
-impl FilmsQuery {
-    fn page(&self) -> i64 {
-        self.strana.unwrap_or(1).max(1)
-    }
-
-    fn order_clause(&self) -> &'static str {
-        let desc = self.sort_desc();
-        match (self.razeni.as_deref(), desc) {
-            (Some("rok"), true) => "f.year DESC NULLS LAST, f.title",
-            (Some("rok"), false) => "f.year ASC NULLS LAST, f.title",
-            // `imdb` and `tmdb` are sibling URL keys, each sorting by its
-            // own *_rating column (#701). Pre-#701 `razeni=imdb` was a
-            // legacy alias for tmdb_rating (the only rating column at the
-            // time of #584/#586); the imdb_rating backfill in #690 made
-            // that alias misleading and it was retired.
-            (Some("imdb"), true) => "f.imdb_rating DESC NULLS LAST, f.title",
-            (Some("imdb"), false) => "f.imdb_rating ASC NULLS LAST, f.title",
-            (Some("tmdb"), true) => "f.tmdb_rating DESC NULLS LAST, f.title",
-            (Some("tmdb"), false) => "f.tmdb_rating ASC NULLS LAST, f.title",
-            (Some("csfd"), true) => "f.csfd_rating DESC NULLS LAST, f.title",
-            (Some("csfd"), false) => "f.csfd_rating ASC NULLS LAST, f.title",
-            (Some("nazev"), true) => "f.title DESC, f.year DESC NULLS LAST",
-            (Some("nazev"), false) => "f.title ASC, f.year DESC NULLS LAST",
-            // Default: "pridano" (added_at) — most recently added first
-            (_, true) => "f.added_at DESC NULLS LAST, f.title",
-            (_, false) => "f.added_at ASC NULLS LAST, f.title",
-        }
-    }
-
     fn sort_key(&self) -> &str {
         self.razeni.as_deref().unwrap_or("pridano")
     }
 
-    /// Credibility threshold for rating-sort listings (#704). 10/10 with 5
-    /// voters used to outrank Shawshank's 3M voters at the top of
-    /// `?razeni=imdb` / `?razeni=tmdb`; the thresholds keep low-sample
-    /// outliers off the leaderboard. NULL votes evaluate to false in the
-    /// inequality, so no explicit `IS NOT NULL` is needed.
-    fn votes_threshold_predicate(&self) -> Option<&'static str> {
-        match self.razeni.as_deref() {
-            Some("imdb") => Some("f.imdb_votes >= 500"),
-            Some("tmdb") => Some("f.tmdb_vote_count >= 50"),
-            _ => None,
-        }
-    }
-
-    fn parse_genre_slugs(input: Option<&String>) -> Vec<String> {
-        // Dedup to keep AND-mode `HAVING COUNT(DISTINCT g.slug) = slugs.len()` correct.
-        let mut slugs: Vec<String> = Vec::new();
-        if let Some(input) = input {
-            for s in input.split(',').map(|g| g.trim()).filter(|g| !g.is_empty()) {
-                if !slugs.iter().any(|x| x == s) {
-                    slugs.push(s.to_string());
-                }
-            }
+    /// Typed listing query for the film repository. Genre sub-pages start
+    /// from this and add the path genre on top.
+    fn to_list_query(&self) -> FilmListQuery {
+        FilmListQuery {
+            filter: FilmFilter {
+                search: search_term(self.q.as_deref()).map(TitleSearch::new),
+                genres: GenreFilter::parse(
+                    self.zanry.as_deref(),
+                    self.bez.as_deref(),
+                    self.rezim.as_deref(),
+                ),
+                year: self.rok.as_deref().and_then(|s| s.trim().parse().ok()),
+                local_language: LocalLanguage::parse(self.jazyk.as_deref()),
+                audio_langs: parse_lang_list(self.audio.as_deref()),
+                subtitles: SubtitleFilter::parse(self.titulky.as_deref()),
+                include_unavailable: false,
+            },
+            sort: CatalogSort::from_key(self.razeni.as_deref()),
+            direction: SortDirection::from_key(self.smer.as_deref()),
+            paging: Paging::new(self.strana, FILMS_PER_PAGE)
+                .with_seek(self.po.as_deref(), self.pred.as_deref()),
         }
-        slugs
     }
 
-    fn include_genres(&self) -> Vec<String> {
-        Self::parse_genre_slugs(self.zanry.as_ref())
-    }
-
-    fn exclude_genres(&self) -> Vec<String> {
-        Self::parse_genre_slugs(self.bez.as_ref())
-    }
-
-    fn year_filter(&self) -> Option<i16> {
-        self.rok.as_ref().and_then(|s| s.trim().parse().ok())
+    /// Listing query for a genre sub-page: the path genre on top of the
+    /// `zanry=` extras, with the genre, year, `jazyk=` and vote filters
+    /// only. Genre pages list every film of the genre — no live-source
+    /// gate, no `audio=` / `titulky=` — and never search.
+    fn to_genre_query(&self, genre_slug: &str) -> FilmListQuery {
+        let mut query = self.to_list_query();
+        query.filter.search = None;
+        query.filter.audio_langs.clear();
+        query.filter.subtitles = None;
+        query.filter.include_unavailable = true;
+        query.filter.genres.require(genre_slug);
+        query
+    }
+
+    /// Listing query for the Atom feed: the page's genre, year and
+    /// language filters, without the search and sort a feed has no use for.
+    pub(crate) fn to_feed_query(&self) -> FilmListQuery {
+        let mut query = self.to_list_query();
+        query.filter.search = None;
+        query.sort = CatalogSort::Added;
+        query
+    }
+
+    /// `?zanry=…&audio=…` — the filters [`Self::to_feed_query`] honours, for
+    /// the feed's self link and the list page's autodiscovery link.
+    pub(crate) fn feed_query_string(&self) -> String {
+        let parts = [
+            ("zanry", &self.zanry),
+            ("bez", &self.bez),
+            ("rezim", &self.rezim),
+            ("rok", &self.rok),
+            ("jazyk", &self.jazyk),
+            ("audio", &self.audio),
+            ("titulky", &self.titulky),
+        ]
+        .map(|(k, v)| {
+            (
+                k,
+                v.as_deref().map(str::trim).unwrap_or_default().to_string(),
+            )
+        });
+        super::feeds::feed_query_string(&parts)
     }
 }
 
@@ -457,22 +243,27 @@ impl FilmsQuery {
 #[template(path = "films_list.html")]
 struct FilmsListTemplate {
     img: String,
-    films: Vec<FilmRow>,
+    films: Vec<FilmRecord>,
     genres: Vec<GenreRow>,
     page: i64,
     total_pages: i64,
     total_count: i64,
+    /// « / » links, carrying the keyset cursors.
+    prev_href: String,
+    next_href: String,
     current_genre: Option<GenreRow>,
     #[allow(dead_code)] // TODO: verify usage — may be needed for sort UI active state
     sort_key: String,
     query_string: String,
+    /// Atom feed with the page's filters, for `<link rel="alternate">`.
+    feed_href: String,
     search_query: Option<String>,
     open_filter: bool,
     /// Genre slugs the user is filtering by right now (from `?zanry=` on main page).
     /// Empty on a category sub-page — that info is already in `current_genre`.
     selected_genre_slugs: Vec<String>,
     /// Genres per film, keyed by film id — rendered as chips in desktop list view.
-    film_genres_map: std::collections::HashMap<i32, Vec<FilmGenreNameRow>>,
+    film_genres_map: std::collections::HashMap<i32, Vec<GenreRecord>>,
     /// Currently-applied audio-language filter (#612). Empty → no filter.
     selected_audio_langs: Vec<String>,
     /// Currently-applied subtitle-language filter (#612). `Some(vec)` means
@@ -498,8 +289,8 @@ impl FilmsListTemplate {
     // NOTE: Askama auto-refs arguments in template method calls — `{{ self.film_genres(f.id) }}`
     // generates a call with `&f.id`. Keep the signature as `&i32` to match; Copilot's
     // "accept i32 by value" suggestion would break Askama codegen here.
-    fn film_genres(&self, film_id: &i32) -> &[FilmGenreNameRow] {
-        static EMPTY: Vec<FilmGenreNameRow> = Vec::new();
+    fn film_genres(&self, film_id: &i32) -> &[GenreRecord] {
+        static EMPTY: Vec<GenreRecord> = Vec::new();
         self.film_genres_map
             .get(film_id)
             .map(|v| v.as_slice())
@@ -653,10 +444,8 @@ fn build_filter_url(params: &std::collections::BTreeMap<String, Vec<String>>) ->
 #[template(path = "film_detail.html")]
 struct FilmDetailTemplate {
     img: String,
-    film: FilmRow,
-    genres: Vec<FilmGenreNameRow>,
-    #[allow(dead_code)] // Fetched from DB but not rendered via Askama; JS handles sources
-    sources: Vec<FilmSourceRow>,
+    film: FilmRecord,
+    genres: Vec<GenreRecord>,
     /// Per-source badge rows (#613). Already sorted by provider
     /// `sort_priority` + primary-first. Rendered under the provider
     /// tabs on the detail page.
@@ -670,16 +459,22 @@ struct FilmDetailTemplate {
     has_source_prehrajto: bool,
     has_source_sledujteto: bool,
     /// Top-10 cast members (joined from `film_actors`). Empty for films
-    /// without TMDB credits backfilled or with no cast on TMDB. Mirrors
-    /// the series detail page's `actors` field — same `PersonRow` shape
-    /// lets us reuse the same Askama template fragment.
-    actors: Vec<super::series::PersonRow>,
+    /// without TMDB credits backfilled or with no cast on TMDB. Same
+    /// `PersonCredit` shape as the series detail page's `actors`, so both
+    /// reuse the same Askama template fragment.
+    actors: Vec<PersonCredit>,
     /// Directors only (TMDB `crew[].job == "Director"` from
     /// `/movie/{id}/credits`). Field is named `creators` to keep the
     /// template parallel with series_detail's `creators` (which there
     /// is series creators + directors). Empty when no directors are
     /// linked.
-    creators: Vec<super::series::PersonRow>,
+    creators: Vec<PersonCredit>,
+    /// `Movie` JSON-LD with the cast and directors linked to `/osobnosti/`.
+    json_ld: String,
+    /// "Součást kolekce" block with previous/next film of the franchise.
+    collection: Option<CollectionNav>,
+    /// "Podobné tituly" carousel, precomputed nightly (`similar_titles`).
+    similar: Vec<SimilarTitle>,
 }
 
 // --- Search API types ---
@@ -694,13 +489,17 @@ struct SearchResult {
     cover: bool,
 }
 
-#[derive(sqlx::FromRow)]
-struct SearchRow {
-    slug: String,
-    title: String,
-    year: Option<i16>,
-    tmdb_rating: Option<f32>,
-    imdb_rating: Option<f32>,
+impl From<TitleSuggestion> for SearchResult {
+    fn from(s: TitleSuggestion) -> Self {
+        Self {
+            slug: s.slug,
+            title: s.title,
+            year: s.year,
+            tmdb_rating: s.tmdb_rating,
+            imdb_rating: s.imdb_rating,
+            cover: true,
+        }
+    }
 }
 
 // --- Handlers ---
@@ -710,140 +509,114 @@ pub async fn films_list(
     State(state): State<AppState>,
     axum::extract::Query(params): axum::extract::Query<FilmsQuery>,
 ) -> WebResult<Response> {
-    let page = params.page();
-    let offset = (page - 1) * FILMS_PER_PAGE;
-    let order = params.order_clause();
-
-    // Search filter. Primary pattern uses ILIKE on title/original_title. If
-    // that returns zero rows for a non-trivial query, fall back to matching
-    // against CONCAT_WS(' ', title, year::text) with the paren-stripped
-    // query — lets "Mstitel (1989)", "Marvinův pokoj 1996)", and partial
-    // "Marvinův pokoj 19" resolve. Mirrors the autocomplete API behavior.
-    let raw_q = params.q.as_deref().map(str::trim).filter(|t| t.len() >= 2);
-    let raw_pattern = raw_q.map(|t| format!("%{t}%"));
-
-    let include = params.include_genres();
-    let exclude = params.exclude_genres();
-    let year_f = params.year_filter();
-
-    let (total_count, films) = run_films_query(
+    let query = params.to_list_query();
+    let selected_genre_slugs = query.filter.genres.include.clone();
+    let open_filter = !selected_genre_slugs.is_empty()
+        || !query.filter.audio_langs.is_empty()
+        || query.filter.subtitles.is_some();
+
+    let html = render_films_listing(
         &state,
-        FilmsSearchMode::Primary,
-        raw_pattern.as_deref(),
         &params,
-        &include,
-        &exclude,
-        year_f,
-        order,
-        offset,
+        query,
+        None,
+        selected_genre_slugs,
+        open_filter,
     )
     .await?;
+    // Search-result HTML is `?q=`-derived: tag it `private,
+    // max-age=60` so the browser can reuse it for back-button /
+    // repeat-search but no shared cache hangs on to it. Gate
+    // matches the actual search predicate (`search_term`), so
+    // `?q=a` still gets the default listing.
+    if is_active_search(params.q.as_deref()) {
+        Ok(super::search_cached_html(html))
+    } else {
+        Ok(Html(html).into_response())
+    }
+}
 
-    let (total_count, films) = match (total_count, raw_q) {
-        (0, Some(q)) => {
-            let normalized = normalize_query(q);
-            // Same min-length guard as the autocomplete API — avoid running
-            // a full-table scan with `%x%` when normalization collapsed the
-            // query to something trivial like "1" (from "(1").
-            if normalized.chars().count() < 2 {
-                (total_count, films)
-            } else {
-                let fb_pattern = format!("%{normalized}%");
-                run_films_query(
-                    &state,
-                    FilmsSearchMode::TitleYear,
-                    Some(&fb_pattern),
-                    &params,
-                    &include,
-                    &exclude,
-                    year_f,
-                    order,
-                    offset,
-                )
-                .await?
-            }
-        }
-        _ => (total_count, films),
-    };
-    let total_pages = (total_count as f64 / FILMS_PER_PAGE as f64).ceil() as i64;
-
-    let genres = load_genres(&state.db).await?;
-
-    // Build query string for pagination links (preserve sort + filters)
-    let query_string = build_films_query_string(&params);
+/// Render `films_list.html` for the main listing and genre sub-pages.
+/// A title search that matches nothing is retried against "title year"
+/// inside `list_films`, so "Mstitel (1989)" still lands on the film.
+async fn render_films_listing(
+    state: &AppState,
+    params: &FilmsQuery,
+    query: FilmListQuery,
+    current_genre: Option<GenreRow>,
+    selected_genre_slugs: Vec<String>,
+    open_filter: bool,
+) -> WebResult<String> {
+    let result =
+        cr_app::catalog::list_films(state.film_repo.as_ref(), &query, &state.listing_count_cache)
+            .await?;
 
-    let search_query = params.q.as_ref().and_then(|q| {
-        let t = q.trim();
-        if t.is_empty() {
-            None
-        } else {
-            Some(t.to_string())
-        }
-    });
+    let genres = state
+        .film_repo
+        .genres()
+        .await?
+        .into_iter()
+        .map(GenreRow::from)
+        .collect();
+    let ids: Vec<i32> = result.films.iter().map(|f| f.id).collect();
+    let film_genres_map = cr_app::catalog::genre_chips(state.film_repo.genres_of(&ids).await?);
 
-    let selected_genre_slugs: Vec<String> = params
-        .zanry
+    let search_query = params
+        .q
         .as_deref()
-        .map(|s| {
-            s.split(',')
-                .map(|t| t.trim().to_string())
-                .filter(|t| !t.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
-    let selected_audio_langs = params.audio_langs_filter().unwrap_or_default();
-    let selected_subs = match params.subs_filter() {
-        Some(None) => Some(Vec::new()),
-        Some(Some(v)) => Some(v),
-        None => None,
-    };
-    let full_query = build_full_query_map(&params);
-    let open_filter = !selected_genre_slugs.is_empty()
-        || !selected_audio_langs.is_empty()
-        || selected_subs.is_some();
-    let film_genres_map = load_film_genres_map(&state.db, &films).await?;
-
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string);
+
+    let feed_href = match &current_genre {
+        Some(genre) => format!("/filmy-online/{}/feed.xml", genre.slug),
+        None => "/filmy-online/feed.xml".to_string(),
+    } + &params.feed_query_string();
+
+    let query_string = build_films_query_string(params);
+    let (prev_href, next_href) =
+        super::keyset_page_hrefs(query.paging.page, &result.cursors, &query_string);
     let tmpl = FilmsListTemplate {
         img: state.image_base_url.clone(),
-        films,
+        feed_href,
+        films: result.films,
         genres,
-        page,
-        total_pages,
-        total_count,
-        current_genre: None,
+        page: query.paging.page,
+        total_pages: query.paging.total_pages(result.total),
+        total_count: result.total,
+        prev_href,
+        next_href,
+        search_query: if current_genre.is_some() {
+            None
+        } else {
+            search_query
+        },
+        current_genre,
         sort_key: params.sort_key().to_string(),
         query_string,
-        search_query,
         open_filter,
         selected_genre_slugs,
         film_genres_map,
-        selected_audio_langs,
-        selected_subs,
-        full_query,
+        selected_audio_langs: query.filter.audio_langs.clone(),
+        selected_subs: query
+            .filter
+            .subtitles
+            .as_ref()
+            .map(|s| s.languages().to_vec()),
+        full_query: build_full_query_map(params),
     };
-    let html = tmpl.render()?;
-    // Search-result HTML is `?q=`-derived: tag it `private,
-    // max-age=60` so the browser can reuse it for back-button /
-    // repeat-search but no shared cache hangs on to it. Gate
-    // matches the actual search predicate (`raw_q` filters
-    // `len() >= 2`), so `?q=a` still gets the default listing.
-    if is_active_search(params.q.as_deref()) {
-        Ok(super::search_cached_html(html))
-    } else {
-        Ok(Html(html).into_response())
-    }
+    Ok(tmpl.render()?)
 }
 
-/// True when `?q=…` is a real search query — same trim+length
-/// gate the search predicate uses (`raw_q` filters `t.len() >= 2`,
-/// byte length). Single source of truth so the search-cache branch
-/// and the predicate gate can't drift apart (Copilot review on
-/// #674 and #675 — the latter caught this helper using
-/// `chars().count()` instead of byte `len()`, which would split
-/// on a single multibyte char like `á` and leave that real search
-/// page uncached).
+/// True when `?q=…` is a real search query — the same
+/// `search_term` gate the film repository search uses. Single
+/// source of truth so the search-cache branch and the predicate
+/// gate can't drift apart (Copilot review on #674 and #675 — the
+/// latter caught this helper using `chars().count()` instead of
+/// byte `len()`, which would split on a single multibyte char
+/// like `á` and leave that real search page uncached).
 fn is_active_search(q: Option<&str>) -> bool {
-    q.map(str::trim).is_some_and(|t| t.len() >= 2)
+    search_term(q).is_some()
 }
 
 /// Serialize the current filter params into a BTreeMap keyed by query
@@ -914,28 +687,13 @@ pub async fn films_detail(
     axum::extract::Query(params): axum::extract::Query<FilmsQuery>,
     headers: axum::http::HeaderMap,
 ) -> WebResult<Response> {
-    // Large cover dynamically proxied from TMDB — real extension in URL
-    // so the response content type matches what the template rendered.
-    // See `films_cover_large_dynamic` for the fallback chain.
-    if slug.ends_with("-large.jpg") || slug.ends_with("-large.png") {
-        return films_cover_large_dynamic(State(state), Path(slug)).await;
-    }
-    // WebP cover request: /filmy-online/some-film.webp (small) or -large.webp
-    if slug.ends_with("-large.webp") {
-        return films_cover_large(State(state), Path(slug)).await;
-    }
-    if slug.ends_with(".webp") {
-        return films_cover(State(state), Path(slug)).await;
+    // Cover image: /filmy-online/{slug}.webp, -large.webp, -large.{jpg,png}
+    if let Some((cover_slug, variant)) = media_cover::parse_cover_request(&slug) {
+        return media_cover::serve_cover(&state, MediaKind::FILM, cover_slug, variant).await;
     }
 
     // First check: is this a genre slug?
-    let genre =
-        sqlx::query_as::<_, GenreRow>("SELECT id, slug, name_cs FROM genres WHERE slug = $1")
-            .bind(&slug)
-            .fetch_optional(&state.db)
-            .await?;
-
-    if let Some(genre) = genre {
+    if let Some(genre) = state.film_repo.find_genre(&slug).await? {
         let from_films_home = headers
             .get(axum::http::header::REFERER)
             .and_then(|h| h.to_str().ok())
@@ -949,39 +707,21 @@ pub async fn films_detail(
                 }
             })
             .unwrap_or(false);
-        return films_by_genre(state, genre, params, from_films_home).await;
+        return films_by_genre(state, genre.into(), params, from_films_home).await;
     }
 
     // Otherwise: film detail
-    let film = sqlx::query_as::<_, FilmRow>(&format!(
-        "SELECT {FILM_COLUMNS} FROM films f WHERE f.slug = $1"
-    ))
-    .bind(&slug)
-    .fetch_optional(&state.db)
-    .await?;
-
-    let Some(film) = film else {
+    let Some(film) = state.film_repo.find_by_slug(&slug).await? else {
         return Ok(not_found_response().into_response());
     };
 
-    let genres = sqlx::query_as::<_, FilmGenreNameRow>(
-        "SELECT g.name_cs, g.slug FROM genres g \
-         JOIN film_genres fg ON g.id = fg.genre_id \
-         WHERE fg.film_id = $1 \
-         ORDER BY g.name_cs",
-    )
-    .bind(film.id)
-    .fetch_all(&state.db)
-    .await?;
-
-    let sources = sqlx::query_as::<_, FilmSourceRow>(
-        "SELECT provider, code, language, audio_type, stream_format \
-         FROM film_sources WHERE film_id = $1 \
-         ORDER BY CASE WHEN audio_type = 'dubbing' THEN 0 ELSE 1 END, provider",
-    )
-    .bind(film.id)
-    .fetch_all(&state.db)
-    .await?;
+    let genres: Vec<GenreRecord> = state
+        .film_repo
+        .genres_of(&[film.id])
+        .await?
+        .into_iter()
+        .map(|(_, g)| g)
+        .collect();
 
     // Per-source badge data (#613). One row per alive video source for this
     // film, with an array-aggregated list of subtitle languages. Ordered by
@@ -1043,43 +783,53 @@ pub async fn films_detail(
     // on error so the detail page still renders (table missing in dev,
     // transient DB issue) — but we log the error first so a real
     // regression doesn't look identical to "this film has no crew".
-    let actors = sqlx::query_as::<_, super::series::PersonRow>(
-        "SELECT p.id, p.name, p.profile_filename, fa.character_name \
-         FROM people p JOIN film_actors fa ON fa.person_id = p.id \
-         WHERE fa.film_id = $1 ORDER BY fa.order_index LIMIT 10",
-    )
-    .bind(film.id)
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_else(|e| {
+    let actors = state.film_repo.cast(film.id, 10).await.unwrap_or_else(|e| {
         tracing::error!(film_id = film.id, error = ?e, "film_actors query failed");
         Vec::new()
     });
 
-    let creators = sqlx::query_as::<_, super::series::PersonRow>(
-        "SELECT p.id, p.name, p.profile_filename, NULL::varchar AS character_name \
-         FROM people p JOIN film_directors fd ON fd.person_id = p.id \
-         WHERE fd.film_id = $1 ORDER BY p.name",
-    )
-    .bind(film.id)
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_else(|e| {
-        tracing::error!(film_id = film.id, error = ?e, "film_directors query failed");
-        Vec::new()
-    });
+    let creators = state
+        .film_repo
+        .directors(film.id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!(film_id = film.id, error = ?e, "film_directors query failed");
+            Vec::new()
+        });
+
+    let collection = collection_nav(&state, film.id).await;
+
+    let similar = state
+        .similar_repo
+        .similar_to(CatalogKind::Film, film.id, SIMILAR_CAROUSEL_LEN)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!(film_id = film.id, error = ?e, "similar_titles query failed");
+            Vec::new()
+        });
+
+    let json_ld = super::people::work_json_ld(
+        "Movie",
+        &film.title,
+        &format!("/filmy-online/{}/", film.slug),
+        film.year,
+        &creators,
+        &actors,
+    );
 
     let tmpl = FilmDetailTemplate {
         img: state.image_base_url.clone(),
         film,
         genres,
-        sources,
         video_sources_for_badges,
         has_source_sktorrent,
         has_source_prehrajto,
         has_source_sledujteto,
         actors,
         creators,
+        json_ld,
+        collection,
+        similar,
     };
     Ok(Html(tmpl.render()?).into_response())
 }
@@ -1096,670 +846,56 @@ pub async fn films_person_image(
 }
 
 /// Genre sub-listing: /filmy-online/{genre-slug}/
+///
+/// Same filters as the main listing with the path genre always included;
+/// `?zanry=` adds further genres (combined per `rezim=`). A stray `?q=`
+/// is ignored — genre pages aren't search results.
 async fn films_by_genre(
     state: AppState,
     genre: GenreRow,
     params: FilmsQuery,
     open_filter: bool,
 ) -> WebResult<Response> {
-    let page = params.page();
-    let offset = (page - 1) * FILMS_PER_PAGE;
-    let order = params.order_clause();
-    let exclude = params.exclude_genres();
-    let year_f = params.year_filter();
-    let zanry_extras = params.include_genres();
-
-    // Merge path genre with extras from ?zanry= into one include list
-    let mut include_slugs: Vec<String> = vec![genre.slug.clone()];
-    for s in zanry_extras.iter() {
-        if !include_slugs.contains(s) {
-            include_slugs.push(s.clone());
-        }
-    }
-    let multi_include = include_slugs.len() > 1;
-
-    // Build WHERE clauses
-    let mut where_parts: Vec<String> = vec![];
-    let mut bind_idx = 1;
-    if !multi_include {
-        where_parts.push(format!(
-            "f.id IN (SELECT fg.film_id FROM film_genres fg WHERE fg.genre_id = ${bind_idx})"
-        ));
-        bind_idx += 1;
-    } else if params.genre_mode_and() {
-        where_parts.push(format!(
-            "f.id IN (SELECT fg.film_id FROM film_genres fg \
-             JOIN genres g ON g.id = fg.genre_id \
-             WHERE g.slug = ANY(${bind_idx}) \
-             GROUP BY fg.film_id HAVING COUNT(DISTINCT g.slug) = {})",
-            include_slugs.len()
-        ));
-        bind_idx += 1;
-    } else {
-        where_parts.push(format!(
-            "f.id IN (SELECT fg.film_id FROM film_genres fg \
-             JOIN genres g ON g.id = fg.genre_id \
-             WHERE g.slug = ANY(${bind_idx}))"
-        ));
-        bind_idx += 1;
-    }
-    if !exclude.is_empty() {
-        where_parts.push(format!(
-            "f.id NOT IN (SELECT fg2.film_id FROM film_genres fg2 \
-             JOIN genres g2 ON g2.id = fg2.genre_id \
-             WHERE g2.slug = ANY(${bind_idx}))"
-        ));
-        bind_idx += 1;
-    }
-    if year_f.is_some() {
-        where_parts.push(format!("f.year = ${bind_idx}"));
-        bind_idx += 1;
-    }
-    if let Some(af) = params.audio_filter() {
-        where_parts.push(af.to_string());
-    }
-    if let Some(p) = params.votes_threshold_predicate() {
-        where_parts.push(p.to_string());
-    }
-    let where_clause = where_parts.join(" AND ");
-
-    // Count
-    let count_query = format!("SELECT count(*) as count FROM films f WHERE {where_clause}");
-    let mut count_q = sqlx::query_as::<_, CountRow>(&count_query);
-    if multi_include {
-        count_q = count_q.bind(include_slugs.clone());
-    } else {
-        count_q = count_q.bind(genre.id);
-    }
-    if !exclude.is_empty() {
-        count_q = count_q.bind(exclude.clone());
-    }
-    if let Some(yr) = year_f {
-        count_q = count_q.bind(yr);
-    }
-    let count_row = count_q.fetch_one(&state.db).await?;
-    let total_count = count_row.count.unwrap_or(0);
-    let total_pages = (total_count as f64 / FILMS_PER_PAGE as f64).ceil() as i64;
-
-    // Films
-    let films_query = format!(
-        "SELECT {FILM_COLUMNS} FROM films f \
-         WHERE {where_clause} \
-         ORDER BY {order} \
-         LIMIT ${limit_idx} OFFSET ${offset_idx}",
-        limit_idx = bind_idx,
-        offset_idx = bind_idx + 1
-    );
-    let mut q = sqlx::query_as::<_, FilmRow>(&films_query);
-    if multi_include {
-        q = q.bind(include_slugs.clone());
-    } else {
-        q = q.bind(genre.id);
-    }
-    if !exclude.is_empty() {
-        q = q.bind(exclude.clone());
-    }
-    if let Some(yr) = year_f {
-        q = q.bind(yr);
-    }
-    let films = q
-        .bind(FILMS_PER_PAGE)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
+    let zanry_extras = params.to_list_query().filter.genres.include;
+    let query = params.to_genre_query(&genre.slug);
 
-    let all_genres = load_genres(&state.db).await?;
-
-    let query_string = build_films_query_string(&params);
-
-    let film_genres_map = load_film_genres_map(&state.db, &films).await?;
-    let tmpl = FilmsListTemplate {
-        img: state.image_base_url.clone(),
-        films,
-        genres: all_genres,
-        page,
-        total_pages,
-        total_count,
-        current_genre: Some(genre),
-        sort_key: params.sort_key().to_string(),
-        query_string,
-        search_query: None,
-        open_filter: open_filter || !zanry_extras.is_empty(),
-        selected_genre_slugs: zanry_extras.clone(),
-        film_genres_map,
-        selected_audio_langs: params.audio_langs_filter().unwrap_or_default(),
-        selected_subs: match params.subs_filter() {
-            Some(None) => Some(Vec::new()),
-            Some(Some(v)) => Some(v),
-            None => None,
-        },
-        full_query: build_full_query_map(&params),
-    };
-    Ok(Html(tmpl.render()?).into_response())
+    let html = render_films_listing(
+        &state,
+        &params,
+        query,
+        Some(genre),
+        zanry_extras.clone(),
+        open_filter || !zanry_extras.is_empty(),
+    )
+    .await?;
+    Ok(Html(html).into_response())
 }
 
 /// GET /api/films/search?q=matrix — search autocomplete.
 ///
-/// Two-stage search:
-/// 1. Primary: ILIKE against `title` / `original_title`. Handles the common
-///    case and titles that literally contain digits ("1984", "2001: A Space
-///    Odyssey").
+/// Served from the in-process full-text index (`cr_app::search`), which
+/// also matches original titles, cast and descriptions, tolerates typos
+/// and indexes the year, so "Marvinův pokoj (1996" resolves directly.
+/// Until the index has been built after startup, `suggest_films` falls
+/// back to the two-stage SQL search:
+/// 1. Primary: diacritic-insensitive match against `title` /
+///    `original_title`.
 /// 2. Fallback (only when primary returns zero): strip parens and collapse
-///    whitespace from the query, then ILIKE against the virtual expression
-///    `CONCAT_WS(' ', title, year)`. Lets "Marvinův pokoj 1996", "Marvinův
-///    pokoj (1996", "Marvinův pokoj 1996)", and even partial "Marvinův pokoj
-///    19" resolve to the same film — no schema/view needed, Postgres
-///    evaluates the expression at query time.
+///    whitespace from the query, then match against `title year`.
 pub async fn films_search(
     State(state): State<AppState>,
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> WebResult<Response> {
-    let q = params.get("q").map(|s| s.trim()).unwrap_or("");
-    if q.len() < 2 {
-        return Ok(super::search_cached_json(Vec::<SearchResult>::new()));
-    }
-
-    let mut rows = search_films_by_title(&state.db, q).await?;
-    if rows.is_empty() {
-        let normalized = normalize_query(q);
-        // Skip fallback for collapsed queries like "(1" → "1" — `%1%` would
-        // bloat results with everything containing a "1" in title or year.
-        if normalized.chars().count() >= 2 {
-            rows = search_films_by_title_year(&state.db, &normalized).await?;
-        }
-    }
-
-    let results: Vec<SearchResult> = rows
-        .into_iter()
-        .map(|r| SearchResult {
-            slug: r.slug,
-            title: r.title,
-            year: r.year,
-            tmdb_rating: r.tmdb_rating,
-            imdb_rating: r.imdb_rating,
-            cover: true,
-        })
-        .collect();
-
+    let q = params.get("q").map(String::as_str).unwrap_or("");
+    let results: Vec<SearchResult> =
+        cr_app::catalog::suggest_films(state.film_repo.as_ref(), &state.catalog_search, q)
+            .await?
+            .into_iter()
+            .map(SearchResult::from)
+            .collect();
     Ok(super::search_cached_json(results))
 }
 
-async fn search_films_by_title(db: &sqlx::PgPool, q: &str) -> Result<Vec<SearchRow>, sqlx::Error> {
-    let pattern = format!("%{q}%");
-    let starts_pattern = format!("{q}%");
-    // WHERE matches via `unaccent()` so "laska nebeska" finds "Láska
-    // nebeská" (#673). ORDER BY keeps the raw ILIKE in the CASE
-    // arms — rows whose title literally contains the query (with the
-    // user's diacritics) win bucket 0/1/2; rows that only match after
-    // unaccent fall to bucket 3, behind the diacritic-exact hits.
-    sqlx::query_as::<_, SearchRow>(
-        "SELECT slug, title, year, tmdb_rating, imdb_rating \
-         FROM films \
-         WHERE unaccent(title) ILIKE unaccent($1) \
-            OR unaccent(original_title) ILIKE unaccent($1) \
-         ORDER BY \
-           CASE WHEN title ILIKE $2 THEN 0 \
-                WHEN title ILIKE $1 THEN 1 \
-                WHEN original_title ILIKE $2 THEN 2 \
-                ELSE 3 END, \
-           tmdb_rating DESC NULLS LAST \
-         LIMIT 10",
-    )
-    .bind(&pattern)
-    .bind(&starts_pattern)
-    .fetch_all(db)
-    .await
-}
-
-async fn search_films_by_title_year(
-    db: &sqlx::PgPool,
-    q: &str,
-) -> Result<Vec<SearchRow>, sqlx::Error> {
-    let pattern = format!("%{q}%");
-    let starts_pattern = format!("{q}%");
-    sqlx::query_as::<_, SearchRow>(
-        "SELECT slug, title, year, tmdb_rating, imdb_rating \
-         FROM films \
-         WHERE unaccent(CONCAT_WS(' ', title, year::text)) ILIKE unaccent($1) \
-            OR unaccent(CONCAT_WS(' ', original_title, year::text)) ILIKE unaccent($1) \
-         ORDER BY \
-           CASE WHEN CONCAT_WS(' ', title, year::text) ILIKE $2 THEN 0 \
-                WHEN CONCAT_WS(' ', title, year::text) ILIKE $1 THEN 1 \
-                WHEN CONCAT_WS(' ', original_title, year::text) ILIKE $2 THEN 2 \
-                ELSE 3 END, \
-           tmdb_rating DESC NULLS LAST \
-         LIMIT 10",
-    )
-    .bind(&pattern)
-    .bind(&starts_pattern)
-    .fetch_all(db)
-    .await
-}
-
-/// Normalize a search query for the title+year fallback: strip parentheses
-/// and collapse whitespace runs to single spaces. Handles malformed paren
-/// pairs: "Title (1996" and "Title 1996)" both normalize to "Title 1996",
-/// which then ILIKE-matches against `CONCAT_WS(' ', title, year)`.
-fn normalize_query(q: &str) -> String {
-    q.chars()
-        .filter(|c| *c != '(' && *c != ')')
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
-}
-
-/// Which predicate shape to use for the search filter in `films_list`.
-#[derive(Clone, Copy)]
-enum FilmsSearchMode {
-    /// `title ILIKE $N OR original_title ILIKE $N` — primary path.
-    Primary,
-    /// `CONCAT_WS(' ', title, year::text) ILIKE $N OR CONCAT_WS(' ',
-    /// original_title, year::text) ILIKE $N` — title+year fallback.
-    TitleYear,
-}
-
-impl FilmsSearchMode {
-    fn predicate(self, bind_idx: usize) -> String {
-        match self {
-            Self::Primary => {
-                format!(
-                    "(unaccent(f.title) ILIKE unaccent(${bind_idx}) \
-                     OR unaccent(f.original_title) ILIKE unaccent(${bind_idx}))"
-                )
-            }
-            Self::TitleYear => format!(
-                "(unaccent(CONCAT_WS(' ', f.title, f.year::text)) ILIKE unaccent(${bind_idx}) \
-                 OR unaccent(CONCAT_WS(' ', f.original_title, f.year::text)) ILIKE unaccent(${bind_idx}))"
-            ),
-        }
-    }
-
-    /// Same shape as `predicate` but without the `unaccent()` wrapper —
-    /// matches only when the column literally contains the user's
-    /// diacritics. Used in ORDER BY to push diacritic-exact hits in
-    /// front of unaccent-only hits.
-    fn raw_predicate(self, bind_idx: usize) -> String {
-        match self {
-            Self::Primary => {
-                format!("(f.title ILIKE ${bind_idx} OR f.original_title ILIKE ${bind_idx})")
-            }
-            Self::TitleYear => format!(
-                "(CONCAT_WS(' ', f.title, f.year::text) ILIKE ${bind_idx} \
-                 OR CONCAT_WS(' ', f.original_title, f.year::text) ILIKE ${bind_idx})"
-            ),
-        }
-    }
-}
-
-/// Run the count + paginated films query for `films_list`, with the chosen
-/// search predicate shape (or no search filter when `search_pattern` is None).
-#[allow(clippy::too_many_arguments)]
-async fn run_films_query(
-    state: &AppState,
-    mode: FilmsSearchMode,
-    search_pattern: Option<&str>,
-    params: &FilmsQuery,
-    include: &[String],
-    exclude: &[String],
-    year_f: Option<i16>,
-    order: &str,
-    offset: i64,
-) -> Result<(i64, Vec<FilmRow>), sqlx::Error> {
-    let db = &state.db;
-    let mut where_parts: Vec<String> = vec![];
-    let mut bind_idx = 1;
-
-    if search_pattern.is_some() {
-        where_parts.push(mode.predicate(bind_idx));
-        bind_idx += 1;
-    }
-    if !include.is_empty() {
-        if params.genre_mode_and() {
-            where_parts.push(format!(
-                "f.id IN (SELECT fg.film_id FROM film_genres fg \
-                 JOIN genres g ON g.id = fg.genre_id \
-                 WHERE g.slug = ANY(${bind_idx}) \
-                 GROUP BY fg.film_id HAVING COUNT(DISTINCT g.slug) = {})",
-                include.len()
-            ));
-        } else {
-            where_parts.push(format!(
-                "f.id IN (SELECT fg.film_id FROM film_genres fg \
-                 JOIN genres g ON g.id = fg.genre_id \
-                 WHERE g.slug = ANY(${bind_idx}))"
-            ));
-        }
-        bind_idx += 1;
-    }
-    if !exclude.is_empty() {
-        where_parts.push(format!(
-            "f.id NOT IN (SELECT fg.film_id FROM film_genres fg \
-             JOIN genres g ON g.id = fg.genre_id \
-             WHERE g.slug = ANY(${bind_idx}))"
-        ));
-        bind_idx += 1;
-    }
-    if year_f.is_some() {
-        where_parts.push(format!("f.year = ${bind_idx}"));
-        bind_idx += 1;
-    }
-    if let Some(af) = params.audio_filter() {
-        where_parts.push(af.to_string());
-    }
-    // Multi-value audio-lang filter (#612). `f.audio_langs @> $N` asks "is
-    // every user-selected lang present in the film's rollup array" — AND
-    // semantics per the issue. GIN on `audio_langs` makes this a cheap
-    // bitmap intersection.
-    let audio_langs_param = params.audio_langs_filter();
-    if audio_langs_param.is_some() {
-        where_parts.push(format!("f.audio_langs @> ${bind_idx}::TEXT[]"));
-        bind_idx += 1;
-    }
-    // Subtitle filter (#612).
-    //   titulky=any → require at least one subtitle track (any language)
-    //   titulky=cs,en → require every listed language as a subtitle track
-    let subs_param = params.subs_filter();
-    let mut subs_bind_idx = None;
-    match &subs_param {
-        Some(None) => {
-            where_parts.push("cardinality(f.subtitle_langs) > 0".to_string());
-        }
-        Some(Some(_)) => {
-            where_parts.push(format!("f.subtitle_langs @> ${bind_idx}::TEXT[]"));
-            subs_bind_idx = Some(bind_idx);
-            bind_idx += 1;
-        }
-        None => {}
-    }
-    let _ = subs_bind_idx; // reserved for future chip rendering; kept to document order
-
-    // Anti-zombie filter: only list films that actually have at least one
-    // alive video source. Before the #611 reader switch, this condition was
-    // implicit — the pre-refactor SELECT read `sktorrent_video_id` /
-    // `prehrajto_url` / `sledujteto_primary_file_id` columns directly, and
-    // films without any source had all three columns NULL but were still
-    // rendered (clicking through led to an empty detail page). The new
-    // schema puts source existence in a separate table, so we have to gate
-    // it explicitly. The partial index `idx_vs_film_alive` makes this EXISTS
-    // a cheap hash-semi-join.
-    where_parts.push(
-        "EXISTS (SELECT 1 FROM video_sources vs \
-                 WHERE vs.film_id = f.id AND vs.is_alive)"
-            .to_string(),
-    );
-    if let Some(p) = params.votes_threshold_predicate() {
-        where_parts.push(p.to_string());
-    }
-
-    let where_clause = if where_parts.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", where_parts.join(" AND "))
-    };
-
-    let count_query = format!("SELECT count(*) as count FROM films f {where_clause}");
-    // Cache key encodes the count SQL plus the bound parameter values
-    // so filtered variants stay separate (the SQL alone has only `$N`
-    // placeholders). 60 s TTL — see `listing_count_cache` doc on
-    // `AppState`.
-    let mut cache_key = String::with_capacity(count_query.len() + 64);
-    cache_key.push_str(&count_query);
-    if let Some(p) = search_pattern {
-        cache_key.push_str("|q=");
-        cache_key.push_str(p);
-    }
-    if !include.is_empty() {
-        cache_key.push_str("|inc=");
-        cache_key.push_str(&include.join(","));
-    }
-    if !exclude.is_empty() {
-        cache_key.push_str("|exc=");
-        cache_key.push_str(&exclude.join(","));
-    }
-    if let Some(yr) = year_f {
-        cache_key.push_str("|yr=");
-        cache_key.push_str(&yr.to_string());
-    }
-    if let Some(langs) = audio_langs_param.as_ref() {
-        cache_key.push_str("|aud=");
-        cache_key.push_str(&langs.join(","));
-    }
-    if let Some(Some(langs)) = subs_param.as_ref() {
-        cache_key.push_str("|sub=");
-        cache_key.push_str(&langs.join(","));
-    }
-
-    let count: i64 = if let Some(hit) = state.listing_count_cache.get(&cache_key).await {
-        hit
-    } else {
-        let mut cq = sqlx::query_as::<_, CountRow>(&count_query);
-        if let Some(p) = search_pattern {
-            cq = cq.bind(p.to_string());
-        }
-        if !include.is_empty() {
-            cq = cq.bind(include.to_vec());
-        }
-        if !exclude.is_empty() {
-            cq = cq.bind(exclude.to_vec());
-        }
-        if let Some(yr) = year_f {
-            cq = cq.bind(yr);
-        }
-        if let Some(langs) = audio_langs_param.as_ref() {
-            cq = cq.bind(langs.clone());
-        }
-        if let Some(Some(langs)) = subs_param.as_ref() {
-            cq = cq.bind(langs.clone());
-        }
-        let count_row = cq.fetch_one(db).await?;
-        let c = count_row.count.unwrap_or(0);
-        state.listing_count_cache.insert(cache_key, c).await;
-        c
-    };
-
-    // When a search is active, push rows whose title literally contains
-    // the user's query (with diacritics) ahead of rows that match only
-    // after `unaccent()` — see #673. The search pattern is bound at $1.
-    let order_clause = if search_pattern.is_some() {
-        format!(
-            "(CASE WHEN {raw} THEN 0 ELSE 1 END), {order}",
-            raw = mode.raw_predicate(1)
-        )
-    } else {
-        order.to_string()
-    };
-
-    let films_query = format!(
-        "SELECT {FILM_COLUMNS} \
-         FROM films f {where_clause} \
-         ORDER BY {order_clause} \
-         LIMIT ${limit_idx} OFFSET ${offset_idx}",
-        limit_idx = bind_idx,
-        offset_idx = bind_idx + 1
-    );
-    let mut fq = sqlx::query_as::<_, FilmRow>(&films_query);
-    if let Some(p) = search_pattern {
-        fq = fq.bind(p.to_string());
-    }
-    if !include.is_empty() {
-        fq = fq.bind(include.to_vec());
-    }
-    if !exclude.is_empty() {
-        fq = fq.bind(exclude.to_vec());
-    }
-    if let Some(yr) = year_f {
-        fq = fq.bind(yr);
-    }
-    if let Some(langs) = audio_langs_param.as_ref() {
-        fq = fq.bind(langs.clone());
-    }
-    if let Some(Some(langs)) = subs_param.as_ref() {
-        fq = fq.bind(langs.clone());
-    }
-    let films = fq.bind(FILMS_PER_PAGE).bind(offset).fetch_all(db).await?;
-
-    Ok((count, films))
-}
-
-/// GET /filmy-online/{slug}.webp — serve WebP cover image
-pub async fn films_cover(
-    State(state): State<AppState>,
-    Path(slug_webp): Path<String>,
-) -> WebResult<Response> {
-    use crate::handlers::cover_proxy::{
-        fetch_cover, new_r2_key, parse_cover_slug, placeholder_webp,
-    };
-
-    let (slug, _is_large) = parse_cover_slug(&slug_webp);
-
-    #[derive(sqlx::FromRow)]
-    struct CoverRow {
-        id: i32,
-    }
-
-    let row = sqlx::query_as::<_, CoverRow>("SELECT id FROM films WHERE slug = $1")
-        .bind(&slug)
-        .fetch_optional(&state.db)
-        .await?;
-
-    let Some(row) = row else {
-        return Ok(placeholder_webp());
-    };
-
-    let new_key = new_r2_key("films", row.id, false);
-    Ok(fetch_cover(&state, &new_key).await)
-}
-
-/// GET /filmy-online/{slug}-large.webp — large (780×1170) cover.
-///
-/// Mirrors `films_cover` but targets the `{id}/cover-large.webp` key.
-/// If neither the new nor the old R2 key exists we fall back to the
-/// small variant — `images tmdb.org` fetching is now the import
-/// pipeline's job, the handler stays stateless.
-pub async fn films_cover_large(
-    State(state): State<AppState>,
-    Path(slug_webp): Path<String>,
-) -> WebResult<Response> {
-    use crate::handlers::cover_proxy::{new_r2_key, parse_cover_slug, placeholder_webp};
-
-    let (slug, _is_large) = parse_cover_slug(&slug_webp);
-
-    #[derive(sqlx::FromRow)]
-    struct CoverRow {
-        id: i32,
-    }
-
-    let row = sqlx::query_as::<_, CoverRow>("SELECT id FROM films WHERE slug = $1")
-        .bind(&slug)
-        .fetch_optional(&state.db)
-        .await?;
-
-    let Some(row) = row else {
-        return Ok(placeholder_webp());
-    };
-
-    use crate::handlers::cover_proxy::{immutable_webp, no_store_webp, try_fetch_r2};
-    // Tuple: (R2 key, is_small_fallback). Large-variant hits are cached
-    // for a year (`immutable`); small-variant fallbacks are `no-store`
-    // so a later-imported large cover can unseat them without a manual
-    // CF purge.
-    let candidates: Vec<(String, bool)> = vec![
-        (new_r2_key("films", row.id, true), false),
-        (format!("films/large/{slug}.webp"), false),
-        (new_r2_key("films", row.id, false), true),
-    ];
-    for (key, is_small_fallback) in &candidates {
-        if let Some(bytes) = try_fetch_r2(&state, key).await {
-            return Ok(if *is_small_fallback {
-                no_store_webp(bytes)
-            } else {
-                immutable_webp(bytes)
-            });
-        }
-    }
-    Ok(placeholder_webp())
-}
-
-/// GET /filmy-online/{slug}-large.{jpg,png} — proxy TMDB poster on demand.
-///
-/// Detail-page thumbnails get few hits, so we skip R2 storage and stream the
-/// TMDB image through. Cloudflare caches the response for a year, so each
-/// edge fetches TMDB at most once per (edge, poster) — the next visitor is
-/// served from cache. On failure we serve a placeholder in the SAME format
-/// the URL advertises (jpeg for `-large.jpg`, png for `-large.png`) so
-/// browsers and OG scrapers decode without a MIME mismatch — the template's
-/// `og:image:type` is derived from the same URL extension.
-pub async fn films_cover_large_dynamic(
-    State(state): State<AppState>,
-    Path(slug_ext): Path<String>,
-) -> WebResult<Response> {
-    use crate::handlers::cover_proxy::placeholder_for_ext;
-
-    // Strip `-large.jpg` / `-large.png`, whichever the request carried, and
-    // remember the extension so fallbacks can emit bytes of the same type.
-    let (slug, ext) = if let Some(s) = slug_ext.strip_suffix("-large.jpg") {
-        (s, "jpg")
-    } else if let Some(s) = slug_ext.strip_suffix("-large.png") {
-        (s, "png")
-    } else {
-        (slug_ext.as_str(), "jpg")
-    };
-
-    #[derive(sqlx::FromRow)]
-    struct CoverRow {
-        tmdb_poster_path: Option<String>,
-    }
-
-    let row = sqlx::query_as::<_, CoverRow>("SELECT tmdb_poster_path FROM films WHERE slug = $1")
-        .bind(slug)
-        .fetch_optional(&state.db)
-        .await?;
-
-    // Row missing or poster_path not backfilled → placeholder in requested
-    // format. The small-cover (`/filmy-online/{slug}.webp`) route still
-    // serves the list-view thumbnail from R2, so nothing user-visible
-    // depends on this handler synthesising something from R2.
-    let Some(path) = row.and_then(|r| r.tmdb_poster_path) else {
-        return Ok(placeholder_for_ext(ext));
-    };
-
-    let url = format!("https://image.tmdb.org/t/p/w780{path}");
-    let Ok(resp) = state
-        .http_client
-        .get(&url)
-        .timeout(std::time::Duration::from_secs(10))
-        .send()
-        .await
-    else {
-        return Ok(placeholder_for_ext(ext));
-    };
-    if !resp.status().is_success() {
-        return Ok(placeholder_for_ext(ext));
-    }
-    let ct = resp
-        .headers()
-        .get(axum::http::header::CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "image/jpeg".to_string());
-    let Ok(bytes) = resp.bytes().await else {
-        return Ok(placeholder_for_ext(ext));
-    };
-    Ok((
-        StatusCode::OK,
-        [
-            (axum::http::header::CONTENT_TYPE, ct),
-            (
-                axum::http::header::CACHE_CONTROL,
-                "public, max-age=31536000, immutable".to_string(),
-            ),
-        ],
-        bytes.to_vec(),
-    )
-        .into_response())
-}
-
 // --- Sktorrent resolve API ---
 
 #[derive(Deserialize)]
@@ -2047,53 +1183,6 @@ async fn scan_sktorrent_cdns(client: &reqwest::Client, video_id: i32) -> Vec<Skt
 
 // --- Helpers ---
 
-async fn load_genres(db: &sqlx::PgPool) -> Result<Vec<GenreRow>, sqlx::Error> {
-    sqlx::query_as::<_, GenreRow>(
-        "SELECT g.id, g.slug, g.name_cs \
-         FROM genres g \
-         JOIN film_genres fg ON g.id = fg.genre_id \
-         GROUP BY g.id, g.slug, g.name_cs \
-         ORDER BY g.name_cs",
-    )
-    .fetch_all(db)
-    .await
-}
-
-#[derive(sqlx::FromRow)]
-struct FilmGenreJoinRow {
-    film_id: i32,
-    name_cs: String,
-    slug: String,
-}
-
-async fn load_film_genres_map(
-    db: &sqlx::PgPool,
-    films: &[FilmRow],
-) -> Result<std::collections::HashMap<i32, Vec<FilmGenreNameRow>>, sqlx::Error> {
-    if films.is_empty() {
-        return Ok(std::collections::HashMap::new());
-    }
-    let ids: Vec<i32> = films.iter().map(|f| f.id).collect();
-    let rows = sqlx::query_as::<_, FilmGenreJoinRow>(
-        "SELECT fg.film_id, g.name_cs, g.slug \
-         FROM film_genres fg JOIN genres g ON g.id = fg.genre_id \
-         WHERE fg.film_id = ANY($1) \
-         ORDER BY fg.film_id, g.name_cs",
-    )
-    .bind(ids)
-    .fetch_all(db)
-    .await?;
-    let mut map: std::collections::HashMap<i32, Vec<FilmGenreNameRow>> =
-        std::collections::HashMap::new();
-    for r in rows {
-        map.entry(r.film_id).or_default().push(FilmGenreNameRow {
-            name_cs: r.name_cs,
-            slug: r.slug,
-        });
-    }
-    Ok(map)
-}
-
 /// Build pagination query string for film list/genre views.
 /// Consolidates the qs_parts logic that was duplicated in `films_list`
 /// and `films_by_genre`.
@@ -2147,7 +1236,8 @@ fn not_found_response() -> Response {
 
 #[cfg(test)]
 mod tests {
-    use super::{FilmsQuery, FilmsSearchMode, is_active_search, normalize_query};
+    use super::{FilmsQuery, is_active_search};
+    use cr_domain::catalog::{CatalogSort, LocalLanguage, SortDirection, SubtitleFilter};
 
     #[test]
     fn is_active_search_gate_matches_predicate_threshold() {
@@ -2204,159 +1294,96 @@ mod tests {
         assert_eq!(cc, "private, max-age=60", "exact policy required");
     }
 
-    #[test]
-    fn predicate_wraps_columns_in_unaccent() {
-        // Both modes must run unaccent() on the column AND on the bound
-        // pattern — that's how diacritics-insensitive matching works
-        // (#673). Lock the shape so we don't accidentally drop the
-        // wrapper from one side of an ILIKE in a future refactor.
-        let primary = FilmsSearchMode::Primary.predicate(1);
-        assert!(
-            primary.contains("unaccent(f.title) ILIKE unaccent($1)"),
-            "{primary}"
-        );
-        assert!(
-            primary.contains("unaccent(f.original_title) ILIKE unaccent($1)"),
-            "{primary}"
-        );
-
-        let title_year = FilmsSearchMode::TitleYear.predicate(1);
-        assert!(
-            title_year
-                .contains("unaccent(CONCAT_WS(' ', f.title, f.year::text)) ILIKE unaccent($1)"),
-            "{title_year}"
-        );
-        assert!(
-            title_year.contains(
-                "unaccent(CONCAT_WS(' ', f.original_title, f.year::text)) ILIKE unaccent($1)"
-            ),
-            "{title_year}"
-        );
+    fn parse(qs: &str) -> FilmsQuery {
+        let uri: axum::http::Uri = format!("/filmy-online/?{qs}").parse().unwrap();
+        axum::extract::Query::<FilmsQuery>::try_from_uri(&uri)
+            .unwrap()
+            .0
     }
 
     #[test]
-    fn raw_predicate_does_not_use_unaccent() {
-        // raw_predicate is the ORDER BY tiebreaker that keeps
-        // diacritic-exact hits in front. If unaccent() leaks in here,
-        // every row becomes "exact" and the priority bucket collapses.
-        let primary = FilmsSearchMode::Primary.raw_predicate(1);
-        assert!(!primary.contains("unaccent"), "{primary}");
-        assert!(primary.contains("f.title ILIKE $1"), "{primary}");
-        assert!(primary.contains("f.original_title ILIKE $1"), "{primary}");
-
-        let title_year = FilmsSearchMode::TitleYear.raw_predicate(1);
-        assert!(!title_year.contains("unaccent"), "{title_year}");
-        assert!(
-            title_year.contains("CONCAT_WS(' ', f.title, f.year::text) ILIKE $1"),
-            "{title_year}"
+    fn list_query_reads_czech_query_keys() {
+        let params = parse(
+            "strana=3&razeni=imdb&smer=asc&zanry=horor,drama&bez=komedie&rezim=and\
+             &q=%20Vetrelec%20&rok=1979&jazyk=dub&audio=CS,en&titulky=cs",
         );
-    }
-
-    fn query_with_jazyk(jazyk: Option<&str>) -> FilmsQuery {
-        FilmsQuery {
-            strana: None,
-            razeni: None,
-            zanry: None,
-            bez: None,
-            q: None,
-            rok: None,
-            rezim: None,
-            smer: None,
-            jazyk: jazyk.map(|s| s.to_string()),
-            audio: None,
-            titulky: None,
-        }
-    }
-
-    #[test]
-    fn audio_filter_dub_matches_audio_langs_rollup() {
-        let q = query_with_jazyk(Some("dub"));
-        let sql = q.audio_filter().expect("expected filter for dub");
-        assert!(
-            sql.contains("f.audio_langs && ARRAY['cs','sk']"),
-            "sql = {sql}"
+        let query = params.to_list_query();
+        assert_eq!(query.paging.page, 3);
+        assert_eq!(query.paging.offset(), 48);
+        assert_eq!(query.sort, CatalogSort::Imdb);
+        assert_eq!(query.direction, SortDirection::Asc);
+        assert_eq!(query.filter.genres.include, ["horor", "drama"]);
+        assert_eq!(query.filter.genres.exclude, ["komedie"]);
+        assert!(query.filter.genres.match_all);
+        assert_eq!(query.filter.search.unwrap().term, "Vetrelec");
+        assert_eq!(query.filter.year, Some(1979));
+        assert_eq!(query.filter.local_language, Some(LocalLanguage::Dubbed));
+        assert_eq!(query.filter.audio_langs, ["cs", "en"]);
+        assert_eq!(
+            query.filter.subtitles,
+            Some(SubtitleFilter::Languages(vec!["cs".into()]))
         );
-        assert!(!sql.contains("subtitle_langs"), "sql = {sql}");
     }
 
     #[test]
-    fn audio_filter_sub_checks_subtitle_langs_rollup() {
-        let q = query_with_jazyk(Some("sub"));
-        let sql = q.audio_filter().expect("expected filter for sub");
-        assert!(sql.contains("cardinality(f.subtitle_langs)"), "sql = {sql}");
-        assert!(!sql.contains("audio_langs"), "sql = {sql}");
+    fn genre_query_keeps_genre_filters_only() {
+        let params = parse("zanry=drama&rok=1979&jazyk=dub&audio=cs&titulky=any&q=vetrelec");
+        let query = params.to_genre_query("horor");
+        assert!(query.filter.genres.include.contains(&"horor".to_string()));
+        assert!(query.filter.genres.include.contains(&"drama".to_string()));
+        assert_eq!(query.filter.year, Some(1979));
+        assert_eq!(query.filter.local_language, Some(LocalLanguage::Dubbed));
+        assert!(query.filter.search.is_none());
+        assert!(query.filter.audio_langs.is_empty());
+        assert_eq!(query.filter.subtitles, None);
+        assert!(query.filter.include_unavailable);
+        assert!(!params.to_list_query().filter.include_unavailable);
     }
 
     #[test]
-    fn audio_filter_dub_and_sub_unions_both_rollups() {
-        let q = query_with_jazyk(Some("dub,sub"));
-        let sql = q.audio_filter().expect("expected filter for dub,sub");
-        assert!(
-            sql.contains("f.audio_langs && ARRAY['cs','sk']"),
-            "sql = {sql}"
+    fn pagination_links_round_trip_the_cursor() {
+        use cr_domain::catalog::{PageCursors, Seek, SortKey};
+
+        let cursors = PageCursors {
+            first: Some(SortKey(vec![
+                Some("2019".into()),
+                Some("Vetřelec, 2".into()),
+            ])),
+            last: Some(SortKey(vec![None, Some("Žralok".into())])),
+        };
+        let (prev, next) = super::super::keyset_page_hrefs(3, &cursors, "&razeni=rok");
+        assert_eq!(
+            prev,
+            "?strana=2&pred=2019%2CVet%C5%99elec%5C%2C%202&razeni=rok"
         );
-        assert!(sql.contains("cardinality(f.subtitle_langs)"), "sql = {sql}");
-    }
-
-    #[test]
-    fn audio_filter_vse_is_none() {
-        assert!(query_with_jazyk(Some("vse")).audio_filter().is_none());
-    }
+        assert_eq!(next, "?strana=4&po=%2C%C5%BDralok&razeni=rok");
 
-    #[test]
-    fn audio_filter_missing_or_empty_is_none() {
-        assert!(query_with_jazyk(None).audio_filter().is_none());
-        assert!(query_with_jazyk(Some("")).audio_filter().is_none());
-        assert!(query_with_jazyk(Some("   ")).audio_filter().is_none());
-    }
-
-    #[test]
-    fn strips_parens_complete_pair() {
-        assert_eq!(normalize_query("Mstitel (1989)"), "Mstitel 1989");
-        assert_eq!(normalize_query("The Matrix (1999)"), "The Matrix 1999");
-    }
-
-    #[test]
-    fn strips_parens_malformed() {
+        let query = parse(&next[1..]).to_list_query();
         assert_eq!(
-            normalize_query("Marvinův pokoj (1996"),
-            "Marvinův pokoj 1996"
+            query.paging.seek,
+            Some(Seek::After(cursors.last.clone().unwrap()))
         );
+        let query = parse(&prev[1..]).to_list_query();
         assert_eq!(
-            normalize_query("Marvinův pokoj 1996)"),
-            "Marvinův pokoj 1996"
+            query.paging.seek,
+            Some(Seek::Before(cursors.first.clone().unwrap()))
         );
-    }
-
-    #[test]
-    fn collapses_whitespace() {
-        assert_eq!(normalize_query("Mstitel    1989"), "Mstitel 1989");
-        assert_eq!(normalize_query("  Mstitel  (1989)  "), "Mstitel 1989");
-    }
-
-    #[test]
-    fn preserves_partial_year() {
-        assert_eq!(normalize_query("Marvinův pokoj 19"), "Marvinův pokoj 19");
-        assert_eq!(normalize_query("Marvinův pokoj 1"), "Marvinův pokoj 1");
-        assert_eq!(normalize_query("Marvinův pokoj (19"), "Marvinův pokoj 19");
-    }
 
-    #[test]
-    fn preserves_ordinary_queries() {
-        assert_eq!(normalize_query("Marvinův pokoj"), "Marvinův pokoj");
-        assert_eq!(
-            normalize_query("2001: A Space Odyssey"),
-            "2001: A Space Odyssey"
-        );
-        assert_eq!(normalize_query("1984"), "1984");
+        // Back to page 1 is always the plain URL.
+        let (prev, _) = super::super::keyset_page_hrefs(2, &cursors, "");
+        assert_eq!(prev, "?strana=1");
     }
 
     #[test]
-    fn empty_when_only_whitespace_or_parens() {
-        assert_eq!(normalize_query(""), "");
-        assert_eq!(normalize_query("   "), "");
-        assert_eq!(normalize_query("()"), "");
-        assert_eq!(normalize_query("(  )"), "");
+    fn list_query_defaults_when_params_absent() {
+        let params = parse("q=a&rok=abc");
+        let query = params.to_list_query();
+        assert_eq!(query.paging.page, 1);
+        assert_eq!(query.sort, CatalogSort::Added);
+        assert_eq!(query.direction, SortDirection::Desc);
+        assert!(query.filter.search.is_none());
+        assert!(query.filter.year.is_none());
+        assert!(query.filter.genres.is_empty());
     }
 
     #[test]
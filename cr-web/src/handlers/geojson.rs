@@ -32,3 +32,74 @@ pub async fn geojson_orp(State(state): State<AppState>, Path(code): Path<String>
         None => StatusCode::NOT_FOUND.into_response(),
     }
 }
+
+#[derive(serde::Deserialize)]
+pub struct ReverseQuery {
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ReverseRow {
+    municipality_id: i32,
+    municipality_slug: String,
+    orp_slug: String,
+    region_slug: String,
+}
+
+/// GET /api/reverse?lat=&lon= — municipality, ORP, district and region
+/// containing the point, resolved in memory by
+/// `cr_infra::boundaries::ReverseGeocoder`. Each unit carries its
+/// official code and name; the municipality additionally gets its
+/// database id and page URL when the code is known to the catalogue.
+pub async fn api_reverse(
+    State(state): State<AppState>,
+    axum::extract::Query(q): axum::extract::Query<ReverseQuery>,
+) -> WebResult<Response> {
+    let at = cr_domain::Coordinates::new(q.lat, q.lon)
+        .map_err(|e| crate::error::WebError::bad_request(e.to_string()))?;
+    let location = state.reverse_geocoder.lookup(at);
+
+    let row = match &location.municipality {
+        Some(m) => {
+            sqlx::query_as::<_, ReverseRow>(
+                "SELECT m.id AS municipality_id, m.slug AS municipality_slug, \
+                 o.slug AS orp_slug, r.slug AS region_slug \
+                 FROM municipalities m \
+                 JOIN orp o ON m.orp_id = o.id \
+                 JOIN districts d ON o.district_id = d.id \
+                 JOIN regions r ON d.region_id = r.id \
+                 WHERE m.municipality_code = $1",
+            )
+            .bind(&m.code)
+            .fetch_optional(&state.db)
+            .await?
+        }
+        None => None,
+    };
+
+    let mut body = serde_json::to_value(&location)?;
+    if let Some(r) = row {
+        let url = if r.orp_slug == r.municipality_slug {
+            format!("/{}/", r.orp_slug)
+        } else {
+            format!("/{}/{}/", r.orp_slug, r.municipality_slug)
+        };
+        body["municipality"]["id"] = r.municipality_id.into();
+        body["municipality"]["url"] = url.into();
+        body["orp"]["url"] = format!("/{}/", r.orp_slug).into();
+        body["region"]["url"] = format!("/{}/", r.region_slug).into();
+    }
+    body["lat"] = q.lat.into();
+    body["lon"] = q.lon.into();
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        body.to_string(),
+    )
+        .into_response())
+}
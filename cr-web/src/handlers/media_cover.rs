@@ -0,0 +1,432 @@
+//! One cover pipeline for every catalog that has `{slug}.webp`-style
+//! cover URLs (`/filmy-online/`, `/serialy-online/`, `/tv-porady/`).
+//!
+//! A request is described by a [`MediaKind`] (which table / R2 prefix)
+//! and a [`CoverVariant`] parsed from the URL suffix:
+//!
+//!     {slug}.webp         → Small        (200×300, R2 `{prefix}/{id}/cover.webp`)
+//!     {slug}-large.webp   → Large        (780×1170, R2 `{prefix}/{id}/cover-large.webp`)
+//!     {slug}-large.jpg    → Dynamic(Jpg) (TMDB poster streamed through)
+//!     {slug}-large.png    → Dynamic(Png)
+//!
+//! Fallback chain for the WebP variants:
+//!
+//!   1. id-keyed R2 object (built with [`cover_proxy::new_r2_key`])
+//!   2. Large only: the pre-#576 slug-keyed object under `legacy_large_prefix`
+//!   3. Stopgap from an earlier miss (built with
+//!      [`cover_proxy::stopgap_r2_key`]), with a short cache
+//!   4. Generate from `tmdb_poster_path` — fetch the TMDB poster, resize,
+//!      encode WebP, serve it with a short cache (not `immutable` — the
+//!      next import replaces it) and upload it to the stopgap key in the
+//!      background so the next miss hits step 3
+//!   5. Large only: the small variant with `no-store`
+//!   6. 1×1 placeholder
+//!
+//! Adding covers for another catalog is a matter of declaring a new
+//! [`MediaKind`] constant and routing its `.webp` URLs through
+//! [`serve_cover`].
+
+use axum::body::Bytes;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+
+use crate::error::WebResult;
+use crate::handlers::cover_proxy::{
+    fetch_cover, generated_webp, immutable_webp, new_r2_key, no_store_webp, placeholder_for_ext,
+    placeholder_webp, stopgap_r2_key, try_fetch_r2,
+};
+use crate::state::AppState;
+
+/// Catalog-specific bits of the cover pipeline. All fields are static
+/// identifiers, never user input, so they are safe to splice into SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaKind {
+    /// Table holding `id`, `slug` and the poster column.
+    pub table: &'static str,
+    /// Top-level R2 prefix of the id-keyed layout.
+    pub r2_prefix: &'static str,
+    /// Prefix of the legacy `{prefix}/{slug}.webp` large covers, if the
+    /// catalog ever had them.
+    pub legacy_large_prefix: Option<&'static str>,
+    /// Column with the TMDB poster path (`/abc.jpg`), `None` when the
+    /// catalog has no TMDB link and R2 is the only source.
+    pub poster_column: Option<&'static str>,
+}
+
+impl MediaKind {
+    pub const FILM: Self = Self {
+        table: "films",
+        r2_prefix: "films",
+        legacy_large_prefix: Some("films/large"),
+        poster_column: Some("tmdb_poster_path"),
+    };
+    pub const SERIES: Self = Self {
+        table: "series",
+        r2_prefix: "series",
+        legacy_large_prefix: Some("series/large"),
+        poster_column: Some("tmdb_poster_path"),
+    };
+    /// Pre-migration TV show covers shared `series_covers_dir`, so their
+    /// legacy large covers sit under the `series/large/` prefix.
+    pub const TV_SHOW: Self = Self {
+        table: "tv_shows",
+        r2_prefix: "tv-shows",
+        legacy_large_prefix: Some("series/large"),
+        poster_column: Some("tmdb_poster_path"),
+    };
+
+    /// Row lookup by `column` (`slug` or `id`, always a literal).
+    fn lookup_sql(&self, column: &str) -> String {
+        let poster = self.poster_column.unwrap_or("NULL::TEXT");
+        format!(
+            "SELECT id, slug, {poster} AS poster_path FROM {} WHERE {column} = $1",
+            self.table
+        )
+    }
+}
+
+/// Raster format advertised by a dynamic cover URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageExt {
+    Jpg,
+    Png,
+}
+
+impl ImageExt {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Jpg => "jpg",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// Which rendition of a cover the URL asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverVariant {
+    Small,
+    Large,
+    /// TMDB poster proxied in its original format. The extension comes
+    /// from the URL so fallbacks can answer with a placeholder of the same
+    /// MIME type (the template derives `og:image:type` from it).
+    Dynamic(ImageExt),
+}
+
+impl CoverVariant {
+    /// Bounding box of the generated WebP and the TMDB size to fetch for it.
+    fn target(self) -> (u32, u32, &'static str) {
+        match self {
+            Self::Small => (200, 300, "w342"),
+            Self::Large | Self::Dynamic(_) => (780, 1170, "w780"),
+        }
+    }
+}
+
+/// Split a cover path segment into `(slug, variant)`. Returns `None` for
+/// anything that isn't a cover URL, so callers can use it as the routing
+/// test in front of their detail handler.
+pub fn parse_cover_request(path: &str) -> Option<(&str, CoverVariant)> {
+    if let Some(s) = path.strip_suffix("-large.jpg") {
+        Some((s, CoverVariant::Dynamic(ImageExt::Jpg)))
+    } else if let Some(s) = path.strip_suffix("-large.png") {
+        Some((s, CoverVariant::Dynamic(ImageExt::Png)))
+    } else if let Some(s) = path.strip_suffix("-large.webp") {
+        Some((s, CoverVariant::Large))
+    } else {
+        path.strip_suffix(".webp").map(|s| (s, CoverVariant::Small))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CoverRow {
+    id: i32,
+    slug: String,
+    poster_path: Option<String>,
+}
+
+/// Serve the cover for `slug` of the given kind. Never 404s — every miss
+/// ends in a placeholder of the requested format.
+pub async fn serve_cover(
+    state: &AppState,
+    kind: MediaKind,
+    slug: &str,
+    variant: CoverVariant,
+) -> WebResult<Response> {
+    let row = sqlx::query_as::<_, CoverRow>(&kind.lookup_sql("slug"))
+        .bind(slug)
+        .fetch_optional(&state.db)
+        .await?;
+
+    let Some(row) = row else {
+        return Ok(match variant {
+            CoverVariant::Dynamic(ext) => placeholder_for_ext(ext.as_str()),
+            _ => placeholder_webp(),
+        });
+    };
+    let poster_path = row.poster_path.as_deref().filter(|p| !p.is_empty());
+
+    Ok(match variant {
+        CoverVariant::Dynamic(ext) => proxy_tmdb(state, poster_path, ext).await,
+        CoverVariant::Small => {
+            let key = new_r2_key(kind.r2_prefix, row.id, false);
+            if poster_path.is_none() {
+                return Ok(fetch_cover(state, &key).await);
+            }
+            if let Some(bytes) = try_fetch_r2(state, &key).await {
+                return Ok(immutable_webp(bytes));
+            }
+            let stopgap = stopgap_r2_key(kind.r2_prefix, row.id, false);
+            if let Some(bytes) = try_fetch_r2(state, &stopgap).await {
+                return Ok(generated_webp(bytes));
+            }
+            match generate(state, poster_path, variant, stopgap).await {
+                Some(bytes) => generated_webp(bytes),
+                None => placeholder_webp(),
+            }
+        }
+        CoverVariant::Large => match large_cover(state, kind, &row).await {
+            LargeCover::Stored(bytes) => immutable_webp(bytes),
+            LargeCover::Generated(bytes) => generated_webp(bytes),
+            // Small variant under the `-large.webp` URL must be `no-store`
+            // so a later-imported large cover can unseat it without a
+            // manual CF purge.
+            LargeCover::Small(bytes) => no_store_webp(bytes),
+            LargeCover::Missing => placeholder_webp(),
+        },
+    })
+}
+
+/// Outcome of steps 1–4 of the chain for the large variant.
+pub enum LargeCover {
+    /// Id-keyed or legacy R2 object — what the URL keeps serving.
+    Stored(Vec<u8>),
+    /// Built from the TMDB poster, now or on an earlier miss — a stopgap
+    /// until the importer uploads the real cover.
+    Generated(Vec<u8>),
+    /// Only the small cover exists; a stand-in until a large one does.
+    Small(Vec<u8>),
+    Missing,
+}
+
+/// Resolve the large cover of the entity with `id` through the same chain
+/// as `{slug}-large.webp`. `None` when the entity doesn't exist.
+pub async fn large_cover_by_id(
+    state: &AppState,
+    kind: MediaKind,
+    id: i32,
+) -> WebResult<Option<LargeCover>> {
+    let row = sqlx::query_as::<_, CoverRow>(&kind.lookup_sql("id"))
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?;
+    Ok(match row {
+        Some(row) => Some(large_cover(state, kind, &row).await),
+        None => None,
+    })
+}
+
+async fn large_cover(state: &AppState, kind: MediaKind, row: &CoverRow) -> LargeCover {
+    let key = new_r2_key(kind.r2_prefix, row.id, true);
+    if let Some(bytes) = try_fetch_r2(state, &key).await {
+        return LargeCover::Stored(bytes);
+    }
+    if let Some(prefix) = kind.legacy_large_prefix
+        && let Some(bytes) = try_fetch_r2(state, &format!("{prefix}/{}.webp", row.slug)).await
+    {
+        return LargeCover::Stored(bytes);
+    }
+    let stopgap = stopgap_r2_key(kind.r2_prefix, row.id, true);
+    if let Some(bytes) = try_fetch_r2(state, &stopgap).await {
+        return LargeCover::Generated(bytes);
+    }
+    let poster_path = row.poster_path.as_deref().filter(|p| !p.is_empty());
+    if let Some(bytes) = generate(state, poster_path, CoverVariant::Large, stopgap).await {
+        return LargeCover::Generated(bytes);
+    }
+    let small = new_r2_key(kind.r2_prefix, row.id, false);
+    match try_fetch_r2(state, &small).await {
+        Some(bytes) => LargeCover::Small(bytes),
+        None => LargeCover::Missing,
+    }
+}
+
+async fn fetch_tmdb(state: &AppState, size: &str, path: &str) -> Option<(String, Bytes)> {
+    let url = format!("https://image.tmdb.org/t/p/{size}{path}");
+    let resp = state
+        .http_client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    let ct = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("image/jpeg")
+        .to_string();
+    let bytes = resp.bytes().await.ok()?;
+    Some((ct, bytes))
+}
+
+/// Stream the TMDB poster through unchanged. Detail-page thumbnails get
+/// few hits, so nothing is stored; Cloudflare caches the response for a
+/// year, so each edge fetches TMDB at most once per poster.
+async fn proxy_tmdb(state: &AppState, poster_path: Option<&str>, ext: ImageExt) -> Response {
+    let Some(path) = poster_path else {
+        return placeholder_for_ext(ext.as_str());
+    };
+    let (_, _, size) = CoverVariant::Dynamic(ext).target();
+    let Some((ct, bytes)) = fetch_tmdb(state, size, path).await else {
+        return placeholder_for_ext(ext.as_str());
+    };
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, ct),
+            (
+                header::CACHE_CONTROL,
+                "public, max-age=31536000, immutable".to_string(),
+            ),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+/// Build a missing WebP variant from the TMDB poster. The encoded bytes
+/// are returned straight away; the upload to the stopgap `key` runs in
+/// the background and is skipped when R2 credentials aren't provisioned
+/// (dev).
+async fn generate(
+    state: &AppState,
+    poster_path: Option<&str>,
+    variant: CoverVariant,
+    key: String,
+) -> Option<Vec<u8>> {
+    let path = poster_path?;
+    let (w, h, size) = variant.target();
+    let (_, source) = fetch_tmdb(state, size, path).await?;
+    let encoded = tokio::task::spawn_blocking(move || encode_cover(&source, w, h))
+        .await
+        .ok()??;
+
+    if let Some(r2) = state.r2_client.clone() {
+        let body = Bytes::from(encoded.clone());
+        tokio::spawn(async move {
+            match r2.upload_thumbnail(&key, body, "image/webp").await {
+                Ok(_) => tracing::info!("Generated cover {key}"),
+                Err(e) => tracing::warn!("Cover upload {key} failed: {e}"),
+            }
+        });
+    }
+    Some(encoded)
+}
+
+/// Smallest side a decoded poster may have. TMDB placeholders and
+/// half-transferred bodies decode to tiny frames — refuse to persist
+/// those rather than pin a junk cover on R2 (mirrors the importer's
+/// `_validate_poster`).
+const MIN_POSTER_SIDE: u32 = 100;
+
+/// Decode, validate and downscale a poster to fit `max_w`×`max_h`, then
+/// encode it as WebP. `image` only ships a lossless WebP encoder, so the
+/// output is larger than the importer's lossy files; the next import run
+/// replaces it.
+fn encode_cover(source: &[u8], max_w: u32, max_h: u32) -> Option<Vec<u8>> {
+    let img = image::load_from_memory(source).ok()?;
+    let (w, h) = (img.width(), img.height());
+    let aspect = w as f32 / h.max(1) as f32;
+    if w < MIN_POSTER_SIDE || h < MIN_POSTER_SIDE || !(0.4..=1.0).contains(&aspect) {
+        return None;
+    }
+    let img = if w > max_w || h > max_h {
+        img.resize(max_w, max_h, image::imageops::FilterType::Lanczos3)
+    } else {
+        img
+    };
+    let rgb = img.to_rgb8();
+    let mut buf = Vec::new();
+    image::codecs::webp::WebPEncoder::new_lossless(&mut buf)
+        .encode(
+            rgb.as_raw(),
+            rgb.width(),
+            rgb.height(),
+            image::ExtendedColorType::Rgb8,
+        )
+        .ok()?;
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_cover_suffix() {
+        assert_eq!(
+            parse_cover_request("matrix.webp"),
+            Some(("matrix", CoverVariant::Small))
+        );
+        assert_eq!(
+            parse_cover_request("matrix-large.webp"),
+            Some(("matrix", CoverVariant::Large))
+        );
+        assert_eq!(
+            parse_cover_request("matrix-large.jpg"),
+            Some(("matrix", CoverVariant::Dynamic(ImageExt::Jpg)))
+        );
+        assert_eq!(
+            parse_cover_request("matrix-large.png"),
+            Some(("matrix", CoverVariant::Dynamic(ImageExt::Png)))
+        );
+    }
+
+    #[test]
+    fn non_cover_paths_are_not_parsed() {
+        assert_eq!(parse_cover_request("matrix"), None);
+        assert_eq!(parse_cover_request("akcni"), None);
+        // Only `-large` jpg/png are cover URLs; a bare `.jpg` is not.
+        assert_eq!(parse_cover_request("matrix.jpg"), None);
+    }
+
+    #[test]
+    fn lookup_sql_uses_static_table_and_poster_column() {
+        assert_eq!(
+            MediaKind::TV_SHOW.lookup_sql("slug"),
+            "SELECT id, slug, tmdb_poster_path AS poster_path FROM tv_shows WHERE slug = $1"
+        );
+        let no_poster = MediaKind {
+            poster_column: None,
+            ..MediaKind::FILM
+        };
+        assert!(
+            no_poster
+                .lookup_sql("id")
+                .contains("NULL::TEXT AS poster_path")
+        );
+        assert!(no_poster.lookup_sql("id").ends_with("WHERE id = $1"));
+    }
+
+    #[test]
+    fn encode_cover_downscales_and_rejects_bad_aspect() {
+        let poster = image::RgbImage::from_pixel(400, 600, image::Rgb([200, 10, 10]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(poster)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let webp = encode_cover(&png, 200, 300).expect("valid poster encodes");
+        let decoded = image::load_from_memory(&webp).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (200, 300));
+
+        let banner = image::RgbImage::from_pixel(800, 200, image::Rgb([0, 0, 0]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(banner)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        assert!(encode_cover(&png, 780, 1170).is_none());
+    }
+}
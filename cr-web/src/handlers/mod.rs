@@ -13,22 +13,33 @@ pub mod admin_backups;
 pub mod admin_cache;
 pub mod admin_dashboard;
 pub mod admin_import;
+pub mod admin_photos;
 pub mod admin_prehrajto;
 mod admin_test_sledujteto;
+pub mod admin_video_queue;
 mod audiobooks;
+mod calendar;
+mod collections;
 pub mod cover_proxy;
 mod csfd_watchlist;
 mod download_video;
+mod export;
+mod feeds;
 mod films;
 mod filmy_serialy;
 mod geojson;
 mod landmarks;
+pub mod media_cover;
 pub mod movies_api;
 mod municipalities;
+mod og_image;
 mod orp;
+mod people;
 mod pools;
 mod regions;
 mod series;
+mod suggest;
+mod tiles;
 mod tv_porady;
 pub mod video_api;
 mod video_sources;
@@ -37,24 +48,37 @@ mod voices;
 // Re-export all public handlers so main.rs doesn't need changes
 pub use admin_test_sledujteto::admin_test_sledujteto;
 pub use audiobooks::audiobooks;
+pub use calendar::{episode_calendar, series_ics};
+pub use collections::collection_detail;
 pub use csfd_watchlist::csfd_watchlist;
 pub use download_video::download_video;
+pub use export::{export_dataset, export_manifest};
+pub use feeds::{
+    film_genre_feed, films_feed, landmarks_feed, municipalities_feed, series_feed,
+    series_slug_feed, tv_porady_feed,
+};
 pub use films::{
     SktorrentSource, films_detail, films_list, films_person_image, films_search, sktorrent_resolve,
 };
 pub use filmy_serialy::filmy_serialy;
-pub use geojson::{geojson_municipality, geojson_orp};
+pub use geojson::{api_reverse, geojson_municipality, geojson_orp};
 pub use landmarks::{api_landmarks, landmarks_by_url, landmarks_index};
+pub use og_image::og_image;
+pub use people::{
+    people_index, people_search, people_sitemap_index, people_sitemap_page, person_detail,
+};
 pub use pools::{pools_by_category, pools_hub};
 pub use series::{
     episode_detail, series_episode_still, series_list, series_person_image, series_resolve,
     series_search,
 };
+pub use suggest::{api_suggest, opensearch_description, search_page};
+pub use tiles::vector_tile;
 pub use tv_porady::{tv_epizoda_detail, tv_porad_detail, tv_porady_list, tv_porady_search};
 pub use video_api::{
     library_delete, library_file, library_list, library_play, library_stream, video_cleanup,
     video_file, video_file_part, video_info, video_prepare, video_recent, video_status,
-    video_thumb,
+    video_status_events, video_thumb,
 };
 pub use voices::prevod_textu_na_hlas;
 
@@ -81,6 +105,14 @@ pub use voices::prevod_textu_na_hlas;
 /// content.
 const SEARCH_CACHE_CONTROL: &str = "private, max-age=60";
 
+/// Public origin of the site, for absolute URLs in feeds, sitemaps,
+/// JSON-LD and other machine-read output.
+pub(crate) const SITE_ORIGIN: &str = "https://ceskarepublika.wiki";
+
+/// Cards in the "Podobné tituly" carousel on film and series detail
+/// pages. The job stores more per title so a few dead sources don't empty it.
+pub(crate) const SIMILAR_CAROUSEL_LEN: i64 = 12;
+
 /// Wrap a JSON-serializable body with the standard search cache
 /// header. See `SEARCH_CACHE_CONTROL` for the rationale.
 pub(crate) fn search_cached_json<T: serde::Serialize>(body: T) -> Response {
@@ -98,6 +130,41 @@ pub(crate) fn search_cached_html(html: String) -> Response {
     ([(header::CACHE_CONTROL, SEARCH_CACHE_CONTROL)], Html(html)).into_response()
 }
 
+/// «/» hrefs of a keyset-paginated listing page. Each carries the sort key
+/// of the row next to it (`po=` / `pred=`), so the next page is read from
+/// that row instead of skipping all the ones before it; page 1 is always
+/// the plain URL. `query_string` is the `&…` tail with the filters.
+pub(crate) fn keyset_page_hrefs(
+    page: i64,
+    cursors: &cr_domain::catalog::PageCursors,
+    query_string: &str,
+) -> (String, String) {
+    let prev = match &cursors.first {
+        Some(key) if page > 2 => format!(
+            "?strana={}&pred={}{query_string}",
+            page - 1,
+            urlencoding::encode(&key.encode())
+        ),
+        _ => format!("?strana={}{query_string}", (page - 1).max(1)),
+    };
+    let next = match &cursors.last {
+        Some(key) => format!(
+            "?strana={}&po={}{query_string}",
+            page + 1,
+            urlencoding::encode(&key.encode())
+        ),
+        None => format!("?strana={}{query_string}", page + 1),
+    };
+    (prev, next)
+}
+
+/// Serialise a schema.org object for a `<script type="application/ld+json">`
+/// block. `</` is escaped so a title containing `</script>` cannot close
+/// the block early; templates render the result with `|safe`.
+pub(crate) fn json_ld(value: &serde_json::Value) -> String {
+    value.to_string().replace("</", "<\\/")
+}
+
 // --- DB row types ---
 
 #[derive(sqlx::FromRow)]
@@ -273,6 +340,8 @@ impl From<cr_domain::repository::PoolRecord> for PoolDetailRow {
 // --- Photo info for gallery display ---
 
 pub(crate) struct PhotoInfo {
+    /// R2 object key — looked up in `photo_hashes` for duplicate suppression.
+    pub(crate) r2_key: String,
     pub(crate) url: String,
     pub(crate) thumb_url: String,
     #[allow(dead_code)] // Stored for future responsive img srcset generation
@@ -325,6 +394,7 @@ pub(crate) async fn fetch_photos(
             };
             let thumb_url = format!("{}?w=360", &url);
             PhotoInfo {
+                r2_key: r.r2_key,
                 url,
                 thumb_url,
                 width: r.width,
@@ -343,12 +413,45 @@ pub(crate) async fn fetch_photos(
         photos.extend(extra);
     }
 
+    suppress_duplicate_photos(state, photos, |p| &p.r2_key).await
+}
+
+/// Drop near-duplicates from a gallery when `GALLERY_SUPPRESS_DUPLICATES=1`.
+///
+/// Looks every photo up in the perceptual-hash index and keeps the first
+/// occurrence of each near-duplicate group in display order (see
+/// [`cr_infra::photo_hash::keep_mask`]). With the flag off, or when the
+/// index lookup fails, the gallery is returned untouched — a duplicate
+/// photo is a cosmetic issue, an empty gallery is not.
+pub(crate) async fn suppress_duplicate_photos<T>(
+    state: &AppState,
+    photos: Vec<T>,
+    r2_key: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    if !state.config.gallery_suppress_duplicates || photos.len() < 2 {
+        return photos;
+    }
+    let keys: Vec<String> = photos.iter().map(|p| r2_key(p).to_string()).collect();
+    let known = match state.photo_hashes.hashes_for_keys(&keys).await {
+        Ok(m) => m,
+        Err(e) => {
+            tracing::error!("suppress_duplicate_photos hash lookup failed: {e}");
+            return photos;
+        }
+    };
+    let hashes: Vec<Option<u64>> = keys.iter().map(|k| known.get(k).copied()).collect();
+    let mask = cr_infra::photo_hash::keep_mask(&hashes, cr_infra::photo_hash::DEFAULT_MAX_DISTANCE);
     photos
+        .into_iter()
+        .zip(mask)
+        .filter_map(|(p, keep)| keep.then_some(p))
+        .collect()
 }
 
 #[derive(sqlx::FromRow)]
 struct LandmarkPhotoRow {
     slug: String,
+    r2_key: String,
     width: Option<i16>,
     height: Option<i16>,
 }
@@ -361,7 +464,7 @@ async fn fetch_landmark_additional_photos(
     municipality_slug: Option<&str>,
 ) -> Vec<PhotoInfo> {
     let rows = sqlx::query_as::<_, LandmarkPhotoRow>(
-        "SELECT slug, width, height FROM landmark_photos \
+        "SELECT slug, r2_key, width, height FROM landmark_photos \
          WHERE npu_catalog_id = $1 ORDER BY photo_index",
     )
     .bind(npu_catalog_id)
@@ -386,6 +489,7 @@ async fn fetch_landmark_additional_photos(
             };
             let thumb_url = format!("{}?w=360", &url);
             PhotoInfo {
+                r2_key: r.r2_key,
                 url,
                 thumb_url,
                 width: r.width.unwrap_or(0),
@@ -465,6 +569,8 @@ pub(crate) struct MunicipalityTemplate {
 }
 
 pub(crate) struct MunicipalityPhotoInfo {
+    /// R2 object key — looked up in `photo_hashes` for duplicate suppression.
+    pub(crate) r2_key: String,
     pub(crate) url: String,
     pub(crate) thumb_url: String,
     pub(crate) description: String,
@@ -473,6 +579,7 @@ pub(crate) struct MunicipalityPhotoInfo {
 #[derive(sqlx::FromRow)]
 struct MunicipalityPhotoRow {
     slug: String,
+    r2_key: String,
     description: Option<String>,
     object_name: Option<String>,
 }
@@ -484,7 +591,7 @@ pub(crate) async fn fetch_municipality_photo(
     municipality_slug: &str,
 ) -> Option<MunicipalityPhotoInfo> {
     let row = sqlx::query_as::<_, MunicipalityPhotoRow>(
-        "SELECT slug, description, object_name FROM municipality_photos \
+        "SELECT slug, r2_key, description, object_name FROM municipality_photos \
          WHERE municipality_code = $1 AND is_primary = true \
          ORDER BY photo_index LIMIT 1",
     )
@@ -505,6 +612,7 @@ pub(crate) async fn fetch_municipality_photo(
     let description = row.description.or(row.object_name).unwrap_or_default();
 
     Some(MunicipalityPhotoInfo {
+        r2_key: row.r2_key,
         url,
         thumb_url,
         description,
@@ -518,7 +626,7 @@ pub(crate) async fn fetch_municipality_gallery(
     municipality_slug: &str,
 ) -> Vec<MunicipalityPhotoInfo> {
     let rows = sqlx::query_as::<_, MunicipalityPhotoRow>(
-        "SELECT slug, description, object_name FROM municipality_photos \
+        "SELECT slug, r2_key, description, object_name FROM municipality_photos \
          WHERE municipality_code = $1 AND is_primary = false \
          ORDER BY photo_index",
     )
@@ -540,6 +648,7 @@ pub(crate) async fn fetch_municipality_gallery(
             let thumb_url = format!("{}?w=360", &url);
             let description = row.description.or(row.object_name).unwrap_or_default();
             MunicipalityPhotoInfo {
+                r2_key: row.r2_key,
                 url,
                 thumb_url,
                 description,
@@ -651,7 +760,6 @@ pub(crate) struct PoolListRow {
 
 #[derive(sqlx::FromRow)]
 pub(crate) struct PoolDetailRow {
-    #[allow(dead_code)] // Primary key; carried from PoolRecord, not rendered in template
     pub(crate) id: i32,
     pub(crate) name: String,
     pub(crate) slug: String,
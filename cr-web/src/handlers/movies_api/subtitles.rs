@@ -1,6 +1,7 @@
 use axum::extract::{Query, State};
 use axum::http::{StatusCode, header};
 use axum::response::IntoResponse;
+use cr_infra::video::{SubtitleFormat, Subtitles};
 use serde::{Deserialize, Serialize};
 
 use crate::state::AppState;
@@ -17,16 +18,30 @@ pub struct SubtitleTrack {
 #[derive(Deserialize)]
 pub struct SubtitleQuery {
     pub url: String,
+    /// `vtt` (default), `srt` or `ass`.
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
 // --- Handler ---
 
-/// Proxy VTT subtitle files from premiumcdn.net through our domain.
-/// HTML5 <track> elements require CORS headers that CDN doesn't provide.
+/// Proxy subtitle files from premiumcdn.net and sledujteto.cz through our
+/// domain. HTML5 <track> elements require CORS headers that CDN doesn't
+/// provide. UTF-8 WebVTT goes out byte for byte, keeping cue settings,
+/// voice spans and STYLE blocks; SRT/ASS sources and Windows-1250 files
+/// are converted to WebVTT for `<track>`, and an explicit `format` always
+/// converts.
 pub async fn movies_subtitle(
     State(state): State<AppState>,
     Query(query): Query<SubtitleQuery>,
 ) -> impl IntoResponse {
+    let format = match query.format.as_deref() {
+        None => SubtitleFormat::Vtt,
+        Some(f) => match SubtitleFormat::parse(f) {
+            Some(format) => format,
+            None => return (StatusCode::BAD_REQUEST, "Unknown subtitle format").into_response(),
+        },
+    };
     let parsed = match reqwest::Url::parse(&query.url) {
         Ok(u) => u,
         Err(_) => return (StatusCode::BAD_REQUEST, "Invalid URL").into_response(),
@@ -67,22 +82,32 @@ pub async fn movies_subtitle(
         .send()
         .await;
 
-    match resp {
+    let bytes = match resp {
         Ok(r) if r.status().is_success() => match r.bytes().await {
-            Ok(bytes) => (
-                StatusCode::OK,
-                [
-                    (header::CONTENT_TYPE, "text/vtt; charset=utf-8".to_string()),
-                    (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*".to_string()),
-                    (header::CACHE_CONTROL, "public, max-age=3600".to_string()),
-                ],
-                bytes,
-            )
-                .into_response(),
-            Err(_) => (StatusCode::BAD_GATEWAY, "Failed to read subtitle").into_response(),
+            Ok(bytes) => bytes,
+            Err(_) => return (StatusCode::BAD_GATEWAY, "Failed to read subtitle").into_response(),
         },
-        _ => (StatusCode::NOT_FOUND, "Subtitle not available").into_response(),
+        _ => return (StatusCode::NOT_FOUND, "Subtitle not available").into_response(),
+    };
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*".to_string()),
+        (header::CACHE_CONTROL, "public, max-age=3600".to_string()),
+    ];
+    if query.format.is_none() && Subtitles::is_utf8_vtt(&bytes) {
+        return (StatusCode::OK, headers, bytes).into_response();
     }
+    let subtitles = match Subtitles::decode(&bytes) {
+        Ok(subtitles) => subtitles,
+        Err(e) => {
+            tracing::warn!(
+                "movies_subtitle: unreadable subtitles at {}: {e}",
+                query.url
+            );
+            return (StatusCode::BAD_GATEWAY, "Invalid subtitle file").into_response();
+        }
+    };
+    (StatusCode::OK, headers, subtitles.render(format)).into_response()
 }
 
 // --- Subtitle extraction helpers ---
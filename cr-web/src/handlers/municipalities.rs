@@ -104,6 +104,23 @@ pub(crate) async fn render_municipality(
     )
     .await;
 
+    // The primary photo takes part in duplicate suppression too (Wikipedia
+    // galleries often repeat the infobox image), and as the first entry it
+    // is always the one that survives.
+    let has_primary = photo.is_some();
+    let mut photos = suppress_duplicate_photos(
+        state,
+        photo.into_iter().chain(gallery_photos).collect(),
+        |p| &p.r2_key,
+    )
+    .await;
+    let photo = if has_primary {
+        Some(photos.remove(0))
+    } else {
+        None
+    };
+    let gallery_photos = photos;
+
     let tmpl = MunicipalityTemplate {
         img: state.image_base_url.clone(),
         region: region_row,
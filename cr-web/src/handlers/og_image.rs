@@ -0,0 +1,420 @@
+//! `/og/{kind}/{id}.png` — Open Graph share cards for detail pages.
+//!
+//! The handler resolves the entity to a [`Card`] (title, breadcrumb and
+//! the R2 key of its primary photo or cover), then:
+//!
+//!   1. serves `og/{kind}/{id}-{fingerprint}.png` from R2 when it exists
+//!   2. otherwise fetches the artwork, renders the card in a blocking
+//!      task, serves it and uploads it to that key in the background
+//!
+//! The key is derived from the card content (see [`Card::r2_key`]), so
+//! the R2 object itself never goes stale — the public URL stays id-based
+//! with a one-day edge cache, and a renamed entity or a new primary photo
+//! shows up in previews after that.
+//!
+//! Film, series and TV show artwork — for episodes, their show's — goes
+//! through the large-cover chain of `media_cover` (R2, legacy key,
+//! TMDB stopgap, small cover). The fingerprint names the importer's cover
+//! key, so only a card drawn from a stored cover is persisted; one built
+//! on the TMDB stopgap or the small stand-in gets a short cache instead,
+//! and the real cover replaces it once it exists.
+//!
+//! When the artwork exists in the database but can't be fetched right
+//! now, the text-only card is served with `no-store` and not persisted —
+//! same reasoning as the cover placeholders in `cover_proxy`.
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+
+use crate::error::{WebError, WebResult};
+use crate::handlers::cover_proxy::{new_r2_key, try_fetch_r2};
+use crate::handlers::media_cover::{self, LargeCover, MediaKind};
+use crate::og_image::{self, Artwork, Card};
+use crate::state::AppState;
+
+/// Entity types with share cards. The string form is both the URL segment
+/// and the R2 prefix segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OgKind {
+    Landmark,
+    Municipality,
+    Film,
+    Series,
+    TvShow,
+    Episode,
+    TvEpisode,
+    Pool,
+}
+
+impl OgKind {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "landmark" => Some(Self::Landmark),
+            "municipality" => Some(Self::Municipality),
+            "film" => Some(Self::Film),
+            "series" => Some(Self::Series),
+            "tv-show" => Some(Self::TvShow),
+            "episode" => Some(Self::Episode),
+            "tv-episode" => Some(Self::TvEpisode),
+            "pool" => Some(Self::Pool),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Landmark => "landmark",
+            Self::Municipality => "municipality",
+            Self::Film => "film",
+            Self::Series => "series",
+            Self::TvShow => "tv-show",
+            Self::Episode => "episode",
+            Self::TvEpisode => "tv-episode",
+            Self::Pool => "pool",
+        }
+    }
+
+    /// Films, series and shows have portrait posters; places have photos.
+    fn is_poster(self) -> bool {
+        self.media().is_some()
+    }
+
+    /// Catalog whose cover the card shows — the parent's for episodes.
+    fn media(self) -> Option<MediaKind> {
+        match self {
+            Self::Film => Some(MediaKind::FILM),
+            Self::Series | Self::Episode => Some(MediaKind::SERIES),
+            Self::TvShow | Self::TvEpisode => Some(MediaKind::TV_SHOW),
+            Self::Landmark | Self::Municipality | Self::Pool => None,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PlaceCardRow {
+    title: String,
+    region: Option<String>,
+    orp: Option<String>,
+    municipality: Option<String>,
+    artwork_key: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct MediaCardRow {
+    title: String,
+    year: Option<i16>,
+}
+
+#[derive(sqlx::FromRow)]
+struct EpisodeCardRow {
+    parent_id: i32,
+    show: String,
+    season: i16,
+    episode: i16,
+    episode_name: Option<String>,
+}
+
+pub async fn og_image(
+    State(state): State<AppState>,
+    Path((kind, file)): Path<(String, String)>,
+) -> WebResult<Response> {
+    let kind = OgKind::parse(&kind).ok_or_else(|| WebError::not_found("Unknown card type"))?;
+    let id: i32 = file
+        .strip_suffix(".png")
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| WebError::not_found("Card not found"))?;
+    let (card, cover_id) = load_card(&state, kind, id)
+        .await?
+        .ok_or_else(|| WebError::not_found("Card not found"))?;
+
+    let key = card.r2_key();
+    if let Some(bytes) = try_fetch_r2(&state, &key).await {
+        return Ok(png_response(bytes, CACHE_PERSISTED));
+    }
+
+    let (source, cache) = match (kind.media(), &card.artwork_key) {
+        (Some(media), _) => match media_cover::large_cover_by_id(&state, media, cover_id).await? {
+            Some(LargeCover::Stored(b)) => (Some(b), CACHE_PERSISTED),
+            Some(LargeCover::Generated(b) | LargeCover::Small(b)) => (Some(b), CACHE_PROVISIONAL),
+            Some(LargeCover::Missing) | None => (None, CACHE_NONE),
+        },
+        (None, Some(artwork_key)) => match try_fetch_r2(&state, artwork_key).await {
+            Some(b) => (Some(b), CACHE_PERSISTED),
+            None => (None, CACHE_NONE),
+        },
+        (None, None) => (None, CACHE_PERSISTED),
+    };
+    let complete = cache == CACHE_PERSISTED;
+    let render_card = card.clone();
+    let png = tokio::task::spawn_blocking(move || {
+        let artwork = match source.and_then(|b| image::load_from_memory(&b).ok()) {
+            Some(img) if kind.is_poster() => Artwork::Poster(img),
+            Some(img) => Artwork::Photo(img),
+            None => Artwork::None,
+        };
+        og_image::render(&render_card, artwork)
+    })
+    .await??;
+
+    if complete && let Some(r2) = state.r2_client.clone() {
+        let body = Bytes::from(png.clone());
+        tokio::spawn(async move {
+            match r2.upload_thumbnail(&key, body, "image/png").await {
+                Ok(_) => tracing::info!("Generated share card {key}"),
+                Err(e) => tracing::warn!("Share card upload {key} failed: {e}"),
+            }
+        });
+    }
+    Ok(png_response(png, cache))
+}
+
+/// Final card: persisted to R2, one-day edge cache.
+const CACHE_PERSISTED: &str = "public, max-age=86400";
+/// Built on a stopgap or small cover: not persisted, re-rendered hourly
+/// at most.
+const CACHE_PROVISIONAL: &str = "public, max-age=3600";
+/// Artwork expected but unavailable right now.
+const CACHE_NONE: &str = "no-store";
+
+fn png_response(bytes: Vec<u8>, cache: &'static str) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, cache),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+/// The card and the id whose cover it shows (the parent's for episodes).
+async fn load_card(state: &AppState, kind: OgKind, id: i32) -> WebResult<Option<(Card, i32)>> {
+    let card = |title: String, breadcrumb: Vec<String>, artwork_key: Option<String>| Card {
+        kind: kind.as_str(),
+        id,
+        title,
+        breadcrumb,
+        artwork_key,
+    };
+    let card = match kind {
+        OgKind::Landmark => sqlx::query_as::<_, PlaceCardRow>(
+            "SELECT l.name AS title, r.name AS region, o.name AS orp, m.name AS municipality, \
+             (SELECT pm.r2_key FROM photo_metadata pm \
+              WHERE pm.entity_type = 'landmark' AND pm.entity_id = l.id \
+              ORDER BY pm.photo_index LIMIT 1) AS artwork_key \
+             FROM landmarks l \
+             LEFT JOIN municipalities m ON l.municipality_id = m.id \
+             LEFT JOIN orp o ON m.orp_id = o.id \
+             LEFT JOIN districts d ON o.district_id = d.id \
+             LEFT JOIN regions r ON d.region_id = r.id \
+             WHERE l.id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let crumbs = place_breadcrumb(&r.title, [r.region, r.orp, r.municipality]);
+            (card(r.title, crumbs, r.artwork_key), id)
+        }),
+        OgKind::Municipality => sqlx::query_as::<_, PlaceCardRow>(
+            "SELECT m.name AS title, r.name AS region, o.name AS orp, NULL::TEXT AS municipality, \
+             (SELECT mp.r2_key FROM municipality_photos mp \
+              WHERE mp.municipality_code = m.municipality_code AND mp.is_primary = true \
+              ORDER BY mp.photo_index LIMIT 1) AS artwork_key \
+             FROM municipalities m \
+             JOIN orp o ON m.orp_id = o.id \
+             JOIN districts d ON o.district_id = d.id \
+             JOIN regions r ON d.region_id = r.id \
+             WHERE m.id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let crumbs = place_breadcrumb(&r.title, [r.region, r.orp, r.municipality]);
+            (card(r.title, crumbs, r.artwork_key), id)
+        }),
+        OgKind::Pool => sqlx::query_as::<_, PlaceCardRow>(
+            "SELECT p.name AS title, r.name AS region, o.name AS orp, m.name AS municipality, \
+             (SELECT pm.r2_key FROM photo_metadata pm \
+              WHERE pm.entity_type = 'pool' AND pm.entity_id = p.id \
+              ORDER BY pm.photo_index LIMIT 1) AS artwork_key \
+             FROM pools p \
+             LEFT JOIN municipalities m ON p.municipality_id = m.id \
+             LEFT JOIN orp o ON p.orp_id = o.id \
+             LEFT JOIN districts d ON o.district_id = d.id \
+             LEFT JOIN regions r ON d.region_id = r.id \
+             WHERE p.id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let crumbs = place_breadcrumb(&r.title, [r.region, r.orp, r.municipality]);
+            (card(r.title, crumbs, r.artwork_key), id)
+        }),
+        // Media cards fingerprint the importer's large cover key; the bytes
+        // come from `media_cover::large_cover_by_id` in the handler.
+        OgKind::Film => {
+            sqlx::query_as::<_, MediaCardRow>("SELECT title, year FROM films WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&state.db)
+                .await?
+                .map(|r| {
+                    let card = card(
+                        media_title(r.title, r.year),
+                        vec!["Filmy online".to_string()],
+                        Some(new_r2_key(MediaKind::FILM.r2_prefix, id, true)),
+                    );
+                    (card, id)
+                })
+        }
+        OgKind::Series => sqlx::query_as::<_, MediaCardRow>(
+            "SELECT title, first_air_year AS year FROM series WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let card = card(
+                media_title(r.title, r.year),
+                vec!["Seriály online".to_string()],
+                Some(new_r2_key(MediaKind::SERIES.r2_prefix, id, true)),
+            );
+            (card, id)
+        }),
+        OgKind::TvShow => sqlx::query_as::<_, MediaCardRow>(
+            "SELECT title, first_air_year AS year FROM tv_shows WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let card = card(
+                media_title(r.title, r.year),
+                vec!["TV pořady".to_string()],
+                Some(new_r2_key(MediaKind::TV_SHOW.r2_prefix, id, true)),
+            );
+            (card, id)
+        }),
+        OgKind::Episode => sqlx::query_as::<_, EpisodeCardRow>(
+            "SELECT s.id AS parent_id, s.title AS show, e.season, e.episode, e.episode_name \
+             FROM episodes e JOIN series s ON s.id = e.series_id WHERE e.id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let card = card(
+                episode_title(&r),
+                vec!["Seriály online".to_string()],
+                Some(new_r2_key(MediaKind::SERIES.r2_prefix, r.parent_id, true)),
+            );
+            (card, r.parent_id)
+        }),
+        OgKind::TvEpisode => sqlx::query_as::<_, EpisodeCardRow>(
+            "SELECT t.id AS parent_id, t.title AS show, e.season, e.episode, e.episode_name \
+             FROM tv_episodes e JOIN tv_shows t ON t.id = e.tv_show_id WHERE e.id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .map(|r| {
+            let card = card(
+                episode_title(&r),
+                vec!["TV pořady".to_string()],
+                Some(new_r2_key(MediaKind::TV_SHOW.r2_prefix, r.parent_id, true)),
+            );
+            (card, r.parent_id)
+        }),
+    };
+    Ok(card)
+}
+
+/// Region › ORP › municipality, skipping missing units and any unit that
+/// repeats the previous one or the title (the ORP seat municipality has
+/// the same name as its ORP).
+fn place_breadcrumb(title: &str, units: [Option<String>; 3]) -> Vec<String> {
+    let mut crumbs: Vec<String> = Vec::new();
+    for unit in units.into_iter().flatten() {
+        if unit != title && crumbs.last() != Some(&unit) {
+            crumbs.push(unit);
+        }
+    }
+    crumbs
+}
+
+fn media_title(title: String, year: Option<i16>) -> String {
+    match year {
+        Some(y) => format!("{title} ({y})"),
+        None => title,
+    }
+}
+
+/// `Show — Name S01E02`, as the episode pages title themselves: a name
+/// that is just a number is left out.
+fn episode_title(r: &EpisodeCardRow) -> String {
+    let name = r
+        .episode_name
+        .as_deref()
+        .filter(|n| !n.is_empty() && !n.starts_with(char::is_numeric))
+        .map(|n| format!("{n} "))
+        .unwrap_or_default();
+    format!("{} — {name}S{:02}E{:02}", r.show, r.season, r.episode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_round_trips() {
+        for kind in [
+            OgKind::Landmark,
+            OgKind::Municipality,
+            OgKind::Film,
+            OgKind::Series,
+            OgKind::TvShow,
+            OgKind::Episode,
+            OgKind::TvEpisode,
+            OgKind::Pool,
+        ] {
+            assert_eq!(OgKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(OgKind::parse("person"), None);
+    }
+
+    #[test]
+    fn episode_title_skips_numeric_names() {
+        let row = |name: Option<&str>| EpisodeCardRow {
+            parent_id: 1,
+            show: "Ulice".to_string(),
+            season: 3,
+            episode: 12,
+            episode_name: name.map(str::to_string),
+        };
+        assert_eq!(episode_title(&row(Some("Návrat"))), "Ulice — Návrat S03E12");
+        assert_eq!(episode_title(&row(Some("12. díl"))), "Ulice — S03E12");
+        assert_eq!(episode_title(&row(None)), "Ulice — S03E12");
+    }
+
+    #[test]
+    fn breadcrumb_drops_repeated_units() {
+        let s = |v: &str| Some(v.to_string());
+        assert_eq!(
+            place_breadcrumb(
+                "Zámek Mikulov",
+                [s("Jihomoravský kraj"), s("Mikulov"), s("Mikulov")]
+            ),
+            vec!["Jihomoravský kraj", "Mikulov"]
+        );
+        assert_eq!(
+            place_breadcrumb("Mikulov", [s("Jihomoravský kraj"), s("Mikulov"), None]),
+            vec!["Jihomoravský kraj"]
+        );
+        assert!(place_breadcrumb("Praha", [None, None, None]).is_empty());
+    }
+}
@@ -0,0 +1,603 @@
+//! Person pages for actors, directors and writers.
+//!
+//!     /osobnosti/                  — person search (`?q=`), most credited
+//!                                    people when the query is empty
+//!     /osobnosti/{slug}/           — detail with the combined filmography
+//!     /api/people/search?q=        — autocomplete JSON
+//!     /sitemaps/osobnosti.xml      — sitemap index
+//!     /sitemaps/osobnosti/{n}.xml  — sitemap pages
+//!
+//! The filmography merges all six credit tables (`film_actors`,
+//! `film_directors`, `series_actors`, `series_directors`,
+//! `episode_directors`, `episode_writers`) into one entry per film or
+//! series; episode credits are listed under their series.
+
+use std::collections::HashMap;
+
+use askama::Template;
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{Html, IntoResponse, Response};
+use cr_domain::dto::PersonCredit;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::error::{WebError, WebResult};
+use crate::handlers::SITE_ORIGIN;
+use crate::state::AppState;
+
+/// Sitemaps may hold 50 000 URLs; stay well below.
+const SITEMAP_PAGE_SIZE: i64 = 40_000;
+/// People listed on `/osobnosti/` without a query.
+const INDEX_LIMIT: i64 = 60;
+
+// --- Rows ---
+
+#[derive(FromRow)]
+struct PersonDetailRow {
+    id: i32,
+    slug: String,
+    name: String,
+    tmdb_id: Option<i32>,
+    profile_filename: Option<String>,
+}
+
+/// One credit from any of the credit tables, flattened by `CREDITS_SQL`.
+#[derive(FromRow, Debug, Clone)]
+struct CreditRow {
+    /// `film` or `series`.
+    kind: String,
+    /// `actor`, `director`, `creator`, `episode_director`, `episode_writer`.
+    role: String,
+    work_id: i32,
+    title: String,
+    slug: String,
+    year: Option<i16>,
+    character_name: Option<String>,
+    season: Option<i16>,
+    episode: Option<i16>,
+    episode_slug: Option<String>,
+    episode_name: Option<String>,
+}
+
+#[derive(FromRow, Serialize)]
+struct PersonListRow {
+    slug: String,
+    name: String,
+    profile_filename: Option<String>,
+    credit_count: i64,
+}
+
+// The year of an episode credit is the episode's air year, falling back to
+// the series' first year for episodes without an air date.
+const CREDITS_SQL: &str = "\
+    SELECT 'film' AS kind, 'actor' AS role, f.id AS work_id, f.title, f.slug, f.year, \
+           fa.character_name, NULL::smallint AS season, NULL::smallint AS episode, \
+           NULL::varchar AS episode_slug, NULL::text AS episode_name \
+    FROM film_actors fa JOIN films f ON f.id = fa.film_id WHERE fa.person_id = $1 \
+    UNION ALL \
+    SELECT 'film', 'director', f.id, f.title, f.slug, f.year, NULL, NULL, NULL, NULL, NULL \
+    FROM film_directors fd JOIN films f ON f.id = fd.film_id WHERE fd.person_id = $1 \
+    UNION ALL \
+    SELECT 'series', 'actor', s.id, s.title, s.slug, s.first_air_year, sa.character_name, \
+           NULL, NULL, NULL, NULL \
+    FROM series_actors sa JOIN series s ON s.id = sa.series_id WHERE sa.person_id = $1 \
+    UNION ALL \
+    SELECT 'series', 'creator', s.id, s.title, s.slug, s.first_air_year, NULL, \
+           NULL, NULL, NULL, NULL \
+    FROM series_directors sd JOIN series s ON s.id = sd.series_id WHERE sd.person_id = $1 \
+    UNION ALL \
+    SELECT 'series', 'episode_director', s.id, s.title, s.slug, \
+           COALESCE(EXTRACT(YEAR FROM e.air_date)::smallint, s.first_air_year), NULL, \
+           e.season, e.episode, e.slug, e.episode_name \
+    FROM episode_directors ed JOIN episodes e ON e.id = ed.episode_id \
+    JOIN series s ON s.id = e.series_id WHERE ed.person_id = $1 \
+    UNION ALL \
+    SELECT 'series', 'episode_writer', s.id, s.title, s.slug, \
+           COALESCE(EXTRACT(YEAR FROM e.air_date)::smallint, s.first_air_year), NULL, \
+           e.season, e.episode, e.slug, e.episode_name \
+    FROM episode_writers ew JOIN episodes e ON e.id = ew.episode_id \
+    JOIN series s ON s.id = e.series_id WHERE ew.person_id = $1";
+
+// Credit count per person, shared by the index and the search ranking.
+const CREDIT_COUNT_SQL: &str = "\
+    (SELECT count(*) FROM film_actors x WHERE x.person_id = p.id) \
+    + (SELECT count(*) FROM film_directors x WHERE x.person_id = p.id) \
+    + (SELECT count(*) FROM series_actors x WHERE x.person_id = p.id) \
+    + (SELECT count(*) FROM series_directors x WHERE x.person_id = p.id) \
+    + (SELECT count(*) FROM episode_directors x WHERE x.person_id = p.id) \
+    + (SELECT count(*) FROM episode_writers x WHERE x.person_id = p.id)";
+
+// --- Filmography ---
+
+/// One film or series on the person page.
+pub struct FilmographyEntry {
+    pub is_series: bool,
+    pub title: String,
+    pub url: String,
+    pub year: Option<i16>,
+    /// Work-level roles in display order, e.g. `Hraje: Kolja`, `Režie`.
+    pub roles: Vec<String>,
+    /// Episode credits, ordered by season and episode.
+    pub episodes: Vec<EpisodeCredit>,
+}
+
+pub struct EpisodeCredit {
+    /// `S01E03`.
+    pub code: String,
+    pub name: Option<String>,
+    pub url: String,
+    /// `režie`, `scénář` or `režie, scénář`.
+    pub roles: String,
+}
+
+fn role_order(role: &str) -> u8 {
+    match role {
+        "director" | "creator" => 0,
+        "actor" => 1,
+        "episode_director" => 2,
+        _ => 3,
+    }
+}
+
+/// Fold flat credit rows into one entry per work, newest first. Works
+/// without a year go last; ties are broken by title.
+fn build_filmography(mut credits: Vec<CreditRow>) -> Vec<FilmographyEntry> {
+    credits.sort_by_key(|c| role_order(&c.role));
+
+    let mut entries: HashMap<(String, i32), FilmographyEntry> = HashMap::new();
+    for c in credits {
+        let key = (c.kind.clone(), c.work_id);
+        let is_series = c.kind == "series";
+        let entry = entries.entry(key).or_insert_with(|| {
+            let section = if is_series {
+                "serialy-online"
+            } else {
+                "filmy-online"
+            };
+            FilmographyEntry {
+                is_series,
+                title: c.title.clone(),
+                url: format!("/{section}/{}/", c.slug),
+                year: None,
+                roles: Vec::new(),
+                episodes: Vec::new(),
+            }
+        });
+        entry.year = match (entry.year, c.year) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let role = match c.role.as_str() {
+            "actor" => match c.character_name.as_deref().filter(|n| !n.is_empty()) {
+                Some(name) => format!("Hraje: {name}"),
+                None => "Hraje".to_string(),
+            },
+            "director" => "Režie".to_string(),
+            "creator" => "Tvorba".to_string(),
+            "episode_director" | "episode_writer" => {
+                let (Some(season), Some(episode)) = (c.season, c.episode) else {
+                    continue;
+                };
+                let label = if c.role == "episode_director" {
+                    "režie"
+                } else {
+                    "scénář"
+                };
+                let code = format!("S{season:02}E{episode:02}");
+                // `episodes` has one row per video source, so the same
+                // episode can come back more than once.
+                match entry.episodes.iter_mut().find(|e| e.code == code) {
+                    Some(existing) if !existing.roles.contains(label) => {
+                        existing.roles = format!("{}, {label}", existing.roles);
+                    }
+                    Some(_) => {}
+                    None => {
+                        let path = c
+                            .episode_slug
+                            .clone()
+                            .unwrap_or_else(|| format!("{season}x{episode}"));
+                        entry.episodes.push(EpisodeCredit {
+                            code,
+                            name: c.episode_name.clone().filter(|n| !n.is_empty()),
+                            url: format!("/serialy-online/{}/{path}/", c.slug),
+                            roles: label.to_string(),
+                        });
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if !entry.roles.contains(&role) {
+            entry.roles.push(role);
+        }
+    }
+
+    let mut out: Vec<FilmographyEntry> = entries.into_values().collect();
+    for entry in &mut out {
+        entry.episodes.sort_by(|a, b| a.code.cmp(&b.code));
+    }
+    out.sort_by(|a, b| {
+        b.year
+            .is_some()
+            .cmp(&a.year.is_some())
+            .then(b.year.cmp(&a.year))
+            .then_with(|| a.title.cmp(&b.title))
+            .then(a.is_series.cmp(&b.is_series))
+    });
+    out
+}
+
+// --- Templates ---
+
+#[derive(Template)]
+#[template(path = "person_detail.html")]
+struct PersonDetailTemplate {
+    img: String,
+    person: PersonDetailRow,
+    filmography: Vec<FilmographyEntry>,
+    film_count: usize,
+    series_count: usize,
+    /// Up to three titles for the meta description.
+    known_for: String,
+    json_ld: String,
+}
+
+#[derive(Template)]
+#[template(path = "people_index.html")]
+struct PeopleIndexTemplate {
+    img: String,
+    query: String,
+    people: Vec<PersonListRow>,
+}
+
+// --- Handlers ---
+
+/// GET /osobnosti/{slug}/
+pub async fn person_detail(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> WebResult<Response> {
+    let person = sqlx::query_as::<_, PersonDetailRow>(
+        "SELECT id, slug, name, tmdb_id, profile_filename FROM people WHERE slug = $1",
+    )
+    .bind(&slug)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| WebError::not_found("Osobnost nenalezena"))?;
+
+    let credits = sqlx::query_as::<_, CreditRow>(CREDITS_SQL)
+        .bind(person.id)
+        .fetch_all(&state.db)
+        .await?;
+    let filmography = build_filmography(credits);
+    let film_count = filmography.iter().filter(|e| !e.is_series).count();
+    let series_count = filmography.len() - film_count;
+    let known_for = filmography
+        .iter()
+        .take(3)
+        .map(|e| e.title.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let json_ld = person_json_ld(&person, &filmography);
+
+    let tmpl = PersonDetailTemplate {
+        img: state.image_base_url.clone(),
+        person,
+        filmography,
+        film_count,
+        series_count,
+        known_for,
+        json_ld,
+    };
+    Ok(Html(tmpl.render()?).into_response())
+}
+
+fn person_json_ld(person: &PersonDetailRow, filmography: &[FilmographyEntry]) -> String {
+    let url = format!("{SITE_ORIGIN}/osobnosti/{}/", person.slug);
+    let mut entity = serde_json::json!({
+        "@type": "Person",
+        "name": person.name,
+        "url": url,
+    });
+    if let Some(f) = &person.profile_filename {
+        entity["image"] = format!("{SITE_ORIGIN}/filmy-online/person/{f}").into();
+    }
+    if let Some(id) = person.tmdb_id {
+        entity["sameAs"] = format!("https://www.themoviedb.org/person/{id}").into();
+    }
+    let works: Vec<serde_json::Value> = filmography
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "@type": if e.is_series { "TVSeries" } else { "Movie" },
+                "name": e.title,
+                "url": format!("{SITE_ORIGIN}{}", e.url),
+            })
+        })
+        .collect();
+    if !works.is_empty() {
+        entity["subjectOf"] = works.into();
+    }
+    super::json_ld(&serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "ProfilePage",
+        "url": url,
+        "mainEntity": entity,
+    }))
+}
+
+/// `Movie` / `TVSeries` JSON-LD for the film and series detail pages, with
+/// the cast and directors linked to their person pages.
+pub(crate) fn work_json_ld(
+    schema_type: &str,
+    title: &str,
+    path: &str,
+    year: Option<i16>,
+    directors: &[PersonCredit],
+    actors: &[PersonCredit],
+) -> String {
+    let person = |p: &PersonCredit| {
+        serde_json::json!({
+            "@type": "Person",
+            "name": p.name,
+            "url": format!("{SITE_ORIGIN}/osobnosti/{}/", p.slug),
+        })
+    };
+    let mut work = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": schema_type,
+        "name": title,
+        "url": format!("{SITE_ORIGIN}{path}"),
+    });
+    if let Some(y) = year {
+        let key = if schema_type == "TVSeries" {
+            "startDate"
+        } else {
+            "dateCreated"
+        };
+        work[key] = y.to_string().into();
+    }
+    if !directors.is_empty() {
+        work["director"] = directors.iter().map(person).collect();
+    }
+    if !actors.is_empty() {
+        work["actor"] = actors.iter().map(person).collect();
+    }
+    super::json_ld(&work)
+}
+
+#[derive(Deserialize)]
+pub struct PeopleQuery {
+    q: Option<String>,
+}
+
+/// GET /osobnosti/ — search by name, or the most credited people.
+pub async fn people_index(
+    State(state): State<AppState>,
+    Query(params): Query<PeopleQuery>,
+) -> WebResult<Response> {
+    let query = params.q.unwrap_or_default().trim().to_string();
+    if query.is_empty()
+        && let Some(html) = state.people_index_cache.get(&()).await
+    {
+        return Ok(Html(html).into_response());
+    }
+    let people = if query.chars().count() >= 2 {
+        search_people(&state.db, &query, 100).await?
+    } else {
+        sqlx::query_as::<_, PersonListRow>(&format!(
+            "SELECT p.slug, p.name, p.profile_filename, {CREDIT_COUNT_SQL} AS credit_count \
+             FROM people p ORDER BY credit_count DESC, p.name LIMIT $1"
+        ))
+        .bind(INDEX_LIMIT)
+        .fetch_all(&state.db)
+        .await?
+    };
+    let html = PeopleIndexTemplate {
+        img: state.image_base_url.clone(),
+        query: query.clone(),
+        people,
+    }
+    .render()?;
+    if query.is_empty() {
+        state.people_index_cache.insert((), html.clone()).await;
+        Ok(Html(html).into_response())
+    } else {
+        Ok(super::search_cached_html(html))
+    }
+}
+
+/// GET /api/people/search?q= — name autocomplete.
+pub async fn people_search(
+    State(state): State<AppState>,
+    Query(params): Query<PeopleQuery>,
+) -> WebResult<Response> {
+    let q = params.q.as_deref().map(str::trim).unwrap_or("");
+    if q.chars().count() < 2 {
+        return Ok(super::search_cached_json(Vec::<PersonListRow>::new()));
+    }
+    let rows = search_people(&state.db, q, 10).await?;
+    Ok(super::search_cached_json(rows))
+}
+
+/// Diacritics-insensitive name match (see the `unaccent` migration). Names
+/// starting with the query rank first, then a surname prefix, then any
+/// substring; within a bucket the most credited person wins.
+async fn search_people(
+    db: &sqlx::PgPool,
+    q: &str,
+    limit: i64,
+) -> Result<Vec<PersonListRow>, sqlx::Error> {
+    let pattern = format!("%{q}%");
+    let starts_pattern = format!("{q}%");
+    let word_pattern = format!("% {q}%");
+    sqlx::query_as::<_, PersonListRow>(&format!(
+        "SELECT p.slug, p.name, p.profile_filename, {CREDIT_COUNT_SQL} AS credit_count \
+         FROM people p \
+         WHERE unaccent(p.name) ILIKE unaccent($1) \
+         ORDER BY \
+           CASE WHEN unaccent(p.name) ILIKE unaccent($2) THEN 0 \
+                WHEN unaccent(p.name) ILIKE unaccent($3) THEN 1 \
+                ELSE 2 END, \
+           credit_count DESC, p.name \
+         LIMIT $4"
+    ))
+    .bind(&pattern)
+    .bind(&starts_pattern)
+    .bind(&word_pattern)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+/// GET /sitemaps/osobnosti.xml — index of the person sitemap pages.
+pub async fn people_sitemap_index(State(state): State<AppState>) -> WebResult<Response> {
+    let total: i64 = sqlx::query_scalar("SELECT count(*) FROM people")
+        .fetch_one(&state.db)
+        .await?;
+    let pages = (total + SITEMAP_PAGE_SIZE - 1) / SITEMAP_PAGE_SIZE;
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for page in 1..=pages.max(1) {
+        xml.push_str(&format!(
+            "<sitemap><loc>{SITE_ORIGIN}/sitemaps/osobnosti/{page}.xml</loc></sitemap>\n"
+        ));
+    }
+    xml.push_str("</sitemapindex>\n");
+    Ok(xml_response(xml))
+}
+
+/// GET /sitemaps/osobnosti/{n}.xml — `SITEMAP_PAGE_SIZE` person URLs,
+/// ordered by id so pages stay stable as people are added.
+pub async fn people_sitemap_page(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+) -> WebResult<Response> {
+    let page: i64 = file
+        .strip_suffix(".xml")
+        .and_then(|n| n.parse().ok())
+        .filter(|&n| n >= 1)
+        .ok_or_else(|| WebError::not_found("Sitemap nenalezena"))?;
+    let slugs: Vec<String> =
+        sqlx::query_scalar("SELECT slug FROM people ORDER BY id LIMIT $1 OFFSET $2")
+            .bind(SITEMAP_PAGE_SIZE)
+            .bind((page - 1) * SITEMAP_PAGE_SIZE)
+            .fetch_all(&state.db)
+            .await?;
+    if slugs.is_empty() && page > 1 {
+        return Err(WebError::not_found("Sitemap nenalezena"));
+    }
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    // Slugs are `[a-z0-9-]` by construction (see the people_slug
+    // migration), so they need no XML escaping.
+    for slug in slugs {
+        xml.push_str(&format!(
+            "<url><loc>{SITE_ORIGIN}/osobnosti/{slug}/</loc></url>\n"
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    Ok(xml_response(xml))
+}
+
+fn xml_response(xml: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        xml,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credit(kind: &str, role: &str, work_id: i32, title: &str, year: Option<i16>) -> CreditRow {
+        CreditRow {
+            kind: kind.to_string(),
+            role: role.to_string(),
+            work_id,
+            title: title.to_string(),
+            slug: title.to_lowercase().replace(' ', "-"),
+            year,
+            character_name: None,
+            season: None,
+            episode: None,
+            episode_slug: None,
+            episode_name: None,
+        }
+    }
+
+    fn episode(role: &str, season: i16, episode: i16, slug: Option<&str>) -> CreditRow {
+        CreditRow {
+            season: Some(season),
+            episode: Some(episode),
+            episode_slug: slug.map(str::to_string),
+            slug: "okresni-prebor".to_string(),
+            ..credit("series", role, 7, "Okresní přebor", Some(2010))
+        }
+    }
+
+    #[test]
+    fn filmography_merges_roles_per_work_and_sorts_by_year() {
+        let mut kolja = credit("film", "actor", 1, "Kolja", Some(1996));
+        kolja.character_name = Some("František Louka".into());
+        let credits = vec![
+            credit("film", "director", 2, "Obecná škola", Some(1991)),
+            kolja,
+            credit("film", "director", 1, "Kolja", Some(1996)),
+            credit("film", "actor", 3, "Bez roku", None),
+            // A series and a film may share an id — they must not merge.
+            credit("series", "actor", 1, "Arabela", Some(1979)),
+        ];
+        let films = build_filmography(credits);
+        let titles: Vec<&str> = films.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Kolja", "Obecná škola", "Arabela", "Bez roku"]);
+        assert_eq!(films[0].roles, ["Režie", "Hraje: František Louka"]);
+        assert_eq!(films[0].url, "/filmy-online/kolja/");
+        assert!(films[2].is_series);
+        assert_eq!(films[2].url, "/serialy-online/arabela/");
+    }
+
+    #[test]
+    fn episode_credits_group_under_their_series() {
+        let credits = vec![
+            episode("episode_writer", 1, 2, Some("pilot")),
+            episode("episode_director", 1, 2, Some("pilot")),
+            // Same episode again via a second video source row.
+            episode("episode_director", 1, 2, Some("pilot")),
+            episode("episode_director", 1, 1, None),
+            credit("series", "creator", 7, "Okresní přebor", Some(2010)),
+        ];
+        let entries = build_filmography(credits);
+        assert_eq!(entries.len(), 1);
+        let series = &entries[0];
+        assert_eq!(series.roles, ["Tvorba"]);
+        let eps: Vec<(&str, &str, &str)> = series
+            .episodes
+            .iter()
+            .map(|e| (e.code.as_str(), e.roles.as_str(), e.url.as_str()))
+            .collect();
+        assert_eq!(
+            eps,
+            [
+                ("S01E01", "režie", "/serialy-online/okresni-prebor/1x1/"),
+                (
+                    "S01E02",
+                    "režie, scénář",
+                    "/serialy-online/okresni-prebor/pilot/"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Site-wide search: suggestions, results page and browser integration.
+//!
+//!     /api/suggest?q=       — mixed suggestions as JSON
+//!     /api/suggest?q=&format=opensearch — OpenSearch suggestions
+//!     /hledat/?q=           — full results page (the search box's submit)
+//!     /opensearch.xml       — lets browsers add the site as a search engine
+//!
+//! All of it is served from the in-memory prefix index in
+//! `cr_app::suggest`, kept current by `search::spawn_suggest_index_loop`.
+//! Until the first build lands the endpoints answer with no hits rather
+//! than hitting the database on every keystroke.
+
+use askama::Template;
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use cr_app::suggest::{MIN_QUERY_CHARS, SUGGEST_LIMIT};
+use cr_domain::dto::SuggestEntry;
+use serde::{Deserialize, Serialize};
+
+use crate::error::WebResult;
+use crate::handlers::SITE_ORIGIN;
+use crate::state::AppState;
+
+/// Most hits a caller can ask `/api/suggest` for.
+const MAX_SUGGEST_LIMIT: usize = 20;
+
+/// Hits on the `/hledat/` results page.
+const RESULTS_LIMIT: usize = 60;
+
+#[derive(Deserialize)]
+pub struct SuggestQuery {
+    q: Option<String>,
+    limit: Option<usize>,
+    /// `opensearch` for the browser suggestion format.
+    format: Option<String>,
+}
+
+/// One `/api/suggest` hit.
+#[derive(Serialize)]
+struct SuggestItem<'a> {
+    /// `film`, `series`, `tv_show`, `municipality` or `landmark`.
+    #[serde(rename = "type")]
+    kind: &'static str,
+    /// Czech name of the type, for display.
+    label: &'static str,
+    title: &'a str,
+    /// Year for titles, region for places.
+    context: Option<&'a str>,
+    url: &'a str,
+    thumbnail: Option<&'a str>,
+}
+
+impl<'a> From<&'a SuggestEntry> for SuggestItem<'a> {
+    fn from(e: &'a SuggestEntry) -> Self {
+        Self {
+            kind: e.kind.key(),
+            label: e.kind.label(),
+            title: &e.title,
+            context: e.context.as_deref(),
+            url: &e.path,
+            thumbnail: e.thumb_path.as_deref(),
+        }
+    }
+}
+
+/// GET /api/suggest?q=
+pub async fn api_suggest(
+    State(state): State<AppState>,
+    Query(params): Query<SuggestQuery>,
+) -> Response {
+    let query = params.q.as_deref().unwrap_or_default().trim();
+    let limit = params
+        .limit
+        .unwrap_or(SUGGEST_LIMIT)
+        .clamp(1, MAX_SUGGEST_LIMIT);
+    let hits = state.suggest.suggest(query, limit);
+    // Not built yet: an empty answer that nobody caches.
+    let cache_control = if hits.is_some() {
+        super::SEARCH_CACHE_CONTROL
+    } else {
+        "no-store"
+    };
+    let hits = hits.unwrap_or_default();
+
+    if params.format.as_deref() == Some("opensearch") {
+        return (
+            [
+                (header::CONTENT_TYPE, "application/x-suggestions+json"),
+                (header::CACHE_CONTROL, cache_control),
+            ],
+            opensearch_suggestions(query, &hits).to_string(),
+        )
+            .into_response();
+    }
+    let items: Vec<SuggestItem> = hits.iter().map(SuggestItem::from).collect();
+    ([(header::CACHE_CONTROL, cache_control)], axum::Json(items)).into_response()
+}
+
+/// `[query, [titles], [descriptions], [urls]]` — the OpenSearch
+/// suggestions extension format browsers understand.
+fn opensearch_suggestions(query: &str, hits: &[SuggestEntry]) -> serde_json::Value {
+    let titles: Vec<&str> = hits.iter().map(|h| h.title.as_str()).collect();
+    let descriptions: Vec<String> = hits
+        .iter()
+        .map(|h| match &h.context {
+            Some(context) => format!("{} · {context}", h.kind.label()),
+            None => h.kind.label().to_string(),
+        })
+        .collect();
+    let urls: Vec<String> = hits
+        .iter()
+        .map(|h| format!("{SITE_ORIGIN}{}", h.path))
+        .collect();
+    serde_json::json!([query, titles, descriptions, urls])
+}
+
+#[derive(Template)]
+#[template(path = "search_results.html")]
+struct SearchResultsTemplate {
+    img: String,
+    query: String,
+    results: Vec<SuggestEntry>,
+    /// The query is long enough to have been looked up.
+    searched: bool,
+}
+
+/// GET /hledat/?q=
+pub async fn search_page(
+    State(state): State<AppState>,
+    Query(params): Query<SuggestQuery>,
+) -> WebResult<Response> {
+    let query = params.q.as_deref().unwrap_or_default().trim().to_string();
+    let searched = query.chars().count() >= MIN_QUERY_CHARS;
+    let results = state
+        .suggest
+        .suggest(&query, RESULTS_LIMIT)
+        .unwrap_or_default();
+    let tmpl = SearchResultsTemplate {
+        img: state.image_base_url.clone(),
+        query,
+        results,
+        searched,
+    };
+    Ok(super::search_cached_html(tmpl.render()?))
+}
+
+/// GET /opensearch.xml
+pub async fn opensearch_description() -> Response {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/opensearchdescription+xml; charset=utf-8",
+            ),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        opensearch_xml(SITE_ORIGIN),
+    )
+        .into_response()
+}
+
+fn opensearch_xml(origin: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/" xmlns:moz="http://www.mozilla.org/2006/browser/search/">
+<ShortName>ceskarepublika.wiki</ShortName>
+<Description>Obce, památky, filmy a seriály na ceskarepublika.wiki</Description>
+<InputEncoding>UTF-8</InputEncoding>
+<Language>cs</Language>
+<Image width="16" height="16" type="image/x-icon">{origin}/favicon.ico</Image>
+<Url type="text/html" method="get" template="{origin}/hledat/?q={{searchTerms}}"/>
+<Url type="application/x-suggestions+json" method="get" template="{origin}/api/suggest?format=opensearch&amp;q={{searchTerms}}"/>
+<Url type="application/opensearchdescription+xml" rel="self" template="{origin}/opensearch.xml"/>
+<moz:SearchForm>{origin}/hledat/</moz:SearchForm>
+</OpenSearchDescription>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cr_domain::dto::SuggestKind;
+
+    #[test]
+    fn opensearch_suggestions_use_absolute_urls() {
+        let hits = vec![SuggestEntry {
+            kind: SuggestKind::Landmark,
+            title: "Hrad Kost".to_string(),
+            context: Some("Libošovice, Královéhradecký kraj".to_string()),
+            path: "/sobotka/libosovice/hrad-kost/".to_string(),
+            thumb_path: None,
+            weight: 1,
+        }];
+        assert_eq!(
+            opensearch_suggestions("kost", &hits).to_string(),
+            r#"["kost",["Hrad Kost"],["Památka · Libošovice, Královéhradecký kraj"],["https://ceskarepublika.wiki/sobotka/libosovice/hrad-kost/"]]"#
+        );
+    }
+
+    #[test]
+    fn opensearch_description_templates() {
+        let xml = opensearch_xml(SITE_ORIGIN);
+        assert!(xml.contains(r#"template="https://ceskarepublika.wiki/hledat/?q={searchTerms}""#));
+        assert!(xml.contains("format=opensearch&amp;q={searchTerms}"));
+    }
+}
@@ -0,0 +1,54 @@
+//! `GET /tiles/{layer}/{z}/{x}/{y}.mvt` — Mapbox Vector Tiles of the
+//! territorial boundaries (`regions`, `districts`, `orp`,
+//! `municipalities`). Geometry and clipping live in `crate::geo::tiles`;
+//! this handler only validates the path, caches encoded tiles in memory
+//! and sets the HTTP headers.
+//!
+//! The boundary files change a few times a year with a deploy, so tiles
+//! are cached for a day both in-process and at the edge.
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+
+use crate::error::{WebError, WebResult};
+use crate::state::AppState;
+
+pub async fn vector_tile(
+    State(state): State<AppState>,
+    Path((layer, z, x, y_mvt)): Path<(String, u8, u32, String)>,
+) -> WebResult<Response> {
+    let Some(y) = y_mvt
+        .strip_suffix(".mvt")
+        .and_then(|y| y.parse::<u32>().ok())
+    else {
+        return Err(WebError::not_found("Unknown tile"));
+    };
+
+    let key = format!("{layer}/{z}/{x}/{y}");
+    let tile = match state.tile_cache.get(&key).await {
+        Some(tile) => tile,
+        None => {
+            let layers = state.tile_layers.clone();
+            let rendered =
+                tokio::task::spawn_blocking(move || layers.render(&layer, z, x, y)).await?;
+            let Some(rendered) = rendered else {
+                return Err(WebError::not_found("Unknown tile"));
+            };
+            let tile = Bytes::from(rendered);
+            state.tile_cache.insert(key, tile.clone()).await;
+            tile
+        }
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/vnd.mapbox-vector-tile"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        tile,
+    )
+        .into_response())
+}
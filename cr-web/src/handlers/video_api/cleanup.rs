@@ -1,6 +1,17 @@
 //! Periodic background reaper for temp video files (#192).
 
-use super::{TMP_VIDEO_CLEANUP_INTERVAL, TMP_VIDEO_DIR, TMP_VIDEO_MAX_AGE, VideoDownloads};
+use std::sync::Arc;
+
+use cr_domain::repository::VideoJobRepository;
+use cr_domain::video_job::VideoJobState;
+use cr_infra::repositories::PgVideoJobRepository;
+
+use super::{TMP_VIDEO_CLEANUP_INTERVAL, TMP_VIDEO_DIR, TMP_VIDEO_MAX_AGE};
+
+/// Failed jobs stay listed on `/admin/video-queue/` this long — they have
+/// no file to reap, and the errors are what the page is for.
+pub(crate) const FAILED_JOB_MAX_AGE: std::time::Duration =
+    std::time::Duration::from_secs(24 * 3600);
 
 /// Scan `dir` once and delete any regular file whose last-modified
 /// timestamp is older than `max_age`. Returns `(deleted_count,
@@ -74,21 +85,29 @@ pub(crate) async fn purge_stale_temp_videos(
     (deleted, freed)
 }
 
-/// Prune in-memory `VideoDownloads` entries whose `created_at` is older
-/// than `max_age`. Returns the number of tokens removed.
+/// Delete the queue rows that outlived their usefulness: ready jobs once
+/// their temp files are due for reaping, failed ones after
+/// [`FAILED_JOB_MAX_AGE`]. Returns the number of jobs removed.
 ///
 /// Runs alongside the on-disk reaper so that once a temp file is
-/// deleted, the matching `/api/video/status/{token}` entry goes with
+/// deleted, the matching `/api/video/status/{token}` job goes with
 /// it — otherwise the handler would keep reporting `Ready` while
 /// `/api/video/file/{token}` returns 500 for a missing file.
-pub(crate) async fn prune_stale_video_downloads(
-    downloads: &VideoDownloads,
-    max_age: std::time::Duration,
-) -> usize {
-    let mut map = downloads.lock().await;
-    let before = map.len();
-    map.retain(|_, task| task.created_at.elapsed() < max_age);
-    before - map.len()
+async fn prune_finished_jobs(jobs: &PgVideoJobRepository) -> u64 {
+    let mut pruned = 0;
+    for (states, max_age) in [
+        (
+            &[VideoJobState::Ready, VideoJobState::ReadyParts][..],
+            TMP_VIDEO_MAX_AGE,
+        ),
+        (&[VideoJobState::Failed][..], FAILED_JOB_MAX_AGE),
+    ] {
+        match jobs.delete_finished(states, max_age).await {
+            Ok(n) => pruned += n,
+            Err(e) => tracing::warn!("temp cleanup: pruning finished jobs failed: {e}"),
+        }
+    }
+    pruned
 }
 
 /// Spawn the long-running periodic reaper. Call once at startup from
@@ -97,15 +116,17 @@ pub(crate) async fn prune_stale_video_downloads(
 ///
 /// Every `TMP_VIDEO_CLEANUP_INTERVAL` it scans [`TMP_VIDEO_DIR`] and
 /// deletes any file older than `TMP_VIDEO_MAX_AGE`, then prunes the
-/// corresponding in-memory `VideoDownloads` entries so the `status`
-/// endpoint stops reporting `Ready` for tokens whose file is gone.
+/// corresponding download jobs so the `status` endpoint stops reporting
+/// `Ready` for tokens whose file is gone.
 ///
 /// The first tick fires on the interval boundary — not immediately on
 /// startup — which deliberately gives in-flight downloads time to
 /// complete before the reaper runs the first time. The ticker uses
 /// `MissedTickBehavior::Skip` so a slow sweep (lots of files / slow
 /// I/O) can't trigger a back-to-back burst of catch-up sweeps.
-pub fn spawn_temp_video_cleanup_loop(downloads: VideoDownloads) -> tokio::task::JoinHandle<()> {
+pub fn spawn_temp_video_cleanup_loop(
+    jobs: Arc<PgVideoJobRepository>,
+) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let dir = std::path::PathBuf::from(TMP_VIDEO_DIR);
         let mut ticker = tokio::time::interval(TMP_VIDEO_CLEANUP_INTERVAL);
@@ -117,7 +138,7 @@ pub fn spawn_temp_video_cleanup_loop(downloads: VideoDownloads) -> tokio::task::
         loop {
             ticker.tick().await;
             let (deleted_files, freed) = purge_stale_temp_videos(&dir, TMP_VIDEO_MAX_AGE).await;
-            let pruned_tokens = prune_stale_video_downloads(&downloads, TMP_VIDEO_MAX_AGE).await;
+            let pruned_tokens = prune_finished_jobs(&jobs).await;
             if deleted_files > 0 || pruned_tokens > 0 {
                 let freed_mb = freed as f64 / (1024.0 * 1024.0);
                 tracing::info!(
@@ -196,55 +217,4 @@ mod temp_cleanup_tests {
         assert_eq!(deleted, 1, "only the file should be deleted, not the dir");
         assert!(tmp.path().join("subdir").exists());
     }
-
-    #[tokio::test]
-    async fn prunes_video_downloads_older_than_max_age() {
-        use super::{VideoDownloads, prune_stale_video_downloads};
-        use crate::handlers::video_api::{DownloadStatus, VideoTask};
-        use std::sync::{
-            Arc,
-            atomic::{AtomicU8, Ordering},
-        };
-
-        let downloads: VideoDownloads =
-            Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new()));
-
-        fn task_with_age(age: Duration) -> VideoTask {
-            VideoTask {
-                status: DownloadStatus::Ready {
-                    size_mb: 1.0,
-                    filename: "x.mp4".to_string(),
-                },
-                progress: Arc::new(AtomicU8::new(100)),
-                file_path: std::path::PathBuf::new(),
-                filename: "x.mp4".to_string(),
-                parts: Vec::new(),
-                // Back-date created_at by subtracting from Instant::now().
-                created_at: std::time::Instant::now() - age,
-                library_id: None,
-            }
-        }
-
-        {
-            let mut map = downloads.lock().await;
-            map.insert("stale1".into(), task_with_age(Duration::from_secs(3600)));
-            map.insert("stale2".into(), task_with_age(Duration::from_secs(3600)));
-            map.insert("fresh".into(), task_with_age(Duration::from_secs(10)));
-        }
-
-        let pruned = prune_stale_video_downloads(&downloads, Duration::from_secs(30 * 60)).await;
-        assert_eq!(pruned, 2, "both stale tokens should be pruned");
-
-        let map = downloads.lock().await;
-        assert!(map.contains_key("fresh"), "fresh token must survive");
-        assert!(!map.contains_key("stale1"));
-        assert!(!map.contains_key("stale2"));
-        // sanity: progress atomic is unused in this test but Clippy might
-        // complain about it being dead if we drop it silently.
-        assert_eq!(
-            map["fresh"].progress.load(Ordering::Relaxed),
-            100,
-            "progress atomic survives unchanged"
-        );
-    }
 }
@@ -1,19 +1,25 @@
 //! yt-dlp download flow: info, prepare, status, file serving, recent, cleanup.
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, Ordering};
 
 use axum::Json;
 use axum::extract::{Path, State};
 use axum::http::{StatusCode, header};
 use axum::response::IntoResponse;
+use cr_domain::export_preset::{ExportTarget, PlanError, plan};
+use cr_domain::repository::VideoJobRepository;
+use cr_domain::video_job::{
+    ANIMATION_FPS, ANIMATION_WIDTHS, AUDIO_BITRATES, AnimationFormat, AnimationOptions,
+    AudioFormat, AudioOptions, ClipRange, MAX_ANIMATION_SECS, NewVideoJob, SubtitleOptions,
+    VideoJob, VideoJobState, parse_timestamp,
+};
+use cr_infra::video::VideoInfo;
 use serde::{Deserialize, Serialize};
 
 use crate::state::AppState;
 
 use super::{
-    DownloadStatus, PartInfo, PartResponse, TMP_VIDEO_DIR, VIDEO_DOWNLOAD_SEMAPHORE,
-    VideoErrorResponse, VideoTask, content_type_for_filename, sanitize_error,
+    DownloadStatus, TMP_VIDEO_DIR, VideoErrorResponse, content_type_for_filename, sanitize_error,
     sanitize_filename_ascii,
 };
 
@@ -31,7 +37,25 @@ pub struct VideoInfoResponse {
     duration: Option<f64>,
     uploader: Option<String>,
     formats: Vec<FormatResponse>,
-    whatsapp_parts: u32,
+    exports: Vec<ExportResponse>,
+    subtitles: Vec<SubtitleResponse>,
+}
+
+/// A subtitle track the video can be prepared with.
+#[derive(Serialize)]
+pub struct SubtitleResponse {
+    lang: String,
+    label: String,
+}
+
+/// One export preset as offered for this video.
+#[derive(Serialize)]
+pub struct ExportResponse {
+    id: &'static str,
+    label: &'static str,
+    /// Files the export will produce; `None` when the video is too long
+    /// for the preset.
+    parts: Option<u32>,
 }
 
 #[derive(Serialize)]
@@ -44,8 +68,11 @@ pub struct FormatResponse {
 #[derive(Deserialize)]
 pub struct VideoPrepareRequest {
     url: String,
+    /// `"video"`, `"audio"` for an audio-only download — then
+    /// `container` names the audio format (see [`AudioFormat`]) — or
+    /// `"gif"` / `"webp"` for an animation of the clip (see
+    /// [`AnimationFormat`]).
     #[serde(default = "default_format")]
-    #[allow(dead_code)]
     format: String,
     #[serde(default = "default_quality")]
     quality: String,
@@ -57,6 +84,30 @@ pub struct VideoPrepareRequest {
     /// because Streamtape re-encodes every upload.
     #[serde(default = "default_container")]
     container: String,
+    /// kbit/s for lossy audio formats, one of [`AUDIO_BITRATES`];
+    /// defaults to [`DEFAULT_AUDIO_BITRATE`](cr_domain::video_job::DEFAULT_AUDIO_BITRATE).
+    #[serde(default)]
+    audio_bitrate: Option<u32>,
+    /// Clip to keep, as typed: seconds, `m:ss` or `h:mm:ss`. Either may be
+    /// left out for the start / end of the video; animations need both.
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+    /// Animation frame rate and width, within [`ANIMATION_FPS`] and
+    /// [`ANIMATION_WIDTHS`].
+    #[serde(default)]
+    fps: Option<u32>,
+    #[serde(default)]
+    width: Option<u32>,
+    /// Language of the subtitle track to add, one of those `video_info`
+    /// listed; video downloads only.
+    #[serde(default)]
+    subtitles: Option<String>,
+    /// Burn the subtitles into the picture instead of adding a soft
+    /// track. Exports can only take them burned.
+    #[serde(default)]
+    burn_subtitles: bool,
 }
 
 fn default_format() -> String {
@@ -88,6 +139,10 @@ pub struct VideoPrepareResponse {
 
 // --- Recent downloads + Cleanup response types ---
 
+/// Jobs consulted for the original filenames of the files in
+/// [`TMP_VIDEO_DIR`].
+const RECENT_JOBS: i64 = 200;
+
 #[derive(Serialize)]
 pub struct RecentFile {
     filename: String,
@@ -107,7 +162,7 @@ pub async fn video_info(
     State(state): State<AppState>,
     Json(req): Json<VideoInfoRequest>,
 ) -> Result<Json<VideoInfoResponse>, (StatusCode, Json<VideoErrorResponse>)> {
-    let url = req.url.trim().to_string();
+    let url = cr_infra::video::canonical_video_url(&req.url);
 
     if url.is_empty() {
         return Err((
@@ -118,16 +173,14 @@ pub async fn video_info(
         ));
     }
 
-    let info = cr_infra::video::extract_video_info(&state.http_client, &url)
-        .await
-        .map_err(|e| {
-            tracing::error!("Video extraction failed for {url}: {e}");
-            let msg = sanitize_error(&e.to_string());
-            (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(VideoErrorResponse { error: msg }),
-            )
-        })?;
+    let info = extract_info(&state, &url).await.map_err(|e| {
+        tracing::error!("Video extraction failed for {url}: {e}");
+        let msg = sanitize_error(&e.to_string());
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(VideoErrorResponse { error: msg }),
+        )
+    })?;
 
     let thumbnail = info.thumbnail.map(|t| {
         if t.contains("cdninstagram.com") || t.contains("fbcdn.net") {
@@ -137,10 +190,17 @@ pub async fn video_info(
         }
     });
 
-    let whatsapp_parts = info
-        .duration
-        .map(cr_infra::video::estimate_whatsapp_parts)
-        .unwrap_or(1);
+    let exports = ExportTarget::ALL
+        .into_iter()
+        .map(|target| ExportResponse {
+            id: target.as_str(),
+            label: target.preset().label,
+            parts: match info.duration {
+                Some(duration) => plan(target.preset(), duration).ok().map(|p| p.parts),
+                None => Some(1),
+            },
+        })
+        .collect();
 
     Ok(Json(VideoInfoResponse {
         title: info.title,
@@ -156,7 +216,15 @@ pub async fn video_info(
                 ext: f.ext.clone(),
             })
             .collect(),
-        whatsapp_parts,
+        exports,
+        subtitles: info
+            .subtitles
+            .into_iter()
+            .map(|t| SubtitleResponse {
+                lang: t.lang,
+                label: t.label,
+            })
+            .collect(),
     }))
 }
 
@@ -164,11 +232,33 @@ pub async fn video_prepare(
     State(state): State<AppState>,
     Json(req): Json<VideoPrepareRequest>,
 ) -> Result<Json<VideoPrepareResponse>, (StatusCode, Json<VideoErrorResponse>)> {
-    let url = req.url.trim().to_string();
+    // Canonical, so the job, the library lookup and duplicate detection
+    // all see one spelling of the video's URL.
+    let url = cr_infra::video::canonical_video_url(&req.url);
+
+    let mut container = req.container.trim().to_lowercase();
+    let audio = if req.format == "audio" {
+        Some(audio_options(&container, req.audio_bitrate)?)
+    } else {
+        None
+    };
+    let animation = match AnimationFormat::parse(&req.format) {
+        Some(format) => Some(animation_options(format, req.fps, req.width)?),
+        None => None,
+    };
+    if let Some(animation) = animation {
+        container = animation.format.as_str().to_string();
+    }
+    let start = timestamp(req.start.as_deref())?;
+    let end = timestamp(req.end.as_deref())?;
+    if animation.is_some() && (start.is_none() || end.is_none()) {
+        return Err(bad_request(
+            "Pro animaci zadejte začátek i konec úseku.".to_string(),
+        ));
+    }
 
     // #366 — validate container; reject anything outside the tested set.
-    let container = req.container.trim().to_lowercase();
-    if !ALLOWED_CONTAINERS.contains(&container.as_str()) {
+    if audio.is_none() && animation.is_none() && !ALLOWED_CONTAINERS.contains(&container.as_str()) {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(VideoErrorResponse {
@@ -181,22 +271,54 @@ pub async fn video_prepare(
     }
 
     // Extract video info to get download URL
-    let info = cr_infra::video::extract_video_info(&state.http_client, &url)
-        .await
-        .map_err(|e| {
-            tracing::error!("Video extraction failed for {url}: {e}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(VideoErrorResponse {
-                    error: format!("Nepodařilo se získat info o videu: {e}"),
-                }),
-            )
-        })?;
+    let info = extract_info(&state, &url).await.map_err(|e| {
+        tracing::error!("Video extraction failed for {url}: {e}");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(VideoErrorResponse {
+                error: format!("Nepodařilo se získat info o videu: {e}"),
+            }),
+        )
+    })?;
 
-    let is_whatsapp = req.quality == "whatsapp";
-    // WhatsApp always produces MP4 (H.264/AAC) via ffmpeg post-processing —
-    // the user-picked container doesn't apply to that variant.
-    let effective_container: &str = if is_whatsapp { "mp4" } else { &container };
+    let clip = clip_range(start, end, info.duration)?;
+    if let Some(clip) = clip.filter(|c| animation.is_some() && c.len_secs() > MAX_ANIMATION_SECS) {
+        return Err(bad_request(format!(
+            "Animace může mít nejvýše {MAX_ANIMATION_SECS:.0} s, úsek má {:.0} s.",
+            clip.len_secs()
+        )));
+    }
+    // What gets exported is the clip, if there is one.
+    let duration = clip.map(ClipRange::len_secs).or(info.duration);
+
+    // Export presets ride on `quality` (`"whatsapp"`, `"discord"`, …).
+    let export =
+        ExportTarget::parse(&req.quality).filter(|_| audio.is_none() && animation.is_none());
+    if let (Some(target), Some(duration)) = (export, duration)
+        && let Err(PlanError::TooLong { max_secs }) = plan(target.preset(), duration)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(VideoErrorResponse {
+                error: format!(
+                    "Video je pro {} příliš dlouhé — vejde se nejvýše {} min.",
+                    target.preset().label,
+                    max_secs / 60
+                ),
+            }),
+        ));
+    }
+    let subtitles = subtitle_options(
+        &info.subtitles,
+        req.subtitles.as_deref(),
+        req.burn_subtitles,
+        audio.is_some() || animation.is_some(),
+        export.is_some(),
+    )?;
+
+    // Exports always produce MP4 (H.264/AAC) via ffmpeg post-processing —
+    // the user-picked container doesn't apply to them.
+    let effective_container: &str = if export.is_some() { "mp4" } else { &container };
 
     // --- #320/#366 Library lookup ---
     // Library rows are always MP4 (Streamtape re-encodes every upload
@@ -220,8 +342,10 @@ pub async fn video_prepare(
     //      publish fires on success, a new MP4 library row is born.
     //
     // Anything other than a successful hit keeps `should_publish = true`.
-    let mut should_publish = true;
-    if let Some(pipeline) = state.video_library.as_ref() {
+    // Audio downloads, clips, animations and subtitled videos skip all of
+    // this: the library only holds whole videos as the source has them.
+    let mut should_publish = audio.is_none() && clip.is_none() && subtitles.is_none();
+    if let Some(pipeline) = state.video_library.as_ref().filter(|_| should_publish) {
         match pipeline.find_existing(&url, &req.quality, "mp4").await {
             Ok(Some(existing)) => {
                 // Bump the card to the top of the grid, regardless of
@@ -234,29 +358,37 @@ pub async fn video_prepare(
                 if effective_container == "mp4" {
                     // Branch 1 — serve directly from the library.
                     let token = uuid::Uuid::new_v4().to_string();
-                    let size_mb = existing.file_size_bytes as f64 / (1024.0 * 1024.0);
                     let filename = format!(
                         "{}.{}",
                         sanitize_filename_ascii(&existing.title, 60),
                         existing.format_ext
                     );
-                    state.video_downloads.lock().await.insert(
-                        token.clone(),
-                        VideoTask {
-                            status: DownloadStatus::Ready {
-                                size_mb: (size_mb * 10.0).round() / 10.0,
-                                filename: filename.clone(),
-                            },
-                            progress: Arc::new(AtomicU8::new(100)),
-                            // No local file — `video_file` will 303
-                            // the client onto `/api/video/library/{id}/file`.
-                            file_path: std::path::PathBuf::new(),
-                            filename,
-                            parts: Vec::new(),
-                            created_at: std::time::Instant::now(),
-                            library_id: Some(existing.id),
-                        },
-                    );
+                    // No local file — `video_file` will 303 the client
+                    // onto `/api/video/library/{id}/file`.
+                    let job = NewVideoJob {
+                        token: token.clone(),
+                        source_url: url.clone(),
+                        format_id: existing.quality.clone(),
+                        resolution: existing.resolution.clone().unwrap_or_default(),
+                        container: existing.format_ext.clone(),
+                        export: None,
+                        audio: None,
+                        clip: None,
+                        animation: None,
+                        subtitles: None,
+                        publish: false,
+                        filename,
+                        title: existing.title.clone(),
+                        duration_sec: existing.duration_sec,
+                        uploader: existing.source_extractor.clone(),
+                        thumbnail_url: existing.thumbnail_url.clone(),
+                    };
+                    state
+                        .video_queue
+                        .jobs
+                        .record_library_hit(&job, existing.id, existing.file_size_bytes)
+                        .await
+                        .map_err(queue_error)?;
                     tracing::info!(
                         "video library dedup hit: token={token} streamtape_id={}",
                         existing.streamtape_file_id
@@ -278,15 +410,28 @@ pub async fn video_prepare(
 
     // Find the requested quality
     let quality = &req.quality;
-    let format = if is_whatsapp {
-        // #366 — WhatsApp downloads at ≤480p for a fast user-facing
-        // turnaround. `convert_for_whatsapp` downscales / re-encodes
-        // further as needed to fit the 16 MB WhatsApp limit; each
-        // resulting wa file is independently published to the
-        // library so users see one card per WhatsApp variant in the
-        // "Stažená videa" grid — single-file output gives one card,
-        // a 3-way split gives three cards, matching the "treat a
-        // WhatsApp download like any other video" rule.
+    let format = if audio.is_some() {
+        // The extractors fetch the best audio stream whatever the format;
+        // the id only matters to those serving muxed files, and there the
+        // best video usually carries the best audio.
+        info.formats.iter().max_by_key(|f| {
+            f.resolution
+                .trim_end_matches('p')
+                .parse::<u32>()
+                .unwrap_or(0)
+        })
+    } else if let Some(target) = export {
+        // #366 — exports download at no more than the preset's
+        // resolution (480p for WhatsApp) for a fast user-facing
+        // turnaround. `export_for_target` downscales / re-encodes
+        // further as needed to fit the platform's limits; each
+        // resulting file is independently published to the library so
+        // users see one card per export variant in the "Stažená videa"
+        // grid — single-file output gives one card, a 3-way split gives
+        // three cards, matching the "treat an export like any other
+        // video" rule.
+        let preset = target.preset();
+        let max_height = preset.max_width.min(preset.max_height);
         info.formats
             .iter()
             .filter(|f| {
@@ -294,7 +439,26 @@ pub async fn video_prepare(
                     .trim_end_matches('p')
                     .parse::<u32>()
                     .unwrap_or(0)
-                    <= 480
+                    <= max_height
+            })
+            .max_by_key(|f| {
+                f.resolution
+                    .trim_end_matches('p')
+                    .parse::<u32>()
+                    .unwrap_or(0)
+            })
+            .or(info.formats.last())
+    } else if animation.is_some() {
+        // Animations end up at most 1280 px wide; 720p is plenty to
+        // scale down from and quick to fetch.
+        info.formats
+            .iter()
+            .filter(|f| {
+                f.resolution
+                    .trim_end_matches('p')
+                    .parse::<u32>()
+                    .unwrap_or(0)
+                    <= 720
             })
             .max_by_key(|f| {
                 f.resolution
@@ -319,19 +483,7 @@ pub async fn video_prepare(
         )
     })?;
 
-    // Create temp directory if it doesn't exist
-    let tmp_dir = std::path::PathBuf::from(TMP_VIDEO_DIR);
-    tokio::fs::create_dir_all(&tmp_dir).await.map_err(|e| {
-        tracing::error!("Failed to create tmp dir: {e}");
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(VideoErrorResponse {
-                error: "Server error".to_string(),
-            }),
-        )
-    })?;
-
-    // Generate token and file path
+    // Generate token and filename
     let token = uuid::Uuid::new_v4().to_string();
     let decoded_title = info
         .title
@@ -348,386 +500,265 @@ pub async fn video_prepare(
     // #366 — the on-disk file is always `.{container}` regardless of
     // what yt-dlp picks internally. `ensure_container` (infra) will
     // transcode via ffmpeg when needed so the invariant holds.
-    let filename = format!("{safe_title}.{effective_container}");
-    let file_path = tmp_dir.join(format!("{token}.{effective_container}"));
-
-    // Store task as "downloading" with shared progress counter
-    let progress = Arc::new(AtomicU8::new(0));
-    state.video_downloads.lock().await.insert(
-        token.clone(),
-        VideoTask {
-            status: DownloadStatus::Downloading {
-                progress_percent: 0,
-            },
-            progress: progress.clone(),
-            file_path: file_path.clone(),
-            filename,
-            parts: Vec::new(),
-            created_at: std::time::Instant::now(),
-            library_id: None,
-        },
-    );
-
-    // Spawn download in background — returns immediately
-    let dl_token = token.clone();
-    let dl_url = url.clone();
-    let dl_format_id = format.format_id.clone();
-    let dl_resolution = format.resolution.clone();
-    let dl_container = effective_container.to_string();
-    let dl_state = state.clone();
-    let dl_client = state.http_client.clone();
-    // Captured for the post-download library publish (#319). The publish
-    // happens fire-and-forget after the user-facing Ready status is set,
-    // so a slow Streamtape upload never blocks the local download flow.
-    //
-    // `format_ext` is hard-coded to `"mp4"` because Streamtape
-    // re-encodes every upload to H.264 MP4 regardless of what file
-    // we hand it (#366). Storing `"webm"` or `"mkv"` would be a lie
-    // — the library bytes are always MP4. The user's container
-    // choice only affects the local temp file served via the
-    // ready-link; the library publish path is container-agnostic.
-    //
-    // `quality` comes from the picked format, not from the raw
-    // request — this matters for WhatsApp, where the request
-    // quality is the `"whatsapp"` sentinel but we actually download
-    // a full-resolution MP4 (`"1080p"` / `"720p"` / ...) so the user
-    // gets both a shareable WhatsApp file and a permanent library
-    // card at the real source quality.
-    let publish_meta_template = cr_infra::video_library::PublishMetadata {
+    let filename = match clip {
+        Some(clip) => format!("{safe_title} (klip {}).{effective_container}", clip.label()),
+        None => format!("{safe_title}.{effective_container}"),
+    };
+
+    // The queue worker does the download (see `queue.rs`); everything it
+    // needs, including the metadata for the post-download library publish
+    // (#319), goes into the job. `format_id` comes from the picked format,
+    // not from the raw request — this matters for exports, where the
+    // request quality is the preset sentinel (`"whatsapp"`, …) but we
+    // actually download a real source format so the user gets both a
+    // shareable file and a permanent library card at the source quality.
+    let job = NewVideoJob {
+        token: token.clone(),
         source_url: url.clone(),
-        title: decoded_title.clone(),
-        description: None,
+        format_id: format.format_id.clone(),
+        resolution: format.resolution.clone(),
+        container: effective_container.to_string(),
+        export,
+        audio,
+        clip,
+        animation,
+        subtitles,
+        publish: should_publish,
+        filename,
+        title: decoded_title,
         duration_sec: info.duration.map(|d| d as i32),
-        source_extractor: info.uploader.clone(),
-        quality: format.format_id.clone(),
-        format_ext: "mp4".to_string(),
-        // #366 — store the human-readable resolution yt-dlp reported
-        // on the picked format (e.g. "720p"). Used by the library
-        // card's top-right badge; falls back to hidden when yt-dlp
-        // couldn't tell us a resolution for this source.
-        resolution: Some(format.resolution.clone()).filter(|s| !s.is_empty()),
-        upstream_thumbnail_url: info.thumbnail.clone(),
+        uploader: info.uploader.clone(),
+        thumbnail_url: info.thumbnail.clone(),
     };
+    let queued = state.video_queue.enqueue(&job).await.map_err(queue_error)?;
+    if queued == token {
+        tracing::info!("Video download queued: {token} for {url}");
+    } else {
+        tracing::info!("Video download for {url} joined running job {queued}");
+    }
 
-    // Check concurrency limit before spawning. The semaphore permit must
-    // move into the spawned task so it's released when the download
-    // finishes; extract it here (the is_err() branch above already bailed,
-    // so `ok()` is guaranteed to yield Some and gives us a panic-free path).
-    let Ok(permit) = VIDEO_DOWNLOAD_SEMAPHORE.try_acquire() else {
-        if let Some(t) = state.video_downloads.lock().await.get_mut(&token) {
-            t.status = DownloadStatus::Failed {
-                error: "Příliš mnoho souběžných stahování. Zkuste to za chvíli.".to_string(),
-            };
-        }
-        return Ok(Json(VideoPrepareResponse { token }));
-    };
+    Ok(Json(VideoPrepareResponse { token: queued }))
+}
 
-    tokio::spawn(async move {
-        let _permit = permit;
-
-        let result = cr_infra::video::download_video_with_progress(
-            &dl_client,
-            &dl_url,
-            &dl_format_id,
-            &dl_resolution,
-            &dl_container,
-            &file_path,
-            Some(progress.clone()),
-        )
-        .await;
-
-        let mut downloads = dl_state.video_downloads.lock().await;
-        if let Some(task) = downloads.get_mut(&dl_token) {
-            match result {
-                Ok(_size) if is_whatsapp => {
-                    // WhatsApp: convert with ffmpeg. ASCII-only sanitiser to
-                    // keep the resulting filename safe in HTTP headers.
-                    let stem = task
-                        .filename
-                        .trim_end_matches(".mp4")
-                        .trim_end_matches(".webm");
-                    let safe = sanitize_filename_ascii(stem, 50);
-                    task.status = DownloadStatus::Converting {
-                        progress_percent: 0,
-                    };
-                    drop(downloads); // release lock during conversion
-
-                    let wa_result = cr_infra::video::convert_for_whatsapp(
-                        &file_path,
-                        &std::path::PathBuf::from(TMP_VIDEO_DIR),
-                        &dl_token,
-                        Some(progress),
-                    )
-                    .await;
-
-                    let mut downloads = dl_state.video_downloads.lock().await;
-                    if let Some(task) = downloads.get_mut(&dl_token) {
-                        match wa_result {
-                            Ok(cr_infra::video::WhatsAppResult::Single { path, size }) => {
-                                let size_mb = size as f64 / (1024.0 * 1024.0);
-                                let fname = format!("{safe} (WhatsApp).mp4");
-                                task.file_path = path.clone();
-                                task.filename = fname.clone();
-                                task.status = DownloadStatus::Ready {
-                                    size_mb: (size_mb * 10.0).round() / 10.0,
-                                    filename: fname,
-                                };
-                                tracing::info!(
-                                    "WhatsApp video ready: {dl_token} ({size_mb:.1} MB)"
-                                );
-                                drop(downloads);
-
-                                // #371 review — the pre-conversion yt-dlp
-                                // temp file is no longer referenced by
-                                // anything (task.file_path now points at
-                                // the wa output). Unlink it immediately
-                                // so disk usage drops right away instead
-                                // of waiting for the 30-min reaper.
-                                if let Err(e) = tokio::fs::remove_file(&file_path).await {
-                                    tracing::warn!(
-                                        "failed to unlink pre-WhatsApp temp file {:?}: {e}",
-                                        file_path
-                                    );
-                                }
-
-                                // #366 — publish the WhatsApp wa file
-                                // to the library as its own row. The
-                                // quality is `"whatsapp"` so it
-                                // coexists with any regular-download
-                                // row for the same URL under a
-                                // different dedup key.
-                                if should_publish
-                                    && let Some(pipeline) = dl_state.video_library.clone()
-                                {
-                                    let mut meta = publish_meta_template.clone();
-                                    meta.quality = "whatsapp".to_string();
-                                    meta.resolution = Some("480p".to_string());
-                                    let path = path.clone();
-                                    tokio::spawn(async move {
-                                        match pipeline.publish_local_video(&path, meta).await {
-                                            Ok(rec) => tracing::info!(
-                                                "whatsapp library publish OK: id={} streamtape_id={}",
-                                                rec.id,
-                                                rec.streamtape_file_id
-                                            ),
-                                            Err(e) => tracing::warn!(
-                                                "whatsapp library publish failed: {e}"
-                                            ),
-                                        }
-                                    });
-                                }
-                            }
-                            Ok(cr_infra::video::WhatsAppResult::Parts(parts)) => {
-                                let part_count = parts.len();
-                                let part_infos: Vec<PartInfo> = parts
-                                    .iter()
-                                    .map(|p| {
-                                        let size_mb = p.size as f64 / (1024.0 * 1024.0);
-                                        PartInfo {
-                                            index: p.index,
-                                            filename: format!(
-                                                "{safe} (WhatsApp část {}).mp4",
-                                                p.index + 1
-                                            ),
-                                            size_mb: (size_mb * 10.0).round() / 10.0,
-                                            file_path: p.path.clone(),
-                                        }
-                                    })
-                                    .collect();
-                                let part_responses: Vec<PartResponse> = part_infos
-                                    .iter()
-                                    .map(|p| PartResponse {
-                                        index: p.index,
-                                        filename: p.filename.clone(),
-                                        size_mb: p.size_mb,
-                                    })
-                                    .collect();
-                                task.parts = part_infos.clone();
-                                task.status = DownloadStatus::ReadyParts {
-                                    parts: part_responses,
-                                };
-                                tracing::info!(
-                                    "WhatsApp video ready: {dl_token} ({part_count} parts)"
-                                );
-                                drop(downloads);
-
-                                // #371 review — the pre-conversion yt-dlp
-                                // temp file is no longer referenced by
-                                // anything (task.parts now point at the
-                                // split wa outputs). Unlink it immediately
-                                // so disk usage drops right away instead
-                                // of waiting for the 30-min reaper.
-                                if let Err(e) = tokio::fs::remove_file(&file_path).await {
-                                    tracing::warn!(
-                                        "failed to unlink pre-WhatsApp temp file {:?}: {e}",
-                                        file_path
-                                    );
-                                }
-
-                                // #366 — publish each WhatsApp part as
-                                // its own library row. Quality is
-                                // `"whatsapp-part{i}"` so all N parts
-                                // live under distinct dedup keys and
-                                // show up as N independent cards in
-                                // the grid. Title carries an "X/N"
-                                // suffix so the user can distinguish
-                                // them at a glance.
-                                if should_publish
-                                    && let Some(pipeline) = dl_state.video_library.clone()
-                                {
-                                    let base_title = publish_meta_template.title.clone();
-                                    let base_meta = publish_meta_template.clone();
-                                    for p in &part_infos {
-                                        let mut meta = base_meta.clone();
-                                        meta.title = format!(
-                                            "{base_title} — WhatsApp část {}/{}",
-                                            p.index + 1,
-                                            part_count
-                                        );
-                                        meta.quality = format!("whatsapp-part{}", p.index);
-                                        meta.resolution = Some("480p".to_string());
-                                        let path = p.file_path.clone();
-                                        let pipeline = pipeline.clone();
-                                        let idx = p.index;
-                                        tokio::spawn(async move {
-                                            match pipeline.publish_local_video(&path, meta).await {
-                                                Ok(rec) => tracing::info!(
-                                                    "whatsapp part{idx} library publish OK: id={} streamtape_id={}",
-                                                    rec.id,
-                                                    rec.streamtape_file_id
-                                                ),
-                                                Err(e) => tracing::warn!(
-                                                    "whatsapp part{idx} library publish failed: {e}"
-                                                ),
-                                            }
-                                        });
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!("WhatsApp conversion failed: {e}");
-                                task.status = DownloadStatus::Failed {
-                                    error: format!("Konverze pro WhatsApp selhala: {e}"),
-                                };
-                            }
-                        }
-                    }
-                }
-                Ok(size) => {
-                    let size_mb = size as f64 / (1024.0 * 1024.0);
-                    tracing::info!("Video ready: {dl_token} ({size_mb:.1} MB) for {dl_url}");
-                    task.status = DownloadStatus::Ready {
-                        size_mb: (size_mb * 10.0).round() / 10.0,
-                        filename: task.filename.clone(),
-                    };
-                    // #319 — fire-and-forget publish to the library. Local
-                    // file is intentionally kept on disk by `publish_local_video`
-                    // (see #363) so the user can still download it via
-                    // `/api/video/file/{token}` after the upload finishes.
-                    // Temp files are reaped by `DELETE /api/video/cleanup`.
-                    //
-                    // #366 — skip publish entirely when the library
-                    // already has a MP4 row for this URL+quality
-                    // (`should_publish = false`). That happens when
-                    // the user asked for WebM/MKV and we did a fresh
-                    // yt-dlp + transcode alongside an existing MP4
-                    // row: republishing would either hit the unique
-                    // constraint or duplicate work Streamtape just
-                    // did. The `touch` call already bumped the row
-                    // to the top of the grid in the handler above.
-                    if should_publish && let Some(pipeline) = dl_state.video_library.clone() {
-                        let publish_path = file_path.clone();
-                        let publish_meta = publish_meta_template.clone();
-                        tokio::spawn(async move {
-                            match pipeline
-                                .publish_local_video(&publish_path, publish_meta)
-                                .await
-                            {
-                                Ok(rec) => tracing::info!(
-                                    "video library publish OK: id={} streamtape_id={}",
-                                    rec.id,
-                                    rec.streamtape_file_id
-                                ),
-                                Err(e) => {
-                                    tracing::warn!("video library publish failed: {e} — local-only")
-                                }
-                            }
-                        });
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Video download failed for {dl_url}: {e}");
-                    task.status = DownloadStatus::Failed {
-                        error: format!("Stažení se nezdařilo: {e}"),
-                    };
+/// Video info for canonical `url`, from [`AppState::video_info_cache`] when
+/// fresh. Concurrent misses for the same URL extract once: each takes the
+/// URL's lock and re-checks the cache before extracting.
+async fn extract_info(state: &AppState, url: &str) -> anyhow::Result<VideoInfo> {
+    let key = url.to_string();
+    if let Some(info) = state.video_info_cache.get(&key).await {
+        return Ok(info);
+    }
+    let lock = {
+        let mut map = state.video_info_in_flight.lock().await;
+        map.entry(key.clone())
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+            .clone()
+    };
+    let result = {
+        let _guard = lock.lock().await;
+        match state.video_info_cache.get(&key).await {
+            Some(info) => Ok(info),
+            None => {
+                let extracted = cr_infra::video::extract_video_info(&state.http_client, url).await;
+                if let Ok(info) = &extracted {
+                    state
+                        .video_info_cache
+                        .insert(key.clone(), info.clone())
+                        .await;
                 }
+                extracted
             }
         }
-    });
+    };
+    // Drop the map entry once no other request holds the lock; see
+    // `release_per_key_lock` in `movies_api::prehrajto` for why `<= 2`.
+    let mut map = state.video_info_in_flight.lock().await;
+    if Arc::strong_count(&lock) <= 2 {
+        map.remove(&key);
+    }
+    result
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<VideoErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(VideoErrorResponse { error }))
+}
+
+/// Audio format and bitrate of an audio-only request, or the 400 to send.
+fn audio_options(
+    container: &str,
+    bitrate: Option<u32>,
+) -> Result<AudioOptions, (StatusCode, Json<VideoErrorResponse>)> {
+    let Some(format) = AudioFormat::parse(container) else {
+        let known: Vec<&str> = AudioFormat::ALL.iter().map(|f| f.as_str()).collect();
+        return Err(bad_request(format!(
+            "Neznámý zvukový formát '{container}' — podporujeme pouze {}.",
+            known.join(", ")
+        )));
+    };
+    AudioOptions::new(format, bitrate).ok_or_else(|| {
+        let known: Vec<String> = AUDIO_BITRATES.iter().map(|b| b.to_string()).collect();
+        bad_request(format!(
+            "Nepodporovaný datový tok — vyberte {} kbit/s.",
+            known.join(", ")
+        ))
+    })
+}
+
+/// Frame rate and width of an animation request, or the 400 to send.
+fn animation_options(
+    format: AnimationFormat,
+    fps: Option<u32>,
+    width: Option<u32>,
+) -> Result<AnimationOptions, (StatusCode, Json<VideoErrorResponse>)> {
+    AnimationOptions::new(format, fps, width).ok_or_else(|| {
+        bad_request(format!(
+            "Animace může mít {}–{} snímků/s a šířku {}–{} px.",
+            ANIMATION_FPS.start(),
+            ANIMATION_FPS.end(),
+            ANIMATION_WIDTHS.start(),
+            ANIMATION_WIDTHS.end()
+        ))
+    })
+}
+
+/// The subtitle track `lang` names among the video's `tracks`, or the 400
+/// to send. Audio and animations (`no_video`) take none; exports only
+/// burned ones, since they re-encode and would drop a soft track.
+fn subtitle_options(
+    tracks: &[cr_infra::video::SubtitleTrack],
+    lang: Option<&str>,
+    burn: bool,
+    no_video: bool,
+    export: bool,
+) -> Result<Option<SubtitleOptions>, (StatusCode, Json<VideoErrorResponse>)> {
+    let Some(lang) = lang.map(str::trim).filter(|l| !l.is_empty()) else {
+        return Ok(None);
+    };
+    if no_video {
+        return Err(bad_request(
+            "Titulky lze přidat jen ke stahovanému videu.".to_string(),
+        ));
+    }
+    if export && !burn {
+        return Err(bad_request(
+            "Pro sdílení na platformy lze titulky jen vypálit do obrazu.".to_string(),
+        ));
+    }
+    let track = tracks
+        .iter()
+        .find(|t| t.lang == lang)
+        .ok_or_else(|| bad_request(format!("Video nemá titulky v jazyce '{lang}'.")))?;
+    Ok(Some(SubtitleOptions {
+        lang: track.lang.clone(),
+        url: track.url.clone(),
+        burn,
+    }))
+}
+
+/// A start / end field of the form; blank counts as not given.
+fn timestamp(field: Option<&str>) -> Result<Option<f64>, (StatusCode, Json<VideoErrorResponse>)> {
+    match field.map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(None),
+        Some(s) => parse_timestamp(s).map(Some).ok_or_else(|| {
+            bad_request(format!(
+                "Neplatný čas '{s}' — zadejte sekundy, m:ss nebo h:mm:ss."
+            ))
+        }),
+    }
+}
+
+/// The clip between `start` and `end`, checked against the video's
+/// `duration`; `None` when neither is given. A missing end means the end
+/// of the video.
+fn clip_range(
+    start: Option<f64>,
+    end: Option<f64>,
+    duration: Option<f64>,
+) -> Result<Option<ClipRange>, (StatusCode, Json<VideoErrorResponse>)> {
+    if start.is_none() && end.is_none() {
+        return Ok(None);
+    }
+    let start = start.unwrap_or(0.0);
+    let Some(end) = end.or(duration) else {
+        return Err(bad_request(
+            "Délka videa není známá — zadejte i konec úseku.".to_string(),
+        ));
+    };
+    if let Some(duration) = duration
+        && end > duration + 0.5
+    {
+        return Err(bad_request(format!(
+            "Úsek končí za koncem videa, které má {duration:.0} s."
+        )));
+    }
+    ClipRange::new(start, end.min(duration.unwrap_or(end)))
+        .map(Some)
+        .ok_or_else(|| bad_request("Konec úseku musí být až po jeho začátku.".to_string()))
+}
 
-    tracing::info!("Video download started: {token} for {url}");
+/// 500 for a queue the database couldn't reach.
+fn queue_error(e: sqlx::Error) -> (StatusCode, Json<VideoErrorResponse>) {
+    tracing::error!("video queue: {e}");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(VideoErrorResponse {
+            error: "Server error".to_string(),
+        }),
+    )
+}
 
-    Ok(Json(VideoPrepareResponse { token }))
+/// The job behind `token`, or the response to send instead.
+pub(super) async fn find_job(
+    state: &AppState,
+    token: &str,
+) -> Result<VideoJob, axum::response::Response> {
+    match state.video_queue.jobs.find(token).await {
+        Ok(Some(job)) => Ok(job),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "Video not found or expired").into_response()),
+        Err(e) => {
+            tracing::error!("video queue: looking up {token} failed: {e}");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Server error").into_response())
+        }
+    }
 }
 
 pub async fn video_status(
     State(state): State<AppState>,
     Path(token): Path<String>,
 ) -> impl IntoResponse {
-    let downloads = state.video_downloads.lock().await;
-    match downloads.get(&token) {
-        Some(task) => {
-            // For downloading/converting status, read live progress from atomic counter
-            let status = match &task.status {
-                DownloadStatus::Downloading { .. } => DownloadStatus::Downloading {
-                    progress_percent: task.progress.load(Ordering::Relaxed),
-                },
-                DownloadStatus::Converting { .. } => DownloadStatus::Converting {
-                    progress_percent: task.progress.load(Ordering::Relaxed),
-                },
-                other => other.clone(),
-            };
+    let (code, status) = match state.video_queue.jobs.find(&token).await {
+        Ok(Some(job)) => (StatusCode::OK, DownloadStatus::from_job(&job)),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            DownloadStatus::Failed {
+                error: "Video not found or expired".to_string(),
+            },
+        ),
+        Err(e) => {
+            tracing::error!("video queue: looking up {token} failed: {e}");
             (
-                StatusCode::OK,
-                [(header::CACHE_CONTROL, "no-store")],
-                Json(status),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                DownloadStatus::Failed {
+                    error: "Server error".to_string(),
+                },
             )
-                .into_response()
         }
-        None => (
-            StatusCode::NOT_FOUND,
-            [(header::CACHE_CONTROL, "no-store")],
-            Json(DownloadStatus::Failed {
-                error: "Video not found or expired".to_string(),
-            }),
-        )
-            .into_response(),
-    }
+    };
+    (code, [(header::CACHE_CONTROL, "no-store")], Json(status)).into_response()
 }
 
 pub async fn video_file(
     State(state): State<AppState>,
     Path(token): Path<String>,
 ) -> impl IntoResponse {
-    // Extract what we need from the task and drop the lock before any
-    // I/O so a slow filesystem read never blocks other handlers.
-    let (file_path, filename, library_id) = {
-        let downloads = state.video_downloads.lock().await;
-        let task = match downloads.get(&token) {
-            Some(t) if matches!(t.status, DownloadStatus::Ready { .. }) => t,
-            Some(_) => {
-                return (StatusCode::CONFLICT, "Video is still downloading").into_response();
-            }
-            None => {
-                return (StatusCode::NOT_FOUND, "Video not found or expired").into_response();
-            }
-        };
-        (
-            task.file_path.clone(),
-            task.filename.clone(),
-            task.library_id,
-        )
+    let job = match find_job(&state, &token).await {
+        Ok(job) if job.state == VideoJobState::Ready => job,
+        Ok(_) => {
+            return (StatusCode::CONFLICT, "Video is still downloading").into_response();
+        }
+        Err(response) => return response,
     };
+    let filename = job.filename;
 
     // Deduped downloads have no local temp file — point the client at
     // the library proxy instead so Streamtape serves the bytes. Using
@@ -735,12 +766,12 @@ pub async fn video_file(
     // redirection quirks for any hypothetical future caller that uses
     // a non-GET verb. Browsers follow this transparently for the
     // `download` attribute on the anchor that triggered it.
-    if let Some(id) = library_id {
+    if let Some(id) = job.library_id {
         return axum::response::Redirect::to(&format!("/api/video/library/{id}/file"))
             .into_response();
     }
 
-    let bytes = match tokio::fs::read(&file_path).await {
+    let bytes = match tokio::fs::read(job.file_path.unwrap_or_default()).await {
         Ok(b) => b,
         Err(e) => {
             tracing::error!("Failed to read video file: {e}");
@@ -769,18 +800,15 @@ pub async fn video_file_part(
     State(state): State<AppState>,
     Path((token, part_index)): Path<(String, usize)>,
 ) -> impl IntoResponse {
-    let downloads = state.video_downloads.lock().await;
-    let task = match downloads.get(&token) {
-        Some(t) if matches!(t.status, DownloadStatus::ReadyParts { .. }) => t,
-        Some(_) => {
+    let job = match find_job(&state, &token).await {
+        Ok(job) if job.state == VideoJobState::ReadyParts => job,
+        Ok(_) => {
             return (StatusCode::CONFLICT, "Video is still processing").into_response();
         }
-        None => {
-            return (StatusCode::NOT_FOUND, "Video not found or expired").into_response();
-        }
+        Err(response) => return response,
     };
 
-    let part = match task.parts.get(part_index) {
+    let part = match job.parts.get(part_index) {
         Some(p) => p,
         None => {
             return (StatusCode::NOT_FOUND, "Part not found").into_response();
@@ -810,17 +838,28 @@ pub async fn video_file_part(
 }
 
 pub async fn video_recent(State(state): State<AppState>) -> Json<Vec<RecentFile>> {
-    let downloads = state.video_downloads.lock().await;
+    // Jobs outlive their files by at most the reaper interval, so the
+    // recent ones cover everything still on disk.
+    let jobs = state
+        .video_queue
+        .jobs
+        .list_recent(RECENT_JOBS)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("video queue: listing jobs failed: {e}");
+            Vec::new()
+        });
     let mut files = Vec::new();
 
     let tmp_dir = std::path::PathBuf::from(TMP_VIDEO_DIR);
     if let Ok(mut entries) = tokio::fs::read_dir(&tmp_dir).await {
         while let Ok(Some(entry)) = entries.next_entry().await {
             if let Ok(meta) = entry.metadata().await {
-                let filename = downloads
-                    .values()
-                    .find(|p| p.file_path == entry.path())
-                    .map(|p| p.filename.clone())
+                let path = entry.path();
+                let filename = jobs
+                    .iter()
+                    .find(|j| j.file_path.as_deref().map(std::path::Path::new) == Some(&path))
+                    .map(|j| j.filename.clone())
                     .unwrap_or_else(|| entry.file_name().to_string_lossy().to_string());
 
                 let size_mb = meta.len() as f64 / (1024.0 * 1024.0);
@@ -848,8 +887,21 @@ pub async fn video_recent(State(state): State<AppState>) -> Json<Vec<RecentFile>
 }
 
 pub async fn video_cleanup(State(state): State<AppState>) -> Json<CleanupResponse> {
-    let mut downloads = state.video_downloads.lock().await;
-    downloads.clear();
+    // Queued and running jobs stay: their workers would only recreate
+    // the files. Finished ones go with the files they point at.
+    let finished = [
+        VideoJobState::Ready,
+        VideoJobState::ReadyParts,
+        VideoJobState::Failed,
+    ];
+    if let Err(e) = state
+        .video_queue
+        .jobs
+        .delete_finished(&finished, std::time::Duration::ZERO)
+        .await
+    {
+        tracing::warn!("video queue: deleting finished jobs failed: {e}");
+    }
 
     let tmp_dir = std::path::PathBuf::from(TMP_VIDEO_DIR);
     let mut deleted = 0;
@@ -0,0 +1,195 @@
+//! Server-Sent Events stream of a download's progress.
+//!
+//! `GET /api/video/status/{token}/events` pushes two kinds of events until
+//! the job finishes:
+//! - `status`   — the same JSON as `/api/video/status/{token}`, sent on
+//!   every phase change (queued → downloading → converting → ready /
+//!   ready_parts / failed) and as the first event;
+//! - `progress` — percentage, bytes, speed and ETA while the job runs.
+//!
+//! While one of this process's workers runs the job, the stream follows its
+//! [`ProgressTracker`](cr_infra::video::ProgressTracker) directly. Otherwise
+//! (still queued, running in another process, finished) it reads the
+//! database every [`DB_POLL_INTERVAL`]. The stream closes after the final
+//! `status` event.
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use cr_domain::repository::VideoJobRepository;
+use cr_domain::video_job::VideoJobState;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::state::AppState;
+
+use super::DownloadStatus;
+use super::download::find_job;
+
+/// How often a job without live progress in this process is re-read.
+const DB_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Minimum gap between two `progress` events; yt-dlp prints many lines a
+/// second and browsers don't need them all.
+const PROGRESS_THROTTLE: Duration = Duration::from_millis(250);
+
+#[derive(Serialize, PartialEq)]
+struct ProgressEvent {
+    /// `downloading` or `converting`
+    phase: &'static str,
+    progress_percent: u8,
+    downloaded_bytes: Option<u64>,
+    total_bytes: Option<u64>,
+    speed_bps: Option<f64>,
+    eta_secs: Option<u64>,
+}
+
+fn phase(state: VideoJobState) -> &'static str {
+    match state {
+        VideoJobState::Converting => "converting",
+        _ => "downloading",
+    }
+}
+
+/// Sends events, remembering the last of each kind so repeats are dropped.
+struct Emitter {
+    tx: mpsc::Sender<Result<Event, Infallible>>,
+    last_status: Option<String>,
+    last_progress: Option<ProgressEvent>,
+}
+
+impl Emitter {
+    /// Send `status` if it says something new. For a running job that's
+    /// only a phase change — the percentage goes out as `progress`.
+    /// `Err` once the client has gone.
+    async fn status(&mut self, status: &DownloadStatus) -> Result<(), ()> {
+        let json = serde_json::to_string(status).unwrap_or_default();
+        let key = match status {
+            DownloadStatus::Downloading { .. } => "downloading".to_string(),
+            DownloadStatus::Converting { .. } => "converting".to_string(),
+            _ => json.clone(),
+        };
+        if self.last_status.as_ref() == Some(&key) {
+            return Ok(());
+        }
+        self.last_status = Some(key);
+        let ev = Event::default().event("status").data(json);
+        self.tx.send(Ok(ev)).await.map_err(|_| ())
+    }
+
+    async fn progress(&mut self, progress: ProgressEvent) -> Result<(), ()> {
+        if self.last_progress.as_ref() == Some(&progress) {
+            return Ok(());
+        }
+        let json = serde_json::to_string(&progress).unwrap_or_default();
+        let ev = Event::default().event("progress").data(json);
+        self.last_progress = Some(progress);
+        self.tx.send(Ok(ev)).await.map_err(|_| ())
+    }
+}
+
+/// GET /api/video/status/{token}/events
+pub async fn video_status_events(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Response {
+    if let Err(resp) = find_job(&state, &token).await {
+        return resp;
+    }
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(async move {
+        let mut emitter = Emitter {
+            tx,
+            last_status: None,
+            last_progress: None,
+        };
+        // Ends on a finished job, a vanished one (cleanup) or a closed
+        // connection.
+        let _ = follow(&state, &token, &mut emitter).await;
+    });
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default()),
+    )
+        .into_response()
+}
+
+async fn follow(state: &AppState, token: &str, emitter: &mut Emitter) -> Result<(), ()> {
+    loop {
+        if emitter.tx.is_closed() {
+            return Err(());
+        }
+        if let Some(live) = state.video_queue.live(token) {
+            let mut rx = live.tracker.subscribe();
+            // Until the worker lets go of the job; re-checked every poll
+            // interval because a finished job produces no more updates.
+            while state.video_queue.live(token).is_some() {
+                if emitter.tx.is_closed() {
+                    return Err(());
+                }
+                let job_state = live.state();
+                let reading = *rx.borrow_and_update();
+                let status = match job_state {
+                    VideoJobState::Converting => DownloadStatus::Converting {
+                        progress_percent: reading.percent,
+                    },
+                    _ => DownloadStatus::Downloading {
+                        progress_percent: reading.percent,
+                    },
+                };
+                emitter.status(&status).await?;
+                emitter
+                    .progress(ProgressEvent {
+                        phase: phase(job_state),
+                        progress_percent: reading.percent,
+                        downloaded_bytes: reading.downloaded_bytes,
+                        total_bytes: reading.total_bytes,
+                        speed_bps: reading.speed_bps,
+                        eta_secs: reading.eta_secs,
+                    })
+                    .await?;
+                tokio::time::sleep(PROGRESS_THROTTLE).await;
+                let _ = tokio::time::timeout(DB_POLL_INTERVAL, rx.changed()).await;
+            }
+        }
+
+        let job = match state.video_queue.jobs.find(token).await {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                let gone = DownloadStatus::Failed {
+                    error: "Video not found or expired".to_string(),
+                };
+                return emitter.status(&gone).await;
+            }
+            Err(e) => {
+                tracing::warn!("video events: looking up {token} failed: {e}");
+                tokio::time::sleep(DB_POLL_INTERVAL).await;
+                continue;
+            }
+        };
+        let status = DownloadStatus::from_job(&job);
+        emitter.status(&status).await?;
+        if job.state.is_finished() {
+            return Ok(());
+        }
+        if job.state.is_running() {
+            // Running elsewhere: the stored percentage is all we have.
+            emitter
+                .progress(ProgressEvent {
+                    phase: phase(job.state),
+                    progress_percent: job.progress,
+                    downloaded_bytes: None,
+                    total_bytes: None,
+                    speed_bps: None,
+                    eta_secs: None,
+                })
+                .await?;
+        }
+        tokio::time::sleep(DB_POLL_INTERVAL).await;
+    }
+}
@@ -4,19 +4,19 @@
 //! - `download` — yt-dlp download flow (info, prepare, status, file, recent, cleanup)
 //! - `library`  — Streamtape/R2 hosted library CRUD + streaming proxy
 //! - `cleanup`  — periodic background reaper for temp files + tests
+//! - `events`   — Server-Sent Events progress stream for one download
+//! - `queue`    — persistent download queue and its worker pool
 //! - `thumbnail` — CDN thumbnail proxy
 
 mod cleanup;
 mod download;
+mod events;
 mod library;
+mod queue;
 mod thumbnail;
 
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::sync::atomic::AtomicU8;
-
+use cr_domain::video_job::{VideoJob, VideoJobState};
 use serde::Serialize;
-use tokio::sync::{Mutex, Semaphore};
 
 // --- Re-exports consumed by main.rs, state.rs, and handlers/mod.rs ---
 
@@ -25,14 +25,14 @@ pub use download::{
     video_cleanup, video_file, video_file_part, video_info, video_prepare, video_recent,
     video_status,
 };
+pub use events::video_status_events;
 pub use library::{library_delete, library_file, library_list, library_play, library_stream};
+pub(crate) use queue::{STALE_AFTER, VIDEO_DOWNLOAD_WORKERS};
+pub use queue::{VideoQueue, spawn_video_download_workers};
 pub use thumbnail::video_thumb;
 
 // --- Shared constants ---
 
-/// Maximum concurrent video downloads.
-pub static VIDEO_DOWNLOAD_SEMAPHORE: Semaphore = Semaphore::const_new(3);
-
 /// On-disk directory where yt-dlp drops freshly downloaded videos before
 /// they are either served to the user via `/api/video/file/{token}` or
 /// published to the library pipeline.
@@ -51,42 +51,31 @@ pub(crate) const TMP_VIDEO_CLEANUP_INTERVAL: std::time::Duration =
 
 // --- Shared types ---
 
-/// Shared state for tracking video download tasks (async).
-pub type VideoDownloads = Arc<Mutex<HashMap<String, VideoTask>>>;
-
-pub struct VideoTask {
-    pub status: DownloadStatus,
-    pub progress: Arc<AtomicU8>,
-    pub file_path: std::path::PathBuf,
-    pub filename: String,
-    pub parts: Vec<PartInfo>,
-    #[allow(dead_code)]
-    pub created_at: std::time::Instant,
-    /// Set for tasks that hit the library dedup path — the client's
-    /// ready-link delegates to `/api/video/library/{id}/file` via a
-    /// 303 See Other from `video_file` (`Redirect::to(...)`) because
-    /// there is no local temp file for deduped downloads (the content
-    /// lives only on Streamtape/R2). `None` for normal downloads
-    /// where `file_path` carries the bytes.
-    pub library_id: Option<i32>,
-}
-
-#[derive(Clone, Serialize)]
-pub struct PartInfo {
-    pub index: usize,
-    pub filename: String,
-    pub size_mb: f64,
-    pub file_path: std::path::PathBuf,
-}
-
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "snake_case", tag = "status")]
 pub enum DownloadStatus {
-    Downloading { progress_percent: u8 },
-    Converting { progress_percent: u8 },
-    Ready { size_mb: f64, filename: String },
-    ReadyParts { parts: Vec<PartResponse> },
-    Failed { error: String },
+    /// Waiting for a worker. `ahead` is the number of jobs before this
+    /// one; `retrying` means an earlier attempt failed.
+    Queued {
+        ahead: i64,
+        retrying: bool,
+    },
+    Downloading {
+        progress_percent: u8,
+    },
+    Converting {
+        progress_percent: u8,
+    },
+    Ready {
+        size_mb: f64,
+        filename: String,
+    },
+    ReadyParts {
+        parts: Vec<PartResponse>,
+    },
+    Failed {
+        error: String,
+    },
 }
 
 #[derive(Clone, Serialize)]
@@ -96,6 +85,50 @@ pub struct PartResponse {
     pub size_mb: f64,
 }
 
+impl DownloadStatus {
+    /// What `/api/video/status/{token}` reports for a stored job.
+    pub(crate) fn from_job(job: &VideoJob) -> Self {
+        match job.state {
+            VideoJobState::Queued => Self::Queued {
+                ahead: job.queue_position,
+                retrying: job.attempts > 0,
+            },
+            VideoJobState::Downloading => Self::Downloading {
+                progress_percent: job.progress,
+            },
+            VideoJobState::Converting => Self::Converting {
+                progress_percent: job.progress,
+            },
+            VideoJobState::Ready => Self::Ready {
+                size_mb: round_mb(job.size_bytes.unwrap_or(0)),
+                filename: job.filename.clone(),
+            },
+            VideoJobState::ReadyParts => Self::ReadyParts {
+                parts: job
+                    .parts
+                    .iter()
+                    .map(|p| PartResponse {
+                        index: p.index as usize,
+                        filename: p.filename.clone(),
+                        size_mb: round_mb(p.size_bytes),
+                    })
+                    .collect(),
+            },
+            VideoJobState::Failed => Self::Failed {
+                error: job
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "Stažení se nezdařilo.".to_string()),
+            },
+        }
+    }
+}
+
+/// Bytes as megabytes with one decimal.
+pub(crate) fn round_mb(bytes: i64) -> f64 {
+    (bytes as f64 / (1024.0 * 1024.0) * 10.0).round() / 10.0
+}
+
 // --- Shared request/response types ---
 
 #[derive(Serialize)]
@@ -209,6 +242,28 @@ pub(crate) fn sanitize_error(raw: &str) -> String {
     "Nepodařilo se získat informace o videu. Zkuste jiný odkaz.".to_string()
 }
 
+/// Whether a yt-dlp / extractor error is one retrying can't fix — the
+/// video is gone, private, behind a login, or the site isn't supported.
+/// The same markers [`sanitize_error`] turns into their own messages.
+pub(crate) fn is_permanent_error(raw: &str) -> bool {
+    [
+        "Sign in to confirm",
+        "not a bot",
+        "login required",
+        "Unsupported URL",
+        "Video unavailable",
+        "not available",
+        "Private video",
+        "No video found",
+        "could not find SDN",
+        "too long for",
+        // Live streams and unsupported HLS encryption
+        "not supported",
+    ]
+    .iter()
+    .any(|marker| raw.contains(marker))
+}
+
 /// Map a file extension to a Content-Type mime string, defaulting to
 /// `video/mp4` for anything we don't recognise so browsers at least
 /// treat the response as some kind of video.
@@ -220,6 +275,12 @@ pub(crate) fn content_type_for_filename(name: &str) -> &'static str {
     match ext.as_deref() {
         Some("webm") => "video/webm",
         Some("mkv") => "video/x-matroska",
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") => "audio/mp4",
+        Some("opus") => "audio/ogg",
+        Some("flac") => "audio/flac",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
         _ => "video/mp4",
     }
 }
@@ -228,7 +289,7 @@ pub(crate) fn content_type_for_filename(name: &str) -> &'static str {
 
 #[cfg(test)]
 mod sanitize_tests {
-    use super::{sanitize_filename_ascii, sanitize_filename_unicode};
+    use super::{is_permanent_error, sanitize_filename_ascii, sanitize_filename_unicode};
 
     #[test]
     fn ascii_title_kept() {
@@ -273,4 +334,13 @@ mod sanitize_tests {
     fn unicode_emoji_only_falls_back() {
         assert_eq!(sanitize_filename_unicode("😭😭😭", 80), "video");
     }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(is_permanent_error("ERROR: [youtube] abc: Private video"));
+        assert!(is_permanent_error("ERROR: Unsupported URL: https://x"));
+        assert!(is_permanent_error("Live HLS streams are not supported"));
+        assert!(!is_permanent_error("HTTP Error 503: Service Unavailable"));
+        assert!(!is_permanent_error("ffmpeg exited with status 1"));
+    }
 }
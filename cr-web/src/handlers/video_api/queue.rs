@@ -0,0 +1,703 @@
+//! Worker pool for the persistent download queue.
+//!
+//! `video_prepare` only enqueues a row in `video_download_jobs`; the
+//! [`VIDEO_DOWNLOAD_WORKERS`] workers spawned here claim rows one at a
+//! time, run yt-dlp (and ffmpeg for clips, exports, audio and animations),
+//! and record the outcome.
+//! While a job runs its worker writes the progress counter back every
+//! [`PROGRESS_INTERVAL`], which doubles as the heartbeat: jobs whose
+//! heartbeat stops for [`STALE_AFTER`] belonged to a process that died and
+//! are put back on the queue by the sweeper — at startup and then every
+//! [`SWEEP_INTERVAL`]. A worker whose heartbeat finds the job requeued
+//! abandons its attempt; temp files carry the attempt number, so the two
+//! never share a path. A failed attempt is retried with the backoff from
+//! [`retry_delay`] unless the error can't go away by itself.
+//!
+//! Jobs running in this process are also registered in the queue's live
+//! map, where `/api/video/status/{token}/events` subscribes to their
+//! progress instead of waiting for the next database write.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cr_domain::repository::VideoJobRepository;
+use cr_domain::video_job::{
+    AnimationOptions, AudioOptions, NewVideoJob, VideoJob, VideoJobOutcome, VideoJobPart,
+    VideoJobState, retry_delay,
+};
+use cr_infra::repositories::PgVideoJobRepository;
+use cr_infra::video::{AudioTags, ExportResult, ProgressTracker};
+use cr_infra::video_library::PublishMetadata;
+use tokio::sync::Notify;
+
+use crate::state::AppState;
+
+use super::{TMP_VIDEO_DIR, is_permanent_error, sanitize_filename_ascii};
+
+/// Downloads running at once per process.
+pub(crate) const VIDEO_DOWNLOAD_WORKERS: usize = 3;
+
+/// Idle workers re-check the queue this often even without a wake-up, to
+/// pick up retries whose backoff ran out and jobs enqueued by another
+/// process.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a running job's progress (and heartbeat) is written.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Heartbeat silence after which a running job counts as abandoned.
+pub(crate) const STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// How often the sweeper looks for abandoned jobs.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The job queue as the handlers see it: the repository, the signal that
+/// wakes an idle worker when a job comes in, and the live progress of the
+/// jobs this process is running, by token.
+#[derive(Clone)]
+pub struct VideoQueue {
+    pub jobs: Arc<PgVideoJobRepository>,
+    wake: Arc<Notify>,
+    running: Arc<Mutex<HashMap<String, Arc<LiveProgress>>>>,
+}
+
+impl VideoQueue {
+    pub fn new(jobs: Arc<PgVideoJobRepository>) -> Self {
+        Self {
+            jobs,
+            wake: Arc::new(Notify::new()),
+            running: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Live progress of `token` if one of this process's workers is on it.
+    /// `None` once its result is in the database.
+    pub(crate) fn live(&self, token: &str) -> Option<Arc<LiveProgress>> {
+        self.running.lock().unwrap().get(token).cloned()
+    }
+
+    /// Queue `job` and wake a worker for it, unless an identical job is
+    /// already queued or running. Returns the token to hand the client.
+    pub async fn enqueue(&self, job: &NewVideoJob) -> Result<String, sqlx::Error> {
+        let token = self.jobs.enqueue(job).await?;
+        if token == job.token {
+            self.wake.notify_one();
+        }
+        Ok(token)
+    }
+}
+
+/// Spawn the sweeper and the workers. Call once at startup from
+/// `main.rs`; the tasks end only when the process exits.
+pub fn spawn_video_download_workers(state: AppState) -> Vec<tokio::task::JoinHandle<()>> {
+    let queue = state.video_queue.clone();
+    let mut tasks = vec![tokio::spawn(async move {
+        // The first tick fires right away: that's the startup resumption
+        // of whatever the previous process left running.
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            match queue.jobs.requeue_stale(STALE_AFTER).await {
+                Ok(0) => {}
+                Ok(n) => {
+                    tracing::info!("video queue: requeued {n} interrupted jobs");
+                    queue.wake.notify_waiters();
+                }
+                Err(e) => tracing::warn!("video queue: stale sweep failed: {e}"),
+            }
+        }
+    })];
+
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "cr-web".to_string());
+    for n in 0..VIDEO_DOWNLOAD_WORKERS {
+        let worker = format!("{host}-{}-{n}", std::process::id());
+        let state = state.clone();
+        tasks.push(tokio::spawn(async move {
+            loop {
+                match state.video_queue.jobs.claim(&worker).await {
+                    Ok(Some(job)) => run_job(&state, &worker, job).await,
+                    Ok(None) => {
+                        tokio::select! {
+                            _ = state.video_queue.wake.notified() => {}
+                            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("video queue: claim failed: {e}");
+                        tokio::time::sleep(POLL_INTERVAL).await;
+                    }
+                }
+            }
+        }));
+    }
+    tasks
+}
+
+/// Live progress of a running job, written by the yt-dlp / ffmpeg
+/// wrappers and read by the flusher and the event stream.
+#[derive(Default)]
+pub(crate) struct LiveProgress {
+    pub(crate) tracker: ProgressTracker,
+    converting: AtomicBool,
+}
+
+impl LiveProgress {
+    pub(crate) fn state(&self) -> VideoJobState {
+        if self.converting.load(Ordering::Relaxed) {
+            VideoJobState::Converting
+        } else {
+            VideoJobState::Downloading
+        }
+    }
+}
+
+async fn run_job(state: &AppState, worker: &str, job: VideoJob) {
+    let jobs = state.video_queue.jobs.clone();
+    let token = job.params.token.clone();
+    tracing::info!(
+        "Video download started: {token} for {} (attempt {})",
+        job.params.source_url,
+        job.attempts
+    );
+
+    let live = Arc::new(LiveProgress::default());
+    let queue = &state.video_queue;
+    queue
+        .running
+        .lock()
+        .unwrap()
+        .insert(token.clone(), live.clone());
+    // The flusher ends as soon as a write finds the job is no longer ours
+    // (requeued by the sweeper, deleted by cleanup); the attempt is then
+    // dropped on the spot, killing its yt-dlp / ffmpeg child.
+    let mut flusher = {
+        let jobs = jobs.clone();
+        let live = live.clone();
+        let worker = worker.to_string();
+        let id = job.id;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let percent = live.tracker.percent();
+                match jobs
+                    .report_progress(id, &worker, live.state(), percent)
+                    .await
+                {
+                    Ok(true) => {}
+                    Ok(false) => return,
+                    Err(e) => {
+                        tracing::warn!("video queue: progress update for job {id} failed: {e}")
+                    }
+                }
+            }
+        })
+    };
+
+    let result = tokio::select! {
+        result = process(state, &job, &live) => result,
+        _ = &mut flusher => {
+            tracing::warn!(
+                "video queue: job {token} changed hands, attempt {} abandoned",
+                job.attempts
+            );
+            remove_attempt_files(&attempt_stem(&job)).await;
+            deregister(queue, &token, &live);
+            return;
+        }
+    };
+    flusher.abort();
+
+    let recorded = match result {
+        Ok(outcome) => jobs.complete(job.id, worker, &outcome).await,
+        Err(failure) => {
+            let retry_in = if is_permanent_error(&failure.raw) {
+                None
+            } else {
+                retry_delay(job.attempts)
+            };
+            match retry_in {
+                Some(delay) => tracing::warn!(
+                    "Video job {token} failed, retrying in {}s: {}",
+                    delay.as_secs(),
+                    failure.raw
+                ),
+                None => tracing::error!("Video job {token} failed: {}", failure.raw),
+            }
+            jobs.fail(job.id, worker, &failure.message, retry_in).await
+        }
+    };
+    match recorded {
+        Ok(true) => {}
+        // Deleted by `/api/video/cleanup` or requeued by the sweeper while
+        // we were busy; the row isn't ours to update any more.
+        Ok(false) => tracing::warn!("video queue: job {token} changed hands, result dropped"),
+        Err(e) => tracing::error!("video queue: recording result of {token} failed: {e}"),
+    }
+    // Only now: event streams that find the job gone read the result
+    // from the database.
+    deregister(queue, &token, &live);
+}
+
+/// Drop `token` from the live map if the entry is still this attempt's —
+/// after a requeue another worker of this process may own it already.
+fn deregister(queue: &VideoQueue, token: &str, live: &Arc<LiveProgress>) {
+    let mut running = queue.running.lock().unwrap();
+    if running.get(token).is_some_and(|l| Arc::ptr_eq(l, live)) {
+        running.remove(token);
+    }
+}
+
+/// Base name of every temp file of this attempt. The attempt number keeps
+/// a requeued job's new attempt off the files of one still winding down.
+fn attempt_stem(job: &VideoJob) -> String {
+    format!("{}.a{}", job.params.token, job.attempts)
+}
+
+/// Remove the temp files of an abandoned attempt.
+async fn remove_attempt_files(stem: &str) {
+    let Ok(mut entries) = tokio::fs::read_dir(TMP_VIDEO_DIR).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let Some(rest) = name.to_str().and_then(|n| n.strip_prefix(stem)) else {
+            continue;
+        };
+        if rest.starts_with(['.', '-'])
+            && let Err(e) = tokio::fs::remove_file(entry.path()).await
+        {
+            tracing::warn!("failed to unlink abandoned temp file {rest:?}: {e}");
+        }
+    }
+}
+
+/// A failed attempt: the raw error for logs and retry decisions, and the
+/// message the status endpoint shows.
+struct Failure {
+    raw: String,
+    message: String,
+}
+
+async fn process(
+    state: &AppState,
+    job: &VideoJob,
+    live: &LiveProgress,
+) -> Result<VideoJobOutcome, Failure> {
+    let params = &job.params;
+    let tmp_dir = std::path::PathBuf::from(TMP_VIDEO_DIR);
+    tokio::fs::create_dir_all(&tmp_dir)
+        .await
+        .map_err(|e| Failure {
+            raw: format!("create {TMP_VIDEO_DIR}: {e}"),
+            message: "Server error".to_string(),
+        })?;
+    if let Some(audio) = params.audio {
+        return process_audio(state, job, live, &tmp_dir, audio).await;
+    }
+    if let Some(animation) = params.animation {
+        return process_animation(state, job, live, &tmp_dir, animation).await;
+    }
+    let attempt = attempt_stem(job);
+    let file_path = tmp_dir.join(format!("{attempt}.{}", params.container));
+
+    let size = cr_infra::video::download_video_with_progress(
+        &state.http_client,
+        &params.source_url,
+        &params.format_id,
+        &params.resolution,
+        &params.container,
+        &file_path,
+        Some(live.tracker.clone()),
+    )
+    .await
+    .map_err(|e| {
+        // Don't leave a half-written file for the retry to trip over.
+        let _ = std::fs::remove_file(&file_path);
+        Failure {
+            raw: e.to_string(),
+            message: format!("Stažení se nezdařilo: {e}"),
+        }
+    })?;
+
+    let size = match params.clip {
+        Some(clip) => {
+            live.converting.store(true, Ordering::Relaxed);
+            live.tracker.reset();
+            cr_infra::video::trim_clip(
+                &file_path,
+                &params.container,
+                clip,
+                Some(live.tracker.clone()),
+            )
+            .await
+            .and_then(|()| Ok(std::fs::metadata(&file_path)?.len()))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&file_path);
+                Failure {
+                    raw: e.to_string(),
+                    message: format!("Vystřižení úseku selhalo: {e}"),
+                }
+            })?
+        }
+        None => size,
+    };
+
+    let size = match &params.subtitles {
+        Some(subtitles) => {
+            live.converting.store(true, Ordering::Relaxed);
+            live.tracker.reset();
+            cr_infra::video::add_subtitles(
+                &state.http_client,
+                &file_path,
+                &params.container,
+                subtitles,
+                params.clip,
+                Some(live.tracker.clone()),
+            )
+            .await
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&file_path);
+                Failure {
+                    raw: e.to_string(),
+                    message: format!("Přidání titulků selhalo: {e}"),
+                }
+            })?
+        }
+        None => size,
+    };
+
+    let Some(target) = params.export else {
+        let size_mb = size as f64 / (1024.0 * 1024.0);
+        tracing::info!(
+            "Video ready: {} ({size_mb:.1} MB) for {}",
+            params.token,
+            params.source_url
+        );
+        // #319 — fire-and-forget publish to the library. Local file is
+        // intentionally kept on disk by `publish_local_video` (see #363)
+        // so the user can still download it via `/api/video/file/{token}`
+        // after the upload finishes. Temp files are reaped by
+        // `DELETE /api/video/cleanup`.
+        //
+        // #366 — `publish` is false when the library already has a MP4
+        // row for this URL+quality and the user asked for WebM/MKV:
+        // republishing would either hit the unique constraint or
+        // duplicate work Streamtape just did.
+        if params.publish {
+            spawn_publish(
+                state,
+                file_path.clone(),
+                publish_metadata(params),
+                "video".to_string(),
+            );
+        }
+        return Ok(VideoJobOutcome::File {
+            file_path: file_path.to_string_lossy().into_owned(),
+            filename: job.filename.clone(),
+            size_bytes: size as i64,
+        });
+    };
+
+    // Export: convert with ffmpeg. ASCII-only sanitiser to keep the
+    // resulting filename safe in HTTP headers.
+    let preset = target.preset();
+    let stem = job
+        .filename
+        .trim_end_matches(".mp4")
+        .trim_end_matches(".webm");
+    let safe = sanitize_filename_ascii(stem, 50);
+    // Flag first: the reset wakes the event streams, which then see the
+    // new phase.
+    live.converting.store(true, Ordering::Relaxed);
+    live.tracker.reset();
+
+    let result = cr_infra::video::export_for_target(
+        &file_path,
+        &tmp_dir,
+        &attempt,
+        target,
+        Some(live.tracker.clone()),
+    )
+    .await;
+
+    // #371 review — the pre-conversion yt-dlp temp file is no longer
+    // referenced by anything once conversion ran. Unlink it immediately so
+    // disk usage drops right away instead of waiting for the 30-min
+    // reaper.
+    if let Err(e) = tokio::fs::remove_file(&file_path).await {
+        tracing::warn!("failed to unlink pre-export temp file {file_path:?}: {e}");
+    }
+
+    // Library resolution of the output: the short side of the preset's box.
+    let resolution = format!("{}p", preset.max_width.min(preset.max_height));
+    match result {
+        Ok(ExportResult::Single { path, size }) => {
+            let size_mb = size as f64 / (1024.0 * 1024.0);
+            tracing::info!(
+                "{} video ready: {} ({size_mb:.1} MB)",
+                preset.label,
+                params.token
+            );
+            // #366 — publish the export to the library as its own row.
+            // The quality is the preset id (`"whatsapp"`, …) so it
+            // coexists with any regular-download row for the same URL
+            // under a different dedup key.
+            if params.publish {
+                let mut meta = publish_metadata(params);
+                meta.quality = target.as_str().to_string();
+                meta.resolution = Some(resolution);
+                spawn_publish(state, path.clone(), meta, target.as_str().to_string());
+            }
+            Ok(VideoJobOutcome::File {
+                file_path: path.to_string_lossy().into_owned(),
+                filename: format!("{safe} ({}).mp4", preset.label),
+                size_bytes: size as i64,
+            })
+        }
+        Ok(ExportResult::Parts(parts)) => {
+            let part_count = parts.len();
+            tracing::info!(
+                "{} video ready: {} ({part_count} parts)",
+                preset.label,
+                params.token
+            );
+            // #366 — publish each part as its own library row. Quality is
+            // `"{preset}-part{i}"` so all N parts live under distinct
+            // dedup keys and show up as N independent cards in the grid.
+            // Title carries an "X/N" suffix so the user can distinguish
+            // them at a glance.
+            if params.publish {
+                for p in &parts {
+                    let mut meta = publish_metadata(params);
+                    meta.title = format!(
+                        "{} — {} část {}/{}",
+                        params.title,
+                        preset.label,
+                        p.index + 1,
+                        part_count
+                    );
+                    meta.quality = format!("{}-part{}", target.as_str(), p.index);
+                    meta.resolution = Some(resolution.clone());
+                    spawn_publish(
+                        state,
+                        p.path.clone(),
+                        meta,
+                        format!("{} part{}", target.as_str(), p.index),
+                    );
+                }
+            }
+            Ok(VideoJobOutcome::Parts(
+                parts
+                    .iter()
+                    .map(|p| VideoJobPart {
+                        index: p.index as i32,
+                        filename: format!("{safe} ({} část {}).mp4", preset.label, p.index + 1),
+                        size_bytes: p.size as i64,
+                        file_path: p.path.to_string_lossy().into_owned(),
+                    })
+                    .collect(),
+            ))
+        }
+        Err(e) => Err(Failure {
+            raw: e.to_string(),
+            message: format!("Konverze pro {} selhala: {e}", preset.label),
+        }),
+    }
+}
+
+/// Audio-only job: fetch the best audio the source has, then convert it
+/// with tags and the thumbnail as cover. Never published — the library
+/// holds videos only.
+async fn process_audio(
+    state: &AppState,
+    job: &VideoJob,
+    live: &LiveProgress,
+    tmp_dir: &std::path::Path,
+    audio: AudioOptions,
+) -> Result<VideoJobOutcome, Failure> {
+    let params = &job.params;
+    let stem = attempt_stem(job);
+    let source_path = tmp_dir.join(format!("{stem}.src"));
+    let file_path = tmp_dir.join(format!("{stem}.{}", audio.format.as_str()));
+
+    cr_infra::video::download_audio_source(
+        &state.http_client,
+        &params.source_url,
+        &params.format_id,
+        &source_path,
+        Some(live.tracker.clone()),
+    )
+    .await
+    .map_err(|e| {
+        let _ = std::fs::remove_file(&source_path);
+        Failure {
+            raw: e.to_string(),
+            message: format!("Stažení se nezdařilo: {e}"),
+        }
+    })?;
+
+    live.converting.store(true, Ordering::Relaxed);
+    live.tracker.reset();
+
+    // A missing cover isn't worth failing the job over.
+    let cover = match &params.thumbnail_url {
+        Some(url) => match cr_infra::video::fetch_cover_art(&state.http_client, url).await {
+            Ok(cover) => Some(cover),
+            Err(e) => {
+                tracing::warn!("audio {}: no cover art: {e}", params.token);
+                None
+            }
+        },
+        None => None,
+    };
+    let tags = AudioTags {
+        title: params.title.clone(),
+        artist: params.uploader.clone(),
+        duration_secs: params.duration_sec.map(f64::from),
+        source_url: Some(params.source_url.clone()),
+    };
+    let converted = cr_infra::video::convert_audio(
+        &source_path,
+        &file_path,
+        audio,
+        params.clip,
+        &tags,
+        cover.as_ref(),
+        Some(live.tracker.clone()),
+    )
+    .await;
+    if let Err(e) = tokio::fs::remove_file(&source_path).await {
+        tracing::warn!("failed to unlink audio source {source_path:?}: {e}");
+    }
+    let size = converted.map_err(|e| Failure {
+        raw: e.to_string(),
+        message: format!("Převod zvuku selhal: {e}"),
+    })?;
+
+    let size_mb = size as f64 / (1024.0 * 1024.0);
+    tracing::info!(
+        "Audio ready: {} ({size_mb:.1} MB) for {}",
+        params.token,
+        params.source_url
+    );
+    Ok(VideoJobOutcome::File {
+        file_path: file_path.to_string_lossy().into_owned(),
+        filename: job.filename.clone(),
+        size_bytes: size as i64,
+    })
+}
+
+/// Animation job: download the video, then turn the clip into a GIF /
+/// WebP. Never published, like audio.
+async fn process_animation(
+    state: &AppState,
+    job: &VideoJob,
+    live: &LiveProgress,
+    tmp_dir: &std::path::Path,
+    animation: AnimationOptions,
+) -> Result<VideoJobOutcome, Failure> {
+    let params = &job.params;
+    // The table doesn't allow one without the other.
+    let Some(clip) = params.clip else {
+        return Err(Failure {
+            raw: "animation job without a clip".to_string(),
+            message: "Server error".to_string(),
+        });
+    };
+    // Matroska takes any codecs, so the download needs no re-encode.
+    let stem = attempt_stem(job);
+    let source_path = tmp_dir.join(format!("{stem}.src.mkv"));
+    let file_path = tmp_dir.join(format!("{stem}.{}", animation.format.as_str()));
+
+    cr_infra::video::download_video_with_progress(
+        &state.http_client,
+        &params.source_url,
+        &params.format_id,
+        &params.resolution,
+        "mkv",
+        &source_path,
+        Some(live.tracker.clone()),
+    )
+    .await
+    .map_err(|e| {
+        let _ = std::fs::remove_file(&source_path);
+        Failure {
+            raw: e.to_string(),
+            message: format!("Stažení se nezdařilo: {e}"),
+        }
+    })?;
+
+    live.converting.store(true, Ordering::Relaxed);
+    live.tracker.reset();
+    let converted = cr_infra::video::export_animation(
+        &source_path,
+        &file_path,
+        clip,
+        animation,
+        Some(live.tracker.clone()),
+    )
+    .await;
+    if let Err(e) = tokio::fs::remove_file(&source_path).await {
+        tracing::warn!("failed to unlink animation source {source_path:?}: {e}");
+    }
+    let size = converted.map_err(|e| Failure {
+        raw: e.to_string(),
+        message: format!("Převod na animaci selhal: {e}"),
+    })?;
+
+    let size_mb = size as f64 / (1024.0 * 1024.0);
+    tracing::info!(
+        "Animation ready: {} ({size_mb:.1} MB) for {}",
+        params.token,
+        params.source_url
+    );
+    Ok(VideoJobOutcome::File {
+        file_path: file_path.to_string_lossy().into_owned(),
+        filename: job.filename.clone(),
+        size_bytes: size as i64,
+    })
+}
+
+/// Library metadata for a job's download.
+///
+/// `format_ext` is hard-coded to `"mp4"` because Streamtape re-encodes
+/// every upload to H.264 MP4 regardless of what file we hand it (#366).
+/// `quality` is the picked format, not the raw request — for exports the
+/// request quality is the preset sentinel (`"whatsapp"`, …) but we download
+/// the real source quality, so the user gets both a shareable file and a
+/// permanent library card.
+fn publish_metadata(params: &NewVideoJob) -> PublishMetadata {
+    PublishMetadata {
+        source_url: params.source_url.clone(),
+        title: params.title.clone(),
+        description: None,
+        duration_sec: params.duration_sec,
+        source_extractor: params.uploader.clone(),
+        quality: params.format_id.clone(),
+        format_ext: "mp4".to_string(),
+        resolution: Some(params.resolution.clone()).filter(|s| !s.is_empty()),
+        upstream_thumbnail_url: params.thumbnail_url.clone(),
+    }
+}
+
+/// Fire-and-forget library publish, so a slow Streamtape upload never
+/// holds up the job.
+fn spawn_publish(state: &AppState, path: std::path::PathBuf, meta: PublishMetadata, what: String) {
+    let Some(pipeline) = state.video_library.clone() else {
+        return;
+    };
+    tokio::spawn(async move {
+        match pipeline.publish_local_video(&path, meta).await {
+            Ok(rec) => tracing::info!(
+                "{what} library publish OK: id={} streamtape_id={}",
+                rec.id,
+                rec.streamtape_file_id
+            ),
+            Err(e) => tracing::warn!("{what} library publish failed: {e} — local-only"),
+        }
+    });
+}
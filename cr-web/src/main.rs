@@ -3,10 +3,15 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use axum::Router;
+use cr_infra::boundaries::ReverseGeocoder;
+use cr_infra::photo_hash::PhotoHashIndex;
 use cr_infra::r2::{R2Client, R2Config};
 use cr_infra::repositories::{
-    PgLandmarkRepository, PgMunicipalityRepository, PgOrpRepository, PgPhotoRepository,
-    PgPoolRepository, PgRegionRepository, PgVideoRepository,
+    PgEpisodeCalendarRepository, PgEpisodeRepository, PgExportRepository, PgFeedRepository,
+    PgFilmCollectionRepository, PgFilmRepository, PgLandmarkRepository, PgMunicipalityRepository,
+    PgOrpRepository, PgPhotoRepository, PgPoolRepository, PgRegionRepository,
+    PgSearchDocumentRepository, PgSeriesRepository, PgSimilarTitlesRepository,
+    PgSuggestSourceRepository, PgTvShowRepository, PgVideoJobRepository, PgVideoRepository,
 };
 use cr_infra::streamtape::{StreamtapeClient, StreamtapeConfig};
 use cr_infra::video_library::VideoLibraryPipeline;
@@ -19,10 +24,16 @@ use tower_http::trace::TraceLayer;
 mod cache;
 mod config;
 mod error;
+mod geo;
 mod handlers;
 mod img_proxy;
+mod og_image;
+mod rate_limit;
+mod search;
+mod similar;
 mod state;
 
+use rate_limit::RateLimitLayer;
 use state::{AppState, GeoJsonIndex};
 
 #[tokio::main]
@@ -47,6 +58,10 @@ async fn main() -> Result<()> {
 
     let geojson_index =
         GeoJsonIndex::load(&config.geojson_dir).context("Failed to load GeoJSON index")?;
+    let reverse_geocoder = ReverseGeocoder::load(&config.geojson_dir)
+        .context("Failed to load reverse geocoder boundaries")?;
+    let tile_layers = geo::tiles::TileLayers::load(&config.geojson_dir)
+        .context("Failed to load vector tile layers")?;
 
     if !config.image_base_url.is_empty() {
         tracing::info!("Dev mode: images proxied from {}", config.image_base_url);
@@ -87,16 +102,33 @@ async fn main() -> Result<()> {
         _ => None,
     };
 
-    let video_downloads: handlers::video_api::VideoDownloads =
-        Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new()));
+    let video_queue =
+        handlers::video_api::VideoQueue::new(Arc::new(PgVideoJobRepository::new(pool.clone())));
 
     // #192 — periodic reaper for /tmp/cr-videos/. Deletes anything older
     // than 30 minutes so temp videos left behind by `publish_local_video`
     // (kept on disk on purpose for the /api/video/file/{token} ready-link,
     // see #363) don't accumulate and exhaust the VPS disk. Also prunes
-    // matching in-memory `video_downloads` entries so `/status/{token}`
-    // doesn't report `Ready` for a file that's already been reaped.
-    let _cleanup_task = handlers::video_api::spawn_temp_video_cleanup_loop(video_downloads.clone());
+    // the matching finished download jobs so `/status/{token}` doesn't
+    // report `Ready` for a file that's already been reaped.
+    let _cleanup_task =
+        handlers::video_api::spawn_temp_video_cleanup_loop(video_queue.jobs.clone());
+
+    // Full-text index behind the catalog autocomplete; built in the
+    // background so a cold start doesn't wait on the catalog scan.
+    let catalog_search = Arc::new(cr_app::search::CatalogSearch::new());
+    let _search_task = search::spawn_search_index_loop(
+        catalog_search.clone(),
+        Arc::new(PgSearchDocumentRepository::new(pool.clone())),
+    );
+    let suggest = Arc::new(cr_app::suggest::Suggester::new());
+    let _suggest_task = search::spawn_suggest_index_loop(
+        suggest.clone(),
+        Arc::new(PgSuggestSourceRepository::new(pool.clone())),
+    );
+
+    let similar_repo = Arc::new(PgSimilarTitlesRepository::new(pool.clone()));
+    let _similar_task = similar::spawn_similar_titles_loop(similar_repo.clone());
 
     let state = AppState {
         image_base_url: config.image_base_url.clone(),
@@ -107,12 +139,34 @@ async fn main() -> Result<()> {
         landmark_repo: Arc::new(PgLandmarkRepository::new(pool.clone())),
         pool_repo: Arc::new(PgPoolRepository::new(pool.clone())),
         photo_repo: Arc::new(PgPhotoRepository::new(pool.clone())),
+        film_repo: Arc::new(PgFilmRepository::new(pool.clone())),
+        series_repo: Arc::new(PgSeriesRepository::new(pool.clone())),
+        episode_repo: Arc::new(PgEpisodeRepository::new(pool.clone())),
+        tv_show_repo: Arc::new(PgTvShowRepository::new(pool.clone())),
+        catalog_search,
+        suggest,
+        similar_repo,
+        collection_repo: Arc::new(PgFilmCollectionRepository::new(pool.clone())),
+        feed_repo: Arc::new(PgFeedRepository::new(pool.clone())),
+        calendar_repo: Arc::new(PgEpisodeCalendarRepository::new(pool.clone())),
+        export_repo: Arc::new(PgExportRepository::new(pool.clone())),
+        photo_hashes: PhotoHashIndex::new(pool.clone()),
         video_repo,
         db: pool,
         geojson_index: Arc::new(geojson_index),
+        reverse_geocoder: Arc::new(reverse_geocoder),
+        tile_layers: Arc::new(tile_layers),
+        // ~4 000 tiles cover the country down to z11 across all layers;
+        // deeper zooms evict the oldest entries.
+        tile_cache: cache::BoundedTtlCache::new(4096, std::time::Duration::from_secs(24 * 3600)),
         http_client: reqwest::Client::new(),
-        video_downloads,
+        video_queue,
+        // Only the metadata and format list are reused — downloads extract
+        // again for fresh media URLs — so 10 min is safe for any source.
+        video_info_cache: cache::BoundedTtlCache::new(500, std::time::Duration::from_secs(10 * 60)),
+        video_info_in_flight: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         streamtape_config: streamtape_config.map(Arc::new),
+        r2_client: r2_config.clone().map(R2Client::new),
         r2_config: r2_config.map(Arc::new),
         video_library,
         streamtape_url_cache: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
@@ -138,8 +192,14 @@ async fn main() -> Result<()> {
         // to a single import worth. 64 entries is enough for the realistic
         // filter cohort (default + genre + year combos seen in CF logs).
         listing_count_cache: cache::BoundedTtlCache::new(64, std::time::Duration::from_secs(60)),
+        // Credits only change on import; ten minutes of staleness is fine.
+        people_index_cache: cache::BoundedTtlCache::new(1, std::time::Duration::from_secs(600)),
     };
 
+    // Download queue workers; the first sweep requeues jobs the previous
+    // process was still running when it went down.
+    let _video_workers = handlers::video_api::spawn_video_download_workers(state.clone());
+
     // API routes with CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -154,27 +214,19 @@ async fn main() -> Result<()> {
             "/geojson/orp/{code}",
             axum::routing::get(handlers::geojson_orp),
         )
+        .route("/reverse", axum::routing::get(handlers::api_reverse))
         .route("/landmarks", axum::routing::get(handlers::api_landmarks))
         .route(
             "/csfd-watchlist.json",
             axum::routing::get(handlers::csfd_watchlist),
         )
-        .route("/video/info", axum::routing::post(handlers::video_info))
-        .route(
-            "/video/prepare",
-            axum::routing::post(handlers::video_prepare),
-        )
         .route(
             "/video/status/{token}",
             axum::routing::get(handlers::video_status),
         )
         .route(
-            "/video/file/{token}",
-            axum::routing::get(handlers::video_file),
-        )
-        .route(
-            "/video/file/{token}/{part_index}",
-            axum::routing::get(handlers::video_file_part),
+            "/video/status/{token}/events",
+            axum::routing::get(handlers::video_status_events),
         )
         .route("/video/recent", axum::routing::get(handlers::video_recent))
         .route("/video/thumb", axum::routing::get(handlers::video_thumb))
@@ -188,14 +240,6 @@ async fn main() -> Result<()> {
             "/video/library/{id}/play",
             axum::routing::get(handlers::library_play),
         )
-        .route(
-            "/video/library/{id}/stream",
-            axum::routing::get(handlers::library_stream),
-        )
-        .route(
-            "/video/library/{id}/file",
-            axum::routing::get(handlers::library_file),
-        )
         .route(
             "/video/library/{id}",
             axum::routing::delete(handlers::library_delete),
@@ -257,11 +301,60 @@ async fn main() -> Result<()> {
             "/tv-porady/search",
             axum::routing::get(handlers::tv_porady_search),
         )
+        .route(
+            "/people/search",
+            axum::routing::get(handlers::people_search),
+        )
+        .route("/suggest", axum::routing::get(handlers::api_suggest))
+        .route("/export", axum::routing::get(handlers::export_manifest))
+        .route(
+            "/export/{dataset}",
+            axum::routing::get(handlers::export_dataset),
+        )
         .route(
             "/films/sktorrent-resolve",
             axum::routing::get(handlers::sktorrent_resolve),
         );
 
+    // The public video API does real work per call (extractors, ffmpeg,
+    // multi-GB responses), so each group gets per-client limits on top of
+    // the worker pool; see `rate_limit`.
+    let limits = &state.config.rate_limits;
+    let api_routes = api_routes
+        .merge(
+            Router::new()
+                .route("/video/info", axum::routing::post(handlers::video_info))
+                .route_layer(RateLimitLayer::new("video-info", limits.video_info)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/video/prepare",
+                    axum::routing::post(handlers::video_prepare),
+                )
+                .route_layer(RateLimitLayer::new("video-prepare", limits.video_prepare)),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/video/file/{token}",
+                    axum::routing::get(handlers::video_file),
+                )
+                .route(
+                    "/video/file/{token}/{part_index}",
+                    axum::routing::get(handlers::video_file_part),
+                )
+                .route(
+                    "/video/library/{id}/stream",
+                    axum::routing::get(handlers::library_stream),
+                )
+                .route(
+                    "/video/library/{id}/file",
+                    axum::routing::get(handlers::library_file),
+                )
+                .route_layer(RateLimitLayer::new("video-files", limits.video_files)),
+        );
+
     // The `/sledujteto/resolve` endpoint is always registered — it's the
     // production playback path that turns a `file_id` stored in
     // `film_sledujteto_uploads` into a hashed streaming URL via
@@ -290,6 +383,12 @@ async fn main() -> Result<()> {
         .route("/", axum::routing::get(handlers::homepage))
         .route("/health", axum::routing::get(handlers::health))
         .nest("/api", api_routes)
+        .route("/hledat", axum::routing::get(handlers::search_page))
+        .route("/hledat/", axum::routing::get(handlers::search_page))
+        .route(
+            "/opensearch.xml",
+            axum::routing::get(handlers::opensearch_description),
+        )
         .route(
             "/admin/",
             axum::routing::get(handlers::admin_dashboard::admin_dashboard),
@@ -346,6 +445,14 @@ async fn main() -> Result<()> {
             "/admin/import/{run_id}",
             axum::routing::get(handlers::admin_import::admin_import_detail),
         )
+        .route(
+            "/admin/photos/duplicates",
+            axum::routing::get(handlers::admin_photos::admin_photo_duplicates),
+        )
+        .route(
+            "/admin/photos/duplicates/",
+            axum::routing::get(handlers::admin_photos::admin_photo_duplicates),
+        )
         .route(
             "/admin/prehrajto/unmatched",
             axum::routing::get(handlers::admin_prehrajto::admin_prehrajto_unmatched),
@@ -358,7 +465,23 @@ async fn main() -> Result<()> {
             "/admin/prehrajto/unmatched.csv",
             axum::routing::get(handlers::admin_prehrajto::admin_prehrajto_unmatched_csv),
         )
+        .route(
+            "/admin/video-queue",
+            axum::routing::get(handlers::admin_video_queue::admin_video_queue),
+        )
+        .route(
+            "/admin/video-queue/",
+            axum::routing::get(handlers::admin_video_queue::admin_video_queue),
+        )
         .route("/pamatky", axum::routing::get(handlers::landmarks_index))
+        .route(
+            "/pamatky/feed.xml",
+            axum::routing::get(handlers::landmarks_feed),
+        )
+        .route(
+            "/obce/feed.xml",
+            axum::routing::get(handlers::municipalities_feed),
+        )
         .route("/pamatky/", axum::routing::get(handlers::landmarks_index))
         .route("/audioknihy", axum::routing::get(handlers::audiobooks))
         .route("/audioknihy/", axum::routing::get(handlers::audiobooks))
@@ -379,6 +502,14 @@ async fn main() -> Result<()> {
             axum::routing::get(handlers::download_video),
         )
         .route("/filmy-online", axum::routing::get(handlers::films_list))
+        .route(
+            "/filmy-online/feed.xml",
+            axum::routing::get(handlers::films_feed),
+        )
+        .route(
+            "/filmy-online/{slug}/feed.xml",
+            axum::routing::get(handlers::film_genre_feed),
+        )
         .route("/filmy-online/", axum::routing::get(handlers::films_list))
         .route(
             "/filmy-online/{slug}",
@@ -389,6 +520,26 @@ async fn main() -> Result<()> {
             axum::routing::get(handlers::films_detail),
         )
         .route("/serialy-online", axum::routing::get(handlers::series_list))
+        .route(
+            "/serialy-online/feed.xml",
+            axum::routing::get(handlers::series_feed),
+        )
+        .route(
+            "/serialy-online/{slug}/feed.xml",
+            axum::routing::get(handlers::series_slug_feed),
+        )
+        .route(
+            "/serialy-online/kalendar",
+            axum::routing::get(handlers::episode_calendar),
+        )
+        .route(
+            "/serialy-online/kalendar/",
+            axum::routing::get(handlers::episode_calendar),
+        )
+        .route(
+            "/serialy-online/{slug}/kalendar.ics",
+            axum::routing::get(handlers::series_ics),
+        )
         .route(
             "/serialy-online/",
             axum::routing::get(handlers::series_list),
@@ -422,6 +573,10 @@ async fn main() -> Result<()> {
             axum::routing::get(handlers::series_resolve),
         )
         .route("/tv-porady", axum::routing::get(handlers::tv_porady_list))
+        .route(
+            "/tv-porady/feed.xml",
+            axum::routing::get(handlers::tv_porady_feed),
+        )
         .route("/tv-porady/", axum::routing::get(handlers::tv_porady_list))
         .route(
             "/tv-porady/{slug}/{ep}",
@@ -447,6 +602,32 @@ async fn main() -> Result<()> {
             "/filmy-a-serialy/",
             axum::routing::get(handlers::filmy_serialy),
         )
+        .route("/osobnosti", axum::routing::get(handlers::people_index))
+        .route("/osobnosti/", axum::routing::get(handlers::people_index))
+        .route(
+            "/osobnosti/{slug}",
+            axum::routing::get(handlers::person_detail),
+        )
+        .route(
+            "/osobnosti/{slug}/",
+            axum::routing::get(handlers::person_detail),
+        )
+        .route(
+            "/kolekce/{slug}",
+            axum::routing::get(handlers::collection_detail),
+        )
+        .route(
+            "/kolekce/{slug}/",
+            axum::routing::get(handlers::collection_detail),
+        )
+        .route(
+            "/sitemaps/osobnosti.xml",
+            axum::routing::get(handlers::people_sitemap_index),
+        )
+        .route(
+            "/sitemaps/osobnosti/{file}",
+            axum::routing::get(handlers::people_sitemap_page),
+        )
         .route(
             "/admin/test-sledujteto",
             axum::routing::get(handlers::admin_test_sledujteto),
@@ -483,6 +664,11 @@ async fn main() -> Result<()> {
             "/prirodni-koupaliste/",
             axum::routing::get(handlers::pools_by_category),
         )
+        .route(
+            "/tiles/{layer}/{z}/{x}/{y}",
+            axum::routing::get(handlers::vector_tile),
+        )
+        .route("/og/{kind}/{file}", axum::routing::get(handlers::og_image))
         .route("/img/{*path}", axum::routing::get(img_proxy::img_proxy))
         .nest_service("/static", ServeDir::new(&state.config.static_dir))
         .fallback(axum::routing::get(handlers::resolve_path));
@@ -496,9 +682,13 @@ async fn main() -> Result<()> {
     tracing::info!("Listening on {addr}");
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // Peer addresses feed the per-client rate limits.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
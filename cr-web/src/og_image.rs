@@ -0,0 +1,413 @@
+//! Open Graph share cards (1200×630 PNG) for detail pages.
+//!
+//! A card is the entity's artwork — a landmark/municipality photo filling
+//! the frame, or a film/series poster on the left over a blurred copy of
+//! itself — with the breadcrumb, the title and the site branding drawn
+//! on top. Text is rasterised with `ab_glyph` from the bundled DejaVu
+//! Sans (`assets/fonts/`), which covers the full Czech alphabet.
+//!
+//! Rendering is pure and synchronous; fetching the artwork and caching
+//! the result on R2 is the job of `handlers::og_image`.
+
+use std::io::Cursor;
+use std::sync::LazyLock;
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+
+pub const WIDTH: u32 = 1200;
+pub const HEIGHT: u32 = 630;
+
+/// Bump whenever the layout changes so every cached card gets a new R2
+/// key (see [`Card::r2_key`]) instead of serving the old design forever.
+const LAYOUT_VERSION: u32 = 1;
+
+const PAD: i32 = 64;
+const BRAND: &str = "ceskarepublika.wiki";
+const BREADCRUMB_SEPARATOR: &str = " › ";
+
+const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+const MUTED: Rgb<u8> = Rgb([203, 213, 225]);
+/// Flag colours for the bottom stripe.
+const CZ_BLUE: Rgb<u8> = Rgb([17, 69, 126]);
+const CZ_RED: Rgb<u8> = Rgb([215, 20, 26]);
+/// Background when there is no artwork at all.
+const FALLBACK_TOP: Rgb<u8> = Rgb([17, 69, 126]);
+const FALLBACK_BOTTOM: Rgb<u8> = Rgb([15, 23, 42]);
+
+static FONT_REGULAR: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!("../assets/fonts/DejaVuSans.ttf"))
+        .expect("bundled DejaVuSans.ttf is a valid font")
+});
+static FONT_BOLD: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!("../assets/fonts/DejaVuSans-Bold.ttf"))
+        .expect("bundled DejaVuSans-Bold.ttf is a valid font")
+});
+
+/// How the artwork is laid out on the card.
+pub enum Artwork {
+    /// Landscape-ish photo, cropped to fill the whole card.
+    Photo(DynamicImage),
+    /// Portrait poster, shown whole on the left.
+    Poster(DynamicImage),
+    /// Nothing to show — flag-blue gradient.
+    None,
+}
+
+/// Text content of a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    /// R2 prefix segment, e.g. `landmark`.
+    pub kind: &'static str,
+    pub id: i32,
+    pub title: String,
+    /// Parent units / section, outermost first.
+    pub breadcrumb: Vec<String>,
+    /// R2 key of the artwork the card is composed from, if any.
+    pub artwork_key: Option<String>,
+}
+
+impl Card {
+    /// Deterministic R2 key: `og/{kind}/{id}-{fingerprint}.png`. The
+    /// fingerprint covers everything drawn on the card, so a rename, a new
+    /// primary photo or a layout change produces a new object while an
+    /// unchanged card keeps hitting the cached one.
+    pub fn r2_key(&self) -> String {
+        let mut hash = Fnv64::new();
+        hash.write(&LAYOUT_VERSION.to_le_bytes());
+        hash.write(self.title.as_bytes());
+        for crumb in &self.breadcrumb {
+            hash.write(b"\0");
+            hash.write(crumb.as_bytes());
+        }
+        hash.write(b"\0\0");
+        hash.write(self.artwork_key.as_deref().unwrap_or("").as_bytes());
+        format!("og/{}/{}-{:016x}.png", self.kind, self.id, hash.finish())
+    }
+}
+
+/// FNV-1a — stable across Rust releases, unlike `DefaultHasher`, which
+/// matters because the hash ends up in persisted object keys.
+struct Fnv64(u64);
+
+impl Fnv64 {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Compose the card and encode it as PNG.
+pub fn render(card: &Card, artwork: Artwork) -> Result<Vec<u8>, image::ImageError> {
+    let (mut canvas, text_left) = match artwork {
+        Artwork::Photo(photo) => {
+            let mut canvas = photo
+                .resize_to_fill(WIDTH, HEIGHT, FilterType::Triangle)
+                .to_rgb8();
+            // Darken the lower part so white text stays readable on any photo.
+            shade_bottom(&mut canvas, HEIGHT as i32 * 2 / 5, 0.85);
+            (canvas, PAD)
+        }
+        Artwork::Poster(poster) => {
+            // Cheap blur: shrink to a few pixels and stretch back.
+            let mut canvas = imageops::resize(
+                &poster
+                    .resize_to_fill(24, 13, FilterType::Triangle)
+                    .to_rgb8(),
+                WIDTH,
+                HEIGHT,
+                FilterType::Triangle,
+            );
+            // Starting the gradient one card-height above the top edge
+            // darkens the whole backdrop, from 45 % at the top to 90 %.
+            shade_bottom(&mut canvas, -(HEIGHT as i32), 0.9);
+            let poster_h = HEIGHT - 2 * 48;
+            let poster = poster
+                .resize(WIDTH, poster_h, FilterType::Lanczos3)
+                .to_rgb8();
+            imageops::replace(&mut canvas, &poster, 48, 48);
+            (canvas, 48 + poster.width() as i32 + 56)
+        }
+        Artwork::None => {
+            let canvas = RgbImage::from_fn(WIDTH, HEIGHT, |_, y| {
+                lerp(FALLBACK_TOP, FALLBACK_BOTTOM, y as f32 / HEIGHT as f32)
+            });
+            (canvas, PAD)
+        }
+    };
+
+    let max_width = (WIDTH as i32 - PAD - text_left) as f32;
+
+    // Branding and flag stripe along the bottom edge.
+    let stripe = 10;
+    for y in HEIGHT - stripe..HEIGHT {
+        for x in 0..WIDTH {
+            let colour = if x < WIDTH / 2 { CZ_BLUE } else { CZ_RED };
+            canvas.put_pixel(x, y, colour);
+        }
+    }
+    let brand_baseline = HEIGHT as i32 - PAD + 8;
+    draw_text(
+        &mut canvas,
+        &FONT_BOLD,
+        28.0,
+        text_left,
+        brand_baseline,
+        WHITE,
+        BRAND,
+    );
+
+    // Title, bottom-aligned above the branding; breadcrumb above it.
+    let (title_px, lines) = fit_title(&card.title, max_width);
+    let line_height = (title_px * 1.15).round() as i32;
+    let mut baseline = brand_baseline - 56 - line_height * (lines.len() as i32 - 1);
+    if !card.breadcrumb.is_empty() {
+        let crumbs = ellipsize(
+            &FONT_REGULAR,
+            30.0,
+            &card.breadcrumb.join(BREADCRUMB_SEPARATOR),
+            max_width,
+        );
+        let crumb_baseline = baseline - line_height + 8;
+        draw_text(
+            &mut canvas,
+            &FONT_REGULAR,
+            30.0,
+            text_left,
+            crumb_baseline,
+            MUTED,
+            &crumbs,
+        );
+    }
+    for line in &lines {
+        draw_text(
+            &mut canvas,
+            &FONT_BOLD,
+            title_px,
+            text_left,
+            baseline,
+            WHITE,
+            line,
+        );
+        baseline += line_height;
+    }
+
+    let mut out = Cursor::new(Vec::new());
+    canvas.write_to(&mut out, ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+/// Largest title size whose wrap fits: two lines at 72 or 60 px, else
+/// three lines at 50 px with the rest ellipsised.
+fn fit_title(title: &str, max_width: f32) -> (f32, Vec<String>) {
+    for px in [72.0, 60.0] {
+        let lines = wrap(&FONT_BOLD, px, title, max_width, 2);
+        if !lines.last().is_some_and(|l| l.ends_with('…')) {
+            return (px, lines);
+        }
+    }
+    (50.0, wrap(&FONT_BOLD, 50.0, title, max_width, 3))
+}
+
+/// Greedy word wrap into at most `max_lines`; overflow is cut with `…`.
+fn wrap(font: &FontRef, px: f32, text: &str, max_width: f32, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut words = text.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{current} {word}")
+        };
+        if current.is_empty() || text_width(font, px, &candidate) <= max_width {
+            current = candidate;
+            continue;
+        }
+        if lines.len() + 1 == max_lines {
+            // Last allowed line: keep everything and let ellipsize cut it.
+            current = std::iter::once(candidate.as_str())
+                .chain(words.by_ref())
+                .collect::<Vec<_>>()
+                .join(" ");
+            break;
+        }
+        lines.push(std::mem::replace(&mut current, word.to_string()));
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+        .into_iter()
+        .map(|l| ellipsize(font, px, &l, max_width))
+        .collect()
+}
+
+/// Shorten `text` with a trailing `…` until it fits `max_width`.
+fn ellipsize(font: &FontRef, px: f32, text: &str, max_width: f32) -> String {
+    if text_width(font, px, text) <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if text_width(font, px, &candidate) <= max_width {
+            return candidate;
+        }
+    }
+    "…".to_string()
+}
+
+fn text_width(font: &FontRef, px: f32, text: &str) -> f32 {
+    let scaled = font.as_scaled(PxScale::from(px));
+    let mut width = 0.0;
+    let mut prev = None;
+    for ch in text.chars() {
+        let id = scaled.glyph_id(ch);
+        if let Some(prev) = prev {
+            width += scaled.kern(prev, id);
+        }
+        width += scaled.h_advance(id);
+        prev = Some(id);
+    }
+    width
+}
+
+fn draw_text(
+    canvas: &mut RgbImage,
+    font: &FontRef,
+    px: f32,
+    x: i32,
+    baseline: i32,
+    colour: Rgb<u8>,
+    text: &str,
+) {
+    let scaled = font.as_scaled(PxScale::from(px));
+    let mut caret = x as f32;
+    let mut prev = None;
+    for ch in text.chars() {
+        let id = scaled.glyph_id(ch);
+        if let Some(prev) = prev {
+            caret += scaled.kern(prev, id);
+        }
+        let glyph = id.with_scale_and_position(px, point(caret, baseline as f32));
+        caret += scaled.h_advance(id);
+        prev = Some(id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= canvas.width() as i32 || py >= canvas.height() as i32 {
+                return;
+            }
+            let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+            *pixel = lerp(*pixel, colour, coverage.min(1.0));
+        });
+    }
+}
+
+/// Vertical gradient from transparent at `from_y` to `strength` black at
+/// the bottom edge.
+fn shade_bottom(canvas: &mut RgbImage, from_y: i32, strength: f32) {
+    let span = (canvas.height() as i32 - from_y).max(1) as f32;
+    for (_, y, pixel) in canvas.enumerate_pixels_mut() {
+        let t = (y as i32 - from_y) as f32 / span;
+        if t > 0.0 {
+            *pixel = lerp(*pixel, Rgb([0, 0, 0]), t * strength);
+        }
+    }
+}
+
+fn lerp(a: Rgb<u8>, b: Rgb<u8>, t: f32) -> Rgb<u8> {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Rgb([mix(a[0], b[0]), mix(a[1], b[1]), mix(a[2], b[2])])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(title: &str) -> Card {
+        Card {
+            kind: "landmark",
+            id: 42,
+            title: title.to_string(),
+            breadcrumb: vec!["Jihomoravský kraj".into(), "Mikulov".into()],
+            artwork_key: Some("landmarks/42/1.webp".into()),
+        }
+    }
+
+    #[test]
+    fn r2_key_is_deterministic_and_tracks_content() {
+        let a = card("Zámek Mikulov");
+        assert_eq!(a.r2_key(), card("Zámek Mikulov").r2_key());
+        assert!(a.r2_key().starts_with("og/landmark/42-"));
+        assert!(a.r2_key().ends_with(".png"));
+
+        let renamed = card("Zámek Valtice");
+        assert_ne!(a.r2_key(), renamed.r2_key());
+        let new_photo = Card {
+            artwork_key: Some("landmarks/42/2.webp".into()),
+            ..a.clone()
+        };
+        assert_ne!(a.r2_key(), new_photo.r2_key());
+        let no_photo = Card {
+            artwork_key: None,
+            ..a.clone()
+        };
+        assert_ne!(a.r2_key(), no_photo.r2_key());
+    }
+
+    #[test]
+    fn wrap_respects_width_and_line_limit() {
+        let title = "Kostel Nanebevzetí Panny Marie a sv. Jana Nepomuckého \
+                     v Horní Dolní u Staré Boleslavi";
+        let lines = wrap(&FONT_BOLD, 72.0, title, 800.0, 2);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with('…'));
+        for line in &lines {
+            assert!(text_width(&FONT_BOLD, 72.0, line) <= 800.0, "{line}");
+        }
+        assert_eq!(wrap(&FONT_BOLD, 72.0, "Brno", 800.0, 2), vec!["Brno"]);
+    }
+
+    #[test]
+    fn fit_title_shrinks_long_titles() {
+        assert_eq!(fit_title("Praha", 1000.0).0, 72.0);
+        let (px, lines) = fit_title(
+            "Hrad a zámek Český Krumlov s barokním divadlem, zahradou a \
+             otáčivým hledištěm na jihu Čech",
+            1000.0,
+        );
+        assert!(px < 72.0);
+        assert!(lines.len() <= 3);
+    }
+
+    #[test]
+    fn renders_png_at_card_size() {
+        for artwork in [
+            Artwork::None,
+            Artwork::Photo(DynamicImage::new_rgb8(800, 600)),
+            Artwork::Poster(DynamicImage::new_rgb8(200, 300)),
+        ] {
+            let png = render(&card("Zámek Mikulov"), artwork).unwrap();
+            let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (WIDTH, HEIGHT));
+        }
+    }
+}
@@ -0,0 +1,567 @@
+//! Per-client rate limiting and daily quotas for the public API.
+//!
+//! [`RateLimitLayer`] is a tower layer for one route group (`/video/info`,
+//! `/video/prepare`, the file endpoints, …). Each group keeps its own
+//! per-client state:
+//!
+//! - a **token bucket** — `burst` requests at once, refilled at the
+//!   configured rate;
+//! - an optional **daily job quota** — successful (2xx) requests per UTC
+//!   day, meant for endpoints that enqueue work;
+//! - an optional **daily byte quota** — response bytes per UTC day, taken
+//!   from `Content-Length` (or the exact body size when there is none).
+//!
+//! A refused request gets a 429 with `Retry-After` and the same
+//! `{"error": …}` body as the rest of the video API.
+//!
+//! Clients are told apart by IP: the TCP peer, or `CF-Connecting-IP` when
+//! the peer is one of Cloudflare's edge ranges (anyone else could set the
+//! header). IPv6 clients are grouped by /64, the block a single subscriber
+//! usually gets. Limits come from `RATE_LIMIT_*` env vars, see
+//! [`RateLimitConfig::parse`].
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as _, bail};
+use axum::Json;
+use axum::body::HttpBody;
+use axum::extract::{ConnectInfo, Request};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use chrono::{NaiveDate, Utc};
+use tower::{Layer, Service};
+
+/// Above this many tracked clients, entries that carry no information
+/// (full bucket, no quota used today) are dropped on the next insert.
+const MAX_CLIENTS: usize = 50_000;
+
+/// Cloudflare's published edge ranges (<https://www.cloudflare.com/ips/>).
+/// `CF-Connecting-IP` is only believed when the peer is in one of these.
+const CLOUDFLARE_RANGES: &[(&str, u8)] = &[
+    ("173.245.48.0", 20),
+    ("103.21.244.0", 22),
+    ("103.22.200.0", 22),
+    ("103.31.4.0", 22),
+    ("141.101.64.0", 18),
+    ("108.162.192.0", 18),
+    ("190.93.240.0", 20),
+    ("188.114.96.0", 20),
+    ("197.234.240.0", 22),
+    ("198.41.128.0", 17),
+    ("162.158.0.0", 15),
+    ("104.16.0.0", 13),
+    ("104.24.0.0", 14),
+    ("172.64.0.0", 13),
+    ("131.0.72.0", 22),
+    ("2400:cb00::", 32),
+    ("2606:4700::", 32),
+    ("2803:f800::", 32),
+    ("2405:b500::", 32),
+    ("2405:8100::", 32),
+    ("2a06:98c0::", 29),
+    ("2c0f:f248::", 32),
+];
+
+/// Limits of one route group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    /// Bucket refill, in requests per second.
+    pub per_sec: f64,
+    /// Bucket size: how many requests may arrive at once.
+    pub burst: u32,
+    /// Successful requests per client and UTC day.
+    pub daily_jobs: Option<u32>,
+    /// Response bytes per client and UTC day.
+    pub daily_bytes: Option<u64>,
+}
+
+impl RateLimitConfig {
+    /// Parse a `RATE_LIMIT_*` value: a rate, then optional comma-separated
+    /// settings, e.g. `20/min`, `6/min,burst=3,jobs=40` or
+    /// `60/min,burst=30,bytes=20G`. Rates are per `s`, `min` or `h`;
+    /// `burst` defaults to the per-minute rate; `jobs` and `bytes` are
+    /// daily, `bytes` takes a `K`/`M`/`G` suffix. `off` disables the
+    /// group (`None`).
+    pub fn parse(spec: &str) -> anyhow::Result<Option<Self>> {
+        let spec = spec.trim();
+        if spec.eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        let mut parts = spec.split(',').map(str::trim);
+        let rate = parts.next().unwrap_or_default();
+        let (count, unit) = rate
+            .split_once('/')
+            .with_context(|| format!("rate \"{rate}\" must look like 20/min"))?;
+        let count: f64 = count
+            .parse()
+            .ok()
+            .filter(|c: &f64| *c > 0.0)
+            .with_context(|| format!("rate \"{rate}\" needs a positive count"))?;
+        let per_sec = match unit {
+            "s" => count,
+            "min" => count / 60.0,
+            "h" => count / 3600.0,
+            _ => bail!("rate \"{rate}\" must be per s, min or h"),
+        };
+
+        let mut config = Self {
+            per_sec,
+            burst: ((per_sec * 60.0).round() as u32).max(1),
+            daily_jobs: None,
+            daily_bytes: None,
+        };
+        for part in parts {
+            let (key, value) = part
+                .split_once('=')
+                .with_context(|| format!("\"{part}\" must be key=value"))?;
+            match key {
+                "burst" => {
+                    config.burst =
+                        value.parse().ok().filter(|b| *b > 0).with_context(|| {
+                            format!("burst \"{value}\" must be a positive number")
+                        })?
+                }
+                "jobs" => {
+                    config.daily_jobs = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("jobs \"{value}\" must be a number"))?,
+                    )
+                }
+                "bytes" => config.daily_bytes = Some(parse_bytes(value)?),
+                _ => bail!("unknown rate limit setting \"{key}\""),
+            }
+        }
+        Ok(Some(config))
+    }
+
+    /// Read `var`, falling back to `default` when it is unset or empty.
+    pub fn from_env(var: &str, default: &str) -> anyhow::Result<Option<Self>> {
+        let value = std::env::var(var)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| default.to_string());
+        Self::parse(&value).with_context(|| format!("{var}=\"{value}\" is not a valid rate limit"))
+    }
+}
+
+fn parse_bytes(value: &str) -> anyhow::Result<u64> {
+    let (digits, multiplier) = match value.as_bytes().last() {
+        Some(b'K' | b'k') => (&value[..value.len() - 1], 1 << 10),
+        Some(b'M' | b'm') => (&value[..value.len() - 1], 1 << 20),
+        Some(b'G' | b'g') => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+    let count: u64 = digits
+        .parse()
+        .with_context(|| format!("bytes \"{value}\" must be a number with K, M or G"))?;
+    Ok(count * multiplier)
+}
+
+/// Why a request was turned away.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Refusal {
+    /// Bucket empty; a token is back in this long.
+    TooFast(Duration),
+    /// Daily quota used up until UTC midnight, this long away.
+    QuotaExhausted(Duration),
+}
+
+impl IntoResponse for Refusal {
+    fn into_response(self) -> Response {
+        let wait = match self {
+            Refusal::TooFast(wait) | Refusal::QuotaExhausted(wait) => wait,
+        };
+        // Whole seconds, rounded up so a client that waits exactly this
+        // long finds a token.
+        let secs = (wait.as_secs() + u64::from(wait.subsec_nanos() > 0)).max(1);
+        let message = match self {
+            Refusal::TooFast(_) => {
+                format!("Příliš mnoho požadavků, zkuste to znovu za {secs} s.")
+            }
+            Refusal::QuotaExhausted(_) => {
+                "Denní limit byl vyčerpán, zkuste to znovu zítra.".to_string()
+            }
+        };
+        let mut response = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(serde_json::json!({ "error": message })),
+        )
+            .into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        response
+    }
+}
+
+/// State of one client within a group.
+struct Client {
+    tokens: f64,
+    refilled: Instant,
+    day: NaiveDate,
+    jobs: u32,
+    bytes: u64,
+}
+
+/// Limits and per-client state of one route group. Shared by every clone
+/// of the layer.
+struct Limiter {
+    group: &'static str,
+    config: RateLimitConfig,
+    clients: Mutex<HashMap<IpAddr, Client>>,
+}
+
+impl Limiter {
+    fn new(group: &'static str, config: RateLimitConfig) -> Self {
+        Self {
+            group,
+            config,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token and, with a job quota, reserve a job. A request that
+    /// then fails must hand the job back via [`Limiter::finish`].
+    fn admit(&self, ip: IpAddr, now: Instant, today: NaiveDate) -> Result<(), Refusal> {
+        let config = &self.config;
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if clients.len() >= MAX_CLIENTS && !clients.contains_key(&ip) {
+            clients.retain(|_, c| !c.is_idle(config, now, today));
+        }
+        let client = clients.entry(ip).or_insert_with(|| Client {
+            tokens: f64::from(config.burst),
+            refilled: now,
+            day: today,
+            jobs: 0,
+            bytes: 0,
+        });
+        if client.day != today {
+            client.day = today;
+            client.jobs = 0;
+            client.bytes = 0;
+        }
+
+        let quota_used = config.daily_jobs.is_some_and(|max| client.jobs >= max)
+            || config.daily_bytes.is_some_and(|max| client.bytes >= max);
+        if quota_used {
+            return Err(Refusal::QuotaExhausted(until_midnight(Utc::now())));
+        }
+
+        client.refill(config, now);
+        if client.tokens < 1.0 {
+            let wait = (1.0 - client.tokens) / config.per_sec;
+            return Err(Refusal::TooFast(Duration::from_secs_f64(wait)));
+        }
+        client.tokens -= 1.0;
+        if config.daily_jobs.is_some() {
+            client.jobs += 1;
+        }
+        Ok(())
+    }
+
+    /// Account for the response to an admitted request.
+    fn finish(&self, ip: IpAddr, succeeded: bool, bytes: u64) {
+        if self.config.daily_jobs.is_none() && self.config.daily_bytes.is_none() {
+            return;
+        }
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(client) = clients.get_mut(&ip) {
+            if self.config.daily_jobs.is_some() && !succeeded {
+                client.jobs = client.jobs.saturating_sub(1);
+            }
+            if self.config.daily_bytes.is_some() {
+                client.bytes += bytes;
+            }
+        }
+    }
+}
+
+impl Client {
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.per_sec).min(f64::from(config.burst));
+        self.refilled = now;
+    }
+
+    /// Indistinguishable from a client never seen before, so safe to drop.
+    fn is_idle(&self, config: &RateLimitConfig, now: Instant, today: NaiveDate) -> bool {
+        let refill_secs = f64::from(config.burst) / config.per_sec;
+        let refilled = now.saturating_duration_since(self.refilled).as_secs_f64() >= refill_secs;
+        let quota_clear = self.day != today || (self.jobs == 0 && self.bytes == 0);
+        refilled && quota_clear
+    }
+}
+
+/// Time from `now` to the next UTC midnight, when daily quotas reset.
+fn until_midnight(now: chrono::DateTime<Utc>) -> Duration {
+    let tomorrow = now.date_naive() + chrono::Days::new(1);
+    let midnight = tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    (midnight - now).to_std().unwrap_or_default()
+}
+
+/// The client a request is counted against. Requests without connect
+/// info (only in tests) share one unspecified address.
+fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>) -> IpAddr {
+    let peer = peer.unwrap_or(IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+    let ip = if is_cloudflare(peer) {
+        headers
+            .get("cf-connecting-ip")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(peer)
+    } else {
+        peer
+    };
+    match ip.to_canonical() {
+        IpAddr::V6(v6) => {
+            let prefix = u128::from(v6) & !((1u128 << 64) - 1);
+            IpAddr::V6(Ipv6Addr::from(prefix))
+        }
+        v4 => v4,
+    }
+}
+
+fn is_cloudflare(ip: IpAddr) -> bool {
+    let ip = ip.to_canonical();
+    CLOUDFLARE_RANGES
+        .iter()
+        .any(|(net, bits)| match (ip, net.parse::<IpAddr>()) {
+            (IpAddr::V4(ip), Ok(IpAddr::V4(net))) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(*bits)).unwrap_or(0);
+                u32::from(ip) & mask == u32::from(net) & mask
+            }
+            (IpAddr::V6(ip), Ok(IpAddr::V6(net))) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(*bits)).unwrap_or(0);
+                u128::from(ip) & mask == u128::from(net) & mask
+            }
+            _ => false,
+        })
+}
+
+/// Response size for the byte quota.
+fn response_bytes(response: &Response) -> u64 {
+    response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .or_else(|| response.body().size_hint().exact())
+        .unwrap_or(0)
+}
+
+/// Tower layer limiting one route group. Apply with `route_layer` so
+/// unmatched paths don't use up tokens. The server must be started with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl RateLimitLayer {
+    /// `None` leaves the group unlimited.
+    pub fn new(group: &'static str, config: Option<RateLimitConfig>) -> Self {
+        Self {
+            limiter: config.map(|config| Arc::new(Limiter::new(group, config))),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl<S> Service<Request> for RateLimit<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The clone isn't necessarily ready; keep the one that is.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let Some(limiter) = self.limiter.clone() else {
+            return Box::pin(inner.call(request));
+        };
+
+        Box::pin(async move {
+            let peer = request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip());
+            let ip = client_ip(request.headers(), peer);
+            if let Err(refusal) = limiter.admit(ip, Instant::now(), Utc::now().date_naive()) {
+                tracing::info!(group = limiter.group, %ip, "rate limited: {refusal:?}");
+                return Ok(refusal.into_response());
+            }
+            let response = inner.call(request).await?;
+            limiter.finish(
+                ip,
+                response.status().is_success(),
+                response_bytes(&response),
+            );
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_limit_specs() {
+        let config = RateLimitConfig::parse("6/min,burst=3,jobs=40")
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.per_sec, 0.1);
+        assert_eq!(config.burst, 3);
+        assert_eq!(config.daily_jobs, Some(40));
+        assert_eq!(config.daily_bytes, None);
+
+        let config = RateLimitConfig::parse("2/s, bytes=20G").unwrap().unwrap();
+        assert_eq!(config.burst, 120);
+        assert_eq!(config.daily_bytes, Some(20 << 30));
+
+        assert_eq!(RateLimitConfig::parse("off").unwrap(), None);
+        assert!(RateLimitConfig::parse("20").is_err());
+        assert!(RateLimitConfig::parse("20/day").is_err());
+        assert!(RateLimitConfig::parse("0/min").is_err());
+        assert!(RateLimitConfig::parse("20/min,burst=0").is_err());
+        assert!(RateLimitConfig::parse("20/min,bytes=lots").is_err());
+        assert!(RateLimitConfig::parse("20/min,speed=1").is_err());
+    }
+
+    #[test]
+    fn bucket_refuses_past_burst_and_refills() {
+        let limiter = Limiter::new(
+            "test",
+            RateLimitConfig::parse("60/min,burst=2").unwrap().unwrap(),
+        );
+        let t0 = Instant::now();
+        let a = ip("192.0.2.1");
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        let Err(Refusal::TooFast(wait)) = limiter.admit(a, t0, day(1)) else {
+            panic!("third request should be refused");
+        };
+        assert_eq!(wait, Duration::from_secs(1));
+        // Another client has its own bucket.
+        assert!(limiter.admit(ip("192.0.2.2"), t0, day(1)).is_ok());
+        assert!(
+            limiter
+                .admit(a, t0 + Duration::from_secs(1), day(1))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn job_quota_counts_only_successes_and_resets_daily() {
+        let limiter = Limiter::new(
+            "test",
+            RateLimitConfig::parse("60/s,jobs=2").unwrap().unwrap(),
+        );
+        let t0 = Instant::now();
+        let a = ip("192.0.2.1");
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        limiter.finish(a, false, 0);
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        limiter.finish(a, true, 0);
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        limiter.finish(a, true, 0);
+        assert!(matches!(
+            limiter.admit(a, t0, day(1)),
+            Err(Refusal::QuotaExhausted(_))
+        ));
+        assert!(limiter.admit(a, t0, day(2)).is_ok());
+    }
+
+    #[test]
+    fn byte_quota_refuses_once_exceeded() {
+        let limiter = Limiter::new(
+            "test",
+            RateLimitConfig::parse("60/s,bytes=1K").unwrap().unwrap(),
+        );
+        let t0 = Instant::now();
+        let a = ip("192.0.2.1");
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        limiter.finish(a, true, 1000);
+        assert!(limiter.admit(a, t0, day(1)).is_ok());
+        limiter.finish(a, true, 1000);
+        assert!(matches!(
+            limiter.admit(a, t0, day(1)),
+            Err(Refusal::QuotaExhausted(_))
+        ));
+    }
+
+    #[test]
+    fn cf_header_is_trusted_only_from_cloudflare() {
+        let mut headers = HeaderMap::new();
+        headers.insert("cf-connecting-ip", HeaderValue::from_static("198.51.100.7"));
+        assert_eq!(
+            client_ip(&headers, Some(ip("172.70.1.2"))),
+            ip("198.51.100.7")
+        );
+        assert_eq!(
+            client_ip(&headers, Some(ip("::ffff:162.158.3.4"))),
+            ip("198.51.100.7")
+        );
+        assert_eq!(
+            client_ip(&headers, Some(ip("203.0.113.9"))),
+            ip("203.0.113.9")
+        );
+        // IPv6 clients share their /64.
+        assert_eq!(
+            client_ip(&HeaderMap::new(), Some(ip("2001:db8:1:2:aaaa::1"))),
+            ip("2001:db8:1:2::")
+        );
+    }
+
+    #[test]
+    fn refusal_sets_retry_after() {
+        let response = Refusal::TooFast(Duration::from_millis(1500)).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+
+        let evening = chrono::DateTime::parse_from_rfc3339("2026-10-19T23:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(until_midnight(evening), Duration::from_secs(3600));
+    }
+}
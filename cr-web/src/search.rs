@@ -0,0 +1,78 @@
+//! Background upkeep of the in-process search indexes: the catalog
+//! full-text index (`cr_app::search`) and the site-wide suggestion index
+//! (`cr_app::suggest`).
+//!
+//! The first full build runs right after startup without blocking it —
+//! the autocomplete handlers use the SQL fallback until it lands. After
+//! that the loop pulls changed rows every minute and rebuilds from
+//! scratch every hour, which is what drops deleted titles. The suggestion
+//! index has no incremental path and is simply rebuilt every few minutes.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cr_app::search::CatalogSearch;
+use cr_app::suggest::Suggester;
+use cr_infra::repositories::{PgSearchDocumentRepository, PgSuggestSourceRepository};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Refresh ticks between full rebuilds.
+const REBUILD_EVERY: u32 = 60;
+
+/// New titles and rewritten place names show up in `/api/suggest` within
+/// this long.
+const SUGGEST_REBUILD_INTERVAL: Duration = Duration::from_secs(600);
+
+pub fn spawn_search_index_loop(
+    search: Arc<CatalogSearch>,
+    repo: Arc<PgSearchDocumentRepository>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut tick: u32 = 0;
+        loop {
+            // The first tick fires immediately, so this is the initial build.
+            ticker.tick().await;
+            let started = std::time::Instant::now();
+            if tick.is_multiple_of(REBUILD_EVERY) {
+                match search.rebuild(repo.as_ref()).await {
+                    Ok(n) => tracing::info!(
+                        "search index: built {n} documents in {} ms",
+                        started.elapsed().as_millis()
+                    ),
+                    Err(e) => tracing::warn!("search index: rebuild failed: {e}"),
+                }
+            } else {
+                match search.refresh(repo.as_ref()).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!("search index: re-indexed {n} changed documents"),
+                    Err(e) => tracing::warn!("search index: refresh failed: {e}"),
+                }
+            }
+            tick = tick.wrapping_add(1);
+        }
+    })
+}
+
+pub fn spawn_suggest_index_loop(
+    suggester: Arc<Suggester>,
+    repo: Arc<PgSuggestSourceRepository>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SUGGEST_REBUILD_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            let started = std::time::Instant::now();
+            match suggester.rebuild(repo.as_ref()).await {
+                Ok(n) => tracing::info!(
+                    "suggest index: built {n} entries in {} ms",
+                    started.elapsed().as_millis()
+                ),
+                Err(e) => tracing::warn!("suggest index: rebuild failed: {e}"),
+            }
+        }
+    })
+}
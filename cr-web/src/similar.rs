@@ -0,0 +1,55 @@
+//! Daily recomputation of the `similar_titles` table.
+//!
+//! Scoring lives in `cr_app::similar`; this loop loads the film and series
+//! profiles, scores them on a blocking thread (a full film run is a few
+//! seconds of CPU) and swaps the stored sets. The first run waits a few
+//! minutes after startup so it doesn't compete with a deploy's cold
+//! caches; until then the carousels show the previous run's rows.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cr_app::similar::{SIMILAR_PER_TITLE, compute_similar};
+use cr_domain::catalog::CatalogKind;
+use cr_domain::repository::SimilarTitlesRepository;
+use cr_infra::repositories::PgSimilarTitlesRepository;
+
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(5 * 60);
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+pub fn spawn_similar_titles_loop(
+    repo: Arc<PgSimilarTitlesRepository>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        let mut ticker = tokio::time::interval(RUN_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            for kind in [CatalogKind::Film, CatalogKind::Series] {
+                let started = std::time::Instant::now();
+                match refresh(&repo, kind).await {
+                    Ok((titles, pairs)) => tracing::info!(
+                        "similar titles ({kind:?}): {titles} titles, {pairs} pairs in {} ms",
+                        started.elapsed().as_millis()
+                    ),
+                    Err(e) => tracing::warn!("similar titles ({kind:?}) failed: {e:#}"),
+                }
+            }
+        }
+    })
+}
+
+/// Recompute one kind. Returns how many titles got matches and the total
+/// number of stored pairs.
+async fn refresh(
+    repo: &PgSimilarTitlesRepository,
+    kind: CatalogKind,
+) -> anyhow::Result<(usize, usize)> {
+    let profiles = repo.profiles(kind).await?;
+    let sets =
+        tokio::task::spawn_blocking(move || compute_similar(&profiles, SIMILAR_PER_TITLE)).await?;
+    repo.replace(kind, &sets).await?;
+    let pairs = sets.iter().map(|s| s.similar.len()).sum();
+    Ok((sets.len(), pairs))
+}
@@ -2,10 +2,14 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
-use cr_infra::r2::R2Config;
+use cr_infra::boundaries::ReverseGeocoder;
+use cr_infra::photo_hash::PhotoHashIndex;
+use cr_infra::r2::{R2Client, R2Config};
 use cr_infra::repositories::{
-    PgLandmarkRepository, PgMunicipalityRepository, PgOrpRepository, PgPhotoRepository,
-    PgPoolRepository, PgRegionRepository, PgVideoRepository,
+    PgEpisodeCalendarRepository, PgEpisodeRepository, PgExportRepository, PgFeedRepository,
+    PgFilmCollectionRepository, PgFilmRepository, PgLandmarkRepository, PgMunicipalityRepository,
+    PgOrpRepository, PgPhotoRepository, PgPoolRepository, PgRegionRepository, PgSeriesRepository,
+    PgSimilarTitlesRepository, PgTvShowRepository, PgVideoRepository,
 };
 use cr_infra::streamtape::StreamtapeConfig;
 use cr_infra::video_library::VideoLibraryPipeline;
@@ -13,7 +17,7 @@ use sqlx::PgPool;
 
 use crate::cache::BoundedTtlCache;
 use crate::handlers::SktorrentSource;
-use crate::handlers::video_api::VideoDownloads;
+use crate::handlers::video_api::VideoQueue;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -23,6 +27,13 @@ pub struct AppState {
     pub config: Arc<crate::config::AppConfig>,
     pub db: PgPool,
     pub geojson_index: Arc<GeoJsonIndex>,
+    /// Point-in-polygon lookup over the boundary files for `/api/reverse`.
+    pub reverse_geocoder: Arc<ReverseGeocoder>,
+    /// Boundary layers pre-projected for `/tiles/{layer}/{z}/{x}/{y}.mvt`.
+    pub tile_layers: Arc<crate::geo::tiles::TileLayers>,
+    /// Encoded vector tiles keyed by `"{layer}/{z}/{x}/{y}"`. The source
+    /// GeoJSON only changes with a deploy, so the TTL is a day.
+    pub tile_cache: BoundedTtlCache<String, axum::body::Bytes>,
     /// Mirrors `config.image_base_url` for the many templates that already
     /// take `&img: String`. New code should prefer `state.config` directly.
     pub image_base_url: String,
@@ -35,10 +46,44 @@ pub struct AppState {
     pub landmark_repo: Arc<PgLandmarkRepository>,
     pub pool_repo: Arc<PgPoolRepository>,
     pub photo_repo: Arc<PgPhotoRepository>,
+    /// Catalog repositories behind `/filmy-online/`, `/serialy-online/`
+    /// and `/tv-porady/`; handlers go through `cr_app::catalog`.
+    pub film_repo: Arc<PgFilmRepository>,
+    pub series_repo: Arc<PgSeriesRepository>,
+    pub episode_repo: Arc<PgEpisodeRepository>,
+    pub tv_show_repo: Arc<PgTvShowRepository>,
+    /// In-process full-text index for the catalog autocomplete endpoints.
+    /// Kept current by `search::spawn_search_index_loop`.
+    pub catalog_search: Arc<cr_app::search::CatalogSearch>,
+    /// Prefix index behind `/api/suggest` and the header search box.
+    /// Rebuilt by `search::spawn_suggest_index_loop`.
+    pub suggest: Arc<cr_app::suggest::Suggester>,
+    /// Precomputed "Podobné tituly" for film and series detail pages.
+    /// Filled by `similar::spawn_similar_titles_loop`.
+    pub similar_repo: Arc<PgSimilarTitlesRepository>,
+    /// Film collections (`/kolekce/`), loaded by `import-collections`.
+    pub collection_repo: Arc<PgFilmCollectionRepository>,
+    /// Atom/RSS feeds (`/filmy-online/feed.xml` and friends).
+    pub feed_repo: Arc<PgFeedRepository>,
+    /// Episode release calendar (`/serialy-online/kalendar/`, `.ics`).
+    pub calendar_repo: Arc<PgEpisodeCalendarRepository>,
+    /// Open-data exports (`/api/export/{dataset}`).
+    pub export_repo: Arc<PgExportRepository>,
+    /// Perceptual-hash index (`photo_hashes`) — duplicate report and
+    /// optional gallery de-duplication.
+    pub photo_hashes: PhotoHashIndex,
     /// Repository for the hosted video library (`videos` table).
     pub video_repo: Arc<PgVideoRepository>,
-    /// Prepared video downloads waiting to be served.
-    pub video_downloads: VideoDownloads,
+    /// Persistent download queue (`video_download_jobs`) behind
+    /// `/api/video/prepare` and `/api/video/status/{token}`.
+    pub video_queue: VideoQueue,
+    /// Extracted video info keyed by canonical URL, so `/api/video/info`
+    /// followed by `/api/video/prepare` runs the extractor once.
+    pub video_info_cache: BoundedTtlCache<String, cr_infra::video::VideoInfo>,
+    /// Per-URL async locks for in-flight extractions, as for
+    /// `prehrajto_in_flight`: concurrent requests for the same video wait
+    /// for the first extraction and read its cached result.
+    pub video_info_in_flight: Arc<tokio::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
     /// Streamtape API credentials for the video library. `None` until the
     /// `STREAMTAPE_LOGIN`/`STREAMTAPE_KEY` env vars are provisioned. Read
     /// at startup only — actual use happens via [`AppState::video_library`].
@@ -48,6 +93,10 @@ pub struct AppState {
     /// the `R2_*` env vars are provisioned. Read at startup only.
     #[allow(dead_code)]
     pub r2_config: Option<Arc<R2Config>>,
+    /// R2 writer used by the cover pipeline to persist covers it generated
+    /// on demand (see `handlers::media_cover`). `None` when the `R2_*` env
+    /// vars are missing — covers are then served but not stored.
+    pub r2_client: Option<R2Client>,
     /// Orchestrator that owns the Streamtape + R2 + DB collaborators for
     /// the hosted video library. `Some` only when both configs are present.
     pub video_library: Option<VideoLibraryPipeline>,
@@ -75,13 +124,18 @@ pub struct AppState {
     /// on the lock and pick up the cached URL after release.
     pub prehrajto_in_flight: Arc<tokio::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
     /// 60 s TTL cache of expensive listing-page counts (`/filmy-online/`,
-    /// `/serialy-online/`). The unfiltered count of films with a live
-    /// video source costs ~60 ms (28 k film_pkey rows × video_sources
-    /// EXISTS probe) and dominates the request budget once the SELECT
-    /// itself dropped to ~2 ms via `idx_films_added_at_id`. The cache
-    /// key is the full SQL count query plus its bound parameters, so
-    /// filtered variants are cached independently without ever colliding.
+    /// `/serialy-online/`, `/tv-porady/`). The unfiltered count of films
+    /// with a live video source costs ~60 ms (28 k film_pkey rows ×
+    /// video_sources EXISTS probe) and dominates the request budget once
+    /// the SELECT itself dropped to ~2 ms via `idx_films_added_at_id`.
+    /// Keys are the typed queries' `count_key()` — the filter plus
+    /// everything else that changes which rows match — so filtered
+    /// variants are cached independently without ever colliding.
     pub listing_count_cache: BoundedTtlCache<String, i64>,
+    /// Rendered `/osobnosti/` without a query. Ranking every person by
+    /// credit count is six correlated counts per row plus a full sort,
+    /// far too slow to repeat for each crawler hit.
+    pub people_index_cache: BoundedTtlCache<(), String>,
 }
 
 /// Cached resolved CDN URL for a single prehraj.to upload.
@@ -0,0 +1,105 @@
+/**
+ * Header search box — mixed suggestions from /api/suggest.
+ *
+ * Markup: form.suggest-box containing input[name=q] and .suggest-dropdown.
+ * Arrow keys move through the hits, Enter opens the highlighted one (or
+ * submits to /hledat/ when nothing is highlighted), Escape closes.
+ */
+(function () {
+    'use strict';
+
+    var MIN_CHARS = 2;
+    var DEBOUNCE_MS = 120;
+
+    function escapeHtml(s) {
+        return String(s).replace(/[&<>"']/g, function (c) {
+            return { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' }[c];
+        });
+    }
+
+    function init(form) {
+        var input = form.querySelector('input[name="q"]');
+        var dropdown = form.querySelector('.suggest-dropdown');
+        if (!input || !dropdown) return;
+
+        var timer = null;
+        var controller = null;
+        var items = [];
+        var active = -1;
+
+        function close() {
+            dropdown.hidden = true;
+            dropdown.innerHTML = '';
+            items = [];
+            active = -1;
+            input.setAttribute('aria-expanded', 'false');
+        }
+
+        function highlight(i) {
+            items.forEach(function (el, n) {
+                el.classList.toggle('active', n === i);
+                el.setAttribute('aria-selected', n === i ? 'true' : 'false');
+            });
+            active = i;
+        }
+
+        function render(hits) {
+            if (!hits.length) { close(); return; }
+            dropdown.innerHTML = hits.map(function (h) {
+                var thumb = h.thumbnail
+                    ? '<img class="suggest-thumb" src="' + escapeHtml(h.thumbnail) + '" alt="" loading="lazy">'
+                    : '<span class="suggest-thumb suggest-thumb-empty"></span>';
+                var meta = escapeHtml(h.label) + (h.context ? ' · ' + escapeHtml(h.context) : '');
+                return '<a class="suggest-item suggest-' + escapeHtml(h.type) + '" role="option" href="' + escapeHtml(h.url) + '">' +
+                    thumb +
+                    '<span class="suggest-text"><span class="suggest-title">' + escapeHtml(h.title) + '</span>' +
+                    '<span class="suggest-meta">' + meta + '</span></span></a>';
+            }).join('');
+            items = Array.prototype.slice.call(dropdown.querySelectorAll('.suggest-item'));
+            active = -1;
+            dropdown.hidden = false;
+            input.setAttribute('aria-expanded', 'true');
+        }
+
+        function lookup() {
+            var q = input.value.trim();
+            if (q.length < MIN_CHARS) { close(); return; }
+            if (controller) controller.abort();
+            controller = new AbortController();
+            fetch('/api/suggest?q=' + encodeURIComponent(q), { signal: controller.signal })
+                .then(function (r) { return r.ok ? r.json() : []; })
+                .then(function (hits) {
+                    // A slow answer for an older query must not win.
+                    if (input.value.trim() === q) render(hits);
+                })
+                .catch(function () {});
+        }
+
+        input.addEventListener('input', function () {
+            clearTimeout(timer);
+            timer = setTimeout(lookup, DEBOUNCE_MS);
+        });
+
+        input.addEventListener('keydown', function (e) {
+            if (dropdown.hidden || !items.length) return;
+            if (e.key === 'ArrowDown') {
+                e.preventDefault();
+                highlight((active + 1) % items.length);
+            } else if (e.key === 'ArrowUp') {
+                e.preventDefault();
+                highlight(active <= 0 ? items.length - 1 : active - 1);
+            } else if (e.key === 'Enter' && active >= 0) {
+                e.preventDefault();
+                window.location.href = items[active].getAttribute('href');
+            } else if (e.key === 'Escape') {
+                close();
+            }
+        });
+
+        document.addEventListener('click', function (e) {
+            if (!form.contains(e.target)) close();
+        });
+    }
+
+    document.querySelectorAll('form.suggest-box').forEach(init);
+})();
@@ -1,13 +1,17 @@
 #!/usr/bin/env python3
 """Download zoos, botanical gardens, aquariums from Wikidata + Wikipedia texts.
 
-Saves to cr_staging.nature_facilities table.
+Saves to cr_staging.nature_facilities table; `municipality_id` is
+resolved from the coordinates via the shared boundary index rather than
+the Wikidata P131 label.
 """
 
 import time
 import requests
 import psycopg2
 
+from reverse_geocode import assign_municipality_ids
+
 STAGING_URL = "postgresql:///cr_staging"
 WIKIDATA_SPARQL = "https://query.wikidata.org/sparql"
 WIKIPEDIA_API = "https://cs.wikipedia.org/w/api.php"
@@ -188,6 +192,7 @@ def main():
 
     conn.commit()
     cur.close()
+    assign_municipality_ids(conn, "nature_facilities", "wikidata_id")
     conn.close()
 
     print(f"\nDone! Saved {saved} facilities to cr_staging.nature_facilities", flush=True)
@@ -13,6 +13,8 @@ import requests
 import psycopg2
 from bs4 import BeautifulSoup
 
+from reverse_geocode import assign_municipality_ids
+
 STAGING_URL = "postgresql:///cr_staging"
 BASE_URL = "https://jduplavat.cz"
 UA = "Mozilla/5.0 (X11; Linux x86_64) CeskaRepublikaWiki/1.0"
@@ -233,6 +235,7 @@ def main():
 
     conn.commit()
     cur.close()
+    assign_municipality_ids(conn, "pools", "slug")
     conn.close()
 
     print(f"\nDone! Scraped: {scraped}, Failed: {failed}, Total: {len(slugs)}", flush=True)
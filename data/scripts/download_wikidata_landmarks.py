@@ -3,13 +3,17 @@
 
 Fetches all items with P762 (Czech cultural heritage ID), including
 cs.wikipedia URL, coordinates, and municipality label.
-Stores results in cr_staging.wikidata_landmarks table.
+Stores results in cr_staging.wikidata_landmarks table; `municipality_id`
+is resolved from the coordinates via the shared boundary index (the
+P131 label is kept for reference only).
 """
 
 import os
 import requests
 import psycopg2
 
+from reverse_geocode import assign_municipality_ids
+
 STAGING_URL = os.environ.get("STAGING_DATABASE_URL", "postgresql:///cr_staging")
 WIKIDATA_SPARQL = "https://query.wikidata.org/sparql"
 
@@ -156,6 +160,7 @@ def main():
     print(f"\nStored {len(items)} items in wikidata_landmarks table", flush=True)
 
     cur.close()
+    assign_municipality_ids(conn, "wikidata_landmarks", "wikidata_id")
     conn.close()
 
 
@@ -0,0 +1,93 @@
+"""Resolve coordinates to municipality / ORP / district / region codes.
+
+Thin wrapper around `assign-municipalities resolve` (cr-infra) so the
+staging importers use exactly the same boundary index and snapping rules
+as `/api/reverse` on the site. Build it once with:
+
+    cargo build --release -p cr-infra --bin assign-municipalities
+
+Set ASSIGN_MUNICIPALITIES_BIN to override the binary path and
+GEOJSON_DATA_DIR to point at the directory with `obce_simple.geojson`.
+Codes are mapped to `municipalities.id` through the main DB at
+DATABASE_URL.
+"""
+
+from __future__ import annotations
+
+import os
+import subprocess
+
+import psycopg2
+
+ASSIGN_BIN = os.environ.get(
+    "ASSIGN_MUNICIPALITIES_BIN",
+    "/home/jirka/Olbrasoft/cr/target/release/assign-municipalities",
+)
+MAIN_URL = os.environ.get("DATABASE_URL", "postgresql:///cr_dev")
+
+
+def resolve_codes(points):
+    """`points` is an iterable of `(key, lat, lon)`.
+
+    Returns `{key: {"municipality": code, "orp": code, "district": code,
+    "region": code}}`; codes are None where the point is outside every
+    polygon. Returns an empty dict (with a warning) when the binary is not
+    built, so importers keep working without it.
+    """
+    lines = "".join(f"{key}\t{lat}\t{lon}\n" for key, lat, lon in points
+                    if lat is not None and lon is not None)
+    if not lines:
+        return {}
+    if not os.path.exists(ASSIGN_BIN):
+        print(f"  WARN: {ASSIGN_BIN} not built — municipality codes not assigned",
+              flush=True)
+        return {}
+    proc = subprocess.run([ASSIGN_BIN, "resolve"], input=lines,
+                          capture_output=True, text=True, check=True)
+    out = {}
+    for line in proc.stdout.splitlines():
+        key, muni, orp, district, region = line.split("\t")
+        out[key] = {
+            "municipality": muni or None,
+            "orp": orp or None,
+            "district": district or None,
+            "region": region or None,
+        }
+    return out
+
+
+def municipality_ids():
+    """`{municipality_code: id}` from the main DB (`DATABASE_URL`)."""
+    conn = psycopg2.connect(MAIN_URL)
+    cur = conn.cursor()
+    cur.execute("SELECT municipality_code, id FROM municipalities")
+    ids = dict(cur.fetchall())
+    cur.close()
+    conn.close()
+    return ids
+
+
+def assign_municipality_ids(conn, table, key_column):
+    """Fill `{table}.municipality_id` in staging from coordinates.
+
+    The id is the main DB's `municipalities.id` — the same column the
+    main `pools` / `landmarks` tables carry — so promoted rows need no
+    further lookup. Only rows that lack an id are touched.
+    """
+    cur = conn.cursor()
+    cur.execute(f"ALTER TABLE {table} ADD COLUMN IF NOT EXISTS municipality_id INT")
+    cur.execute(f"SELECT {key_column}, latitude, longitude FROM {table} "
+                "WHERE municipality_id IS NULL AND latitude IS NOT NULL")
+    resolved = resolve_codes(cur.fetchall())
+    ids = municipality_ids() if resolved else {}
+    assigned = 0
+    for key, codes in resolved.items():
+        municipality_id = ids.get(codes["municipality"])
+        if municipality_id is not None:
+            cur.execute(f"UPDATE {table} SET municipality_id = %s WHERE {key_column} = %s",
+                        (municipality_id, key))
+            assigned += 1
+    conn.commit()
+    cur.close()
+    print(f"  {table}: municipality assigned from coordinates: "
+          f"{assigned}/{len(resolved)}", flush=True)
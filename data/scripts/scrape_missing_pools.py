@@ -8,6 +8,8 @@ import requests
 import psycopg2
 from bs4 import BeautifulSoup
 
+from reverse_geocode import assign_municipality_ids
+
 STAGING_URL = "postgresql:///cr_staging"
 BASE_URL = "https://jduplavat.cz"
 UA = "Mozilla/5.0 (X11; Linux x86_64) CeskaRepublikaWiki/1.0"
@@ -171,6 +173,7 @@ def main():
 
     conn.commit()
     cur.close()
+    assign_municipality_ids(conn, "pools", "slug")
     conn.close()
     print(f"\nDone! Added {done} pools", flush=True)
 
@@ -5,26 +5,55 @@
 
 set -e
 
+# Perceptual-hash duplicate check (cr-infra `photo-hash-index check`).
+# Files that look like an already indexed photo are skipped and logged to
+# $LOG_DUP for manual review; uploaded files are added to the index right
+# away so later files in the same run are checked against them. Build the
+# binary once with
+#   cargo build --release -p cr-infra --bin photo-hash-index
+# or set SKIP_DUPLICATE_CHECK=1 to upload everything unconditionally.
+PHOTO_HASH_BIN="${PHOTO_HASH_BIN:-/home/jirka/Olbrasoft/cr/target/release/photo-hash-index}"
+SKIP_DUPLICATE_CHECK="${SKIP_DUPLICATE_CHECK:-0}"
+
 BUCKET="cr-images"
 IMG_DIR="/home/jirka/Olbrasoft/cr/data/images/landmarks"
 LOG_OK="/tmp/r2_landmarks_ok.log"
+LOG_DUP="/tmp/r2_landmarks_dupes.log"
 LOG_ERR="/tmp/r2_landmarks_err.log"
 
 > "$LOG_OK"
 > "$LOG_ERR"
+> "$LOG_DUP"
 
-export BUCKET IMG_DIR
+export BUCKET IMG_DIR LOG_DUP PHOTO_HASH_BIN SKIP_DUPLICATE_CHECK
 
 upload_one() {
     local FILE="$1"
     local BASENAME=$(basename "$FILE")
     local KEY="landmarks/${BASENAME}"
 
+    # Exit status 2 = near-duplicate already in the index (matches printed
+    # to stdout, one per line); the destination key itself doesn't count,
+    # so re-runs and replacements go through. Any other failure falls
+    # through to upload.
+    if [ "$SKIP_DUPLICATE_CHECK" != "1" ]; then
+        local MATCHES STATUS=0
+        MATCHES=$("$PHOTO_HASH_BIN" check "$FILE" "$KEY" 2>/dev/null) || STATUS=$?
+        if [ "$STATUS" -eq 2 ]; then
+            echo "$MATCHES" >> "$LOG_DUP"
+            return 0
+        fi
+    fi
+
     if npx wrangler r2 object put "${BUCKET}/${KEY}" \
         --file="$FILE" \
         --content-type="image/webp" \
         --remote 2>&1 | grep -q "Upload complete"; then
         echo "$KEY" >> /tmp/r2_landmarks_ok.log
+        if [ "$SKIP_DUPLICATE_CHECK" != "1" ]; then
+            "$PHOTO_HASH_BIN" add "$FILE" "$KEY" 2>/dev/null \
+                || echo "WARN: $KEY uploaded but not indexed" >&2
+        fi
     else
         echo "$KEY" >> /tmp/r2_landmarks_err.log
     fi
@@ -41,7 +70,8 @@ find "$IMG_DIR" -name "*.webp" | parallel -j4 upload_one {}
 
 OK=$(wc -l < "$LOG_OK" 2>/dev/null || echo 0)
 ERR=$(wc -l < "$LOG_ERR" 2>/dev/null || echo 0)
+DUP=$(wc -l < "$LOG_DUP" 2>/dev/null || echo 0)
 
 echo ""
-echo "Done! Uploaded: $OK, Failed: $ERR, Total: $TOTAL"
+echo "Done! Uploaded: $OK, Failed: $ERR, Duplicates skipped: $DUP, Total: $TOTAL"
 echo "End: $(date)"
@@ -119,11 +119,27 @@ def _push_cover_to_r2(out_dir: Path, entity_id: int) -> None:
                         dest, " | ".join(tail))
             continue
         pushed += 1
+        _delete_stopgap(dest.removesuffix(".webp") + "-stopgap.webp")
     if pushed > 0:
         log.info("cover uploaded to R2 id=%d prefix=%s",
                  entity_id, prefix)
 
 
+def _delete_stopgap(dest: str) -> None:
+    """Drop the web's stopgap cover (`{variant}-stopgap.webp`, generated
+    from the TMDB poster while the real one was missing) now that the real
+    cover is on R2. Best-effort: a leftover stopgap is never served while
+    the real cover exists.
+    """
+    try:
+        subprocess.run(
+            ["rclone", "deletefile", dest],
+            capture_output=True, text=True, timeout=_R2_PUSH_TIMEOUT,
+        )
+    except Exception as e:  # noqa: BLE001
+        log.warning("rclone stopgap delete raised for %s: %s", dest, e)
+
+
 def _validate_poster(img: "Image.Image", entity_id: int) -> bool:
     """Reject tiny / wrong-aspect frames that would land as a junk cover.
 
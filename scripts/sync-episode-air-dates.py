@@ -0,0 +1,236 @@
+#!/usr/bin/env python3
+"""Nightly sync of episode air dates from TMDB for the release calendar
+(`/serialy-online/kalendar/`, the "Připravované epizody" block on series
+pages and `/serialy-online/{slug}/kalendar.ics`).
+
+Background: `series_enricher.py` stores `air_date` when it first creates
+an episode, but nothing ever revisits it — announced dates move, and
+episodes that TMDB lists only after the enrichment never show up at all.
+The calendar needs both current dates and the episodes that haven't
+aired yet.
+
+For every series and TV pořad with a `tmdb_id` that is still airing
+(`last_air_year` from last year on, or unknown), this script fetches
+`/tv/{id}` and then the seasons holding its last and next episode
+(`/tv/{id}/season/{n}`, cs-CZ). Per TMDB episode with an air date it:
+
+  * updates `air_date` on our rows of that episode when it differs, and
+    fills `episode_name` where we have none;
+  * inserts a source-less stub row when we don't have the episode yet
+    and it airs no more than STUB_PAST_DAYS ago. Stubs are what the
+    enricher already creates for episodes without a source — detail
+    pages list only playable episodes, so they stay invisible there
+    until an import attaches a source.
+
+`--all` walks every show with a `tmdb_id` and every season, filling air
+dates on existing rows only (no stubs) — the one-off backfill for
+episodes imported before the enricher stored dates.
+
+Usage:
+    DATABASE_URL=postgres://... TMDB_API_KEY=... \\
+        python3 scripts/sync-episode-air-dates.py [--dry-run] [--all] [--limit N]
+"""
+
+from __future__ import annotations
+
+import argparse
+import logging
+import os
+import sys
+import time
+from datetime import date, datetime, timedelta, timezone
+from email.utils import parsedate_to_datetime
+
+import psycopg2
+import requests
+
+TMDB_BASE = "https://api.themoviedb.org/3"
+
+# Stubs for episodes that aired up to this many days ago still appear in
+# the current week of the calendar; older gaps are the importers' job.
+STUB_PAST_DAYS = 14
+
+# (show table, episode table, episode FK). Both go through `/tv/...` —
+# TMDB doesn't distinguish scripted series from TV pořady.
+TABLES = (
+    ("series", "episodes", "series_id"),
+    ("tv_shows", "tv_episodes", "tv_show_id"),
+)
+
+
+def _parse_retry_after(value: str) -> float:
+    """Retry-After as delta-seconds or HTTP-date; 1 s when unparseable.
+    Same rule as backfill-tmdb-ratings.py."""
+    if not value:
+        return 1.0
+    try:
+        return float(value)
+    except ValueError:
+        try:
+            target = parsedate_to_datetime(value)
+            return max((target - datetime.now(timezone.utc)).total_seconds(), 1.0)
+        except (TypeError, ValueError):
+            return 1.0
+
+
+def _tmdb_get(api_key: str, path: str) -> dict | None:
+    """GET a TMDB resource in Czech; None on 404 or any failure. Up to 3
+    retries on 429, honouring `Retry-After`."""
+    r = None
+    for attempt in range(3):
+        try:
+            r = requests.get(
+                f"{TMDB_BASE}{path}",
+                params={"api_key": api_key, "language": "cs-CZ"},
+                timeout=15,
+            )
+        except requests.RequestException as exc:
+            logging.warning("%s fetch failed: %s", path, exc)
+            return None
+        if r.status_code == 429:
+            wait = _parse_retry_after(r.headers.get("Retry-After", ""))
+            time.sleep(min(wait, 10) + 0.1 * attempt)
+            continue
+        break
+    if r is None or not r.ok:
+        if r is not None and r.status_code != 404:
+            logging.warning("%s returned %s", path, r.status_code)
+        return None
+    try:
+        return r.json()
+    except ValueError as exc:
+        logging.warning("%s JSON parse failed: %s", path, exc)
+        return None
+
+
+def _parse_date(value) -> date | None:
+    if not value:
+        return None
+    try:
+        return date.fromisoformat(value)
+    except ValueError:
+        return None
+
+
+def _load_shows(cur, table: str, all_shows: bool, limit: int) -> list[tuple[int, int]]:
+    """(id, tmdb_id) of the shows to sync."""
+    sql = f"SELECT id, tmdb_id FROM {table} WHERE tmdb_id IS NOT NULL"
+    if not all_shows:
+        sql += (
+            " AND (last_air_year IS NULL"
+            " OR last_air_year >= EXTRACT(YEAR FROM CURRENT_DATE)::int - 1)"
+        )
+    sql += " ORDER BY id"
+    if limit > 0:
+        sql += f" LIMIT {int(limit)}"
+    cur.execute(sql)
+    return cur.fetchall()
+
+
+def _seasons_to_fetch(show: dict, all_seasons: bool) -> list[int]:
+    if all_seasons:
+        return sorted(
+            s["season_number"] for s in show.get("seasons") or [] if s.get("season_number") is not None
+        )
+    seasons = set()
+    for key in ("last_episode_to_air", "next_episode_to_air"):
+        ep = show.get(key) or {}
+        if ep.get("season_number") is not None:
+            seasons.add(ep["season_number"])
+    return sorted(seasons)
+
+
+def _sync_episode(cur, ep_table: str, fk: str, show_id: int, season: int, ep: dict,
+                  allow_stub: bool, stub_from: date, counts: dict[str, int]) -> None:
+    number = ep.get("episode_number")
+    air_date = _parse_date(ep.get("air_date"))
+    if number is None or air_date is None:
+        return
+    name = (ep.get("name") or "").strip() or None
+    cur.execute(
+        f"""UPDATE {ep_table}
+               SET air_date = %s,
+                   episode_name = COALESCE(NULLIF(episode_name, ''), %s)
+             WHERE {fk} = %s AND season = %s AND episode = %s
+               AND air_date IS DISTINCT FROM %s""",
+        (air_date, name, show_id, season, number, air_date),
+    )
+    if cur.rowcount:
+        counts["updated"] += cur.rowcount
+        return
+    if not allow_stub or air_date < stub_from:
+        return
+    cur.execute(
+        f"""INSERT INTO {ep_table} ({fk}, season, episode, episode_name, overview, air_date)
+            SELECT %s, %s, %s, %s, %s, %s
+             WHERE NOT EXISTS (
+                   SELECT 1 FROM {ep_table}
+                    WHERE {fk} = %s AND season = %s AND episode = %s)""",
+        (show_id, season, number, name, (ep.get("overview") or "").strip() or None,
+         air_date, show_id, season, number),
+    )
+    counts["stubs"] += cur.rowcount
+
+
+def main() -> int:
+    logging.basicConfig(level=logging.INFO, format="%(asctime)s %(levelname)s %(message)s")
+    parser = argparse.ArgumentParser(description=__doc__.split("\n")[0])
+    parser.add_argument("--dry-run", action="store_true", help="roll back instead of committing")
+    parser.add_argument("--all", action="store_true",
+                        help="every show and season, air dates only (backfill)")
+    parser.add_argument("--limit", type=int, default=0, help="max shows per table (0 = all)")
+    args = parser.parse_args()
+
+    db_url = os.environ.get("DATABASE_URL")
+    api_key = os.environ.get("TMDB_API_KEY")
+    if not db_url or not api_key:
+        logging.error("DATABASE_URL and TMDB_API_KEY must be set")
+        return 2
+
+    stub_from = date.today() - timedelta(days=STUB_PAST_DAYS)
+    counts = {"shows": 0, "seasons": 0, "updated": 0, "stubs": 0, "failed": 0}
+    conn = psycopg2.connect(db_url)
+    try:
+        for show_table, ep_table, fk in TABLES:
+            with conn.cursor() as cur:
+                shows = _load_shows(cur, show_table, args.all, args.limit)
+            logging.info("%s: %d shows to sync", show_table, len(shows))
+            for show_id, tmdb_id in shows:
+                show = _tmdb_get(api_key, f"/tv/{tmdb_id}")
+                if show is None:
+                    counts["failed"] += 1
+                    continue
+                counts["shows"] += 1
+                with conn.cursor() as cur:
+                    for season in _seasons_to_fetch(show, args.all):
+                        data = _tmdb_get(api_key, f"/tv/{tmdb_id}/season/{season}")
+                        if data is None:
+                            counts["failed"] += 1
+                            continue
+                        counts["seasons"] += 1
+                        for ep in data.get("episodes") or []:
+                            _sync_episode(cur, ep_table, fk, show_id, season, ep,
+                                          not args.all, stub_from, counts)
+                # One transaction per show keeps a failure from losing the
+                # whole run.
+                if args.dry_run:
+                    conn.rollback()
+                else:
+                    conn.commit()
+    except Exception:
+        conn.rollback()
+        logging.exception("air-date sync aborted")
+        return 1
+    finally:
+        conn.close()
+
+    logging.info(
+        "done%s: %d shows, %d seasons, %d episodes re-dated, %d stubs added, %d fetches failed",
+        " (dry run)" if args.dry_run else "",
+        counts["shows"], counts["seasons"], counts["updated"], counts["stubs"], counts["failed"],
+    )
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())